@@ -0,0 +1,95 @@
+//! Benchmarks of the hot paths of the rust core.
+//!
+//! Run with `cargo bench --bench hot_paths`, adding `--features profiling` to also report the
+//! counters of each benchmark.
+
+use chrono::NaiveDateTime;
+use indexmap::IndexMap;
+use rateslib::calendars::{ndt, Convention, Modifier, NamedCal};
+use rateslib::curves::{CurveDF, LogLinearInterpolator, Nodes};
+use rateslib::dual::{
+    with_buffer_pool, with_hessian_mode, Dual, Dual2, Gradient2, HessianMode, MathFuncs,
+};
+use rateslib::splines::PPSpline;
+use std::hint::black_box;
+use std::time::Instant;
+
+/// Run `f` repeatedly and print the average time of one iteration.
+fn bench<R>(name: &str, iterations: u32, mut f: impl FnMut() -> R) {
+    #[cfg(feature = "profiling")]
+    rateslib::profiling::reset();
+    let start = Instant::now();
+    for _ in 0..iterations {
+        black_box(f());
+    }
+    let ns = start.elapsed().as_nanos() as f64 / iterations as f64;
+    println!("{name:<32} {ns:>12.1} ns/iter");
+    #[cfg(feature = "profiling")]
+    println!("{:<32} {:?}", "", rateslib::profiling::snapshot());
+}
+
+fn vars(prefix: &str, n: usize) -> Vec<String> {
+    (0..n).map(|i| format!("{prefix}{i}")).collect()
+}
+
+fn main() {
+    let x = Dual::new(1.5, vars("v", 100));
+    let y = Dual::new(2.5, vars("v", 100));
+    let z = Dual::new(2.5, vars("w", 100));
+    bench("dual add, shared vars", 100_000, || &x + &x);
+    bench("dual add, equal vars", 100_000, || &x + &y);
+    bench("dual add, distinct vars", 10_000, || &x + &z);
+    bench("dual mul, distinct vars", 10_000, || &x * &z);
+    bench("dual mul, distinct vars, pooled", 10, || {
+        with_buffer_pool(|| (0..1_000).fold(None, |_, _| Some(&x * &z)))
+    });
+
+    let (s, t) = (Dual::new(1.5, vars("s", 2)), Dual::new(2.5, vars("t", 2)));
+    bench("dual mul, 2 shared vars", 100_000, || &s * &s);
+    bench("dual mul, 2 distinct vars", 100_000, || &s * &t);
+
+    let x2 = Dual2::new(1.5, vars("v", 20));
+    let z2 = Dual2::new(2.5, vars("w", 20));
+    bench("dual2 mul, shared vars", 10_000, || &x2 * &x2);
+    bench("dual2 mul, distinct vars", 10_000, || &x2 * &z2);
+    for mode in [HessianMode::Eager, HessianMode::Lazy] {
+        bench(&format!("dual2 exp chain, {mode:?}"), 1_000, || {
+            with_hessian_mode(mode, || {
+                let y = (0..10).fold(x2.clone(), |acc, _| (&acc * &x2).exp() * 0.5);
+                y.gradient2(vec!["v0".to_string()])
+            })
+        });
+    }
+
+    let nodes = Nodes::F64(IndexMap::from_iter((0..11).map(|i| {
+        let t: f64 = i as f64;
+        (ndt(2024 + i, 1, 1), (-0.03 * t).exp())
+    })));
+    let curve = CurveDF::try_new(
+        nodes,
+        LogLinearInterpolator::new(),
+        "bench",
+        Convention::Act365F,
+        Modifier::ModF,
+        None,
+        NamedCal::try_new("all").unwrap(),
+    )
+    .unwrap();
+    let dates: Vec<NaiveDateTime> = (0..100_u32)
+        .map(|i| ndt(2024 + (i / 10) as i32, 1 + i % 12, 15))
+        .collect();
+    bench("curve df, 100 dates", 10_000, || {
+        dates
+            .iter()
+            .map(|d| curve.interpolated_value(d))
+            .collect::<Vec<_>>()
+    });
+
+    let t = vec![0., 0., 0., 0., 1., 2., 3., 4., 5., 5., 5., 5.];
+    let spline = PPSpline::<f64>::try_new(4, t, Some(vec![1.0; 8])).unwrap();
+    bench("spline eval, 100 points", 10_000, || {
+        (0..100_u32)
+            .map(|i| spline.ppdnev_single(&(i as f64 * 0.05), 0).unwrap())
+            .sum::<f64>()
+    });
+}
@@ -0,0 +1,4 @@
+//! The shared and static libraries of the C interface to the Rust core, which is documented
+//! by [rateslib::ffi].
+
+pub use rateslib::ffi::*;
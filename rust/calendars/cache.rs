@@ -0,0 +1,308 @@
+use crate::calendars::dateroll::DateRoll;
+use crate::error::RateslibError;
+use chrono::prelude::*;
+use chrono::Days;
+use serde::{Deserialize, Serialize};
+use std::ops::{Add, Sub};
+
+/// A date of a [BusDayIndex], as the number of days since the UNIX epoch, 1st Jan 1970, so
+/// that positions within the index are integer subtraction.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub(crate) struct Date(i32);
+
+impl Add<i32> for Date {
+    type Output = Date;
+    fn add(self, days: i32) -> Date {
+        Date(self.0 + days)
+    }
+}
+
+impl Sub<Date> for Date {
+    type Output = i32;
+    fn sub(self, other: Date) -> i32 {
+        self.0 - other.0
+    }
+}
+
+impl From<&NaiveDateTime> for Date {
+    fn from(value: &NaiveDateTime) -> Self {
+        Date(value.and_utc().timestamp().div_euclid(86400) as i32)
+    }
+}
+
+impl From<Date> for NaiveDateTime {
+    fn from(value: Date) -> Self {
+        DateTime::from_timestamp(value.0 as i64 * 86400, 0)
+            .unwrap()
+            .naive_utc()
+    }
+}
+
+/// A precomputed index of the business days of a calendar over a fixed range of dates.
+///
+/// Business day lookups, counts and additions within the range are O(1).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BusDayIndex {
+    start: Date,
+    /// The number of business days in `[start, start + i)` for each `i` in the range and
+    /// one beyond its end.
+    counts: Vec<u32>,
+    /// The ordered business days in the range.
+    bus_days: Vec<Date>,
+}
+
+impl BusDayIndex {
+    /// Index the business days of a `calendar` between `start` and `end`, inclusive.
+    pub fn try_new<T: DateRoll>(
+        calendar: &T,
+        start: &NaiveDateTime,
+        end: &NaiveDateTime,
+    ) -> Result<Self, RateslibError> {
+        let (start, end) = (Date::from(start), Date::from(end));
+        if end < start {
+            return Err(RateslibError::value(
+                "`end` of a `BusDayIndex` cannot be before its `start`.",
+            ));
+        }
+        let n = (end - start + 1) as usize;
+        let mut counts = Vec::with_capacity(n + 1);
+        let mut bus_days = Vec::new();
+        counts.push(0_u32);
+        for i in 0..n {
+            let date = start + i as i32;
+            if calendar.is_bus_day(&NaiveDateTime::from(date)) {
+                bus_days.push(date);
+            }
+            counts.push(bus_days.len() as u32);
+        }
+        Ok(Self {
+            start,
+            counts,
+            bus_days,
+        })
+    }
+
+    /// Return the position of the `date` within the range, if contained.
+    fn position(&self, date: &Date) -> Option<usize> {
+        let i = *date - self.start;
+        match i >= 0 && (i as usize) < self.counts.len() - 1 {
+            true => Some(i as usize),
+            false => None,
+        }
+    }
+
+    /// Return whether the `date` is a business day, if it is within the indexed range.
+    pub(crate) fn is_bus_day(&self, date: &Date) -> Option<bool> {
+        self.position(date)
+            .map(|i| self.counts[i + 1] > self.counts[i])
+    }
+
+    /// Return the number of business days in `[start, end)`, negated if `end` is before
+    /// `start`, if both dates are within the indexed range or one day beyond its end.
+    pub(crate) fn bus_day_count(&self, start: &Date, end: &Date) -> Option<i32> {
+        let count = |d: &Date| -> Option<i32> {
+            let i = *d - self.start;
+            match i >= 0 && (i as usize) < self.counts.len() {
+                true => Some(self.counts[i as usize] as i32),
+                false => None,
+            }
+        };
+        Some(count(end)? - count(start)?)
+    }
+
+    /// Return the business day `days` business days from the business day `date`, if both
+    /// are within the indexed range.
+    pub(crate) fn add_bus_days(&self, date: &Date, days: i32) -> Option<Date> {
+        let i = self.position(date)?;
+        let k = self.counts[i] as i64 + days as i64;
+        match k >= 0 && (k as usize) < self.bus_days.len() {
+            true => Some(self.bus_days[k as usize]),
+            false => None,
+        }
+    }
+}
+
+/// A calendar wrapped with a [BusDayIndex] for O(1) business day arithmetic.
+///
+/// Dates outside of the indexed range, or with a non-zero time component, fall back to the
+/// methods of the wrapped calendar.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedCal<T: DateRoll> {
+    pub(crate) calendar: T,
+    pub(crate) index: BusDayIndex,
+}
+
+impl<T: DateRoll> CachedCal<T> {
+    /// Wrap a `calendar` with an index of business days between `start` and `end`, inclusive.
+    pub fn try_new(
+        calendar: T,
+        start: &NaiveDateTime,
+        end: &NaiveDateTime,
+    ) -> Result<Self, RateslibError> {
+        let index = BusDayIndex::try_new(&calendar, start, end)?;
+        Ok(Self { calendar, index })
+    }
+
+    /// Return the [BusDayIndex] of the calendar.
+    pub fn index(&self) -> &BusDayIndex {
+        &self.index
+    }
+
+    /// Return the number of business days in `[start, end)`, negated if `end` is before `start`.
+    pub fn bus_day_count(&self, start: &NaiveDateTime, end: &NaiveDateTime) -> i32 {
+        if let (Some(s), Some(e)) = (indexable(start), indexable(end)) {
+            if let Some(count) = self.index.bus_day_count(&s, &e) {
+                return count;
+            }
+        }
+        let (a, b, sign) = match end < start {
+            true => (end, start, -1),
+            false => (start, end, 1),
+        };
+        let mut count = 0;
+        let mut date = *a;
+        while date < *b {
+            if self.calendar.is_bus_day(&date) {
+                count += 1;
+            }
+            date = date + Days::new(1);
+        }
+        sign * count
+    }
+}
+
+/// Return the [Date] of a datetime with no time component.
+fn indexable(date: &NaiveDateTime) -> Option<Date> {
+    match date.time() == NaiveTime::MIN {
+        true => Some(Date::from(date)),
+        false => None,
+    }
+}
+
+impl<T: DateRoll> DateRoll for CachedCal<T> {
+    fn is_weekday(&self, date: &NaiveDateTime) -> bool {
+        self.calendar.is_weekday(date)
+    }
+
+    fn is_holiday(&self, date: &NaiveDateTime) -> bool {
+        self.calendar.is_holiday(date)
+    }
+
+    fn is_settlement(&self, date: &NaiveDateTime) -> bool {
+        self.calendar.is_settlement(date)
+    }
+
+    fn is_bus_day(&self, date: &NaiveDateTime) -> bool {
+        match indexable(date).and_then(|d| self.index.is_bus_day(&d)) {
+            Some(value) => value,
+            None => self.calendar.is_bus_day(date),
+        }
+    }
+
+    fn add_bus_days(
+        &self,
+        date: &NaiveDateTime,
+        days: i8,
+        settlement: bool,
+    ) -> Result<NaiveDateTime, RateslibError> {
+        let cached = indexable(date)
+            .filter(|d| self.index.is_bus_day(d) == Some(true))
+            .and_then(|d| self.index.add_bus_days(&d, days as i32));
+        match cached {
+            None => self.calendar.add_bus_days(date, days, settlement),
+            Some(d) => {
+                let new_date = NaiveDateTime::from(d);
+                if !settlement {
+                    Ok(new_date)
+                } else if days < 0 {
+                    Ok(self.roll_backward_settled_bus_day(&new_date))
+                } else {
+                    Ok(self.roll_forward_settled_bus_day(&new_date))
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::{ndt, NamedCal};
+
+    fn fixture() -> CachedCal<NamedCal> {
+        let cal = NamedCal::try_new("tgt,ldn|nyc").unwrap();
+        CachedCal::try_new(cal, &ndt(2020, 1, 1), &ndt(2026, 12, 31)).unwrap()
+    }
+
+    #[test]
+    fn test_index_range() {
+        let cal = NamedCal::try_new("tgt").unwrap();
+        assert!(BusDayIndex::try_new(&cal, &ndt(2020, 1, 2), &ndt(2020, 1, 1)).is_err());
+        let index = BusDayIndex::try_new(&cal, &ndt(2020, 1, 1), &ndt(2020, 1, 31)).unwrap();
+        assert!(index.position(&Date::from(&ndt(2020, 1, 31))).is_some());
+        assert!(index.position(&Date::from(&ndt(2020, 2, 1))).is_none());
+        // 1st Jan holiday and 8 weekend days
+        assert_eq!(
+            index.bus_day_count(&Date::from(&ndt(2020, 1, 1)), &Date::from(&ndt(2020, 2, 1))),
+            Some(22)
+        );
+        assert_eq!(index.add_bus_days(&Date::from(&ndt(2020, 1, 30)), 2), None);
+    }
+
+    #[test]
+    fn test_equivalence_add_bus_days() {
+        let cal = fixture();
+        let mut date = ndt(2020, 3, 1);
+        while date < ndt(2026, 6, 1) {
+            if cal.calendar.is_bus_day(&date) {
+                for days in [-10_i8, -2, 0, 1, 2, 5, 40] {
+                    for settlement in [true, false] {
+                        assert_eq!(
+                            cal.add_bus_days(&date, days, settlement).unwrap(),
+                            cal.calendar.add_bus_days(&date, days, settlement).unwrap()
+                        );
+                    }
+                }
+            }
+            assert_eq!(cal.is_bus_day(&date), cal.calendar.is_bus_day(&date));
+            date = date + Days::new(1);
+        }
+    }
+
+    #[test]
+    fn test_fallback_outside_range() {
+        let cal = fixture();
+        let date = ndt(2026, 12, 30);
+        assert_eq!(
+            cal.add_bus_days(&date, 5, false).unwrap(),
+            cal.calendar.add_bus_days(&date, 5, false).unwrap()
+        );
+        assert!(cal.add_bus_days(&ndt(2020, 1, 1), 1, false).is_err());
+    }
+
+    #[test]
+    fn test_bus_day_count() {
+        let cal = fixture();
+        let (start, end) = (ndt(2021, 1, 1), ndt(2022, 1, 1));
+        let count = cal.bus_day_count(&start, &end);
+        assert_eq!(-count, cal.bus_day_count(&end, &start));
+        let expected = cal
+            .calendar
+            .cal_date_range(&start, &(end - Days::new(1)))
+            .unwrap()
+            .iter()
+            .filter(|d| cal.calendar.is_bus_day(d))
+            .count() as i32;
+        assert_eq!(count, expected);
+        // fallback beyond the indexed range
+        let (start, end) = (ndt(2026, 6, 1), ndt(2027, 6, 1));
+        let expected = cal
+            .calendar
+            .cal_date_range(&start, &(end - Days::new(1)))
+            .unwrap()
+            .iter()
+            .filter(|d| cal.calendar.is_bus_day(d))
+            .count() as i32;
+        assert_eq!(cal.bus_day_count(&start, &end), expected);
+    }
+}
@@ -1,16 +1,18 @@
 use chrono::prelude::*;
 use chrono::Weekday;
 use indexmap::set::IndexSet;
-use pyo3::exceptions::PyValueError;
-use pyo3::{pyclass, FromPyObject, PyErr};
+#[cfg(feature = "python")]
+use pyo3::{pyclass, FromPyObject};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 
 use crate::calendars::dateroll::DateRoll;
 use crate::calendars::named::get_calendar_by_name;
+use crate::error::RateslibError;
 
 /// Container for calendar types.
-#[derive(Debug, Clone, PartialEq, FromPyObject, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "python", derive(FromPyObject))]
 pub enum CalType {
     Cal(Cal),
     UnionCal(UnionCal),
@@ -26,7 +28,7 @@ pub enum CalType {
 /// - `holidays`: which defines specific dates that may be exceptions to the general working week, and cannot be
 ///   business days.
 ///
-#[pyclass(module = "rateslib.rs")]
+#[cfg_attr(feature = "python", pyclass(module = "rateslib.rs"))]
 #[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Cal {
     pub(crate) holidays: IndexSet<NaiveDateTime>,
@@ -67,7 +69,7 @@ impl Cal {
 /// A business day is defined as allowing settlement relative to an associated calendar if:
 ///
 /// - the date in question is also a business day in the associated settlement calendar.
-#[pyclass(module = "rateslib.rs")]
+#[cfg_attr(feature = "python", pyclass(module = "rateslib.rs"))]
 #[derive(Clone, Default, Debug, Serialize, Deserialize)]
 pub struct UnionCal {
     pub(crate) calendars: Vec<Cal>,
@@ -87,9 +89,9 @@ impl UnionCal {
 ///
 /// This struct is designed for use when serialization of a calendar as part of an another composite
 /// struct seeks to be related to named calendar combinations and not an inefficient list of dates.
-#[pyclass(module = "rateslib.rs")]
+#[cfg_attr(feature = "python", pyclass(module = "rateslib.rs"))]
 #[derive(Clone, Debug, Serialize, Deserialize)]
-#[serde(from = "NamedCalDataModel")]
+#[serde(try_from = "NamedCalDataModel")]
 pub struct NamedCal {
     pub(crate) name: String,
     #[serde(skip)]
@@ -101,9 +103,16 @@ struct NamedCalDataModel {
     name: String,
 }
 
-impl std::convert::From<NamedCalDataModel> for NamedCal {
-    fn from(model: NamedCalDataModel) -> Self {
-        Self::try_new(&model.name).expect("NamedCal data model contains bad data.")
+impl std::convert::TryFrom<NamedCalDataModel> for NamedCal {
+    type Error = RateslibError;
+
+    fn try_from(model: NamedCalDataModel) -> Result<Self, Self::Error> {
+        Self::try_new(&model.name).map_err(|_| {
+            RateslibError::Calendar(format!(
+                "NamedCal data model contains bad data: `{}`.",
+                model.name
+            ))
+        })
     }
 }
 
@@ -113,11 +122,11 @@ impl NamedCal {
     /// `name` must be a string that contains named calendars separated by commas, additionally
     /// separating business day calendars with associated settlement calendars by a pipe. A valid
     /// example input is "tgt,ldn|fed".
-    pub fn try_new(name: &str) -> Result<Self, PyErr> {
+    pub fn try_new(name: &str) -> Result<Self, RateslibError> {
         let name_ = name.to_lowercase();
         let parts: Vec<&str> = name_.split("|").collect();
         if parts.len() > 2 {
-            Err(PyValueError::new_err(
+            Err(RateslibError::value(
                 "Cannot use more than one pipe ('|') operator in `name`.",
             ))
         } else if parts.len() == 1 {
@@ -143,7 +152,7 @@ impl NamedCal {
     }
 }
 
-fn parse_cals(name: &str) -> Result<Vec<Cal>, PyErr> {
+fn parse_cals(name: &str) -> Result<Vec<Cal>, RateslibError> {
     let mut cals: Vec<Cal> = Vec::new();
     for cal in name.split(",") {
         cals.push(get_calendar_by_name(cal)?)
@@ -174,6 +183,7 @@ impl DateRoll for UnionCal {
         self.calendars.iter().any(|cal| cal.is_holiday(date))
     }
 
+    #[allow(clippy::unnecessary_map_or)]
     fn is_settlement(&self, date: &NaiveDateTime) -> bool {
         self.settlement_calendars
             .as_ref()
@@ -271,12 +281,17 @@ impl PartialEq<NamedCal> for Cal {
 
 /// Create a `NaiveDateTime` with default null time.
 ///
-/// Panics if date values are invalid.
+/// Panics if date values are invalid, for which [try_ndt] returns an error.
 pub fn ndt(year: i32, month: u32, day: u32) -> NaiveDateTime {
+    try_ndt(year, month, day).expect("`year`, `month` `day` are invalid.")
+}
+
+/// Create a `NaiveDateTime` with default null time, or return an error if date values are
+/// invalid.
+pub fn try_ndt(year: i32, month: u32, day: u32) -> Result<NaiveDateTime, RateslibError> {
     NaiveDate::from_ymd_opt(year, month, day)
-        .expect("`year`, `month` `day` are invalid.")
-        .and_hms_opt(0, 0, 0)
-        .unwrap()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .ok_or(RateslibError::InvalidDate { year, month, day })
 }
 
 // UNIT TESTS
@@ -439,4 +454,23 @@ mod tests {
         assert_ne!(cal2, ucal);
         assert_ne!(ucal, cal2);
     }
+
+    #[test]
+    fn test_try_ndt() {
+        assert_eq!(try_ndt(2024, 2, 29).unwrap(), ndt(2024, 2, 29));
+        assert_eq!(
+            try_ndt(2023, 2, 29).unwrap_err(),
+            RateslibError::InvalidDate {
+                year: 2023,
+                month: 2,
+                day: 29
+            }
+        );
+    }
+
+    #[test]
+    fn test_named_cal_deserialize_bad_data() {
+        let result: Result<NamedCal, _> = serde_json::from_str(r#"{"name":"bad"}"#);
+        assert!(result.is_err());
+    }
 }
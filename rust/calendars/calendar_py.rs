@@ -1,6 +1,6 @@
 //! Wrapper module to export to Python using pyo3 bindings.
 
-use crate::calendars::named::get_calendar_by_name;
+use crate::calendars::named::{apply_holiday_updates, get_calendar_by_name, HolidayUpdates};
 use crate::calendars::{Cal, CalType, Convention, DateRoll, Modifier, NamedCal, RollDay, UnionCal};
 use crate::json::json_py::DeserializedObj;
 use crate::json::JSON;
@@ -57,7 +57,7 @@ impl Convention {
     pub fn __getstate__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
         Ok(PyBytes::new_bound(py, &serialize(&self).unwrap()))
     }
-    pub fn __getnewargs__<'py>(&self) -> PyResult<(u8,)> {
+    pub fn __getnewargs__(&self) -> PyResult<(u8,)> {
         match self {
             Convention::One => Ok((0_u8,)),
             Convention::OnePlus => Ok((1_u8,)),
@@ -97,7 +97,7 @@ impl Modifier {
     pub fn __getstate__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
         Ok(PyBytes::new_bound(py, &serialize(&self).unwrap()))
     }
-    pub fn __getnewargs__<'py>(&self) -> PyResult<(u8,)> {
+    pub fn __getnewargs__(&self) -> PyResult<(u8,)> {
         match self {
             Modifier::Act => Ok((0_u8,)),
             Modifier::F => Ok((1_u8,)),
@@ -264,7 +264,7 @@ impl Cal {
         days: i8,
         settlement: bool,
     ) -> PyResult<NaiveDateTime> {
-        self.add_bus_days(&date, days, settlement)
+        Ok(self.add_bus_days(&date, days, settlement)?)
     }
 
     /// Return a date separated by months from an input date, and rolled with a modifier.
@@ -290,7 +290,7 @@ impl Cal {
         roll: RollDay,
         settlement: bool,
     ) -> PyResult<NaiveDateTime> {
-        Ok(self.add_months(&date, months, &modifier, &roll, settlement))
+        Ok(self.add_months(&date, months, &modifier, &roll, settlement)?)
     }
 
     /// Adjust a non-business date to a business date under a specific modification rule.
@@ -374,7 +374,7 @@ impl Cal {
         start: NaiveDateTime,
         end: NaiveDateTime,
     ) -> PyResult<Vec<NaiveDateTime>> {
-        self.bus_date_range(&start, &end)
+        Ok(self.bus_date_range(&start, &end)?)
     }
 
     /// Return a list of calendar dates within a range.
@@ -395,7 +395,7 @@ impl Cal {
         start: NaiveDateTime,
         end: NaiveDateTime,
     ) -> PyResult<Vec<NaiveDateTime>> {
-        self.cal_date_range(&start, &end)
+        Ok(self.cal_date_range(&start, &end)?)
     }
 
     // Pickling
@@ -527,7 +527,7 @@ impl UnionCal {
         days: i8,
         settlement: bool,
     ) -> PyResult<NaiveDateTime> {
-        self.add_bus_days(&date, days, settlement)
+        Ok(self.add_bus_days(&date, days, settlement)?)
     }
 
     /// Return a date separated by months from an input date, and rolled with a modifier.
@@ -542,7 +542,7 @@ impl UnionCal {
         roll: RollDay,
         settlement: bool,
     ) -> PyResult<NaiveDateTime> {
-        Ok(self.add_months(&date, months, &modifier, &roll, settlement))
+        Ok(self.add_months(&date, months, &modifier, &roll, settlement)?)
     }
 
     /// Adjust a non-business date to a business date under a specific modification rule.
@@ -575,7 +575,7 @@ impl UnionCal {
         start: NaiveDateTime,
         end: NaiveDateTime,
     ) -> PyResult<Vec<NaiveDateTime>> {
-        self.bus_date_range(&start, &end)
+        Ok(self.bus_date_range(&start, &end)?)
     }
 
     /// Return a list of calendar dates in a range.
@@ -587,7 +587,7 @@ impl UnionCal {
         start: NaiveDateTime,
         end: NaiveDateTime,
     ) -> PyResult<Vec<NaiveDateTime>> {
-        self.cal_date_range(&start, &end)
+        Ok(self.cal_date_range(&start, &end)?)
     }
 
     // Pickling
@@ -632,7 +632,7 @@ impl UnionCal {
 impl NamedCal {
     #[new]
     fn new_py(name: String) -> PyResult<Self> {
-        NamedCal::try_new(&name)
+        Ok(NamedCal::try_new(&name)?)
     }
 
     #[getter]
@@ -705,7 +705,7 @@ impl NamedCal {
         days: i8,
         settlement: bool,
     ) -> PyResult<NaiveDateTime> {
-        self.add_bus_days(&date, days, settlement)
+        Ok(self.add_bus_days(&date, days, settlement)?)
     }
 
     /// Return a date separated by months from an input date, and rolled with a modifier.
@@ -720,7 +720,7 @@ impl NamedCal {
         roll: RollDay,
         settlement: bool,
     ) -> PyResult<NaiveDateTime> {
-        Ok(self.add_months(&date, months, &modifier, &roll, settlement))
+        Ok(self.add_months(&date, months, &modifier, &roll, settlement)?)
     }
 
     /// Adjust a non-business date to a business date under a specific modification rule.
@@ -753,7 +753,7 @@ impl NamedCal {
         start: NaiveDateTime,
         end: NaiveDateTime,
     ) -> PyResult<Vec<NaiveDateTime>> {
-        self.bus_date_range(&start, &end)
+        Ok(self.bus_date_range(&start, &end)?)
     }
 
     /// Return a list of calendar dates in a range.
@@ -765,7 +765,7 @@ impl NamedCal {
         start: NaiveDateTime,
         end: NaiveDateTime,
     ) -> PyResult<Vec<NaiveDateTime>> {
-        self.cal_date_range(&start, &end)
+        Ok(self.cal_date_range(&start, &end)?)
     }
 
     // Pickling
@@ -810,5 +810,12 @@ impl NamedCal {
 #[pyfunction]
 #[pyo3(name = "get_named_calendar")]
 pub fn get_calendar_by_name_py(name: &str) -> PyResult<Cal> {
-    get_calendar_by_name(name)
+    Ok(get_calendar_by_name(name)?)
+}
+
+/// Merge holiday updates, in JSON form, into the named calendars.
+#[pyfunction]
+#[pyo3(name = "load_holiday_updates")]
+pub fn load_holiday_updates_py(json: &str) -> PyResult<()> {
+    Ok(apply_holiday_updates(HolidayUpdates::from_json(json)?)?)
 }
@@ -1,13 +1,15 @@
-use crate::calendars::calendar::ndt;
+use crate::calendars::calendar::{ndt, try_ndt};
+use crate::calendars::tenor::Tenor;
+use crate::error::RateslibError;
 use chrono::prelude::*;
 use chrono::{Days, Weekday};
-use pyo3::exceptions::PyValueError;
-use pyo3::{pyclass, PyErr};
+#[cfg(feature = "python")]
+use pyo3::pyclass;
 use serde::{Deserialize, Serialize};
 use std::cmp::{Ordering, PartialEq};
 
 /// A roll day.
-#[pyclass(module = "rateslib.rs")]
+#[cfg_attr(feature = "python", pyclass(module = "rateslib.rs"))]
 #[derive(Copy, Clone)]
 pub enum RollDay {
     /// Inherit the day of the input date as the roll.
@@ -23,7 +25,7 @@ pub enum RollDay {
 }
 
 /// A rule to adjust a non-business day to a business day.
-#[pyclass(module = "rateslib.rs")]
+#[cfg_attr(feature = "python", pyclass(module = "rateslib.rs"))]
 #[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Modifier {
     /// Actual: date is unchanged, even if it is a non-business day.
@@ -171,16 +173,16 @@ pub trait DateRoll {
     /// day is equivalent to the rolling forwards or backwards, respectively.
     fn lag(&self, date: &NaiveDateTime, days: i8, settlement: bool) -> NaiveDateTime {
         if self.is_bus_day(date) {
-            return self.add_bus_days(date, days, settlement).unwrap();
+            return self.shift_bus_days(date, days, settlement);
         }
         match days.cmp(&0_i8) {
             Ordering::Equal => self.roll_forward_bus_day(date),
-            Ordering::Less => self
-                .add_bus_days(&self.roll_backward_bus_day(date), days + 1, settlement)
-                .unwrap(),
-            Ordering::Greater => self
-                .add_bus_days(&self.roll_forward_bus_day(date), days - 1, settlement)
-                .unwrap(),
+            Ordering::Less => {
+                self.shift_bus_days(&self.roll_backward_bus_day(date), days + 1, settlement)
+            }
+            Ordering::Greater => {
+                self.shift_bus_days(&self.roll_forward_bus_day(date), days - 1, settlement)
+            }
         }
     }
 
@@ -218,12 +220,17 @@ pub trait DateRoll {
         date: &NaiveDateTime,
         days: i8,
         settlement: bool,
-    ) -> Result<NaiveDateTime, PyErr> {
+    ) -> Result<NaiveDateTime, RateslibError> {
         if self.is_non_bus_day(date) {
-            return Err(PyValueError::new_err(
+            return Err(RateslibError::value(
                 "Cannot add business days to an input `date` that is not a business day.",
             ));
         }
+        Ok(self.shift_bus_days(date, days, settlement))
+    }
+
+    /// Add a given number of business days to a `date` which is a business day.
+    fn shift_bus_days(&self, date: &NaiveDateTime, days: i8, settlement: bool) -> NaiveDateTime {
         let mut new_date = *date;
         let mut counter: i8 = 0;
         if days < 0 {
@@ -241,11 +248,11 @@ pub trait DateRoll {
         }
 
         if !settlement {
-            Ok(new_date)
+            new_date
         } else if days < 0 {
-            Ok(self.roll_backward_settled_bus_day(&new_date))
+            self.roll_backward_settled_bus_day(&new_date)
         } else {
-            Ok(self.roll_forward_settled_bus_day(&new_date))
+            self.roll_forward_settled_bus_day(&new_date)
         }
     }
 
@@ -258,7 +265,7 @@ pub trait DateRoll {
         modifier: &Modifier,
         roll: &RollDay,
         settlement: bool,
-    ) -> NaiveDateTime
+    ) -> Result<NaiveDateTime, RateslibError>
     where
         Self: Sized,
     {
@@ -273,7 +280,7 @@ pub trait DateRoll {
         let rem_months = months - yr_roll * 12;
 
         // determine the new month
-        let mut new_month = i32::try_from(date.month()).unwrap() + rem_months;
+        let mut new_month = date.month() as i32 + rem_months;
         if new_month <= 0 {
             yr_roll -= 1;
             new_month = new_month.rem_euclid(12);
@@ -286,9 +293,8 @@ pub trait DateRoll {
         }
 
         // perform the date roll
-        let new_date =
-            get_roll(date.year() + yr_roll, new_month.try_into().unwrap(), &roll_).unwrap();
-        self.roll(&new_date, modifier, settlement)
+        let new_date = get_roll(date.year() + yr_roll, new_month as u32, &roll_)?;
+        Ok(self.roll(&new_date, modifier, settlement))
     }
 
     /// Return a vector of business dates between a start and end, inclusive.
@@ -296,9 +302,9 @@ pub trait DateRoll {
         &self,
         start: &NaiveDateTime,
         end: &NaiveDateTime,
-    ) -> Result<Vec<NaiveDateTime>, PyErr> {
+    ) -> Result<Vec<NaiveDateTime>, RateslibError> {
         if self.is_non_bus_day(start) || self.is_non_bus_day(end) {
-            return Err(PyValueError::new_err("`start` and `end` for a calendar `bus_date_range` must both be valid business days"));
+            return Err(RateslibError::value("`start` and `end` for a calendar `bus_date_range` must both be valid business days"));
         }
         let mut vec = Vec::new();
         let mut sample_date = *start;
@@ -314,7 +320,7 @@ pub trait DateRoll {
         &self,
         start: &NaiveDateTime,
         end: &NaiveDateTime,
-    ) -> Result<Vec<NaiveDateTime>, PyErr> {
+    ) -> Result<Vec<NaiveDateTime>, RateslibError> {
         let mut vec = Vec::new();
         let mut sample_date = *start;
         while sample_date <= *end {
@@ -323,31 +329,117 @@ pub trait DateRoll {
         }
         Ok(vec)
     }
+
+    /// Return the number of business days from `start`, inclusive, to `end`, exclusive, which
+    /// is negative if `end` is before `start`.
+    fn bus_days_between(&self, start: &NaiveDateTime, end: &NaiveDateTime) -> i32 {
+        let (sign, mut date, end) = match start <= end {
+            true => (1, *start, *end),
+            false => (-1, *end, *start),
+        };
+        let mut count = 0;
+        while date < end {
+            if self.is_bus_day(&date) {
+                count += 1;
+            }
+            date = date + Days::new(1);
+        }
+        sign * count
+    }
+
+    /// Return the [Tenor] from `start` to `end`: a whole number of months if adding it under
+    /// the `roll` reaches `end` unadjusted, otherwise a whole number of weeks or of days.
+    ///
+    /// Adding the [Tenor] to `start` with [add_tenor_with_roll](DateRoll::add_tenor_with_roll),
+    /// the `roll` and [Modifier::Act] returns `end`.
+    fn tenor_between(&self, start: &NaiveDateTime, end: &NaiveDateTime, roll: &RollDay) -> Tenor
+    where
+        Self: Sized,
+    {
+        let months = (end.year() - start.year()) * 12 + end.month() as i32 - start.month() as i32;
+        let days = (*end - *start).num_days() as i32;
+        if days == 0 {
+            Tenor::Days(0)
+        } else if months != 0
+            && self
+                .add_months(start, months, &Modifier::Act, roll, false)
+                .is_ok_and(|d| d == *end)
+        {
+            Tenor::Months(months)
+        } else if days % 7 == 0 {
+            Tenor::Weeks(days / 7)
+        } else {
+            Tenor::Days(days)
+        }
+    }
+
+    /// Add a `tenor`, which may be negative, to a `date`, rolling months on the `roll` day,
+    /// with the result adjusted by the `modifier` to a business day that may or may not allow
+    /// `settlement`.
+    ///
+    /// A tenor of business days counts business days from a business day `date`, as does
+    /// [add_bus_days](DateRoll::add_bus_days), and ignores the `modifier`.
+    fn add_tenor_with_roll(
+        &self,
+        date: &NaiveDateTime,
+        tenor: &Tenor,
+        modifier: &Modifier,
+        roll: &RollDay,
+        settlement: bool,
+    ) -> Result<NaiveDateTime, RateslibError>
+    where
+        Self: Sized,
+    {
+        let add_days = |days: i32| {
+            let new_date = *date + chrono::Duration::days(days as i64);
+            self.roll(&new_date, modifier, settlement)
+        };
+        match tenor {
+            Tenor::Days(n) => Ok(add_days(*n)),
+            Tenor::Weeks(n) => Ok(add_days(7 * n)),
+            Tenor::Months(n) => self.add_months(date, *n, modifier, roll, settlement),
+            Tenor::BusDays(n) => {
+                if self.is_non_bus_day(date) {
+                    return Err(RateslibError::value(
+                        "Cannot add business days to an input `date` that is not a business day.",
+                    ));
+                }
+                let mut new_date = *date;
+                for _ in 0..n.abs() {
+                    new_date = match *n < 0 {
+                        true => self.roll_backward_bus_day(&(new_date - Days::new(1))),
+                        false => self.roll_forward_bus_day(&(new_date + Days::new(1))),
+                    };
+                }
+                Ok(match (settlement, *n < 0) {
+                    (false, _) => new_date,
+                    (true, true) => self.roll_backward_settled_bus_day(&new_date),
+                    (true, false) => self.roll_forward_settled_bus_day(&new_date),
+                })
+            }
+        }
+    }
 }
 
 /// Return a specific roll date given the `month`, `year` and `roll`.
-pub fn get_roll(year: i32, month: u32, roll: &RollDay) -> Result<NaiveDateTime, PyErr> {
+pub fn get_roll(year: i32, month: u32, roll: &RollDay) -> Result<NaiveDateTime, RateslibError> {
     match roll {
-        RollDay::Int { day: val } => Ok(get_roll_by_day(year, month, *val)),
-        RollDay::EoM {} => Ok(get_roll_by_day(year, month, 31)),
-        RollDay::SoM {} => Ok(get_roll_by_day(year, month, 1)),
+        RollDay::Int { day: val } => Ok(get_roll_by_day(year, month, *val)?),
+        RollDay::EoM {} => Ok(get_roll_by_day(year, month, 31)?),
+        RollDay::SoM {} => Ok(get_roll_by_day(year, month, 1)?),
         RollDay::IMM {} => Ok(get_imm(year, month)),
-        RollDay::Unspecified {} => Err(PyValueError::new_err("`roll` cannot be unspecified.")),
+        RollDay::Unspecified {} => Err(RateslibError::value("`roll` cannot be unspecified.")),
     }
 }
 
 /// Return a specific roll date given the `month`, `year` and `roll`.
-fn get_roll_by_day(year: i32, month: u32, day: u32) -> NaiveDateTime {
-    let d = NaiveDate::from_ymd_opt(year, month, day);
-    match d {
-        Some(date) => NaiveDateTime::new(date, NaiveTime::from_hms_opt(0, 0, 0).unwrap()),
-        None => {
-            if day > 28 {
-                get_roll_by_day(year, month, day - 1)
-            } else {
-                panic!("Unexpected error in `get_roll_by_day`")
-            }
-        }
+///
+/// A `day` beyond the end of the month rolls to the last day of the month.
+fn get_roll_by_day(year: i32, month: u32, day: u32) -> Result<NaiveDateTime, RateslibError> {
+    match try_ndt(year, month, day) {
+        Ok(date) => Ok(date),
+        Err(_) if day > 28 => get_roll_by_day(year, month, day - 1),
+        Err(e) => Err(e),
     }
 }
 
@@ -611,6 +703,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_add_bus_days_error() {
         let cal = fixture_hol_cal();
         match cal.add_bus_days(&ndt(2015, 9, 7), 3, true) {
@@ -637,6 +730,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::needless_range_loop)]
     fn test_add_37_months() {
         let cal = get_calendar_by_name("all").unwrap();
 
@@ -662,13 +756,15 @@ mod tests {
                     &Modifier::Act,
                     &RollDay::Unspecified {},
                     true
-                ),
+                )
+                .unwrap(),
                 dates[i].1
             )
         }
     }
 
     #[test]
+    #[allow(clippy::needless_range_loop)]
     fn test_sub_37_months() {
         let cal = get_calendar_by_name("all").unwrap();
 
@@ -694,13 +790,15 @@ mod tests {
                     &Modifier::Act,
                     &RollDay::Unspecified {},
                     true
-                ),
+                )
+                .unwrap(),
                 dates[i].1
             )
         }
     }
 
     #[test]
+    #[allow(clippy::needless_range_loop, clippy::useless_vec)]
     fn test_add_months_roll() {
         let cal = get_calendar_by_name("all").unwrap();
         let roll = vec![
@@ -712,13 +810,19 @@ mod tests {
         ];
         for i in 0..5 {
             assert_eq!(
-                cal.add_months(&ndt(1998, 3, 7), -15, &Modifier::Act, &roll[i].0, true),
+                cal.add_months(&ndt(1998, 3, 7), -15, &Modifier::Act, &roll[i].0, true)
+                    .unwrap(),
                 roll[i].1
             );
         }
+        let invalid = RollDay::Int { day: 0 };
+        assert!(cal
+            .add_months(&ndt(1998, 3, 7), -15, &Modifier::Act, &invalid, true)
+            .is_err());
     }
 
     #[test]
+    #[allow(clippy::needless_range_loop, clippy::useless_vec)]
     fn test_add_months_modifier() {
         let cal = get_calendar_by_name("bus").unwrap();
         let modi = vec![
@@ -736,13 +840,150 @@ mod tests {
                     &modi[i].0,
                     &RollDay::Unspecified {},
                     true
-                ),
+                )
+                .unwrap(),
                 modi[i].1
             );
         }
     }
 
     #[test]
+    fn test_bus_days_between() {
+        let cal = fixture_hol_cal();
+        // Friday 4th to Tuesday 8th September 2015 over a weekend and Monday holiday
+        assert_eq!(cal.bus_days_between(&ndt(2015, 9, 4), &ndt(2015, 9, 8)), 1);
+        assert_eq!(cal.bus_days_between(&ndt(2015, 9, 8), &ndt(2015, 9, 4)), -1);
+        assert_eq!(cal.bus_days_between(&ndt(2015, 9, 4), &ndt(2015, 9, 4)), 0);
+        assert_eq!(cal.bus_days_between(&ndt(2015, 9, 4), &ndt(2015, 9, 11)), 4);
+    }
+
+    #[test]
+    fn test_tenor_between() {
+        let cal = get_calendar_by_name("bus").unwrap();
+        let unspecified = RollDay::Unspecified {};
+        let cases = [
+            (
+                ndt(2024, 1, 15),
+                ndt(2024, 4, 15),
+                unspecified,
+                Tenor::Months(3),
+            ),
+            (
+                ndt(2024, 4, 15),
+                ndt(2024, 1, 15),
+                unspecified,
+                Tenor::Months(-3),
+            ),
+            (
+                ndt(2024, 1, 31),
+                ndt(2024, 2, 29),
+                unspecified,
+                Tenor::Months(1),
+            ),
+            // a month end rolls to a month end only under an end of month roll
+            (
+                ndt(2024, 2, 29),
+                ndt(2024, 3, 31),
+                unspecified,
+                Tenor::Days(31),
+            ),
+            (
+                ndt(2024, 2, 29),
+                ndt(2024, 3, 31),
+                RollDay::EoM {},
+                Tenor::Months(1),
+            ),
+            (
+                ndt(2024, 1, 1),
+                ndt(2024, 1, 15),
+                unspecified,
+                Tenor::Weeks(2),
+            ),
+            (
+                ndt(2024, 1, 1),
+                ndt(2024, 1, 1),
+                unspecified,
+                Tenor::Days(0),
+            ),
+        ];
+        for (start, end, roll, expected) in cases {
+            assert_eq!(cal.tenor_between(&start, &end, &roll), expected);
+        }
+    }
+
+    #[test]
+    fn test_add_tenor_with_roll() {
+        let cal = fixture_hol_cal();
+        let (act, roll) = (Modifier::Act, RollDay::Unspecified {});
+        let add = |date, tenor: &str, modifier: &Modifier, roll: &RollDay| {
+            cal.add_tenor_with_roll(
+                &date,
+                &Tenor::try_new(tenor).unwrap(),
+                modifier,
+                roll,
+                false,
+            )
+            .unwrap()
+        };
+        assert_eq!(add(ndt(2015, 9, 4), "1B", &act, &roll), ndt(2015, 9, 8));
+        assert_eq!(add(ndt(2015, 9, 8), "-1B", &act, &roll), ndt(2015, 9, 4));
+        assert_eq!(
+            add(ndt(2015, 8, 7), "1M", &Modifier::F, &roll),
+            ndt(2015, 9, 8)
+        );
+        assert_eq!(
+            add(ndt(2015, 10, 7), "-1M", &Modifier::P, &roll),
+            ndt(2015, 9, 4)
+        );
+        assert_eq!(
+            add(ndt(2015, 6, 30), "2M", &act, &RollDay::EoM {}),
+            ndt(2015, 8, 31)
+        );
+        assert_eq!(add(ndt(2015, 9, 1), "-2W", &act, &roll), ndt(2015, 8, 18));
+        assert!(cal
+            .add_tenor_with_roll(&ndt(2015, 9, 7), &Tenor::BusDays(1), &act, &roll, false)
+            .is_err());
+    }
+
+    #[test]
+    fn test_tenor_properties() {
+        // properties over pseudo-random dates, tenors and rolls
+        use crate::montecarlo::NormalRng;
+        let cal = get_calendar_by_name("ldn").unwrap();
+        let mut rng = NormalRng::new(42);
+        let date = |rng: &mut NormalRng| ndt(2000, 1, 1) + Days::new(rng.next_u64() % 12000);
+        let rolls = [
+            RollDay::Unspecified {},
+            RollDay::EoM {},
+            RollDay::Int { day: 30 },
+        ];
+        for _ in 0..500 {
+            let (a, b, c) = (date(&mut rng), date(&mut rng), date(&mut rng));
+            // business days between dates are antisymmetric and additive
+            assert_eq!(cal.bus_days_between(&a, &b), -cal.bus_days_between(&b, &a));
+            assert_eq!(
+                cal.bus_days_between(&a, &b) + cal.bus_days_between(&b, &c),
+                cal.bus_days_between(&a, &c)
+            );
+            // the tenor between dates added to the start returns the end
+            let roll = rolls[(rng.next_u64() % 3) as usize];
+            let tenor = cal.tenor_between(&a, &b, &roll);
+            let end = cal
+                .add_tenor_with_roll(&a, &tenor, &Modifier::Act, &roll, false)
+                .unwrap();
+            assert_eq!(end, b);
+            // a number of business days added to a business day are counted between them
+            let start = cal.roll_forward_bus_day(&a);
+            let n = (rng.next_u64() % 61) as i32 - 30;
+            let end = cal
+                .add_tenor_with_roll(&start, &Tenor::BusDays(n), &Modifier::Act, &roll, false)
+                .unwrap();
+            assert_eq!(cal.bus_days_between(&start, &end), n);
+        }
+    }
+
+    #[test]
+    #[allow(clippy::needless_range_loop, clippy::useless_vec)]
     fn test_add_months_modifier_p() {
         let cal = get_calendar_by_name("bus").unwrap();
         let modi = vec![
@@ -760,7 +1001,8 @@ mod tests {
                     &modi[i].0,
                     &RollDay::Unspecified {},
                     true
-                ),
+                )
+                .unwrap(),
                 modi[i].1
             );
         }
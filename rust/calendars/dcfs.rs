@@ -1,8 +1,14 @@
+use crate::calendars::calendar::ndt;
+use crate::calendars::date::days_in_month;
+use crate::calendars::dateroll::{DateRoll, Modifier, RollDay};
+use crate::error::RateslibError;
+use chrono::prelude::*;
+#[cfg(feature = "python")]
 use pyo3::{pyclass, pyfunction};
 use serde::{Deserialize, Serialize};
 use std::cmp::PartialEq;
 
-#[pyclass(module = "rateslib.rs")]
+#[cfg_attr(feature = "python", pyclass(module = "rateslib.rs"))]
 #[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
 pub enum Convention {
     /// Return 1.0 for any period.
@@ -15,14 +21,240 @@ pub enum Convention {
     Act365FPlus,
     /// Actual days in period divided by 360.
     Act360,
+    /// 30 days in month and 360 days in year with day adjustment of both start and end.
     ThirtyE360,
+    /// 30 days in month and 360 days in year with end day adjustment conditional on start.
     Thirty360,
+    /// 30 days in month and 360 days in year with ISDA adjustment of February month end.
     Thirty360ISDA,
+    /// Actual days in period divided by actual days in each calendar year.
     ActActISDA,
+    /// Actual days in period relative to the days in a regular period of the frequency.
     ActActICMA,
+    /// Business days in period divided by 252.
     Bus252,
 }
 
+impl Convention {
+    /// Return the day count fraction (DCF) between two dates.
+    ///
+    /// - `termination` is required by `Thirty360ISDA` and `ActActICMA`.
+    /// - `frequency_months` and `stub` are required by `ActActICMA`.
+    /// - `roll` is used by `ActActICMA` to determine regular periods for stubs.
+    /// - `calendar` is used by `Bus252` to count business days.
+    #[allow(clippy::too_many_arguments)]
+    pub fn dcf<T: DateRoll>(
+        &self,
+        start: &NaiveDateTime,
+        end: &NaiveDateTime,
+        termination: Option<&NaiveDateTime>,
+        frequency_months: Option<u32>,
+        stub: Option<bool>,
+        roll: &RollDay,
+        calendar: &T,
+    ) -> Result<f64, RateslibError> {
+        match self {
+            Convention::One => Ok(1.0),
+            Convention::OnePlus => Ok(dcf_1plus(start, end)),
+            Convention::Act365F => Ok(days(start, end) / 365.0),
+            Convention::Act365FPlus => Ok(dcf_act365fplus(start, end)),
+            Convention::Act360 => Ok(days(start, end) / 360.0),
+            Convention::Thirty360 => {
+                let ds = start.day().min(30);
+                let de = if ds == 30 {
+                    end.day().min(30)
+                } else {
+                    end.day()
+                };
+                Ok(dcf_30360_days(start, end, ds, de))
+            }
+            Convention::ThirtyE360 => Ok(dcf_30360_days(
+                start,
+                end,
+                start.day().min(30),
+                end.day().min(30),
+            )),
+            Convention::Thirty360ISDA => {
+                let termination = termination.ok_or_else(|| missing("termination"))?;
+                let ds = if start.day() == 31 || is_end_feb(start) {
+                    30
+                } else {
+                    start.day()
+                };
+                let de = if end.day() == 31 || (is_end_feb(end) && end != termination) {
+                    30
+                } else {
+                    end.day()
+                };
+                Ok(dcf_30360_days(start, end, ds, de))
+            }
+            Convention::ActActISDA => Ok(dcf_actactisda(start, end)),
+            Convention::ActActICMA => {
+                let frequency_months =
+                    frequency_months.ok_or_else(|| missing("frequency_months"))?;
+                let termination = termination.ok_or_else(|| missing("termination"))?;
+                let stub = stub.ok_or_else(|| missing("stub"))?;
+                dcf_actacticma(
+                    start,
+                    end,
+                    termination,
+                    frequency_months,
+                    stub,
+                    roll,
+                    calendar,
+                )
+            }
+            Convention::Bus252 => dcf_bus252(start, end, calendar),
+        }
+    }
+}
+
+fn missing(arg: &str) -> RateslibError {
+    RateslibError::value(format!(
+        "`{}` must be supplied with specified `convention`.",
+        arg
+    ))
+}
+
+/// Return the actual days between two datetimes, including the fraction of a day of their
+/// times of day.
+fn days(start: &NaiveDateTime, end: &NaiveDateTime) -> f64 {
+    (*end - *start).num_seconds() as f64 / 86400.0
+}
+
+fn dcf_1plus(start: &NaiveDateTime, end: &NaiveDateTime) -> f64 {
+    (end.year() - start.year()) as f64 + (end.month() as f64 - start.month() as f64) / 12.0
+}
+
+/// Return the date with the year replaced, clamping 29th Feb to 28th Feb if necessary.
+fn with_year(date: &NaiveDateTime, year: i32) -> NaiveDateTime {
+    ndt(
+        year,
+        date.month(),
+        date.day().min(days_in_month(year, date.month())),
+    )
+}
+
+fn dcf_act365fplus(start: &NaiveDateTime, end: &NaiveDateTime) -> f64 {
+    if *end <= with_year(start, start.year() + 1) {
+        days(start, end) / 365.0
+    } else if *end <= with_year(start, end.year()) {
+        (end.year() - start.year()) as f64 + days(&with_year(start, end.year()), end) / 365.0
+    } else {
+        (end.year() - start.year() - 1) as f64
+            + days(&with_year(start, end.year() - 1), end) / 365.0
+    }
+}
+
+fn dcf_30360_days(start: &NaiveDateTime, end: &NaiveDateTime, ds: u32, de: u32) -> f64 {
+    (end.year() - start.year()) as f64
+        + (end.month() as f64 - start.month() as f64) / 12.0
+        + (de as f64 - ds as f64) / 360.0
+}
+
+fn is_end_feb(date: &NaiveDateTime) -> bool {
+    date.month() == 2 && date.day() == days_in_month(date.year(), 2)
+}
+
+fn year_days(year: i32) -> f64 {
+    if days_in_month(year, 2) == 29 {
+        366.0
+    } else {
+        365.0
+    }
+}
+
+fn dcf_actactisda(start: &NaiveDateTime, end: &NaiveDateTime) -> f64 {
+    if start == end {
+        return 0.0;
+    }
+    let start_ = ndt(start.year(), start.month(), start.day());
+    let end_ = ndt(end.year(), end.month(), end.day());
+    (end.year() - start.year() - 1) as f64
+        + days(&start_, &ndt(start.year() + 1, 1, 1)) / year_days(start.year())
+        + days(&ndt(end.year(), 1, 1), &end_) / year_days(end.year())
+}
+
+fn dcf_actacticma<T: DateRoll>(
+    start: &NaiveDateTime,
+    end: &NaiveDateTime,
+    termination: &NaiveDateTime,
+    frequency_months: u32,
+    stub: bool,
+    roll: &RollDay,
+    calendar: &T,
+) -> Result<f64, RateslibError> {
+    if !stub && frequency_months < 13 {
+        return Ok(frequency_months as f64 / 12.0);
+    }
+    // zero coupon frequencies are handled as annual stubs.
+    let months = frequency_months.min(12) as i32;
+    let mut fraction = -1.0;
+    if end == termination {
+        // back stub, possibly long, measured forward from start.
+        let (mut fwd_end_0, mut fwd_end_1) = (*start, *start);
+        while *end > fwd_end_1 {
+            fwd_end_0 = fwd_end_1;
+            fraction += 1.0;
+            fwd_end_1 = calendar.add_months(
+                start,
+                (fraction as i32 + 1) * months,
+                &Modifier::Act,
+                roll,
+                false,
+            )?;
+        }
+        fraction += days(&fwd_end_0, end) / days(&fwd_end_0, &fwd_end_1);
+    } else {
+        // front stub, possibly long, measured backward from end.
+        let (mut prev_start_0, mut prev_start_1) = (*end, *end);
+        while *start < prev_start_1 {
+            prev_start_0 = prev_start_1;
+            fraction += 1.0;
+            prev_start_1 = calendar.add_months(
+                end,
+                -(fraction as i32 + 1) * months,
+                &Modifier::Act,
+                roll,
+                false,
+            )?;
+        }
+        fraction += days(start, &prev_start_0) / days(&prev_start_1, &prev_start_0);
+    }
+    Ok(fraction * months as f64 / 12.0)
+}
+
+fn dcf_bus252<T: DateRoll>(
+    start: &NaiveDateTime,
+    end: &NaiveDateTime,
+    calendar: &T,
+) -> Result<f64, RateslibError> {
+    if end < start {
+        return Err(RateslibError::value(
+            "Cannot return negative DCF for `end` before `start`.",
+        ));
+    } else if end == start {
+        return Ok(0.0);
+    }
+    let start_ = calendar.roll_forward_bus_day(start);
+    let end_ = calendar.roll_backward_bus_day(end);
+    let subtract = if end_ == *end { -1.0 } else { 0.0 };
+    if start_ == end_ {
+        if end_ < *end {
+            // a business day between a start and a non-business end.
+            return Ok(1.0 / 252.0);
+        } else {
+            // a non-business start before a business end does not count.
+            return Ok(0.0);
+        }
+    } else if start_ > end_ {
+        return Ok(0.0);
+    }
+    let dr = calendar.bus_date_range(&start_, &end_)?;
+    Ok((dr.len() as f64 + subtract) / 252.0)
+}
+
+#[cfg(feature = "python")]
 #[pyfunction]
 pub(crate) fn _get_convention_str(convention: Convention) -> String {
     match convention {
@@ -39,3 +271,195 @@ pub(crate) fn _get_convention_str(convention: Convention) -> String {
         Convention::Bus252 => "Bus252".to_string(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::{Cal, NamedCal};
+
+    fn dcf(
+        convention: Convention,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+        termination: Option<NaiveDateTime>,
+        frequency_months: Option<u32>,
+        stub: Option<bool>,
+    ) -> f64 {
+        let cal = Cal::new(vec![], vec![]);
+        convention
+            .dcf(
+                &start,
+                &end,
+                termination.as_ref(),
+                frequency_months,
+                stub,
+                &RollDay::Unspecified {},
+                &cal,
+            )
+            .unwrap()
+    }
+
+    fn is_close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-12
+    }
+
+    #[test]
+    fn test_act_conventions() {
+        let (s, e) = (ndt(2022, 1, 1), ndt(2022, 4, 1));
+        assert!(is_close(
+            dcf(Convention::Act360, s, e, None, None, None),
+            90.0 / 360.0
+        ));
+        assert!(is_close(
+            dcf(Convention::Act365F, s, e, None, None, None),
+            90.0 / 365.0
+        ));
+        assert!(is_close(dcf(Convention::One, s, e, None, None, None), 1.0));
+        assert!(is_close(
+            dcf(Convention::OnePlus, s, e, None, None, None),
+            0.25
+        ));
+    }
+
+    #[test]
+    fn test_act_conventions_intraday() {
+        let s = ndt(2024, 3, 15) + chrono::Duration::hours(9);
+        let e = ndt(2024, 3, 15) + chrono::Duration::hours(15);
+        assert!(is_close(
+            dcf(Convention::Act365F, s, e, None, None, None),
+            0.25 / 365.0
+        ));
+        assert!(is_close(
+            dcf(
+                Convention::Act360,
+                s,
+                e + chrono::Duration::days(1),
+                None,
+                None,
+                None
+            ),
+            1.25 / 360.0
+        ));
+    }
+
+    #[test]
+    fn test_act365fplus() {
+        let s = ndt(2022, 2, 15);
+        let options = [
+            (ndt(2022, 8, 15), 181.0 / 365.0),
+            (ndt(2024, 2, 15), 2.0),
+            (ndt(2024, 3, 15), 2.0 + 29.0 / 365.0),
+            (ndt(2024, 1, 15), 1.0 + 334.0 / 365.0),
+        ];
+        for (e, expected) in options {
+            assert!(is_close(
+                dcf(Convention::Act365FPlus, s, e, None, None, None),
+                expected
+            ));
+        }
+    }
+
+    #[test]
+    fn test_thirty360_conventions() {
+        let (s, e) = (ndt(2022, 1, 31), ndt(2022, 3, 31));
+        assert!(is_close(
+            dcf(Convention::Thirty360, s, e, None, None, None),
+            60.0 / 360.0
+        ));
+        assert!(is_close(
+            dcf(Convention::ThirtyE360, s, e, None, None, None),
+            60.0 / 360.0
+        ));
+        let (s, e) = (ndt(2022, 1, 15), ndt(2022, 3, 31));
+        assert!(is_close(
+            dcf(Convention::Thirty360, s, e, None, None, None),
+            76.0 / 360.0
+        ));
+        assert!(is_close(
+            dcf(Convention::ThirtyE360, s, e, None, None, None),
+            75.0 / 360.0
+        ));
+        // February month end is adjusted unless it is the termination
+        let (s, e) = (ndt(2022, 8, 31), ndt(2023, 2, 28));
+        let result = dcf(
+            Convention::Thirty360ISDA,
+            s,
+            e,
+            Some(ndt(2025, 2, 28)),
+            None,
+            None,
+        );
+        assert!(is_close(result, 0.5));
+        let result = dcf(Convention::Thirty360ISDA, s, e, Some(e), None, None);
+        assert!(is_close(result, 178.0 / 360.0));
+    }
+
+    #[test]
+    fn test_actactisda() {
+        let (s, e) = (ndt(2023, 11, 1), ndt(2024, 2, 1));
+        let expected = 61.0 / 365.0 + 31.0 / 366.0;
+        assert!(is_close(
+            dcf(Convention::ActActISDA, s, e, None, None, None),
+            expected
+        ));
+    }
+
+    #[test]
+    fn test_actacticma() {
+        let t = ndt(2025, 6, 15);
+        let (s, e) = (ndt(2022, 6, 15), ndt(2022, 12, 15));
+        let result = dcf(Convention::ActActICMA, s, e, Some(t), Some(6), Some(false));
+        assert!(is_close(result, 0.5));
+        // short front stub
+        let (s, e) = (ndt(2022, 9, 15), ndt(2022, 12, 15));
+        let result = dcf(Convention::ActActICMA, s, e, Some(t), Some(6), Some(true));
+        assert!(is_close(result, 0.5 * 91.0 / 183.0));
+        // long back stub
+        let (s, e) = (ndt(2024, 12, 15), ndt(2025, 4, 15));
+        let result = dcf(Convention::ActActICMA, s, e, Some(e), Some(3), Some(true));
+        assert!(is_close(result, 0.25 + 0.25 * 31.0 / 92.0));
+        let result = Convention::ActActICMA.dcf(
+            &s,
+            &e,
+            None,
+            Some(3),
+            Some(true),
+            &RollDay::Unspecified {},
+            &Cal::new(vec![], vec![]),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_bus252() {
+        let cal = NamedCal::try_new("bus").unwrap();
+        let bus252 = |s: NaiveDateTime, e: NaiveDateTime| {
+            Convention::Bus252
+                .dcf(&s, &e, None, None, None, &RollDay::Unspecified {}, &cal)
+                .unwrap()
+        };
+        // Mon to Mon; five business days
+        assert!(is_close(
+            bus252(ndt(2024, 3, 4), ndt(2024, 3, 11)),
+            5.0 / 252.0
+        ));
+        // Sat to Sun; no business days
+        assert!(is_close(bus252(ndt(2024, 3, 9), ndt(2024, 3, 10)), 0.0));
+        // Fri to Sat
+        assert!(is_close(
+            bus252(ndt(2024, 3, 8), ndt(2024, 3, 9)),
+            1.0 / 252.0
+        ));
+        assert!(Convention::Bus252
+            .dcf(
+                &ndt(2024, 3, 9),
+                &ndt(2024, 3, 8),
+                None,
+                None,
+                None,
+                &RollDay::Unspecified {},
+                &cal
+            )
+            .is_err());
+    }
+}
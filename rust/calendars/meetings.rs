@@ -0,0 +1,237 @@
+use crate::calendars::{ndt, DateRoll, NamedCal};
+use crate::error::RateslibError;
+use chrono::NaiveDateTime;
+
+/// The decision dates of the scheduled policy meetings of a central bank, and the business day
+/// calendar and lag by which its decisions take effect.
+///
+/// The named calendars *"fomc"*, *"ecb"*, *"boe"* and *"boj"* contain the announced meetings of
+/// 2024 to 2026, which may be extended, or corrected for unscheduled meetings, with
+/// [MeetingCalendar::with_dates].
+///
+/// # Examples
+///
+/// ```rust
+/// # use rateslib::calendars::{ndt, MeetingCalendar};
+/// let fomc = MeetingCalendar::try_new("fomc").unwrap();
+/// let effective = fomc.effective_dates(&ndt(2025, 1, 1), &ndt(2025, 4, 1));
+/// // decisions take effect on the following business day.
+/// assert_eq!(effective, vec![ndt(2025, 1, 30), ndt(2025, 3, 20)]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeetingCalendar {
+    pub(crate) name: String,
+    pub(crate) dates: Vec<NaiveDateTime>,
+    pub(crate) calendar: NamedCal,
+    pub(crate) lag: i8,
+}
+
+impl MeetingCalendar {
+    /// Create a calendar of decision `dates` taking effect `lag` business days later on the
+    /// `calendar`.
+    pub fn new(name: &str, mut dates: Vec<NaiveDateTime>, calendar: NamedCal, lag: i8) -> Self {
+        dates.sort();
+        dates.dedup();
+        Self {
+            name: name.to_lowercase(),
+            dates,
+            calendar,
+            lag,
+        }
+    }
+
+    /// Return the named meeting calendar of *"fomc"*, *"ecb"*, *"boe"* or *"boj"*.
+    pub fn try_new(name: &str) -> Result<Self, RateslibError> {
+        let name = name.to_lowercase();
+        let (dates, calendar, lag): (&[(i32, u32, u32)], &str, i8) = match name.as_str() {
+            "fomc" => (FOMC, "fed", 1),
+            // rates change from the start of the following maintenance period, on a Wednesday
+            "ecb" => (ECB, "tgt", 4),
+            "boe" => (BOE, "ldn", 0),
+            "boj" => (BOJ, "tyo", 1),
+            _ => {
+                return Err(RateslibError::value(format!(
+                    "'{}' must be one of 'fomc', 'ecb', 'boe' or 'boj'.",
+                    name
+                )))
+            }
+        };
+        let dates = dates.iter().map(|(y, m, d)| ndt(*y, *m, *d)).collect();
+        Ok(Self::new(&name, dates, NamedCal::try_new(calendar)?, lag))
+    }
+
+    /// Return the calendar with the additional decision `dates`.
+    pub fn with_dates(mut self, dates: &[NaiveDateTime]) -> Self {
+        self.dates.extend_from_slice(dates);
+        Self::new(&self.name, self.dates, self.calendar, self.lag)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Return the decision dates between `start` and `end`, inclusive.
+    pub fn decision_dates(&self, start: &NaiveDateTime, end: &NaiveDateTime) -> Vec<NaiveDateTime> {
+        self.dates
+            .iter()
+            .filter(|d| *d >= start && *d <= end)
+            .copied()
+            .collect()
+    }
+
+    /// Return the dates on which the decisions take effect, between `start` and `end` exclusive,
+    /// which are the dates at which the policy rate may change.
+    pub fn effective_dates(
+        &self,
+        start: &NaiveDateTime,
+        end: &NaiveDateTime,
+    ) -> Vec<NaiveDateTime> {
+        self.dates
+            .iter()
+            .map(|d| self.calendar.lag(d, self.lag, false))
+            .filter(|d| d > start && d < end)
+            .collect()
+    }
+}
+
+/// Second days of the two day meetings of the Federal Open Market Committee.
+const FOMC: &[(i32, u32, u32)] = &[
+    (2024, 1, 31),
+    (2024, 3, 20),
+    (2024, 5, 1),
+    (2024, 6, 12),
+    (2024, 7, 31),
+    (2024, 9, 18),
+    (2024, 11, 7),
+    (2024, 12, 18),
+    (2025, 1, 29),
+    (2025, 3, 19),
+    (2025, 5, 7),
+    (2025, 6, 18),
+    (2025, 7, 30),
+    (2025, 9, 17),
+    (2025, 10, 29),
+    (2025, 12, 10),
+    (2026, 1, 28),
+    (2026, 3, 18),
+    (2026, 4, 29),
+    (2026, 6, 17),
+    (2026, 7, 29),
+    (2026, 9, 16),
+    (2026, 10, 28),
+    (2026, 12, 9),
+];
+
+/// Monetary policy meetings of the Governing Council of the European Central Bank.
+const ECB: &[(i32, u32, u32)] = &[
+    (2024, 1, 25),
+    (2024, 3, 7),
+    (2024, 4, 11),
+    (2024, 6, 6),
+    (2024, 7, 18),
+    (2024, 9, 12),
+    (2024, 10, 17),
+    (2024, 12, 12),
+    (2025, 1, 30),
+    (2025, 3, 6),
+    (2025, 4, 17),
+    (2025, 6, 5),
+    (2025, 7, 24),
+    (2025, 9, 11),
+    (2025, 10, 30),
+    (2025, 12, 18),
+    (2026, 2, 5),
+    (2026, 3, 19),
+    (2026, 4, 30),
+    (2026, 6, 11),
+    (2026, 7, 23),
+    (2026, 9, 10),
+    (2026, 10, 29),
+    (2026, 12, 17),
+];
+
+/// Announcements of the Monetary Policy Committee of the Bank of England.
+const BOE: &[(i32, u32, u32)] = &[
+    (2024, 2, 1),
+    (2024, 3, 21),
+    (2024, 5, 9),
+    (2024, 6, 20),
+    (2024, 8, 1),
+    (2024, 9, 19),
+    (2024, 11, 7),
+    (2024, 12, 19),
+    (2025, 2, 6),
+    (2025, 3, 20),
+    (2025, 5, 8),
+    (2025, 6, 19),
+    (2025, 8, 7),
+    (2025, 9, 18),
+    (2025, 11, 6),
+    (2025, 12, 18),
+    (2026, 2, 5),
+    (2026, 3, 19),
+    (2026, 4, 30),
+    (2026, 6, 18),
+    (2026, 7, 30),
+    (2026, 9, 17),
+    (2026, 11, 5),
+    (2026, 12, 17),
+];
+
+/// Final days of the Monetary Policy Meetings of the Bank of Japan.
+const BOJ: &[(i32, u32, u32)] = &[
+    (2024, 1, 23),
+    (2024, 3, 19),
+    (2024, 4, 26),
+    (2024, 6, 14),
+    (2024, 7, 31),
+    (2024, 9, 20),
+    (2024, 10, 31),
+    (2024, 12, 19),
+    (2025, 1, 24),
+    (2025, 3, 19),
+    (2025, 5, 1),
+    (2025, 6, 17),
+    (2025, 7, 31),
+    (2025, 9, 19),
+    (2025, 10, 30),
+    (2025, 12, 19),
+    (2026, 1, 23),
+    (2026, 3, 19),
+    (2026, 4, 28),
+    (2026, 6, 16),
+    (2026, 7, 31),
+    (2026, 9, 18),
+    (2026, 10, 30),
+    (2026, 12, 18),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_meeting_calendars() {
+        for name in ["fomc", "ECB", "boe", "boj"] {
+            let m = MeetingCalendar::try_new(name).unwrap();
+            assert_eq!(
+                m.decision_dates(&ndt(2025, 1, 1), &ndt(2025, 12, 31)).len(),
+                8
+            );
+        }
+        let ecb = MeetingCalendar::try_new("ecb").unwrap();
+        // a Thursday decision takes effect on the following Wednesday
+        assert_eq!(
+            ecb.effective_dates(&ndt(2025, 6, 1), &ndt(2025, 7, 1)),
+            vec![ndt(2025, 6, 11)]
+        );
+        let boe = MeetingCalendar::try_new("boe").unwrap();
+        assert_eq!(
+            boe.effective_dates(&ndt(2025, 12, 1), &ndt(2026, 1, 1)),
+            vec![ndt(2025, 12, 18)]
+        );
+        let extended = boe.with_dates(&[ndt(2027, 2, 4), ndt(2025, 12, 18)]);
+        assert_eq!(extended.dates.len(), 25);
+        assert!(MeetingCalendar::try_new("snb").is_err());
+    }
+}
@@ -94,19 +94,37 @@
 //! ```
 
 mod calendar;
-pub use crate::calendars::calendar::{ndt, Cal, CalType, NamedCal, UnionCal};
+pub use crate::calendars::calendar::{ndt, try_ndt, Cal, CalType, NamedCal, UnionCal};
 
 pub mod named;
-pub use crate::calendars::named::get_calendar_by_name;
+pub use crate::calendars::named::{
+    apply_holiday_updates, get_calendar_by_name, holiday_updates_version, reset_holiday_updates,
+    HolidayChanges, HolidayUpdates,
+};
+
+mod date;
+pub use crate::calendars::date::{days_in_month, is_leap_year};
+
+mod cache;
+pub use crate::calendars::cache::{BusDayIndex, CachedCal};
 
 mod dateroll;
 pub use crate::calendars::dateroll::{get_imm, get_roll, DateRoll, Modifier, RollDay};
 
+mod tenor;
+pub use crate::calendars::tenor::Tenor;
+
+mod meetings;
+pub use crate::calendars::meetings::MeetingCalendar;
+
 mod dcfs;
-pub use crate::calendars::dcfs::Convention;
+#[cfg(feature = "python")]
 pub(crate) use crate::calendars::dcfs::_get_convention_str;
+pub use crate::calendars::dcfs::Convention;
 
 mod serde;
 
+#[cfg(feature = "python")]
 pub(crate) mod calendar_py;
+#[cfg(feature = "python")]
 pub(crate) use crate::calendars::calendar_py::_get_modifier_str;
@@ -15,13 +15,18 @@ pub mod zur;
 
 pub mod wlg;
 
+mod updates;
+pub use crate::calendars::named::updates::{
+    apply_holiday_updates, holiday_updates_version, reset_holiday_updates, HolidayChanges,
+    HolidayUpdates,
+};
+
 use crate::calendars::calendar::Cal;
+use crate::error::RateslibError;
 use chrono::NaiveDateTime;
-use pyo3::exceptions::PyValueError;
-use pyo3::PyErr;
 use std::collections::HashMap;
 
-fn get_weekmask_by_name(name: &str) -> Result<Vec<u8>, PyErr> {
+fn get_weekmask_by_name(name: &str) -> Result<Vec<u8>, RateslibError> {
     let hmap: HashMap<&str, &[u8]> = HashMap::from([
         ("all", all::WEEKMASK),
         ("bus", bus::WEEKMASK),
@@ -38,7 +43,7 @@ fn get_weekmask_by_name(name: &str) -> Result<Vec<u8>, PyErr> {
         ("wlg", wlg::WEEKMASK),
     ]);
     match hmap.get(name) {
-        None => Err(PyValueError::new_err(format!(
+        None => Err(RateslibError::value(format!(
             "'{}' is not found in list of existing calendars.",
             name
         ))),
@@ -46,7 +51,7 @@ fn get_weekmask_by_name(name: &str) -> Result<Vec<u8>, PyErr> {
     }
 }
 
-fn get_holidays_by_name(name: &str) -> Result<Vec<NaiveDateTime>, PyErr> {
+fn get_holidays_by_name(name: &str) -> Result<Vec<NaiveDateTime>, RateslibError> {
     let hmap: HashMap<&str, &[&str]> = HashMap::from([
         ("all", all::HOLIDAYS),
         ("bus", bus::HOLIDAYS),
@@ -63,7 +68,7 @@ fn get_holidays_by_name(name: &str) -> Result<Vec<NaiveDateTime>, PyErr> {
         ("wlg", wlg::HOLIDAYS),
     ]);
     match hmap.get(name) {
-        None => Err(PyValueError::new_err(format!(
+        None => Err(RateslibError::value(format!(
             "'{}' is not found in list of existing calendars.",
             name
         ))),
@@ -74,7 +79,7 @@ fn get_holidays_by_name(name: &str) -> Result<Vec<NaiveDateTime>, PyErr> {
     }
 }
 
-// fn get_rules_by_name(name: &str) -> Result<Vec<&str>, PyErr> {
+// fn get_rules_by_name(name: &str) -> Result<Vec<&str>, RateslibError> {
 //     let hmap: HashMap<&str, &[&str]> = HashMap::from([
 //         ("all", all::RULES),
 //         ("bus", bus::RULES),
@@ -91,14 +96,15 @@ fn get_holidays_by_name(name: &str) -> Result<Vec<NaiveDateTime>, PyErr> {
 //         ("wlg", wlg::RULES),
 //     ]);
 //     match hmap.get(name) {
-//         None => Err(PyValueError::new_err(format!("'{}' is not found in list of existing calendars.", name))),
+//         None => Err(RateslibError::value(format!("'{}' is not found in list of existing calendars.", name))),
 //         Some(value) => Ok(value.to_vec())
 //     }
 // }
 
 /// Return a static `Cal` specified by a named identifier.
 ///
-/// For available 3-digit names see `named` module documentation.
+/// For available 3-digit names see `named` module documentation. Holidays include any
+/// changes merged by [apply_holiday_updates].
 ///
 /// # Examples
 ///
@@ -106,9 +112,13 @@ fn get_holidays_by_name(name: &str) -> Result<Vec<NaiveDateTime>, PyErr> {
 /// # use rateslib::calendars::get_calendar_by_name;
 /// let ldn_cal = get_calendar_by_name("ldn").unwrap();
 /// ```
-pub fn get_calendar_by_name(name: &str) -> Result<Cal, PyErr> {
+pub fn get_calendar_by_name(name: &str) -> Result<Cal, RateslibError> {
+    let holidays = get_holidays_by_name(name)?;
     Ok(Cal::new(
-        get_holidays_by_name(name)?,
+        match updates::holiday_changes(name) {
+            Some(changes) => changes.apply(holidays),
+            None => holidays,
+        },
         get_weekmask_by_name(name)?,
         // get_rules_by_name(name)?
     ))
@@ -226,6 +236,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_get_calendar_error() {
         match get_calendar_by_name("badname") {
             Ok(_) => assert!(false),
@@ -0,0 +1,239 @@
+//! Merge announcements of holidays made after release into the named calendars at runtime.
+
+use crate::calendars::named::get_holidays_by_name;
+use crate::error::RateslibError;
+use chrono::{NaiveDate, NaiveDateTime};
+use indexmap::{IndexMap, IndexSet};
+use serde::Deserialize;
+use std::sync::{OnceLock, RwLock};
+
+/// The holidays added to and removed from a named calendar.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HolidayChanges {
+    pub added: IndexSet<NaiveDateTime>,
+    pub removed: IndexSet<NaiveDateTime>,
+}
+
+impl HolidayChanges {
+    fn add(&mut self, date: NaiveDateTime) {
+        self.removed.shift_remove(&date);
+        self.added.insert(date);
+    }
+
+    fn remove(&mut self, date: NaiveDateTime) {
+        self.added.shift_remove(&date);
+        self.removed.insert(date);
+    }
+
+    fn merge(&mut self, other: &HolidayChanges) {
+        other.added.iter().for_each(|d| self.add(*d));
+        other.removed.iter().for_each(|d| self.remove(*d));
+    }
+
+    /// Return the `holidays` of a calendar with the changes applied.
+    pub(crate) fn apply(&self, holidays: Vec<NaiveDateTime>) -> Vec<NaiveDateTime> {
+        holidays
+            .into_iter()
+            .filter(|d| !self.removed.contains(d))
+            .chain(self.added.iter().copied())
+            .collect()
+    }
+}
+
+/// A versioned set of [HolidayChanges] to the named calendars.
+///
+/// The JSON form maps calendar names to dates, formatted `%Y-%m-%d`, to add and remove:
+///
+/// ```json
+/// {"version": 2, "calendars": {"nyc": {"add": ["2025-01-09"], "remove": []}}}
+/// ```
+///
+/// The CSV form has a row of `calendar,date,action` for each change, with an action of `add`
+/// or `remove`, and an optional header row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HolidayUpdates {
+    pub version: u32,
+    pub calendars: IndexMap<String, HolidayChanges>,
+}
+
+#[derive(Deserialize)]
+struct HolidayUpdatesDataModel {
+    version: u32,
+    calendars: IndexMap<String, HolidayChangesDataModel>,
+}
+
+#[derive(Deserialize)]
+struct HolidayChangesDataModel {
+    #[serde(default)]
+    add: Vec<String>,
+    #[serde(default)]
+    remove: Vec<String>,
+}
+
+fn parse_date(date: &str) -> Result<NaiveDateTime, RateslibError> {
+    NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+        .map(|d| d.and_hms_opt(0, 0, 0).unwrap())
+        .map_err(|_| {
+            RateslibError::value(format!(
+                "Holiday '{}' must be a date formatted '%Y-%m-%d'.",
+                date
+            ))
+        })
+}
+
+impl HolidayUpdates {
+    pub fn from_json(json: &str) -> Result<Self, RateslibError> {
+        let model: HolidayUpdatesDataModel = serde_json::from_str(json)
+            .map_err(|e| RateslibError::value(format!("Invalid holiday updates: {}", e)))?;
+        let mut calendars = IndexMap::new();
+        for (name, changes) in model.calendars.into_iter() {
+            let mut parsed = HolidayChanges::default();
+            for date in changes.add.iter() {
+                parsed.add(parse_date(date)?);
+            }
+            for date in changes.remove.iter() {
+                parsed.remove(parse_date(date)?);
+            }
+            calendars.insert(name.to_lowercase(), parsed);
+        }
+        Ok(Self {
+            version: model.version,
+            calendars,
+        })
+    }
+
+    pub fn from_csv(version: u32, csv: &str) -> Result<Self, RateslibError> {
+        let mut calendars: IndexMap<String, HolidayChanges> = IndexMap::new();
+        for line in csv.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            if fields.len() != 3 {
+                return Err(RateslibError::value(format!(
+                    "Holiday update '{}' must have fields of `calendar,date,action`.",
+                    line
+                )));
+            }
+            if fields[0].eq_ignore_ascii_case("calendar") {
+                continue;
+            }
+            let changes = calendars.entry(fields[0].to_lowercase()).or_default();
+            let date = parse_date(fields[1])?;
+            match fields[2].to_lowercase().as_str() {
+                "add" => changes.add(date),
+                "remove" => changes.remove(date),
+                _ => {
+                    return Err(RateslibError::value(format!(
+                        "Holiday update action '{}' must be 'add' or 'remove'.",
+                        fields[2]
+                    )))
+                }
+            }
+        }
+        Ok(Self { version, calendars })
+    }
+}
+
+#[derive(Default)]
+struct AppliedUpdates {
+    version: Option<u32>,
+    calendars: IndexMap<String, HolidayChanges>,
+}
+
+fn registry() -> &'static RwLock<AppliedUpdates> {
+    static REGISTRY: OnceLock<RwLock<AppliedUpdates>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(AppliedUpdates::default()))
+}
+
+/// Merge `updates` into the named calendars constructed thereafter, such as by
+/// [get_calendar_by_name](crate::calendars::get_calendar_by_name).
+///
+/// Updates are applied in increasing order of version and later changes to a date supersede
+/// earlier ones. Raises if the version is not after that of the updates already applied, or an
+/// updated calendar is not a named calendar.
+pub fn apply_holiday_updates(updates: HolidayUpdates) -> Result<(), RateslibError> {
+    for name in updates.calendars.keys() {
+        get_holidays_by_name(name)?;
+    }
+    let mut applied = registry().write().unwrap();
+    if let Some(version) = applied.version {
+        if updates.version <= version {
+            return Err(RateslibError::value(format!(
+                "Holiday updates of version {} are not after the applied version {}.",
+                updates.version, version
+            )));
+        }
+    }
+    for (name, changes) in updates.calendars.iter() {
+        applied
+            .calendars
+            .entry(name.clone())
+            .or_default()
+            .merge(changes);
+    }
+    applied.version = Some(updates.version);
+    Ok(())
+}
+
+/// Return the version of the latest holiday updates applied, if any.
+pub fn holiday_updates_version() -> Option<u32> {
+    registry().read().unwrap().version
+}
+
+/// Remove all applied holiday updates, restoring the compiled holidays of the named calendars.
+pub fn reset_holiday_updates() {
+    *registry().write().unwrap() = AppliedUpdates::default();
+}
+
+/// Return the applied changes to the named calendar `name`, if any.
+pub(crate) fn holiday_changes(name: &str) -> Option<HolidayChanges> {
+    registry().read().unwrap().calendars.get(name).cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::{get_calendar_by_name, ndt, DateRoll};
+
+    #[test]
+    fn test_parse_updates() {
+        let json = r#"{"version": 1, "calendars": {"LDN": {"add": ["2099-01-06"]}}}"#;
+        let updates = HolidayUpdates::from_json(json).unwrap();
+        assert_eq!(
+            updates.calendars["ldn"].added,
+            IndexSet::from([ndt(2099, 1, 6)])
+        );
+        let csv = "calendar,date,action\nldn,2099-01-06,add\nldn,2099-01-06,remove\n";
+        let updates = HolidayUpdates::from_csv(1, csv).unwrap();
+        // the later change to a date supersedes the earlier
+        assert!(updates.calendars["ldn"].added.is_empty());
+        assert!(HolidayUpdates::from_csv(1, "ldn,2099-01-06,move").is_err());
+        assert!(HolidayUpdates::from_json(
+            r#"{"version": 1, "calendars": {"ldn": {"add": ["06/01/2099"]}}}"#
+        )
+        .is_err());
+    }
+
+    // the registry is global so that every assertion of applied updates is in this one test
+    #[test]
+    fn test_apply_holiday_updates() {
+        let (new, old) = (ndt(2099, 1, 6), ndt(2024, 12, 25));
+        assert!(get_calendar_by_name("wlg").unwrap().is_bus_day(&new));
+        assert!(get_calendar_by_name("wlg").unwrap().is_holiday(&old));
+        let csv = format!("wlg,{},add\nwlg,{},remove", new.date(), old.date());
+        apply_holiday_updates(HolidayUpdates::from_csv(3, &csv).unwrap()).unwrap();
+        assert_eq!(holiday_updates_version(), Some(3));
+        let cal = get_calendar_by_name("wlg").unwrap();
+        assert!(cal.is_holiday(&new));
+        assert!(!cal.is_holiday(&old));
+        // stale versions and unknown calendars are rejected
+        let stale = format!("wlg,{},remove", new.date());
+        assert!(apply_holiday_updates(HolidayUpdates::from_csv(2, &stale).unwrap()).is_err());
+        let unknown = format!("xyz,{},add", new.date());
+        assert!(apply_holiday_updates(HolidayUpdates::from_csv(4, &unknown).unwrap()).is_err());
+        // a later version supersedes an earlier one
+        apply_holiday_updates(HolidayUpdates::from_csv(4, &stale).unwrap()).unwrap();
+        assert!(!get_calendar_by_name("wlg").unwrap().is_holiday(&new));
+        reset_holiday_updates();
+        assert_eq!(holiday_updates_version(), None);
+        assert!(get_calendar_by_name("wlg").unwrap().is_holiday(&old));
+    }
+}
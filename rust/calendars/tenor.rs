@@ -0,0 +1,84 @@
+use crate::error::RateslibError;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::Neg;
+
+/// A signed period of time measured from a date, such as `"3M"`, `"2Y"`, `"-1W"` or `"2B"`.
+///
+/// Years are expressed as multiples of 12 months.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Tenor {
+    /// A number of calendar days, with unit `D`.
+    Days(i32),
+    /// A number of business days, with unit `B`.
+    BusDays(i32),
+    /// A number of weeks, with unit `W`.
+    Weeks(i32),
+    /// A number of months, with unit `M`, or years with unit `Y`.
+    Months(i32),
+}
+
+impl Tenor {
+    pub fn try_new(tenor: &str) -> Result<Self, RateslibError> {
+        let tenor = tenor.trim().to_uppercase();
+        let invalid = || RateslibError::value(format!("`tenor` '{}' is invalid.", tenor));
+        let (n, unit) = tenor.split_at(tenor.len().saturating_sub(1));
+        let n: i32 = n.parse().map_err(|_| invalid())?;
+        match unit {
+            "D" => Ok(Tenor::Days(n)),
+            "B" => Ok(Tenor::BusDays(n)),
+            "W" => Ok(Tenor::Weeks(n)),
+            "M" => Ok(Tenor::Months(n)),
+            "Y" => Ok(Tenor::Months(12 * n)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl Neg for Tenor {
+    type Output = Tenor;
+
+    fn neg(self) -> Tenor {
+        match self {
+            Tenor::Days(n) => Tenor::Days(-n),
+            Tenor::BusDays(n) => Tenor::BusDays(-n),
+            Tenor::Weeks(n) => Tenor::Weeks(-n),
+            Tenor::Months(n) => Tenor::Months(-n),
+        }
+    }
+}
+
+impl fmt::Display for Tenor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Tenor::Days(n) => write!(f, "{}D", n),
+            Tenor::BusDays(n) => write!(f, "{}B", n),
+            Tenor::Weeks(n) => write!(f, "{}W", n),
+            Tenor::Months(n) if *n != 0 && n % 12 == 0 => write!(f, "{}Y", n / 12),
+            Tenor::Months(n) => write!(f, "{}M", n),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_display() {
+        for (s, tenor) in [
+            ("3m", Tenor::Months(3)),
+            ("2Y", Tenor::Months(24)),
+            ("-1W", Tenor::Weeks(-1)),
+            ("2B", Tenor::BusDays(2)),
+            ("0D", Tenor::Days(0)),
+        ] {
+            assert_eq!(Tenor::try_new(s).unwrap(), tenor);
+        }
+        assert_eq!(Tenor::Months(-24).to_string(), "-2Y");
+        assert_eq!(Tenor::Months(18).to_string(), "18M");
+        assert_eq!(-Tenor::try_new("-18M").unwrap(), Tenor::Months(18));
+        assert!(Tenor::try_new("3X").is_err());
+        assert!(Tenor::try_new("M").is_err());
+    }
+}
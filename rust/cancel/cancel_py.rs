@@ -0,0 +1,29 @@
+//! Wrapper module to export the cancellation of computations to Python using pyo3 bindings.
+
+use crate::cancel::CancellationToken;
+use pyo3::prelude::*;
+
+#[pymethods]
+impl CancellationToken {
+    #[new]
+    fn new_py() -> Self {
+        CancellationToken::new()
+    }
+
+    /// Cancel every computation running under the token, which raise a `CancelledError`.
+    #[pyo3(name = "cancel")]
+    fn cancel_py(&self) {
+        self.cancel()
+    }
+
+    #[pyo3(name = "is_cancelled")]
+    fn is_cancelled_py(&self) -> bool {
+        self.is_cancelled()
+    }
+
+    /// Clear the cancellation so that the token may be reused.
+    #[pyo3(name = "reset")]
+    fn reset_py(&self) {
+        self.reset()
+    }
+}
@@ -0,0 +1,96 @@
+//! Cancel long running computations of the rust core cooperatively.
+//!
+//! A computation [run](CancellationToken::run) under a [CancellationToken] raises a
+//! `CancelledError` at its next [check] after the token is cancelled, for example from
+//! another Python thread while the computation runs with the GIL released. Calibration,
+//! portfolio valuation and Monte Carlo simulation check between iterations, instruments and
+//! paths. Work mapped across threads runs under the token of its caller.
+
+#[cfg(feature = "python")]
+pub(crate) mod cancel_py;
+
+use crate::error::RateslibError;
+#[cfg(feature = "python")]
+use pyo3::pyclass;
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// A flag, shared between threads, by which a running computation is cancelled.
+#[cfg_attr(feature = "python", pyclass(module = "rateslib.rs"))]
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    pub(crate) cancelled: Arc<AtomicBool>,
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<CancellationToken>> = const { RefCell::new(None) };
+}
+
+/// Restores the token of the thread when a run ends, including by a panic.
+struct Restore(Option<CancellationToken>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        CURRENT.with(|c| *c.borrow_mut() = self.0.take());
+    }
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel every computation running under the token.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Clear the cancellation so that the token may be reused.
+    pub fn reset(&self) {
+        self.cancelled.store(false, Ordering::Relaxed);
+    }
+
+    /// Run `f` on the current thread under the token.
+    pub fn run<T>(&self, f: impl FnOnce() -> T) -> T {
+        let previous = CURRENT.with(|c| c.borrow_mut().replace(self.clone()));
+        let _restore = Restore(previous);
+        f()
+    }
+}
+
+/// Return the token of the computation running on the current thread, if any.
+#[cfg(not(any(feature = "wasm", target_arch = "wasm32")))]
+pub(crate) fn current() -> Option<CancellationToken> {
+    CURRENT.with(|c| c.borrow().clone())
+}
+
+/// Raise if the token of the computation running on the current thread is cancelled.
+pub fn check() -> Result<(), RateslibError> {
+    let cancelled = CURRENT.with(|c| c.borrow().as_ref().is_some_and(|t| t.is_cancelled()));
+    match cancelled {
+        true => Err(RateslibError::Cancelled),
+        false => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_and_check() {
+        let token = CancellationToken::new();
+        assert!(token.run(check).is_ok());
+        token.cancel();
+        assert!(token.run(check).is_err());
+        // outside of a run there is nothing to cancel
+        assert!(check().is_ok());
+        token.reset();
+        assert!(token.run(check).is_ok());
+    }
+}
@@ -0,0 +1,233 @@
+use crate::context::Context;
+use crate::curves::PricingCurve;
+use crate::error::RateslibError;
+use crate::fx::rates::{Ccy, FXRates};
+use crate::fx::volatility::FxVolSurface;
+use chrono::NaiveDateTime;
+use indexmap::IndexMap;
+use std::sync::{Arc, RwLock};
+
+/// A curve which may be shared between threads.
+pub type SharedCurve = Arc<dyn PricingCurve + Send + Sync>;
+
+/// A coherent state of a [Market] of curves, FX volatility surfaces, FX rates and fixings.
+///
+/// Each item is held behind an [Arc], so that a state is cheap to clone and an update copies
+/// only the items it replaces.
+#[derive(Clone, Default)]
+pub struct MarketState {
+    pub(crate) version: u64,
+    pub(crate) curves: IndexMap<String, SharedCurve>,
+    pub(crate) ccy_curves: IndexMap<Ccy, String>,
+    pub(crate) vols: IndexMap<String, Arc<FxVolSurface>>,
+    pub(crate) fx: Option<Arc<FXRates>>,
+    pub(crate) fixings: IndexMap<String, Arc<IndexMap<NaiveDateTime, f64>>>,
+}
+
+impl MarketState {
+    /// Return the number of updates applied to the market to reach this state.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Return the curve mapped to `name`.
+    pub fn curve(&self, name: &str) -> Result<&dyn PricingCurve, RateslibError> {
+        match self.curves.get(name) {
+            Some(c) => Ok(c.as_ref()),
+            None => Err(RateslibError::value(format!(
+                "Curve '{}' is not in the `Market`.",
+                name
+            ))),
+        }
+    }
+
+    /// Return the FX volatility surface mapped to `name`.
+    pub fn vol(&self, name: &str) -> Result<&FxVolSurface, RateslibError> {
+        self.vols.get(name).map(|v| v.as_ref()).ok_or_else(|| {
+            RateslibError::value(format!(
+                "Volatility surface '{}' is not in the `Market`.",
+                name
+            ))
+        })
+    }
+
+    pub fn fx(&self) -> Option<&FXRates> {
+        self.fx.as_deref()
+    }
+
+    /// Return the fixing of an `index` for a period accruing from `date`, if known.
+    pub fn fixing(&self, index: &str, date: &NaiveDateTime) -> Option<f64> {
+        self.fixings.get(&index.to_lowercase())?.get(date).copied()
+    }
+
+    /// Map a `curve` to `name`, replacing any curve of the same name.
+    pub fn set_curve(&mut self, name: &str, curve: SharedCurve) {
+        self.curves.insert(name.to_string(), curve);
+    }
+
+    /// Map the discount curve of a currency `ccy` to the curve `name`.
+    pub fn set_ccy_curve(&mut self, ccy: Ccy, name: &str) {
+        self.ccy_curves.insert(ccy, name.to_string());
+    }
+
+    /// Map an FX volatility `surface` to `name`, replacing any surface of the same name.
+    pub fn set_vol(&mut self, name: &str, surface: FxVolSurface) {
+        self.vols.insert(name.to_string(), Arc::new(surface));
+    }
+
+    pub fn set_fx(&mut self, fx: FXRates) {
+        self.fx = Some(Arc::new(fx));
+    }
+
+    /// Record the `fixings` of an `index`, copying only the fixings of that index.
+    pub fn add_fixings(&mut self, index: &str, fixings: IndexMap<NaiveDateTime, f64>) {
+        let entry = self.fixings.entry(index.to_lowercase()).or_default();
+        Arc::make_mut(entry).extend(fixings);
+    }
+
+    /// Return a [Context] of every curve, FX rate and fixing of the state, for pricing at
+    /// `eval_date` in a base currency `base_ccy`.
+    pub fn context(
+        &self,
+        eval_date: NaiveDateTime,
+        base_ccy: Ccy,
+    ) -> Result<Context<'_>, RateslibError> {
+        let mut context = Context::new(eval_date, base_ccy);
+        for (name, curve) in self.curves.iter() {
+            context = context.with_curve(name, curve.as_ref())?;
+        }
+        for (ccy, name) in self.ccy_curves.iter() {
+            context = context.with_ccy_curve(*ccy, name)?;
+        }
+        if let Some(fx) = self.fx() {
+            context = context.with_fx(fx);
+        }
+        for (index, fixings) in self.fixings.iter() {
+            context = context.with_fixings(index, fixings.as_ref().clone());
+        }
+        Ok(context)
+    }
+}
+
+/// A market of curves, FX volatility surfaces, FX rates and fixings shared between threads.
+///
+/// Pricing threads take a [snapshot](Market::snapshot) of a coherent [MarketState], which is
+/// unaffected by later updates, while a feed thread applies updates copy-on-write by
+/// [update](Market::update).
+#[derive(Default)]
+pub struct Market {
+    state: RwLock<Arc<MarketState>>,
+}
+
+impl Market {
+    pub fn new(state: MarketState) -> Self {
+        Self {
+            state: RwLock::new(Arc::new(state)),
+        }
+    }
+
+    /// Return the current state of the market.
+    pub fn snapshot(&self) -> Arc<MarketState> {
+        self.state.read().unwrap().clone()
+    }
+
+    /// Apply an `update` to a copy of the current state and publish it as the new state,
+    /// returning its version.
+    ///
+    /// Updates are applied one at a time. If the update raises, the state is unchanged.
+    pub fn update<F>(&self, update: F) -> Result<u64, RateslibError>
+    where
+        F: FnOnce(&mut MarketState) -> Result<(), RateslibError>,
+    {
+        let mut guard = self.state.write().unwrap();
+        let mut state = guard.as_ref().clone();
+        update(&mut state)?;
+        state.version += 1;
+        *guard = Arc::new(state);
+        Ok(guard.version)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::ndt;
+    use crate::instruments::instrument::tests::irs_fixture;
+    use crate::instruments::Instrument;
+    use crate::periods::period::tests::curve_fixture;
+    use crate::periods::Curves;
+    use std::thread;
+
+    #[test]
+    fn test_snapshot_is_unaffected_by_updates() {
+        let market = Market::default();
+        market
+            .update(|m| {
+                m.set_curve("sofr", Arc::new(curve_fixture("a")));
+                m.add_fixings("SOFR", IndexMap::from_iter([(ndt(2023, 12, 29), 5.3)]));
+                Ok(())
+            })
+            .unwrap();
+        let before = market.snapshot();
+        let version = market
+            .update(|m| {
+                m.set_curve("sofr", Arc::new(curve_fixture("b")));
+                m.add_fixings("sofr", IndexMap::from_iter([(ndt(2023, 12, 28), 5.31)]));
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(version, 2);
+        let after = market.snapshot();
+        assert_eq!(before.curve("sofr").unwrap().id(), "a");
+        assert_eq!(after.curve("sofr").unwrap().id(), "b");
+        assert_eq!(before.fixing("sofr", &ndt(2023, 12, 28)), None);
+        assert_eq!(after.fixing("sofr", &ndt(2023, 12, 28)), Some(5.31));
+        assert!(after.curve("estr").is_err() && after.vol("eurusd").is_err());
+        // a failed update publishes nothing
+        assert!(market
+            .update(|m| {
+                m.curves.clear();
+                Err(RateslibError::value("feed error"))
+            })
+            .is_err());
+        assert_eq!(market.snapshot().version(), 2);
+        assert!(market.snapshot().curve("sofr").is_ok());
+    }
+
+    #[test]
+    fn test_concurrent_pricing_and_updates() {
+        let market = Market::default();
+        market
+            .update(|m| {
+                m.set_curve("sofr", Arc::new(curve_fixture("c")));
+                Ok(())
+            })
+            .unwrap();
+        let irs = irs_fixture(2.0);
+        thread::scope(|s| {
+            s.spawn(|| {
+                for _ in 0..20 {
+                    market
+                        .update(|m| {
+                            m.set_curve("sofr", Arc::new(curve_fixture("c")));
+                            Ok(())
+                        })
+                        .unwrap();
+                }
+            });
+            for _ in 0..4 {
+                s.spawn(|| {
+                    for _ in 0..20 {
+                        let state = market.snapshot();
+                        let context = state
+                            .context(ndt(2024, 1, 1), Ccy::try_new("usd").unwrap())
+                            .unwrap();
+                        let curve = context.curve("sofr").unwrap();
+                        irs.npv(&Curves::new(Some(curve), Some(curve))).unwrap();
+                    }
+                });
+            }
+        });
+        assert_eq!(market.snapshot().version(), 21);
+    }
+}
@@ -0,0 +1,446 @@
+//! Thread the market state of a pricing call explicitly rather than through global defaults.
+//!
+//! A [Context] holds an evaluation date, a base currency, named curves, FX rates and sources
+//! of fixings. Contexts are independent values, so that instruments may be priced
+//! concurrently under multiple as-of dates within one process. A context may be rebased to a
+//! later evaluation date, rolling its curves and FX rates forward without rebuilding them.
+//!
+//! A [Market] shares owned market data between threads, publishing updates copy-on-write as
+//! [MarketState]s from which contexts are created.
+
+use crate::curves::{PricingCurve, RollMethod, RolledCurve};
+use crate::dual::Number;
+use crate::error::RateslibError;
+use crate::fx::rates::{Ccy, FXRates, Money};
+use crate::instruments::Instrument;
+use crate::periods::{Curves, PeriodType};
+use chrono::NaiveDateTime;
+use indexmap::IndexMap;
+use std::borrow::Cow;
+
+mod market;
+pub use crate::context::market::{Market, MarketState, SharedCurve};
+
+/// The market state under which instruments are priced.
+///
+/// Every curve of a context must have its initial date at the evaluation date, so that values
+/// measured under a context are consistent as of that date.
+#[derive(Clone)]
+pub struct Context<'a> {
+    pub(crate) eval_date: NaiveDateTime,
+    pub(crate) base_ccy: Ccy,
+    pub(crate) curves: IndexMap<String, ContextCurve<'a>>,
+    pub(crate) ccy_curves: IndexMap<Ccy, String>,
+    pub(crate) fx: Option<Cow<'a, FXRates>>,
+    pub(crate) fixings: IndexMap<String, IndexMap<NaiveDateTime, f64>>,
+}
+
+/// A curve of a [Context], as given or as rolled forward by a rebase of the context.
+#[derive(Clone, Copy)]
+pub(crate) enum ContextCurve<'a> {
+    Given(&'a dyn PricingCurve),
+    Rolled(RolledCurve<'a>),
+}
+
+impl<'a> ContextCurve<'a> {
+    fn curve(&self) -> &dyn PricingCurve {
+        match self {
+            ContextCurve::Given(c) => *c,
+            ContextCurve::Rolled(c) => c,
+        }
+    }
+
+    fn roll(&self, horizon: NaiveDateTime) -> Result<Self, RateslibError> {
+        Ok(ContextCurve::Rolled(match self {
+            ContextCurve::Given(c) => RolledCurve::try_new(*c, horizon, RollMethod::Forward)?,
+            ContextCurve::Rolled(c) => c.roll(horizon)?,
+        }))
+    }
+}
+
+impl<'a> Context<'a> {
+    pub fn new(eval_date: NaiveDateTime, base_ccy: Ccy) -> Self {
+        Self {
+            eval_date,
+            base_ccy,
+            curves: IndexMap::new(),
+            ccy_curves: IndexMap::new(),
+            fx: None,
+            fixings: IndexMap::new(),
+        }
+    }
+
+    /// Return the context with a `curve` mapped to `name`, or raise if its initial date is not
+    /// the evaluation date.
+    pub fn with_curve(
+        mut self,
+        name: &str,
+        curve: &'a dyn PricingCurve,
+    ) -> Result<Self, RateslibError> {
+        if curve.initial_date() != self.eval_date {
+            return Err(RateslibError::value(format!(
+                "The initial date of curve '{}' must be the evaluation date of the `Context`.",
+                name
+            )));
+        }
+        self.curves
+            .insert(name.to_string(), ContextCurve::Given(curve));
+        Ok(self)
+    }
+
+    /// Return the context with the curve mapped to `name` as the discount curve of `ccy`, with
+    /// which FX rates are rolled forward by a [rebase](Context::rebase).
+    pub fn with_ccy_curve(mut self, ccy: Ccy, name: &str) -> Result<Self, RateslibError> {
+        self.curve(name)?;
+        self.ccy_curves.insert(ccy, name.to_string());
+        Ok(self)
+    }
+
+    /// Return the context with the `fx` rates used to convert values into the base currency.
+    pub fn with_fx(mut self, fx: &'a FXRates) -> Self {
+        self.fx = Some(Cow::Borrowed(fx));
+        self
+    }
+
+    /// Return the context with the historical `fixings`, in percent, of an `index`, such as
+    /// `"sofr"`, by the accrual start date of the period fixing.
+    pub fn with_fixings(mut self, index: &str, fixings: IndexMap<NaiveDateTime, f64>) -> Self {
+        self.fixings
+            .entry(index.to_lowercase())
+            .or_default()
+            .extend(fixings);
+        self
+    }
+
+    pub fn eval_date(&self) -> NaiveDateTime {
+        self.eval_date
+    }
+
+    pub fn base_ccy(&self) -> Ccy {
+        self.base_ccy
+    }
+
+    /// Return the FX rates of the context, if any.
+    pub fn fx(&self) -> Option<&FXRates> {
+        self.fx.as_deref()
+    }
+
+    /// Return the curve mapped to `name`.
+    pub fn curve(&self, name: &str) -> Result<&dyn PricingCurve, RateslibError> {
+        self.curves.get(name).map(|c| c.curve()).ok_or_else(|| {
+            RateslibError::value(format!("Curve '{}' is not mapped in the `Context`.", name))
+        })
+    }
+
+    /// Return the [Curves] of the curves mapped to the `forecasting` and `discounting` names.
+    pub fn curves(
+        &self,
+        forecasting: Option<&str>,
+        discounting: Option<&str>,
+    ) -> Result<Curves<'_>, RateslibError> {
+        Ok(Curves::new(
+            forecasting.map(|n| self.curve(n)).transpose()?,
+            discounting.map(|n| self.curve(n)).transpose()?,
+        ))
+    }
+
+    /// Return the fixing of an `index` for a period accruing from `date`, if known.
+    pub fn fixing(&self, index: &str, date: &NaiveDateTime) -> Option<f64> {
+        self.fixings.get(&index.to_lowercase())?.get(date).copied()
+    }
+
+    /// Set the fixing of each floating period of `periods` which starts accruing before the
+    /// evaluation date from the fixings of an `index`, or raise if a fixing is unknown.
+    pub fn set_fixings(
+        &self,
+        periods: &mut [PeriodType],
+        index: &str,
+    ) -> Result<(), RateslibError> {
+        for period in periods.iter_mut() {
+            if let PeriodType::Float(p) = period {
+                if p.fixing.is_none() && p.base.start < self.eval_date {
+                    p.fixing = Some(self.fixing(index, &p.base.start).ok_or_else(|| {
+                        RateslibError::value(format!(
+                            "The '{}' fixing for {} is not known to the `Context`.",
+                            index, p.base.start
+                        ))
+                    })?);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Return an `amount` converted into the base currency at the spot FX rates.
+    pub fn to_base(&self, amount: Money) -> Result<Money, RateslibError> {
+        if amount.ccy == self.base_ccy {
+            return Ok(amount);
+        }
+        let fx = self.fx().ok_or_else(|| {
+            RateslibError::value("`FXRates` are required to convert into the base currency.")
+        })?;
+        amount.convert(&self.base_ccy, fx)
+    }
+
+    /// Return the NPV, in the base currency, of an `instrument` whose cashflows are in the
+    /// currency `ccy`, valued on the curves mapped to the `forecasting` and `discounting`
+    /// names.
+    pub fn npv(
+        &self,
+        instrument: &dyn Instrument,
+        ccy: Ccy,
+        forecasting: Option<&str>,
+        discounting: Option<&str>,
+    ) -> Result<Money, RateslibError> {
+        let npv: Number = instrument.npv(&self.curves(forecasting, discounting)?)?;
+        self.to_base(Money::new(npv, ccy))
+    }
+
+    /// Return the context rebased to a later `eval_date`.
+    ///
+    /// Curves are rolled forward to `eval_date` at their forward rates, as views sharing the
+    /// node data of the given curves. FX rates are rolled forward, and their settlement moved
+    /// by the same period, with the discount curves mapped by
+    /// [with_ccy_curve](Context::with_ccy_curve). For each `(index, curve, periods)` of
+    /// `floating`, the rate forecast by the named curve for each unfixed floating period of
+    /// `periods` which starts accruing before `eval_date` is recorded as a fixing of the index,
+    /// unless already known.
+    pub fn rebase(
+        &self,
+        eval_date: NaiveDateTime,
+        floating: &[(&str, &str, &[PeriodType])],
+    ) -> Result<Self, RateslibError> {
+        if eval_date < self.eval_date {
+            return Err(RateslibError::value(
+                "A `Context` cannot be rebased to an earlier evaluation date.",
+            ));
+        }
+        let mut context = self.clone();
+        for (index, name, periods) in floating.iter() {
+            let curve = self.curve(name)?;
+            for period in periods.iter() {
+                if let PeriodType::Float(p) = period {
+                    let start = p.base.start;
+                    let elapsed = self.eval_date <= start && start < eval_date;
+                    if elapsed && p.fixing.is_none() && self.fixing(index, &start).is_none() {
+                        let rate = f64::from(&curve.rate(&start, &p.base.end)?);
+                        context
+                            .fixings
+                            .entry(index.to_lowercase())
+                            .or_default()
+                            .insert(start, rate);
+                    }
+                }
+            }
+        }
+        if let Some(fx) = self.fx() {
+            let curves = fx
+                .currencies
+                .iter()
+                .map(|ccy| {
+                    let name = self.ccy_curves.get(ccy).ok_or_else(|| {
+                        RateslibError::value(format!(
+                            "A discount curve for '{}' is required to rebase the `Context`.",
+                            ccy.name
+                        ))
+                    })?;
+                    Ok((*ccy, self.curve(name)?))
+                })
+                .collect::<Result<IndexMap<Ccy, &dyn PricingCurve>, RateslibError>>()?;
+            let settlement =
+                fx.fx_rates[0].settlement.unwrap_or(self.eval_date) + (eval_date - self.eval_date);
+            context.fx = Some(Cow::Owned(fx.rebase(&settlement, &curves)?));
+        }
+        for curve in context.curves.values_mut() {
+            *curve = curve.roll(eval_date)?;
+        }
+        context.eval_date = eval_date;
+        Ok(context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::{ndt, Convention, Modifier, NamedCal};
+    use crate::curves::{CurveDF, LogLinearInterpolator, Nodes};
+    use crate::fx::rates::FXRate;
+    use crate::instruments::instrument::tests::irs_fixture;
+    use crate::periods::period::tests::curve_fixture;
+
+    /// A curve with a flat zero rate of 2% from an `initial` date.
+    fn curve_from(initial: NaiveDateTime) -> CurveDF<LogLinearInterpolator, NamedCal> {
+        let end = ndt(2040, 1, 1);
+        let t = (end - initial).num_days() as f64 / 365.0;
+        CurveDF::try_new(
+            Nodes::F64(IndexMap::from_iter([
+                (initial, 1.0),
+                (end, (-0.02_f64 * t).exp()),
+            ])),
+            LogLinearInterpolator::new(),
+            "c",
+            Convention::Act365F,
+            Modifier::ModF,
+            None,
+            NamedCal::try_new("all").unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_context_curves() {
+        let curve = curve_fixture("c");
+        let usd = Ccy::try_new("usd").unwrap();
+        let ctx = Context::new(ndt(2024, 1, 1), usd)
+            .with_curve("sofr", &curve)
+            .unwrap();
+        let irs = irs_fixture(2.0);
+        let npv = ctx.npv(&irs, usd, Some("sofr"), Some("sofr")).unwrap();
+        let curves = Curves::new(Some(&curve), Some(&curve));
+        assert_eq!(npv.amount, irs.npv(&curves).unwrap());
+        assert!(ctx.curves(Some("estr"), None).is_err());
+        // a curve from another as-of date is rejected
+        assert!(Context::new(ndt(2024, 1, 2), usd)
+            .with_curve("sofr", &curve)
+            .is_err());
+    }
+
+    #[test]
+    fn test_context_base_currency() {
+        let curve = curve_fixture("c");
+        let (usd, eur) = (Ccy::try_new("usd").unwrap(), Ccy::try_new("eur").unwrap());
+        let fx = FXRates::try_new(
+            vec![FXRate::try_new("eur", "usd", Number::F64(1.1), None).unwrap()],
+            None,
+        )
+        .unwrap();
+        let irs = irs_fixture(2.0);
+        let ctx = Context::new(ndt(2024, 1, 1), usd)
+            .with_curve("estr", &curve)
+            .unwrap();
+        assert!(ctx.npv(&irs, eur, Some("estr"), Some("estr")).is_err());
+        let ctx = ctx.with_fx(&fx);
+        let npv = ctx.npv(&irs, eur, Some("estr"), Some("estr")).unwrap();
+        let local = f64::from(
+            &irs.npv(&ctx.curves(Some("estr"), Some("estr")).unwrap())
+                .unwrap(),
+        );
+        assert_eq!(npv.ccy, usd);
+        assert!((f64::from(&npv.amount) - 1.1 * local).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_context_fixings() {
+        let usd = Ccy::try_new("usd").unwrap();
+        let mut irs = irs_fixture(2.0);
+        let ctx = Context::new(ndt(2024, 6, 1), usd);
+        assert!(ctx.set_fixings(&mut irs.leg2.periods, "sofr").is_err());
+        let ctx = ctx.with_fixings("SOFR", IndexMap::from_iter([(ndt(2024, 1, 1), 5.3)]));
+        assert_eq!(ctx.fixing("sofr", &ndt(2024, 1, 1)), Some(5.3));
+        ctx.set_fixings(&mut irs.leg2.periods, "sofr").unwrap();
+        let PeriodType::Float(p) = &irs.leg2.periods[0] else {
+            panic!("The first period of the floating leg floats")
+        };
+        assert_eq!(p.fixing, Some(5.3));
+    }
+
+    #[test]
+    fn test_rebase_curves_and_fixings() {
+        let curve = curve_fixture("c");
+        let usd = Ccy::try_new("usd").unwrap();
+        let irs = irs_fixture(2.0);
+        let ctx = Context::new(ndt(2024, 1, 1), usd)
+            .with_curve("sofr", &curve)
+            .unwrap();
+        let h = ndt(2024, 6, 1);
+        let rebased = ctx
+            .rebase(h, &[("sofr", "sofr", &irs.leg2.periods)])
+            .unwrap();
+        assert_eq!(rebased.eval_date(), h);
+        // the rolled curve preserves the forward discount factors of the given curve
+        let d = ndt(2030, 1, 1);
+        let df = f64::from(&rebased.curve("sofr").unwrap().df(&d));
+        assert!((df - f64::from(&(curve.df(&d) / curve.df(&h)))).abs() < 1e-14);
+        // the first floating period has elapsed and its forecast rate is now a fixing
+        let PeriodType::Float(p) = &irs.leg2.periods[0] else {
+            panic!("The first period of the floating leg floats")
+        };
+        let forecast = f64::from(&curve.rate(&p.base.start, &p.base.end).unwrap());
+        assert_eq!(rebased.fixing("sofr", &p.base.start), Some(forecast));
+        assert_eq!(rebased.fixings["sofr"].len(), 1);
+        let mut periods = irs.leg2.periods.clone();
+        rebased.set_fixings(&mut periods, "sofr").unwrap();
+        // rebasing in two steps is rebasing once
+        let twice = ctx
+            .rebase(ndt(2024, 3, 1), &[])
+            .unwrap()
+            .rebase(h, &[])
+            .unwrap();
+        assert_eq!(
+            twice.curve("sofr").unwrap().df(&d),
+            rebased.curve("sofr").unwrap().df(&d)
+        );
+        assert!(rebased.rebase(ndt(2024, 1, 1), &[]).is_err());
+        // a rebased context may not take curves of the original evaluation date
+        assert!(rebased.with_curve("estr", &curve).is_err());
+    }
+
+    #[test]
+    fn test_rebase_fx() {
+        let curve = curve_fixture("c");
+        let (usd, eur) = (Ccy::try_new("usd").unwrap(), Ccy::try_new("eur").unwrap());
+        let fx = FXRates::try_new(
+            vec![FXRate::try_new("eur", "usd", Number::F64(1.1), Some(ndt(2024, 1, 3))).unwrap()],
+            None,
+        )
+        .unwrap();
+        let ctx = Context::new(ndt(2024, 1, 1), usd)
+            .with_curve("c", &curve)
+            .unwrap()
+            .with_fx(&fx);
+        assert!(ctx.rebase(ndt(2024, 2, 1), &[]).is_err());
+        assert!(ctx.clone().with_ccy_curve(eur, "estr").is_err());
+        let ctx = ctx
+            .with_ccy_curve(eur, "c")
+            .unwrap()
+            .with_ccy_curve(usd, "c")
+            .unwrap();
+        let rebased = ctx.rebase(ndt(2024, 2, 1), &[]).unwrap();
+        let rebased_fx = rebased.fx().unwrap();
+        assert_eq!(rebased_fx.fx_rates[0].settlement, Some(ndt(2024, 2, 3)));
+        // equal interest rates leave the FX rate unchanged, and the original context is unchanged
+        let rate = f64::from(&rebased_fx.rate(&eur, &usd).unwrap());
+        assert!((rate - 1.1).abs() < 1e-12);
+        assert_eq!(
+            ctx.fx().unwrap().fx_rates[0].settlement,
+            Some(ndt(2024, 1, 3))
+        );
+    }
+
+    #[test]
+    fn test_concurrent_contexts() {
+        let usd = Ccy::try_new("usd").unwrap();
+        let irs = irs_fixture(2.0);
+        let dates = [ndt(2024, 1, 1), ndt(2024, 6, 1), ndt(2024, 11, 1)];
+        let npvs: Vec<f64> = std::thread::scope(|s| {
+            let handles: Vec<_> = dates
+                .iter()
+                .map(|d| {
+                    let irs = &irs;
+                    s.spawn(move || {
+                        let curve = curve_from(*d);
+                        let ctx = Context::new(*d, usd).with_curve("c", &curve).unwrap();
+                        f64::from(&ctx.npv(irs, usd, Some("c"), Some("c")).unwrap().amount)
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        // each as-of date values the swap independently of the others
+        for (d, npv) in dates.iter().zip(npvs) {
+            let curve = curve_from(*d);
+            let expected = irs.npv(&Curves::new(Some(&curve), Some(&curve))).unwrap();
+            assert_eq!(npv, f64::from(&expected));
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use crate::dual::Number;
+use chrono::NaiveDateTime;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+/// The discount factors evaluated and reused by a curve cache over its scope.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// The number of discount factors which were interpolated from the nodes of a curve.
+    pub misses: usize,
+    /// The number of discount factors which were reused from the cache.
+    pub hits: usize,
+}
+
+#[derive(Default)]
+struct CurveCache {
+    values: HashMap<usize, HashMap<NaiveDateTime, Number>>,
+    stats: CacheStats,
+}
+
+thread_local! {
+    static CACHE: RefCell<Option<CurveCache>> = const { RefCell::new(None) };
+}
+
+/// Evaluate `f` with a cache of the discount factors of curves on the current thread.
+///
+/// Each discount factor of a [CurveDF](crate::curves::CurveDF) evaluated within `f` is
+/// interpolated once per curve and date, and reused by every period and instrument measuring
+/// the same date. A nested call shares the cache of the outer call, whose statistics it returns
+/// in their state on exit.
+///
+/// Curves are identified by the address of their nodes. The values of a curve are discarded
+/// when it is dropped or its nodes are replaced by its methods, but a curve whose nodes are
+/// otherwise changed within `f` must be followed by [invalidate_curve_cache].
+pub fn with_curve_cache<R>(f: impl FnOnce() -> R) -> (R, CacheStats) {
+    let outer = CACHE.with(|c| {
+        let mut c = c.borrow_mut();
+        let outer = c.is_some();
+        if !outer {
+            *c = Some(CurveCache::default());
+        }
+        outer
+    });
+    let result = f();
+    let stats = CACHE.with(|c| {
+        let mut c = c.borrow_mut();
+        let stats = c.as_ref().map(|cache| cache.stats).unwrap_or_default();
+        if !outer {
+            *c = None;
+        }
+        stats
+    });
+    (result, stats)
+}
+
+/// Return whether a curve cache is active on the current thread.
+#[cfg(not(any(feature = "wasm", target_arch = "wasm32")))]
+pub(crate) fn is_active() -> bool {
+    CACHE.with(|c| c.borrow().is_some())
+}
+
+/// Add the `stats` of a curve cache of another thread to the active cache, if any.
+#[cfg(not(any(feature = "wasm", target_arch = "wasm32")))]
+pub(crate) fn record(stats: CacheStats) {
+    CACHE.with(|c| {
+        if let Some(cache) = c.borrow_mut().as_mut() {
+            cache.stats.misses += stats.misses;
+            cache.stats.hits += stats.hits;
+        }
+    })
+}
+
+/// Discard every discount factor of the active curve cache of the current thread, if any.
+pub fn invalidate_curve_cache() {
+    CACHE.with(|c| {
+        if let Some(cache) = c.borrow_mut().as_mut() {
+            cache.values.clear();
+        }
+    })
+}
+
+/// Discard the discount factors of the curve at `address` from the active curve cache.
+pub(crate) fn evict(address: usize) {
+    CACHE.with(|c| {
+        if let Ok(mut c) = c.try_borrow_mut() {
+            if let Some(cache) = c.as_mut() {
+                cache.values.remove(&address);
+            }
+        }
+    })
+}
+
+/// Return the discount factor of the curve at `address` at `date` from the active cache, or
+/// evaluate and cache it with `f`.
+pub(crate) fn cached_df(
+    address: usize,
+    date: &NaiveDateTime,
+    f: impl FnOnce() -> Number,
+) -> Number {
+    let hit = CACHE.with(|c| {
+        let mut c = c.borrow_mut();
+        let cache = c.as_mut()?;
+        let value = cache
+            .values
+            .get(&address)
+            .and_then(|v| v.get(date))
+            .cloned();
+        if value.is_some() {
+            cache.stats.hits += 1;
+        }
+        Some(value)
+    });
+    match hit {
+        None => f(),
+        Some(Some(value)) => value,
+        Some(None) => {
+            let value = f();
+            CACHE.with(|c| {
+                if let Some(cache) = c.borrow_mut().as_mut() {
+                    cache.stats.misses += 1;
+                    cache
+                        .values
+                        .entry(address)
+                        .or_default()
+                        .insert(*date, value.clone());
+                }
+            });
+            value
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::ndt;
+    use crate::curves::PricingCurve;
+    use crate::dual::ADOrder;
+    use crate::periods::period::tests::curve_fixture;
+
+    #[test]
+    fn test_curve_cache() {
+        let mut curve = curve_fixture("c");
+        let date = ndt(2024, 6, 1);
+        let expected = curve.df(&date);
+        let (dfs, stats) = with_curve_cache(|| {
+            let (inner, inner_stats) = with_curve_cache(|| curve.df(&date));
+            assert_eq!(inner_stats.misses, 1);
+            vec![inner, curve.df(&date), curve.df(&ndt(2024, 7, 1))]
+        });
+        assert_eq!(dfs[0], expected);
+        assert_eq!(dfs[1], expected);
+        assert_eq!(stats, CacheStats { misses: 2, hits: 1 });
+        let (_, stats) = with_curve_cache(|| {
+            curve.df(&date);
+            // changing the nodes of the curve discards its cached values
+            curve.set_ad_order(ADOrder::Two).unwrap();
+            assert!(matches!(curve.df(&date), crate::dual::Number::Dual2(_)));
+        });
+        assert_eq!(stats.misses, 2);
+    }
+
+    #[test]
+    #[cfg(not(any(feature = "wasm", target_arch = "wasm32")))]
+    fn test_curve_cache_across_threads() {
+        let curve = curve_fixture("c");
+        let date = ndt(2024, 6, 1);
+        let items: Vec<usize> = (0..64).collect();
+        let (dfs, stats) =
+            with_curve_cache(|| crate::parallel::par_map(&items, |_| curve.df(&date)).unwrap());
+        assert!(dfs.iter().all(|df| *df == curve.df(&date)));
+        // each thread caches in its own cache, whose statistics are added to the caller
+        assert_eq!(stats.misses + stats.hits, 64);
+    }
+}
@@ -1,12 +1,16 @@
 use crate::calendars::DateRoll;
-use crate::calendars::{Convention, Modifier};
+use crate::calendars::{Convention, Modifier, RollDay};
+use crate::curves::cache::cached_df;
 use crate::curves::interpolation::utils::index_left;
 use crate::curves::nodes::{Nodes, NodesTimestamp};
-use crate::dual::{get_variable_tags, ADOrder, Dual, Dual2, Number};
-use chrono::NaiveDateTime;
+use crate::curves::{evict, CurveError, ForwardProfile, SmoothnessMetrics};
+use crate::dual::{get_variable_tags, ADOrder, Dual, Dual2, Gradient1, Number};
+use crate::error::RateslibError;
+use crate::profiling::{record, Counter};
+use crate::trace::{event, Level};
+use chrono::{DateTime, NaiveDateTime};
 use indexmap::IndexMap;
-use pyo3::exceptions::PyValueError;
-use pyo3::PyErr;
+use ndarray::Array2;
 use serde::{Deserialize, Serialize};
 use std::cmp::PartialEq;
 
@@ -27,6 +31,15 @@ pub trait CurveInterpolation {
     /// Get a value from the curve's `Nodes` expressed in its input form, i.e. discount factor or value.
     fn interpolated_value(&self, nodes: &NodesTimestamp, date: &NaiveDateTime) -> Number;
 
+    /// Get a value from the curve's `Nodes`, or raise if the interpolation cannot be evaluated.
+    fn try_interpolated_value(
+        &self,
+        nodes: &NodesTimestamp,
+        date: &NaiveDateTime,
+    ) -> Result<Number, CurveError> {
+        Ok(self.interpolated_value(nodes, date))
+    }
+
     /// Get the left side node key index of the given datetime
     fn node_index(&self, nodes: &NodesTimestamp, date_timestamp: i64) -> usize {
         // let timestamp = date.and_utc().timestamp();
@@ -43,8 +56,12 @@ impl<T: CurveInterpolation, U: DateRoll> CurveDF<T, U> {
         modifier: Modifier,
         index_base: Option<f64>,
         calendar: U,
-    ) -> Result<Self, PyErr> {
+    ) -> Result<Self, RateslibError> {
         let mut nodes = NodesTimestamp::from(nodes);
+        let given = nodes.keys().len();
+        if given < 2 {
+            return Err(CurveError::InsufficientNodes { required: 2, given }.into());
+        }
         nodes.sort_keys();
         Ok(Self {
             nodes,
@@ -67,14 +84,47 @@ impl<T: CurveInterpolation, U: DateRoll> CurveDF<T, U> {
     }
 
     pub fn interpolated_value(&self, date: &NaiveDateTime) -> Number {
+        record(Counter::CurveEvaluations);
+        event(Level::Trace, "curves", || {
+            format!("interpolated_value{{id: {}, date: {}}}", self.id, date)
+        });
         self.interpolator.interpolated_value(&self.nodes, date)
     }
 
+    /// Return the interpolated value at a `date`, or raise if the interpolator cannot evaluate it.
+    pub fn try_interpolated_value(&self, date: &NaiveDateTime) -> Result<Number, RateslibError> {
+        record(Counter::CurveEvaluations);
+        Ok(self
+            .interpolator
+            .try_interpolated_value(&self.nodes, date)?)
+    }
+
+    /// Return the address of the nodes of the curve, by which its values are cached, and which
+    /// is unchanged when the curve is moved.
+    pub(crate) fn address(&self) -> usize {
+        match &self.nodes {
+            NodesTimestamp::F64(m) => std::ptr::from_ref(m.as_slice()) as *const () as usize,
+            NodesTimestamp::Dual(m) => std::ptr::from_ref(m.as_slice()) as *const () as usize,
+            NodesTimestamp::Dual2(m) => std::ptr::from_ref(m.as_slice()) as *const () as usize,
+        }
+    }
+
     pub fn node_index(&self, date_timestamp: i64) -> usize {
         self.interpolator.node_index(&self.nodes, date_timestamp)
     }
 
-    pub fn set_ad_order(&mut self, ad: ADOrder) -> Result<(), PyErr> {
+    /// Return the [CurveError] of a `date` out of the range of the nodes.
+    fn out_of_range(&self, date: &NaiveDateTime) -> CurveError {
+        let keys = self.nodes.keys();
+        let to_date = |k: i64| DateTime::from_timestamp(k, 0).unwrap().naive_utc();
+        CurveError::OutOfRange {
+            date: *date,
+            bounds: (to_date(keys[0]), to_date(keys[keys.len() - 1])),
+        }
+    }
+
+    pub fn set_ad_order(&mut self, ad: ADOrder) -> Result<(), RateslibError> {
+        evict(self.address());
         let vars: Vec<String> = get_variable_tags(&self.id, self.nodes.keys().len());
         match (ad, &self.nodes) {
             (ADOrder::Zero, NodesTimestamp::F64(_))
@@ -131,20 +181,191 @@ impl<T: CurveInterpolation, U: DateRoll> CurveDF<T, U> {
         }
     }
 
-    pub fn index_value(&self, date: &NaiveDateTime) -> Result<Number, PyErr> {
+    pub fn index_value(&self, date: &NaiveDateTime) -> Result<Number, RateslibError> {
         match self.index_base {
-            None => Err(PyValueError::new_err("Can only calculate `index_value` for a Curve which has been initialised with `index_base`.")),
+            None => Err(RateslibError::curve("Can only calculate `index_value` for a Curve which has been initialised with `index_base`.")),
             Some(ib) => {
                 if date.and_utc().timestamp() < self.nodes.first_key() {
                     Ok(Number::F64(0.0))
                 } else {
-                    Ok(Number::F64(ib) / self.interpolated_value(date))
+                    Ok(Number::F64(ib) / self.try_interpolated_value(date)?)
                 }
             }
         }
     }
 }
 
+impl<T: CurveInterpolation + Clone, U: DateRoll + Clone> CurveDF<T, U> {
+    /// Return the curve re-expressed on nodes at the given timestamps, valued by interpolation
+    /// of this curve, at the same `ADOrder` with variables tagged by `id` and node position.
+    fn reexpress(&self, keys: Vec<i64>) -> Result<Self, RateslibError> {
+        let values = keys
+            .into_iter()
+            .map(|k| {
+                let date = DateTime::from_timestamp(k, 0).unwrap().naive_utc();
+                Ok((k, f64::from(self.try_interpolated_value(&date)?)))
+            })
+            .collect::<Result<Vec<_>, RateslibError>>()?;
+        let mut curve = self.clone();
+        curve.nodes = NodesTimestamp::F64(IndexMap::from_iter(values));
+        curve.set_ad_order(self.ad())?;
+        Ok(curve)
+    }
+
+    /// Return the curve with an additional node at `date`, valued by interpolation so that the
+    /// curve is unchanged at the nodes. The node may extend the curve beyond its final node but
+    /// not before its initial node.
+    ///
+    /// At a non-zero `ADOrder` every node is re-tagged by `id` and node position, and the
+    /// sensitivities to the existing nodes are mapped to the new nodes with
+    /// [node_jacobian](CurveDF::node_jacobian).
+    pub fn with_node(&self, date: &NaiveDateTime) -> Result<Self, RateslibError> {
+        let mut keys = self.nodes.keys();
+        let timestamp = date.and_utc().timestamp();
+        if timestamp < keys[0] {
+            return Err(self.out_of_range(date).into());
+        }
+        if keys.contains(&timestamp) {
+            return Err(CurveError::DuplicateNode { date: *date }.into());
+        }
+        keys.push(timestamp);
+        keys.sort();
+        self.reexpress(keys)
+    }
+
+    /// Return the curve without its node at `date`, other than the initial node, interpolating
+    /// between the remaining nodes.
+    ///
+    /// At a non-zero `ADOrder` every node is re-tagged by `id` and node position.
+    pub fn without_node(&self, date: &NaiveDateTime) -> Result<Self, RateslibError> {
+        let mut keys = self.nodes.keys();
+        let timestamp = date.and_utc().timestamp();
+        match keys.iter().position(|k| *k == timestamp) {
+            None => Err(CurveError::MissingNode { date: *date }.into()),
+            Some(0) => Err(self.out_of_range(date).into()),
+            Some(_) if keys.len() <= 2 => Err(CurveError::InsufficientNodes {
+                required: 2,
+                given: keys.len() - 1,
+            }
+            .into()),
+            Some(i) => {
+                keys.remove(i);
+                self.reexpress(keys)
+            }
+        }
+    }
+
+    /// Return the Jacobian of the values of this curve at the nodes of `other` to the node
+    /// values of this curve, with a row for each node of `other`.
+    ///
+    /// Where `other` re-expresses this curve, such as by [with_node](CurveDF::with_node), the
+    /// sensitivities of a value to the nodes of this curve are the product of the transposed
+    /// Jacobian and its sensitivities to the nodes of `other`.
+    pub fn node_jacobian(&self, other: &Self) -> Result<Array2<f64>, RateslibError> {
+        let mut curve = self.clone();
+        curve.set_ad_order(ADOrder::Zero)?;
+        curve.set_ad_order(ADOrder::One)?;
+        let vars = get_variable_tags(&self.id, self.nodes.keys().len());
+        let rows: Vec<Vec<f64>> = other
+            .nodes
+            .keys()
+            .into_iter()
+            .map(|k| {
+                let date = DateTime::from_timestamp(k, 0).unwrap().naive_utc();
+                Ok(match curve.try_interpolated_value(&date)? {
+                    Number::Dual(d) => d.gradient1(vars.clone()).to_vec(),
+                    _ => vec![0.0; vars.len()],
+                })
+            })
+            .collect::<Result<_, RateslibError>>()?;
+        Ok(Array2::from_shape_fn((rows.len(), vars.len()), |(i, j)| {
+            rows[i][j]
+        }))
+    }
+}
+
+/// Measure values from a curve for the pricing of periods and instruments.
+pub trait PricingCurve {
+    /// Return the identifier of the curve.
+    fn id(&self) -> &str;
+
+    /// Return the initial node date of the curve.
+    fn initial_date(&self) -> NaiveDateTime;
+
+    /// Return the discount factor, or survival probability, at a date.
+    fn df(&self, date: &NaiveDateTime) -> Number;
+
+    /// Return the day count fraction between two dates under the convention of the curve.
+    fn dcf(&self, start: &NaiveDateTime, end: &NaiveDateTime) -> Result<f64, RateslibError>;
+
+    /// Return the index value at a date.
+    fn index_value(&self, date: &NaiveDateTime) -> Result<Number, RateslibError>;
+
+    /// Return the simple forward rate, in percent, between two dates.
+    fn rate(&self, start: &NaiveDateTime, end: &NaiveDateTime) -> Result<Number, RateslibError> {
+        let dcf = self.dcf(start, end)?;
+        Ok((self.df(start) / self.df(end) - 1.0) / dcf * 100.0)
+    }
+
+    /// Return the [ForwardProfile] of forward rates over consecutive periods of `days` from the
+    /// initial date until `end`.
+    fn forward_profile(
+        &self,
+        end: &NaiveDateTime,
+        days: i64,
+    ) -> Result<ForwardProfile, RateslibError> {
+        ForwardProfile::try_new(self, end, days)
+    }
+
+    /// Return the [SmoothnessMetrics] of the forward rates over consecutive periods of `days`
+    /// from the initial date until `end`.
+    fn smoothness(
+        &self,
+        end: &NaiveDateTime,
+        days: i64,
+    ) -> Result<SmoothnessMetrics, RateslibError> {
+        Ok(self.forward_profile(end, days)?.smoothness())
+    }
+}
+
+impl<T: CurveInterpolation, U: DateRoll> Drop for CurveDF<T, U> {
+    fn drop(&mut self) {
+        evict(self.address());
+    }
+}
+
+impl<T: CurveInterpolation, U: DateRoll> PricingCurve for CurveDF<T, U> {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn initial_date(&self) -> NaiveDateTime {
+        DateTime::from_timestamp(self.nodes.first_key(), 0)
+            .unwrap()
+            .naive_utc()
+    }
+
+    fn df(&self, date: &NaiveDateTime) -> Number {
+        cached_df(self.address(), date, || self.interpolated_value(date))
+    }
+
+    fn dcf(&self, start: &NaiveDateTime, end: &NaiveDateTime) -> Result<f64, RateslibError> {
+        self.convention.dcf(
+            start,
+            end,
+            None,
+            None,
+            None,
+            &RollDay::Unspecified {},
+            &self.calendar,
+        )
+    }
+
+    fn index_value(&self, date: &NaiveDateTime) -> Result<Number, RateslibError> {
+        CurveDF::index_value(self, date)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -214,6 +435,22 @@ mod tests {
         assert_eq!(result, Number::F64(0.9950147597711371))
     }
 
+    #[test]
+    fn test_intraday_df_and_rate() {
+        let c = curve_fixture();
+        let (d, noon) = (
+            ndt(2000, 7, 1),
+            ndt(2000, 7, 1) + chrono::Duration::hours(12),
+        );
+        let df = f64::from(c.df(&noon));
+        assert!(f64::from(c.df(&(d + chrono::Duration::days(1)))) < df);
+        assert!(df < f64::from(c.df(&d)));
+        // the rate over half a day is measured with the fraction of the day
+        let rate = f64::from(&c.rate(&d, &noon).unwrap());
+        let daily = f64::from(&c.rate(&d, &(d + chrono::Duration::days(1))).unwrap());
+        assert!((rate - daily).abs() < 1e-4);
+    }
+
     fn nodes_timestamp_fixture() -> NodesTimestamp {
         let nodes = Nodes::F64(IndexMap::from_iter(vec![
             (ndt(2000, 1, 1), 1.0_f64),
@@ -272,10 +509,80 @@ mod tests {
         assert_eq!(result, Number::F64(100.0 / 0.99))
     }
 
+    #[test]
+    fn test_pricing_curve_rate() {
+        let c = curve_fixture();
+        assert_eq!(c.initial_date(), ndt(2000, 1, 1));
+        let result = c.rate(&ndt(2000, 1, 1), &ndt(2001, 1, 1)).unwrap();
+        let expected = (1.0 / 0.99 - 1.0) / (366.0 / 360.0) * 100.0;
+        assert!((f64::from(result) - expected).abs() < 1e-12);
+    }
+
     #[test]
     fn test_index_value_prior_to_first() {
         let index_curve = index_curve_fixture();
         let result = index_curve.index_value(&ndt(1980, 1, 1)).unwrap();
         assert_eq!(result, Number::F64(0.0))
     }
+
+    #[test]
+    fn test_with_node() {
+        let c = curve_fixture();
+        let refined = c.with_node(&ndt(2001, 7, 1)).unwrap();
+        assert_eq!(refined.nodes.keys().len(), 4);
+        for date in [
+            ndt(2000, 7, 1),
+            ndt(2001, 3, 1),
+            ndt(2001, 9, 1),
+            ndt(2002, 1, 1),
+        ] {
+            let (a, b) = (f64::from(c.df(&date)), f64::from(refined.df(&date)));
+            assert!((a - b).abs() < 1e-15);
+        }
+        assert!(c.with_node(&ndt(2001, 1, 1)).is_err());
+        assert!(c.with_node(&ndt(1999, 1, 1)).is_err());
+
+        // sensitivities to the inserted node map to its neighbours by log-linear weights
+        let j = c.node_jacobian(&refined).unwrap();
+        assert_eq!(j.dim(), (4, 3));
+        assert_eq!(j.row(0).to_vec(), vec![1.0, 0.0, 0.0]);
+        assert_eq!(j.row(3).to_vec(), vec![0.0, 0.0, 1.0]);
+        let w = 181.0 / 365.0;
+        let v = f64::from(c.df(&ndt(2001, 7, 1)));
+        assert!((j[[2, 1]] - (1.0 - w) * v / 0.99).abs() < 1e-12);
+        assert!((j[[2, 2]] - w * v / 0.98).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_without_node() {
+        let mut c = curve_dual_fixture();
+        let coarse = c.without_node(&ndt(2001, 1, 1)).unwrap();
+        assert_eq!(coarse.nodes.keys().len(), 2);
+        assert_eq!(
+            coarse.df(&ndt(2002, 1, 1)),
+            Number::Dual(Dual::new(0.98, vec!["crv1".to_string()]))
+        );
+        assert!(coarse.without_node(&ndt(2002, 1, 1)).is_err());
+        assert!(c.without_node(&ndt(2000, 1, 1)).is_err());
+        assert!(c.without_node(&ndt(2001, 6, 1)).is_err());
+        let _ = c.set_ad_order(ADOrder::Two);
+        let coarse = c.without_node(&ndt(2001, 1, 1)).unwrap();
+        assert_eq!(coarse.ad(), ADOrder::Two);
+    }
+
+    #[test]
+    fn test_insufficient_nodes() {
+        let nodes = Nodes::F64(IndexMap::from_iter(vec![(ndt(2000, 1, 1), 1.0_f64)]));
+        let cal = NamedCal::try_new("all").unwrap();
+        let result = CurveDF::try_new(
+            nodes,
+            LogLinearInterpolator::new(),
+            "crv",
+            Convention::Act360,
+            Modifier::ModF,
+            None,
+            cal,
+        );
+        assert!(result.is_err());
+    }
 }
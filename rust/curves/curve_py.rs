@@ -1,13 +1,15 @@
 //! Wrapper module to export Rust curve data types to Python using pyo3 bindings.
 
 use crate::calendars::CalType;
-use crate::calendars::{Convention, Modifier};
+use crate::calendars::{Convention, Modifier, NamedCal};
 use crate::curves::nodes::{Nodes, NodesTimestamp};
 use crate::curves::{
-    CurveDF, CurveInterpolation, FlatBackwardInterpolator, FlatForwardInterpolator,
-    LinearInterpolator, LinearZeroRateInterpolator, LogLinearInterpolator, NullInterpolator,
+    CurveDF, CurveError, CurveInterpolation, DiffTolerance, FlatBackwardInterpolator,
+    FlatForwardInterpolator, LinearInterpolator, LinearZeroRateInterpolator, LogLinearInterpolator,
+    NullInterpolator, PricingCurve,
 };
 use crate::dual::{get_variable_tags, set_order, ADOrder, Dual, Dual2, Number};
+use crate::error::RateslibError;
 use crate::json::json_py::DeserializedObj;
 use crate::json::JSON;
 use bincode::{deserialize, serialize};
@@ -59,6 +61,17 @@ impl CurveInterpolation for CurveInterpolator {
             CurveInterpolator::Null(i) => i.interpolated_value(nodes, date),
         }
     }
+
+    fn try_interpolated_value(
+        &self,
+        nodes: &NodesTimestamp,
+        date: &NaiveDateTime,
+    ) -> Result<Number, CurveError> {
+        match self {
+            CurveInterpolator::Null(i) => i.try_interpolated_value(nodes, date),
+            _ => Ok(self.interpolated_value(nodes, date)),
+        }
+    }
 }
 
 #[pyclass(module = "rateslib.rs")]
@@ -70,6 +83,7 @@ pub(crate) struct Curve {
 #[pymethods]
 impl Curve {
     #[new]
+    #[allow(clippy::too_many_arguments)]
     fn new_py(
         nodes: IndexMap<NaiveDateTime, Number>,
         interpolator: CurveInterpolator,
@@ -139,7 +153,7 @@ impl Curve {
 
     #[pyo3(name = "index_value")]
     fn index_value_py(&self, date: NaiveDateTime) -> PyResult<Number> {
-        self.inner.index_value(&date)
+        Ok(self.inner.index_value(&date)?)
     }
 
     fn set_ad_order(&mut self, ad: ADOrder) -> PyResult<()> {
@@ -147,8 +161,76 @@ impl Curve {
         Ok(())
     }
 
-    fn __getitem__(&self, date: NaiveDateTime) -> Number {
-        self.inner.interpolated_value(&date)
+    /// Return the curve with an additional node valued by interpolation.
+    ///
+    /// Parameters
+    /// ----------
+    /// date: datetime
+    ///     The date of the new node, after the initial node.
+    ///
+    /// Returns
+    /// -------
+    /// Curve
+    fn with_node(&self, date: NaiveDateTime) -> PyResult<Self> {
+        Ok(Self {
+            inner: self.inner.with_node(&date)?,
+        })
+    }
+
+    /// Return the curve without one of its nodes, other than the initial node.
+    ///
+    /// Parameters
+    /// ----------
+    /// date: datetime
+    ///     The date of the node to remove.
+    ///
+    /// Returns
+    /// -------
+    /// Curve
+    fn without_node(&self, date: NaiveDateTime) -> PyResult<Self> {
+        Ok(Self {
+            inner: self.inner.without_node(&date)?,
+        })
+    }
+
+    /// Compare another curve to this curve at their nodes and by sampled forward rates.
+    ///
+    /// Parameters
+    /// ----------
+    /// other: Curve
+    ///     The curve compared to this curve.
+    /// days: int
+    ///     The length of the periods of the sampled forward rates.
+    /// df_tolerance: float
+    ///     The absolute difference of discount factors at the nodes considered unchanged.
+    /// forward_tolerance: float
+    ///     The absolute difference of forward rates, in basis points, considered unchanged.
+    ///
+    /// Returns
+    /// -------
+    /// tuple[bool, str]
+    ///     Whether the curves are within the tolerances, and a JSON string of the differences.
+    #[pyo3(signature = (other, days=91, df_tolerance=1e-10, forward_tolerance=1e-4))]
+    fn diff(
+        &self,
+        other: &Curve,
+        days: i64,
+        df_tolerance: f64,
+        forward_tolerance: f64,
+    ) -> PyResult<(bool, String)> {
+        let tolerance = DiffTolerance {
+            df: df_tolerance,
+            forward: forward_tolerance,
+        };
+        let diff = self.inner.diff(&other.inner, days, tolerance)?;
+        match diff.to_json() {
+            Ok(json) => Ok((diff.is_within_tolerance(), json)),
+            Err(e) => Err(PyValueError::new_err(e.to_string())),
+        }
+    }
+
+    fn __getitem__(&self, date: NaiveDateTime) -> PyResult<Number> {
+        Ok(self.inner.try_interpolated_value(&date)?)
     }
 
     fn __eq__(&self, other: Curve) -> bool {
@@ -179,6 +261,7 @@ impl Curve {
     pub fn __getstate__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
         Ok(PyBytes::new_bound(py, &serialize(&self).unwrap()))
     }
+    #[allow(clippy::type_complexity)]
     pub fn __getnewargs__(
         &self,
     ) -> PyResult<(
@@ -206,6 +289,44 @@ impl Curve {
 
 // /// Convert the `nodes`of a `Curve` from a `HashMap` input form into the local data model.
 // /// Will upcast f64 values to a new ADOrder adding curve variable tags by id.
+impl PricingCurve for Curve {
+    fn id(&self) -> &str {
+        self.inner.id()
+    }
+
+    fn initial_date(&self) -> NaiveDateTime {
+        self.inner.initial_date()
+    }
+
+    fn df(&self, date: &NaiveDateTime) -> Number {
+        self.inner.df(date)
+    }
+
+    fn dcf(&self, start: &NaiveDateTime, end: &NaiveDateTime) -> Result<f64, RateslibError> {
+        self.inner.dcf(start, end)
+    }
+
+    fn index_value(&self, date: &NaiveDateTime) -> Result<Number, RateslibError> {
+        PricingCurve::index_value(&self.inner, date)
+    }
+}
+
+impl From<&CurveDF<LogLinearInterpolator, NamedCal>> for Curve {
+    fn from(curve: &CurveDF<LogLinearInterpolator, NamedCal>) -> Self {
+        Self {
+            inner: CurveDF {
+                nodes: curve.nodes.clone(),
+                interpolator: CurveInterpolator::LogLinear(curve.interpolator.clone()),
+                id: curve.id.clone(),
+                convention: curve.convention,
+                modifier: curve.modifier,
+                index_base: curve.index_base,
+                calendar: CalType::NamedCal(curve.calendar.clone()),
+            },
+        }
+    }
+}
+
 // fn hashmap_into_nodes_timestamp(
 //     h: HashMap<NaiveDateTime, Number>,
 //     ad: ADOrder,
@@ -0,0 +1,239 @@
+use crate::calendars::DateRoll;
+use crate::curves::{CurveDF, CurveInterpolation, ForwardProfile};
+use crate::error::RateslibError;
+use crate::json::JSON;
+use chrono::{DateTime, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The differences between two curves within which they are considered unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DiffTolerance {
+    /// The absolute difference of discount factors at the nodes.
+    pub df: f64,
+    /// The absolute difference of sampled forward rates, in basis points.
+    pub forward: f64,
+}
+
+impl Default for DiffTolerance {
+    fn default() -> Self {
+        Self {
+            df: 1e-10,
+            forward: 1e-4,
+        }
+    }
+}
+
+/// The discount factors of two curves at a node date of either curve.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeDiff {
+    pub date: NaiveDateTime,
+    pub left: f64,
+    pub right: f64,
+    /// Whether the date is a node of both curves, rather than interpolated on one of them.
+    pub shared: bool,
+}
+
+impl NodeDiff {
+    pub fn difference(&self) -> f64 {
+        self.right - self.left
+    }
+}
+
+/// The forward rates, in percent, of two curves over a sampled period from `start`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForwardDiff {
+    pub start: NaiveDateTime,
+    pub left: f64,
+    pub right: f64,
+}
+
+impl ForwardDiff {
+    /// Return the difference of the forward rates in basis points.
+    pub fn difference(&self) -> f64 {
+        (self.right - self.left) * 100.0
+    }
+}
+
+/// A structured comparison of two curves, such as the calibrations of a curve before and after
+/// a change to the library, by their nodes and by forward rates sampled over equal periods.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CurveDiff {
+    pub(crate) left: String,
+    pub(crate) right: String,
+    pub(crate) nodes: Vec<NodeDiff>,
+    pub(crate) forwards: Vec<ForwardDiff>,
+    pub(crate) tolerance: DiffTolerance,
+}
+
+impl CurveDiff {
+    pub fn nodes(&self) -> &[NodeDiff] {
+        &self.nodes
+    }
+
+    pub fn forwards(&self) -> &[ForwardDiff] {
+        &self.forwards
+    }
+
+    /// Return the largest absolute difference of discount factors at the nodes.
+    pub fn max_df_difference(&self) -> f64 {
+        self.nodes
+            .iter()
+            .fold(0.0, |m, n| m.max(n.difference().abs()))
+    }
+
+    /// Return the largest absolute difference of the sampled forward rates, in basis points.
+    pub fn max_forward_difference(&self) -> f64 {
+        self.forwards
+            .iter()
+            .fold(0.0, |m, f| m.max(f.difference().abs()))
+    }
+
+    /// Return the nodes whose difference exceeds the tolerance.
+    pub fn node_breaches(&self) -> Vec<&NodeDiff> {
+        self.nodes
+            .iter()
+            .filter(|n| n.difference().abs() > self.tolerance.df)
+            .collect()
+    }
+
+    /// Return the sampled forward rates whose difference exceeds the tolerance.
+    pub fn forward_breaches(&self) -> Vec<&ForwardDiff> {
+        self.forwards
+            .iter()
+            .filter(|f| f.difference().abs() > self.tolerance.forward)
+            .collect()
+    }
+
+    /// Whether every difference is within the tolerance.
+    pub fn is_within_tolerance(&self) -> bool {
+        self.node_breaches().is_empty() && self.forward_breaches().is_empty()
+    }
+}
+
+impl JSON for CurveDiff {}
+
+impl fmt::Display for CurveDiff {
+    /// Display a summary of the comparison and each difference exceeding the tolerance.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "CurveDiff '{}' -> '{}': {} nodes, max df difference {:e}; {} forwards, max forward \
+             difference {:.6}bp",
+            self.left,
+            self.right,
+            self.nodes.len(),
+            self.max_df_difference(),
+            self.forwards.len(),
+            self.max_forward_difference()
+        )?;
+        for n in self.node_breaches() {
+            writeln!(
+                f,
+                "  node {}{}: {:.12} -> {:.12} ({:e})",
+                n.date.date(),
+                if n.shared { "" } else { " (interpolated)" },
+                n.left,
+                n.right,
+                n.difference()
+            )?;
+        }
+        for r in self.forward_breaches() {
+            writeln!(
+                f,
+                "  forward {}: {:.8}% -> {:.8}% ({:.6}bp)",
+                r.start.date(),
+                r.left,
+                r.right,
+                r.difference()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: CurveInterpolation, U: DateRoll> CurveDF<T, U> {
+    /// Return a [CurveDiff] of `other` relative to this curve, at the nodes of either curve and
+    /// by forward rates over consecutive periods of `days` until the earlier final node.
+    pub fn diff<T2: CurveInterpolation, U2: DateRoll>(
+        &self,
+        other: &CurveDF<T2, U2>,
+        days: i64,
+        tolerance: DiffTolerance,
+    ) -> Result<CurveDiff, RateslibError> {
+        let (left_keys, right_keys) = (self.nodes.keys(), other.nodes.keys());
+        let mut keys: Vec<i64> = left_keys.iter().chain(right_keys.iter()).copied().collect();
+        keys.sort();
+        keys.dedup();
+        let nodes = keys
+            .into_iter()
+            .map(|k| {
+                let date = DateTime::from_timestamp(k, 0).unwrap().naive_utc();
+                Ok(NodeDiff {
+                    date,
+                    left: f64::from(self.try_interpolated_value(&date)?),
+                    right: f64::from(other.try_interpolated_value(&date)?),
+                    shared: left_keys.contains(&k) && right_keys.contains(&k),
+                })
+            })
+            .collect::<Result<_, RateslibError>>()?;
+        let end = left_keys[left_keys.len() - 1].min(right_keys[right_keys.len() - 1]);
+        let end = DateTime::from_timestamp(end, 0).unwrap().naive_utc();
+        let (lp, rp) = (
+            ForwardProfile::try_new(self, &end, days)?,
+            ForwardProfile::try_new(other, &end, days)?,
+        );
+        let forwards = lp
+            .dates()
+            .iter()
+            .zip(lp.rates().iter().zip(rp.rates().iter()))
+            .map(|(start, (left, right))| ForwardDiff {
+                start: *start,
+                left: *left,
+                right: *right,
+            })
+            .collect();
+        Ok(CurveDiff {
+            left: self.id.clone(),
+            right: other.id.clone(),
+            nodes,
+            forwards,
+            tolerance,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::ndt;
+    use crate::curves::nodes::NodesTimestamp;
+    use crate::periods::period::tests::curve_fixture;
+
+    #[test]
+    fn test_curve_diff() {
+        let curve = curve_fixture("v1");
+        let same = curve
+            .diff(&curve_fixture("v2"), 91, DiffTolerance::default())
+            .unwrap();
+        assert!(same.is_within_tolerance());
+        assert_eq!(same.nodes().len(), 2);
+        assert!(same.forwards().len() > 30);
+
+        // an additional node which moves the curve by one basis point at five years
+        let mut shifted = curve.with_node(&ndt(2029, 1, 1)).unwrap();
+        let NodesTimestamp::F64(values) = &mut shifted.nodes else {
+            panic!("the fixture has f64 nodes")
+        };
+        values[&ndt(2029, 1, 1).and_utc().timestamp()] *= (-0.0001_f64 * 5.0).exp();
+        let diff = curve.diff(&shifted, 91, DiffTolerance::default()).unwrap();
+        assert!(!diff.is_within_tolerance());
+        let breaches = diff.node_breaches();
+        assert_eq!(breaches.len(), 1);
+        assert!(!breaches[0].shared);
+        assert!((diff.max_forward_difference() - 1.0).abs() < 0.1);
+        let report = diff.to_string();
+        assert!(report.starts_with("CurveDiff 'v1' -> 'v1': 3 nodes"));
+        assert!(report.contains("node 2029-01-01 (interpolated)"));
+    }
+}
@@ -0,0 +1,89 @@
+use chrono::NaiveDateTime;
+#[cfg(feature = "python")]
+use pyo3::PyErr;
+use std::fmt;
+
+/// The reasons that a curve cannot be constructed, modified or evaluated.
+///
+/// Converts into the Python `CurveError`, a subclass of `RateslibError`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CurveError {
+    /// A date outside the range of the curve nodes, given as the initial and final node dates.
+    OutOfRange {
+        date: NaiveDateTime,
+        bounds: (NaiveDateTime, NaiveDateTime),
+    },
+    /// A date at which the curve already has a node.
+    DuplicateNode { date: NaiveDateTime },
+    /// A date at which the curve has no node.
+    MissingNode { date: NaiveDateTime },
+    /// Fewer nodes than are required to define the curve.
+    InsufficientNodes { required: usize, given: usize },
+    /// A value of a curve whose interpolation occurs in Python.
+    NullInterpolation,
+    /// Another invalid argument to the construction or evaluation of a curve.
+    Invalid(String),
+}
+
+impl fmt::Display for CurveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CurveError::OutOfRange { date, bounds } => write!(
+                f,
+                "`{}` is out of range of the Curve nodes from `{}` to `{}`.",
+                date, bounds.0, bounds.1
+            ),
+            CurveError::DuplicateNode { date } => {
+                write!(f, "The Curve already has a node at `{}`.", date)
+            }
+            CurveError::MissingNode { date } => {
+                write!(f, "The Curve has no node at `{}`.", date)
+            }
+            CurveError::InsufficientNodes { required, given } => write!(
+                f,
+                "A Curve requires at least {} nodes but {} were given.",
+                required, given
+            ),
+            CurveError::NullInterpolation => write!(
+                f,
+                "A Curve with a `NullInterpolator` cannot be interpolated by the rust core."
+            ),
+            CurveError::Invalid(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for CurveError {}
+
+#[cfg(feature = "python")]
+impl From<CurveError> for PyErr {
+    fn from(value: CurveError) -> Self {
+        crate::error::error_py::CurveError::new_err(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::ndt;
+
+    #[test]
+    fn test_display() {
+        let e = CurveError::OutOfRange {
+            date: ndt(1999, 1, 1),
+            bounds: (ndt(2000, 1, 1), ndt(2001, 1, 1)),
+        };
+        assert_eq!(
+            e.to_string(),
+            "`1999-01-01 00:00:00` is out of range of the Curve nodes from `2000-01-01 00:00:00` to `2001-01-01 00:00:00`."
+        );
+        let e = CurveError::InsufficientNodes {
+            required: 2,
+            given: 1,
+        };
+        assert_eq!(
+            e.to_string(),
+            "A Curve requires at least 2 nodes but 1 were given."
+        );
+    }
+}
@@ -0,0 +1,257 @@
+use crate::calendars::{Convention, Modifier, NamedCal};
+use crate::curves::nodes::Nodes;
+use crate::curves::{CurveDF, LogLinearInterpolator, PricingCurve};
+use crate::dual::linalg::dsolve;
+use crate::dual::{get_variable_tags, Dual, Gradient1, MathFuncs, Number, Vars};
+use crate::error::RateslibError;
+use crate::instruments::{Cds, Instrument};
+use crate::periods::{Curves, Recovery};
+use chrono::NaiveDateTime;
+use indexmap::{IndexMap, IndexSet};
+use ndarray::{s, Array2};
+
+/// A curve of survival probabilities with constant hazard rates between the maturities of the
+/// credit default swaps from which it is bootstrapped.
+///
+/// Survival probabilities are interpolated log-linearly on an Act365F basis, so that hazard
+/// rates are flat forward between the nodes as in the ISDA standard model. The bootstrapped
+/// survival probabilities are dual numbers with sensitivities to the par spreads of the swaps,
+/// tagged by the `id` and the index of the quote, and to any variables of the swaps and the
+/// discount curve, such as a [Recovery] variable, with the spreads held fixed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HazardCurve {
+    pub(crate) curve: CurveDF<LogLinearInterpolator, NamedCal>,
+    /// The initial date and the maturity of each swap.
+    pub(crate) dates: Vec<NaiveDateTime>,
+    pub(crate) hazards: Vec<f64>,
+}
+
+impl HazardCurve {
+    /// Bootstrap a [HazardCurve] from `initial` to the par `spreads`, in percent, of the `cds`,
+    /// in order of maturity, discounted on the `discount` curve.
+    ///
+    /// The hazard rate of each step is solved in turn by Newton's method so that its swap is
+    /// priced at par, and the sensitivities of the curve are those of the hazard rates implied
+    /// by the triangular Jacobian of the spreads to the hazard rates.
+    pub fn bootstrap(
+        id: &str,
+        initial: NaiveDateTime,
+        cds: &[Cds],
+        spreads: &[f64],
+        discount: &dyn PricingCurve,
+        calendar: NamedCal,
+    ) -> Result<Self, RateslibError> {
+        if cds.is_empty() || cds.len() != spreads.len() {
+            return Err(RateslibError::curve(
+                "Bootstrapping a `HazardCurve` requires a spread for each of at least one CDS.",
+            ));
+        }
+        let mut dates = vec![initial];
+        dates.extend(cds.iter().map(|c| c.maturity()));
+        if dates.windows(2).any(|w| w[0] >= w[1]) {
+            return Err(RateslibError::curve(
+                "The CDS of a `HazardCurve` must mature in increasing order after its initial date.",
+            ));
+        }
+        let tags = get_variable_tags(id, cds.len());
+        let curve = |n: usize, hazards: &[f64]| {
+            let nodes = hazard_nodes(&dates[..=n], hazards, &tags);
+            hazard_curve(nodes, id, calendar.clone())
+        };
+        let mut hazards: Vec<f64> = Vec::with_capacity(cds.len());
+        for (k, (swap, spread)) in cds.iter().zip(spreads.iter()).enumerate() {
+            // the credit triangle
+            let recovery = f64::from(&swap.leg2().recovery().rate(&swap.maturity()));
+            hazards.push(spread / (100.0 * (1.0 - recovery)));
+            for i in 0..=MAX_ITERATIONS {
+                let hazard = curve(k + 1, &hazards)?;
+                let rate = Dual::from(&swap.rate(&Curves::new(Some(&hazard), Some(discount)))?);
+                let diff = rate.real() - spread;
+                if diff.abs() < TOLERANCE {
+                    break;
+                }
+                let slope = rate.gradient1(vec![tags[k].clone()])[0];
+                if i == MAX_ITERATIONS || slope == 0.0 {
+                    return Err(RateslibError::curve(format!(
+                        "The hazard rate to {} did not converge to the spread of its CDS.",
+                        dates[k + 1]
+                    )));
+                }
+                hazards[k] -= diff / slope;
+            }
+        }
+
+        // map the sensitivities of the survival probabilities from the hazard rates to the
+        // spreads and to any other variables of the swaps, such as recovery rates, at which the
+        // spreads are held fixed
+        let n = cds.len();
+        let hazard = curve(n, &hazards)?;
+        let curves = Curves::new(Some(&hazard), Some(discount));
+        let rates = cds
+            .iter()
+            .map(|c| Ok(Dual::from(&c.rate(&curves)?)))
+            .collect::<Result<Vec<Dual>, RateslibError>>()?;
+        let mut vars: IndexSet<String> = IndexSet::from_iter(tags.iter().cloned());
+        vars.extend(rates.iter().flat_map(|r| r.vars().iter().cloned()));
+        let vars: Vec<String> = vars.into_iter().collect();
+        let mut jacobian = Array2::<f64>::zeros((n, vars.len()));
+        for (mut row, rate) in jacobian.rows_mut().into_iter().zip(rates.iter()) {
+            row.assign(&rate.gradient1(vars.clone()));
+        }
+        // the hazard rates move by the inverse of their jacobian to a unit change in a spread,
+        // and offset the change in the spreads of a unit change in any other variable
+        let dh = jacobian.slice(s![.., ..n]).to_owned();
+        let mut rhs = -jacobian;
+        rhs.slice_mut(s![.., ..n]).assign(&Array2::eye(n));
+        let mut sensitivity = Array2::<f64>::zeros(rhs.dim());
+        for (mut x, b) in sensitivity.columns_mut().into_iter().zip(rhs.columns()) {
+            x.assign(&dsolve(&dh.view(), &b, false));
+        }
+        let Nodes::Dual(nodes) = hazard_nodes(&dates, &hazards, &tags) else {
+            unreachable!("hazard nodes are dual numbers")
+        };
+        let nodes = nodes
+            .into_iter()
+            .map(|(d, q)| {
+                let gradient = q.gradient1(tags.clone()).dot(&sensitivity);
+                Ok((d, Dual::try_new(q.real(), vars.clone(), gradient.to_vec())?))
+            })
+            .collect::<Result<IndexMap<_, _>, RateslibError>>()?;
+        Ok(Self {
+            curve: hazard_curve(Nodes::Dual(nodes), id, calendar)?,
+            dates,
+            hazards,
+        })
+    }
+
+    /// Bootstrap a [HazardCurve] to the par `spreads`, in percent, of [Cds::standard] swaps
+    /// effective from `initial` to each of the `maturities`, with a common `recovery`.
+    pub fn bootstrap_standard(
+        id: &str,
+        initial: NaiveDateTime,
+        maturities: &[NaiveDateTime],
+        spreads: &[f64],
+        recovery: Recovery,
+        discount: &dyn PricingCurve,
+        calendar: NamedCal,
+    ) -> Result<Self, RateslibError> {
+        let cds = maturities
+            .iter()
+            .zip(spreads.iter())
+            .map(|(m, s)| Cds::standard(initial, *m, *s, 1.0, recovery.clone(), &calendar))
+            .collect::<Result<Vec<Cds>, RateslibError>>()?;
+        Self::bootstrap(id, initial, &cds, spreads, discount, calendar)
+    }
+
+    /// Return the curve of survival probabilities.
+    pub fn curve(&self) -> &CurveDF<LogLinearInterpolator, NamedCal> {
+        &self.curve
+    }
+
+    /// Return the initial date and the maturity of each bootstrapped swap.
+    pub fn dates(&self) -> &[NaiveDateTime] {
+        &self.dates
+    }
+
+    /// Return the continuously compounded hazard rate of each step, as a decimal.
+    pub fn hazards(&self) -> &[f64] {
+        &self.hazards
+    }
+
+    /// Return the probability of survival to `date`.
+    pub fn survival(&self, date: &NaiveDateTime) -> Number {
+        self.curve.df(date)
+    }
+}
+
+const MAX_ITERATIONS: usize = 50;
+const TOLERANCE: f64 = 1e-12;
+
+/// Return the survival probabilities at the `dates` of the steps of `hazards`, with sensitivity
+/// to the `tags` of the hazard rates.
+fn hazard_nodes(dates: &[NaiveDateTime], hazards: &[f64], tags: &[String]) -> Nodes {
+    let mut log_q = Dual::new(0.0, vec![]);
+    let mut nodes = IndexMap::from_iter([(dates[0], log_q.exp())]);
+    for ((w, hazard), tag) in dates.windows(2).zip(hazards.iter()).zip(tags.iter()) {
+        let years = (w[1] - w[0]).num_days() as f64 / 365.0;
+        log_q = log_q - Dual::new(*hazard, vec![tag.clone()]) * years;
+        nodes.insert(w[1], log_q.exp());
+    }
+    Nodes::Dual(nodes)
+}
+
+fn hazard_curve(
+    nodes: Nodes,
+    id: &str,
+    calendar: NamedCal,
+) -> Result<CurveDF<LogLinearInterpolator, NamedCal>, RateslibError> {
+    CurveDF::try_new(
+        nodes,
+        LogLinearInterpolator::new(),
+        id,
+        Convention::Act365F,
+        Modifier::ModF,
+        None,
+        calendar,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::ndt;
+    use crate::periods::period::tests::curve_fixture;
+
+    fn bootstrap(spreads: &[f64], recovery: Recovery) -> HazardCurve {
+        let maturities = [ndt(2025, 6, 20), ndt(2026, 12, 20), ndt(2028, 12, 20)];
+        let cal = NamedCal::try_new("nyc").unwrap();
+        let disc = curve_fixture("d");
+        let initial = ndt(2024, 1, 1);
+        HazardCurve::bootstrap_standard("h", initial, &maturities, spreads, recovery, &disc, cal)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_hazard_bootstrap_reprices() {
+        let spreads = [0.8, 1.1, 1.5];
+        let curve = bootstrap(&spreads, 0.4.into());
+        let disc = curve_fixture("d");
+        let curves = Curves::new(Some(curve.curve()), Some(&disc));
+        let cal = NamedCal::try_new("nyc").unwrap();
+        for (d, s) in curve.dates()[1..].iter().zip(spreads) {
+            let cds = Cds::standard(ndt(2024, 1, 1), *d, 1.0, 1e7, 0.4.into(), &cal).unwrap();
+            assert!((f64::from(&cds.rate(&curves).unwrap()) - s).abs() < 1e-10);
+        }
+        // hazard rates increase with spreads, which are about 0.6 of the hazard rate
+        assert!(curve.hazards().windows(2).all(|w| w[1] > w[0]));
+        assert!((curve.hazards()[0] - 0.008 / 0.6 * 365.0 / 360.0).abs() < 5e-4);
+    }
+
+    #[test]
+    fn test_hazard_spread_sensitivities() {
+        let spreads = [0.8, 1.1, 1.5];
+        let curve = bootstrap(&spreads, Recovery::variable("rr", 0.4));
+        let date = ndt(2027, 6, 1);
+        let Number::Dual(q) = curve.survival(&date) else {
+            panic!("survival probabilities are dual numbers")
+        };
+        let mut vars = get_variable_tags("h", 3);
+        vars.push("rr".to_string());
+        let gradient = q.gradient1(vars);
+        for i in 0..3 {
+            let mut bumped = spreads;
+            bumped[i] += 1e-5;
+            let q_up = bootstrap(&bumped, 0.4.into()).survival(&date);
+            assert!((gradient[i] - (f64::from(q_up) - q.real()) / 1e-5).abs() < 1e-4);
+        }
+        // a higher recovery implies a higher hazard rate for the same spreads
+        let q_up = bootstrap(&spreads, (0.4 + 1e-6).into()).survival(&date);
+        assert!((gradient[3] - (f64::from(q_up) - q.real()) / 1e-6).abs() < 1e-4);
+        assert!(gradient[3] < 0.0);
+        // survival at an earlier date is insensitive to the spreads of later swaps
+        let Number::Dual(q) = curve.survival(&ndt(2025, 1, 1)) else {
+            panic!("survival probabilities are dual numbers")
+        };
+        assert!(q.gradient1(get_variable_tags("h", 3))[1].abs() < 1e-12);
+    }
+}
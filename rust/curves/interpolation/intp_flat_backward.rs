@@ -1,25 +1,34 @@
 use crate::curves::nodes::NodesTimestamp;
 use crate::curves::CurveInterpolation;
 use crate::dual::Number;
+#[cfg(feature = "python")]
 use bincode::{deserialize, serialize};
 use chrono::NaiveDateTime;
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
+#[cfg(feature = "python")]
 use pyo3::types::{PyBytes, PyTuple};
-use pyo3::{pyclass, pymethods, Bound, PyResult, Python};
 use serde::{Deserialize, Serialize};
 use std::cmp::PartialEq;
 
 /// Define flat backward interpolation of nodes.
-#[pyclass(module = "rateslib.rs")]
+#[cfg_attr(feature = "python", pyclass(module = "rateslib.rs"))]
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct FlatBackwardInterpolator {}
 
-#[pymethods]
 impl FlatBackwardInterpolator {
-    #[new]
     pub fn new() -> Self {
         FlatBackwardInterpolator {}
     }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl FlatBackwardInterpolator {
+    #[new]
+    fn new_py() -> Self {
+        FlatBackwardInterpolator::new()
+    }
 
     // Pickling
     pub fn __setstate__(&mut self, state: Bound<'_, PyBytes>) -> PyResult<()> {
@@ -34,6 +43,12 @@ impl FlatBackwardInterpolator {
     }
 }
 
+impl Default for FlatBackwardInterpolator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl CurveInterpolation for FlatBackwardInterpolator {
     fn interpolated_value(&self, nodes: &NodesTimestamp, date: &NaiveDateTime) -> Number {
         let x = date.and_utc().timestamp();
@@ -1,25 +1,34 @@
 use crate::curves::nodes::NodesTimestamp;
 use crate::curves::CurveInterpolation;
 use crate::dual::Number;
+#[cfg(feature = "python")]
 use bincode::{deserialize, serialize};
 use chrono::NaiveDateTime;
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
+#[cfg(feature = "python")]
 use pyo3::types::{PyBytes, PyTuple};
-use pyo3::{pyclass, pymethods, Bound, PyResult, Python};
 use serde::{Deserialize, Serialize};
 use std::cmp::PartialEq;
 
 /// Define flat forward interpolation of nodes.
-#[pyclass(module = "rateslib.rs")]
+#[cfg_attr(feature = "python", pyclass(module = "rateslib.rs"))]
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct FlatForwardInterpolator {}
 
-#[pymethods]
 impl FlatForwardInterpolator {
-    #[new]
     pub fn new() -> Self {
         FlatForwardInterpolator {}
     }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl FlatForwardInterpolator {
+    #[new]
+    fn new_py() -> Self {
+        FlatForwardInterpolator::new()
+    }
 
     // Pickling
     pub fn __setstate__(&mut self, state: Bound<'_, PyBytes>) -> PyResult<()> {
@@ -34,6 +43,12 @@ impl FlatForwardInterpolator {
     }
 }
 
+impl Default for FlatForwardInterpolator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl CurveInterpolation for FlatForwardInterpolator {
     fn interpolated_value(&self, nodes: &NodesTimestamp, date: &NaiveDateTime) -> Number {
         let x = date.and_utc().timestamp();
@@ -2,25 +2,34 @@ use crate::curves::interpolation::utils::linear_interp;
 use crate::curves::nodes::NodesTimestamp;
 use crate::curves::CurveInterpolation;
 use crate::dual::Number;
+#[cfg(feature = "python")]
 use bincode::{deserialize, serialize};
 use chrono::NaiveDateTime;
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
+#[cfg(feature = "python")]
 use pyo3::types::{PyBytes, PyTuple};
-use pyo3::{pyclass, pymethods, Bound, PyResult, Python};
 use serde::{Deserialize, Serialize};
 use std::cmp::PartialEq;
 
 /// Define linear interpolation of nodes.
-#[pyclass(module = "rateslib.rs")]
+#[cfg_attr(feature = "python", pyclass(module = "rateslib.rs"))]
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct LinearInterpolator {}
 
-#[pymethods]
 impl LinearInterpolator {
-    #[new]
     pub fn new() -> Self {
         LinearInterpolator {}
     }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl LinearInterpolator {
+    #[new]
+    fn new_py() -> Self {
+        LinearInterpolator::new()
+    }
 
     // Pickling
     pub fn __setstate__(&mut self, state: Bound<'_, PyBytes>) -> PyResult<()> {
@@ -35,6 +44,12 @@ impl LinearInterpolator {
     }
 }
 
+impl Default for LinearInterpolator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl CurveInterpolation for LinearInterpolator {
     fn interpolated_value(&self, nodes: &NodesTimestamp, date: &NaiveDateTime) -> Number {
         let x = date.and_utc().timestamp();
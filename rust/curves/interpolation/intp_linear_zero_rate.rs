@@ -2,28 +2,36 @@ use crate::curves::interpolation::utils::linear_zero_interp;
 use crate::curves::nodes::NodesTimestamp;
 use crate::curves::CurveInterpolation;
 use crate::dual::Number;
+#[cfg(feature = "python")]
 use bincode::{deserialize, serialize};
 use chrono::NaiveDateTime;
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
+#[cfg(feature = "python")]
 use pyo3::types::{PyBytes, PyTuple};
-use pyo3::{pyclass, pymethods, Bound, PyResult, Python};
 use serde::{Deserialize, Serialize};
 use std::cmp::PartialEq;
 
 /// Define linear zero rate interpolation of nodes.
 ///
 /// This interpolation can only be used with discount factors node values.
-#[pyclass(module = "rateslib.rs")]
+#[cfg_attr(feature = "python", pyclass(module = "rateslib.rs"))]
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct LinearZeroRateInterpolator {}
 
-#[pymethods]
-
 impl LinearZeroRateInterpolator {
-    #[new]
     pub fn new() -> Self {
         LinearZeroRateInterpolator {}
     }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl LinearZeroRateInterpolator {
+    #[new]
+    fn new_py() -> Self {
+        LinearZeroRateInterpolator::new()
+    }
 
     // Pickling
     pub fn __setstate__(&mut self, state: Bound<'_, PyBytes>) -> PyResult<()> {
@@ -38,6 +46,12 @@ impl LinearZeroRateInterpolator {
     }
 }
 
+impl Default for LinearZeroRateInterpolator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl CurveInterpolation for LinearZeroRateInterpolator {
     fn interpolated_value(&self, nodes: &NodesTimestamp, date: &NaiveDateTime) -> Number {
         let x = date.and_utc().timestamp();
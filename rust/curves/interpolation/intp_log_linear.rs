@@ -2,25 +2,34 @@ use crate::curves::interpolation::utils::log_linear_interp;
 use crate::curves::nodes::NodesTimestamp;
 use crate::curves::CurveInterpolation;
 use crate::dual::Number;
+#[cfg(feature = "python")]
 use bincode::{deserialize, serialize};
 use chrono::NaiveDateTime;
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
+#[cfg(feature = "python")]
 use pyo3::types::{PyBytes, PyTuple};
-use pyo3::{pyclass, pymethods, Bound, PyResult, Python};
 use serde::{Deserialize, Serialize};
 use std::cmp::PartialEq;
 
 /// Define log-linear interpolation of nodes.
-#[pyclass(module = "rateslib.rs")]
+#[cfg_attr(feature = "python", pyclass(module = "rateslib.rs"))]
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct LogLinearInterpolator {}
 
-#[pymethods]
 impl LogLinearInterpolator {
-    #[new]
     pub fn new() -> Self {
         LogLinearInterpolator {}
     }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl LogLinearInterpolator {
+    #[new]
+    fn new_py() -> Self {
+        LogLinearInterpolator::new()
+    }
 
     // Pickling
     pub fn __setstate__(&mut self, state: Bound<'_, PyBytes>) -> PyResult<()> {
@@ -35,6 +44,12 @@ impl LogLinearInterpolator {
     }
 }
 
+impl Default for LogLinearInterpolator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl CurveInterpolation for LogLinearInterpolator {
     fn interpolated_value(&self, nodes: &NodesTimestamp, date: &NaiveDateTime) -> Number {
         let x = date.and_utc().timestamp();
@@ -1,27 +1,36 @@
 use crate::curves::nodes::NodesTimestamp;
-use crate::curves::CurveInterpolation;
+use crate::curves::{CurveError, CurveInterpolation};
 use crate::dual::Number;
+#[cfg(feature = "python")]
 use bincode::{deserialize, serialize};
 use chrono::NaiveDateTime;
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
+#[cfg(feature = "python")]
 use pyo3::types::{PyBytes, PyTuple};
-use pyo3::{pyclass, pymethods, Bound, PyResult, Python};
 use serde::{Deserialize, Serialize};
 use std::cmp::PartialEq;
 
 /// Define a null interpolation object.
 ///
 /// This is used by PyO3 binding to indicate interpolation occurs in Python.
-#[pyclass(module = "rateslib.rs")]
+#[cfg_attr(feature = "python", pyclass(module = "rateslib.rs"))]
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct NullInterpolator {}
 
-#[pymethods]
 impl NullInterpolator {
-    #[new]
     pub fn new() -> Self {
         NullInterpolator {}
     }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl NullInterpolator {
+    #[new]
+    fn new_py() -> Self {
+        NullInterpolator::new()
+    }
 
     // Pickling
     pub fn __setstate__(&mut self, state: Bound<'_, PyBytes>) -> PyResult<()> {
@@ -36,11 +45,25 @@ impl NullInterpolator {
     }
 }
 
+impl Default for NullInterpolator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Interpolation occurs in Python, so values cannot be obtained, and
+/// [try_interpolated_value](CurveInterpolation::try_interpolated_value) raises.
 impl CurveInterpolation for NullInterpolator {
     fn interpolated_value(&self, _nodes: &NodesTimestamp, _date: &NaiveDateTime) -> Number {
         panic!("NullInterpolator cannot be used to obtain interpolated values.");
-        #[allow(unreachable_code)]
-        Number::F64(0.0)
+    }
+
+    fn try_interpolated_value(
+        &self,
+        _nodes: &NodesTimestamp,
+        _date: &NaiveDateTime,
+    ) -> Result<Number, CurveError> {
+        Err(CurveError::NullInterpolation)
     }
 }
 
@@ -67,4 +90,14 @@ mod tests {
         let li = NullInterpolator::new();
         li.interpolated_value(&nts, &ndt(2000, 7, 1));
     }
+
+    #[test]
+    fn test_null_try_interpolation() {
+        let nts = nodes_timestamp_fixture();
+        let li = NullInterpolator::new();
+        assert_eq!(
+            li.try_interpolated_value(&nts, &ndt(2000, 7, 1)),
+            Err(CurveError::NullInterpolation)
+        );
+    }
 }
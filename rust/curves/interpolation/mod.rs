@@ -1,3 +1,4 @@
+#[cfg(feature = "python")]
 pub(crate) mod interpolation_py;
 
 pub(crate) mod intp_flat_backward;
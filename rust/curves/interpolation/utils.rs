@@ -1,8 +1,5 @@
-use crate::dual::{MathFuncs, NumberOps};
-use std::{
-    cmp::{PartialEq, PartialOrd},
-    ops::{Mul, Sub},
-};
+use crate::dual::{NumberOps, Scalar};
+use std::cmp::{PartialEq, PartialOrd};
 
 // pub(crate) fn linear_interp<T, U>(x1: &T, y1: &U, x2: &T, y2: &U, x: &T) -> U
 // where
@@ -14,30 +11,28 @@ use std::{
 // }
 
 /// Calculate the linear interpolation between two coordinates.
-pub(crate) fn linear_interp<T>(x1: f64, y1: &T, x2: f64, y2: &T, x: f64) -> T
+pub fn linear_interp<T: Scalar>(x1: f64, y1: &T, x2: f64, y2: &T, x: f64) -> T
 where
     for<'a> &'a T: NumberOps<T>,
-    T: Mul<f64, Output = T>,
 {
     y1 + &((y2 - y1) * ((x - x1) / (x2 - x1)))
 }
 
 /// Calculate the log-linear interpolation between two coordinates.
-pub(crate) fn log_linear_interp<T>(x1: f64, y1: &T, x2: f64, y2: &T, x: f64) -> T
+pub fn log_linear_interp<T: Scalar>(x1: f64, y1: &T, x2: f64, y2: &T, x: f64) -> T
 where
     for<'a> &'a T: NumberOps<T>,
-    T: Mul<f64, Output = T> + MathFuncs,
 {
     let (y1, y2) = (y1.log(), y2.log());
     let y = linear_interp(x1, &y1, x2, &y2, x);
     y.exp()
 }
 
-/// Calculate the linear zero rate interpolation between two coordinates.
-pub(crate) fn linear_zero_interp<T>(x0: f64, x1: f64, y1: &T, x2: f64, y2: &T, x: f64) -> T
+/// Calculate the linear zero rate interpolation between two coordinates, where `x0` is the
+/// initial coordinate at which zero rates are measured.
+pub fn linear_zero_interp<T: Scalar>(x0: f64, x1: f64, y1: &T, x2: f64, y2: &T, x: f64) -> T
 where
     for<'a> &'a T: NumberOps<T>,
-    T: Mul<f64, Output = T> + MathFuncs + Sub + Clone,
 {
     let t1: f64 = x1 - x0;
     let t2: f64 = x2 - x0;
@@ -64,6 +59,8 @@ where
 /// - 1.71: returns 1 (within second interval)
 /// - 2.8: returns 2 (closed right side of third interval)
 /// - 3.5: returns 2 (extrapolated out of range)
+///
+/// A sequence of fewer than two elements, which has no intervals, returns `left_count`.
 pub(crate) fn index_left<T>(list_input: &[T], value: &T, left_count: Option<usize>) -> usize
 where
     for<'a> &'a T: PartialOrd + PartialEq,
@@ -71,8 +68,7 @@ where
     let lc = left_count.unwrap_or(0_usize);
     let n = list_input.len();
     match n {
-        1 => panic!("`index_left` designed for intervals. Cannot index sequence of length 1."),
-        2 => lc,
+        0..=2 => lc,
         _ => {
             let split = (n - 1_usize) / 2_usize; // this will take the floor of result
             if n == 3 && value == &list_input[split] {
@@ -89,7 +85,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::dual::Dual;
+    use crate::dual::{Dual, MathFuncs};
 
     #[test]
     fn index_left_() {
@@ -100,6 +96,7 @@ mod tests {
         assert_eq!(index_left(&a, &1.71, None), 1_usize);
         assert_eq!(index_left(&a, &2.8, None), 2_usize);
         assert_eq!(index_left(&a, &3.5, None), 2_usize);
+        assert_eq!(index_left(&[1.2], &3.5, None), 0_usize);
     }
 
     #[test]
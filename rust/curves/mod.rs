@@ -1,5 +1,8 @@
 //! Create curves for calculating interest rates and discount factors.
 
+mod error;
+pub use crate::curves::error::CurveError;
+
 pub(crate) mod nodes;
 pub use crate::curves::nodes::Nodes;
 
@@ -10,10 +13,42 @@ pub use crate::curves::interpolation::intp_linear::LinearInterpolator;
 pub use crate::curves::interpolation::intp_linear_zero_rate::LinearZeroRateInterpolator;
 pub use crate::curves::interpolation::intp_log_linear::LogLinearInterpolator;
 pub use crate::curves::interpolation::intp_null::NullInterpolator;
+pub use crate::curves::interpolation::utils::{
+    linear_interp, linear_zero_interp, log_linear_interp,
+};
 
 pub(crate) mod curve;
-pub use crate::curves::curve::{CurveDF, CurveInterpolation};
+pub use crate::curves::curve::{CurveDF, CurveInterpolation, PricingCurve};
+
+pub(crate) mod cache;
+pub(crate) use crate::curves::cache::evict;
+pub use crate::curves::cache::{invalidate_curve_cache, with_curve_cache, CacheStats};
+
+mod diff;
+pub use crate::curves::diff::{CurveDiff, DiffTolerance, ForwardDiff, NodeDiff};
+
+mod hazard;
+pub use crate::curves::hazard::HazardCurve;
+
+mod seasonality;
+pub use crate::curves::seasonality::{SeasonalCurve, Seasonality};
+
+mod step;
+pub use crate::curves::step::StepCurve;
+
+mod roll;
+pub use crate::curves::roll::{RollMethod, RolledCurve};
+
+mod quotes;
+pub use crate::curves::quotes::{
+    convert_zero_rate, df_from_zero_rate, dfs_from_par_rates, forward_rate, par_rate, zero_rate,
+    zero_rate_from_df, Compounding,
+};
+
+mod smoothness;
+pub use crate::curves::smoothness::{ForwardProfile, SmoothnessMetrics};
 
+#[cfg(feature = "python")]
 pub(crate) mod curve_py;
 
 mod serde;
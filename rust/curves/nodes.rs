@@ -1,4 +1,6 @@
-use crate::dual::{Dual, Dual2, Number};
+#[cfg(feature = "python")]
+use crate::dual::Number;
+use crate::dual::{Dual, Dual2};
 use chrono::{DateTime, NaiveDateTime};
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
@@ -92,6 +94,7 @@ impl NodesTimestamp {
         }
     }
 
+    #[cfg(feature = "python")]
     pub(crate) fn index_map(&self) -> IndexMap<NaiveDateTime, Number> {
         macro_rules! create_map {
             ($map:ident, $Variant:ident) => {
@@ -0,0 +1,225 @@
+use crate::curves::PricingCurve;
+use crate::dual::{MathFuncs, Number};
+use crate::error::RateslibError;
+use chrono::NaiveDateTime;
+use num_traits::Pow;
+use serde::{Deserialize, Serialize};
+
+/// The compounding of a zero rate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Compounding {
+    Simple,
+    /// Compounded `frequency` times per annum.
+    Periodic {
+        frequency: u32,
+    },
+    Continuous,
+}
+
+impl Compounding {
+    /// Create a [Compounding::Periodic], validating the `frequency` is positive.
+    pub fn try_periodic(frequency: u32) -> Result<Self, RateslibError> {
+        let compounding = Compounding::Periodic { frequency };
+        compounding.validate()?;
+        Ok(compounding)
+    }
+
+    /// Raise if the compounding is periodic with a `frequency` of zero.
+    pub fn validate(&self) -> Result<(), RateslibError> {
+        match self {
+            Compounding::Periodic { frequency: 0 } => Err(RateslibError::value(
+                "`frequency` of a periodic compounding must be positive.",
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Return the discount factor of a zero `rate`, in percent, over a day count fraction `dcf`.
+pub fn df_from_zero_rate(
+    rate: &Number,
+    dcf: f64,
+    compounding: Compounding,
+) -> Result<Number, RateslibError> {
+    compounding.validate()?;
+    let r = rate / 100.0;
+    Ok(match compounding {
+        Compounding::Simple => 1.0 / (r * dcf + 1.0),
+        Compounding::Periodic { frequency } => {
+            let f = frequency as f64;
+            (r / f + 1.0).pow(-f * dcf)
+        }
+        Compounding::Continuous => (r * -dcf).exp(),
+    })
+}
+
+/// Return the zero rate, in percent, of a discount factor `df` over a day count fraction
+/// `dcf`.
+pub fn zero_rate_from_df(
+    df: &Number,
+    dcf: f64,
+    compounding: Compounding,
+) -> Result<Number, RateslibError> {
+    compounding.validate()?;
+    let r = match compounding {
+        Compounding::Simple => (1.0 / df - 1.0) / dcf,
+        Compounding::Periodic { frequency } => {
+            let f = frequency as f64;
+            (df.pow(-1.0 / (f * dcf)) - 1.0) * f
+        }
+        Compounding::Continuous => -df.log() / dcf,
+    };
+    Ok(r * 100.0)
+}
+
+/// Return a zero `rate`, in percent, over a day count fraction `dcf` under another
+/// compounding.
+pub fn convert_zero_rate(
+    rate: &Number,
+    dcf: f64,
+    from: Compounding,
+    to: Compounding,
+) -> Result<Number, RateslibError> {
+    zero_rate_from_df(&df_from_zero_rate(rate, dcf, from)?, dcf, to)
+}
+
+/// Return the simply compounded forward rate, in percent, between two discount factors
+/// separated by a day count fraction `dcf`.
+pub fn forward_rate(df_start: &Number, df_end: &Number, dcf: f64) -> Number {
+    (df_start / df_end - 1.0) / dcf * 100.0
+}
+
+/// Return the par swap rate, in percent, of a fixed leg starting at discount factor
+/// `df_start` with payment discount factors `dfs` and period day count fractions `dcfs`.
+pub fn par_rate(df_start: &Number, dfs: &[Number], dcfs: &[f64]) -> Result<Number, RateslibError> {
+    if dfs.is_empty() || dfs.len() != dcfs.len() {
+        return Err(RateslibError::curve(
+            "`dfs` and `dcfs` must be non-empty and of equal length.",
+        ));
+    }
+    let annuity = dfs
+        .iter()
+        .zip(dcfs.iter())
+        .fold(Number::F64(0.0), |acc, (df, dcf)| acc + df * *dcf);
+    Ok((df_start - dfs.last().unwrap()) / annuity * 100.0)
+}
+
+/// Return the payment discount factors of consecutive fixed legs starting at discount factor
+/// `df_start`, where the `i`th leg has the first `i + 1` periods of day count fractions
+/// `dcfs`, and is priced at the par `rates`, in percent.
+pub fn dfs_from_par_rates(
+    df_start: &Number,
+    rates: &[Number],
+    dcfs: &[f64],
+) -> Result<Vec<Number>, RateslibError> {
+    if rates.len() != dcfs.len() {
+        return Err(RateslibError::curve(
+            "`rates` and `dcfs` must be of equal length.",
+        ));
+    }
+    let mut dfs: Vec<Number> = Vec::with_capacity(rates.len());
+    let mut annuity = Number::F64(0.0);
+    for (rate, dcf) in rates.iter().zip(dcfs.iter()) {
+        let r = rate / 100.0;
+        let df = (df_start - &r * &annuity) / (&r * *dcf + 1.0);
+        annuity = annuity + &df * *dcf;
+        dfs.push(df);
+    }
+    Ok(dfs)
+}
+
+/// Return the zero rate, in percent, of a `curve` to a `date` measured with the day count
+/// convention of the curve from its initial date.
+pub fn zero_rate(
+    curve: &dyn PricingCurve,
+    date: &NaiveDateTime,
+    compounding: Compounding,
+) -> Result<Number, RateslibError> {
+    let dcf = curve.dcf(&curve.initial_date(), date)?;
+    if dcf <= 0.0 {
+        return Err(RateslibError::curve(
+            "`date` of a zero rate must be after the initial date of the curve.",
+        ));
+    }
+    zero_rate_from_df(&curve.df(date), dcf, compounding)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::ndt;
+    use crate::dual::{Dual, Gradient1};
+    use crate::periods::period::tests::curve_fixture;
+
+    fn gradient(n: &Number, var: &str) -> f64 {
+        match n {
+            Number::Dual(d) => d.gradient1(vec![var.to_string()])[0],
+            _ => panic!("expected a Dual"),
+        }
+    }
+
+    #[test]
+    fn test_round_trips() {
+        let rate = Number::Dual(Dual::new(3.0, vec!["r".to_string()]));
+        for compounding in [
+            Compounding::Simple,
+            Compounding::Periodic { frequency: 2 },
+            Compounding::Continuous,
+        ] {
+            let df = df_from_zero_rate(&rate, 2.5, compounding).unwrap();
+            let back = zero_rate_from_df(&df, 2.5, compounding).unwrap();
+            assert!((f64::from(&back) - 3.0).abs() < 1e-12);
+            assert!((gradient(&back, "r") - 1.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_convert_zero_rate() {
+        let annual = Compounding::Periodic { frequency: 1 };
+        let r = convert_zero_rate(&Number::F64(5.0), 3.0, annual, Compounding::Continuous);
+        assert!((f64::from(&r.unwrap()) - 100.0 * 1.05_f64.ln()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_zero_frequency_raises() {
+        assert!(matches!(
+            Compounding::try_periodic(0),
+            Err(RateslibError::Value(_))
+        ));
+        assert_eq!(
+            Compounding::try_periodic(4).unwrap(),
+            Compounding::Periodic { frequency: 4 }
+        );
+        let zero = Compounding::Periodic { frequency: 0 };
+        assert!(df_from_zero_rate(&Number::F64(5.0), 1.0, zero).is_err());
+        assert!(zero_rate_from_df(&Number::F64(0.95), 1.0, zero).is_err());
+        let curve = curve_fixture("c");
+        assert!(zero_rate(&curve, &ndt(2026, 1, 1), zero).is_err());
+    }
+
+    #[test]
+    fn test_par_rates_bootstrap() {
+        let dfs = vec![Number::F64(0.97), Number::F64(0.94), Number::F64(0.90)];
+        let dcfs = vec![1.0, 1.01, 0.99];
+        let start = Number::F64(1.0);
+        let rates: Vec<Number> = (1..=3)
+            .map(|n| par_rate(&start, &dfs[..n], &dcfs[..n]).unwrap())
+            .collect();
+        let back = dfs_from_par_rates(&start, &rates, &dcfs).unwrap();
+        for (a, b) in back.iter().zip(dfs.iter()) {
+            assert!((f64::from(a) - f64::from(b)).abs() < 1e-14);
+        }
+        assert!(par_rate(&start, &dfs, &dcfs[..1]).is_err());
+    }
+
+    #[test]
+    fn test_forward_and_zero_rate() {
+        let curve = curve_fixture("c");
+        let (a, b) = (ndt(2025, 1, 1), ndt(2026, 1, 1));
+        let fwd = forward_rate(&curve.df(&a), &curve.df(&b), curve.dcf(&a, &b).unwrap());
+        assert_eq!(fwd, curve.rate(&a, &b).unwrap());
+        let z = zero_rate(&curve, &b, Compounding::Continuous).unwrap();
+        assert!((f64::from(&z) - 2.0).abs() < 1e-10);
+        assert!(zero_rate(&curve, &ndt(2024, 1, 1), Compounding::Simple).is_err());
+    }
+}
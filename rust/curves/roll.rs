@@ -0,0 +1,220 @@
+use crate::calendars::DateRoll;
+use crate::curves::nodes::NodesTimestamp;
+use crate::curves::{CurveDF, CurveInterpolation, PricingCurve};
+use crate::dual::{Dual, Dual2, Number};
+use crate::error::RateslibError;
+use chrono::{Duration, NaiveDateTime};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+/// The assumption under which a curve is rolled forward to a horizon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RollMethod {
+    /// The curve at the horizon is implied by the forward rates of the curve, so that
+    /// discount factors are divided by the discount factor of the horizon.
+    Forward,
+    /// The curve retains its shape, so that rates over a tenor from the initial date are
+    /// unchanged over the same tenor from the horizon.
+    Unchanged,
+}
+
+/// A view of a [PricingCurve] rolled forward to a `horizon`.
+///
+/// The view shares the node data of its curve, so that a curve may be rolled repeatedly, such
+/// as intraday, without being rebuilt.
+#[derive(Clone, Copy)]
+pub struct RolledCurve<'a> {
+    curve: &'a dyn PricingCurve,
+    horizon: NaiveDateTime,
+    method: RollMethod,
+}
+
+impl<'a> RolledCurve<'a> {
+    pub fn try_new(
+        curve: &'a dyn PricingCurve,
+        horizon: NaiveDateTime,
+        method: RollMethod,
+    ) -> Result<Self, RateslibError> {
+        validate_horizon(curve.initial_date(), &horizon)?;
+        Ok(Self {
+            curve,
+            horizon,
+            method,
+        })
+    }
+
+    /// Return the view of the same curve rolled further forward to a later `horizon`.
+    pub fn roll(&self, horizon: NaiveDateTime) -> Result<Self, RateslibError> {
+        validate_horizon(self.horizon, &horizon)?;
+        RolledCurve::try_new(self.curve, horizon, self.method)
+    }
+
+    fn shift(&self) -> Duration {
+        self.horizon - self.curve.initial_date()
+    }
+}
+
+impl PricingCurve for RolledCurve<'_> {
+    fn id(&self) -> &str {
+        self.curve.id()
+    }
+
+    fn initial_date(&self) -> NaiveDateTime {
+        self.horizon
+    }
+
+    fn df(&self, date: &NaiveDateTime) -> Number {
+        match self.method {
+            RollMethod::Forward => self.curve.df(date) / self.curve.df(&self.horizon),
+            RollMethod::Unchanged => self.curve.df(&(*date - self.shift())),
+        }
+    }
+
+    fn dcf(&self, start: &NaiveDateTime, end: &NaiveDateTime) -> Result<f64, RateslibError> {
+        self.curve.dcf(start, end)
+    }
+
+    fn index_value(&self, date: &NaiveDateTime) -> Result<Number, RateslibError> {
+        match self.method {
+            RollMethod::Forward => self.curve.index_value(date),
+            RollMethod::Unchanged => self.curve.index_value(&(*date - self.shift())),
+        }
+    }
+}
+
+fn validate_horizon(initial: NaiveDateTime, horizon: &NaiveDateTime) -> Result<(), RateslibError> {
+    if *horizon < initial {
+        return Err(RateslibError::curve(
+            "`horizon` of a curve roll cannot be before the initial date of the curve.",
+        ));
+    }
+    Ok(())
+}
+
+impl<T: CurveInterpolation + Clone, U: DateRoll + Clone> CurveDF<T, U> {
+    /// Return the curve rolled forward to a `horizon`.
+    ///
+    /// A [RollMethod::Forward] roll removes the nodes before the `horizon`, adds a node at it,
+    /// and is exact for curves which interpolate log-linearly between the `horizon` and the
+    /// following node. An index curve rolled forward has its index base at the forecast index
+    /// value of the `horizon`. A [RollMethod::Unchanged] roll shifts every node date.
+    pub fn roll(&self, horizon: &NaiveDateTime, method: RollMethod) -> Result<Self, RateslibError> {
+        validate_horizon(self.initial_date(), horizon)?;
+        let h = horizon.and_utc().timestamp();
+        let mut curve = self.clone();
+        match method {
+            RollMethod::Unchanged => {
+                let shift = h - self.nodes.first_key();
+                curve.nodes = match &self.nodes {
+                    NodesTimestamp::F64(m) => NodesTimestamp::F64(shift_keys(m, shift)),
+                    NodesTimestamp::Dual(m) => NodesTimestamp::Dual(shift_keys(m, shift)),
+                    NodesTimestamp::Dual2(m) => NodesTimestamp::Dual2(shift_keys(m, shift)),
+                };
+            }
+            RollMethod::Forward => {
+                if self.nodes.keys().last().is_some_and(|last| h >= *last) {
+                    return Err(RateslibError::curve(
+                        "`horizon` of a forward curve roll must be before the final node.",
+                    ));
+                }
+                let df_h = self.df(horizon);
+                curve.nodes = match &self.nodes {
+                    NodesTimestamp::F64(m) => {
+                        let d = f64::from(&df_h);
+                        NodesTimestamp::F64(forward_nodes(m, h, 1.0, |v| v / d))
+                    }
+                    NodesTimestamp::Dual(m) => {
+                        let d = Dual::from(&df_h);
+                        let one = Dual::new(1.0, vec![]);
+                        NodesTimestamp::Dual(forward_nodes(m, h, one, |v| v / &d))
+                    }
+                    NodesTimestamp::Dual2(m) => {
+                        let d = Dual2::from(&df_h);
+                        let one = Dual2::new(1.0, vec![]);
+                        NodesTimestamp::Dual2(forward_nodes(m, h, one, |v| v / &d))
+                    }
+                };
+                if self.index_base.is_some() {
+                    curve.index_base = Some(f64::from(&self.index_value(horizon)?));
+                }
+            }
+        }
+        Ok(curve)
+    }
+}
+
+fn shift_keys<V: Clone>(nodes: &IndexMap<i64, V>, shift: i64) -> IndexMap<i64, V> {
+    IndexMap::from_iter(nodes.iter().map(|(k, v)| (k + shift, v.clone())))
+}
+
+fn forward_nodes<V, F: Fn(&V) -> V>(
+    nodes: &IndexMap<i64, V>,
+    horizon: i64,
+    one: V,
+    rebase: F,
+) -> IndexMap<i64, V> {
+    let mut rolled = IndexMap::from_iter([(horizon, one)]);
+    rolled.extend(
+        nodes
+            .iter()
+            .filter(|(k, _)| **k > horizon)
+            .map(|(k, v)| (*k, rebase(v))),
+    );
+    rolled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::ndt;
+    use crate::periods::period::tests::curve_fixture;
+
+    #[test]
+    fn test_forward_roll() {
+        let curve = curve_fixture("c");
+        let h = ndt(2026, 3, 1);
+        let rolled = curve.roll(&h, RollMethod::Forward).unwrap();
+        let view = RolledCurve::try_new(&curve, h, RollMethod::Forward).unwrap();
+        assert_eq!(rolled.initial_date(), h);
+        for d in [ndt(2027, 1, 1), ndt(2030, 6, 1)] {
+            // forward rates are preserved
+            let (a, b) = (rolled.rate(&h, &d).unwrap(), curve.rate(&h, &d).unwrap());
+            assert!((f64::from(&a) - f64::from(&b)).abs() < 1e-10);
+            assert!((f64::from(&view.df(&d)) - f64::from(&rolled.df(&d))).abs() < 1e-12);
+        }
+        let index = f64::from(&curve.index_value(&ndt(2029, 1, 1)).unwrap());
+        let rolled_index = f64::from(&rolled.index_value(&ndt(2029, 1, 1)).unwrap());
+        assert!((index - rolled_index).abs() < 1e-10);
+        assert!(curve.roll(&ndt(2034, 1, 1), RollMethod::Forward).is_err());
+    }
+
+    #[test]
+    fn test_unchanged_roll() {
+        let curve = curve_fixture("c");
+        let h = ndt(2025, 1, 1);
+        let rolled = curve.roll(&h, RollMethod::Unchanged).unwrap();
+        let view = RolledCurve::try_new(&curve, h, RollMethod::Unchanged).unwrap();
+        let a = rolled.rate(&ndt(2026, 1, 1), &ndt(2027, 1, 1)).unwrap();
+        // 2024 is a leap year so the same tenor starts a day later on the original curve
+        let b = curve.rate(&ndt(2025, 1, 2), &ndt(2026, 1, 2)).unwrap();
+        assert!((f64::from(&a) - f64::from(&b)).abs() < 1e-10);
+        assert_eq!(view.df(&ndt(2028, 5, 1)), rolled.df(&ndt(2028, 5, 1)));
+        assert!(RolledCurve::try_new(&curve, ndt(2023, 1, 1), RollMethod::Forward).is_err());
+        // rolling a view in two steps is rolling the curve in one
+        let twice = view.roll(ndt(2026, 1, 1)).unwrap();
+        let once = curve.roll(&ndt(2026, 1, 1), RollMethod::Unchanged).unwrap();
+        assert_eq!(twice.df(&ndt(2028, 5, 1)), once.df(&ndt(2028, 5, 1)));
+        assert!(twice.roll(ndt(2025, 6, 1)).is_err());
+    }
+
+    #[test]
+    fn test_forward_roll_dual() {
+        let mut curve = curve_fixture("c");
+        curve.set_ad_order(crate::dual::ADOrder::One).unwrap();
+        let rolled = curve.roll(&ndt(2025, 1, 1), RollMethod::Forward).unwrap();
+        match rolled.df(&ndt(2030, 1, 1)) {
+            Number::Dual(d) => assert!(d.vars.iter().any(|v| v == "c0")),
+            _ => panic!("expected a Dual"),
+        }
+    }
+}
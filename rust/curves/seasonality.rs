@@ -0,0 +1,258 @@
+use crate::curves::PricingCurve;
+use crate::dual::{get_variable_tags, ADOrder, Dual, Dual2, MathFuncs, Number};
+use crate::error::RateslibError;
+use crate::solver::{Solver, SolverResult, SolverSystem};
+use chrono::{Datelike, NaiveDateTime};
+
+/// Multiplicative monthly seasonal factors overlaid on the projections of an inflation index.
+///
+/// The factors are parametrised by the log of the factor of each calendar month, normalised so
+/// that their product over a year is one and the annual inflation rate of the index is
+/// unchanged. The log factors are variables tagged by the `id` and the index of the month,
+/// from zero for January, and may be estimated from historical index fixings with
+/// [Seasonality::estimate].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Seasonality {
+    pub(crate) log_factors: [f64; 12],
+    pub(crate) tags: Vec<String>,
+    pub(crate) ad: ADOrder,
+}
+
+impl Seasonality {
+    /// Create a [Seasonality] of the `factors` of each calendar month, from January, which must be
+    /// positive, with first order sensitivity to them.
+    pub fn try_new(id: &str, factors: &[f64]) -> Result<Self, RateslibError> {
+        if factors.len() != 12 || factors.iter().any(|f| *f <= 0.0) {
+            return Err(RateslibError::curve(
+                "`Seasonality` requires a positive factor for each of the 12 months.",
+            ));
+        }
+        let mut log_factors = [0.0; 12];
+        for (l, f) in log_factors.iter_mut().zip(factors.iter()) {
+            *l = f.ln();
+        }
+        Ok(Self {
+            log_factors,
+            tags: get_variable_tags(id, 12),
+            ad: ADOrder::One,
+        })
+    }
+
+    /// Return the seasonality with its factors at the `ad` order.
+    pub fn with_ad(mut self, ad: ADOrder) -> Self {
+        self.ad = ad;
+        self
+    }
+
+    /// Return the normalised factor of the calendar month of `date`.
+    pub fn factor(&self, date: &NaiveDateTime) -> Number {
+        let logs: Vec<Number> = self
+            .log_factors
+            .iter()
+            .zip(self.tags.iter())
+            .map(|(l, tag)| match self.ad {
+                ADOrder::Zero => Number::F64(*l),
+                ADOrder::One => Number::Dual(Dual::new(*l, vec![tag.clone()])),
+                ADOrder::Two => Number::Dual2(Dual2::new(*l, vec![tag.clone()])),
+            })
+            .collect();
+        let mean = logs.iter().fold(Number::F64(0.0), |acc, l| acc + l) / 12.0;
+        (&logs[date.month0() as usize] - &mean).exp()
+    }
+
+    /// Return the normalised factors of each calendar month, from January.
+    pub fn factors(&self) -> Vec<f64> {
+        let mean = self.log_factors.iter().sum::<f64>() / 12.0;
+        self.log_factors.iter().map(|l| (l - mean).exp()).collect()
+    }
+
+    /// Estimate the factors from monthly historical `fixings` of an index, of at least two
+    /// years, by least squares regression of the log of the index on a linear trend and the
+    /// log factors with the default [Solver].
+    pub fn estimate(
+        id: &str,
+        fixings: &[(NaiveDateTime, f64)],
+    ) -> Result<(Self, SolverResult), RateslibError> {
+        if fixings.len() < 24 || fixings.iter().any(|(_, v)| *v <= 0.0) {
+            return Err(RateslibError::curve(
+                "`Seasonality` estimation requires at least 24 positive monthly fixings.",
+            ));
+        }
+        let d0 = fixings[0].0;
+        let years = |d: &NaiveDateTime| (*d - d0).num_days() as f64 / 365.25;
+        let (first, last) = (fixings[0], fixings[fixings.len() - 1]);
+        let trend = (last.1 / first.1).ln() / years(&last.0);
+        let mut variables = vec![first.1.ln(), trend];
+        variables.extend([0.0; 11]);
+        let mut system = FixingSystem {
+            observations: fixings
+                .iter()
+                .map(|(d, _)| (years(d), d.month0() as usize))
+                .collect(),
+            variables,
+            tags: get_variable_tags(id, 13),
+        };
+        let targets = fixings.iter().map(|(_, v)| v.ln()).collect();
+        let result = Solver::try_new_default(targets)?.iterate(&mut system)?;
+        let mut log_factors = [0.0; 12];
+        log_factors[..11].copy_from_slice(&system.variables[2..]);
+        log_factors[11] = -system.variables[2..].iter().sum::<f64>();
+        let seasonality = Self {
+            log_factors,
+            tags: get_variable_tags(id, 12),
+            ad: ADOrder::One,
+        };
+        Ok((seasonality, result))
+    }
+}
+
+/// The regression of the log of index fixings, observed at times in years and calendar months,
+/// on a level, a trend and the log factors of the first 11 months, the last being minus their
+/// sum.
+struct FixingSystem {
+    observations: Vec<(f64, usize)>,
+    variables: Vec<f64>,
+    tags: Vec<String>,
+}
+
+impl SolverSystem for FixingSystem {
+    fn variable_tags(&self) -> Vec<String> {
+        self.tags.clone()
+    }
+
+    fn variables(&self) -> Vec<f64> {
+        self.variables.clone()
+    }
+
+    fn set_variables(&mut self, values: &[f64]) -> Result<(), RateslibError> {
+        self.variables = values.to_vec();
+        Ok(())
+    }
+
+    fn rates(&self) -> Result<Vec<Dual>, RateslibError> {
+        let x: Vec<Dual> = self
+            .variables
+            .iter()
+            .zip(self.tags.iter())
+            .map(|(v, tag)| Dual::new(*v, vec![tag.clone()]))
+            .collect();
+        let december = x[2..].iter().fold(Dual::new(0.0, vec![]), |acc, s| acc - s);
+        Ok(self
+            .observations
+            .iter()
+            .map(|(t, m)| {
+                let season = if *m == 11 { &december } else { &x[2 + m] };
+                &x[0] + &x[1] * *t + season
+            })
+            .collect())
+    }
+}
+
+/// A view of an inflation [PricingCurve] whose index values are adjusted by a [Seasonality]
+/// relative to the month of its initial date, at which the index value is unchanged.
+#[derive(Clone, Copy)]
+pub struct SeasonalCurve<'a> {
+    curve: &'a dyn PricingCurve,
+    seasonality: &'a Seasonality,
+}
+
+impl<'a> SeasonalCurve<'a> {
+    pub fn new(curve: &'a dyn PricingCurve, seasonality: &'a Seasonality) -> Self {
+        Self { curve, seasonality }
+    }
+}
+
+impl PricingCurve for SeasonalCurve<'_> {
+    fn id(&self) -> &str {
+        self.curve.id()
+    }
+
+    fn initial_date(&self) -> NaiveDateTime {
+        self.curve.initial_date()
+    }
+
+    fn df(&self, date: &NaiveDateTime) -> Number {
+        self.curve.df(date)
+    }
+
+    fn dcf(&self, start: &NaiveDateTime, end: &NaiveDateTime) -> Result<f64, RateslibError> {
+        self.curve.dcf(start, end)
+    }
+
+    fn index_value(&self, date: &NaiveDateTime) -> Result<Number, RateslibError> {
+        let factor = self.seasonality.factor(date) / self.seasonality.factor(&self.initial_date());
+        self.curve.index_value(date)?.try_mul(&factor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::{ndt, Convention, Modifier, NamedCal};
+    use crate::curves::{CurveDF, LogLinearInterpolator, Nodes};
+    use crate::dual::Gradient1;
+    use chrono::Months;
+    use indexmap::IndexMap;
+
+    const FACTORS: [f64; 12] = [
+        0.996, 0.999, 1.002, 1.003, 1.002, 1.001, 0.998, 0.999, 1.001, 1.000, 0.999, 1.000,
+    ];
+
+    #[test]
+    fn test_seasonal_curve() {
+        let nodes = Nodes::F64(IndexMap::from_iter([
+            (ndt(2024, 1, 1), 1.0),
+            (ndt(2034, 1, 1), (-0.025_f64 * 3653.0 / 365.0).exp()),
+        ]));
+        let cal = NamedCal::try_new("all").unwrap();
+        let curve = CurveDF::try_new(
+            nodes,
+            LogLinearInterpolator::new(),
+            "cpi",
+            Convention::Act365F,
+            Modifier::ModF,
+            Some(300.0),
+            cal,
+        )
+        .unwrap();
+        let seasonality = Seasonality::try_new("season", &FACTORS).unwrap();
+        let seasonal = SeasonalCurve::new(&curve, &seasonality);
+        let initial = ndt(2024, 1, 1);
+        assert!((f64::from(&seasonal.index_value(&initial).unwrap()) - 300.0).abs() < 1e-12);
+        // the seasonal adjustment cancels over a whole year
+        let (a, b) = (ndt(2025, 3, 1), ndt(2026, 3, 1));
+        let ratio = |c: &dyn PricingCurve| {
+            f64::from(&(c.index_value(&b).unwrap() / c.index_value(&a).unwrap()))
+        };
+        assert!((ratio(&seasonal) - ratio(&curve)).abs() < 1e-14);
+        let value = seasonal.index_value(&ndt(2025, 4, 15)).unwrap();
+        let expected =
+            f64::from(curve.index_value(&ndt(2025, 4, 15)).unwrap()) * FACTORS[3] / FACTORS[0];
+        assert!((f64::from(&value) - expected).abs() < 1e-9);
+        let Number::Dual(d) = value else {
+            panic!("the seasonal factors are dual numbers")
+        };
+        assert!(d.gradient1(vec!["season3".to_string()])[0] > 0.0);
+        assert!(Seasonality::try_new("s", &FACTORS[..11]).is_err());
+    }
+
+    #[test]
+    fn test_estimate_seasonality() {
+        // fixings with a 2.5% trend and known seasonal factors, recovered up to the solver
+        // tolerance
+        let fixings: Vec<(NaiveDateTime, f64)> = (0..48)
+            .map(|i| {
+                let d = ndt(2020, 1, 1) + Months::new(i);
+                let t = (d - ndt(2020, 1, 1)).num_days() as f64 / 365.25;
+                (d, 110.0 * (0.025 * t).exp() * FACTORS[i as usize % 12])
+            })
+            .collect();
+        let (seasonality, result) = Seasonality::estimate("s", &fixings).unwrap();
+        assert!(result.status.is_success());
+        let gm = FACTORS.iter().map(|f| f.ln()).sum::<f64>() / 12.0;
+        for (f, expected) in seasonality.factors().iter().zip(FACTORS) {
+            assert!((f - expected / gm.exp()).abs() < 1e-6);
+        }
+        assert!(Seasonality::estimate("s", &fixings[..12]).is_err());
+    }
+}
@@ -1,4 +1,5 @@
 use crate::calendars::DateRoll;
+#[cfg(feature = "python")]
 use crate::curves::curve_py::Curve;
 use crate::curves::{CurveDF, CurveInterpolation};
 use crate::json::JSON;
@@ -11,12 +12,14 @@ where
 {
 }
 
+#[cfg(feature = "python")]
 impl JSON for Curve {}
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::calendars::{ndt, Convention, Modifier, NamedCal};
+    #[cfg(feature = "python")]
     use crate::curves::curve_py::CurveInterpolator;
     use crate::curves::{
         FlatBackwardInterpolator, FlatForwardInterpolator, LinearInterpolator,
@@ -56,6 +59,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "python")]
     fn test_curve_json_py_enum() {
         let interpolator = CurveInterpolator::Linear(LinearInterpolator::new());
         let curve = curve_fixture(interpolator);
@@ -0,0 +1,157 @@
+use crate::curves::PricingCurve;
+use crate::error::RateslibError;
+use chrono::{Duration, NaiveDateTime};
+
+/// Changes in forward rates, in percent, below which a profile is considered flat.
+const FLAT_TOLERANCE: f64 = 1e-10;
+
+/// The simple forward rates, in percent, of a curve over consecutive periods of equal length.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForwardProfile {
+    pub(crate) dates: Vec<NaiveDateTime>,
+    pub(crate) rates: Vec<f64>,
+    pub(crate) days: i64,
+}
+
+/// Metrics of the smoothness of a [ForwardProfile], which allow interpolation schemes to be
+/// compared on the same calibration.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SmoothnessMetrics {
+    /// The largest absolute second difference of forward rates, in percent per year squared.
+    pub max_curvature: f64,
+    /// The sum of squared second differences of forward rates, scaled as an integral of the
+    /// squared curvature over years.
+    pub roughness: f64,
+    /// The sum of absolute changes in consecutive forward rates, in percent.
+    pub total_variation: f64,
+    /// The number of changes in the direction of the forward rates.
+    pub oscillations: usize,
+}
+
+impl ForwardProfile {
+    /// Create the [ForwardProfile] of a `curve` over consecutive periods of `days` from its
+    /// initial date, the last of which ends no later than `end`.
+    pub fn try_new<C: PricingCurve + ?Sized>(
+        curve: &C,
+        end: &NaiveDateTime,
+        days: i64,
+    ) -> Result<Self, RateslibError> {
+        if days <= 0 {
+            return Err(RateslibError::curve(
+                "The period of a `ForwardProfile` must be a positive number of days.",
+            ));
+        }
+        let step = Duration::days(days);
+        let mut dates = vec![];
+        let mut rates = vec![];
+        let mut start = curve.initial_date();
+        while start + step <= *end {
+            rates.push(f64::from(&curve.rate(&start, &(start + step))?));
+            dates.push(start);
+            start += step;
+        }
+        if rates.len() < 3 {
+            return Err(RateslibError::curve(
+                "A `ForwardProfile` requires at least three periods before `end`.",
+            ));
+        }
+        Ok(Self { dates, rates, days })
+    }
+
+    /// Return the start date of each period.
+    pub fn dates(&self) -> &[NaiveDateTime] {
+        &self.dates
+    }
+
+    /// Return the forward rate, in percent, of each period.
+    pub fn rates(&self) -> &[f64] {
+        &self.rates
+    }
+
+    /// Return the [SmoothnessMetrics] of the profile.
+    pub fn smoothness(&self) -> SmoothnessMetrics {
+        let h = self.days as f64 / 365.0;
+        let diffs: Vec<f64> = self.rates.windows(2).map(|w| w[1] - w[0]).collect();
+        let curvatures: Vec<f64> = diffs.windows(2).map(|w| (w[1] - w[0]) / (h * h)).collect();
+        let mut oscillations = 0;
+        let mut direction = 0.0;
+        for d in diffs.iter().filter(|d| d.abs() > FLAT_TOLERANCE) {
+            if direction * d < 0.0 {
+                oscillations += 1;
+            }
+            direction = d.signum();
+        }
+        SmoothnessMetrics {
+            max_curvature: curvatures.iter().fold(0.0, |m, c| c.abs().max(m)),
+            roughness: curvatures.iter().map(|c| c * c * h).sum(),
+            total_variation: diffs.iter().map(|d| d.abs()).sum(),
+            oscillations,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::{ndt, Convention, Modifier, NamedCal};
+    use crate::curves::{CurveDF, LinearZeroRateInterpolator, LogLinearInterpolator, Nodes};
+    use crate::periods::period::tests::curve_fixture;
+    use indexmap::IndexMap;
+
+    fn humped_nodes() -> Nodes {
+        Nodes::F64(IndexMap::from_iter([
+            (ndt(2024, 1, 1), 1.0),
+            (ndt(2025, 1, 1), 0.97),
+            (ndt(2026, 1, 1), 0.93),
+            (ndt(2027, 1, 1), 0.91),
+        ]))
+    }
+
+    #[test]
+    fn test_flat_curve_is_smooth() {
+        let curve = curve_fixture("c");
+        let profile = curve.forward_profile(&ndt(2030, 1, 1), 30).unwrap();
+        assert_eq!(profile.dates()[1], ndt(2024, 1, 31));
+        assert_eq!(profile.rates().len(), profile.dates().len());
+        let metrics = profile.smoothness();
+        assert_eq!(metrics.oscillations, 0);
+        assert!(metrics.max_curvature < 1e-8);
+        assert!(metrics.total_variation < 1e-8);
+    }
+
+    #[test]
+    fn test_compare_interpolation() {
+        let cal = NamedCal::try_new("all").unwrap();
+        let (conv, modifier) = (Convention::Act365F, Modifier::ModF);
+        let log_linear = CurveDF::try_new(
+            humped_nodes(),
+            LogLinearInterpolator::new(),
+            "c",
+            conv,
+            modifier,
+            None,
+            cal.clone(),
+        )
+        .unwrap();
+        let linear_zero = CurveDF::try_new(
+            humped_nodes(),
+            LinearZeroRateInterpolator::new(),
+            "c",
+            conv,
+            modifier,
+            None,
+            cal,
+        )
+        .unwrap();
+        let end = ndt(2027, 1, 1);
+        // the piecewise flat forwards rise then fall with the hump of the curve
+        let ll = log_linear.smoothness(&end, 7).unwrap();
+        assert_eq!(ll.oscillations, 1);
+        let lz = linear_zero.smoothness(&end, 7).unwrap();
+        // the sawtooth forwards of linear zero rates vary more than piecewise flat forwards
+        assert!(lz.total_variation > ll.total_variation);
+        assert!(ll.max_curvature > 0.0 && ll.roughness > 0.0);
+        assert!(log_linear.forward_profile(&end, 0).is_err());
+        assert!(log_linear.forward_profile(&ndt(2024, 1, 10), 7).is_err());
+    }
+}
@@ -0,0 +1,273 @@
+use crate::calendars::{Convention, MeetingCalendar, Modifier, NamedCal};
+use crate::curves::nodes::{Nodes, NodesTimestamp};
+use crate::curves::{evict, CurveDF, LogLinearInterpolator};
+use crate::dual::{get_variable_tags, Dual, MathFuncs};
+use crate::error::RateslibError;
+use crate::instruments::Instrument;
+use crate::periods::Curves;
+use crate::solver::{Solver, SolverResult, SolverSystem};
+use chrono::NaiveDateTime;
+use indexmap::IndexMap;
+
+/// A curve of policy rates which are constant between the dates on which they may change, such
+/// as the effective dates of the decisions of a central bank.
+///
+/// Each rate, in percent, is an overnight rate compounded daily on an Act360 basis, so that the
+/// discount factors at the change dates are interpolated log-linearly and every overnight
+/// forward within a step is the rate of the step. The rates are the variables of the curve,
+/// tagged by the `id` and the index of the step, and are calibrated to OIS quotes with
+/// [StepCurve::calibrate].
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepCurve {
+    pub(crate) curve: CurveDF<LogLinearInterpolator, NamedCal>,
+    /// The initial date, each change date and the final date.
+    pub(crate) dates: Vec<NaiveDateTime>,
+    pub(crate) rates: Vec<f64>,
+    pub(crate) tags: Vec<String>,
+}
+
+impl StepCurve {
+    /// Create a [StepCurve] from `initial` to `end` whose `rates` apply from the initial date and
+    /// from each of the `change_dates` strictly between them.
+    pub fn try_new(
+        id: &str,
+        initial: NaiveDateTime,
+        end: NaiveDateTime,
+        change_dates: &[NaiveDateTime],
+        rates: &[f64],
+        calendar: NamedCal,
+    ) -> Result<Self, RateslibError> {
+        let mut dates: Vec<NaiveDateTime> = change_dates
+            .iter()
+            .filter(|d| **d > initial && **d < end)
+            .copied()
+            .collect();
+        dates.sort();
+        dates.dedup();
+        dates.insert(0, initial);
+        dates.push(end);
+        if rates.len() != dates.len() - 1 {
+            return Err(RateslibError::curve(format!(
+                "A `StepCurve` with {} steps requires {} rates, got {}.",
+                dates.len() - 1,
+                dates.len() - 1,
+                rates.len()
+            )));
+        }
+        let tags = get_variable_tags(id, rates.len());
+        let nodes = step_nodes(&dates, rates, &tags);
+        let curve = CurveDF::try_new(
+            nodes,
+            LogLinearInterpolator::new(),
+            id,
+            Convention::Act360,
+            Modifier::ModF,
+            None,
+            calendar,
+        )?;
+        Ok(Self {
+            curve,
+            dates,
+            rates: rates.to_vec(),
+            tags,
+        })
+    }
+
+    /// Create a [StepCurve] whose rate may change at the effective date of each meeting of the
+    /// `meetings` calendar, with every step at the initial `rate`.
+    pub fn from_meetings(
+        id: &str,
+        initial: NaiveDateTime,
+        end: NaiveDateTime,
+        meetings: &MeetingCalendar,
+        rate: f64,
+        calendar: NamedCal,
+    ) -> Result<Self, RateslibError> {
+        let change_dates = meetings.effective_dates(&initial, &end);
+        let rates = vec![rate; change_dates.len() + 1];
+        Self::try_new(id, initial, end, &change_dates, &rates, calendar)
+    }
+
+    /// Return the curve of discount factors.
+    pub fn curve(&self) -> &CurveDF<LogLinearInterpolator, NamedCal> {
+        &self.curve
+    }
+
+    /// Return the initial date, each change date and the final date of the curve.
+    pub fn dates(&self) -> &[NaiveDateTime] {
+        &self.dates
+    }
+
+    pub fn rates(&self) -> &[f64] {
+        &self.rates
+    }
+
+    /// Return the rate in effect on `date`, which is the first or last rate outside the curve.
+    pub fn rate_at(&self, date: &NaiveDateTime) -> f64 {
+        let step = self.dates[1..self.dates.len() - 1]
+            .iter()
+            .take_while(|d| *d <= date)
+            .count();
+        self.rates[step]
+    }
+
+    /// Overwrite the rates of the curve.
+    pub fn set_rates(&mut self, rates: &[f64]) -> Result<(), RateslibError> {
+        if rates.len() != self.rates.len() {
+            return Err(RateslibError::curve(format!(
+                "A `StepCurve` with {} steps requires {} rates, got {}.",
+                self.rates.len(),
+                self.rates.len(),
+                rates.len()
+            )));
+        }
+        self.rates = rates.to_vec();
+        self.curve.nodes = NodesTimestamp::from(step_nodes(&self.dates, rates, &self.tags));
+        evict(self.curve.address());
+        Ok(())
+    }
+
+    /// Calibrate the rates to the `quotes`, in percent, of the `instruments`, forecast and
+    /// discounted on the curve, with the default [Solver].
+    pub fn calibrate<I: Instrument>(
+        &mut self,
+        instruments: &[I],
+        quotes: &[f64],
+    ) -> Result<SolverResult, RateslibError> {
+        if instruments.len() != quotes.len() {
+            return Err(RateslibError::curve(
+                "Each calibrating instrument of a `StepCurve` requires a quote.",
+            ));
+        }
+        let mut system = StepSystem {
+            curve: self,
+            instruments,
+        };
+        Solver::try_new_default(quotes.to_vec())?.iterate(&mut system)
+    }
+}
+
+/// Return the discount factors at the `dates` of the steps of `rates`, with sensitivity to the
+/// `tags` of the rates.
+fn step_nodes(dates: &[NaiveDateTime], rates: &[f64], tags: &[String]) -> Nodes {
+    let mut df = Dual::new(1.0, vec![]);
+    let mut nodes = IndexMap::from_iter([(dates[0], df.clone())]);
+    for ((w, rate), tag) in dates.windows(2).zip(rates.iter()).zip(tags.iter()) {
+        let days = (w[1] - w[0]).num_days() as f64;
+        let daily = Dual::new(*rate, vec![tag.clone()]) / 36000.0 + 1.0;
+        df = df * (daily.log() * -days).exp();
+        nodes.insert(w[1], df.clone());
+    }
+    Nodes::Dual(nodes)
+}
+
+struct StepSystem<'a, I: Instrument> {
+    curve: &'a mut StepCurve,
+    instruments: &'a [I],
+}
+
+impl<I: Instrument> SolverSystem for StepSystem<'_, I> {
+    fn variable_tags(&self) -> Vec<String> {
+        self.curve.tags.clone()
+    }
+
+    fn variables(&self) -> Vec<f64> {
+        self.curve.rates.clone()
+    }
+
+    fn set_variables(&mut self, values: &[f64]) -> Result<(), RateslibError> {
+        self.curve.set_rates(values)
+    }
+
+    fn rates(&self) -> Result<Vec<Dual>, RateslibError> {
+        let curves = Curves::new(Some(&self.curve.curve), Some(&self.curve.curve));
+        self.instruments
+            .iter()
+            .map(|i| Ok(Dual::from(&i.rate(&curves)?)))
+            .collect()
+    }
+
+    fn is_valid(&self) -> bool {
+        self.curve.rates.iter().all(|r| *r > -100.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::{ndt, RollDay};
+    use crate::curves::PricingCurve;
+    use crate::instruments::Irs;
+    use crate::scheduling::{Frequency, Schedule};
+
+    fn ois(end: NaiveDateTime, cal: &NamedCal) -> Irs {
+        let schedule = Schedule::try_new(
+            ndt(2025, 1, 2),
+            end,
+            Frequency::Zero {},
+            RollDay::Unspecified {},
+            Modifier::Act,
+            cal,
+            0,
+        )
+        .unwrap();
+        let conv = Convention::Act360;
+        Irs::try_new(schedule, 4.0, 1e6, conv, conv, 0.0, cal).unwrap()
+    }
+
+    #[test]
+    fn test_step_curve_is_step_constant() {
+        let cal = NamedCal::try_new("fed").unwrap();
+        let dates = [ndt(2025, 3, 20)];
+        let curve = StepCurve::try_new(
+            "sofr",
+            ndt(2025, 1, 2),
+            ndt(2025, 7, 1),
+            &dates,
+            &[4.3, 4.0],
+            cal,
+        )
+        .unwrap();
+        for (start, rate) in [(ndt(2025, 2, 3), 4.3), (ndt(2025, 3, 20), 4.0)] {
+            let end = start + chrono::Days::new(1);
+            let forward = f64::from(curve.curve().rate(&start, &end).unwrap());
+            assert!((forward - rate).abs() < 1e-9);
+        }
+        assert_eq!(curve.rate_at(&ndt(2025, 3, 19)), 4.3);
+        assert_eq!(curve.rate_at(&ndt(2025, 3, 20)), 4.0);
+        assert_eq!(curve.rate_at(&ndt(2026, 1, 1)), 4.0);
+        let cal = NamedCal::try_new("fed").unwrap();
+        assert!(
+            StepCurve::try_new("x", ndt(2025, 1, 2), ndt(2025, 7, 1), &dates, &[4.0], cal).is_err()
+        );
+    }
+
+    #[test]
+    fn test_step_curve_calibration() {
+        let cal = NamedCal::try_new("fed").unwrap();
+        let fomc = MeetingCalendar::try_new("fomc").unwrap();
+        let mut curve =
+            StepCurve::from_meetings("sofr", ndt(2025, 1, 2), ndt(2025, 7, 1), &fomc, 4.0, cal)
+                .unwrap();
+        assert_eq!(curve.rates().len(), 5);
+        // one swap maturing within each step
+        let cal = NamedCal::try_new("fed").unwrap();
+        let ends = [
+            ndt(2025, 1, 28),
+            ndt(2025, 3, 3),
+            ndt(2025, 4, 15),
+            ndt(2025, 6, 2),
+            ndt(2025, 7, 1),
+        ];
+        let swaps: Vec<Irs> = ends.iter().map(|e| ois(*e, &cal)).collect();
+        let quotes = [4.33, 4.30, 4.22, 4.12, 4.05];
+        let result = curve.calibrate(&swaps, &quotes).unwrap();
+        assert!(result.status.is_success());
+        let curves = Curves::new(Some(curve.curve()), Some(curve.curve()));
+        for (swap, quote) in swaps.iter().zip(quotes) {
+            assert!((f64::from(&swap.rate(&curves).unwrap()) - quote).abs() < 1e-6);
+        }
+        // the implied policy path declines with the quotes
+        assert!(curve.rates().windows(2).all(|w| w[1] < w[0]));
+    }
+}
@@ -0,0 +1,187 @@
+use crate::dual::enums::Number;
+use crate::dual::reduction::Reduction;
+use crate::error::RateslibError;
+use ndarray::Array1;
+
+/// The index returned by an arg reduction over values of equal real part.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum TiePolicy {
+    /// The first of the equal values.
+    #[default]
+    First,
+    /// The last of the equal values.
+    Last,
+}
+
+/// Reductions and elementwise comparisons of arrays of [Number], such as the payoffs of the
+/// paths of a simulation, which retain the gradients of the selected values.
+///
+/// Values are compared by their real parts. Where two values compare equal an elementwise
+/// operation selects the value of `self`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use rateslib::dual::{ArrayNumber, Dual, Number};
+/// # use ndarray::Array1;
+/// let s = Number::Dual(Dual::new(105.0, vec!["s".to_string()]));
+/// let strikes = Array1::from_vec(vec![Number::F64(100.0), Number::F64(110.0)]);
+/// let payoffs = strikes.mapv(|k| &s - &k).clamp(Some(&Number::F64(0.0)), None);
+/// assert_eq!(f64::from(&payoffs[0]), 5.0);
+/// assert_eq!(payoffs[1], Number::F64(0.0));
+/// ```
+pub trait ArrayNumber {
+    /// Return the elementwise larger of `self` and `other`, which must have the same length.
+    fn maximum(&self, other: &Self) -> Result<Array1<Number>, RateslibError>;
+
+    /// Return the elementwise smaller of `self` and `other`, which must have the same length.
+    fn minimum(&self, other: &Self) -> Result<Array1<Number>, RateslibError>;
+
+    /// Return each value bounded below by `lower` and above by `upper`, where given.
+    fn clamp(&self, lower: Option<&Number>, upper: Option<&Number>) -> Array1<Number>;
+
+    /// Return the index of the largest value, or `None` if empty or every value is NaN.
+    fn argmax(&self, tie: TiePolicy) -> Option<usize>;
+
+    /// Return the index of the smallest value, or `None` if empty or every value is NaN.
+    fn argmin(&self, tie: TiePolicy) -> Option<usize>;
+
+    /// Return the sum of the values accumulated in the order of the `reduction`.
+    fn sum_by(&self, reduction: Reduction) -> Result<Number, RateslibError>;
+
+    /// Return the product of the values, which is one if empty, or raise on mixed first and
+    /// second order dual numbers.
+    fn try_product(&self) -> Result<Number, RateslibError>;
+}
+
+impl ArrayNumber for Array1<Number> {
+    fn maximum(&self, other: &Self) -> Result<Array1<Number>, RateslibError> {
+        zip_select(self, other, |a, b| a >= b)
+    }
+
+    fn minimum(&self, other: &Self) -> Result<Array1<Number>, RateslibError> {
+        zip_select(self, other, |a, b| a <= b)
+    }
+
+    fn clamp(&self, lower: Option<&Number>, upper: Option<&Number>) -> Array1<Number> {
+        self.mapv(|v| match (lower, upper) {
+            (Some(l), _) if f64::from(&v) < f64::from(l) => l.clone(),
+            (_, Some(u)) if f64::from(&v) > f64::from(u) => u.clone(),
+            _ => v,
+        })
+    }
+
+    fn argmax(&self, tie: TiePolicy) -> Option<usize> {
+        arg_select(self, tie, |a, b| a > b)
+    }
+
+    fn argmin(&self, tie: TiePolicy) -> Option<usize> {
+        arg_select(self, tie, |a, b| a < b)
+    }
+
+    fn sum_by(&self, reduction: Reduction) -> Result<Number, RateslibError> {
+        reduction.sum(self.as_slice().unwrap_or(&self.to_vec()))
+    }
+
+    fn try_product(&self) -> Result<Number, RateslibError> {
+        self.iter()
+            .try_fold(Number::F64(1.0), |acc, v| acc.try_mul(v))
+    }
+}
+
+/// Return the value of `a` where `keep_a` of the real parts, otherwise the value of `b`.
+fn zip_select(
+    a: &Array1<Number>,
+    b: &Array1<Number>,
+    keep_a: impl Fn(f64, f64) -> bool,
+) -> Result<Array1<Number>, RateslibError> {
+    if a.len() != b.len() {
+        return Err(RateslibError::value(format!(
+            "Arrays of lengths {} and {} cannot be compared elementwise.",
+            a.len(),
+            b.len()
+        )));
+    }
+    Ok(Array1::from_iter(a.iter().zip(b.iter()).map(
+        |(x, y)| match keep_a(f64::from(x), f64::from(y)) {
+            true => x.clone(),
+            false => y.clone(),
+        },
+    )))
+}
+
+/// Return the index of the value whose real part is `better` than every other, ignoring NaN.
+fn arg_select(
+    a: &Array1<Number>,
+    tie: TiePolicy,
+    better: impl Fn(f64, f64) -> bool,
+) -> Option<usize> {
+    let mut best: Option<(usize, f64)> = None;
+    for (i, v) in a.iter().map(f64::from).enumerate() {
+        if v.is_nan() {
+            continue;
+        }
+        best = match best {
+            None => Some((i, v)),
+            Some((_, b)) if better(v, b) || (tie == TiePolicy::Last && v == b) => Some((i, v)),
+            keep => keep,
+        };
+    }
+    best.map(|(i, _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dual::{Dual, Dual2, Gradient1};
+
+    fn values() -> Array1<Number> {
+        Array1::from_vec(vec![
+            Number::Dual(Dual::new(1.0, vec!["a".to_string()])),
+            Number::F64(3.0),
+            Number::F64(f64::NAN),
+            Number::Dual(Dual::new(3.0, vec!["b".to_string()])),
+            Number::F64(-2.0),
+        ])
+    }
+
+    #[test]
+    fn test_elementwise_and_arg_reductions() {
+        let v = values();
+        let floor = Array1::from_elem(5, Number::F64(2.0));
+        let m = v.maximum(&floor).unwrap();
+        assert_eq!(m[0], Number::F64(2.0));
+        assert_eq!(m[3], v[3]);
+        assert_eq!(v.minimum(&floor).unwrap()[0], v[0]);
+        assert!(v.maximum(&Array1::from_elem(2, Number::F64(0.0))).is_err());
+        let c = v.clamp(Some(&Number::F64(0.0)), Some(&Number::F64(2.0)));
+        assert_eq!(c[1], Number::F64(2.0));
+        assert_eq!(c[4], Number::F64(0.0));
+        assert_eq!(c[0], v[0]);
+        assert_eq!(v.argmax(TiePolicy::First), Some(1));
+        assert_eq!(v.argmax(TiePolicy::Last), Some(3));
+        assert_eq!(v.argmin(TiePolicy::First), Some(4));
+        assert_eq!(
+            Array1::<Number>::from_vec(vec![]).argmax(TiePolicy::First),
+            None
+        );
+    }
+
+    #[test]
+    fn test_sum_and_product() {
+        let v = values().slice_move(ndarray::s![..2]);
+        let Number::Dual(sum) = v.sum_by(Reduction::Compensated).unwrap() else {
+            panic!("the sum of dual numbers is a dual number")
+        };
+        assert_eq!(sum.real, 4.0);
+        let Number::Dual(product) = v.try_product().unwrap() else {
+            panic!("the product of dual numbers is a dual number")
+        };
+        assert_eq!(product.gradient1(vec!["a".to_string()])[0], 3.0);
+        let mixed = Array1::from_vec(vec![
+            Number::Dual(Dual::new(1.0, vec![])),
+            Number::Dual2(Dual2::new(1.0, vec![])),
+        ]);
+        assert!(mixed.try_product().is_err());
+    }
+}
@@ -0,0 +1,151 @@
+use crate::dual::dual::{Dual, Gradient1};
+use crate::dual::enums::Number;
+use crate::dual::get_variable_tags;
+use crate::error::RateslibError;
+use indexmap::IndexSet;
+use std::sync::Arc;
+
+/// The prefix of the local variables by which each segment of a checkpointed computation is
+/// differentiated, which must not prefix the variables of its inputs.
+const CHECKPOINT_PREFIX: &str = "__checkpoint";
+
+/// Evaluate `steps` applications of a `step` function to an `initial` state, such as the time
+/// steps of a Monte Carlo path or the layers of a lattice, with first order gradients
+/// recombined across segments of `segment` steps.
+///
+/// Forward mode gradients with respect to many variables, such as the nodes of several curves,
+/// make every intermediate value of a deep computation as large as its variables. Each segment
+/// is instead evaluated with the state differentiated only with respect to itself, and the
+/// Jacobian of the segment is chained onto the gradients of the state at its start. Each step
+/// therefore carries gradients to the state and to any variables introduced by `step` itself,
+/// at the cost of one recombination per segment.
+///
+/// `step` is called with the index of the step and the current state, returning the next
+/// state of the same length. The state may be of [f64] or [Dual], but not
+/// [Dual2](crate::dual::Dual2).
+pub fn checkpointed<F>(
+    initial: Vec<Number>,
+    steps: usize,
+    segment: usize,
+    mut step: F,
+) -> Result<Vec<Number>, RateslibError>
+where
+    F: FnMut(usize, &[Number]) -> Result<Vec<Number>, RateslibError>,
+{
+    if segment == 0 {
+        return Err(RateslibError::value(
+            "`segment` of a checkpointed computation must be positive.",
+        ));
+    }
+    let n = initial.len();
+    let tags = get_variable_tags(CHECKPOINT_PREFIX, n);
+    let mut state = initial;
+    let mut k = 0;
+    while k < steps {
+        check_first_order(&state)?;
+        let mut local: Vec<Number> = state
+            .iter()
+            .zip(tags.iter())
+            .map(|(s, tag)| Number::Dual(Dual::new(f64::from(s), vec![tag.clone()])))
+            .collect();
+        let end = steps.min(k + segment);
+        for i in k..end {
+            local = step(i, &local)?;
+            if local.len() != n {
+                return Err(RateslibError::value(format!(
+                    "`step` {} of a checkpointed computation returned a state of length {}, \
+                     expected {}.",
+                    i,
+                    local.len(),
+                    n
+                )));
+            }
+        }
+        check_first_order(&local)?;
+        state = local
+            .iter()
+            .map(|out| recombine(out, &state, &tags))
+            .collect::<Result<_, RateslibError>>()?;
+        k = end;
+    }
+    Ok(state)
+}
+
+fn check_first_order(state: &[Number]) -> Result<(), RateslibError> {
+    match state.iter().any(|s| matches!(s, Number::Dual2(_))) {
+        true => Err(RateslibError::value(
+            "A checkpointed computation supports only first order gradients; `Dual2` found.",
+        )),
+        false => Ok(()),
+    }
+}
+
+/// Return the output `out` of a segment with its gradients to the local `tags` of the state
+/// replaced by the gradients of the state at the start of the segment, `start`.
+fn recombine(out: &Number, start: &[Number], tags: &[String]) -> Result<Number, RateslibError> {
+    let Number::Dual(d) = out else {
+        return Ok(out.clone());
+    };
+    let local: IndexSet<&String> = tags.iter().collect();
+    let (vars, dual): (Vec<String>, Vec<f64>) = d
+        .vars
+        .iter()
+        .zip(d.dual.iter())
+        .filter(|(v, _)| !local.contains(v))
+        .map(|(v, x)| (v.clone(), *x))
+        .unzip();
+    let mut result = Number::Dual(Dual::try_new(d.real, vars, dual)?);
+    let jacobian = d.gradient1(tags.to_vec());
+    for (s, c) in start.iter().zip(jacobian.iter()) {
+        if let Number::Dual(s) = s {
+            if *c != 0.0 {
+                let gradient = Dual {
+                    real: 0.0,
+                    vars: Arc::clone(&s.vars),
+                    dual: &s.dual * *c,
+                };
+                result = result + Number::Dual(gradient);
+            }
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dual::Dual2;
+
+    #[test]
+    fn test_checkpointed_compounding() {
+        let x0 = Number::Dual(Dual::new(100.0, vec!["x0".to_string()]));
+        let r = Number::Dual(Dual::new(0.001, vec!["r".to_string()]));
+        let steps = 1000;
+        let result = checkpointed(vec![x0, Number::F64(0.0)], steps, 64, |_, s| {
+            Ok(vec![&s[0] * (&r + 1.0), &s[1] + &s[0] * 0.001])
+        })
+        .unwrap();
+        let Number::Dual(x) = &result[0] else {
+            panic!("the compounded value is a dual number")
+        };
+        let g = 1.001_f64.powi(steps as i32);
+        assert!((x.real - 100.0 * g).abs() < 1e-9);
+        let grad = x.gradient1(vec!["x0".to_string(), "r".to_string()]);
+        assert!((grad[0] - g).abs() < 1e-10);
+        assert!((grad[1] - 1000.0 * 100.0 * 1.001_f64.powi(999)).abs() < 1e-6);
+        assert!(x.vars.iter().all(|v| !v.starts_with(CHECKPOINT_PREFIX)));
+        // the accumulated state depends on the initial value only through the compounded state
+        let Number::Dual(total) = &result[1] else {
+            panic!("the accumulated value is a dual number")
+        };
+        assert!((total.gradient1(vec!["x0".to_string()])[0] - (g - 1.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_checkpointed_raises() {
+        let x = Number::Dual2(Dual2::new(1.0, vec!["x".to_string()]));
+        assert!(checkpointed(vec![x], 10, 4, |_, s| Ok(s.to_vec())).is_err());
+        assert!(checkpointed(vec![Number::F64(1.0)], 10, 0, |_, s| Ok(s.to_vec())).is_err());
+        assert!(checkpointed(vec![Number::F64(1.0)], 10, 4, |_, _| Ok(vec![])).is_err());
+    }
+}
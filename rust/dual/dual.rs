@@ -1,31 +1,41 @@
+pub use crate::dual::dual_ops::approx::{ApproxEq, ApproxMismatch};
 pub use crate::dual::dual_ops::convert::{set_order, set_order_clone};
+pub use crate::dual::dual_ops::display::{TopK, DISPLAY_TOP_K};
+pub use crate::dual::dual_ops::fast_math::FastMathFuncs;
 pub use crate::dual::dual_ops::math_funcs::MathFuncs;
 pub use crate::dual::dual_ops::numeric_ops::NumberOps;
+pub use crate::dual::dual_ops::smooth::SmoothFuncs;
+use crate::dual::lazy::LazyArray2;
+use crate::dual::ordering::{vars_ordering, VarsOrdering};
+use crate::dual::pool::zeros2;
+use crate::dual::small::SmallArray1;
+use crate::error::RateslibError;
+use crate::profiling::{record, Counter};
 use indexmap::set::IndexSet;
-use ndarray::{Array, Array1, Array2, Axis};
-use pyo3::exceptions::PyValueError;
-use pyo3::{pyclass, PyErr};
+use ndarray::{Array, Array1, Array2, ArrayView1, Axis};
+#[cfg(feature = "python")]
+use pyo3::pyclass;
 use serde::{Deserialize, Serialize};
 use std::cmp::PartialEq;
 use std::sync::Arc;
 
 /// A dual number data type supporting first order derivatives.
-#[pyclass(module = "rateslib.rs")]
+#[cfg_attr(feature = "python", pyclass(module = "rateslib.rs"))]
 #[derive(Clone, Default, Debug, Deserialize, Serialize)]
 pub struct Dual {
     pub(crate) real: f64,
     pub(crate) vars: Arc<IndexSet<String>>,
-    pub(crate) dual: Array1<f64>,
+    pub(crate) dual: SmallArray1,
 }
 
 /// A dual number data type supporting second order derivatives.
-#[pyclass(module = "rateslib.rs")]
+#[cfg_attr(feature = "python", pyclass(module = "rateslib.rs"))]
 #[derive(Clone, Default, Debug, Serialize, Deserialize)]
 pub struct Dual2 {
     pub(crate) real: f64,
     pub(crate) vars: Arc<IndexSet<String>>,
-    pub(crate) dual: Array1<f64>,
-    pub(crate) dual2: Array2<f64>,
+    pub(crate) dual: SmallArray1,
+    pub(crate) dual2: LazyArray2,
 }
 
 /// The state of the `vars` measured between two dual number type structs; a LHS relative to a RHS.
@@ -105,6 +115,14 @@ where
         Self: Sized,
     {
         let state_ = state.unwrap_or_else(|| self.vars_cmp(other.vars()));
+        if vars_ordering() == VarsOrdering::Sorted
+            && !matches!(
+                state_,
+                VarsRelationship::ArcEquivalent | VarsRelationship::ValueEquivalent
+            )
+        {
+            return self.to_sorted_vars(other);
+        }
         match state_ {
             VarsRelationship::ArcEquivalent => (self.clone(), other.clone()),
             VarsRelationship::ValueEquivalent => {
@@ -129,6 +147,7 @@ where
     where
         Self: Sized,
     {
+        record(Counter::UnionVars);
         let comb_vars = Arc::new(IndexSet::from_iter(
             self.vars().union(other.vars()).cloned(),
         ));
@@ -138,6 +157,26 @@ where
         )
     }
 
+    /// Construct a tuple of 2 `Self` types whose `vars` are linked by the sorted union of
+    /// their own variables, reusing the Arc pointer of either if already sorted.
+    fn to_sorted_vars(&self, other: &Self) -> (Self, Self)
+    where
+        Self: Sized,
+    {
+        let mut sorted = IndexSet::from_iter(self.vars().union(other.vars()).cloned());
+        sorted.sort();
+        let is_sorted = |v: &Arc<IndexSet<String>>| v.iter().eq(sorted.iter());
+        let arc = if is_sorted(self.vars()) {
+            Arc::clone(self.vars())
+        } else if is_sorted(other.vars()) {
+            Arc::clone(other.vars())
+        } else {
+            record(Counter::UnionVars);
+            Arc::new(sorted)
+        };
+        (self.to_new_vars(&arc, None), other.to_new_vars(&arc, None))
+    }
+
     /// Compare if two `Dual` structs share the same `vars` by Arc pointer equivalence.
     ///
     /// # Examples
@@ -176,17 +215,16 @@ impl Vars for Dual {
         state: Option<VarsRelationship>,
     ) -> Self {
         let match_val = state.unwrap_or_else(|| self.vars_cmp(arc_vars));
-        let dual_: Array1<f64> = match match_val {
+        let dual_ = match match_val {
             VarsRelationship::ArcEquivalent | VarsRelationship::ValueEquivalent => {
                 self.dual.clone()
             }
-            _ => {
-                let lookup_or_zero = |v| match self.vars.get_index_of(v) {
+            _ => SmallArray1::from_fn(arc_vars.len(), |i| {
+                match self.vars.get_index_of(&arc_vars[i]) {
                     Some(idx) => self.dual[idx],
                     None => 0.0_f64,
-                };
-                Array1::from_vec(arc_vars.iter().map(lookup_or_zero).collect())
-            }
+                }
+            }),
         };
         Self {
             real: self.real,
@@ -218,38 +256,31 @@ impl Vars for Dual2 {
         arc_vars: &Arc<IndexSet<String>>,
         state: Option<VarsRelationship>,
     ) -> Self {
-        let dual_: Array1<f64>;
-        let mut dual2_: Array2<f64> = Array2::zeros((arc_vars.len(), arc_vars.len()));
+        let mut dual_: SmallArray1;
+        let dual2_: LazyArray2;
         let match_val = state.unwrap_or_else(|| self.vars_cmp(arc_vars));
         match match_val {
             VarsRelationship::ArcEquivalent | VarsRelationship::ValueEquivalent => {
                 dual_ = self.dual.clone();
-                dual2_.clone_from(&self.dual2);
+                dual2_ = self.dual2.clone();
             }
             _ => {
-                let lookup_or_zero = |v| match self.vars.get_index_of(v) {
-                    Some(idx) => self.dual[idx],
-                    None => 0.0_f64,
-                };
-                dual_ = Array1::from_vec(arc_vars.iter().map(lookup_or_zero).collect());
-
+                dual_ = SmallArray1::zeros(arc_vars.len());
+                let mut dual2: Array2<f64> = zeros2(arc_vars.len());
+                let self_dual2: &Array2<f64> = &self.dual2;
                 let indices: Vec<Option<usize>> =
                     arc_vars.iter().map(|x| self.vars.get_index_of(x)).collect();
                 for (i, row_index) in indices.iter().enumerate() {
-                    match row_index {
-                        Some(row_value) => {
-                            for (j, col_index) in indices.iter().enumerate() {
-                                match col_index {
-                                    Some(col_value) => {
-                                        dual2_[[i, j]] = self.dual2[[*row_value, *col_value]]
-                                    }
-                                    None => {}
-                                }
+                    if let Some(row_value) = row_index {
+                        dual_[i] = self.dual[*row_value];
+                        for (j, col_index) in indices.iter().enumerate() {
+                            if let Some(col_value) = col_index {
+                                dual2[[i, j]] = self_dual2[[*row_value, *col_value]]
                             }
                         }
-                        None => {}
                     }
                 }
+                dual2_ = dual2.into();
             }
         }
         Self {
@@ -266,6 +297,11 @@ pub trait Gradient1: Vars {
     /// Get a reference to the Array containing the first order gradients.
     fn dual(&self) -> &Array1<f64>;
 
+    /// Get a view of the first order gradients, which does not copy gradients stored inline.
+    fn dual_view(&self) -> ArrayView1<'_, f64> {
+        self.dual().view()
+    }
+
     /// Return a set of first order gradients ordered by the given vector.
     ///
     /// Duplicate `vars` are dropped before parsing.
@@ -274,7 +310,7 @@ pub trait Gradient1: Vars {
         let state = self.vars_cmp(&arc_vars);
         match state {
             VarsRelationship::ArcEquivalent | VarsRelationship::ValueEquivalent => {
-                self.dual().clone()
+                self.dual_view().to_owned()
             }
             _ => {
                 let mut dual_ = Array1::<f64>::zeros(arc_vars.len());
@@ -284,24 +320,66 @@ pub trait Gradient1: Vars {
                     .enumerate()
                 {
                     if let Some(value) = index {
-                        dual_[i] = self.dual()[value]
+                        dual_[i] = self.dual_view()[value]
                     }
                 }
                 dual_
             }
         }
     }
+
+    /// Return the `k` variables with the largest absolute first order gradients, in
+    /// descending order of magnitude.
+    fn top_sensitivities(&self, k: usize) -> Vec<(String, f64)> {
+        let mut pairs: Vec<(String, f64)> = self
+            .vars()
+            .iter()
+            .cloned()
+            .zip(self.dual_view().iter().cloned())
+            .collect();
+        pairs.sort_by(|a, b| b.1.abs().total_cmp(&a.1.abs()));
+        pairs.truncate(k);
+        pairs
+    }
+
+    /// Return the Euclidean norm of the first order gradients.
+    fn grad_norm(&self) -> f64 {
+        self.dual_view().dot(&self.dual_view()).sqrt()
+    }
+
+    /// Return the largest absolute first order gradient, or zero without `vars`.
+    fn max_abs_sensitivity(&self) -> f64 {
+        self.dual_view().iter().fold(0.0, |m, x| m.max(x.abs()))
+    }
+
+    /// Return the `vars` with a non-zero first order gradient.
+    fn nonzero_vars(&self) -> Vec<String> {
+        self.vars()
+            .iter()
+            .zip(self.dual_view().iter())
+            .filter(|(_, x)| **x != 0.0)
+            .map(|(v, _)| v.clone())
+            .collect()
+    }
 }
 
 impl Gradient1 for Dual {
     fn dual(&self) -> &Array1<f64> {
-        &self.dual
+        self.dual.as_array()
+    }
+
+    fn dual_view(&self) -> ArrayView1<'_, f64> {
+        self.dual.view()
     }
 }
 
 impl Gradient1 for Dual2 {
     fn dual(&self) -> &Array1<f64> {
-        &self.dual
+        self.dual.as_array()
+    }
+
+    fn dual_view(&self) -> ArrayView1<'_, f64> {
+        self.dual.view()
     }
 }
 
@@ -313,6 +391,7 @@ pub trait Gradient2: Gradient1 {
     /// Return a set of first order gradients ordered by the given vector.
     ///
     /// Duplicate `vars` are dropped before parsing.
+    #[allow(clippy::single_match)]
     fn gradient2(&self, vars: Vec<String>) -> Array2<f64> {
         let arc_vars = Arc::new(IndexSet::from_iter(vars));
         let state = self.vars_cmp(&arc_vars);
@@ -344,6 +423,7 @@ pub trait Gradient2: Gradient1 {
         }
     }
 
+    #[allow(clippy::single_match)]
     fn gradient1_manifold(&self, vars: Vec<String>) -> Array1<Dual2> {
         let indices: Vec<Option<usize>> =
             vars.iter().map(|x| self.vars().get_index_of(x)).collect();
@@ -361,10 +441,10 @@ pub trait Gradient2: Gradient1 {
                         }
                     }
                     grad[i] = Dual2 {
-                        real: self.dual()[*i_val],
+                        real: self.dual_view()[*i_val],
                         vars: Arc::clone(&default_zero.vars),
-                        dual2: Array2::zeros((vars.len(), vars.len())),
-                        dual,
+                        dual2: LazyArray2::zeros(vars.len()),
+                        dual: dual.into(),
                     };
                 }
                 None => grad[i] = default_zero.clone(),
@@ -398,7 +478,7 @@ impl Dual {
         let unique_vars_ = Arc::new(IndexSet::from_iter(vars));
         Self {
             real,
-            dual: Array1::ones(unique_vars_.len()),
+            dual: SmallArray1::ones(unique_vars_.len()),
             vars: unique_vars_,
         }
     }
@@ -422,15 +502,15 @@ impl Dual {
     /// let x = Dual::try_new(2.5, vec!["x".to_string()], vec![4.2]).unwrap();
     /// // x: <Dual: 2.5, (x), [4.2]>
     /// ```
-    pub fn try_new(real: f64, vars: Vec<String>, dual: Vec<f64>) -> Result<Self, PyErr> {
+    pub fn try_new(real: f64, vars: Vec<String>, dual: Vec<f64>) -> Result<Self, RateslibError> {
         let unique_vars_ = Arc::new(IndexSet::from_iter(vars));
         let dual_ = if dual.is_empty() {
-            Array1::ones(unique_vars_.len())
+            SmallArray1::ones(unique_vars_.len())
         } else {
-            Array1::from_vec(dual)
+            SmallArray1::from(dual)
         };
         if unique_vars_.len() != dual_.len() {
-            Err(PyValueError::new_err(
+            Err(RateslibError::value(
                 "`vars` and `dual` must have the same length.",
             ))
         } else {
@@ -490,7 +570,7 @@ impl Dual {
         real: f64,
         vars: Vec<String>,
         dual: Vec<f64>,
-    ) -> Result<Self, PyErr> {
+    ) -> Result<Self, RateslibError> {
         let new = Self::try_new(real, vars, dual)?;
         Ok(new.to_new_vars(other.vars(), None))
     }
@@ -502,7 +582,7 @@ impl Dual {
         Dual {
             real,
             vars: Arc::clone(other.vars()),
-            dual,
+            dual: dual.into(),
         }
     }
 
@@ -532,8 +612,8 @@ impl Dual2 {
         let unique_vars_ = Arc::new(IndexSet::from_iter(vars));
         Self {
             real,
-            dual: Array1::ones(unique_vars_.len()),
-            dual2: Array2::zeros((unique_vars_.len(), unique_vars_.len())),
+            dual: SmallArray1::ones(unique_vars_.len()),
+            dual2: LazyArray2::zeros(unique_vars_.len()),
             vars: unique_vars_,
         }
     }
@@ -562,15 +642,15 @@ impl Dual2 {
         vars: Vec<String>,
         dual: Vec<f64>,
         dual2: Vec<f64>,
-    ) -> Result<Self, PyErr> {
+    ) -> Result<Self, RateslibError> {
         let unique_vars_ = Arc::new(IndexSet::from_iter(vars));
         let dual_ = if dual.is_empty() {
-            Array1::ones(unique_vars_.len())
+            SmallArray1::ones(unique_vars_.len())
         } else {
-            Array1::from_vec(dual)
+            SmallArray1::from(dual)
         };
         if unique_vars_.len() != dual_.len() {
-            return Err(PyValueError::new_err(
+            return Err(RateslibError::value(
                 "`vars` and `dual` must have the same length.",
             ));
         }
@@ -579,7 +659,7 @@ impl Dual2 {
             Array2::zeros((unique_vars_.len(), unique_vars_.len()))
         } else {
             if dual2.len() != (unique_vars_.len() * unique_vars_.len()) {
-                return Err(PyValueError::new_err(
+                return Err(RateslibError::value(
                     "`vars` and `dual2` must have compatible lengths.",
                 ));
             }
@@ -591,7 +671,7 @@ impl Dual2 {
             real,
             vars: unique_vars_,
             dual: dual_,
-            dual2: dual2_,
+            dual2: dual2_.into(),
         })
     }
 
@@ -643,7 +723,7 @@ impl Dual2 {
         vars: Vec<String>,
         dual: Vec<f64>,
         dual2: Vec<f64>,
-    ) -> Result<Self, PyErr> {
+    ) -> Result<Self, RateslibError> {
         let new = Self::try_new(real, vars, dual, dual2)?;
         Ok(new.to_new_vars(other.vars(), None))
     }
@@ -662,8 +742,8 @@ impl Dual2 {
         Dual2 {
             real,
             vars: Arc::clone(other.vars()),
-            dual,
-            dual2,
+            dual: dual.into(),
+            dual2: dual2.into(),
         }
     }
 
@@ -711,6 +791,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::bool_comparison)]
     fn ptr_eq() {
         let x = Dual::new(1.0, vec!["a".to_string()]);
         let y = Dual::new(1.0, vec!["a".to_string()]);
@@ -779,7 +860,7 @@ mod tests {
     #[test]
     fn unitialised_derivs_eq_1() {
         let d = Dual::new(2.3, Vec::from([String::from("a"), String::from("b")]));
-        for (_, val) in d.dual.indexed_iter() {
+        for val in d.dual.iter() {
             assert!(*val == 1.0)
         }
     }
@@ -1143,7 +1224,7 @@ mod tests {
     #[test]
     fn uninitialised_derivs_eq_one2() {
         let d = Dual2::new(2.3, Vec::from([String::from("a"), String::from("b")]));
-        for (_, val) in d.dual.indexed_iter() {
+        for val in d.dual.iter() {
             assert!(*val == 1.0)
         }
     }
@@ -1189,4 +1270,20 @@ mod tests {
     //     let b = Dual2::new(3.0, Vec::new(), Vec::new(), Vec::new());
     //     a + b
     // }
+
+    #[test]
+    fn gradient_statistics() {
+        let x = Dual::try_new(
+            1.0,
+            vec!["x".to_string(), "y".to_string(), "z".to_string()],
+            vec![3.0, 0.0, -4.0],
+        )
+        .unwrap();
+        assert_eq!(x.grad_norm(), 5.0);
+        assert_eq!(x.max_abs_sensitivity(), 4.0);
+        assert_eq!(x.nonzero_vars(), vec!["x".to_string(), "z".to_string()]);
+        let c = Dual2::new(1.0, vec![]);
+        assert_eq!((c.grad_norm(), c.max_abs_sensitivity()), (0.0, 0.0));
+        assert!(c.nonzero_vars().is_empty());
+    }
 }
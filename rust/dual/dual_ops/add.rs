@@ -1,5 +1,6 @@
 use crate::dual::dual::{Dual, Dual2, Vars, VarsRelationship};
 use crate::dual::enums::Number;
+use crate::dual::pool::recycle;
 use auto_ops::{impl_op_ex, impl_op_ex_commutative};
 use std::sync::Arc;
 
@@ -18,7 +19,9 @@ impl_op_ex!(+ |a: &Dual, b: &Dual| -> Dual {
         }
         _ => {
             let (x, y) = a.to_union_vars(b, Some(state));
-            Dual {real: x.real + y.real, dual: &x.dual + &y.dual, vars: Arc::clone(&x.vars)}
+            let dual = x.dual + &y.dual;
+            recycle(y.dual);
+            Dual {real: x.real + y.real, dual, vars: x.vars}
         }
     }
 });
@@ -36,11 +39,10 @@ impl_op_ex!(+ |a: &Dual2, b: &Dual2| -> Dual2 {
         }
         _ => {
             let (x, y) = a.to_union_vars(b, Some(state));
-            Dual2 {
-                real: x.real + y.real,
-                dual: &x.dual + &y.dual,
-                dual2: &x.dual2 + &y.dual2,
-                vars: Arc::clone(&x.vars)}
+            let (dual, dual2) = (x.dual + &y.dual, x.dual2 + &y.dual2);
+            recycle(y.dual);
+            recycle(y.dual2);
+            Dual2 {real: x.real + y.real, dual, dual2, vars: x.vars}
         }
     }
 });
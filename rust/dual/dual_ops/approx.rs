@@ -0,0 +1,161 @@
+use crate::dual::dual::{Dual, Dual2, Vars};
+use crate::dual::enums::Number;
+use std::fmt;
+
+/// The first component of two values not within tolerance, reported by [ApproxEq].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApproxMismatch {
+    /// The component compared: `real`, a first derivative `d/dx`, a second derivative
+    /// `d2/dxdy` or `order` for values of different AD order.
+    pub component: String,
+    pub left: f64,
+    pub right: f64,
+}
+
+impl fmt::Display for ApproxMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "`{}` is not close: {} != {}",
+            self.component, self.left, self.right
+        )
+    }
+}
+
+/// Approximate equality of values and derivatives.
+///
+/// Each component `a` of `self` is close to the component `b` of `other` if
+/// `|a - b| <= atol + rtol * |b|`. Derivatives are compared over the union of `vars`, a
+/// variable absent from either value having a zero derivative, and second derivatives compare
+/// the Hessian, that is twice the `dual2` component.
+pub trait ApproxEq {
+    /// Return the first component not within tolerance, if any.
+    fn approx_eq(&self, other: &Self, rtol: f64, atol: f64) -> Result<(), ApproxMismatch>;
+}
+
+fn close(
+    component: impl Fn() -> String,
+    a: f64,
+    b: f64,
+    rtol: f64,
+    atol: f64,
+) -> Result<(), ApproxMismatch> {
+    match (a - b).abs() <= atol + rtol * b.abs() || a == b {
+        true => Ok(()),
+        false => Err(ApproxMismatch {
+            component: component(),
+            left: a,
+            right: b,
+        }),
+    }
+}
+
+impl ApproxEq for f64 {
+    fn approx_eq(&self, other: &Self, rtol: f64, atol: f64) -> Result<(), ApproxMismatch> {
+        close(|| "real".to_string(), *self, *other, rtol, atol)
+    }
+}
+
+impl ApproxEq for Dual {
+    fn approx_eq(&self, other: &Self, rtol: f64, atol: f64) -> Result<(), ApproxMismatch> {
+        self.real.approx_eq(&other.real, rtol, atol)?;
+        let (x, y) = self.to_union_vars(other, None);
+        for (i, var) in x.vars.iter().enumerate() {
+            close(|| format!("d/d{}", var), x.dual[i], y.dual[i], rtol, atol)?;
+        }
+        Ok(())
+    }
+}
+
+impl ApproxEq for Dual2 {
+    fn approx_eq(&self, other: &Self, rtol: f64, atol: f64) -> Result<(), ApproxMismatch> {
+        self.real.approx_eq(&other.real, rtol, atol)?;
+        let (x, y) = self.to_union_vars(other, None);
+        for (i, var) in x.vars.iter().enumerate() {
+            close(|| format!("d/d{}", var), x.dual[i], y.dual[i], rtol, atol)?;
+        }
+        for (i, vi) in x.vars.iter().enumerate() {
+            for (j, vj) in x.vars.iter().enumerate() {
+                let (a, b) = (2.0 * x.dual2[[i, j]], 2.0 * y.dual2[[i, j]]);
+                close(|| format!("d2/d{}d{}", vi, vj), a, b, rtol, atol)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ApproxEq for Number {
+    fn approx_eq(&self, other: &Self, rtol: f64, atol: f64) -> Result<(), ApproxMismatch> {
+        match (self, other) {
+            (Number::F64(a), Number::F64(b)) => a.approx_eq(b, rtol, atol),
+            (Number::F64(a), Number::Dual(b)) => Dual::new(*a, vec![]).approx_eq(b, rtol, atol),
+            (Number::Dual(a), Number::F64(b)) => a.approx_eq(&Dual::new(*b, vec![]), rtol, atol),
+            (Number::F64(a), Number::Dual2(b)) => Dual2::new(*a, vec![]).approx_eq(b, rtol, atol),
+            (Number::Dual2(a), Number::F64(b)) => a.approx_eq(&Dual2::new(*b, vec![]), rtol, atol),
+            (Number::Dual(a), Number::Dual(b)) => a.approx_eq(b, rtol, atol),
+            (Number::Dual2(a), Number::Dual2(b)) => a.approx_eq(b, rtol, atol),
+            (Number::Dual(_), Number::Dual2(_)) => Err(ApproxMismatch {
+                component: "order".to_string(),
+                left: 1.0,
+                right: 2.0,
+            }),
+            (Number::Dual2(_), Number::Dual(_)) => Err(ApproxMismatch {
+                component: "order".to_string(),
+                left: 2.0,
+                right: 1.0,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dual_within_tolerance() {
+        let a = Dual::try_new(1.0, vec!["x".to_string(), "y".to_string()], vec![1.0, 2.0]).unwrap();
+        let b = Dual::try_new(
+            1.0 + 1e-10,
+            vec!["y".to_string(), "x".to_string(), "z".to_string()],
+            vec![2.0, 1.0 + 1e-10, 1e-12],
+        )
+        .unwrap();
+        assert!(a.approx_eq(&b, 0.0, 1e-9).is_ok());
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_dual_reports_first_variable() {
+        let a = Dual::try_new(1.0, vec!["x".to_string(), "y".to_string()], vec![1.0, 2.0]).unwrap();
+        let b = Dual::try_new(1.0, vec!["x".to_string(), "y".to_string()], vec![1.0, 2.1]).unwrap();
+        let err = a.approx_eq(&b, 1e-3, 0.0).unwrap_err();
+        assert_eq!(err.component, "d/dy");
+        assert_eq!((err.left, err.right), (2.0, 2.1));
+        assert!(a.approx_eq(&b, 0.1, 0.0).is_ok());
+        assert_eq!(
+            format!("{}", err),
+            "`d/dy` is not close: 2 != 2.1".to_string()
+        );
+    }
+
+    #[test]
+    fn test_dual2_hessian() {
+        let x = Dual2::new(2.0, vec!["x".to_string()]);
+        let a = &x * &x;
+        let b = Dual2::try_new(4.0, vec!["x".to_string()], vec![4.0], vec![1.1]).unwrap();
+        let err = a.approx_eq(&b, 0.0, 1e-6).unwrap_err();
+        assert_eq!(err.component, "d2/dxdx");
+        assert_eq!((err.left, err.right), (2.0, 2.2));
+    }
+
+    #[test]
+    fn test_number() {
+        let a = Number::F64(1.0);
+        let b = Number::Dual(Dual::new(1.0, vec!["x".to_string()]));
+        assert_eq!(a.approx_eq(&b, 0.0, 1e-9).unwrap_err().component, "d/dx");
+        let c = Number::Dual2(Dual2::new(1.0, vec![]));
+        assert_eq!(b.approx_eq(&c, 0.0, 1.0).unwrap_err().component, "order");
+        assert!(a.approx_eq(&c, 0.0, 0.0).is_ok());
+    }
+}
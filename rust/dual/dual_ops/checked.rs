@@ -0,0 +1,65 @@
+use crate::dual::enums::Number;
+use crate::error::RateslibError;
+
+impl Number {
+    /// Return an error if `self` and `other` combine first and second order dual numbers.
+    fn check_compatible(&self, other: &Number, operator: &str) -> Result<(), RateslibError> {
+        match (self, other) {
+            (Number::Dual(_), Number::Dual2(_)) => Err(RateslibError::MixedDualTypes {
+                operation: format!("Dual {} Dual2", operator),
+            }),
+            (Number::Dual2(_), Number::Dual(_)) => Err(RateslibError::MixedDualTypes {
+                operation: format!("Dual2 {} Dual", operator),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Return the sum, or raise where the operator would panic on mixed dual types.
+    pub fn try_add(&self, other: &Number) -> Result<Number, RateslibError> {
+        self.check_compatible(other, "+")?;
+        Ok(self + other)
+    }
+
+    /// Return the difference, or raise where the operator would panic on mixed dual types.
+    pub fn try_sub(&self, other: &Number) -> Result<Number, RateslibError> {
+        self.check_compatible(other, "-")?;
+        Ok(self - other)
+    }
+
+    /// Return the product, or raise where the operator would panic on mixed dual types.
+    pub fn try_mul(&self, other: &Number) -> Result<Number, RateslibError> {
+        self.check_compatible(other, "*")?;
+        Ok(self * other)
+    }
+
+    /// Return the quotient, or raise where the operator would panic on mixed dual types.
+    pub fn try_div(&self, other: &Number) -> Result<Number, RateslibError> {
+        self.check_compatible(other, "/")?;
+        Ok(self / other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dual::{Dual, Dual2};
+
+    #[test]
+    fn test_checked_ops() {
+        let (f, d) = (
+            Number::F64(2.0),
+            Number::Dual(Dual::new(1.0, vec!["x".to_string()])),
+        );
+        let d2 = Number::Dual2(Dual2::new(1.0, vec!["x".to_string()]));
+        assert_eq!(f.try_add(&d).unwrap(), &f + &d);
+        assert_eq!(d2.try_mul(&f).unwrap(), &d2 * &f);
+        assert_eq!(
+            d.try_div(&d2).unwrap_err(),
+            RateslibError::MixedDualTypes {
+                operation: "Dual / Dual2".to_string()
+            }
+        );
+        assert!(d2.try_sub(&d).is_err());
+    }
+}
@@ -0,0 +1,104 @@
+use crate::dual::dual::{Dual, Dual2};
+use crate::dual::enums::Number;
+use ndarray::{Array1, Array2, Axis};
+use std::sync::Arc;
+
+impl Dual {
+    /// Return the dual number without the `vars` of which the gradient is exactly zero, such as
+    /// variables which cancelled over a long chain of operations.
+    ///
+    /// If no variable is dropped the `vars` remain shared with `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use rateslib::dual::{Dual, Vars};
+    /// let x = Dual::new(1.0, vec!["x".to_string()]);
+    /// let y = Dual::new(2.0, vec!["y".to_string()]);
+    /// let z = (&x + &y) - &y;
+    /// assert_eq!(z.vars().len(), 2);
+    /// assert_eq!(z.compress().vars().len(), 1);
+    /// ```
+    pub fn compress(&self) -> Self {
+        let keep: Vec<usize> = (0..self.vars.len())
+            .filter(|i| self.dual[*i] != 0.0)
+            .collect();
+        if keep.len() == self.vars.len() {
+            return self.clone();
+        }
+        Dual {
+            real: self.real,
+            vars: Arc::new(keep.iter().map(|i| self.vars[*i].clone()).collect()),
+            dual: Array1::from_iter(keep.iter().map(|i| self.dual[*i])).into(),
+        }
+    }
+}
+
+impl Dual2 {
+    /// Return the dual number without the `vars` of which the first and second order gradients
+    /// are all exactly zero.
+    ///
+    /// If no variable is dropped the `vars` remain shared with `self`.
+    pub fn compress(&self) -> Self {
+        let dual2: &Array2<f64> = &self.dual2;
+        let keep: Vec<usize> = (0..self.vars.len())
+            .filter(|i| {
+                self.dual[*i] != 0.0
+                    || dual2.index_axis(Axis(0), *i).iter().any(|x| *x != 0.0)
+                    || dual2.index_axis(Axis(1), *i).iter().any(|x| *x != 0.0)
+            })
+            .collect();
+        if keep.len() == self.vars.len() {
+            return self.clone();
+        }
+        Dual2 {
+            real: self.real,
+            vars: Arc::new(keep.iter().map(|i| self.vars[*i].clone()).collect()),
+            dual: Array1::from_iter(keep.iter().map(|i| self.dual[*i])).into(),
+            dual2: Array2::from_shape_fn((keep.len(), keep.len()), |(i, j)| {
+                dual2[[keep[i], keep[j]]]
+            })
+            .into(),
+        }
+    }
+}
+
+impl Number {
+    /// Return the number without the variables of which every gradient is exactly zero.
+    pub fn compress(&self) -> Self {
+        match self {
+            Number::F64(f) => Number::F64(*f),
+            Number::Dual(d) => Number::Dual(d.compress()),
+            Number::Dual2(d) => Number::Dual2(d.compress()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dual::{Gradient1, Gradient2, Vars};
+
+    #[test]
+    fn test_compress() {
+        let vars = vec!["x".to_string(), "y".to_string(), "z".to_string()];
+        let d = Dual::try_new(1.0, vars.clone(), vec![1.0, 0.0, 2.0]).unwrap();
+        let c = d.compress();
+        assert_eq!(c.vars().iter().collect::<Vec<_>>(), vec!["x", "z"]);
+        assert_eq!(c.gradient1(vars.clone()), d.gradient1(vars.clone()));
+        assert!(c.compress().ptr_eq(&c));
+
+        // `y` has no first order gradient but a cross gamma with `x`, and is kept
+        let d2 = Dual2::try_new(
+            1.0,
+            vars.clone(),
+            vec![1.0, 0.0, 0.0],
+            vec![0.0, 0.5, 0.0, 0.5, 0.0, 0.0, 0.0, 0.0, 0.0],
+        )
+        .unwrap();
+        let c2 = d2.compress();
+        assert_eq!(c2.vars().len(), 2);
+        assert_eq!(c2.gradient2(vars.clone()), d2.gradient2(vars));
+        assert_eq!(Number::F64(2.5).compress(), Number::F64(2.5));
+    }
+}
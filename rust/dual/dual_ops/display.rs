@@ -0,0 +1,118 @@
+use crate::dual::dual::{Dual, Dual2, Gradient1};
+use crate::dual::enums::Number;
+use std::fmt;
+
+/// The number of sensitivities shown by the [Display](fmt::Display) of a dual number.
+pub const DISPLAY_TOP_K: usize = 5;
+
+/// Display of a dual number showing its real value and largest sensitivities.
+///
+/// Sensitivities are sorted by descending magnitude and truncated at `k`, with the number of
+/// omitted variables stated. The precision of the formatter, default 6, applies to all values.
+pub struct TopK<'a, T: Gradient1> {
+    name: &'static str,
+    real: f64,
+    value: &'a T,
+    k: usize,
+}
+
+impl<T: Gradient1> fmt::Display for TopK<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let p = f.precision().unwrap_or(6);
+        write!(f, "<{}: {:.*}", self.name, p, self.real)?;
+        for (var, value) in self.value.top_sensitivities(self.k) {
+            write!(f, ", {}: {:.*}", var, p, value)?;
+        }
+        let omitted = self.value.vars().len().saturating_sub(self.k);
+        if omitted > 0 {
+            write!(f, ", ... (+{} vars)", omitted)?;
+        }
+        write!(f, ">")
+    }
+}
+
+impl Dual {
+    /// Return a displayable of the real value and the `k` largest sensitivities.
+    pub fn display_top_k(&self, k: usize) -> TopK<'_, Dual> {
+        TopK {
+            name: "Dual",
+            real: self.real,
+            value: self,
+            k,
+        }
+    }
+}
+
+impl Dual2 {
+    /// Return a displayable of the real value and the `k` largest first order sensitivities.
+    pub fn display_top_k(&self, k: usize) -> TopK<'_, Dual2> {
+        TopK {
+            name: "Dual2",
+            real: self.real,
+            value: self,
+            k,
+        }
+    }
+}
+
+impl fmt::Display for Dual {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.display_top_k(DISPLAY_TOP_K), f)
+    }
+}
+
+impl fmt::Display for Dual2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.display_top_k(DISPLAY_TOP_K), f)
+    }
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Number::F64(v) => fmt::Display::fmt(v, f),
+            Number::Dual(d) => fmt::Display::fmt(d, f),
+            Number::Dual2(d) => fmt::Display::fmt(d, f),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture() -> Dual {
+        let vars: Vec<String> = (0..7).map(|i| format!("v{}", i)).collect();
+        Dual::try_new(1.5, vars, vec![0.1, -3.0, 0.0, 2.0, -0.5, 7.0, 1.0]).unwrap()
+    }
+
+    #[test]
+    fn test_top_sensitivities() {
+        let top = fixture().top_sensitivities(3);
+        assert_eq!(
+            top,
+            vec![
+                ("v5".to_string(), 7.0),
+                ("v1".to_string(), -3.0),
+                ("v3".to_string(), 2.0)
+            ]
+        );
+        assert_eq!(fixture().top_sensitivities(10).len(), 7);
+    }
+
+    #[test]
+    fn test_display() {
+        let d = fixture();
+        assert_eq!(
+            format!("{:.2}", d.display_top_k(2)),
+            "<Dual: 1.50, v5: 7.00, v1: -3.00, ... (+5 vars)>"
+        );
+        assert_eq!(
+            format!("{:.1}", d),
+            "<Dual: 1.5, v5: 7.0, v1: -3.0, v3: 2.0, v6: 1.0, v4: -0.5, ... (+2 vars)>"
+        );
+        let d2 = Dual2::new(2.0, vec!["x".to_string()]);
+        assert_eq!(format!("{}", d2), "<Dual2: 2.000000, x: 1.000000>");
+        assert_eq!(format!("{:.1}", Number::F64(2.0)), "2.0");
+    }
+}
@@ -231,6 +231,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_enum_f64() {
         let n = Number::Dual(Dual::new(2.0, vec![]));
         assert!(n == 2.0);
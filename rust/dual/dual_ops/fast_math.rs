@@ -0,0 +1,197 @@
+use crate::dual::dual::{Dual, Dual2};
+use crate::dual::enums::Number;
+use crate::dual::lazy::LazyArray2;
+use std::sync::Arc;
+
+/// Elementary functions with specialised implementations which avoid the generic
+/// [Pow](num_traits::Pow) and are accurate for arguments near zero.
+pub trait FastMathFuncs {
+    /// Return the value raised to an integer power.
+    fn powi(&self, n: i32) -> Self;
+    /// Return `exp(x) - 1`, accurate for small `x`.
+    fn exp_m1(&self) -> Self;
+    /// Return `ln(1 + x)`, accurate for small `x`.
+    fn ln_1p(&self) -> Self;
+    /// Return the square of the value.
+    fn squared(&self) -> Self;
+    /// Return the cube of the value.
+    fn cubed(&self) -> Self;
+}
+
+impl FastMathFuncs for f64 {
+    fn powi(&self, n: i32) -> Self {
+        f64::powi(*self, n)
+    }
+    fn exp_m1(&self) -> Self {
+        f64::exp_m1(*self)
+    }
+    fn ln_1p(&self) -> Self {
+        f64::ln_1p(*self)
+    }
+    fn squared(&self) -> Self {
+        self * self
+    }
+    fn cubed(&self) -> Self {
+        self * self * self
+    }
+}
+
+/// Apply a function with value `f` and first derivative `df` to a [Dual].
+pub(crate) fn chain(x: &Dual, f: f64, df: f64) -> Dual {
+    Dual {
+        real: f,
+        vars: Arc::clone(&x.vars),
+        dual: df * &x.dual,
+    }
+}
+
+/// Apply a function with value `f`, first derivative `df` and second derivative `d2f` to a
+/// [Dual2].
+pub(crate) fn chain2(x: &Dual2, f: f64, df: f64, d2f: f64) -> Dual2 {
+    Dual2 {
+        real: f,
+        vars: Arc::clone(&x.vars),
+        dual: df * &x.dual,
+        dual2: LazyArray2::combine(
+            x.dual.len(),
+            &[(df, &x.dual2)],
+            &[(0.5 * d2f, &x.dual, &x.dual)],
+        ),
+    }
+}
+
+impl FastMathFuncs for Dual {
+    fn powi(&self, n: i32) -> Self {
+        let df = match n {
+            0 => 0.0,
+            _ => n as f64 * self.real.powi(n - 1),
+        };
+        chain(self, self.real.powi(n), df)
+    }
+    fn exp_m1(&self) -> Self {
+        chain(self, self.real.exp_m1(), self.real.exp())
+    }
+    fn ln_1p(&self) -> Self {
+        chain(self, self.real.ln_1p(), 1.0 / (1.0 + self.real))
+    }
+    fn squared(&self) -> Self {
+        chain(self, self.real * self.real, 2.0 * self.real)
+    }
+    fn cubed(&self) -> Self {
+        let x2 = self.real * self.real;
+        chain(self, x2 * self.real, 3.0 * x2)
+    }
+}
+
+impl FastMathFuncs for Dual2 {
+    fn powi(&self, n: i32) -> Self {
+        let (df, d2f) = match n {
+            0 => (0.0, 0.0),
+            1 => (1.0, 0.0),
+            _ => (
+                n as f64 * self.real.powi(n - 1),
+                (n * (n - 1)) as f64 * self.real.powi(n - 2),
+            ),
+        };
+        chain2(self, self.real.powi(n), df, d2f)
+    }
+    fn exp_m1(&self) -> Self {
+        let e = self.real.exp();
+        chain2(self, self.real.exp_m1(), e, e)
+    }
+    fn ln_1p(&self) -> Self {
+        let r = 1.0 / (1.0 + self.real);
+        chain2(self, self.real.ln_1p(), r, -r * r)
+    }
+    fn squared(&self) -> Self {
+        chain2(self, self.real * self.real, 2.0 * self.real, 2.0)
+    }
+    fn cubed(&self) -> Self {
+        let x2 = self.real * self.real;
+        chain2(self, x2 * self.real, 3.0 * x2, 6.0 * self.real)
+    }
+}
+
+macro_rules! fast_math_func {
+    ($self: ident, $name: ident $(, $arg: expr)?) => {
+        match $self {
+            Number::F64(f) => Number::F64(FastMathFuncs::$name(f $(, $arg)?)),
+            Number::Dual(d) => Number::Dual(d.$name($($arg)?)),
+            Number::Dual2(d) => Number::Dual2(d.$name($($arg)?)),
+        }
+    };
+}
+
+impl FastMathFuncs for Number {
+    fn powi(&self, n: i32) -> Self {
+        fast_math_func!(self, powi, n)
+    }
+    fn exp_m1(&self) -> Self {
+        fast_math_func!(self, exp_m1)
+    }
+    fn ln_1p(&self) -> Self {
+        fast_math_func!(self, ln_1p)
+    }
+    fn squared(&self) -> Self {
+        fast_math_func!(self, squared)
+    }
+    fn cubed(&self) -> Self {
+        fast_math_func!(self, cubed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dual::MathFuncs;
+    use num_traits::Pow;
+
+    fn dual2() -> Dual2 {
+        Dual2::try_new(
+            1.3,
+            vec!["x".to_string(), "y".to_string()],
+            vec![1.0, 2.0],
+            vec![0.5, 0.1, 0.1, 0.2],
+        )
+        .unwrap()
+    }
+
+    fn assert_close2(a: &Dual2, b: &Dual2) {
+        assert!((a.real - b.real).abs() < 1e-12);
+        assert!((&a.dual - &b.dual).iter().all(|v| v.abs() < 1e-12));
+        assert!((&a.dual2 - &b.dual2).iter().all(|v| v.abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_powi_matches_pow() {
+        let d = Dual::try_new(1.3, vec!["x".to_string()], vec![2.0]).unwrap();
+        for n in [-2, 0, 1, 2, 5] {
+            let (a, b) = (d.powi(n), (&d).pow(n as f64));
+            assert!((a.real - b.real).abs() < 1e-12);
+            assert!((a.dual[0] - b.dual[0]).abs() < 1e-12);
+            assert_close2(&dual2().powi(n), &(&dual2()).pow(n as f64));
+        }
+        assert_close2(&dual2().squared(), &dual2().powi(2));
+        assert_close2(&dual2().cubed(), &dual2().powi(3));
+        assert_eq!(d.squared(), &d * &d);
+    }
+
+    #[test]
+    fn test_exp_m1_ln_1p() {
+        assert_close2(&dual2().exp_m1(), &(dual2().exp() - 1.0));
+        assert_close2(&dual2().ln_1p(), &(dual2() + 1.0).log());
+        // accuracy near zero is retained
+        let small = Dual::new(1e-12, vec!["x".to_string()]);
+        assert_eq!(small.exp_m1().real, 1e-12_f64.exp_m1());
+        assert!(((small.exp() - 1.0).real - 1e-12).abs() > 1e-17);
+        assert!((small.ln_1p().real - 1e-12).abs() < 1e-24);
+    }
+
+    #[test]
+    fn test_number() {
+        let n = Number::Dual(Dual::new(2.0, vec!["x".to_string()]));
+        assert_eq!(n.powi(3), n.cubed());
+        assert_eq!(Number::F64(2.0).squared(), Number::F64(4.0));
+        assert_eq!(Number::F64(0.0).ln_1p(), Number::F64(0.0));
+    }
+}
@@ -1,6 +1,6 @@
 use crate::dual::dual::{Dual, Dual2};
 use crate::dual::enums::Number;
-use ndarray::{Array2, Axis};
+use crate::dual::lazy::LazyArray2;
 
 impl From<Dual> for f64 {
     fn from(value: Dual) -> Self {
@@ -66,24 +66,24 @@ impl From<f64> for Dual2 {
 
 impl From<Dual> for Dual2 {
     fn from(value: Dual) -> Self {
-        let n = value.dual.len_of(Axis(0));
+        let n = value.dual.len();
         Dual2 {
             real: value.real,
             vars: value.vars.clone(),
             dual: value.dual,
-            dual2: Array2::zeros((n, n)),
+            dual2: LazyArray2::zeros(n),
         }
     }
 }
 
 impl From<&Dual> for Dual2 {
     fn from(value: &Dual) -> Self {
-        let n = value.dual.len_of(Axis(0));
+        let n = value.dual.len();
         Dual2 {
             real: value.real,
             vars: value.vars.clone(),
             dual: value.dual.clone(),
-            dual2: Array2::zeros((n, n)),
+            dual2: LazyArray2::zeros(n),
         }
     }
 }
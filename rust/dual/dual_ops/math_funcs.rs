@@ -1,6 +1,6 @@
 use crate::dual::dual::{Dual, Dual2};
 use crate::dual::enums::Number;
-use crate::dual::linalg::fouter11_;
+use crate::dual::lazy::LazyArray2;
 use num_traits::Pow;
 use statrs::distribution::{ContinuousCDF, Normal};
 use std::f64::consts::PI;
@@ -63,7 +63,11 @@ impl MathFuncs for Dual2 {
             real: c,
             vars: Arc::clone(&self.vars),
             dual: c * &self.dual,
-            dual2: c * (&self.dual2 + 0.5 * fouter11_(&self.dual.view(), &self.dual.view())),
+            dual2: LazyArray2::combine(
+                self.dual.len(),
+                &[(c, &self.dual2)],
+                &[(0.5 * c, &self.dual, &self.dual)],
+            ),
         }
     }
     fn log(&self) -> Self {
@@ -72,8 +76,11 @@ impl MathFuncs for Dual2 {
             real: self.real.ln(),
             vars: Arc::clone(&self.vars),
             dual: scalar * &self.dual,
-            dual2: scalar * &self.dual2
-                - fouter11_(&self.dual.view(), &self.dual.view()) * 0.5 * (scalar * scalar),
+            dual2: LazyArray2::combine(
+                self.dual.len(),
+                &[(scalar, &self.dual2)],
+                &[(-0.5 * scalar * scalar, &self.dual, &self.dual)],
+            ),
         }
     }
     fn norm_cdf(&self) -> Self {
@@ -81,12 +88,15 @@ impl MathFuncs for Dual2 {
         let base = n.cdf(self.real);
         let scalar = 1.0 / (2.0 * PI).sqrt() * (-0.5_f64 * self.real.pow(2.0_f64)).exp();
         let scalar2 = scalar * -self.real;
-        let cross_beta = fouter11_(&self.dual.view(), &self.dual.view());
         Dual2 {
             real: base,
             vars: Arc::clone(&self.vars),
             dual: scalar * &self.dual,
-            dual2: scalar * &self.dual2 + 0.5_f64 * scalar2 * cross_beta,
+            dual2: LazyArray2::combine(
+                self.dual.len(),
+                &[(scalar, &self.dual2)],
+                &[(0.5 * scalar2, &self.dual, &self.dual)],
+            ),
         }
     }
     fn inv_norm_cdf(&self) -> Self {
@@ -94,12 +104,15 @@ impl MathFuncs for Dual2 {
         let base = n.inverse_cdf(self.real);
         let scalar = (2.0 * PI).sqrt() * (0.5_f64 * base.pow(2.0_f64)).exp();
         let scalar2 = scalar.pow(2.0_f64) * base;
-        let cross_beta = fouter11_(&self.dual.view(), &self.dual.view());
         Dual2 {
             real: base,
             vars: Arc::clone(&self.vars),
             dual: scalar * &self.dual,
-            dual2: scalar * &self.dual2 + 0.5_f64 * scalar2 * cross_beta,
+            dual2: LazyArray2::combine(
+                self.dual.len(),
+                &[(scalar, &self.dual2)],
+                &[(0.5 * scalar2, &self.dual, &self.dual)],
+            ),
         }
     }
 }
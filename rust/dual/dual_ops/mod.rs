@@ -1,7 +1,12 @@
 mod add;
+pub mod approx;
+mod checked;
+mod compress;
 pub mod convert;
+pub mod display;
 mod div;
 mod eq;
+pub mod fast_math;
 mod from;
 pub mod math_funcs;
 mod mul;
@@ -13,6 +18,7 @@ mod ord;
 mod pow;
 mod rem;
 mod signed;
+pub mod smooth;
 mod sub;
 mod sum;
 mod zero;
@@ -1,8 +1,8 @@
 use crate::dual::dual::{Dual, Dual2, Vars, VarsRelationship};
 use crate::dual::enums::Number;
-use crate::dual::linalg::fouter11_;
+use crate::dual::lazy::LazyArray2;
+use crate::dual::pool::recycle;
 use auto_ops::{impl_op_ex, impl_op_ex_commutative};
-use ndarray::Array2;
 use std::sync::Arc;
 
 // Mul
@@ -26,17 +26,24 @@ impl_op_ex_commutative!(*|a: &Dual2, b: &f64| -> Dual2 {
 impl_op_ex!(*|a: &Dual, b: &Dual| -> Dual {
     let state = a.vars_cmp(b.vars());
     match state {
-        VarsRelationship::ArcEquivalent | VarsRelationship::ValueEquivalent => Dual {
-            real: a.real * b.real,
-            dual: &a.dual * b.real + &b.dual * a.real,
-            vars: Arc::clone(&a.vars),
-        },
+        VarsRelationship::ArcEquivalent | VarsRelationship::ValueEquivalent => {
+            let mut dual = &a.dual * b.real;
+            dual.scaled_add(a.real, &b.dual);
+            Dual {
+                real: a.real * b.real,
+                dual,
+                vars: Arc::clone(&a.vars),
+            }
+        }
         _ => {
             let (x, y) = a.to_union_vars(b, Some(state));
+            let mut dual = x.dual * y.real;
+            dual.scaled_add(x.real, &y.dual);
+            recycle(y.dual);
             Dual {
                 real: x.real * y.real,
-                dual: &x.dual * y.real + &y.dual * x.real,
-                vars: Arc::clone(&x.vars),
+                dual,
+                vars: x.vars,
             }
         }
     }
@@ -47,25 +54,36 @@ impl_op_ex!(*|a: &Dual2, b: &Dual2| -> Dual2 {
     let state = a.vars_cmp(b.vars());
     match state {
         VarsRelationship::ArcEquivalent | VarsRelationship::ValueEquivalent => {
-            let mut dual2: Array2<f64> = &a.dual2 * b.real + &b.dual2 * a.real;
-            let cross_beta = fouter11_(&a.dual.view(), &b.dual.view());
-            dual2 = dual2 + 0.5_f64 * (&cross_beta + &cross_beta.t());
+            let dual2 = LazyArray2::combine(
+                a.dual.len(),
+                &[(b.real, &a.dual2), (a.real, &b.dual2)],
+                &[(0.5, &a.dual, &b.dual), (0.5, &b.dual, &a.dual)],
+            );
+            let mut dual = &a.dual * b.real;
+            dual.scaled_add(a.real, &b.dual);
             Dual2 {
                 real: a.real * b.real,
-                dual: &a.dual * b.real + &b.dual * a.real,
+                dual,
                 vars: Arc::clone(&a.vars),
                 dual2,
             }
         }
         _ => {
             let (x, y) = a.to_union_vars(b, Some(state));
-            let mut dual2: Array2<f64> = &x.dual2 * y.real + &y.dual2 * x.real;
-            let cross_beta = fouter11_(&x.dual.view(), &y.dual.view());
-            dual2 = dual2 + 0.5_f64 * (&cross_beta + &cross_beta.t());
+            let dual2 = LazyArray2::combine(
+                x.dual.len(),
+                &[(y.real, &x.dual2), (x.real, &y.dual2)],
+                &[(0.5, &x.dual, &y.dual), (0.5, &y.dual, &x.dual)],
+            );
+            recycle(x.dual2);
+            recycle(y.dual2);
+            let mut dual = x.dual * y.real;
+            dual.scaled_add(x.real, &y.dual);
+            recycle(y.dual);
             Dual2 {
                 real: x.real * y.real,
-                dual: &x.dual * y.real + &y.dual * x.real,
-                vars: Arc::clone(&x.vars),
+                dual,
+                vars: x.vars,
                 dual2,
             }
         }
@@ -1,6 +1,6 @@
 use crate::dual::dual::{Dual, Dual2, Vars};
 use crate::dual::enums::Number;
-use crate::dual::linalg::fouter11_;
+use crate::dual::lazy::LazyArray2;
 use num_traits::Pow;
 use std::sync::Arc;
 
@@ -31,12 +31,16 @@ impl Pow<f64> for Dual2 {
     fn pow(self, power: f64) -> Self::Output {
         let coeff = power * self.real.powf(power - 1.);
         let coeff2 = 0.5 * power * (power - 1.) * self.real.powf(power - 2.);
-        let beta_cross = fouter11_(&self.dual.view(), &self.dual.view());
+        let dual2 = LazyArray2::combine(
+            self.dual.len(),
+            &[(coeff, &self.dual2)],
+            &[(coeff2, &self.dual, &self.dual)],
+        );
         Dual2 {
             real: self.real.powf(power),
             vars: self.vars,
             dual: self.dual * coeff,
-            dual2: self.dual2 * coeff + beta_cross * coeff2,
+            dual2,
         }
     }
 }
@@ -46,12 +50,16 @@ impl Pow<f64> for &Dual2 {
     fn pow(self, power: f64) -> Self::Output {
         let coeff = power * self.real.powf(power - 1.);
         let coeff2 = 0.5 * power * (power - 1.) * self.real.powf(power - 2.);
-        let beta_cross = fouter11_(&self.dual.view(), &self.dual.view());
+        let dual2 = LazyArray2::combine(
+            self.dual.len(),
+            &[(coeff, &self.dual2)],
+            &[(coeff2, &self.dual, &self.dual)],
+        );
         Dual2 {
             real: self.real.powf(power),
             vars: Arc::clone(self.vars()),
             dual: &self.dual * coeff,
-            dual2: &self.dual2 * coeff + beta_cross * coeff2,
+            dual2,
         }
     }
 }
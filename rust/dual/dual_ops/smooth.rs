@@ -0,0 +1,206 @@
+use crate::dual::dual::{Dual, Dual2};
+use crate::dual::dual_ops::fast_math::{chain, chain2};
+use crate::dual::enums::Number;
+
+/// Maximum, minimum and absolute value with optional smoothing of their kink.
+///
+/// Without `smoothing`, or if it is not positive, the functions are exact and their first
+/// derivative is a step, taking the average of either side at the kink, with zero second
+/// derivative. With a `smoothing` width `h` the maximum is blended with the softplus function,
+/// `h ln(1 + exp(d / h))` of the difference `d` of the arguments, and the absolute value
+/// with `x tanh(x / h)`, so that second derivatives, such as the gamma of an option payoff,
+/// are continuous near the kink. The smoothed values differ from the exact values by at
+/// most `h ln 2` and `0.28 h` respectively.
+pub trait SmoothFuncs {
+    /// Return the maximum of `self` and `other`.
+    fn max_of(&self, other: &Self, smoothing: Option<f64>) -> Self;
+    /// Return the minimum of `self` and `other`.
+    fn min_of(&self, other: &Self, smoothing: Option<f64>) -> Self;
+    /// Return the absolute value.
+    fn abs_of(&self, smoothing: Option<f64>) -> Self;
+}
+
+/// Return the value and first two derivatives of the, possibly smoothed, `max(d, 0)`.
+fn max_zero(d: f64, smoothing: Option<f64>) -> (f64, f64, f64) {
+    match smoothing.filter(|h| *h > 0.0) {
+        None => {
+            let step = match d {
+                _ if d > 0.0 => 1.0,
+                _ if d < 0.0 => 0.0,
+                _ => 0.5,
+            };
+            (d.max(0.0), step, 0.0)
+        }
+        Some(h) => {
+            let u = d / h;
+            let s = 1.0 / (1.0 + (-u).exp());
+            (
+                d.max(0.0) + h * (-u.abs()).exp().ln_1p(),
+                s,
+                s * (1.0 - s) / h,
+            )
+        }
+    }
+}
+
+/// Return the value and first two derivatives of the, possibly smoothed, `|x|`.
+fn abs(x: f64, smoothing: Option<f64>) -> (f64, f64, f64) {
+    match smoothing.filter(|h| *h > 0.0) {
+        None => {
+            let sign = match x {
+                _ if x > 0.0 => 1.0,
+                _ if x < 0.0 => -1.0,
+                _ => 0.0,
+            };
+            (x.abs(), sign, 0.0)
+        }
+        Some(h) => {
+            let u = x / h;
+            let t = u.tanh();
+            let sech2 = 1.0 - t * t;
+            (x * t, t + u * sech2, 2.0 / h * sech2 * (1.0 - u * t))
+        }
+    }
+}
+
+impl SmoothFuncs for f64 {
+    fn max_of(&self, other: &Self, smoothing: Option<f64>) -> Self {
+        other + max_zero(self - other, smoothing).0
+    }
+    fn min_of(&self, other: &Self, smoothing: Option<f64>) -> Self {
+        self - max_zero(self - other, smoothing).0
+    }
+    fn abs_of(&self, smoothing: Option<f64>) -> Self {
+        abs(*self, smoothing).0
+    }
+}
+
+impl SmoothFuncs for Dual {
+    fn max_of(&self, other: &Self, smoothing: Option<f64>) -> Self {
+        let d = self - other;
+        let (f, df, _) = max_zero(d.real, smoothing);
+        other + &chain(&d, f, df)
+    }
+    fn min_of(&self, other: &Self, smoothing: Option<f64>) -> Self {
+        let d = self - other;
+        let (f, df, _) = max_zero(d.real, smoothing);
+        self - &chain(&d, f, df)
+    }
+    fn abs_of(&self, smoothing: Option<f64>) -> Self {
+        let (f, df, _) = abs(self.real, smoothing);
+        chain(self, f, df)
+    }
+}
+
+impl SmoothFuncs for Dual2 {
+    fn max_of(&self, other: &Self, smoothing: Option<f64>) -> Self {
+        let d = self - other;
+        let (f, df, d2f) = max_zero(d.real, smoothing);
+        other + &chain2(&d, f, df, d2f)
+    }
+    fn min_of(&self, other: &Self, smoothing: Option<f64>) -> Self {
+        let d = self - other;
+        let (f, df, d2f) = max_zero(d.real, smoothing);
+        self - &chain2(&d, f, df, d2f)
+    }
+    fn abs_of(&self, smoothing: Option<f64>) -> Self {
+        let (f, df, d2f) = abs(self.real, smoothing);
+        chain2(self, f, df, d2f)
+    }
+}
+
+impl SmoothFuncs for Number {
+    fn max_of(&self, other: &Self, smoothing: Option<f64>) -> Self {
+        match self - other {
+            Number::F64(d) => other + max_zero(d, smoothing).0,
+            Number::Dual(d) => other + Number::Dual(d.max_of(&Dual::new(0.0, vec![]), smoothing)),
+            Number::Dual2(d) => {
+                other + Number::Dual2(d.max_of(&Dual2::new(0.0, vec![]), smoothing))
+            }
+        }
+    }
+    fn min_of(&self, other: &Self, smoothing: Option<f64>) -> Self {
+        self - &(self - other).max_of(&Number::F64(0.0), smoothing)
+    }
+    fn abs_of(&self, smoothing: Option<f64>) -> Self {
+        match self {
+            Number::F64(f) => Number::F64(f.abs_of(smoothing)),
+            Number::Dual(d) => Number::Dual(d.abs_of(smoothing)),
+            Number::Dual2(d) => Number::Dual2(d.abs_of(smoothing)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dual::{Gradient1, Gradient2};
+
+    fn x2(value: f64) -> Dual2 {
+        Dual2::new(value, vec!["x".to_string()])
+    }
+
+    fn gamma(d: &Dual2) -> f64 {
+        d.gradient2(vec!["x".to_string()])[[0, 0]]
+    }
+
+    #[test]
+    fn test_exact() {
+        assert_eq!(2.0_f64.max_of(&3.0, None), 3.0);
+        assert_eq!(2.0_f64.min_of(&3.0, Some(0.0)), 2.0);
+        assert_eq!((-2.0_f64).abs_of(None), 2.0);
+        let x = Dual::new(2.0, vec!["x".to_string()]);
+        let zero = Dual::new(0.0, vec![]);
+        assert_eq!(
+            x.max_of(&zero, None).gradient1(vec!["x".to_string()])[0],
+            1.0
+        );
+        assert_eq!(
+            x.min_of(&zero, None).gradient1(vec!["x".to_string()])[0],
+            0.0
+        );
+        assert_eq!(gamma(&x2(1.0).max_of(&x2(0.0), None)), 0.0);
+    }
+
+    #[test]
+    fn test_smoothed_max_near_kink() {
+        let (h, zero) = (0.01, Dual2::new(0.0, vec![]));
+        let m = x2(0.0).max_of(&zero, Some(h));
+        assert!((m.real - h * 2_f64.ln()).abs() < 1e-15);
+        assert_eq!(m.gradient1(vec!["x".to_string()])[0], 0.5);
+        // the second derivative is that of the logistic density
+        assert!((gamma(&m) - 0.25 / h).abs() < 1e-9);
+        let far = x2(1.0).max_of(&zero, Some(h));
+        assert!((far.real - 1.0).abs() < 1e-15);
+        assert!(gamma(&far).abs() < 1e-12);
+        let n = x2(-0.02).min_of(&zero, Some(h));
+        assert!((n.real - (-0.02 - h * (-2_f64).exp().ln_1p())).abs() < 1e-15);
+    }
+
+    #[test]
+    fn test_smoothed_abs() {
+        let h = 0.1;
+        let a = x2(0.0).abs_of(Some(h));
+        assert_eq!(a.real, 0.0);
+        assert!((gamma(&a) - 2.0 / h).abs() < 1e-12);
+        let b = x2(-1.0).abs_of(Some(h));
+        assert!((b.real - 1.0).abs() < 1e-8);
+        assert!((b.gradient1(vec!["x".to_string()])[0] + 1.0).abs() < 1e-6);
+        // finite difference of the first derivative
+        let e = 1e-6;
+        let d = |x: f64| x2(x).abs_of(Some(h)).gradient1(vec!["x".to_string()])[0];
+        let fd = (d(0.05 + e) - d(0.05 - e)) / (2.0 * e);
+        assert!((gamma(&x2(0.05).abs_of(Some(h))) - fd).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_number_mixed() {
+        let x = Number::Dual(Dual::new(1.0, vec!["x".to_string()]));
+        let k = Number::F64(1.0);
+        let m = x.max_of(&k, Some(0.01));
+        assert!((f64::from(&m) - (1.0 + 0.01 * 2_f64.ln())).abs() < 1e-15);
+        assert_eq!(Number::F64(-1.0).abs_of(None), Number::F64(1.0));
+        let n = k.min_of(&x, None);
+        assert_eq!(f64::from(&n), 1.0);
+    }
+}
@@ -1,5 +1,6 @@
 use crate::dual::dual::{Dual, Dual2, Vars, VarsRelationship};
 use crate::dual::enums::Number;
+use crate::dual::pool::recycle;
 use auto_ops::impl_op_ex;
 use std::sync::Arc;
 
@@ -46,10 +47,12 @@ impl_op_ex!(-|a: &Dual, b: &Dual| -> Dual {
         },
         _ => {
             let (x, y) = a.to_union_vars(b, Some(state));
+            let dual = x.dual - &y.dual;
+            recycle(y.dual);
             Dual {
                 real: x.real - y.real,
-                dual: &x.dual - &y.dual,
-                vars: Arc::clone(&x.vars),
+                dual,
+                vars: x.vars,
             }
         }
     }
@@ -67,11 +70,14 @@ impl_op_ex!(-|a: &Dual2, b: &Dual2| -> Dual2 {
         },
         _ => {
             let (x, y) = a.to_union_vars(b, Some(state));
+            let (dual, dual2) = (x.dual - &y.dual, x.dual2 - &y.dual2);
+            recycle(y.dual);
+            recycle(y.dual2);
             Dual2 {
                 real: x.real - y.real,
-                dual: &x.dual - &y.dual,
-                dual2: &x.dual2 - &y.dual2,
-                vars: Arc::clone(&x.vars),
+                dual,
+                dual2,
+                vars: x.vars,
             }
         }
     }
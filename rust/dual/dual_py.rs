@@ -3,6 +3,7 @@
 use crate::dual::dual::{Dual, Dual2, Gradient1, Gradient2, Vars};
 use crate::dual::dual_ops::math_funcs::MathFuncs;
 use crate::dual::enums::{ADOrder, Number};
+use crate::error::RateslibError;
 use bincode::{deserialize, serialize};
 use num_traits::{Pow, Signed};
 use pyo3::exceptions::{PyTypeError, PyValueError};
@@ -64,7 +65,7 @@ impl ADOrder {
     pub fn __getstate__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
         Ok(PyBytes::new_bound(py, &serialize(&self).unwrap()))
     }
-    pub fn __getnewargs__<'py>(&self) -> PyResult<(u8,)> {
+    pub fn __getnewargs__(&self) -> PyResult<(u8,)> {
         match self {
             ADOrder::Zero => Ok((0_u8,)),
             ADOrder::One => Ok((1_u8,)),
@@ -77,7 +78,7 @@ impl ADOrder {
 impl Dual {
     #[new]
     fn new_py(real: f64, vars: Vec<String>, dual: Vec<f64>) -> PyResult<Self> {
-        Dual::try_new(real, vars, dual)
+        Ok(Dual::try_new(real, vars, dual)?)
     }
 
     /// Create a :class:`~rateslib.dual.Dual` object with ``vars`` linked with another.
@@ -120,7 +121,7 @@ impl Dual {
     ///    x1.ptr_eq(x3)
     #[staticmethod]
     fn vars_from(other: &Dual, real: f64, vars: Vec<String>, dual: Vec<f64>) -> PyResult<Self> {
-        Dual::try_new_from(other, real, vars, dual)
+        Ok(Dual::try_new_from(other, real, vars, dual)?)
     }
 
     #[getter]
@@ -137,13 +138,13 @@ impl Dual {
 
     #[getter]
     #[pyo3(name = "dual")]
-    fn dual_py<'py>(&'py self, py: Python<'py>) -> PyResult<Bound<'_, PyArray1<f64>>> {
-        Ok(self.dual().to_pyarray_bound(py))
+    fn dual_py<'py>(&'py self, py: Python<'py>) -> PyResult<Bound<'py, PyArray1<f64>>> {
+        Ok(self.dual_view().to_pyarray_bound(py))
     }
 
     #[getter]
     #[pyo3(name = "dual2")]
-    fn dual2_py<'py>(&'py self, _py: Python<'py>) -> PyResult<&PyArray2<f64>> {
+    fn dual2_py<'py>(&'py self, _py: Python<'py>) -> PyResult<&'py PyArray2<f64>> {
         Err(PyValueError::new_err(
             "`Dual` variable cannot possess `dual2` attribute.",
         ))
@@ -154,17 +155,57 @@ impl Dual {
         &'py self,
         py: Python<'py>,
         vars: Vec<String>,
-    ) -> PyResult<Bound<'_, PyArray1<f64>>> {
+    ) -> PyResult<Bound<'py, PyArray1<f64>>> {
         Ok(self.gradient1(vars).to_pyarray_bound(py))
     }
 
     #[pyo3(name = "grad2")]
-    fn grad2<'py>(&'py self, _py: Python<'py>, _vars: Vec<String>) -> PyResult<&PyArray2<f64>> {
+    fn grad2<'py>(&'py self, _py: Python<'py>, _vars: Vec<String>) -> PyResult<&'py PyArray2<f64>> {
         Err(PyValueError::new_err(
             "Cannot evaluate second order derivative on a Dual.",
         ))
     }
 
+    /// Return the Euclidean norm of the first order gradients.
+    ///
+    /// Returns
+    /// -------
+    /// float
+    #[pyo3(name = "grad_norm")]
+    fn grad_norm_py(&self) -> PyResult<f64> {
+        Ok(self.grad_norm())
+    }
+
+    /// Return the largest absolute first order gradient.
+    ///
+    /// Returns
+    /// -------
+    /// float
+    #[pyo3(name = "max_abs_sensitivity")]
+    fn max_abs_sensitivity_py(&self) -> PyResult<f64> {
+        Ok(self.max_abs_sensitivity())
+    }
+
+    /// Return the variables with a non-zero first order gradient.
+    ///
+    /// Returns
+    /// -------
+    /// list[str]
+    #[pyo3(name = "nonzero_vars")]
+    fn nonzero_vars_py(&self) -> PyResult<Vec<String>> {
+        Ok(self.nonzero_vars())
+    }
+
+    /// Return the dual number without the variables of which every gradient is exactly zero.
+    ///
+    /// Returns
+    /// -------
+    /// Dual
+    #[pyo3(name = "compress")]
+    fn compress_py(&self) -> Self {
+        self.compress()
+    }
+
     /// Evaluate if the ARC pointers of two `Dual` data types are equivalent.
     ///
     /// Parameters
@@ -183,7 +224,7 @@ impl Dual {
     fn __repr__(&self) -> PyResult<String> {
         let mut _vars = Vec::from_iter(self.vars().iter().take(3).map(String::as_str)).join(", ");
         let mut _dual =
-            Vec::from_iter(self.dual().iter().take(3).map(|x| format!("{:.1}", x))).join(", ");
+            Vec::from_iter(self.dual_view().iter().take(3).map(|x| format!("{:.1}", x))).join(", ");
         if self.vars().len() > 3 {
             _vars.push_str(", ...");
             _dual.push_str(", ...");
@@ -328,7 +369,10 @@ impl Dual {
 
     fn __pow__(&self, power: Number, modulo: Option<i32>) -> PyResult<Self> {
         if modulo.unwrap_or(0) != 0 {
-            panic!("Power function with mod not available for Dual.")
+            return Err(RateslibError::Unsupported(
+                "Power function with mod not available for Dual.".to_string(),
+            )
+            .into());
         }
         match power {
             Number::F64(f) => Ok(self.clone().pow(f)),
@@ -407,7 +451,7 @@ impl Dual2 {
     /// Python wrapper to construct a new `Dual2`.
     #[new]
     pub fn new_py(real: f64, vars: Vec<String>, dual: Vec<f64>, dual2: Vec<f64>) -> PyResult<Self> {
-        Dual2::try_new(real, vars, dual, dual2)
+        Ok(Dual2::try_new(real, vars, dual, dual2)?)
     }
 
     /// Create a :class:`~rateslib.dual.Dual2` object with ``vars`` linked with another.
@@ -450,7 +494,7 @@ impl Dual2 {
         dual: Vec<f64>,
         dual2: Vec<f64>,
     ) -> PyResult<Self> {
-        Dual2::try_new_from(other, real, vars, dual, dual2)
+        Ok(Dual2::try_new_from(other, real, vars, dual, dual2)?)
     }
 
     #[getter]
@@ -467,13 +511,13 @@ impl Dual2 {
 
     #[getter]
     #[pyo3(name = "dual")]
-    fn dual_py<'py>(&'py self, py: Python<'py>) -> PyResult<Bound<'_, PyArray1<f64>>> {
-        Ok(self.dual.to_pyarray_bound(py))
+    fn dual_py<'py>(&'py self, py: Python<'py>) -> PyResult<Bound<'py, PyArray1<f64>>> {
+        Ok(self.dual.view().to_pyarray_bound(py))
     }
 
     #[getter]
     #[pyo3(name = "dual2")]
-    fn dual2_py<'py>(&'py self, py: Python<'py>) -> PyResult<Bound<'_, PyArray2<f64>>> {
+    fn dual2_py<'py>(&'py self, py: Python<'py>) -> PyResult<Bound<'py, PyArray2<f64>>> {
         Ok(self.dual2.to_pyarray_bound(py))
     }
 
@@ -482,7 +526,7 @@ impl Dual2 {
         &'py self,
         py: Python<'py>,
         vars: Vec<String>,
-    ) -> PyResult<Bound<'_, PyArray1<f64>>> {
+    ) -> PyResult<Bound<'py, PyArray1<f64>>> {
         Ok(self.gradient1(vars).to_pyarray_bound(py))
     }
 
@@ -491,7 +535,7 @@ impl Dual2 {
         &'py self,
         py: Python<'py>,
         vars: Vec<String>,
-    ) -> PyResult<Bound<'_, PyArray2<f64>>> {
+    ) -> PyResult<Bound<'py, PyArray2<f64>>> {
         Ok(self.gradient2(vars).to_pyarray_bound(py))
     }
 
@@ -505,6 +549,46 @@ impl Dual2 {
         Ok(out.into_raw_vec())
     }
 
+    /// Return the Euclidean norm of the first order gradients.
+    ///
+    /// Returns
+    /// -------
+    /// float
+    #[pyo3(name = "grad_norm")]
+    fn grad_norm_py(&self) -> PyResult<f64> {
+        Ok(self.grad_norm())
+    }
+
+    /// Return the largest absolute first order gradient.
+    ///
+    /// Returns
+    /// -------
+    /// float
+    #[pyo3(name = "max_abs_sensitivity")]
+    fn max_abs_sensitivity_py(&self) -> PyResult<f64> {
+        Ok(self.max_abs_sensitivity())
+    }
+
+    /// Return the variables with a non-zero first order gradient.
+    ///
+    /// Returns
+    /// -------
+    /// list[str]
+    #[pyo3(name = "nonzero_vars")]
+    fn nonzero_vars_py(&self) -> PyResult<Vec<String>> {
+        Ok(self.nonzero_vars())
+    }
+
+    /// Return the dual number without the variables of which every gradient is exactly zero.
+    ///
+    /// Returns
+    /// -------
+    /// Dual2
+    #[pyo3(name = "compress")]
+    fn compress_py(&self) -> Self {
+        self.compress()
+    }
+
     /// Evaluate if the ARC pointers of two `Dual2` data types are equivalent. See
     /// :meth:`~rateslib.dual.Dual.ptr_eq`.
     #[pyo3(name = "ptr_eq")]
@@ -663,7 +747,10 @@ impl Dual2 {
 
     fn __pow__(&self, power: Number, modulo: Option<i32>) -> PyResult<Self> {
         if modulo.unwrap_or(0) != 0 {
-            panic!("Power function with mod not available for Dual.")
+            return Err(RateslibError::Unsupported(
+                "Power function with mod not available for Dual.".to_string(),
+            )
+            .into());
         }
         match power {
             Number::F64(f) => Ok(self.clone().pow(f)),
@@ -724,12 +811,13 @@ impl Dual2 {
     fn __getstate__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
         Ok(PyBytes::new_bound(py, &serialize(&self).unwrap()))
     }
+    #[allow(clippy::type_complexity)]
     fn __getnewargs__(&self) -> PyResult<(f64, Vec<String>, Vec<f64>, Vec<f64>)> {
         Ok((
             self.real,
             self.vars().iter().cloned().collect(),
             self.dual.to_vec(),
-            self.dual2.clone().into_raw_vec(),
+            self.dual2.iter().cloned().collect(),
         ))
     }
 
@@ -1,11 +1,13 @@
 use crate::dual::{Dual, Dual2};
+use crate::error::RateslibError;
 use crate::splines::{PPSplineDual, PPSplineDual2, PPSplineF64};
 use ndarray::{Array1, Array2};
-use pyo3::{pyclass, FromPyObject, PyErr};
+#[cfg(feature = "python")]
+use pyo3::{pyclass, FromPyObject};
 use serde::{Deserialize, Serialize};
 
 /// Defines the order of gradients available in a calculation with AD.
-#[pyclass(module = "rateslib.rs")]
+#[cfg_attr(feature = "python", pyclass(module = "rateslib.rs"))]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub enum ADOrder {
     /// Floating point arithmetic only.
@@ -17,7 +19,11 @@ pub enum ADOrder {
 }
 
 /// Container for the three core numeric types; [f64], [Dual] and [Dual2].
-#[derive(Debug, Clone, FromPyObject, Serialize, Deserialize)]
+///
+/// Operators combining a [Dual] with a [Dual2] panic. The checked operations, such as
+/// [Number::try_add], raise instead.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "python", derive(FromPyObject))]
 pub enum Number {
     Dual(Dual),
     Dual2(Dual2),
@@ -61,5 +67,5 @@ pub enum NumberPPSpline {
 /// An example of this trait is used by certain [PPSpline] indicating that an x-value as
 /// some [Number] can be mapped under spline interpolation to some y-value as another [Number].
 pub trait NumberMapping {
-    fn mapped_value(&self, x: &Number) -> Result<Number, PyErr>;
+    fn mapped_value(&self, x: &Number) -> Result<Number, RateslibError>;
 }
@@ -0,0 +1,148 @@
+use crate::dual::dual::{Dual, Dual2, Gradient1};
+use crate::dual::enums::{ADOrder, Number};
+use indexmap::{IndexMap, IndexSet};
+use ndarray::ArrayView1;
+
+/// The kind of an [Exogenous] variable, which prefixes its tag.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ExogenousKind {
+    /// An FX rate, such as `"fx_eurusd"`, as tagged by [FXRates](crate::fx::rates::FXRates).
+    Fx,
+    /// A volatility, such as `"vol_atm_1y"`.
+    Vol,
+    /// A spread, such as `"spread_cds_5y"`.
+    Spread,
+    /// A correlation, such as `"corr_eurusd_usdjpy"`.
+    Correlation,
+}
+
+impl ExogenousKind {
+    fn prefix(&self) -> &'static str {
+        match self {
+            ExogenousKind::Fx => "fx_",
+            ExogenousKind::Vol => "vol_",
+            ExogenousKind::Spread => "spread_",
+            ExogenousKind::Correlation => "corr_",
+        }
+    }
+}
+
+/// A variable exogenous to the curves of a valuation, such as an FX rate or a volatility,
+/// which is injected into pricing inputs as a dual number to measure sensitivities to it.
+///
+/// # Examples
+///
+/// ```rust
+/// # use rateslib::dual::{ADOrder, Exogenous};
+/// let vol = Exogenous::vol("ATM_1Y");
+/// assert_eq!(vol.tag(), "vol_atm_1y");
+/// let x = vol.value(0.2, ADOrder::One);
+/// let premium = &x * &x * 50.0;
+/// assert!((vol.sensitivity(&premium) - 20.0).abs() < 1e-12);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Exogenous {
+    kind: ExogenousKind,
+    name: String,
+}
+
+impl Exogenous {
+    /// Create a variable of a `kind` identified by `name`, which is lowercased.
+    pub fn new(kind: ExogenousKind, name: &str) -> Self {
+        Self {
+            kind,
+            name: name.to_lowercase(),
+        }
+    }
+
+    pub fn fx(pair: &str) -> Self {
+        Self::new(ExogenousKind::Fx, pair)
+    }
+
+    pub fn vol(name: &str) -> Self {
+        Self::new(ExogenousKind::Vol, name)
+    }
+
+    pub fn spread(name: &str) -> Self {
+        Self::new(ExogenousKind::Spread, name)
+    }
+
+    pub fn correlation(name: &str) -> Self {
+        Self::new(ExogenousKind::Correlation, name)
+    }
+
+    pub fn kind(&self) -> ExogenousKind {
+        self.kind
+    }
+
+    /// Return the tag of the variable in the `vars` of a dual number.
+    pub fn tag(&self) -> String {
+        format!("{}{}", self.kind.prefix(), self.name)
+    }
+
+    /// Return the `real` value of the variable at the `ad` order, with a unit gradient to
+    /// itself.
+    pub fn value(&self, real: f64, ad: ADOrder) -> Number {
+        match ad {
+            ADOrder::Zero => Number::F64(real),
+            ADOrder::One => Number::Dual(Dual::new(real, vec![self.tag()])),
+            ADOrder::Two => Number::Dual2(Dual2::new(real, vec![self.tag()])),
+        }
+    }
+
+    /// Return the first order sensitivity of a `value` to the variable, which is zero if the
+    /// value does not depend on it.
+    pub fn sensitivity(&self, value: &Number) -> f64 {
+        match value {
+            Number::F64(_) => 0.0,
+            Number::Dual(d) => d.gradient1(vec![self.tag()])[0],
+            Number::Dual2(d) => d.gradient1(vec![self.tag()])[0],
+        }
+    }
+}
+
+/// Return the first order sensitivities of a `value` to each of its variables of a `kind`,
+/// keyed by tag in the order of the `vars` of the value.
+pub fn exogenous_sensitivities(value: &Number, kind: ExogenousKind) -> IndexMap<String, f64> {
+    let sensitivities = |vars: &IndexSet<String>, dual: ArrayView1<f64>| {
+        vars.iter()
+            .zip(dual.iter())
+            .filter(|(v, _)| v.starts_with(kind.prefix()))
+            .map(|(v, x)| (v.clone(), *x))
+            .collect()
+    };
+    match value {
+        Number::F64(_) => IndexMap::new(),
+        Number::Dual(d) => sensitivities(&d.vars, d.dual_view()),
+        Number::Dual2(d) => sensitivities(&d.vars, d.dual_view()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exogenous_sensitivities() {
+        let (eurusd, usdjpy) = (Exogenous::fx("EURUSD"), Exogenous::fx("usdjpy"));
+        let vol = Exogenous::vol("atm_1y");
+        let value = eurusd.value(1.1, ADOrder::Two) * 2.0
+            + usdjpy.value(150.0, ADOrder::Two)
+            + vol.value(0.1, ADOrder::Two) * 3.0;
+        assert_eq!(eurusd.sensitivity(&value), 2.0);
+        assert_eq!(Exogenous::spread("cds_5y").sensitivity(&value), 0.0);
+        let fx = exogenous_sensitivities(&value, ExogenousKind::Fx);
+        assert_eq!(
+            fx,
+            IndexMap::<String, f64>::from_iter([
+                ("fx_eurusd".to_string(), 2.0),
+                ("fx_usdjpy".to_string(), 1.0)
+            ])
+        );
+        assert_eq!(
+            exogenous_sensitivities(&value, ExogenousKind::Vol)["vol_atm_1y"],
+            3.0
+        );
+        assert_eq!(vol.value(0.1, ADOrder::Zero), Number::F64(0.1));
+    }
+}
@@ -0,0 +1,208 @@
+use crate::dual::{MathFuncs, NumberOps};
+use crate::error::RateslibError;
+use auto_ops::{impl_op_ex, impl_op_ex_commutative};
+use serde::{Deserialize, Serialize};
+
+/// A closed interval of real numbers which bounds the result of a calculation.
+///
+/// Every operation rounds its lower bound down and its upper bound up by one unit in the
+/// last place, so that the interval contains the exact result of the operations applied to
+/// any values within its operands. A pricing pass with [Interval] inputs therefore bounds the
+/// accumulated rounding error, and an input interval of `value ± tolerance` propagates the
+/// tolerance of, for example, a node or an interpolated value. Bounds are conservative,
+/// since a variable appearing more than once in an expression is treated as independent
+/// values. Division by an interval containing zero yields the unbounded interval.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Interval {
+    lo: f64,
+    hi: f64,
+}
+
+impl Interval {
+    /// Create an [Interval] between `lo` and `hi`.
+    pub fn try_new(lo: f64, hi: f64) -> Result<Self, RateslibError> {
+        if lo.is_nan() || hi.is_nan() || lo > hi {
+            return Err(RateslibError::value(
+                "`lo` of an `Interval` must not be greater than `hi`.",
+            ));
+        }
+        Ok(Self { lo, hi })
+    }
+
+    /// Create an [Interval] containing only `value`.
+    pub fn point(value: f64) -> Self {
+        Self {
+            lo: value,
+            hi: value,
+        }
+    }
+
+    /// Create an [Interval] of `value` plus or minus an absolute `tolerance`.
+    pub fn around(value: f64, tolerance: f64) -> Self {
+        Self::outward(value - tolerance.abs(), value + tolerance.abs())
+    }
+
+    /// The interval with bounds widened by one unit in the last place.
+    fn outward(lo: f64, hi: f64) -> Self {
+        Self {
+            lo: lo.next_down(),
+            hi: hi.next_up(),
+        }
+    }
+
+    /// Return the unbounded interval.
+    fn entire() -> Self {
+        Self {
+            lo: f64::NEG_INFINITY,
+            hi: f64::INFINITY,
+        }
+    }
+
+    pub fn lo(&self) -> f64 {
+        self.lo
+    }
+
+    pub fn hi(&self) -> f64 {
+        self.hi
+    }
+
+    /// Return the midpoint of the interval.
+    pub fn mid(&self) -> f64 {
+        self.lo + (self.hi - self.lo) / 2.0
+    }
+
+    /// Return the width of the interval, which bounds the error of its midpoint to twice
+    /// its value.
+    pub fn width(&self) -> f64 {
+        self.hi - self.lo
+    }
+
+    /// Return whether the interval contains a `value`.
+    pub fn contains(&self, value: f64) -> bool {
+        self.lo <= value && value <= self.hi
+    }
+
+    fn from_bounds(values: [f64; 4]) -> Self {
+        let lo = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let hi = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        Self::outward(lo, hi)
+    }
+
+    /// Apply a monotonically increasing function.
+    fn increasing(&self, f: impl Fn(f64) -> f64) -> Self {
+        Self::outward(f(self.lo), f(self.hi))
+    }
+}
+
+impl From<f64> for Interval {
+    fn from(value: f64) -> Self {
+        Interval::point(value)
+    }
+}
+
+impl_op_ex!(+ |a: &Interval, b: &Interval| -> Interval { Interval::outward(a.lo + b.lo, a.hi + b.hi) });
+impl_op_ex!(-|a: &Interval, b: &Interval| -> Interval {
+    Interval::outward(a.lo - b.hi, a.hi - b.lo)
+});
+impl_op_ex!(*|a: &Interval, b: &Interval| -> Interval {
+    Interval::from_bounds([a.lo * b.lo, a.lo * b.hi, a.hi * b.lo, a.hi * b.hi])
+});
+impl_op_ex!(/ |a: &Interval, b: &Interval| -> Interval {
+    if b.contains(0.0) {
+        return Interval::entire();
+    }
+    Interval::from_bounds([a.lo / b.lo, a.lo / b.hi, a.hi / b.lo, a.hi / b.hi])
+});
+impl_op_ex!(-|a: &Interval| -> Interval {
+    Interval {
+        lo: -a.hi,
+        hi: -a.lo,
+    }
+});
+
+impl_op_ex_commutative!(+ |a: &Interval, b: &f64| -> Interval { a + Interval::point(*b) });
+impl_op_ex_commutative!(*|a: &Interval, b: &f64| -> Interval { a * Interval::point(*b) });
+impl_op_ex!(-|a: &Interval, b: &f64| -> Interval { a - Interval::point(*b) });
+impl_op_ex!(-|a: &f64, b: &Interval| -> Interval { Interval::point(*a) - b });
+impl_op_ex!(/ |a: &Interval, b: &f64| -> Interval { a / Interval::point(*b) });
+impl_op_ex!(/ |a: &f64, b: &Interval| -> Interval { Interval::point(*a) / b });
+
+impl NumberOps<Interval> for Interval {}
+
+impl MathFuncs for Interval {
+    fn exp(&self) -> Self {
+        self.increasing(f64::exp)
+    }
+
+    /// Return the natural logarithm, which is unbounded below if the interval contains zero
+    /// and undefined, NaN, for negative values.
+    fn log(&self) -> Self {
+        self.increasing(f64::ln)
+    }
+
+    fn norm_cdf(&self) -> Self {
+        let i = self.increasing(|x| x.norm_cdf());
+        Interval {
+            lo: i.lo.max(0.0),
+            hi: i.hi.min(1.0),
+        }
+    }
+
+    fn inv_norm_cdf(&self) -> Self {
+        self.increasing(|x| x.inv_norm_cdf())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curves::interpolation::utils::log_linear_interp;
+
+    #[test]
+    fn test_interval_ops() {
+        let a = Interval::try_new(1.0, 2.0).unwrap();
+        let b = Interval::try_new(-3.0, 0.5).unwrap();
+        let c = a * b;
+        assert!(c.contains(-6.0) && c.contains(1.0) && !c.contains(1.1));
+        let d = a - b;
+        assert!(d.contains(0.5) && d.contains(5.0));
+        assert_eq!(a / b, Interval::entire());
+        assert!((1.0 / &a).contains(0.5));
+        assert_eq!(-a, Interval::try_new(-2.0, -1.0).unwrap());
+        assert!(Interval::try_new(2.0, 1.0).is_err());
+    }
+
+    #[test]
+    fn test_rounding_error_is_bounded() {
+        // 0.1 summed ten times is not exactly 1.0 in f64
+        let mut f = 0.0_f64;
+        let mut i = Interval::point(0.0);
+        for _ in 0..10 {
+            f += 0.1;
+            i = i + 0.1;
+        }
+        assert_ne!(f, 1.0);
+        assert!(i.contains(f) && i.contains(1.0));
+        assert!(i.width() < 1e-14);
+    }
+
+    #[test]
+    fn test_math_funcs() {
+        let x = Interval::around(0.5, 0.01);
+        assert!(x.exp().contains(0.49_f64.exp()) && x.exp().contains(0.51_f64.exp()));
+        assert!(x.log().contains(0.5_f64.ln()));
+        let p = x.inv_norm_cdf().norm_cdf();
+        assert!(p.contains(0.49) && p.contains(0.51));
+    }
+
+    #[test]
+    fn test_generic_interpolation() {
+        // the interpolated discount factor bounds the effect of the node tolerances
+        let (y1, y2) = (Interval::around(0.99, 1e-8), Interval::around(0.95, 1e-8));
+        let y = log_linear_interp(0.0, &y1, 10.0, &y2, 4.0);
+        let f = log_linear_interp(0.0, &0.99_f64, 10.0, &0.95_f64, 4.0);
+        assert!(y.contains(f));
+        // `y1` appears twice in the interpolation so its tolerance is overestimated
+        assert!(y.width() > 2e-8 && y.width() < 4e-8);
+    }
+}
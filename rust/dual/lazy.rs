@@ -0,0 +1,405 @@
+use crate::dual::pool::{zeros2, Recycle};
+use crate::dual::small::SmallArray1;
+use ndarray::Array2;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cell::Cell;
+use std::ops::{Add, Deref, DerefMut, Mul, Neg, Sub};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// The evaluation of the second order gradients of a [Dual2](crate::dual::Dual2) created by
+/// an operation.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum HessianMode {
+    /// The `n x n` array of second order gradients is calculated by every operation.
+    #[default]
+    Eager,
+    /// Operations record their second order gradients as a combination of those of their
+    /// operands and of rank one updates, which is only evaluated when the array is read, so
+    /// that intermediate values consumed by further operations never build their array.
+    Lazy,
+}
+
+static GLOBAL_MODE: AtomicU8 = AtomicU8::new(0);
+
+thread_local! {
+    static SCOPED_MODE: Cell<Option<HessianMode>> = const { Cell::new(None) };
+}
+
+impl HessianMode {
+    fn to_u8(self) -> u8 {
+        match self {
+            HessianMode::Eager => 0,
+            HessianMode::Lazy => 1,
+        }
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => HessianMode::Lazy,
+            _ => HessianMode::Eager,
+        }
+    }
+}
+
+/// Set the [HessianMode] used by all threads.
+pub fn set_hessian_mode(mode: HessianMode) {
+    GLOBAL_MODE.store(mode.to_u8(), Ordering::Relaxed)
+}
+
+/// Return the [HessianMode] in effect on the current thread.
+pub fn hessian_mode() -> HessianMode {
+    SCOPED_MODE
+        .with(|m| m.get())
+        .unwrap_or_else(|| HessianMode::from_u8(GLOBAL_MODE.load(Ordering::Relaxed)))
+}
+
+/// Evaluate `f` with a [HessianMode] on the current thread, overriding the global mode.
+pub fn with_hessian_mode<R>(mode: HessianMode, f: impl FnOnce() -> R) -> R {
+    let _restore = Restore(SCOPED_MODE.with(|m| m.replace(Some(mode))));
+    f()
+}
+
+/// Restores the mode of the thread when an evaluation ends, including by a panic.
+struct Restore(Option<HessianMode>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        SCOPED_MODE.with(|m| m.set(self.0));
+    }
+}
+
+/// The depth of unevaluated operations beyond which an array is evaluated on creation.
+const MAX_DEPTH: usize = 64;
+
+#[derive(Default)]
+struct Expr {
+    terms: Vec<(f64, LazyArray2)>,
+    outers: Vec<(f64, SmallArray1, SmallArray1)>,
+}
+
+struct Node {
+    n: usize,
+    depth: usize,
+    value: OnceLock<Array2<f64>>,
+    expr: Mutex<Expr>,
+}
+
+/// A square array of second order gradients, either evaluated or recorded as the sum of
+/// scaled arrays and rank one updates `c u v^T`.
+///
+/// The array dereferences to an [Array2], evaluating and caching a recorded expression on
+/// first access. Cloning shares the array.
+#[derive(Clone)]
+pub(crate) struct LazyArray2 {
+    inner: Arc<Node>,
+}
+
+impl LazyArray2 {
+    fn dense(a: Array2<f64>) -> Self {
+        Self {
+            inner: Arc::new(Node {
+                n: a.nrows(),
+                depth: 0,
+                value: OnceLock::from(a),
+                expr: Mutex::new(Expr::default()),
+            }),
+        }
+    }
+
+    /// Return an `n x n` array of zeros.
+    pub(crate) fn zeros(n: usize) -> Self {
+        match hessian_mode() {
+            HessianMode::Eager => Self::dense(zeros2(n)),
+            HessianMode::Lazy => Self {
+                inner: Arc::new(Node {
+                    n,
+                    depth: 0,
+                    value: OnceLock::new(),
+                    expr: Mutex::new(Expr::default()),
+                }),
+            },
+        }
+    }
+
+    /// Return `sum(c * a) + sum(c * u v^T)` over the `terms` and `outers` respectively, of
+    /// dimension `n`.
+    pub(crate) fn combine(
+        n: usize,
+        terms: &[(f64, &LazyArray2)],
+        outers: &[(f64, &SmallArray1, &SmallArray1)],
+    ) -> Self {
+        match hessian_mode() {
+            HessianMode::Eager => {
+                let mut out = zeros2(n);
+                terms.iter().for_each(|(c, a)| out.scaled_add(*c, &***a));
+                outers
+                    .iter()
+                    .for_each(|(c, u, v)| add_outer(&mut out, *c, u, v));
+                Self::dense(out)
+            }
+            HessianMode::Lazy => {
+                let depth = 1 + terms.iter().map(|(_, a)| a.depth()).max().unwrap_or(0);
+                let lazy = Self {
+                    inner: Arc::new(Node {
+                        n,
+                        depth,
+                        value: OnceLock::new(),
+                        expr: Mutex::new(Expr {
+                            terms: terms.iter().map(|(c, a)| (*c, (*a).clone())).collect(),
+                            outers: outers
+                                .iter()
+                                .map(|(c, u, v)| (*c, (*u).clone(), (*v).clone()))
+                                .collect(),
+                        }),
+                    }),
+                };
+                if depth > MAX_DEPTH {
+                    let _ = lazy.evaluate();
+                }
+                lazy
+            }
+        }
+    }
+
+    /// Return whether the array has been evaluated.
+    pub(crate) fn is_evaluated(&self) -> bool {
+        self.inner.value.get().is_some()
+    }
+
+    fn depth(&self) -> usize {
+        match self.is_evaluated() {
+            true => 0,
+            false => self.inner.depth,
+        }
+    }
+
+    fn evaluate(&self) -> &Array2<f64> {
+        self.inner.value.get_or_init(|| {
+            let expr = std::mem::take(&mut *self.inner.expr.lock().unwrap());
+            let mut out = zeros2(self.inner.n);
+            accumulate_expr(expr, 1.0, &mut out);
+            out
+        })
+    }
+
+    /// Add `c` times the array to `out`, consuming the recorded expression of an array which
+    /// is not shared with any other value.
+    fn accumulate(&self, c: f64, out: &mut Array2<f64>) {
+        if let Some(a) = self.inner.value.get() {
+            out.scaled_add(c, a);
+        } else if Arc::strong_count(&self.inner) > 1 {
+            out.scaled_add(c, self.evaluate());
+        } else {
+            let expr = std::mem::take(&mut *self.inner.expr.lock().unwrap());
+            accumulate_expr(expr, c, out);
+        }
+    }
+}
+
+fn accumulate_expr(expr: Expr, c: f64, out: &mut Array2<f64>) {
+    for (ci, a) in expr.terms.iter() {
+        a.accumulate(c * ci, out);
+    }
+    for (ci, u, v) in expr.outers.iter() {
+        add_outer(out, c * ci, u, v);
+    }
+}
+
+/// Perform `out += c u v^T` in place.
+fn add_outer(out: &mut Array2<f64>, c: f64, u: &SmallArray1, v: &SmallArray1) {
+    let (u, v) = (u.as_slice(), v.as_slice());
+    for (i, ui) in u.iter().enumerate() {
+        let cu = c * ui;
+        for (j, vj) in v.iter().enumerate() {
+            out[[i, j]] += cu * vj;
+        }
+    }
+}
+
+impl Default for LazyArray2 {
+    fn default() -> Self {
+        Self::dense(Array2::zeros((0, 0)))
+    }
+}
+
+impl From<Array2<f64>> for LazyArray2 {
+    fn from(a: Array2<f64>) -> Self {
+        Self::dense(a)
+    }
+}
+
+impl Deref for LazyArray2 {
+    type Target = Array2<f64>;
+
+    fn deref(&self) -> &Array2<f64> {
+        self.evaluate()
+    }
+}
+
+impl DerefMut for LazyArray2 {
+    fn deref_mut(&mut self) -> &mut Array2<f64> {
+        if Arc::get_mut(&mut self.inner).is_none() {
+            *self = Self::dense(self.evaluate().clone());
+        }
+        let _ = self.evaluate();
+        let node = Arc::get_mut(&mut self.inner).expect("Uniquely owned above");
+        node.value.get_mut().expect("Evaluated above")
+    }
+}
+
+impl Recycle for LazyArray2 {
+    fn into_buffer(self) -> Option<Vec<f64>> {
+        let node = Arc::into_inner(self.inner)?;
+        node.value.into_inner()?.into_buffer()
+    }
+}
+
+impl PartialEq for LazyArray2 {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl PartialEq<Array2<f64>> for LazyArray2 {
+    fn eq(&self, other: &Array2<f64>) -> bool {
+        **self == *other
+    }
+}
+
+impl std::fmt::Debug for LazyArray2 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+impl Serialize for LazyArray2 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (**self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for LazyArray2 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self::dense(Array2::<f64>::deserialize(deserializer)?))
+    }
+}
+
+impl Add<&LazyArray2> for &LazyArray2 {
+    type Output = LazyArray2;
+    fn add(self, rhs: &LazyArray2) -> LazyArray2 {
+        LazyArray2::combine(self.inner.n, &[(1.0, self), (1.0, rhs)], &[])
+    }
+}
+
+impl Add<&LazyArray2> for LazyArray2 {
+    type Output = LazyArray2;
+    fn add(self, rhs: &LazyArray2) -> LazyArray2 {
+        &self + rhs
+    }
+}
+
+impl Sub<&LazyArray2> for &LazyArray2 {
+    type Output = LazyArray2;
+    fn sub(self, rhs: &LazyArray2) -> LazyArray2 {
+        LazyArray2::combine(self.inner.n, &[(1.0, self), (-1.0, rhs)], &[])
+    }
+}
+
+impl Sub<&LazyArray2> for LazyArray2 {
+    type Output = LazyArray2;
+    fn sub(self, rhs: &LazyArray2) -> LazyArray2 {
+        &self - rhs
+    }
+}
+
+impl Mul<f64> for &LazyArray2 {
+    type Output = LazyArray2;
+    fn mul(self, rhs: f64) -> LazyArray2 {
+        LazyArray2::combine(self.inner.n, &[(rhs, self)], &[])
+    }
+}
+
+impl Mul<f64> for LazyArray2 {
+    type Output = LazyArray2;
+    fn mul(self, rhs: f64) -> LazyArray2 {
+        &self * rhs
+    }
+}
+
+impl Mul<&LazyArray2> for f64 {
+    type Output = LazyArray2;
+    fn mul(self, rhs: &LazyArray2) -> LazyArray2 {
+        rhs * self
+    }
+}
+
+impl Neg for LazyArray2 {
+    type Output = LazyArray2;
+    fn neg(self) -> LazyArray2 {
+        &self * -1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dual::{Dual2, Gradient2, MathFuncs};
+    use ndarray::arr2;
+
+    fn x(name: &str, value: f64) -> Dual2 {
+        Dual2::new(value, vec![name.to_string()])
+    }
+
+    fn calculation() -> Dual2 {
+        let (a, b) = (x("a", 1.5), x("b", 2.0));
+        let mut z = &a * &b;
+        for _ in 0..100 {
+            z = &(&z * &a).log() + &(&b * 0.5).exp();
+        }
+        z
+    }
+
+    #[test]
+    fn test_lazy_matches_eager() {
+        let eager = with_hessian_mode(HessianMode::Eager, calculation);
+        let lazy = with_hessian_mode(HessianMode::Lazy, calculation);
+        assert!(!lazy.dual2.is_evaluated());
+        let (e, l) = (eager.dual2(), lazy.dual2());
+        assert!(lazy.dual2.is_evaluated());
+        e.iter()
+            .zip(l.iter())
+            .for_each(|(a, b)| assert!((a - b).abs() < 1e-12));
+        assert_eq!(eager.dual, lazy.dual);
+    }
+
+    #[test]
+    fn test_shared_operands() {
+        let z = with_hessian_mode(HessianMode::Lazy, || {
+            let a = x("a", 2.0);
+            let a2 = &a * &a;
+            &a2 * &a2
+        });
+        // d2(a^4) / da2 = 12 a^2
+        assert_eq!(z.gradient2(vec!["a".to_string()]), arr2(&[[48.0]]));
+    }
+
+    #[test]
+    fn test_mutation_of_shared_array() {
+        let a = with_hessian_mode(HessianMode::Lazy, || &x("a", 2.0) * &x("a", 2.0));
+        let mut b = a.clone();
+        b.dual2[[0, 0]] = 5.0;
+        assert_eq!(a.dual2[[0, 0]], 1.0);
+        assert_eq!(b.dual2[[0, 0]], 5.0);
+    }
+
+    #[test]
+    fn test_mode_restored_after_panic() {
+        let before = hessian_mode();
+        let result = std::panic::catch_unwind(|| {
+            with_hessian_mode(HessianMode::Lazy, || panic!("the calculation failed"))
+        });
+        assert!(result.is_err());
+        assert_eq!(hessian_mode(), before);
+    }
+}
@@ -451,6 +451,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::needless_borrow)]
     fn dsolve_dual() {
         let a: Array2<Dual> = Array2::eye(2);
         let b: Array1<Dual> = arr1(&[
@@ -201,6 +201,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::needless_borrow)]
     fn fdsolve_dual() {
         let a: Array2<f64> = Array2::eye(2);
         let b: Array1<Dual> = arr1(&[
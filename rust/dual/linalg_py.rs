@@ -2,6 +2,7 @@
 
 use crate::dual::dual::{Dual, Dual2};
 use crate::dual::linalg::{dsolve, fdsolve};
+use crate::error::RateslibError;
 use ndarray::{Array1, ArrayView2};
 use num_traits::identities::Zero;
 use num_traits::Signed;
@@ -11,7 +12,19 @@ use std::cmp::PartialOrd;
 use std::iter::Sum;
 use std::ops::{Div, Mul, Sub};
 
-fn dsolve_py<T>(a: Vec<T>, b: Vec<T>, allow_lsq: bool) -> Vec<T>
+/// Return an error unless `a` has a row for each element of `b` and is square, or is
+/// overdetermined when `allow_lsq`.
+fn check_shape(rows: usize, cols: usize, b: usize, allow_lsq: bool) -> Result<(), RateslibError> {
+    if rows != b || cols == 0 || (rows != cols && !(allow_lsq && rows > cols)) {
+        return Err(RateslibError::Shape(format!(
+            "Inputs `a` of shape ({}, {}) and `b` of length {} for dual solve were incorrect shapes.",
+            rows, cols, b
+        )));
+    }
+    Ok(())
+}
+
+fn dsolve_py<T>(a: Vec<T>, b: Vec<T>, allow_lsq: bool) -> Result<Vec<T>, RateslibError>
 where
     T: PartialOrd + Signed + Clone + Sum + Zero,
     for<'a> &'a T: Sub<&'a T, Output = T> + Mul<&'a T, Output = T> + Div<&'a T, Output = T>,
@@ -20,12 +33,18 @@ where
     // &'py PyArray1<Dual>
     let a1 = Array1::from_vec(a);
     let b_ = Array1::from_vec(b);
-    let (r, c) = (a1.len() / b_.len(), b_.len());
+    if b_.is_empty() || a1.len() % b_.len() != 0 {
+        return Err(RateslibError::Shape(
+            "Inputs `a` and `b` for dual solve were incorrect shapes.".to_string(),
+        ));
+    }
+    let (r, c) = (b_.len(), a1.len() / b_.len());
+    check_shape(r, c, b_.len(), allow_lsq)?;
     let a2 = a1
         .into_shape((r, c))
-        .expect("Inputs `a` and `b` for dual solve were incorrect shapes");
+        .map_err(|e| RateslibError::Shape(e.to_string()))?;
     let out = dsolve(&a2.view(), &b_.view(), allow_lsq);
-    out.into_raw_vec()
+    Ok(out.into_raw_vec())
 }
 
 /// Wrapper to solve ax = b, when `a` and `b` contain `Dual` data types.
@@ -37,7 +56,7 @@ pub fn dsolve1_py(
     b: Vec<Dual>,
     allow_lsq: bool,
 ) -> PyResult<Vec<Dual>> {
-    Ok(dsolve_py(a, b, allow_lsq))
+    Ok(dsolve_py(a, b, allow_lsq)?)
 }
 
 /// Wrapper to solve ax = b, when `a` and `b` contain `Dual2` data types.
@@ -49,18 +68,20 @@ pub fn dsolve2_py(
     b: Vec<Dual2>,
     allow_lsq: bool,
 ) -> PyResult<Vec<Dual2>> {
-    Ok(dsolve_py(a, b, allow_lsq))
+    Ok(dsolve_py(a, b, allow_lsq)?)
 }
 
-fn fdsolve_py<T>(a: ArrayView2<f64>, b: Vec<T>, allow_lsq: bool) -> Vec<T>
+fn fdsolve_py<T>(a: ArrayView2<f64>, b: Vec<T>, allow_lsq: bool) -> Result<Vec<T>, RateslibError>
 where
     T: PartialOrd + Signed + Clone + Sum + Zero,
     for<'a> &'a T: Sub<&'a T, Output = T>,
     for<'a> &'a f64: Mul<&'a T, Output = T>,
 {
+    let (r, c) = a.dim();
+    check_shape(r, c, b.len(), allow_lsq)?;
     let b_ = Array1::from_vec(b);
     let out = fdsolve(&a.view(), &b_.view(), allow_lsq);
-    out.into_raw_vec()
+    Ok(out.into_raw_vec())
 }
 
 /// Wrapper to solve ax = b, when `b` contains `Dual` data types.
@@ -72,7 +93,7 @@ pub fn fdsolve1_py(
     b: Vec<Dual>,
     allow_lsq: bool,
 ) -> PyResult<Vec<Dual>> {
-    unsafe { Ok(fdsolve_py(a.as_array(), b, allow_lsq)) }
+    unsafe { Ok(fdsolve_py(a.as_array(), b, allow_lsq)?) }
 }
 
 /// Wrapper to solve ax = b, when `b` contains `Dual2` data types.
@@ -84,5 +105,5 @@ pub fn fdsolve2_py(
     b: Vec<Dual2>,
     allow_lsq: bool,
 ) -> PyResult<Vec<Dual2>> {
-    unsafe { Ok(fdsolve_py(a.as_array(), b, allow_lsq)) }
+    unsafe { Ok(fdsolve_py(a.as_array(), b, allow_lsq)?) }
 }
@@ -12,20 +12,77 @@
 //!
 //! Mathematical operations are defined to give dual numbers the ability to combine, and
 //! flexibly reference different variables at any point during calculations.
+//! The ordering of combined variables follows the order of operations unless a canonical,
+//! sorted, [VarsOrdering] is set.
+//! Gradients with respect to a small number of variables are stored inline, and the
+//! intermediate arrays of a calculation may be reused with [with_buffer_pool]. The second
+//! order gradients of a [Dual2] may be evaluated only when read with a lazy [HessianMode].
+//!
+//! First order derivatives at other precisions, [f32] or the extended [DoubleDouble], are
+//! available with the generic [DualR], which converts to and from [Dual] and [Number].
+//! An [Interval] implements the same operations to bound the rounding error of a calculation.
+//! Types of other crates implementing these operations and [MathFuncs] are a [Scalar].
+//! Functions with kinks or jumps are defined with a [Piecewise] and explicit [BreakpointPolicy].
+//! Sums of many numbers may be accumulated in a fixed, reproducible order with a [Reduction].
+//! Variables exogenous to curves, such as FX rates and volatilities, are tagged by [Exogenous].
+//! The gradients of very deep sequential computations may be [checkpointed] by segment.
+//! Arrays of [Number] are compared and reduced, retaining gradients, with [ArrayNumber].
 //!
 
 pub mod docs;
 
+#[allow(clippy::module_inception)]
 mod dual;
 pub use crate::dual::dual::{
-    set_order, set_order_clone, Dual, Dual2, Gradient1, Gradient2, MathFuncs, NumberOps, Vars,
-    VarsRelationship,
+    set_order, set_order_clone, ApproxEq, ApproxMismatch, Dual, Dual2, FastMathFuncs, Gradient1,
+    Gradient2, MathFuncs, NumberOps, SmoothFuncs, TopK, Vars, VarsRelationship, DISPLAY_TOP_K,
 };
 
 mod dual_ops;
+
+mod ordering;
+pub use crate::dual::ordering::{
+    set_vars_ordering, vars_ordering, with_vars_ordering, VarsOrdering,
+};
+
+mod small;
+
+mod lazy;
+pub use crate::dual::lazy::{hessian_mode, set_hessian_mode, with_hessian_mode, HessianMode};
+
+mod pool;
+pub use crate::dual::pool::{with_buffer_pool, PoolStats};
+
+mod precision;
+pub use crate::dual::precision::{DoubleDouble, Dual32, DualDD, DualR, Real};
+
+mod interval;
+pub use crate::dual::interval::Interval;
+
+mod scalar;
+pub use crate::dual::scalar::Scalar;
+
+mod piecewise;
+pub use crate::dual::piecewise::{BreakpointPolicy, Piecewise};
+
+mod array;
+pub use crate::dual::array::{ArrayNumber, TiePolicy};
+
+mod checkpoint;
+pub use crate::dual::checkpoint::checkpointed;
+
+mod exogenous;
+pub use crate::dual::exogenous::{exogenous_sensitivities, Exogenous, ExogenousKind};
+
+mod reduction;
+pub(crate) use crate::dual::reduction::compensated_f64;
+pub use crate::dual::reduction::Reduction;
+
+#[cfg(feature = "python")]
 pub(crate) mod dual_py;
 
 pub mod linalg;
+#[cfg(feature = "python")]
 pub(crate) mod linalg_py;
 
 mod enums;
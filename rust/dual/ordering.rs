@@ -0,0 +1,109 @@
+use std::cell::Cell;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// The ordering of the `vars` of a dual number created by combining two dual numbers with
+/// differing `vars`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum VarsOrdering {
+    /// The `vars` of the left operand followed by any new `vars` of the right operand, so
+    /// that the ordering depends upon the order of operations.
+    #[default]
+    Insertion,
+    /// The `vars` sorted lexicographically, so that gradients and their serialization are
+    /// reproducible regardless of the order of operations or the scheduling of threads.
+    Sorted,
+}
+
+static GLOBAL_ORDERING: AtomicU8 = AtomicU8::new(0);
+
+thread_local! {
+    static SCOPED_ORDERING: Cell<Option<VarsOrdering>> = const { Cell::new(None) };
+}
+
+impl VarsOrdering {
+    fn to_u8(self) -> u8 {
+        match self {
+            VarsOrdering::Insertion => 0,
+            VarsOrdering::Sorted => 1,
+        }
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => VarsOrdering::Sorted,
+            _ => VarsOrdering::Insertion,
+        }
+    }
+}
+
+/// Set the [VarsOrdering] used by all threads.
+pub fn set_vars_ordering(ordering: VarsOrdering) {
+    GLOBAL_ORDERING.store(ordering.to_u8(), Ordering::Relaxed)
+}
+
+/// Return the [VarsOrdering] in effect on the current thread.
+pub fn vars_ordering() -> VarsOrdering {
+    SCOPED_ORDERING
+        .with(|o| o.get())
+        .unwrap_or_else(|| VarsOrdering::from_u8(GLOBAL_ORDERING.load(Ordering::Relaxed)))
+}
+
+/// Evaluate `f` with a [VarsOrdering] on the current thread, overriding the global ordering.
+pub fn with_vars_ordering<R>(ordering: VarsOrdering, f: impl FnOnce() -> R) -> R {
+    let _restore = Restore(SCOPED_ORDERING.with(|o| o.replace(Some(ordering))));
+    f()
+}
+
+/// Restores the ordering of the thread when an evaluation ends, including by a panic.
+struct Restore(Option<VarsOrdering>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        SCOPED_ORDERING.with(|o| o.set(self.0));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dual::{Dual, Gradient1, Vars};
+
+    fn x(name: &str, value: f64) -> Dual {
+        Dual::new(value, vec![name.to_string()])
+    }
+
+    #[test]
+    fn test_insertion_ordering() {
+        let z = with_vars_ordering(VarsOrdering::Insertion, || x("b", 1.0) + x("a", 2.0));
+        assert_eq!(z.vars().iter().collect::<Vec<_>>(), vec!["b", "a"]);
+    }
+
+    #[test]
+    fn test_sorted_ordering_is_canonical() {
+        with_vars_ordering(VarsOrdering::Sorted, || {
+            let (a, b, c) = (x("a", 1.0), x("b", 2.0), x("c", 3.0));
+            let z1 = &(&c * &a) + &b;
+            let z2 = &b + &(&a * &c);
+            assert_eq!(z1.vars().iter().collect::<Vec<_>>(), vec!["a", "b", "c"]);
+            assert_eq!(z1.vars(), z2.vars());
+            assert_eq!(z1.dual(), z2.dual());
+            assert_eq!(z1.gradient1(vec!["c".to_string()])[0], 1.0);
+            // permuted superset vars are also reordered
+            let p = Dual::new(1.0, vec!["y".to_string(), "x".to_string()]);
+            let q = Dual::new(1.0, vec!["x".to_string(), "y".to_string()]) * 2.0;
+            let r = &p + &q;
+            assert_eq!(r.vars().iter().collect::<Vec<_>>(), vec!["x", "y"]);
+            assert_eq!(r.dual().to_vec(), vec![3.0, 3.0]);
+        });
+        assert_eq!(vars_ordering(), VarsOrdering::Insertion);
+    }
+
+    #[test]
+    fn test_ordering_restored_after_panic() {
+        let result = std::panic::catch_unwind(|| {
+            with_vars_ordering(VarsOrdering::Sorted, || panic!("the calculation failed"))
+        });
+        assert!(result.is_err());
+        assert_eq!(vars_ordering(), VarsOrdering::Insertion);
+    }
+}
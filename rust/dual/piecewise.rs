@@ -0,0 +1,165 @@
+use crate::dual::enums::Number;
+use crate::error::RateslibError;
+use serde::{Deserialize, Serialize};
+
+/// The piece, and therefore derivative, a [Piecewise] function takes at a breakpoint.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BreakpointPolicy {
+    /// Evaluate the piece to the left of the breakpoint.
+    Left,
+    /// Evaluate the piece to the right of the breakpoint.
+    #[default]
+    Right,
+    /// Average the value and derivatives of the pieces either side of the breakpoint.
+    Average,
+}
+
+type Piece = Box<dyn Fn(&Number) -> Number + Send + Sync>;
+
+/// A function of a [Number] defined by separate pieces between breakpoints.
+///
+/// Between breakpoints the function and its derivatives are those of the piece, such as the
+/// fixed coupon of a step-up bond or the indicator of a barrier. At a breakpoint the
+/// [BreakpointPolicy] determines the convention, so that the derivative of a kink or jump is
+/// an explicit choice rather than an artefact of the comparison operator.
+pub struct Piecewise {
+    breakpoints: Vec<f64>,
+    pieces: Vec<Piece>,
+    policy: BreakpointPolicy,
+}
+
+impl Piecewise {
+    /// Create a [Piecewise] function from strictly increasing `breakpoints` and one more
+    /// `pieces` than breakpoints, the first applying below the first breakpoint.
+    pub fn try_new(
+        breakpoints: Vec<f64>,
+        pieces: Vec<Piece>,
+        policy: BreakpointPolicy,
+    ) -> Result<Self, RateslibError> {
+        if pieces.len() != breakpoints.len() + 1 {
+            return Err(RateslibError::value(
+                "`pieces` of a `Piecewise` function must number one more than `breakpoints`.",
+            ));
+        }
+        if breakpoints.windows(2).any(|w| w[1] <= w[0]) {
+            return Err(RateslibError::value(
+                "`breakpoints` of a `Piecewise` function must be strictly increasing.",
+            ));
+        }
+        Ok(Self {
+            breakpoints,
+            pieces,
+            policy,
+        })
+    }
+
+    /// Create a piecewise constant function, with zero derivative except at breakpoints.
+    pub fn try_new_step(
+        breakpoints: Vec<f64>,
+        values: Vec<f64>,
+        policy: BreakpointPolicy,
+    ) -> Result<Self, RateslibError> {
+        let pieces: Vec<Piece> = values
+            .into_iter()
+            .map(|v| Box::new(move |_: &Number| Number::F64(v)) as Piece)
+            .collect();
+        Self::try_new(breakpoints, pieces, policy)
+    }
+
+    pub fn breakpoints(&self) -> &[f64] {
+        &self.breakpoints
+    }
+
+    pub fn policy(&self) -> BreakpointPolicy {
+        self.policy
+    }
+
+    /// Evaluate the function at `x`, selecting the piece by its real component.
+    pub fn eval(&self, x: &Number) -> Number {
+        let r = f64::from(x);
+        let i = self.breakpoints.partition_point(|b| *b < r);
+        if i == self.breakpoints.len() || self.breakpoints[i] != r {
+            return (self.pieces[i])(x);
+        }
+        match self.policy {
+            BreakpointPolicy::Left => (self.pieces[i])(x),
+            BreakpointPolicy::Right => (self.pieces[i + 1])(x),
+            BreakpointPolicy::Average => ((self.pieces[i])(x) + (self.pieces[i + 1])(x)) * 0.5,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dual::{Dual, Dual2, Gradient1, Gradient2};
+
+    fn call(strike: f64, policy: BreakpointPolicy) -> Piecewise {
+        Piecewise::try_new(
+            vec![strike],
+            vec![
+                Box::new(|_| Number::F64(0.0)),
+                Box::new(move |x| x - strike),
+            ],
+            policy,
+        )
+        .unwrap()
+    }
+
+    fn delta(n: &Number) -> f64 {
+        match n {
+            Number::Dual(d) => d.gradient1(vec!["x".to_string()])[0],
+            Number::Dual2(d) => d.gradient1(vec!["x".to_string()])[0],
+            Number::F64(_) => 0.0,
+        }
+    }
+
+    #[test]
+    fn test_policy_at_kink() {
+        let x = Number::Dual(Dual::new(1.0, vec!["x".to_string()]));
+        let options = [
+            (BreakpointPolicy::Left, 0.0),
+            (BreakpointPolicy::Right, 1.0),
+            (BreakpointPolicy::Average, 0.5),
+        ];
+        for (policy, expected) in options {
+            let value = call(1.0, policy).eval(&x);
+            assert_eq!(f64::from(&value), 0.0);
+            assert_eq!(delta(&value), expected);
+        }
+    }
+
+    #[test]
+    fn test_away_from_breakpoints() {
+        let f = call(1.0, BreakpointPolicy::Left);
+        let x = Number::Dual2(Dual2::new(1.5, vec!["x".to_string()]));
+        let value = f.eval(&x);
+        assert_eq!(f64::from(&value), 0.5);
+        assert_eq!(delta(&value), 1.0);
+        if let Number::Dual2(d) = value {
+            assert_eq!(d.gradient2(vec!["x".to_string()])[[0, 0]], 0.0);
+        }
+        assert_eq!(f.eval(&Number::F64(0.5)), Number::F64(0.0));
+    }
+
+    #[test]
+    fn test_step_coupons() {
+        let coupons = Piecewise::try_new_step(
+            vec![2.0, 5.0],
+            vec![3.0, 3.5, 4.0],
+            BreakpointPolicy::Average,
+        )
+        .unwrap();
+        assert_eq!(coupons.eval(&Number::F64(1.0)), Number::F64(3.0));
+        assert_eq!(coupons.eval(&Number::F64(2.0)), Number::F64(3.25));
+        assert_eq!(coupons.eval(&Number::F64(7.0)), Number::F64(4.0));
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(Piecewise::try_new_step(vec![1.0], vec![1.0], BreakpointPolicy::Left).is_err());
+        assert!(
+            Piecewise::try_new_step(vec![2.0, 1.0], vec![1.0; 3], BreakpointPolicy::Left).is_err()
+        );
+    }
+}
@@ -0,0 +1,195 @@
+use crate::dual::small::SmallArray1;
+use ndarray::{Array1, Array2};
+use std::cell::RefCell;
+
+/// The maximum number of idle buffers retained by a pool.
+const MAX_BUFFERS: usize = 64;
+
+/// The allocations made and avoided by a buffer pool over its scope.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    /// The number of gradient buffers which were newly allocated.
+    pub allocations: usize,
+    /// The number of gradient buffers which were reused from the pool.
+    pub reuses: usize,
+}
+
+#[derive(Default)]
+struct BufferPool {
+    buffers: Vec<Vec<f64>>,
+    stats: PoolStats,
+}
+
+thread_local! {
+    static POOL: RefCell<Option<BufferPool>> = const { RefCell::new(None) };
+}
+
+/// Evaluate `f` with a pool of gradient buffers on the current thread.
+///
+/// The intermediate gradient and Hessian arrays of dual number operations within `f`, such as
+/// those aligned to a union of `vars`, are returned to the pool and reused by subsequent
+/// operations instead of being deallocated. A nested call shares the pool of the outer call,
+/// whose statistics it returns in their state on exit.
+///
+/// # Examples
+///
+/// ```rust
+/// # use rateslib::dual::{with_buffer_pool, Dual};
+/// let vars = |p: &str| (0..8).map(|i| format!("{p}{i}")).collect::<Vec<_>>();
+/// let (x, y) = (Dual::new(1.0, vars("x")), Dual::new(2.0, vars("y")));
+/// let (_, stats) = with_buffer_pool(|| (0..10).fold(None, |_, _| Some(&x * &y)));
+/// assert!(stats.reuses > 0);
+/// ```
+pub fn with_buffer_pool<R>(f: impl FnOnce() -> R) -> (R, PoolStats) {
+    let outer = POOL.with(|p| {
+        let mut p = p.borrow_mut();
+        let outer = p.is_some();
+        if !outer {
+            *p = Some(BufferPool::default());
+        }
+        outer
+    });
+    let result = f();
+    let stats = POOL.with(|p| {
+        let mut p = p.borrow_mut();
+        let stats = p.as_ref().map(|pool| pool.stats).unwrap_or_default();
+        if !outer {
+            *p = None;
+        }
+        stats
+    });
+    (result, stats)
+}
+
+/// Return a zeroed vector of length `len`, reusing a pooled buffer if available.
+fn take(len: usize) -> Vec<f64> {
+    let pooled = POOL.with(|p| {
+        let mut p = p.borrow_mut();
+        let pool = p.as_mut()?;
+        match pool.buffers.iter().rposition(|b| b.capacity() >= len) {
+            Some(i) => {
+                pool.stats.reuses += 1;
+                Some(pool.buffers.swap_remove(i))
+            }
+            None => {
+                pool.stats.allocations += 1;
+                None
+            }
+        }
+    });
+    match pooled {
+        Some(mut v) => {
+            v.clear();
+            v.resize(len, 0.0);
+            v
+        }
+        None => vec![0.0; len],
+    }
+}
+
+/// Return a buffer to the pool, or deallocate it if no pool is active.
+fn give(v: Vec<f64>) {
+    if v.capacity() == 0 {
+        return;
+    }
+    POOL.with(|p| {
+        if let Some(pool) = p.borrow_mut().as_mut() {
+            if pool.buffers.len() < MAX_BUFFERS {
+                pool.buffers.push(v);
+            }
+        }
+    })
+}
+
+/// Return a zeroed gradient array of length `len`.
+pub(crate) fn zeros1(len: usize) -> Array1<f64> {
+    Array1::from_vec(take(len))
+}
+
+/// Return a zeroed Hessian array of shape `(n, n)`.
+pub(crate) fn zeros2(n: usize) -> Array2<f64> {
+    Array2::from_shape_vec((n, n), take(n * n)).expect("Pre checked dimensions")
+}
+
+/// An array whose buffer may be returned to a pool.
+pub(crate) trait Recycle {
+    fn into_buffer(self) -> Option<Vec<f64>>;
+}
+
+impl<D: ndarray::Dimension> Recycle for ndarray::Array<f64, D> {
+    fn into_buffer(self) -> Option<Vec<f64>> {
+        Some(self.into_raw_vec())
+    }
+}
+
+impl Recycle for SmallArray1 {
+    fn into_buffer(self) -> Option<Vec<f64>> {
+        self.into_heap().map(|a| a.into_raw_vec())
+    }
+}
+
+/// Return the buffer of a gradient or Hessian array which is no longer required to the pool.
+pub(crate) fn recycle(a: impl Recycle) {
+    if let Some(v) = a.into_buffer() {
+        give(v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dual::{Dual, Dual2, Gradient1, Gradient2};
+
+    fn vars(prefix: &str, n: usize) -> Vec<String> {
+        (0..n).map(|i| format!("{prefix}{i}")).collect()
+    }
+
+    #[test]
+    fn test_pool_reuses_buffers() {
+        let (a, stats) = with_buffer_pool(|| {
+            recycle(Array1::<f64>::ones(4));
+            zeros1(3)
+        });
+        assert_eq!(a, Array1::<f64>::zeros(3));
+        assert_eq!(
+            stats,
+            PoolStats {
+                allocations: 0,
+                reuses: 1
+            }
+        );
+        // without a pool buffers are not retained
+        recycle(Array1::<f64>::ones(4));
+        let (_, stats) = with_buffer_pool(|| zeros1(3));
+        assert_eq!(
+            stats,
+            PoolStats {
+                allocations: 1,
+                reuses: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_pool_reduces_allocations() {
+        let (x, z) = (Dual::new(1.5, vars("v", 10)), Dual::new(2.5, vars("w", 10)));
+        let n = 20;
+        let (result, stats) = with_buffer_pool(|| (0..n).fold(None, |_, _| Some(&x * &z)).unwrap());
+        assert_eq!(result, &x * &z);
+        assert_eq!(stats.allocations + stats.reuses, 2 * n);
+        assert!(stats.allocations <= n + 1);
+    }
+
+    #[test]
+    fn test_pooled_dual2_mul() {
+        let x = Dual2::new(1.5, vars("v", 1));
+        let z = Dual2::new(2.0, vars("w", 1));
+        let (pooled, _) = with_buffer_pool(|| &(&x * &z) * &x);
+        // d2(x^2 z) / dx dw = 2x
+        assert_eq!(
+            pooled.gradient2(vec!["v0".to_string(), "w0".to_string()]),
+            ndarray::arr2(&[[4.0, 3.0], [3.0, 0.0]])
+        );
+        assert_eq!(pooled.dual().to_vec(), vec![6.0, 2.25]);
+    }
+}
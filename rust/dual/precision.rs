@@ -0,0 +1,498 @@
+use crate::dual::{Dual, Number};
+use indexmap::IndexSet;
+use ndarray::Array1;
+use std::fmt::Debug;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::sync::Arc;
+
+/// A floating point type which can be the real part of a [DualR].
+pub trait Real:
+    Copy
+    + Debug
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    fn from_f64(x: f64) -> Self;
+    fn to_f64(self) -> f64;
+    fn exp(self) -> Self;
+    fn ln(self) -> Self;
+    fn sqrt(self) -> Self;
+
+    fn zero() -> Self {
+        Self::from_f64(0.0)
+    }
+
+    fn one() -> Self {
+        Self::from_f64(1.0)
+    }
+}
+
+impl Real for f64 {
+    fn from_f64(x: f64) -> Self {
+        x
+    }
+    fn to_f64(self) -> f64 {
+        self
+    }
+    fn exp(self) -> Self {
+        f64::exp(self)
+    }
+    fn ln(self) -> Self {
+        f64::ln(self)
+    }
+    fn sqrt(self) -> Self {
+        f64::sqrt(self)
+    }
+}
+
+impl Real for f32 {
+    fn from_f64(x: f64) -> Self {
+        x as f32
+    }
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+    fn exp(self) -> Self {
+        f32::exp(self)
+    }
+    fn ln(self) -> Self {
+        f32::ln(self)
+    }
+    fn sqrt(self) -> Self {
+        f32::sqrt(self)
+    }
+}
+
+/// An extended precision float represented as the unevaluated sum of two [f64], with a
+/// precision of approximately 106 bits.
+///
+/// Arithmetic uses error free transformations, following Hida, Li and Bailey's
+/// double-double algorithms, and is a portable substitute for `f128`.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Default)]
+pub struct DoubleDouble {
+    hi: f64,
+    lo: f64,
+}
+
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    let bb = s - a;
+    (s, (a - (s - bb)) + (b - bb))
+}
+
+fn quick_two_sum(a: f64, b: f64) -> DoubleDouble {
+    let s = a + b;
+    DoubleDouble {
+        hi: s,
+        lo: b - (s - a),
+    }
+}
+
+impl DoubleDouble {
+    /// The natural logarithm of 2.
+    const LN_2: DoubleDouble = DoubleDouble {
+        hi: std::f64::consts::LN_2,
+        lo: 2.319_046_813_846_299_6e-17,
+    };
+
+    pub fn hi(&self) -> f64 {
+        self.hi
+    }
+
+    pub fn lo(&self) -> f64 {
+        self.lo
+    }
+
+    /// Multiply by an exact power of two.
+    fn ldexp(self, k: i32) -> Self {
+        let scale = 2_f64.powi(k);
+        DoubleDouble {
+            hi: self.hi * scale,
+            lo: self.lo * scale,
+        }
+    }
+}
+
+impl Add for DoubleDouble {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        let (s, e) = two_sum(self.hi, other.hi);
+        let (t, f) = two_sum(self.lo, other.lo);
+        let r = quick_two_sum(s, e + t);
+        quick_two_sum(r.hi, r.lo + f)
+    }
+}
+
+impl Neg for DoubleDouble {
+    type Output = Self;
+    fn neg(self) -> Self {
+        DoubleDouble {
+            hi: -self.hi,
+            lo: -self.lo,
+        }
+    }
+}
+
+impl Sub for DoubleDouble {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        self + -other
+    }
+}
+
+impl Mul for DoubleDouble {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        let p = self.hi * other.hi;
+        let e = self.hi.mul_add(other.hi, -p);
+        quick_two_sum(p, e + (self.hi * other.lo + self.lo * other.hi))
+    }
+}
+
+impl Div for DoubleDouble {
+    type Output = Self;
+    fn div(self, other: Self) -> Self {
+        let q1 = self.hi / other.hi;
+        let r = self - other * DoubleDouble::from_f64(q1);
+        let q2 = r.hi / other.hi;
+        let r = r - other * DoubleDouble::from_f64(q2);
+        let q3 = r.hi / other.hi;
+        quick_two_sum(q1, q2) + DoubleDouble::from_f64(q3)
+    }
+}
+
+impl Real for DoubleDouble {
+    fn from_f64(x: f64) -> Self {
+        DoubleDouble { hi: x, lo: 0.0 }
+    }
+
+    fn to_f64(self) -> f64 {
+        self.hi + self.lo
+    }
+
+    /// Reduce the argument by multiples of ln 2 and a factor of 1024, sum the Taylor series
+    /// and square the result back.
+    fn exp(self) -> Self {
+        if !self.hi.is_finite() || self.hi.abs() > 709.0 {
+            return DoubleDouble::from_f64(self.hi.exp());
+        }
+        let k = (self.hi / std::f64::consts::LN_2).round();
+        let r = (self - DoubleDouble::LN_2 * DoubleDouble::from_f64(k)).ldexp(-10);
+        let (mut sum, mut term) = (DoubleDouble::one(), DoubleDouble::one());
+        for n in 1..=20 {
+            term = term * r / DoubleDouble::from_f64(n as f64);
+            sum = sum + term;
+            if term.hi.abs() < 1e-36 {
+                break;
+            }
+        }
+        for _ in 0..10 {
+            sum = sum * sum;
+        }
+        sum.ldexp(k as i32)
+    }
+
+    /// Refine the [f64] logarithm with Newton iterations, `y + x exp(-y) - 1`.
+    fn ln(self) -> Self {
+        if self.hi <= 0.0 || !self.hi.is_finite() {
+            return DoubleDouble::from_f64(self.hi.ln());
+        }
+        let mut y = DoubleDouble::from_f64(self.hi.ln());
+        for _ in 0..2 {
+            y = y + self * (-y).exp() - DoubleDouble::one();
+        }
+        y
+    }
+
+    fn sqrt(self) -> Self {
+        if self.hi <= 0.0 {
+            return DoubleDouble::from_f64(self.hi.sqrt());
+        }
+        let a = DoubleDouble::from_f64(self.hi.sqrt());
+        a + (self - a * a) / (a * DoubleDouble::from_f64(2.0))
+    }
+}
+
+/// A first order dual number with a real part of generic precision.
+///
+/// [DualR] supports the arithmetic and [Real] functions of its precision. It is intended
+/// for memory constrained scenario calculations using [f32], with [Dual32], and for
+/// validating numerical error with [DoubleDouble], with [DualDD]. Values are converted to
+/// and from the [f64] types of the library, [Dual] and [Number], with [From].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DualR<T: Real> {
+    pub(crate) real: T,
+    pub(crate) vars: Arc<IndexSet<String>>,
+    pub(crate) dual: Vec<T>,
+}
+
+/// A first order dual number with [f32] precision.
+pub type Dual32 = DualR<f32>;
+
+/// A first order dual number with [DoubleDouble] precision.
+pub type DualDD = DualR<DoubleDouble>;
+
+impl<T: Real> DualR<T> {
+    /// Create a [DualR] with unit gradient to each of the `vars`.
+    pub fn new(real: T, vars: Vec<String>) -> Self {
+        let vars = Arc::new(IndexSet::from_iter(vars));
+        Self {
+            real,
+            dual: vec![T::one(); vars.len()],
+            vars,
+        }
+    }
+
+    /// Create a [DualR] with no variables.
+    pub fn constant(real: T) -> Self {
+        Self::new(real, vec![])
+    }
+
+    pub fn real(&self) -> T {
+        self.real
+    }
+
+    pub fn vars(&self) -> &Arc<IndexSet<String>> {
+        &self.vars
+    }
+
+    /// Return the gradient with respect to a variable, which is zero if not contained.
+    pub fn gradient(&self, var: &str) -> T {
+        match self.vars.get_index_of(var) {
+            Some(i) => self.dual[i],
+            None => T::zero(),
+        }
+    }
+
+    /// Return the gradients of `self` and `other` expressed over the union of their vars.
+    fn union(&self, other: &Self) -> (Arc<IndexSet<String>>, Vec<T>, Vec<T>) {
+        if Arc::ptr_eq(&self.vars, &other.vars) || self.vars == other.vars {
+            return (
+                Arc::clone(&self.vars),
+                self.dual.clone(),
+                other.dual.clone(),
+            );
+        }
+        let vars: Arc<IndexSet<String>> = Arc::new(self.vars.union(&other.vars).cloned().collect());
+        let a = vars.iter().map(|v| self.gradient(v)).collect();
+        let b = vars.iter().map(|v| other.gradient(v)).collect();
+        (vars, a, b)
+    }
+
+    /// Apply a function with value `f` and derivative `df` at the real part.
+    fn chain(&self, f: T, df: T) -> Self {
+        Self {
+            real: f,
+            vars: Arc::clone(&self.vars),
+            dual: self.dual.iter().map(|d| *d * df).collect(),
+        }
+    }
+
+    pub fn exp(&self) -> Self {
+        let f = self.real.exp();
+        self.chain(f, f)
+    }
+
+    pub fn ln(&self) -> Self {
+        self.chain(self.real.ln(), T::one() / self.real)
+    }
+
+    pub fn sqrt(&self) -> Self {
+        let f = self.real.sqrt();
+        self.chain(f, T::one() / (f * T::from_f64(2.0)))
+    }
+}
+
+impl<T: Real> Add for &DualR<T> {
+    type Output = DualR<T>;
+    fn add(self, other: Self) -> DualR<T> {
+        let (vars, a, b) = self.union(other);
+        DualR {
+            real: self.real + other.real,
+            vars,
+            dual: a.into_iter().zip(b).map(|(x, y)| x + y).collect(),
+        }
+    }
+}
+
+impl<T: Real> Sub for &DualR<T> {
+    type Output = DualR<T>;
+    fn sub(self, other: Self) -> DualR<T> {
+        let (vars, a, b) = self.union(other);
+        DualR {
+            real: self.real - other.real,
+            vars,
+            dual: a.into_iter().zip(b).map(|(x, y)| x - y).collect(),
+        }
+    }
+}
+
+impl<T: Real> Mul for &DualR<T> {
+    type Output = DualR<T>;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn mul(self, other: Self) -> DualR<T> {
+        let (vars, a, b) = self.union(other);
+        DualR {
+            real: self.real * other.real,
+            vars,
+            dual: a
+                .into_iter()
+                .zip(b)
+                .map(|(x, y)| x * other.real + y * self.real)
+                .collect(),
+        }
+    }
+}
+
+impl<T: Real> Div for &DualR<T> {
+    type Output = DualR<T>;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, other: Self) -> DualR<T> {
+        let (vars, a, b) = self.union(other);
+        let d2 = other.real * other.real;
+        DualR {
+            real: self.real / other.real,
+            vars,
+            dual: a
+                .into_iter()
+                .zip(b)
+                .map(|(x, y)| (x * other.real - y * self.real) / d2)
+                .collect(),
+        }
+    }
+}
+
+impl<T: Real> Neg for &DualR<T> {
+    type Output = DualR<T>;
+    fn neg(self) -> DualR<T> {
+        self.chain(-self.real, -T::one())
+    }
+}
+
+macro_rules! impl_owned_op {
+    ($trait:ident, $method:ident) => {
+        impl<T: Real> $trait for DualR<T> {
+            type Output = DualR<T>;
+            fn $method(self, other: Self) -> DualR<T> {
+                (&self).$method(&other)
+            }
+        }
+
+        impl<T: Real> $trait<T> for &DualR<T> {
+            type Output = DualR<T>;
+            fn $method(self, other: T) -> DualR<T> {
+                self.$method(&DualR::constant(other))
+            }
+        }
+    };
+}
+impl_owned_op!(Add, add);
+impl_owned_op!(Sub, sub);
+impl_owned_op!(Mul, mul);
+impl_owned_op!(Div, div);
+
+impl<T: Real> From<&Dual> for DualR<T> {
+    fn from(value: &Dual) -> Self {
+        Self {
+            real: T::from_f64(value.real),
+            vars: Arc::clone(&value.vars),
+            dual: value.dual.iter().map(|d| T::from_f64(*d)).collect(),
+        }
+    }
+}
+
+impl<T: Real> From<&DualR<T>> for Dual {
+    fn from(value: &DualR<T>) -> Self {
+        Dual {
+            real: value.real.to_f64(),
+            vars: Arc::clone(&value.vars),
+            dual: Array1::from_iter(value.dual.iter().map(|d| d.to_f64())).into(),
+        }
+    }
+}
+
+impl<T: Real> From<&Number> for DualR<T> {
+    /// Convert a [Number], dropping any second order derivatives.
+    fn from(value: &Number) -> Self {
+        match value {
+            Number::F64(f) => DualR::constant(T::from_f64(*f)),
+            _ => DualR::from(&Dual::from(value)),
+        }
+    }
+}
+
+impl<T: Real> From<&DualR<T>> for Number {
+    fn from(value: &DualR<T>) -> Self {
+        Number::Dual(Dual::from(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dd(x: f64) -> DoubleDouble {
+        DoubleDouble::from_f64(x)
+    }
+
+    #[test]
+    fn test_double_double_arithmetic() {
+        let third = dd(1.0) / dd(3.0);
+        assert!((third * dd(3.0) - dd(1.0)).to_f64().abs() < 1e-31);
+        // 1 + 1e-20 is lost in f64 but retained in double-double
+        let x = dd(1.0) + dd(1e-20);
+        assert_eq!((x - dd(1.0)).to_f64(), 1e-20);
+        assert!((dd(2.0).sqrt() * dd(2.0).sqrt() - dd(2.0)).to_f64().abs() < 1e-31);
+        assert!(dd(1.0) < x);
+    }
+
+    #[test]
+    fn test_double_double_exp_ln() {
+        assert!((dd(2.0).ln() - DoubleDouble::LN_2).to_f64().abs() < 1e-31);
+        assert!((DoubleDouble::LN_2.exp() - dd(2.0)).to_f64().abs() < 1e-30);
+        // the squaring of the reduced argument amplifies rounding of the series
+        let x = dd(-3.7);
+        assert!((x.exp().ln() - x).to_f64().abs() < 1e-28);
+        assert!((dd(10.0).exp().to_f64() - 10_f64.exp()).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_dual_precision_ops() {
+        let x = Dual32::new(2.0, vec!["x".to_string()]);
+        let y = Dual32::new(3.0, vec!["y".to_string()]);
+        let z = &(&x * &y) / &(&x + &y);
+        assert!((z.real() - 1.2).abs() < 1e-6);
+        // dz/dx = y^2 / (x + y)^2
+        assert!((z.gradient("x") - 0.36).abs() < 1e-6);
+        assert!((z.gradient("y") - 0.16).abs() < 1e-6);
+        let e = (&x * 0.5_f32).exp();
+        assert!((e.gradient("x") - 0.5 * 1_f32.exp()).abs() < 1e-6);
+        assert_eq!((-&x).gradient("x"), -1.0);
+        assert_eq!(x.gradient("z"), 0.0);
+    }
+
+    #[test]
+    fn test_dual_double_double() {
+        let x = DualDD::new(dd(1.0), vec!["x".to_string()]);
+        let y = (&(&x / dd(3.0)) * dd(3.0)).ln().sqrt();
+        assert!(y.real().to_f64().abs() < 1e-15);
+        let z = &x.exp() - &x.exp();
+        assert_eq!(z.gradient("x"), dd(0.0));
+    }
+
+    #[test]
+    fn test_conversions() {
+        let d = Dual::new(1.5, vec!["a".to_string(), "b".to_string()]) * 2.0;
+        let d32 = Dual32::from(&d);
+        assert_eq!(d32.gradient("b"), 2.0);
+        let back = Dual::from(&d32);
+        assert_eq!(back, d);
+        let n = Number::from(&DualDD::from(&Number::Dual(d.clone())));
+        assert_eq!(n, Number::Dual(d));
+        assert_eq!(Dual32::from(&Number::F64(2.0)), Dual32::constant(2.0));
+    }
+}
@@ -0,0 +1,222 @@
+use crate::dual::dual::{Dual, Dual2};
+use crate::dual::enums::Number;
+use crate::error::RateslibError;
+use indexmap::IndexSet;
+use num_traits::Zero;
+#[cfg(feature = "python")]
+use pyo3::pyclass;
+
+/// The number of values below which a pairwise reduction sums sequentially.
+const PAIRWISE_BLOCK: usize = 8;
+
+/// The order in which a sum of [Number]s, such as the NPVs of a portfolio, is accumulated.
+///
+/// Floating point addition is not associative, so the sum, and the sum of each gradient entry,
+/// depends on its order. Each reduction has an order fixed by the number of values alone, so
+/// that a sum is reproduced exactly between runs however its values were evaluated.
+#[cfg_attr(feature = "python", pyclass(module = "rateslib.rs"))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Reduction {
+    /// Accumulate each value in turn, from the first.
+    #[default]
+    Sequential,
+    /// Accumulate a balanced tree of partial sums, of which the rounding error grows with the
+    /// logarithm of the number of values rather than linearly.
+    Pairwise,
+    /// Accumulate each value in turn with Neumaier compensation of the rounding error of the
+    /// real part and of each gradient entry, such as for the many cashflows of long legs.
+    Compensated,
+}
+
+impl Reduction {
+    /// Return the sum of the `values`, or raise on mixed first and second order dual numbers.
+    pub fn sum(&self, values: &[Number]) -> Result<Number, RateslibError> {
+        match self {
+            Reduction::Sequential => sequential(values),
+            Reduction::Pairwise => pairwise(values),
+            Reduction::Compensated => compensated(values),
+        }
+    }
+}
+
+fn sequential(values: &[Number]) -> Result<Number, RateslibError> {
+    values
+        .iter()
+        .try_fold(Number::zero(), |acc, v| acc.try_add(v))
+}
+
+fn pairwise(values: &[Number]) -> Result<Number, RateslibError> {
+    if values.len() <= PAIRWISE_BLOCK {
+        return sequential(values);
+    }
+    let (left, right) = values.split_at(values.len() / 2);
+    pairwise(left)?.try_add(&pairwise(right)?)
+}
+
+/// A running sum with the Neumaier compensation of its rounding error.
+#[derive(Debug, Copy, Clone, Default)]
+pub(crate) struct Neumaier {
+    sum: f64,
+    compensation: f64,
+}
+
+impl Neumaier {
+    pub(crate) fn add(&mut self, x: f64) {
+        let t = self.sum + x;
+        if self.sum.abs() >= x.abs() {
+            self.compensation += (self.sum - t) + x;
+        } else {
+            self.compensation += (x - t) + self.sum;
+        }
+        self.sum = t;
+    }
+
+    pub(crate) fn value(&self) -> f64 {
+        self.sum + self.compensation
+    }
+}
+
+/// Return the compensated sum of the `values` of [f64], such as the fractions of the periods
+/// of a leg.
+pub(crate) fn compensated_f64<'a>(values: impl IntoIterator<Item = &'a f64>) -> f64 {
+    let mut acc = Neumaier::default();
+    values.into_iter().for_each(|v| acc.add(*v));
+    acc.value()
+}
+
+fn compensated(values: &[Number]) -> Result<Number, RateslibError> {
+    let (mut first, mut second) = (false, false);
+    let mut vars: IndexSet<String> = IndexSet::new();
+    for value in values.iter() {
+        match value {
+            Number::F64(_) => {}
+            Number::Dual(d) => {
+                first = true;
+                vars.extend(d.vars.iter().cloned());
+            }
+            Number::Dual2(d) => {
+                second = true;
+                vars.extend(d.vars.iter().cloned());
+            }
+        }
+    }
+    if first && second {
+        return Err(RateslibError::MixedDualTypes {
+            operation: "Dual + Dual2".to_string(),
+        });
+    }
+
+    let n = vars.len();
+    let mut real = Neumaier::default();
+    let mut dual = vec![Neumaier::default(); n];
+    let mut dual2 = vec![Neumaier::default(); if second { n * n } else { 0 }];
+    for value in values.iter() {
+        match value {
+            Number::F64(f) => real.add(*f),
+            Number::Dual(d) => {
+                real.add(d.real);
+                for (j, var) in d.vars.iter().enumerate() {
+                    dual[vars.get_index_of(var).unwrap()].add(d.dual[j]);
+                }
+            }
+            Number::Dual2(d) => {
+                real.add(d.real);
+                let index: Vec<usize> = d
+                    .vars
+                    .iter()
+                    .map(|v| vars.get_index_of(v).unwrap())
+                    .collect();
+                let d2 = &*d.dual2;
+                for (j, &x) in index.iter().enumerate() {
+                    dual[x].add(d.dual[j]);
+                    for (k, &y) in index.iter().enumerate() {
+                        dual2[x * n + y].add(d2[[j, k]]);
+                    }
+                }
+            }
+        }
+    }
+
+    let vars: Vec<String> = vars.into_iter().collect();
+    let dual: Vec<f64> = dual.iter().map(|acc| acc.value()).collect();
+    match (first, second) {
+        (false, false) => Ok(Number::F64(real.value())),
+        (true, _) => Ok(Number::Dual(Dual::try_new(real.value(), vars, dual)?)),
+        (false, true) => Ok(Number::Dual2(Dual2::try_new(
+            real.value(),
+            vars,
+            dual,
+            dual2.iter().map(|acc| acc.value()).collect(),
+        )?)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dual::{Gradient1, Gradient2};
+
+    #[test]
+    fn test_pairwise_reduction() {
+        let mut values: Vec<Number> = vec![Number::F64(1e16)];
+        values.extend((0..100).map(|_| Number::F64(1.0)));
+        values.push(Number::F64(-1e16));
+        // sequentially each unit is lost to rounding against 1e16
+        assert_eq!(
+            Reduction::Sequential.sum(&values).unwrap(),
+            Number::F64(0.0)
+        );
+        let pairwise = Reduction::Pairwise.sum(&values).unwrap();
+        assert_eq!(pairwise, Reduction::Pairwise.sum(&values).unwrap());
+        assert!(f64::from(pairwise) > 0.0);
+        let duals: Vec<Number> = (0..20)
+            .map(|i| Number::Dual(Dual::new(i as f64, vec![format!("v{}", i % 3)])))
+            .collect();
+        let Number::Dual(sum) = Reduction::Pairwise.sum(&duals).unwrap() else {
+            panic!("the sum of dual numbers is a dual number")
+        };
+        assert_eq!(sum.real, 190.0);
+        assert_eq!(
+            sum.gradient1(vec!["v0".to_string(), "v1".to_string()]),
+            ndarray::arr1(&[7.0, 7.0])
+        );
+    }
+
+    #[test]
+    fn test_compensated_reduction() {
+        let mut values: Vec<Number> = vec![Number::F64(1e16)];
+        values.extend((0..100).map(|_| Number::F64(1.0)));
+        values.push(Number::F64(-1e16));
+        assert_eq!(
+            Reduction::Compensated.sum(&values).unwrap(),
+            Number::F64(100.0)
+        );
+        assert_eq!(compensated_f64(&[0.1; 10]), 1.0);
+
+        // the compensation applies to each gradient entry
+        let x = vec!["x".to_string(), "y".to_string()];
+        let mut values: Vec<Number> = vec![Number::Dual2(
+            Dual2::try_new(1.0, x.clone(), vec![1e16, 0.0], vec![1e16, 0.0, 0.0, 0.0]).unwrap(),
+        )];
+        values.extend((0..100).map(|_| Number::Dual2(Dual2::new(0.0, vec!["x".to_string()]))));
+        values.push(Number::Dual2(
+            Dual2::try_new(0.0, x.clone(), vec![-1e16, 1.0], vec![-1e16, 0.0, 0.0, 0.0]).unwrap(),
+        ));
+        let Number::Dual2(sum) = Reduction::Compensated.sum(&values).unwrap() else {
+            panic!("the sum of dual numbers is a dual number")
+        };
+        assert_eq!(sum.gradient1(x.clone()), ndarray::arr1(&[100.0, 1.0]));
+        assert_eq!(sum.gradient2(x)[[0, 0]], 0.0);
+    }
+
+    #[test]
+    fn test_mixed_reduction_raises() {
+        let values = vec![
+            Number::Dual(Dual::new(1.0, vec!["x".to_string()])),
+            Number::Dual2(Dual2::new(1.0, vec!["x".to_string()])),
+        ];
+        assert!(Reduction::Sequential.sum(&values).is_err());
+        assert!(Reduction::Pairwise.sum(&values).is_err());
+        assert!(Reduction::Compensated.sum(&values).is_err());
+    }
+}
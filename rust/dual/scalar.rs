@@ -0,0 +1,121 @@
+use crate::dual::MathFuncs;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// A numeric type on which generic calculations of the library can run.
+///
+/// [Scalar] is implemented for every type with the required arithmetic, with itself and with
+/// [f64], and [MathFuncs]. This includes [f64], [Dual](crate::dual::Dual),
+/// [Dual2](crate::dual::Dual2), [Number](crate::dual::Number) and
+/// [Interval](crate::dual::Interval). A downstream crate implements these traits for its own
+/// scalar, such as a vector of Monte Carlo samples, to use functions generic over [Scalar],
+/// for example the interpolation functions of [curves](crate::curves). Generic functions
+/// also bound `for<'a> &'a T: NumberOps<T>` to operate on references without cloning.
+pub trait Scalar:
+    Sized
+    + Clone
+    + MathFuncs
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + Add<f64, Output = Self>
+    + Sub<f64, Output = Self>
+    + Mul<f64, Output = Self>
+    + Div<f64, Output = Self>
+{
+}
+
+impl<T> Scalar for T where
+    T: Sized
+        + Clone
+        + MathFuncs
+        + Add<Output = T>
+        + Sub<Output = T>
+        + Mul<Output = T>
+        + Div<Output = T>
+        + Neg<Output = T>
+        + Add<f64, Output = T>
+        + Sub<f64, Output = T>
+        + Mul<f64, Output = T>
+        + Div<f64, Output = T>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curves::{linear_zero_interp, log_linear_interp};
+    use crate::dual::{Dual, Dual2, Interval, Number};
+    use auto_ops::{impl_op_ex, impl_op_ex_commutative};
+
+    /// A scalar of Monte Carlo samples, as a downstream crate might define.
+    #[derive(Debug, Clone, PartialEq)]
+    struct Samples(Vec<f64>);
+
+    impl Samples {
+        fn map(&self, f: impl Fn(f64) -> f64) -> Samples {
+            Samples(self.0.iter().map(|x| f(*x)).collect())
+        }
+
+        fn zip(&self, other: &Samples, f: impl Fn(f64, f64) -> f64) -> Samples {
+            Samples(
+                self.0
+                    .iter()
+                    .zip(other.0.iter())
+                    .map(|(a, b)| f(*a, *b))
+                    .collect(),
+            )
+        }
+    }
+
+    impl_op_ex!(+ |a: &Samples, b: &Samples| -> Samples { a.zip(b, |x, y| x + y) });
+    impl_op_ex!(-|a: &Samples, b: &Samples| -> Samples { a.zip(b, |x, y| x - y) });
+    impl_op_ex!(*|a: &Samples, b: &Samples| -> Samples { a.zip(b, |x, y| x * y) });
+    impl_op_ex!(/ |a: &Samples, b: &Samples| -> Samples { a.zip(b, |x, y| x / y) });
+    impl_op_ex!(-|a: &Samples| -> Samples { a.map(|x| -x) });
+    impl_op_ex_commutative!(+ |a: &Samples, b: &f64| -> Samples { a.map(|x| x + b) });
+    impl_op_ex_commutative!(*|a: &Samples, b: &f64| -> Samples { a.map(|x| x * b) });
+    impl_op_ex!(-|a: &Samples, b: &f64| -> Samples { a.map(|x| x - b) });
+    impl_op_ex!(/ |a: &Samples, b: &f64| -> Samples { a.map(|x| x / b) });
+
+    impl MathFuncs for Samples {
+        fn exp(&self) -> Self {
+            self.map(f64::exp)
+        }
+        fn log(&self) -> Self {
+            self.map(f64::ln)
+        }
+        fn norm_cdf(&self) -> Self {
+            self.map(|x| x.norm_cdf())
+        }
+        fn inv_norm_cdf(&self) -> Self {
+            self.map(|x| x.inv_norm_cdf())
+        }
+    }
+
+    fn is_scalar<T: Scalar>() {}
+
+    #[test]
+    fn test_library_scalars() {
+        is_scalar::<f64>();
+        is_scalar::<Dual>();
+        is_scalar::<Dual2>();
+        is_scalar::<Number>();
+        is_scalar::<Interval>();
+        is_scalar::<Samples>();
+    }
+
+    #[test]
+    fn test_external_scalar_interpolation() {
+        let (y1, y2) = (Samples(vec![0.99, 0.98]), Samples(vec![0.95, 0.90]));
+        let y = log_linear_interp(0.0, &y1, 10.0, &y2, 4.0);
+        for i in 0..2 {
+            let expected = log_linear_interp(0.0, &y1.0[i], 10.0, &y2.0[i], 4.0);
+            assert!((y.0[i] - expected).abs() < 1e-15);
+        }
+        let z = linear_zero_interp(0.0, 1.0, &y1, 10.0, &y2, 4.0);
+        let expected = linear_zero_interp(0.0, 1.0, &y1.0[1], 10.0, &y2.0[1], 4.0);
+        assert!((z.0[1] - expected).abs() < 1e-15);
+    }
+}
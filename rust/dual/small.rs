@@ -0,0 +1,339 @@
+use crate::dual::pool::zeros1;
+use ndarray::{Array1, ArrayView1};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::ops::{Add, Index, IndexMut, Mul, Neg, Sub};
+use std::sync::OnceLock;
+
+/// The largest number of gradients stored inline.
+pub(crate) const INLINE_CAPACITY: usize = 4;
+
+/// A one dimensional array of gradients stored inline, without a heap allocation, when it
+/// has at most [INLINE_CAPACITY] elements.
+///
+/// Arithmetic and element access operate on the slice of either variant, so small gradients
+/// never allocate; [view](SmallArray1::view) borrows the elements as an [ArrayView1] for any
+/// other array operation. An inline array is copied into an [Array1] only when one is borrowed
+/// by [as_array](SmallArray1::as_array), and the copy is kept until the elements are mutated.
+pub(crate) enum SmallArray1 {
+    Inline([f64; INLINE_CAPACITY], u8, OnceLock<Array1<f64>>),
+    Heap(Array1<f64>),
+}
+
+impl Default for SmallArray1 {
+    fn default() -> Self {
+        SmallArray1::Inline([0.0; INLINE_CAPACITY], 0, OnceLock::new())
+    }
+}
+
+impl Clone for SmallArray1 {
+    fn clone(&self) -> Self {
+        match self {
+            SmallArray1::Inline(a, len, _) => SmallArray1::Inline(*a, *len, OnceLock::new()),
+            SmallArray1::Heap(a) => SmallArray1::Heap(a.clone()),
+        }
+    }
+}
+
+impl SmallArray1 {
+    /// Return an array of `len` elements populated by `f` of each index.
+    pub(crate) fn from_fn(len: usize, f: impl Fn(usize) -> f64) -> Self {
+        if len <= INLINE_CAPACITY {
+            let mut inline = [0.0; INLINE_CAPACITY];
+            (0..len).for_each(|i| inline[i] = f(i));
+            SmallArray1::Inline(inline, len as u8, OnceLock::new())
+        } else {
+            let mut a = zeros1(len);
+            a.iter_mut().enumerate().for_each(|(i, x)| *x = f(i));
+            SmallArray1::Heap(a)
+        }
+    }
+
+    /// Return an array of `len` zeros.
+    pub(crate) fn zeros(len: usize) -> Self {
+        Self::from_fn(len, |_| 0.0)
+    }
+
+    /// Return an array of `len` ones.
+    pub(crate) fn ones(len: usize) -> Self {
+        Self::from_fn(len, |_| 1.0)
+    }
+
+    #[cfg(test)]
+    fn is_inline(&self) -> bool {
+        matches!(self, SmallArray1::Inline(..))
+    }
+
+    pub(crate) fn as_slice(&self) -> &[f64] {
+        match self {
+            SmallArray1::Inline(a, len, _) => &a[..*len as usize],
+            SmallArray1::Heap(a) => a.as_slice().expect("Arrays are contiguous"),
+        }
+    }
+
+    /// Borrow the elements as an [Array1], copying inline elements on the first borrow.
+    pub(crate) fn as_array(&self) -> &Array1<f64> {
+        match self {
+            SmallArray1::Inline(_, _, array) => {
+                array.get_or_init(|| Array1::from_vec(self.as_slice().to_vec()))
+            }
+            SmallArray1::Heap(a) => a,
+        }
+    }
+
+    pub(crate) fn as_slice_mut(&mut self) -> &mut [f64] {
+        match self {
+            SmallArray1::Inline(a, len, array) => {
+                array.take();
+                &mut a[..*len as usize]
+            }
+            SmallArray1::Heap(a) => a.as_slice_mut().expect("Arrays are contiguous"),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    pub(crate) fn view(&self) -> ArrayView1<'_, f64> {
+        ArrayView1::from(self.as_slice())
+    }
+
+    pub(crate) fn iter(&self) -> std::slice::Iter<'_, f64> {
+        self.as_slice().iter()
+    }
+
+    pub(crate) fn iter_mut(&mut self) -> std::slice::IterMut<'_, f64> {
+        self.as_slice_mut().iter_mut()
+    }
+
+    #[cfg(any(test, feature = "python"))]
+    pub(crate) fn to_vec(&self) -> Vec<f64> {
+        self.as_slice().to_vec()
+    }
+
+    /// Return the heap allocated array, if there is one, for reuse.
+    pub(crate) fn into_heap(self) -> Option<Array1<f64>> {
+        match self {
+            SmallArray1::Inline(..) => None,
+            SmallArray1::Heap(a) => Some(a),
+        }
+    }
+
+    /// Return the elements of `self` and `other` combined by `f`.
+    fn zip_with(&self, other: &Self, f: impl Fn(f64, f64) -> f64) -> Self {
+        let (a, b) = (self.as_slice(), other.as_slice());
+        assert_eq!(a.len(), b.len(), "Gradients must have equal lengths.");
+        Self::from_fn(a.len(), |i| f(a[i], b[i]))
+    }
+
+    /// Return the elements of `self` mapped by `f`.
+    pub(crate) fn map(&self, f: impl Fn(f64) -> f64) -> Self {
+        let a = self.as_slice();
+        Self::from_fn(a.len(), |i| f(a[i]))
+    }
+
+    /// Map the elements of `self` by `f` in place.
+    pub(crate) fn map_inplace(&mut self, f: impl Fn(f64) -> f64) {
+        self.iter_mut().for_each(|x| *x = f(*x));
+    }
+
+    /// Perform `self += alpha * rhs` in place.
+    pub(crate) fn scaled_add(&mut self, alpha: f64, rhs: &Self) {
+        let b = rhs.as_slice();
+        assert_eq!(self.len(), b.len(), "Gradients must have equal lengths.");
+        self.iter_mut()
+            .zip(b.iter())
+            .for_each(|(x, y)| *x += alpha * y);
+    }
+}
+
+impl From<Array1<f64>> for SmallArray1 {
+    fn from(a: Array1<f64>) -> Self {
+        SmallArray1::Heap(a.as_standard_layout().into_owned())
+    }
+}
+
+impl From<Vec<f64>> for SmallArray1 {
+    fn from(v: Vec<f64>) -> Self {
+        match v.len() <= INLINE_CAPACITY {
+            true => Self::from_fn(v.len(), |i| v[i]),
+            false => SmallArray1::Heap(Array1::from_vec(v)),
+        }
+    }
+}
+
+impl Index<usize> for SmallArray1 {
+    type Output = f64;
+
+    fn index(&self, index: usize) -> &f64 {
+        &self.as_slice()[index]
+    }
+}
+
+impl IndexMut<usize> for SmallArray1 {
+    fn index_mut(&mut self, index: usize) -> &mut f64 {
+        &mut self.as_slice_mut()[index]
+    }
+}
+
+impl PartialEq for SmallArray1 {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl PartialEq<Array1<f64>> for SmallArray1 {
+    fn eq(&self, other: &Array1<f64>) -> bool {
+        self.view() == other.view()
+    }
+}
+
+impl std::fmt::Debug for SmallArray1 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.view().fmt(f)
+    }
+}
+
+impl Serialize for SmallArray1 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.view().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SmallArray1 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Array1::<f64>::deserialize(deserializer)?
+            .into_raw_vec()
+            .into())
+    }
+}
+
+impl Add<&SmallArray1> for &SmallArray1 {
+    type Output = SmallArray1;
+    fn add(self, rhs: &SmallArray1) -> SmallArray1 {
+        self.zip_with(rhs, |a, b| a + b)
+    }
+}
+
+impl Add<&SmallArray1> for SmallArray1 {
+    type Output = SmallArray1;
+    fn add(mut self, rhs: &SmallArray1) -> SmallArray1 {
+        self.scaled_add(1.0, rhs);
+        self
+    }
+}
+
+impl Sub<&SmallArray1> for &SmallArray1 {
+    type Output = SmallArray1;
+    fn sub(self, rhs: &SmallArray1) -> SmallArray1 {
+        self.zip_with(rhs, |a, b| a - b)
+    }
+}
+
+impl Sub<&SmallArray1> for SmallArray1 {
+    type Output = SmallArray1;
+    fn sub(mut self, rhs: &SmallArray1) -> SmallArray1 {
+        self.scaled_add(-1.0, rhs);
+        self
+    }
+}
+
+impl Mul<f64> for &SmallArray1 {
+    type Output = SmallArray1;
+    fn mul(self, rhs: f64) -> SmallArray1 {
+        self.map(|a| a * rhs)
+    }
+}
+
+impl Mul<f64> for SmallArray1 {
+    type Output = SmallArray1;
+    fn mul(mut self, rhs: f64) -> SmallArray1 {
+        self.map_inplace(|x| x * rhs);
+        self
+    }
+}
+
+impl Mul<&SmallArray1> for f64 {
+    type Output = SmallArray1;
+    fn mul(self, rhs: &SmallArray1) -> SmallArray1 {
+        rhs * self
+    }
+}
+
+impl Mul<SmallArray1> for f64 {
+    type Output = SmallArray1;
+    fn mul(self, rhs: SmallArray1) -> SmallArray1 {
+        rhs * self
+    }
+}
+
+impl Neg for &SmallArray1 {
+    type Output = SmallArray1;
+    fn neg(self) -> SmallArray1 {
+        self.map(|a| -a)
+    }
+}
+
+impl Neg for SmallArray1 {
+    type Output = SmallArray1;
+    fn neg(self) -> SmallArray1 {
+        self * -1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inline_arithmetic() {
+        let a = SmallArray1::from(vec![1.0, 2.0]);
+        let b = SmallArray1::from(vec![3.0, 5.0]);
+        let mut c = &(&a + &b) * 2.0 - &a;
+        assert!(c.is_inline());
+        c.scaled_add(-1.0, &b);
+        c[0] += 1.0;
+        assert_eq!(c.to_vec(), vec![5.0, 7.0]);
+        assert!(c.is_inline());
+        assert_eq!(c.view().dot(&b.view()), 50.0);
+        assert_eq!(c, Array1::from_vec(vec![5.0, 7.0]));
+    }
+
+    #[test]
+    fn test_large_arrays_are_heap_allocated() {
+        let a = SmallArray1::ones(INLINE_CAPACITY + 1);
+        assert!(!a.is_inline());
+        assert_eq!((&a + &a).to_vec(), vec![2.0; INLINE_CAPACITY + 1]);
+        assert!(a.into_heap().is_some());
+    }
+
+    #[test]
+    fn test_mutation_in_place() {
+        let mut a = SmallArray1::from(vec![1.0, 2.0]);
+        assert_eq!(a.view().sum(), 3.0);
+        a[1] = 4.0;
+        a.map_inplace(|x| x * 2.0);
+        assert_eq!(a.as_slice(), &[2.0, 8.0]);
+        assert!(a.is_inline());
+    }
+
+    #[test]
+    fn test_borrowed_array_follows_mutation() {
+        let mut a = SmallArray1::from(vec![1.0, 2.0]);
+        assert_eq!(a.as_array(), &Array1::from_vec(vec![1.0, 2.0]));
+        a[0] = 3.0;
+        assert_eq!(a.as_array(), &Array1::from_vec(vec![3.0, 2.0]));
+        assert!(a.is_inline());
+    }
+
+    #[test]
+    fn test_serialization_matches_array() {
+        let a = SmallArray1::from(vec![1.0, 2.0]);
+        let json = serde_json::to_string(&a).unwrap();
+        assert_eq!(
+            json,
+            serde_json::to_string(&Array1::from_vec(vec![1.0, 2.0])).unwrap()
+        );
+        assert_eq!(serde_json::from_str::<SmallArray1>(&json).unwrap(), a);
+    }
+}
@@ -0,0 +1,93 @@
+//! Python exception classes raised from the rust core.
+//!
+//! Each subclasses `RateslibError`, itself a `ValueError`, except `DualTypeError` which is a
+//! `TypeError`, consistent with the exceptions previously raised.
+
+use pyo3::create_exception;
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::{PyErr, Python};
+use std::fmt;
+use std::sync::Arc;
+
+create_exception!(
+    rs,
+    RateslibError,
+    PyValueError,
+    "The base class of errors raised by the rust core of rateslib."
+);
+create_exception!(
+    rs,
+    CurveError,
+    RateslibError,
+    "Raised on an invalid Curve definition or evaluation."
+);
+create_exception!(
+    rs,
+    SplineError,
+    RateslibError,
+    "Raised on an invalid PPSpline definition or evaluation."
+);
+create_exception!(
+    rs,
+    CalendarError,
+    RateslibError,
+    "Raised on an invalid calendar or date."
+);
+create_exception!(
+    rs,
+    LinalgError,
+    RateslibError,
+    "Raised on arrays of incompatible shapes for a linear algebra operation."
+);
+create_exception!(
+    rs,
+    SolverError,
+    RateslibError,
+    "Raised on a calibration which cannot be set up, or which fails."
+);
+create_exception!(
+    rs,
+    CurrencyError,
+    RateslibError,
+    "Raised on an operation combining amounts in different currencies."
+);
+create_exception!(
+    rs,
+    CancelledError,
+    RateslibError,
+    "Raised by a computation whose `CancellationToken` is cancelled."
+);
+create_exception!(
+    rs,
+    DualTypeError,
+    PyTypeError,
+    "Raised on an operation combining `Dual` and `Dual2`."
+);
+
+/// An exception raised in Python, shared so that a [RateslibError](super::RateslibError) which
+/// carries it may be cloned.
+#[derive(Debug, Clone)]
+pub struct PythonError(Arc<PyErr>);
+
+impl PythonError {
+    pub(crate) fn new(err: PyErr) -> Self {
+        Self(Arc::new(err))
+    }
+
+    /// Return the exception, cloned if it is shared.
+    pub(crate) fn into_inner(self) -> PyErr {
+        Arc::try_unwrap(self.0).unwrap_or_else(|e| Python::with_gil(|py| e.clone_ref(py)))
+    }
+}
+
+impl PartialEq for PythonError {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl fmt::Display for PythonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
@@ -0,0 +1,213 @@
+//! The crate-wide error type and the Python exceptions it maps to.
+//!
+//! Fallible functions of the core return a [RateslibError], rather than panicking, which is
+//! independent of Python so that the core builds without the `python` feature. With the
+//! feature it converts into a `PyErr` of a distinct Python exception class for each component
+//! of the core. The operator traits of dual numbers cannot return a `Result`, so checked
+//! alternatives are provided for inputs whose types are not known to be compatible.
+
+#[cfg(feature = "python")]
+pub(crate) mod error_py;
+#[cfg(feature = "python")]
+pub use crate::error::error_py::PythonError;
+
+use crate::curves::CurveError;
+use crate::splines::SplineError;
+#[cfg(feature = "python")]
+use pyo3::exceptions::{PyNotImplementedError, PyTypeError, PyValueError};
+#[cfg(feature = "python")]
+use pyo3::PyErr;
+use std::fmt;
+
+/// The errors raised by the rust core of rateslib.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RateslibError {
+    /// An invalid curve definition or evaluation.
+    Curve(CurveError),
+    /// An invalid spline definition or evaluation.
+    Spline(SplineError),
+    /// An operation combining first and second order dual numbers.
+    MixedDualTypes { operation: String },
+    /// An operation combining amounts denominated in different currencies.
+    CurrencyMismatch { operation: String },
+    /// A calendar date which does not exist.
+    InvalidDate { year: i32, month: u32, day: u32 },
+    /// An invalid calendar definition.
+    Calendar(String),
+    /// Arrays whose shapes are incompatible with a linear algebra operation.
+    Shape(String),
+    /// A calibration which cannot be set up, or which fails.
+    Solver(String),
+    /// An operation which is not supported.
+    Unsupported(String),
+    /// A computation cancelled by a [CancellationToken](crate::cancel::CancellationToken).
+    Cancelled,
+    /// An invalid value of an argument outside the components with their own variant, such as
+    /// the specification of an instrument or a simulation.
+    ///
+    /// Converts into a plain Python `ValueError`, as was raised for these arguments before the
+    /// exception classes of the components were introduced.
+    Value(String),
+    /// An argument of an invalid type.
+    Type(String),
+    /// An exception raised by Python code called from the core, such as a progress callback.
+    #[cfg(feature = "python")]
+    Python(PythonError),
+}
+
+impl RateslibError {
+    /// Return a [RateslibError::Value] with the `message`.
+    pub fn value(message: impl Into<String>) -> Self {
+        RateslibError::Value(message.into())
+    }
+
+    /// Return a [CurveError::Invalid] with the `message`.
+    pub fn curve(message: impl Into<String>) -> Self {
+        RateslibError::Curve(CurveError::Invalid(message.into()))
+    }
+
+    /// Return a [SplineError::Invalid] with the `message`.
+    pub fn spline(message: impl Into<String>) -> Self {
+        RateslibError::Spline(SplineError::Invalid(message.into()))
+    }
+
+    /// Return a [RateslibError::Solver] with the `message`.
+    pub fn solver(message: impl Into<String>) -> Self {
+        RateslibError::Solver(message.into())
+    }
+}
+
+impl fmt::Display for RateslibError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RateslibError::Curve(e) => write!(f, "{}", e),
+            RateslibError::Spline(e) => write!(f, "{}", e),
+            RateslibError::MixedDualTypes { operation } => {
+                write!(f, "Cannot mix dual types: {}", operation)
+            }
+            RateslibError::CurrencyMismatch { operation } => {
+                write!(f, "Cannot mix currencies: {}", operation)
+            }
+            RateslibError::InvalidDate { year, month, day } => write!(
+                f,
+                "`year`, `month` `day` are invalid: {}-{}-{}.",
+                year, month, day
+            ),
+            RateslibError::Calendar(message)
+            | RateslibError::Shape(message)
+            | RateslibError::Solver(message)
+            | RateslibError::Unsupported(message)
+            | RateslibError::Value(message)
+            | RateslibError::Type(message) => write!(f, "{}", message),
+            RateslibError::Cancelled => write!(f, "The computation was cancelled."),
+            #[cfg(feature = "python")]
+            RateslibError::Python(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for RateslibError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RateslibError::Curve(e) => Some(e),
+            RateslibError::Spline(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<CurveError> for RateslibError {
+    fn from(value: CurveError) -> Self {
+        RateslibError::Curve(value)
+    }
+}
+
+impl From<SplineError> for RateslibError {
+    fn from(value: SplineError) -> Self {
+        RateslibError::Spline(value)
+    }
+}
+
+#[cfg(feature = "python")]
+impl From<PyErr> for RateslibError {
+    fn from(value: PyErr) -> Self {
+        RateslibError::Python(PythonError::new(value))
+    }
+}
+
+#[cfg(feature = "python")]
+impl From<RateslibError> for PyErr {
+    fn from(value: RateslibError) -> Self {
+        match value {
+            RateslibError::Curve(e) => e.into(),
+            RateslibError::Spline(e) => e.into(),
+            RateslibError::MixedDualTypes { .. } => {
+                error_py::DualTypeError::new_err(value.to_string())
+            }
+            RateslibError::InvalidDate { .. } | RateslibError::Calendar(_) => {
+                error_py::CalendarError::new_err(value.to_string())
+            }
+            RateslibError::CurrencyMismatch { .. } => {
+                error_py::CurrencyError::new_err(value.to_string())
+            }
+            RateslibError::Shape(_) => error_py::LinalgError::new_err(value.to_string()),
+            RateslibError::Solver(_) => error_py::SolverError::new_err(value.to_string()),
+            RateslibError::Unsupported(_) => PyNotImplementedError::new_err(value.to_string()),
+            RateslibError::Cancelled => error_py::CancelledError::new_err(value.to_string()),
+            RateslibError::Value(message) => PyValueError::new_err(message),
+            RateslibError::Type(message) => PyTypeError::new_err(message),
+            RateslibError::Python(e) => e.into_inner(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_and_source() {
+        let e = RateslibError::from(SplineError::UnsortedKnots);
+        assert_eq!(
+            e.to_string(),
+            "The knot sequence `t` of a PPSpline must be non-decreasing."
+        );
+        assert!(std::error::Error::source(&e).is_some());
+        let e = RateslibError::MixedDualTypes {
+            operation: "Dual + Dual2".to_string(),
+        };
+        assert_eq!(e.to_string(), "Cannot mix dual types: Dual + Dual2");
+    }
+
+    #[test]
+    #[cfg(feature = "python")]
+    fn test_python_exception_classes() {
+        use pyo3::Python;
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let e: PyErr = RateslibError::from(CurveError::InsufficientNodes {
+                required: 2,
+                given: 1,
+            })
+            .into();
+            assert!(e.is_instance_of::<error_py::CurveError>(py));
+            assert!(e.is_instance_of::<error_py::RateslibError>(py));
+            assert!(e.is_instance_of::<pyo3::exceptions::PyValueError>(py));
+            let e: PyErr = RateslibError::MixedDualTypes {
+                operation: "Dual * Dual2".to_string(),
+            }
+            .into();
+            assert!(e.is_instance_of::<pyo3::exceptions::PyTypeError>(py));
+            let e: PyErr = RateslibError::solver("diverged").into();
+            assert!(e.is_instance_of::<error_py::SolverError>(py));
+            let e: PyErr = RateslibError::curve("invalid").into();
+            assert!(e.is_instance_of::<error_py::CurveError>(py));
+            let e: PyErr = RateslibError::value("invalid").into();
+            assert!(!e.is_instance_of::<error_py::RateslibError>(py));
+            assert!(e.is_instance_of::<pyo3::exceptions::PyValueError>(py));
+            // an exception raised in Python is returned unchanged
+            let e = RateslibError::from(pyo3::exceptions::PyKeyError::new_err("k"));
+            assert!(PyErr::from(e).is_instance_of::<pyo3::exceptions::PyKeyError>(py));
+        });
+    }
+}
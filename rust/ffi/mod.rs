@@ -0,0 +1,454 @@
+//! A minimal C interface to the Rust core, for consumers other than Python, such as C++ or
+//! Java services, which embed the library without the PyO3 layer.
+//!
+//! A curve set is created from JSON of [MarketQuote]s by curve name, with
+//! [rl_curve_set_new], and instruments priced against it with [rl_price] and [rl_risk], whose
+//! requests and responses are also JSON. Strings are UTF-8 and null terminated, and strings
+//! returned by the library are released with [rl_string_free]. On failure, functions return a
+//! null pointer and the error is available from [rl_last_error] and [rl_last_error_code].
+//!
+//! The shared and static libraries for linking are built by the `rateslib-ffi` crate of the
+//! workspace, which depends on the core without the Python bindings.
+//!
+//! ```text
+//! {"effective": "2024-01-03T00:00:00", "curves": {"eur": [{"spec": "eur_irs", "tenor": "1Y", "rate": 3.1}]}}
+//! {"instrument": {"quote": {"spec": "eur_irs", "tenor": "2Y", "rate": 2.9}}, "curve": "eur", "currency": "eur"}
+//! ```
+
+use crate::dual::{get_variable_tags, Dual};
+use crate::error::RateslibError;
+use crate::fx::rates::Ccy;
+use crate::instruments::{Instrument, Irs};
+use crate::interop::{calibrate_curve, quote_instrument, CurveCalibration, MarketQuote};
+use crate::periods::Curves;
+use crate::risk::{DeltaLadder, RiskMapping};
+use chrono::NaiveDateTime;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+/// The reasons that a call through the C interface fails.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FfiError {
+    /// A null pointer argument, named by the argument.
+    Null(&'static str),
+    /// A string argument which is not UTF-8.
+    Utf8,
+    /// A request which is not valid JSON of the expected form.
+    Json(String),
+    /// An error of the core library.
+    Core(RateslibError),
+    /// A panic of the core library, caught at the interface.
+    Panic(String),
+}
+
+impl FfiError {
+    /// Return the code of the error returned by [rl_last_error_code].
+    pub fn code(&self) -> c_int {
+        match self {
+            FfiError::Null(_) => 1,
+            FfiError::Utf8 => 2,
+            FfiError::Json(_) => 3,
+            FfiError::Core(_) => 4,
+            FfiError::Panic(_) => 5,
+        }
+    }
+}
+
+impl fmt::Display for FfiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FfiError::Null(arg) => write!(f, "The argument `{}` is null.", arg),
+            FfiError::Utf8 => write!(f, "A string argument is not UTF-8."),
+            FfiError::Json(e) => write!(f, "{}", e),
+            FfiError::Core(e) => write!(f, "{}", e),
+            FfiError::Panic(e) => write!(f, "The library panicked: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for FfiError {}
+
+impl From<RateslibError> for FfiError {
+    fn from(value: RateslibError) -> Self {
+        FfiError::Core(value)
+    }
+}
+
+impl From<serde_json::Error> for FfiError {
+    fn from(value: serde_json::Error) -> Self {
+        FfiError::Json(value.to_string())
+    }
+}
+
+/// A set of named curves, each calibrated to its [MarketQuote]s.
+pub struct CurveSet {
+    pub(crate) effective: NaiveDateTime,
+    pub(crate) curves: IndexMap<String, CurveCalibration>,
+    /// The `(curve, tenor)` label of each calibrating instrument, in the order of the curves.
+    pub(crate) labels: Vec<(String, String)>,
+}
+
+#[derive(Deserialize)]
+struct CurveSetRequest {
+    effective: NaiveDateTime,
+    curves: IndexMap<String, Vec<MarketQuote>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum InstrumentRequest {
+    Irs(Box<Irs>),
+    /// A swap of a registered specification starting at the effective date of the curve set.
+    Quote(MarketQuote),
+}
+
+#[derive(Deserialize)]
+struct PricingRequest {
+    instrument: InstrumentRequest,
+    /// The forecasting curve, which also discounts unless a `disc_curve` is given.
+    curve: String,
+    disc_curve: Option<String>,
+    currency: Option<String>,
+}
+
+#[derive(Serialize)]
+struct PriceResponse {
+    npv: f64,
+    rate: f64,
+}
+
+#[derive(Serialize)]
+struct RiskResponse {
+    npv: f64,
+    currency: String,
+    delta: IndexMap<String, IndexMap<String, f64>>,
+}
+
+impl CurveSet {
+    pub fn try_from_json(json: &str) -> Result<Self, FfiError> {
+        let request: CurveSetRequest = serde_json::from_str(json)?;
+        let mut curves = IndexMap::new();
+        let mut labels = vec![];
+        for (name, quotes) in request.curves.into_iter() {
+            // order the quotes by maturity, as are the instruments of the calibration
+            let mut quotes = quotes
+                .into_iter()
+                .map(|q| {
+                    let end = *quote_instrument(&q, request.effective)?
+                        .leg1()
+                        .schedule()
+                        .pschedule()
+                        .last()
+                        .ok_or_else(|| {
+                            RateslibError::value(format!(
+                                "The quote of tenor '{}' has an empty schedule.",
+                                q.tenor
+                            ))
+                        })?;
+                    Ok((end, q))
+                })
+                .collect::<Result<Vec<_>, RateslibError>>()?;
+            quotes.sort_by_key(|(end, _)| *end);
+            let quotes: Vec<MarketQuote> = quotes.into_iter().map(|(_, q)| q).collect();
+            let calibration = calibrate_curve(&quotes, request.effective, &name)?;
+            if !calibration.result.status.is_success() {
+                return Err(
+                    RateslibError::value(format!("Curve '{}' failed to calibrate.", name)).into(),
+                );
+            }
+            labels.extend(quotes.iter().map(|q| (name.clone(), q.tenor.clone())));
+            curves.insert(name, calibration);
+        }
+        Ok(Self {
+            effective: request.effective,
+            curves,
+            labels,
+        })
+    }
+
+    fn curve(&self, name: &str) -> Result<&CurveCalibration, FfiError> {
+        self.curves.get(name).ok_or_else(|| {
+            RateslibError::value(format!("Curve '{}' is not in the curve set.", name)).into()
+        })
+    }
+
+    /// Return the instrument and curves of a pricing request.
+    fn resolve(&self, request: &PricingRequest) -> Result<(Irs, Curves<'_>), FfiError> {
+        let irs = match &request.instrument {
+            InstrumentRequest::Irs(irs) => irs.as_ref().clone(),
+            InstrumentRequest::Quote(q) => quote_instrument(q, self.effective)?,
+        };
+        let forecasting = &self.curve(&request.curve)?.curve;
+        let discounting = match &request.disc_curve {
+            Some(name) => &self.curve(name)?.curve,
+            None => forecasting,
+        };
+        Ok((irs, Curves::new(Some(forecasting), Some(discounting))))
+    }
+
+    /// Return the price response JSON of a pricing request JSON.
+    pub fn price_json(&self, json: &str) -> Result<String, FfiError> {
+        let request: PricingRequest = serde_json::from_str(json)?;
+        let (irs, curves) = self.resolve(&request)?;
+        let response = PriceResponse {
+            npv: f64::from(&irs.npv(&curves)?),
+            rate: f64::from(&irs.rate(&curves)?),
+        };
+        Ok(serde_json::to_string(&response)?)
+    }
+
+    /// Return the risk response JSON of a pricing request JSON, with the delta to the quotes
+    /// of every curve of the set.
+    pub fn risk_json(&self, json: &str) -> Result<String, FfiError> {
+        let request: PricingRequest = serde_json::from_str(json)?;
+        let currency = request
+            .currency
+            .as_deref()
+            .ok_or_else(|| RateslibError::value("A risk request requires a `currency`."))?;
+        let currency = Ccy::try_new(currency)?;
+        let (irs, curves) = self.resolve(&request)?;
+        let npv = irs.npv(&curves)?;
+        let mut rates: Vec<Dual> = vec![];
+        let mut tags: Vec<String> = vec![];
+        for (name, calibration) in self.curves.iter() {
+            let curve = Curves::new(Some(&calibration.curve), Some(&calibration.curve));
+            for instrument in calibration.instruments.iter() {
+                rates.push(Dual::from(&instrument.rate(&curve)?));
+            }
+            let n = calibration.instruments.len();
+            tags.extend_from_slice(&get_variable_tags(name, n + 1)[1..]);
+        }
+        let mapping = RiskMapping::try_new(&rates, tags, self.labels.clone())?;
+        let ladder = DeltaLadder::try_new(&npv, &mapping, currency)?;
+        let response = RiskResponse {
+            npv: f64::from(&npv),
+            currency: currency.name.to_string(),
+            delta: ladder.grid().clone(),
+        };
+        Ok(serde_json::to_string(&response)?)
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<(c_int, CString)> = RefCell::new((0, CString::default()));
+}
+
+fn set_last_error(err: FfiError) {
+    let message = CString::new(err.to_string().replace('\0', "")).unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = (err.code(), message));
+}
+
+/// Read a UTF-8 string argument.
+unsafe fn read_str<'a>(s: *const c_char, arg: &'static str) -> Result<&'a str, FfiError> {
+    if s.is_null() {
+        return Err(FfiError::Null(arg));
+    }
+    CStr::from_ptr(s).to_str().map_err(|_| FfiError::Utf8)
+}
+
+/// Run `f`, converting a panic into an error, since unwinding across the C interface is
+/// undefined behaviour.
+fn guard<T>(f: impl FnOnce() -> Result<T, FfiError>) -> Result<T, FfiError> {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        let message = match (
+            payload.downcast_ref::<&str>(),
+            payload.downcast_ref::<String>(),
+        ) {
+            (Some(s), _) => s.to_string(),
+            (None, Some(s)) => s.clone(),
+            (None, None) => "unknown panic".to_string(),
+        };
+        Err(FfiError::Panic(message))
+    })
+}
+
+/// Return an owned string to the caller, or null recording the error.
+fn into_raw(result: Result<String, FfiError>) -> *mut c_char {
+    let result = result.and_then(|s| CString::new(s).map_err(|e| FfiError::Json(e.to_string())));
+    match result {
+        Ok(s) => s.into_raw(),
+        Err(e) => {
+            set_last_error(e);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Create a curve set from JSON, returning null on failure.
+///
+/// # Safety
+///
+/// `json` must be a null terminated string. The curve set must be released with
+/// [rl_curve_set_free].
+#[no_mangle]
+pub unsafe extern "C" fn rl_curve_set_new(json: *const c_char) -> *mut CurveSet {
+    match guard(|| CurveSet::try_from_json(read_str(json, "json")?)) {
+        Ok(set) => Box::into_raw(Box::new(set)),
+        Err(e) => {
+            set_last_error(e);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Release a curve set.
+///
+/// # Safety
+///
+/// `set` must be null or returned by [rl_curve_set_new], and not already released.
+#[no_mangle]
+pub unsafe extern "C" fn rl_curve_set_free(set: *mut CurveSet) {
+    if !set.is_null() {
+        drop(Box::from_raw(set));
+    }
+}
+
+/// Return the JSON `{"npv", "rate"}` of the instrument of a pricing request, or null on
+/// failure.
+///
+/// # Safety
+///
+/// `set` must be a live curve set and `json` a null terminated string. The returned string
+/// must be released with [rl_string_free].
+#[no_mangle]
+pub unsafe extern "C" fn rl_price(set: *const CurveSet, json: *const c_char) -> *mut c_char {
+    into_raw(guard(|| {
+        let set = set.as_ref().ok_or(FfiError::Null("set"))?;
+        set.price_json(read_str(json, "json")?)
+    }))
+}
+
+/// Return the JSON `{"npv", "currency", "delta"}` of the instrument of a pricing request,
+/// where `delta` is the change in NPV for a 1bp increase in each quote by curve and tenor,
+/// or null on failure.
+///
+/// # Safety
+///
+/// `set` must be a live curve set and `json` a null terminated string. The returned string
+/// must be released with [rl_string_free].
+#[no_mangle]
+pub unsafe extern "C" fn rl_risk(set: *const CurveSet, json: *const c_char) -> *mut c_char {
+    into_raw(guard(|| {
+        let set = set.as_ref().ok_or(FfiError::Null("set"))?;
+        set.risk_json(read_str(json, "json")?)
+    }))
+}
+
+/// Return the message of the last error on the calling thread.
+///
+/// The string is owned by the library and valid until the next failing call on the thread.
+#[no_mangle]
+pub extern "C" fn rl_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().1.as_ptr())
+}
+
+/// Return the [code](FfiError::code) of the last error on the calling thread, or zero if no
+/// call on the thread has failed.
+#[no_mangle]
+pub extern "C" fn rl_last_error_code() -> c_int {
+    LAST_ERROR.with(|e| e.borrow().0)
+}
+
+/// Release a string returned by the library.
+///
+/// # Safety
+///
+/// `s` must be null or a string returned by the library, and not already released.
+#[no_mangle]
+pub unsafe extern "C" fn rl_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    const CURVES: &str = r#"{"effective": "2024-01-03T00:00:00", "curves": {"eur": [
+        {"spec": "eur_irs", "tenor": "2Y", "rate": 2.85},
+        {"spec": "eur_irs", "tenor": "1Y", "rate": 3.10},
+        {"spec": "eur_irs", "tenor": "5Y", "rate": 2.65}
+    ]}}"#;
+
+    unsafe fn call(
+        f: unsafe extern "C" fn(*const CurveSet, *const c_char) -> *mut c_char,
+        set: *const CurveSet,
+        json: &str,
+    ) -> Result<Value, String> {
+        let json = CString::new(json).unwrap();
+        let out = f(set, json.as_ptr());
+        if out.is_null() {
+            return Err(CStr::from_ptr(rl_last_error())
+                .to_str()
+                .unwrap()
+                .to_string());
+        }
+        let value = serde_json::from_str(CStr::from_ptr(out).to_str().unwrap()).unwrap();
+        rl_string_free(out);
+        Ok(value)
+    }
+
+    #[test]
+    fn test_price_and_risk() {
+        unsafe {
+            let json = CString::new(CURVES).unwrap();
+            let set = rl_curve_set_new(json.as_ptr());
+            assert!(!set.is_null());
+            let request = r#"{"instrument": {"quote": {"spec": "eur_irs", "tenor": "2Y",
+                "rate": 2.95}}, "curve": "eur", "currency": "eur"}"#;
+            let price = call(rl_price, set, request).unwrap();
+            assert!((price["rate"].as_f64().unwrap() - 2.85).abs() < 1e-6);
+            // paying a fixed rate above the par rate
+            assert!(price["npv"].as_f64().unwrap() < 0.0);
+            let risk = call(rl_risk, set, request).unwrap();
+            assert_eq!(risk["currency"], "eur");
+            // the swap is sensitive mostly to the quote of its own tenor
+            let delta = &risk["delta"]["eur"];
+            assert!(delta["2Y"].as_f64().unwrap() > 100.0);
+            assert!(delta["1Y"].as_f64().unwrap().abs() < 1.0);
+            assert!(delta["5Y"].as_f64().unwrap().abs() < 1e-6);
+            let err = call(rl_price, set, r#"{"instrument": {}, "curve": "usd"}"#);
+            assert!(err.is_err());
+            let err = call(
+                rl_price,
+                set,
+                r#"{"instrument": {"quote": {"spec": "eur_irs", "tenor": "1Y", "rate": 3.0}},
+                "curve": "usd"}"#,
+            );
+            assert_eq!(err.unwrap_err(), "Curve 'usd' is not in the curve set.");
+            rl_curve_set_free(set);
+        }
+    }
+
+    #[test]
+    fn test_invalid_curve_set() {
+        unsafe {
+            assert!(rl_curve_set_new(ptr::null()).is_null());
+            assert_eq!(rl_last_error_code(), 1);
+            let json = CString::new(r#"{"effective": "2024-01-03T00:00:00"}"#).unwrap();
+            assert!(rl_curve_set_new(json.as_ptr()).is_null());
+            assert_eq!(rl_last_error_code(), 3);
+            let message = CStr::from_ptr(rl_last_error()).to_str().unwrap();
+            assert!(message.contains("curves"));
+            assert!(rl_price(ptr::null(), json.as_ptr()).is_null());
+            assert_eq!(rl_last_error_code(), 1);
+        }
+    }
+
+    #[test]
+    fn test_panic_is_caught() {
+        let result = guard(|| -> Result<(), FfiError> { panic!("an invariant failed") });
+        let err = result.unwrap_err();
+        assert_eq!(err, FfiError::Panic("an invariant failed".to_string()));
+        assert_eq!(err.code(), 5);
+        assert!(into_raw(Err(err)).is_null());
+        assert_eq!(rl_last_error_code(), 5);
+    }
+}
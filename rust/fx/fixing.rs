@@ -0,0 +1,160 @@
+//! Register the FX fixing references, such as the WMR 4pm fix, by which non-deliverable and
+//! path dependent FX products determine their fixing dates.
+
+use crate::calendars::{DateRoll, NamedCal};
+use crate::error::RateslibError;
+use chrono::{NaiveDateTime, NaiveTime};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::sync::{OnceLock, RwLock};
+
+/// The conventions of an FX fixing reference, registered by name such as `"wmr_4pm"`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FxFixingSource {
+    /// The name of the calendar of the business days on which the fixing is published.
+    pub calendar: String,
+    /// The local time of day of the fixing.
+    pub time: NaiveTime,
+    /// The time zone of the fixing time, such as `"Europe/London"`.
+    pub time_zone: String,
+    /// The number of business days of the calendar by which the fixing precedes settlement.
+    pub lag: i8,
+}
+
+impl FxFixingSource {
+    fn new(calendar: &str, hour: u32, min: u32, time_zone: &str, lag: i8) -> Self {
+        Self {
+            calendar: calendar.to_string(),
+            time: NaiveTime::from_hms_opt(hour, min, 0).unwrap(),
+            time_zone: time_zone.to_string(),
+            lag,
+        }
+    }
+
+    /// Return the calendar of the source.
+    pub fn named_calendar(&self) -> Result<NamedCal, RateslibError> {
+        NamedCal::try_new(&self.calendar)
+    }
+
+    /// Return the fixing date for a `settlement` date, at the local fixing time.
+    ///
+    /// The fixing is `lag` business days of the calendar before settlement, where a
+    /// settlement on a non-business day first rolls back to the preceding business day as one
+    /// day of the lag.
+    pub fn fixing_date(&self, settlement: &NaiveDateTime) -> Result<NaiveDateTime, RateslibError> {
+        let date = self
+            .named_calendar()?
+            .lag(&settlement.date().into(), -self.lag, false);
+        Ok(date.date().and_time(self.time))
+    }
+
+    /// Return the fixing date of each of the `settlements`, such as the settlement dates of
+    /// the periods of a target redemption forward.
+    pub fn fixing_dates(
+        &self,
+        settlements: &[NaiveDateTime],
+    ) -> Result<Vec<NaiveDateTime>, RateslibError> {
+        settlements.iter().map(|s| self.fixing_date(s)).collect()
+    }
+}
+
+fn default_sources() -> IndexMap<String, FxFixingSource> {
+    use FxFixingSource as S;
+    IndexMap::from_iter(
+        [
+            ("wmr_4pm", S::new("ldn", 16, 0, "Europe/London", 2)),
+            ("ecb", S::new("tgt", 14, 15, "Europe/Berlin", 2)),
+            ("bfix", S::new("bus", 16, 0, "Europe/London", 2)),
+            ("bfix_tyo", S::new("tyo", 15, 0, "Asia/Tokyo", 2)),
+        ]
+        .map(|(k, v)| (k.to_string(), v)),
+    )
+}
+
+fn registry() -> &'static RwLock<IndexMap<String, FxFixingSource>> {
+    static REGISTRY: OnceLock<RwLock<IndexMap<String, FxFixingSource>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(default_sources()))
+}
+
+/// Return the [FxFixingSource] registered under `name`.
+pub fn get_fixing_source(name: &str) -> Result<FxFixingSource, RateslibError> {
+    registry()
+        .read()
+        .unwrap()
+        .get(&name.to_lowercase())
+        .cloned()
+        .ok_or_else(|| {
+            RateslibError::value(format!(
+                "`source` '{}' is not a known FX fixing source.",
+                name
+            ))
+        })
+}
+
+/// Register an [FxFixingSource] under `name`, replacing any source, including a default
+/// source, of the same name.
+pub fn register_fixing_source(name: &str, source: FxFixingSource) {
+    registry()
+        .write()
+        .unwrap()
+        .insert(name.to_lowercase(), source);
+}
+
+/// Restore the default source of `name`, or remove a user defined source.
+pub fn reset_fixing_source(name: &str) {
+    let name = name.to_lowercase();
+    let mut sources = registry().write().unwrap();
+    match default_sources().shift_remove(&name) {
+        Some(source) => sources.insert(name, source),
+        None => sources.shift_remove(&name),
+    };
+}
+
+/// Return the names of the registered fixing sources.
+pub fn fixing_source_names() -> Vec<String> {
+    registry().read().unwrap().keys().cloned().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::ndt;
+
+    #[test]
+    fn test_fixing_dates() {
+        let wmr = get_fixing_source("WMR_4PM").unwrap();
+        // settlement on Wednesday 3rd January 2024 fixes on Friday 29th December 2023
+        let fixing = wmr.fixing_date(&ndt(2024, 1, 3)).unwrap();
+        assert_eq!(
+            fixing,
+            ndt(2023, 12, 29).date().and_hms_opt(16, 0, 0).unwrap()
+        );
+        // a settlement on Saturday rolls back to Friday as the first day of the lag
+        assert_eq!(
+            wmr.fixing_date(&ndt(2024, 1, 6)).unwrap().date(),
+            ndt(2024, 1, 4).date()
+        );
+        let ecb = get_fixing_source("ecb").unwrap();
+        let fixings = ecb
+            .fixing_dates(&[ndt(2024, 4, 3), ndt(2024, 5, 3)])
+            .unwrap();
+        // Easter Monday 1st April 2024 is a TARGET holiday
+        assert_eq!(
+            fixings[0],
+            ndt(2024, 3, 28).date().and_hms_opt(14, 15, 0).unwrap()
+        );
+        assert_eq!(fixings[1].date(), ndt(2024, 4, 30).date());
+    }
+
+    #[test]
+    fn test_register_fixing_source() {
+        assert!(get_fixing_source("ptax").is_err());
+        let ptax = FxFixingSource::new("nyc", 13, 0, "America/Sao_Paulo", 2);
+        register_fixing_source("PTAX", ptax.clone());
+        assert_eq!(get_fixing_source("ptax").unwrap(), ptax);
+        assert!(fixing_source_names().contains(&"ptax".to_string()));
+        reset_fixing_source("ptax");
+        assert!(get_fixing_source("ptax").is_err());
+        assert!(fixing_source_names().contains(&"wmr_4pm".to_string()));
+    }
+}
@@ -1,2 +1,6 @@
+pub mod fixing;
+pub mod options;
 pub mod rates;
+#[cfg(feature = "python")]
 pub mod rates_py;
+pub mod volatility;
@@ -0,0 +1,107 @@
+use crate::error::RateslibError;
+use crate::fx::options::{GkMarket, OptionType};
+use crate::lattice::SpotTree;
+use serde::{Deserialize, Serialize};
+
+/// An FX option on one unit of foreign currency, exercisable at any time up to expiry,
+/// valued on a [SpotTree].
+///
+/// Greeks are measured from the lattice rather than by automatic differentiation, since the
+/// exercise boundary has no closed form.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FxAmericanOption {
+    pub(crate) strike: f64,
+    pub(crate) expiry: f64,
+    pub(crate) option_type: OptionType,
+    pub(crate) steps: usize,
+}
+
+impl FxAmericanOption {
+    /// Create an [FxAmericanOption] with an `expiry` in years, valued on a lattice of `steps`
+    /// time steps.
+    pub fn new(strike: f64, expiry: f64, option_type: OptionType, steps: usize) -> Self {
+        Self {
+            strike,
+            expiry,
+            option_type,
+            steps,
+        }
+    }
+
+    fn tree(&self, market: &GkMarket) -> Result<SpotTree, RateslibError> {
+        market.validate(self.expiry)?;
+        let r_d = f64::from(&market.domestic_rate);
+        SpotTree::try_new(
+            f64::from(&market.spot),
+            self.expiry,
+            r_d,
+            r_d - f64::from(&market.foreign_rate),
+            f64::from(&market.volatility),
+            self.steps,
+        )
+    }
+
+    fn payoff(&self) -> impl Fn(f64) -> f64 {
+        let (k, phi) = (self.strike, self.option_type.phi());
+        move |s| (phi * (s - k)).max(0.0)
+    }
+
+    /// Return the value of the option.
+    pub fn npv(&self, market: &GkMarket) -> Result<f64, RateslibError> {
+        Ok(self.tree(market)?.value(self.payoff(), true))
+    }
+
+    /// Return the value, spot delta and spot gamma of the option.
+    pub fn npv_delta_gamma(&self, market: &GkMarket) -> Result<(f64, f64, f64), RateslibError> {
+        Ok(self.tree(market)?.value_delta_gamma(self.payoff(), true))
+    }
+
+    /// Return the early exercise premium over the equivalent European option on the lattice.
+    pub fn early_exercise_premium(&self, market: &GkMarket) -> Result<f64, RateslibError> {
+        let tree = self.tree(market)?;
+        Ok(tree.value(self.payoff(), true) - tree.value(self.payoff(), false))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dual::Number;
+    use crate::fx::options::FxVanillaOption;
+
+    fn market() -> GkMarket {
+        GkMarket::new(
+            Number::F64(1.10),
+            Number::F64(0.06),
+            Number::F64(0.01),
+            Number::F64(0.12),
+        )
+    }
+
+    #[test]
+    fn test_american_exceeds_european() {
+        let market = market();
+        for option_type in [OptionType::Call, OptionType::Put] {
+            let american = FxAmericanOption::new(1.12, 1.0, option_type, 300);
+            let european = f64::from(
+                &FxVanillaOption::new(1.12, 1.0, option_type)
+                    .npv(&market)
+                    .unwrap(),
+            );
+            assert!(american.npv(&market).unwrap() >= european - 1e-4);
+        }
+        // with a high domestic rate an American put carries an early exercise premium
+        let put = FxAmericanOption::new(1.12, 1.0, OptionType::Put, 300);
+        assert!(put.early_exercise_premium(&market).unwrap() > 1e-4);
+    }
+
+    #[test]
+    fn test_lattice_greeks() {
+        let market = market();
+        let option = FxAmericanOption::new(1.12, 1.0, OptionType::Put, 300);
+        let (value, delta, gamma) = option.npv_delta_gamma(&market).unwrap();
+        assert!((value - option.npv(&market).unwrap()).abs() < 1e-14);
+        assert!(delta < 0.0 && delta > -1.0);
+        assert!(gamma > 0.0);
+    }
+}
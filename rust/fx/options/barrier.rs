@@ -0,0 +1,339 @@
+use crate::dual::{MathFuncs, Number};
+use crate::error::RateslibError;
+use crate::fx::options::vanilla::gk_price;
+use crate::fx::options::{GkMarket, OptionType};
+use num_traits::Pow;
+use serde::{Deserialize, Serialize};
+
+/// The direction of a barrier from spot and whether touching it activates or extinguishes
+/// the option.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BarrierType {
+    DownIn,
+    DownOut,
+    UpIn,
+    UpOut,
+}
+
+impl BarrierType {
+    fn is_down(&self) -> bool {
+        matches!(self, BarrierType::DownIn | BarrierType::DownOut)
+    }
+
+    fn is_in(&self) -> bool {
+        matches!(self, BarrierType::DownIn | BarrierType::UpIn)
+    }
+}
+
+/// A European FX option with a single barrier, valued with the Reiner-Rubinstein formulae.
+///
+/// A `rebate` is paid at expiry if an in-option is not activated, or when an out-option is
+/// extinguished. Barriers monitored at discrete intervals are valued as continuously
+/// monitored options with the barrier shifted away from spot by the Broadie-Glasserman-Kou
+/// adjustment, `exp(0.5826 σ √Δt)`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FxBarrierOption {
+    pub(crate) strike: f64,
+    pub(crate) barrier: f64,
+    pub(crate) expiry: f64,
+    pub(crate) option_type: OptionType,
+    pub(crate) barrier_type: BarrierType,
+    pub(crate) rebate: f64,
+    pub(crate) monitoring: Option<f64>,
+}
+
+impl FxBarrierOption {
+    /// Create an [FxBarrierOption] with an `expiry` in years and, for discretely monitored
+    /// barriers, a `monitoring` interval in years.
+    pub fn new(
+        strike: f64,
+        barrier: f64,
+        expiry: f64,
+        option_type: OptionType,
+        barrier_type: BarrierType,
+        rebate: f64,
+        monitoring: Option<f64>,
+    ) -> Self {
+        Self {
+            strike,
+            barrier,
+            expiry,
+            option_type,
+            barrier_type,
+            rebate,
+            monitoring,
+        }
+    }
+
+    /// Return the continuously monitored barrier equivalent to the option's barrier.
+    pub fn effective_barrier(&self, market: &GkMarket) -> Number {
+        let h = Number::F64(self.barrier);
+        match self.monitoring {
+            None => h,
+            Some(dt) => {
+                let sign = if self.barrier_type.is_down() {
+                    -1.0
+                } else {
+                    1.0
+                };
+                h * (&market.volatility * (0.5826 * sign * dt.sqrt())).exp()
+            }
+        }
+    }
+
+    /// Return the value of the option.
+    pub fn npv(&self, market: &GkMarket) -> Result<Number, RateslibError> {
+        market.validate(self.expiry)?;
+        let t = self.expiry;
+        let (s, r, r_f, vol) = (
+            &market.spot,
+            &market.domestic_rate,
+            &market.foreign_rate,
+            &market.volatility,
+        );
+        let (phi, k) = (self.option_type.phi(), self.strike);
+        let h = self.effective_barrier(market);
+        let eta = if self.barrier_type.is_down() {
+            1.0
+        } else {
+            -1.0
+        };
+
+        // a barrier already breached has activated or extinguished the option
+        let breached = match self.barrier_type.is_down() {
+            true => *s <= h,
+            false => *s >= h,
+        };
+        if breached {
+            return Ok(match self.barrier_type.is_in() {
+                true => gk_price(s, k, t, r, r_f, vol, phi),
+                false => Number::F64(self.rebate),
+            });
+        }
+
+        let v = vol * t.sqrt();
+        let vol2 = vol.pow(2.0);
+        let mu = (r - r_f - &vol2 * 0.5) / &vol2;
+        let df_f = (r_f * -t).exp();
+        let df_d = (r * -t).exp();
+        let hs = &h / s;
+        let hs_pow = |p: &Number| -> Number { (p * hs.log()).exp() };
+        let n = |x: Number| x.norm_cdf();
+        let one_mu_v = (&mu + 1.0) * &v;
+
+        let x1 = (s / k).log() / &v + &one_mu_v;
+        let x2 = (s / &h).log() / &v + &one_mu_v;
+        let y1 = ((&h).pow(2.0) / (s * k)).log() / &v + &one_mu_v;
+        let y2 = hs.log() / &v + &one_mu_v;
+        let hs_2mu1 = hs_pow(&((&mu + 1.0) * 2.0));
+        let hs_2mu = hs_pow(&(&mu * 2.0));
+
+        let a = (s * &df_f * n(&x1 * phi) - &df_d * k * n((&x1 - &v) * phi)) * phi;
+        let b = (s * &df_f * n(&x2 * phi) - &df_d * k * n((&x2 - &v) * phi)) * phi;
+        let c =
+            (s * &df_f * &hs_2mu1 * n(&y1 * eta) - &df_d * k * &hs_2mu * n((&y1 - &v) * eta)) * phi;
+        let d =
+            (s * &df_f * &hs_2mu1 * n(&y2 * eta) - &df_d * k * &hs_2mu * n((&y2 - &v) * eta)) * phi;
+        let (no_touch, one_touch) = touch_values(market, &h, t, eta);
+        let e = no_touch * self.rebate;
+        let f = one_touch * self.rebate;
+
+        let above = k > f64::from(&h);
+        let value = match (self.barrier_type, self.option_type, above) {
+            (BarrierType::DownIn, OptionType::Call, true) => c + e,
+            (BarrierType::DownIn, OptionType::Call, false) => a - b + d + e,
+            (BarrierType::UpIn, OptionType::Call, true) => a + e,
+            (BarrierType::UpIn, OptionType::Call, false) => b - c + d + e,
+            (BarrierType::DownIn, OptionType::Put, true) => b - c + d + e,
+            (BarrierType::DownIn, OptionType::Put, false) => a + e,
+            (BarrierType::UpIn, OptionType::Put, true) => a - b + d + e,
+            (BarrierType::UpIn, OptionType::Put, false) => c + e,
+            (BarrierType::DownOut, OptionType::Call, true) => a - c + f,
+            (BarrierType::DownOut, OptionType::Call, false) => b - d + f,
+            (BarrierType::UpOut, OptionType::Call, true) => f,
+            (BarrierType::UpOut, OptionType::Call, false) => a - b + c - d + f,
+            (BarrierType::DownOut, OptionType::Put, true) => a - b + c - d + f,
+            (BarrierType::DownOut, OptionType::Put, false) => f,
+            (BarrierType::UpOut, OptionType::Put, true) => b - d + f,
+            (BarrierType::UpOut, OptionType::Put, false) => a - c + f,
+        };
+        Ok(value)
+    }
+}
+
+/// Return the value of a unit of domestic currency paid at expiry if the barrier `h` is
+/// not touched before the expiry `t`, and of a unit paid when it is touched, with `eta` equal
+/// to `1` for a barrier below spot and `-1` for a barrier above.
+pub(crate) fn touch_values(market: &GkMarket, h: &Number, t: f64, eta: f64) -> (Number, Number) {
+    let (s, r, r_f, vol) = (
+        &market.spot,
+        &market.domestic_rate,
+        &market.foreign_rate,
+        &market.volatility,
+    );
+    let v = vol * t.sqrt();
+    let vol2 = vol.pow(2.0);
+    let mu = (r - r_f - &vol2 * 0.5) / &vol2;
+    let lambda = ((&mu).pow(2.0) + r * 2.0 / &vol2).pow(0.5);
+    let hs = h / s;
+    let hs_pow = |p: &Number| -> Number { (p * hs.log()).exp() };
+    let n = |x: Number| x.norm_cdf();
+
+    let x2 = (s / h).log() / &v + (&mu + 1.0) * &v;
+    let y2 = hs.log() / &v + (&mu + 1.0) * &v;
+    let z = hs.log() / &v + &lambda * &v;
+    let no_touch =
+        (r * -t).exp() * (n((&x2 - &v) * eta) - hs_pow(&(&mu * 2.0)) * n((&y2 - &v) * eta));
+    let one_touch = hs_pow(&(&mu + &lambda)) * n(&z * eta)
+        + hs_pow(&(&mu - &lambda)) * n((&z - &lambda * &v * 2.0) * eta);
+    (no_touch, one_touch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dual::Gradient1;
+    use crate::fx::options::vanilla::tests::market_fixture;
+    use crate::fx::options::FxVanillaOption;
+
+    fn value(option: &FxBarrierOption, market: &GkMarket) -> f64 {
+        f64::from(&option.npv(market).unwrap())
+    }
+
+    #[test]
+    fn test_in_out_parity() {
+        let market = market_fixture();
+        for (option_type, strike) in [(OptionType::Call, 1.08), (OptionType::Put, 1.14)] {
+            let vanilla = f64::from(
+                &FxVanillaOption::new(strike, 0.5, option_type)
+                    .npv(&market)
+                    .unwrap(),
+            );
+            for (barrier, types) in [
+                (1.02, [BarrierType::DownIn, BarrierType::DownOut]),
+                (1.20, [BarrierType::UpIn, BarrierType::UpOut]),
+            ] {
+                let total: f64 = types
+                    .iter()
+                    .map(|bt| {
+                        let opt =
+                            FxBarrierOption::new(strike, barrier, 0.5, option_type, *bt, 0.0, None);
+                        value(&opt, &market)
+                    })
+                    .sum();
+                assert!((total - vanilla).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_haug_reference() {
+        // Haug, The Complete Guide to Option Pricing Formulas, table 4-13: S=100, T=0.5,
+        // r=8%, b=4%, K=90, H=95, rebate=3 and vol=25%
+        let market = GkMarket::new(
+            Number::F64(100.0),
+            Number::F64(0.08),
+            Number::F64(0.04),
+            Number::F64(0.25),
+        );
+        let down_out = FxBarrierOption::new(
+            90.0,
+            95.0,
+            0.5,
+            OptionType::Call,
+            BarrierType::DownOut,
+            3.0,
+            None,
+        );
+        assert!((value(&down_out, &market) - 9.0246).abs() < 1e-4);
+        let down_in = FxBarrierOption::new(
+            90.0,
+            95.0,
+            0.5,
+            OptionType::Call,
+            BarrierType::DownIn,
+            3.0,
+            None,
+        );
+        assert!((value(&down_in, &market) - 7.7627).abs() < 1e-4);
+        let up_out = FxBarrierOption::new(
+            90.0,
+            105.0,
+            0.5,
+            OptionType::Call,
+            BarrierType::UpOut,
+            3.0,
+            None,
+        );
+        assert!((value(&up_out, &market) - 2.6789).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_discrete_monitoring() {
+        let market = market_fixture();
+        let continuous = FxBarrierOption::new(
+            1.08,
+            1.02,
+            0.5,
+            OptionType::Call,
+            BarrierType::DownOut,
+            0.0,
+            None,
+        );
+        let daily = FxBarrierOption::new(
+            1.08,
+            1.02,
+            0.5,
+            OptionType::Call,
+            BarrierType::DownOut,
+            0.0,
+            Some(1.0 / 252.0),
+        );
+        // a discretely monitored knock-out is less likely to be extinguished
+        assert!(value(&daily, &market) > value(&continuous, &market));
+        assert!(f64::from(&daily.effective_barrier(&market)) < 1.02);
+    }
+
+    #[test]
+    fn test_breached_barrier() {
+        let market = market_fixture();
+        let knock_in = FxBarrierOption::new(
+            1.08,
+            1.15,
+            0.5,
+            OptionType::Call,
+            BarrierType::DownIn,
+            0.0,
+            None,
+        );
+        let vanilla = FxVanillaOption::new(1.08, 0.5, OptionType::Call)
+            .npv(&market)
+            .unwrap();
+        assert!((value(&knock_in, &market) - f64::from(&vanilla)).abs() < 1e-14);
+    }
+
+    #[test]
+    fn test_ad_greeks() {
+        let market = market_fixture();
+        let opt = FxBarrierOption::new(
+            1.08,
+            1.02,
+            0.5,
+            OptionType::Call,
+            BarrierType::DownOut,
+            0.0,
+            None,
+        );
+        let delta = match opt.npv(&market).unwrap() {
+            Number::Dual(d) => d.gradient1(vec!["spot".to_string()])[0],
+            _ => panic!("expected a Dual"),
+        };
+        let h = 1e-6;
+        let mut bumped = market.clone();
+        bumped.spot = Number::F64(1.10 + h);
+        let up = value(&opt, &bumped);
+        bumped.spot = Number::F64(1.10 - h);
+        let down = value(&opt, &bumped);
+        assert!((delta - (up - down) / (2.0 * h)).abs() < 1e-6);
+    }
+}
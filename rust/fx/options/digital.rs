@@ -0,0 +1,217 @@
+use crate::dual::{MathFuncs, Number};
+use crate::error::RateslibError;
+use crate::fx::options::barrier::touch_values;
+use crate::fx::options::{GkMarket, OptionType};
+use crate::models::{black76_digital, DigitalMethod, DigitalPayout};
+use serde::{Deserialize, Serialize};
+
+/// A European digital FX option, valued in domestic currency.
+///
+/// A [DigitalPayout::Cash] option pays a domestic `amount` and a [DigitalPayout::Asset]
+/// option pays one unit of foreign currency if it expires in the money. Valued with
+/// [DigitalMethod::CallSpread] the option is replicated by vanilla options struck `width`
+/// apart, which bounds the delta and gamma of the option close to the strike and expiry.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FxDigitalOption {
+    pub(crate) strike: f64,
+    pub(crate) expiry: f64,
+    pub(crate) option_type: OptionType,
+    pub(crate) payout: DigitalPayout,
+    pub(crate) method: DigitalMethod,
+}
+
+impl FxDigitalOption {
+    /// Create an [FxDigitalOption] with an `expiry` in years.
+    pub fn new(
+        strike: f64,
+        expiry: f64,
+        option_type: OptionType,
+        payout: DigitalPayout,
+        method: DigitalMethod,
+    ) -> Self {
+        Self {
+            strike,
+            expiry,
+            option_type,
+            payout,
+            method,
+        }
+    }
+
+    /// Return the value of the option.
+    pub fn npv(&self, market: &GkMarket) -> Result<Number, RateslibError> {
+        market.validate(self.expiry)?;
+        let df = (&market.domestic_rate * -self.expiry).exp();
+        Ok(black76_digital(
+            &market.forward(self.expiry),
+            self.strike,
+            self.expiry,
+            &market.volatility,
+            &df,
+            self.option_type.phi(),
+            self.payout,
+            self.method,
+        ))
+    }
+}
+
+/// The payout of an [FxTouchOption].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TouchType {
+    /// Pays when the barrier is touched.
+    OneTouchAtHit,
+    /// Pays at expiry if the barrier has been touched.
+    OneTouchAtExpiry,
+    /// Pays at expiry if the barrier has not been touched.
+    NoTouch,
+}
+
+/// An FX option paying a domestic `amount` depending upon whether spot touches a
+/// continuously monitored barrier before expiry.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FxTouchOption {
+    pub(crate) barrier: f64,
+    pub(crate) expiry: f64,
+    pub(crate) amount: f64,
+    pub(crate) touch_type: TouchType,
+    pub(crate) barrier_above: bool,
+}
+
+impl FxTouchOption {
+    /// Create an [FxTouchOption] with an `expiry` in years and a `barrier` above or below
+    /// spot as given by `barrier_above`.
+    pub fn new(
+        barrier: f64,
+        expiry: f64,
+        amount: f64,
+        touch_type: TouchType,
+        barrier_above: bool,
+    ) -> Self {
+        Self {
+            barrier,
+            expiry,
+            amount,
+            touch_type,
+            barrier_above,
+        }
+    }
+
+    /// Return the value of the option.
+    pub fn npv(&self, market: &GkMarket) -> Result<Number, RateslibError> {
+        market.validate(self.expiry)?;
+        let t = self.expiry;
+        let df = (&market.domestic_rate * -t).exp();
+        let breached = match self.barrier_above {
+            true => market.spot >= self.barrier,
+            false => market.spot <= self.barrier,
+        };
+        let value = match (breached, self.touch_type) {
+            (true, TouchType::OneTouchAtHit) => Number::F64(1.0),
+            (true, TouchType::OneTouchAtExpiry) => df,
+            (true, TouchType::NoTouch) => Number::F64(0.0),
+            (false, touch_type) => {
+                let eta = if self.barrier_above { -1.0 } else { 1.0 };
+                let (no_touch, one_touch) =
+                    touch_values(market, &Number::F64(self.barrier), t, eta);
+                match touch_type {
+                    TouchType::OneTouchAtHit => one_touch,
+                    TouchType::OneTouchAtExpiry => df - no_touch,
+                    TouchType::NoTouch => no_touch,
+                }
+            }
+        };
+        Ok(value * self.amount)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dual::Gradient1;
+    use crate::fx::options::vanilla::tests::market_fixture;
+    use crate::fx::options::FxVanillaOption;
+
+    fn spot_delta(value: &Number) -> f64 {
+        match value {
+            Number::Dual(d) => d.gradient1(vec!["spot".to_string()])[0],
+            _ => panic!("expected a Dual"),
+        }
+    }
+
+    #[test]
+    fn test_digital_decomposition() {
+        // an asset-or-nothing call less a strike of cash-or-nothing calls is a vanilla call
+        let market = market_fixture();
+        let asset = FxDigitalOption::new(
+            1.12,
+            0.5,
+            OptionType::Call,
+            DigitalPayout::Asset,
+            DigitalMethod::Analytic,
+        );
+        let cash = FxDigitalOption::new(
+            1.12,
+            0.5,
+            OptionType::Call,
+            DigitalPayout::Cash { amount: 1.12 },
+            DigitalMethod::Analytic,
+        );
+        let vanilla = FxVanillaOption::new(1.12, 0.5, OptionType::Call)
+            .npv(&market)
+            .unwrap();
+        let diff = asset.npv(&market).unwrap() - cash.npv(&market).unwrap() - vanilla;
+        assert!(f64::from(&diff).abs() < 1e-12);
+        assert!(spot_delta(&diff).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_call_spread_replication() {
+        let market = market_fixture();
+        let payout = DigitalPayout::Cash { amount: 1.0 };
+        let analytic =
+            FxDigitalOption::new(1.12, 0.5, OptionType::Put, payout, DigitalMethod::Analytic);
+        let narrow = FxDigitalOption::new(
+            1.12,
+            0.5,
+            OptionType::Put,
+            payout,
+            DigitalMethod::CallSpread { width: 1e-4 },
+        );
+        let (a, b) = (analytic.npv(&market).unwrap(), narrow.npv(&market).unwrap());
+        assert!((f64::from(&a) - f64::from(&b)).abs() < 1e-6);
+        assert!((spot_delta(&a) - spot_delta(&b)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_touch_parity() {
+        let market = market_fixture();
+        let df = (-0.04_f64 * 0.5).exp();
+        let value = |touch_type: TouchType| {
+            let option = FxTouchOption::new(1.15, 0.5, 1e6, touch_type, true);
+            f64::from(&option.npv(&market).unwrap())
+        };
+        let (at_hit, at_expiry) = (
+            value(TouchType::OneTouchAtHit),
+            value(TouchType::OneTouchAtExpiry),
+        );
+        assert!((at_expiry + value(TouchType::NoTouch) - 1e6 * df).abs() < 1e-6);
+        // payment at the hit is earlier and worth more
+        assert!(at_hit > at_expiry && at_expiry > 0.0);
+    }
+
+    #[test]
+    fn test_distant_no_touch() {
+        // a barrier far from spot is not touched with near certainty
+        let market = market_fixture();
+        let option = FxTouchOption::new(0.5, 0.5, 1.0, TouchType::NoTouch, false);
+        let df = (-0.04_f64 * 0.5).exp();
+        assert!((f64::from(&option.npv(&market).unwrap()) - df).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_breached_touch() {
+        let market = market_fixture();
+        let option = FxTouchOption::new(1.12, 0.5, 1.0, TouchType::OneTouchAtHit, false);
+        assert_eq!(option.npv(&market).unwrap(), Number::F64(1.0));
+    }
+}
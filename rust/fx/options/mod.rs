@@ -0,0 +1,30 @@
+//! Price FX options under the Garman-Kohlhagen model.
+//!
+//! Analytic prices of [FxVanillaOption] and [FxBarrierOption] are expressed with [Number] so
+//! that Greeks are available by automatic differentiation of the [GkMarket] inputs. American
+//! exercise, which has no closed form, is priced on a trinomial spot lattice by an
+//! [FxAmericanOption]. Digital and touch payouts are valued by an [FxDigitalOption] and an
+//! [FxTouchOption]. [VannaVolga] corrects the value of any of these options for the smile
+//! of an [FxVolSurface](crate::fx::volatility::FxVolSurface). A [ScenarioGrid] revalues
+//! options over a two dimensional grid of market shocks, such as a spot and volatility risk
+//! matrix.
+//!
+//! [Number]: crate::dual::Number
+
+mod vanilla;
+pub use crate::fx::options::vanilla::{FxVanillaOption, GkMarket, OptionType};
+
+mod barrier;
+pub use crate::fx::options::barrier::{BarrierType, FxBarrierOption};
+
+mod american;
+pub use crate::fx::options::american::FxAmericanOption;
+
+mod digital;
+pub use crate::fx::options::digital::{FxDigitalOption, FxTouchOption, TouchType};
+
+mod vanna_volga;
+pub use crate::fx::options::vanna_volga::{VannaVolga, VannaVolgaValue};
+
+mod scenario;
+pub use crate::fx::options::scenario::{ScenarioGrid, ScenarioMatrix, ShockAxis};
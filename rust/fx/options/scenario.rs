@@ -0,0 +1,229 @@
+use crate::dual::Number;
+use crate::error::RateslibError;
+use crate::fx::options::GkMarket;
+use crate::parallel::par_map;
+use ndarray::Array2;
+
+/// An input of a [GkMarket] shocked along an axis of a [ScenarioGrid].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShockAxis {
+    /// The spot rate is scaled by one plus the shock.
+    Spot,
+    /// The volatility is shifted by the shock, as a decimal.
+    Volatility,
+    /// The domestic rate is shifted by the shock, as a decimal.
+    DomesticRate,
+    /// The foreign rate is shifted by the shock, as a decimal.
+    ForeignRate,
+}
+
+impl ShockAxis {
+    fn apply(&self, market: &mut GkMarket, shock: f64) {
+        match self {
+            ShockAxis::Spot => market.spot = &market.spot * (1.0 + shock),
+            ShockAxis::Volatility => market.volatility = &market.volatility + shock,
+            ShockAxis::DomesticRate => market.domestic_rate = &market.domestic_rate + shock,
+            ShockAxis::ForeignRate => market.foreign_rate = &market.foreign_rate + shock,
+        }
+    }
+}
+
+/// A two dimensional grid of shocks to a [GkMarket], such as the spot and volatility risk
+/// matrix of an options book.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScenarioGrid {
+    pub(crate) rows: (ShockAxis, Vec<f64>),
+    pub(crate) columns: (ShockAxis, Vec<f64>),
+}
+
+/// The values of a [ScenarioGrid], with a row for each shock to its first axis and a column
+/// for each shock to its second.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScenarioMatrix {
+    /// The value in the unshocked market.
+    pub base: f64,
+    /// The value in each shocked market.
+    pub npv: Array2<f64>,
+}
+
+impl ScenarioMatrix {
+    /// Return the change in value from the base in each shocked market.
+    pub fn pnl(&self) -> Array2<f64> {
+        &self.npv - self.base
+    }
+}
+
+impl ScenarioGrid {
+    /// Create a [ScenarioGrid] of the `row_shocks` to one input of the market by the
+    /// `column_shocks` to another.
+    pub fn try_new(
+        row_axis: ShockAxis,
+        row_shocks: Vec<f64>,
+        column_axis: ShockAxis,
+        column_shocks: Vec<f64>,
+    ) -> Result<Self, RateslibError> {
+        if row_axis == column_axis {
+            return Err(RateslibError::value(
+                "The axes of a `ScenarioGrid` must shock different market inputs.",
+            ));
+        }
+        if row_shocks.is_empty() || column_shocks.is_empty() {
+            return Err(RateslibError::value(
+                "The axes of a `ScenarioGrid` require at least one shock.",
+            ));
+        }
+        Ok(Self {
+            rows: (row_axis, row_shocks),
+            columns: (column_axis, column_shocks),
+        })
+    }
+
+    pub fn rows(&self) -> (ShockAxis, &[f64]) {
+        (self.rows.0, &self.rows.1)
+    }
+
+    pub fn columns(&self) -> (ShockAxis, &[f64]) {
+        (self.columns.0, &self.columns.1)
+    }
+
+    /// Return the values by a `pricer` of the `market` under each pair of shocks.
+    pub fn revalue<F>(&self, market: &GkMarket, pricer: F) -> Result<ScenarioMatrix, RateslibError>
+    where
+        F: Fn(&GkMarket) -> Result<Number, RateslibError> + Sync,
+    {
+        self.revalue_cached(market, |_| Ok(()), |_, m| pricer(m))
+    }
+
+    /// Return the values by a `pricer` of the `market` under each pair of shocks, where the
+    /// work shared by the markets of a row, such as curves built from shocked rates, is built
+    /// by `prepare` once for each row shock.
+    ///
+    /// The market is reduced to `f64` and rows are valued in parallel. The column axis must not
+    /// shock an input on which the prepared work depends.
+    pub fn revalue_cached<C, P, F>(
+        &self,
+        market: &GkMarket,
+        prepare: P,
+        pricer: F,
+    ) -> Result<ScenarioMatrix, RateslibError>
+    where
+        P: Fn(&GkMarket) -> Result<C, RateslibError> + Sync,
+        F: Fn(&C, &GkMarket) -> Result<Number, RateslibError> + Sync,
+    {
+        let market = GkMarket::new(
+            Number::F64(f64::from(&market.spot)),
+            Number::F64(f64::from(&market.domestic_rate)),
+            Number::F64(f64::from(&market.foreign_rate)),
+            Number::F64(f64::from(&market.volatility)),
+        );
+        let base = f64::from(&pricer(&prepare(&market)?, &market)?);
+        let rows: Vec<Result<Vec<f64>, RateslibError>> = par_map(&self.rows.1, |row_shock| {
+            let mut row_market = market.clone();
+            self.rows.0.apply(&mut row_market, *row_shock);
+            let cache = prepare(&row_market)?;
+            self.columns
+                .1
+                .iter()
+                .map(|shock| {
+                    let mut m = row_market.clone();
+                    self.columns.0.apply(&mut m, *shock);
+                    Ok(f64::from(&pricer(&cache, &m)?))
+                })
+                .collect()
+        })?;
+        let mut npv = Array2::zeros((self.rows.1.len(), self.columns.1.len()));
+        for (i, row) in rows.into_iter().enumerate() {
+            for (j, v) in row?.into_iter().enumerate() {
+                npv[[i, j]] = v;
+            }
+        }
+        Ok(ScenarioMatrix { base, npv })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dual::MathFuncs;
+    use crate::fx::options::vanilla::tests::market_fixture;
+    use crate::fx::options::{FxVanillaOption, OptionType};
+
+    #[test]
+    fn test_spot_vol_grid() {
+        let market = market_fixture();
+        let call = FxVanillaOption::new(1.12, 0.5, OptionType::Call);
+        let grid = ScenarioGrid::try_new(
+            ShockAxis::Spot,
+            vec![-0.02, 0.0, 0.02],
+            ShockAxis::Volatility,
+            vec![-0.01, 0.0, 0.01, 0.02],
+        )
+        .unwrap();
+        let matrix = grid.revalue(&market, |m| call.npv(m)).unwrap();
+        assert_eq!(matrix.npv.dim(), (3, 4));
+        assert_eq!(matrix.npv[[1, 1]], matrix.base);
+        assert_eq!(matrix.pnl()[[1, 1]], 0.0);
+        let mut shocked = market.clone();
+        shocked.spot = Number::F64(1.10 * 1.02);
+        shocked.volatility = Number::F64(0.11);
+        let expected = f64::from(&call.npv(&shocked).unwrap());
+        assert!((matrix.npv[[2, 2]] - expected).abs() < 1e-14);
+        // a call gains value with spot and volatility
+        let pnl = matrix.pnl();
+        assert!(pnl[[0, 1]] < 0.0 && pnl[[2, 1]] > 0.0);
+        assert!(pnl[[1, 0]] < 0.0 && pnl[[1, 3]] > pnl[[1, 2]]);
+    }
+
+    #[test]
+    fn test_grid_errors() {
+        assert!(
+            ScenarioGrid::try_new(ShockAxis::Spot, vec![0.0], ShockAxis::Spot, vec![0.0]).is_err()
+        );
+        assert!(
+            ScenarioGrid::try_new(ShockAxis::Spot, vec![], ShockAxis::Volatility, vec![0.0])
+                .is_err()
+        );
+        // a negative shocked volatility fails to price
+        let grid = ScenarioGrid::try_new(
+            ShockAxis::DomesticRate,
+            vec![0.01],
+            ShockAxis::Volatility,
+            vec![-0.2],
+        )
+        .unwrap();
+        let call = FxVanillaOption::new(1.12, 0.5, OptionType::Call);
+        assert!(grid.revalue(&market_fixture(), |m| call.npv(m)).is_err());
+    }
+
+    #[test]
+    fn test_prepared_work_is_shared_by_rows() {
+        // the discount factor of a rate shocked row is prepared once per row
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        let calls = AtomicUsize::new(0);
+        let call = FxVanillaOption::new(1.12, 0.5, OptionType::Call);
+        let grid = ScenarioGrid::try_new(
+            ShockAxis::DomesticRate,
+            vec![-0.01, 0.01],
+            ShockAxis::Volatility,
+            vec![-0.01, 0.0, 0.01],
+        )
+        .unwrap();
+        let cached = grid
+            .revalue_cached(
+                &market_fixture(),
+                |m| {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    Ok((&m.domestic_rate * -0.5).exp())
+                },
+                |df, m| Ok(call.npv(m)? / df.clone()),
+            )
+            .unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        let plain = grid.revalue(&market_fixture(), |m| call.npv(m)).unwrap();
+        let mut m = market_fixture();
+        m.domestic_rate = Number::F64(0.05);
+        let df = (0.05_f64 * -0.5).exp();
+        assert!((cached.npv[[1, 1]] * df - plain.npv[[1, 1]]).abs() < 1e-14);
+        assert!((plain.npv[[1, 1]] - f64::from(&call.npv(&m).unwrap())).abs() < 1e-14);
+    }
+}
@@ -0,0 +1,168 @@
+use crate::dual::{MathFuncs, Number};
+use crate::error::RateslibError;
+use num_traits::Pow;
+use serde::{Deserialize, Serialize};
+
+/// The right of an option to buy, or sell, the foreign currency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OptionType {
+    Call,
+    Put,
+}
+
+impl OptionType {
+    /// Return `1` for a call and `-1` for a put.
+    pub fn phi(&self) -> f64 {
+        match self {
+            OptionType::Call => 1.0,
+            OptionType::Put => -1.0,
+        }
+    }
+}
+
+/// The market inputs of the Garman-Kohlhagen model.
+///
+/// Rates are continuously compounded and, with the `volatility`, are decimals per annum.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GkMarket {
+    /// The spot rate, in domestic currency per unit of foreign currency.
+    pub spot: Number,
+    pub domestic_rate: Number,
+    pub foreign_rate: Number,
+    pub volatility: Number,
+}
+
+impl GkMarket {
+    pub fn new(
+        spot: Number,
+        domestic_rate: Number,
+        foreign_rate: Number,
+        volatility: Number,
+    ) -> Self {
+        Self {
+            spot,
+            domestic_rate,
+            foreign_rate,
+            volatility,
+        }
+    }
+
+    /// Return the forward rate at `expiry`, in years.
+    pub fn forward(&self, expiry: f64) -> Number {
+        &self.spot * ((&self.domestic_rate - &self.foreign_rate) * expiry).exp()
+    }
+
+    pub(crate) fn validate(&self, expiry: f64) -> Result<(), RateslibError> {
+        if expiry <= 0.0 || self.volatility <= 0.0 || self.spot <= 0.0 {
+            return Err(RateslibError::value(
+                "`expiry`, `volatility` and `spot` of an FX option must be positive.",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A European FX option on one unit of foreign currency, valued in domestic currency.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FxVanillaOption {
+    pub(crate) strike: f64,
+    pub(crate) expiry: f64,
+    pub(crate) option_type: OptionType,
+}
+
+impl FxVanillaOption {
+    /// Create an [FxVanillaOption] with an `expiry` in years.
+    pub fn new(strike: f64, expiry: f64, option_type: OptionType) -> Self {
+        Self {
+            strike,
+            expiry,
+            option_type,
+        }
+    }
+
+    /// Return the Garman-Kohlhagen value of the option.
+    pub fn npv(&self, market: &GkMarket) -> Result<Number, RateslibError> {
+        market.validate(self.expiry)?;
+        Ok(gk_price(
+            &market.spot,
+            self.strike,
+            self.expiry,
+            &market.domestic_rate,
+            &market.foreign_rate,
+            &market.volatility,
+            self.option_type.phi(),
+        ))
+    }
+}
+
+/// Return the Garman-Kohlhagen value of a European option with direction `phi`.
+pub(crate) fn gk_price(
+    spot: &Number,
+    strike: f64,
+    t: f64,
+    r_d: &Number,
+    r_f: &Number,
+    vol: &Number,
+    phi: f64,
+) -> Number {
+    let v = vol * t.sqrt();
+    let d1 = ((spot / strike).log() + (r_d - r_f + vol.pow(2.0) * 0.5) * t) / &v;
+    let d2 = &d1 - &v;
+    (spot * (r_f * -t).exp() * (&d1 * phi).norm_cdf()
+        - (r_d * -t).exp() * strike * (&d2 * phi).norm_cdf())
+        * phi
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::dual::{Dual, Gradient1};
+
+    pub(crate) fn market_fixture() -> GkMarket {
+        GkMarket::new(
+            Number::Dual(Dual::new(1.10, vec!["spot".to_string()])),
+            Number::F64(0.04),
+            Number::F64(0.02),
+            Number::Dual(Dual::new(0.10, vec!["vol".to_string()])),
+        )
+    }
+
+    #[test]
+    fn test_put_call_parity() {
+        let market = market_fixture();
+        let call = FxVanillaOption::new(1.12, 0.5, OptionType::Call)
+            .npv(&market)
+            .unwrap();
+        let put = FxVanillaOption::new(1.12, 0.5, OptionType::Put)
+            .npv(&market)
+            .unwrap();
+        let parity =
+            f64::from(&market.spot) * (-0.02_f64 * 0.5).exp() - 1.12 * (-0.04_f64 * 0.5).exp();
+        assert!((f64::from(&(call - put)) - parity).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_ad_delta() {
+        let market = market_fixture();
+        let call = FxVanillaOption::new(1.12, 0.5, OptionType::Call)
+            .npv(&market)
+            .unwrap();
+        let delta = match call {
+            Number::Dual(d) => d.gradient1(vec!["spot".to_string()])[0],
+            _ => panic!("expected a Dual"),
+        };
+        let v = 0.1 * 0.5_f64.sqrt();
+        let d1 = ((1.10_f64 / 1.12).ln() + (0.02 + 0.005) * 0.5) / v;
+        let expected = (-0.01_f64).exp() * d1.norm_cdf();
+        assert!((delta - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_invalid_market() {
+        let mut market = market_fixture();
+        market.volatility = Number::F64(0.0);
+        assert!(FxVanillaOption::new(1.1, 0.5, OptionType::Call)
+            .npv(&market)
+            .is_err());
+    }
+}
@@ -0,0 +1,235 @@
+use crate::dual::{MathFuncs, Number};
+use crate::error::RateslibError;
+use crate::fx::options::GkMarket;
+use crate::fx::volatility::FxVolSurface;
+use num_traits::Pow;
+use std::f64::consts::PI;
+
+/// Relative spot and absolute volatility shifts of the finite difference Greeks of an option.
+const SPOT_BUMP: f64 = 1e-4;
+const VOL_BUMP: f64 = 1e-4;
+
+/// The Vanna-Volga value of an FX option.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VannaVolgaValue {
+    /// The value of the option including the smile correction.
+    pub npv: Number,
+    /// The Garman-Kohlhagen value of the option at the at-the-money volatility.
+    pub atm_npv: Number,
+    /// The smile correction to the at-the-money value.
+    pub correction: Number,
+    /// The notional of the 25 delta put, at-the-money and 25 delta call hedges.
+    pub weights: [Number; 3],
+}
+
+/// A Vanna-Volga engine valuing FX options off the smile of an [FxVolSurface].
+///
+/// An option is valued at the at-the-money volatility and corrected by the smile cost of the
+/// portfolio of 25 delta put, at-the-money and 25 delta call vanillas with the same vega,
+/// vanna and volga. Vanilla Greeks are analytic whilst the Greeks of the option are finite
+/// differences of its pricing function, and all values are differentiable with respect to the
+/// quotes of the surface.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VannaVolga {
+    pub(crate) surface: FxVolSurface,
+}
+
+impl VannaVolga {
+    pub fn new(surface: FxVolSurface) -> Self {
+        Self { surface }
+    }
+
+    /// Return the Vanna-Volga value of an option expiring at `expiry`, in years, by its
+    /// Garman-Kohlhagen `pricer`.
+    ///
+    /// The volatility of the `market` is ignored and replaced with at-the-money volatility of
+    /// the surface.
+    pub fn npv<F>(
+        &self,
+        market: &GkMarket,
+        expiry: f64,
+        pricer: F,
+    ) -> Result<VannaVolgaValue, RateslibError>
+    where
+        F: Fn(&GkMarket) -> Result<Number, RateslibError>,
+    {
+        let smile = self.surface.smile(expiry)?;
+        let forward = market.forward(expiry);
+        let strikes = smile.pillar_strikes(&forward);
+        let vols = smile.pillar_vols();
+        let atm = vols[1].clone();
+
+        let with = |spot: &Number, vol: &Number| {
+            GkMarket::new(
+                spot.clone(),
+                market.domestic_rate.clone(),
+                market.foreign_rate.clone(),
+                vol.clone(),
+            )
+        };
+        let value = |ds: f64, dv: f64| pricer(&with(&(&market.spot * (1.0 + ds)), &(&atm + dv)));
+        with(&market.spot, &atm).validate(expiry)?;
+        let (h, ds) = (VOL_BUMP, SPOT_BUMP);
+        let atm_npv = value(0.0, 0.0)?;
+        let (up, down) = (value(0.0, h)?, value(0.0, -h)?);
+        let target = [
+            (&up - &down) / (2.0 * h),
+            (value(ds, h)? - value(ds, -h)? - value(-ds, h)? + value(-ds, -h)?)
+                / (&market.spot * (4.0 * ds * h)),
+            (up + down - &atm_npv * 2.0) / (h * h),
+        ];
+
+        let pillars: Vec<Pillar> = strikes
+            .iter()
+            .zip(vols.iter())
+            .map(|(k, v)| Pillar::new(market, k, v, &atm, expiry))
+            .collect();
+        let columns: Vec<[Number; 3]> = pillars
+            .iter()
+            .map(|p| [p.vega.clone(), p.vanna.clone(), p.volga.clone()])
+            .collect();
+        let weights = solve3(&columns, &target);
+        let mut correction = Number::F64(0.0);
+        for (w, p) in weights.iter().zip(pillars.iter()) {
+            correction = correction + w * &p.smile_cost;
+        }
+        Ok(VannaVolgaValue {
+            npv: &atm_npv + &correction,
+            atm_npv,
+            correction,
+            weights,
+        })
+    }
+}
+
+/// The smile cost and at-the-money Greeks of a pillar vanilla call.
+struct Pillar {
+    smile_cost: Number,
+    vega: Number,
+    vanna: Number,
+    volga: Number,
+}
+
+impl Pillar {
+    fn new(market: &GkMarket, strike: &Number, vol: &Number, atm: &Number, t: f64) -> Self {
+        let (s, r_d, r_f) = (&market.spot, &market.domestic_rate, &market.foreign_rate);
+        let (df_d, df_f) = ((r_d * -t).exp(), (r_f * -t).exp());
+        let d1_d2 = |vol: &Number| {
+            let v = vol * t.sqrt();
+            let d1 = ((s / strike).log() + (r_d - r_f + vol.pow(2.0) * 0.5) * t) / &v;
+            let d2 = &d1 - &v;
+            (d1, d2)
+        };
+        let call = |vol: &Number| {
+            let (d1, d2) = d1_d2(vol);
+            s * &df_f * d1.norm_cdf() - &df_d * strike * d2.norm_cdf()
+        };
+        let (d1, d2) = d1_d2(atm);
+        let pdf = ((&d1).pow(2.0) * -0.5).exp() / (2.0 * PI).sqrt();
+        let vega = s * &df_f * &pdf * t.sqrt();
+        Self {
+            smile_cost: call(vol) - call(atm),
+            vanna: -(&df_f * &pdf * &d2) / atm,
+            volga: &vega * &d1 * &d2 / atm,
+            vega,
+        }
+    }
+}
+
+/// Solve the 3x3 linear system with `columns` for the `target` by Cramer's rule.
+fn solve3(columns: &[[Number; 3]], target: &[Number; 3]) -> [Number; 3] {
+    let det = |c: [&[Number; 3]; 3]| -> Number {
+        &c[0][0] * (&c[1][1] * &c[2][2] - &c[2][1] * &c[1][2])
+            - &c[1][0] * (&c[0][1] * &c[2][2] - &c[2][1] * &c[0][2])
+            + &c[2][0] * (&c[0][1] * &c[1][2] - &c[1][1] * &c[0][2])
+    };
+    let (a, b, c) = (&columns[0], &columns[1], &columns[2]);
+    let d = det([a, b, c]);
+    [
+        det([target, b, c]) / &d,
+        det([a, target, c]) / &d,
+        det([a, b, target]) / &d,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dual::{Dual, Gradient1};
+    use crate::fx::options::{BarrierType, FxBarrierOption, FxVanillaOption, OptionType};
+    use crate::fx::volatility::surface::tests::smile_fixture;
+    use crate::fx::volatility::FxDeltaVolSmile;
+
+    fn market() -> GkMarket {
+        GkMarket::new(
+            Number::F64(1.10),
+            Number::F64(0.04),
+            Number::F64(0.02),
+            Number::F64(0.0),
+        )
+    }
+
+    fn engine(smile: FxDeltaVolSmile) -> VannaVolga {
+        VannaVolga::new(FxVolSurface::try_new(vec![smile]).unwrap())
+    }
+
+    #[test]
+    fn test_pillar_vanillas_reprice() {
+        let (market, smile) = (market(), smile_fixture(0.5));
+        let strikes = smile.pillar_strikes(&market.forward(0.5));
+        let vols = smile.pillar_vols();
+        let vv = engine(smile);
+        for (k, v) in strikes.iter().zip(vols.iter()) {
+            let option = FxVanillaOption::new(f64::from(k), 0.5, OptionType::Call);
+            let value = vv.npv(&market, 0.5, |m| option.npv(m)).unwrap();
+            let mut m = market.clone();
+            m.volatility = Number::F64(f64::from(v));
+            let expected = f64::from(&option.npv(&m).unwrap());
+            assert!((f64::from(&value.npv) - expected).abs() < 1e-7);
+        }
+    }
+
+    #[test]
+    fn test_vanilla_matches_smile() {
+        // between the wings a vanilla is close to its value at the smile volatility
+        let (market, smile) = (market(), smile_fixture(0.5));
+        let forward = market.forward(0.5);
+        let vv = engine(smile.clone());
+        for k in [1.08, 1.12, 1.15] {
+            let option = FxVanillaOption::new(k, 0.5, OptionType::Put);
+            let value = f64::from(&vv.npv(&market, 0.5, |m| option.npv(m)).unwrap().npv);
+            let mut m = market.clone();
+            m.volatility = Number::F64(f64::from(&smile.vol(&forward, &Number::F64(k))));
+            let expected = f64::from(&option.npv(&m).unwrap());
+            assert!((value - expected).abs() < 5e-5);
+        }
+    }
+
+    #[test]
+    fn test_quote_sensitivity() {
+        let market = market();
+        let option = FxBarrierOption::new(
+            1.10,
+            1.02,
+            0.5,
+            OptionType::Call,
+            BarrierType::DownOut,
+            0.0,
+            None,
+        );
+        let value = |rr: Number| {
+            let smile =
+                FxDeltaVolSmile::try_new(0.5, Number::F64(0.10), rr, Number::F64(0.004)).unwrap();
+            engine(smile).npv(&market, 0.5, |m| option.npv(m)).unwrap()
+        };
+        let ad = match value(Number::Dual(Dual::new(-0.015, vec!["rr".to_string()]))).npv {
+            Number::Dual(d) => d.gradient1(vec!["rr".to_string()])[0],
+            _ => panic!("expected a Dual"),
+        };
+        let fd = (f64::from(&value(Number::F64(-0.015 + 1e-5)).npv)
+            - f64::from(&value(Number::F64(-0.015 - 1e-5)).npv))
+            / 2e-5;
+        assert!((ad - fd).abs() < 1e-6 * fd.abs().max(1.0));
+        assert!(ad != 0.0);
+    }
+}
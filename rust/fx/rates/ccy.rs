@@ -1,10 +1,11 @@
+use crate::error::RateslibError;
 use internment::Intern;
-use pyo3::exceptions::PyValueError;
-use pyo3::{pyclass, PyErr};
+#[cfg(feature = "python")]
+use pyo3::pyclass;
 use serde::{Deserialize, Serialize};
 
 /// A currency identified by 3-ascii ISO code.
-#[pyclass(module = "rateslib.rs")]
+#[cfg_attr(feature = "python", pyclass(module = "rateslib.rs"))]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Ccy {
     pub(crate) name: Intern<String>,
@@ -17,10 +18,10 @@ impl Ccy {
     /// to lowercase to promote performant equality between "USD" and "usd".
     ///
     /// Panics if `name` is not 3 bytes in length.
-    pub fn try_new(name: &str) -> Result<Self, PyErr> {
+    pub fn try_new(name: &str) -> Result<Self, RateslibError> {
         let ccy: String = name.to_string().to_lowercase();
         if ccy.len() != 3 {
-            return Err(PyValueError::new_err(
+            return Err(RateslibError::value(
                 "`Ccy` must be 3 ascii character in length, e.g. 'usd'.",
             ));
         }
@@ -42,6 +43,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn ccy_creation_error() {
         match Ccy::try_new("FOUR") {
             Ok(_) => assert!(false),
@@ -1,6 +1,5 @@
+use crate::error::RateslibError;
 use crate::fx::rates::ccy::Ccy;
-use pyo3::exceptions::PyValueError;
-use pyo3::PyErr;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
@@ -10,11 +9,11 @@ pub struct FXPair(pub(crate) Ccy, pub(crate) Ccy);
 
 impl FXPair {
     /// Constructs a new `FXPair`, as a combination of two distinct `Ccy`s.
-    pub fn try_new(lhs: &str, rhs: &str) -> Result<Self, PyErr> {
+    pub fn try_new(lhs: &str, rhs: &str) -> Result<Self, RateslibError> {
         let lhs_ = Ccy::try_new(lhs)?;
         let rhs_ = Ccy::try_new(rhs)?;
         if lhs_ == rhs_ {
-            return Err(PyValueError::new_err(
+            return Err(RateslibError::value(
                 "`FXPair` must be created from two distinct currencies, not same.",
             ));
         }
@@ -40,6 +39,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn fxpair_creation_error() {
         match FXPair::try_new("usd", "USD") {
             Ok(_) => assert!(false),
@@ -1,11 +1,13 @@
 use crate::dual::Number;
+use crate::error::RateslibError;
 use crate::fx::rates::fxpair::FXPair;
 use chrono::NaiveDateTime;
-use pyo3::{pyclass, PyErr};
+#[cfg(feature = "python")]
+use pyo3::pyclass;
 use serde::{Deserialize, Serialize};
 
 /// An FX rate containing `FXPair`, `rate` and `settlement` info.
-#[pyclass(module = "rateslib.rs")]
+#[cfg_attr(feature = "python", pyclass(module = "rateslib.rs"))]
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct FXRate {
     pub(crate) pair: FXPair,
@@ -19,7 +21,7 @@ impl FXRate {
         rhs: &str,
         rate: Number,
         settlement: Option<NaiveDateTime>,
-    ) -> Result<Self, PyErr> {
+    ) -> Result<Self, RateslibError> {
         Ok(FXRate {
             pair: FXPair::try_new(lhs, rhs)?,
             rate,
@@ -1,18 +1,21 @@
 //! Create objects related to the management and valuation of monetary amounts in different
 //! currencies, measured at different settlement dates in time.
 
+use crate::curves::PricingCurve;
 use crate::dual::linalg::argabsmax;
-use crate::dual::{set_order_clone, ADOrder, Dual, Dual2, Number, NumberArray2};
+use crate::dual::{set_order_clone, ADOrder, Dual, Dual2, Exogenous, Number, NumberArray2};
+use crate::error::RateslibError;
 use crate::json::JSON;
 use chrono::prelude::*;
 use indexmap::set::IndexSet;
+use indexmap::IndexMap;
 use itertools::Itertools;
 use ndarray::{Array2, ArrayViewMut2, Axis};
 use num_traits::{One, Zero};
-use pyo3::exceptions::PyValueError;
-use pyo3::{pyclass, PyErr};
+#[cfg(feature = "python")]
+use pyo3::pyclass;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::ops::{Div, Mul};
 
 pub(crate) mod ccy;
@@ -24,8 +27,16 @@ pub use crate::fx::rates::fxpair::FXPair;
 pub(crate) mod fxrate;
 pub use crate::fx::rates::fxrate::FXRate;
 
+pub(crate) mod money;
+pub use crate::fx::rates::money::{Cashflow, Money};
+
+pub(crate) mod settlement;
+pub use crate::fx::rates::settlement::{
+    Decimal, RoundingMode, Settlement, SettlementRounding, SettlementRule,
+};
+
 /// A multi-currency FX market deriving all crosses from a vector of `FXRate`s.
-#[pyclass(module = "rateslib.rs")]
+#[cfg_attr(feature = "python", pyclass(module = "rateslib.rs"))]
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(from = "FXRatesDataModel")]
 pub struct FXRates {
@@ -49,7 +60,8 @@ impl std::convert::From<FXRatesDataModel> for FXRates {
 }
 
 impl FXRates {
-    pub fn try_new(fx_rates: Vec<FXRate>, base: Option<Ccy>) -> Result<Self, PyErr> {
+    #[allow(clippy::unnecessary_map_or)]
+    pub fn try_new(fx_rates: Vec<FXRate>, base: Option<Ccy>) -> Result<Self, RateslibError> {
         // Validations:
         // 1. fx_rates is non-zero length
         // 2. currencies are not under or over overspecified
@@ -58,7 +70,7 @@ impl FXRates {
 
         // 1.
         if fx_rates.is_empty() {
-            return Err(PyValueError::new_err(
+            return Err(RateslibError::value(
                 "`fx_rates` must contain at least on fx rate.",
             ));
         }
@@ -75,11 +87,11 @@ impl FXRates {
 
         // 2.
         if q > (fx_rates.len() + 1) {
-            return Err(PyValueError::new_err(
+            return Err(RateslibError::value(
                 "FX Array cannot be solved. `fx_rates` is underspecified.",
             ));
         } else if q < (fx_rates.len() + 1) {
-            return Err(PyValueError::new_err(
+            return Err(RateslibError::value(
                 "FX Array cannot be solved. `fx_rates` is overspecified.",
             ));
         }
@@ -92,7 +104,7 @@ impl FXRates {
                     .iter()
                     .all(|d| d.settlement.map_or(false, |v| v == date)))
                 {
-                    return Err(PyValueError::new_err(
+                    return Err(RateslibError::value(
                         "`fx_rates` must have consistent `settlement` dates across all rates.",
                     ));
                 }
@@ -102,7 +114,7 @@ impl FXRates {
                     .iter()
                     .all(|d| d.settlement.map_or(true, |_v| false)))
                 {
-                    return Err(PyValueError::new_err(
+                    return Err(RateslibError::value(
                         "`fx_rates` must have consistent `settlement` dates across all rates.",
                     ));
                 }
@@ -131,13 +143,13 @@ impl FXRates {
         }
     }
 
-    pub fn update(&mut self, fx_rates: Vec<FXRate>) -> Result<(), PyErr> {
+    pub fn update(&mut self, fx_rates: Vec<FXRate>) -> Result<(), RateslibError> {
         // validate that the input vector contains FX pairs that are already associated with the instance
         if !(fx_rates
             .iter()
             .all(|v| self.fx_rates.iter().any(|x| x.pair == v.pair)))
         {
-            return Err(PyValueError::new_err(
+            return Err(RateslibError::value(
                 "The given `fx_rates` pairs are not contained in the `FXRates` object.",
             ));
         }
@@ -159,7 +171,7 @@ impl FXRates {
         Ok(())
     }
 
-    pub fn set_ad_order(&mut self, ad: ADOrder) -> Result<(), PyErr> {
+    pub fn set_ad_order(&mut self, ad: ADOrder) -> Result<(), RateslibError> {
         match (ad, &self.fx_array) {
             (ADOrder::Zero, NumberArray2::F64(_))
             | (ADOrder::One, NumberArray2::Dual(_))
@@ -225,6 +237,111 @@ impl FXRates {
             }
         }
     }
+
+    /// Return each of the `cashflows`, an amount in a currency paid at an optional settlement
+    /// date, converted into the `target` currency.
+    ///
+    /// The rate of each currency is looked up once for the whole list. A cashflow settling on
+    /// a date other than the settlement of the FX rates is converted at the forward FX rate
+    /// implied by the discount factors of the `curves` of its currency and of the `target`,
+    /// which are also each evaluated once per date.
+    pub fn convert_many(
+        &self,
+        cashflows: &[(Number, Ccy, Option<NaiveDateTime>)],
+        target: &Ccy,
+        curves: Option<&IndexMap<Ccy, &dyn PricingCurve>>,
+    ) -> Result<Vec<Number>, RateslibError> {
+        let spot_settlement = self.fx_rates[0].settlement;
+        let mut rates: HashMap<Ccy, Number> = HashMap::new();
+        let mut dfs: HashMap<(Ccy, NaiveDateTime), Number> = HashMap::new();
+        let mut df = |ccy: &Ccy, date: &NaiveDateTime| -> Result<Number, RateslibError> {
+            if let Some(v) = dfs.get(&(*ccy, *date)) {
+                return Ok(v.clone());
+            }
+            let curve = curves.and_then(|c| c.get(ccy)).ok_or_else(|| {
+                RateslibError::value(format!(
+                    "A discount curve for '{}' is required to convert a forward cashflow.",
+                    ccy.name
+                ))
+            })?;
+            let v = curve.df(date);
+            dfs.insert((*ccy, *date), v.clone());
+            Ok(v)
+        };
+
+        let mut converted = Vec::with_capacity(cashflows.len());
+        for (amount, ccy, settlement) in cashflows.iter() {
+            if ccy == target {
+                converted.push(amount.clone());
+                continue;
+            }
+            let rate = match rates.get(ccy) {
+                Some(r) => r.clone(),
+                None => {
+                    let r = self.rate(ccy, target).ok_or_else(|| {
+                        RateslibError::value(format!(
+                            "`FXRates` cannot convert between '{}' and '{}'.",
+                            ccy.name, target.name
+                        ))
+                    })?;
+                    rates.insert(*ccy, r.clone());
+                    r
+                }
+            };
+            let rate = match (settlement, spot_settlement) {
+                (Some(date), Some(spot)) if *date != spot => {
+                    let ccy_ratio = df(ccy, date)?.try_div(&df(ccy, &spot)?)?;
+                    let target_ratio = df(target, date)?.try_div(&df(target, &spot)?)?;
+                    rate.try_mul(&ccy_ratio)?.try_div(&target_ratio)?
+                }
+                _ => rate,
+            };
+            converted.push(amount.try_mul(&rate)?);
+        }
+        Ok(converted)
+    }
+
+    /// Return the FX rates settling at a later `settlement` date, at the forward FX rates
+    /// implied by the discount factors of the `curves` of each currency.
+    pub fn rebase(
+        &self,
+        settlement: &NaiveDateTime,
+        curves: &IndexMap<Ccy, &dyn PricingCurve>,
+    ) -> Result<FXRates, RateslibError> {
+        let spot = self.fx_rates[0].settlement.ok_or_else(|| {
+            RateslibError::value("`FXRates` must have a `settlement` date to be rebased.")
+        })?;
+        if *settlement < spot {
+            return Err(RateslibError::value(
+                "`settlement` of rebased `FXRates` cannot be before their current settlement.",
+            ));
+        }
+        let ratio = |ccy: &Ccy| -> Result<Number, RateslibError> {
+            let curve = curves.get(ccy).ok_or_else(|| {
+                RateslibError::value(format!(
+                    "A discount curve for '{}' is required to rebase `FXRates`.",
+                    ccy.name
+                ))
+            })?;
+            curve.df(settlement).try_div(&curve.df(&spot))
+        };
+        let fx_rates = self
+            .fx_rates
+            .iter()
+            .map(|fxr| {
+                let rate = fxr
+                    .rate
+                    .try_mul(&ratio(&fxr.pair.0)?)?
+                    .try_div(&ratio(&fxr.pair.1)?)?;
+                Ok(FXRate {
+                    pair: fxr.pair,
+                    rate,
+                    settlement: Some(*settlement),
+                })
+            })
+            .collect::<Result<Vec<FXRate>, RateslibError>>()?;
+        FXRates::try_new(fx_rates, Some(self.currencies[0]))
+    }
 }
 
 /// Return a one-hot mapping, in 2-d array form of the initial connections between currencies,
@@ -264,17 +381,18 @@ where
     fx_array
 }
 
+#[allow(clippy::needless_return)]
 fn mut_arrays_remaining_elements<T>(
     mut fx_array: ArrayViewMut2<T>,
     mut edges: ArrayViewMut2<i16>,
     mut prev_value: HashSet<usize>,
-) -> Result<bool, PyErr>
+) -> Result<bool, RateslibError>
 where
     for<'a> &'a T: Mul<&'a T, Output = T>,
     for<'a> f64: Div<&'a T, Output = T>,
 {
     if prev_value.len() == edges.len_of(Axis(0)) {
-        return Err(PyValueError::new_err(
+        return Err(RateslibError::value(
             "FX Array cannot be solved. There are degenerate FX rate pairs.\n\
                 For example ('eurusd' + 'usdeur') or ('usdeur', 'eurjpy', 'usdjpy').",
         ));
@@ -333,9 +451,12 @@ fn create_fx_array(
     currencies: &IndexSet<Ccy>,
     fx_rates: &[FXRate],
     ad: ADOrder,
-) -> Result<NumberArray2, PyErr> {
+) -> Result<NumberArray2, RateslibError> {
     let fx_pairs: Vec<FXPair> = fx_rates.iter().map(|x| x.pair).collect();
-    let vars: Vec<String> = fx_pairs.iter().map(|x| format!("fx_{}", x)).collect();
+    let vars: Vec<String> = fx_pairs
+        .iter()
+        .map(|x| Exogenous::fx(&x.to_string()).tag())
+        .collect();
     let mut edges = create_initial_edges(currencies, &fx_pairs);
     let fx_rates_: Vec<Number> = fx_rates
         .iter()
@@ -415,6 +536,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn fxrates_creation_error() {
         let fxr = FXRates::try_new(
             vec![
@@ -506,4 +628,103 @@ mod tests {
             .into();
         assert_eq!(d3, rate)
     }
+
+    #[test]
+    fn test_convert_many() {
+        let fxr = FXRates::try_new(
+            vec![
+                FXRate::try_new("eur", "usd", Number::F64(1.08), Some(ndt(2024, 1, 1))).unwrap(),
+                FXRate::try_new("usd", "jpy", Number::F64(110.0), Some(ndt(2024, 1, 1))).unwrap(),
+            ],
+            None,
+        )
+        .unwrap();
+        let (eur, usd, jpy) = (
+            Ccy::try_new("eur").unwrap(),
+            Ccy::try_new("usd").unwrap(),
+            Ccy::try_new("jpy").unwrap(),
+        );
+        let cashflows = vec![
+            (Number::F64(100.0), eur, None),
+            (Number::F64(1100.0), jpy, Some(ndt(2024, 1, 1))),
+            (Number::F64(5.0), usd, None),
+        ];
+        let result: Vec<f64> = fxr
+            .convert_many(&cashflows, &usd, None)
+            .unwrap()
+            .iter()
+            .map(f64::from)
+            .collect();
+        assert!((result[0] - 108.0).abs() < 1e-9);
+        assert!((result[1] - 10.0).abs() < 1e-9);
+        assert_eq!(result[2], 5.0);
+        // the conversion of a single cashflow matches the batch
+        assert_eq!(
+            fxr.convert_many(&cashflows[1..2], &usd, None).unwrap()[0],
+            fxr.convert_many(&cashflows, &usd, None).unwrap()[1]
+        );
+    }
+
+    #[test]
+    fn test_convert_many_forward() {
+        use crate::periods::period::tests::curve_fixture;
+        let fxr = FXRates::try_new(
+            vec![FXRate::try_new("eur", "usd", Number::F64(1.08), Some(ndt(2024, 1, 1))).unwrap()],
+            None,
+        )
+        .unwrap();
+        let (eur, usd) = (Ccy::try_new("eur").unwrap(), Ccy::try_new("usd").unwrap());
+        let (eur_curve, usd_curve) = (curve_fixture("eur"), curve_fixture("usd"));
+        let cashflows = vec![(Number::F64(100.0), eur, Some(ndt(2025, 1, 1)))];
+        assert!(fxr.convert_many(&cashflows, &usd, None).is_err());
+        let curves: IndexMap<Ccy, &dyn PricingCurve> = IndexMap::from_iter([
+            (eur, &eur_curve as &dyn PricingCurve),
+            (usd, &usd_curve as &dyn PricingCurve),
+        ]);
+        // equal interest rates imply an unchanged forward FX rate
+        let result = fxr.convert_many(&cashflows, &usd, Some(&curves)).unwrap();
+        assert!((f64::from(&result[0]) - 108.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_rebase() {
+        use crate::calendars::{Convention, Modifier, NamedCal};
+        use crate::curves::{CurveDF, LogLinearInterpolator, Nodes};
+        use crate::periods::period::tests::curve_fixture;
+        let fxr = FXRates::try_new(
+            vec![FXRate::try_new("eur", "usd", Number::F64(1.08), Some(ndt(2024, 1, 1))).unwrap()],
+            None,
+        )
+        .unwrap();
+        let (eur, usd) = (Ccy::try_new("eur").unwrap(), Ccy::try_new("usd").unwrap());
+        let eur_curve = CurveDF::try_new(
+            Nodes::F64(IndexMap::from_iter([
+                (ndt(2024, 1, 1), 1.0),
+                (ndt(2034, 1, 1), 0.7),
+            ])),
+            LogLinearInterpolator::new(),
+            "eur",
+            Convention::Act365F,
+            Modifier::ModF,
+            None,
+            NamedCal::try_new("all").unwrap(),
+        )
+        .unwrap();
+        let usd_curve = curve_fixture("usd");
+        let curves: IndexMap<Ccy, &dyn PricingCurve> = IndexMap::from_iter([
+            (eur, &eur_curve as &dyn PricingCurve),
+            (usd, &usd_curve as &dyn PricingCurve),
+        ]);
+        let date = ndt(2024, 7, 1);
+        let rebased = fxr.rebase(&date, &curves).unwrap();
+        assert_eq!(rebased.fx_rates[0].settlement, Some(date));
+        // the rebased spot rate is the forward rate of the settlement date
+        let forward = fxr
+            .convert_many(&[(Number::F64(1.0), eur, Some(date))], &usd, Some(&curves))
+            .unwrap();
+        let rate = f64::from(&rebased.rate(&eur, &usd).unwrap());
+        assert!((rate - f64::from(&forward[0])).abs() < 1e-12);
+        assert!(rate < 1.08);
+        assert!(fxr.rebase(&ndt(2023, 1, 1), &curves).is_err());
+    }
 }
@@ -0,0 +1,225 @@
+use crate::dual::Number;
+use crate::error::RateslibError;
+use crate::fx::rates::{Ccy, FXRates};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use std::ops::Neg;
+
+/// An amount denominated in a currency.
+///
+/// Amounts are only combined with amounts of the same currency, so that the checked arithmetic
+/// raises instead of silently adding, say, *"usd"* to *"eur"*. Use [Money::convert] to express
+/// an amount in another currency first.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Money {
+    pub amount: Number,
+    pub ccy: Ccy,
+}
+
+impl Money {
+    pub fn new(amount: Number, ccy: Ccy) -> Self {
+        Self { amount, ccy }
+    }
+
+    /// Return a zero amount in the currency `ccy`.
+    pub fn zero(ccy: Ccy) -> Self {
+        Self::new(Number::F64(0.0), ccy)
+    }
+
+    fn check_ccy(&self, other: &Money, operator: &str) -> Result<(), RateslibError> {
+        match self.ccy == other.ccy {
+            true => Ok(()),
+            false => Err(RateslibError::CurrencyMismatch {
+                operation: format!("{} {} {}", self.ccy.name, operator, other.ccy.name),
+            }),
+        }
+    }
+
+    /// Return the sum, or raise if the currencies differ.
+    pub fn try_add(&self, other: &Money) -> Result<Money, RateslibError> {
+        self.check_ccy(other, "+")?;
+        Ok(Money::new(self.amount.try_add(&other.amount)?, self.ccy))
+    }
+
+    /// Return the difference, or raise if the currencies differ.
+    pub fn try_sub(&self, other: &Money) -> Result<Money, RateslibError> {
+        self.check_ccy(other, "-")?;
+        Ok(Money::new(self.amount.try_sub(&other.amount)?, self.ccy))
+    }
+
+    /// Return the amount scaled by a dimensionless `factor`.
+    pub fn try_scale(&self, factor: &Number) -> Result<Money, RateslibError> {
+        Ok(Money::new(self.amount.try_mul(factor)?, self.ccy))
+    }
+
+    /// Return the total of `amounts` in the currency `ccy`, or raise if any amount is
+    /// denominated in a different currency.
+    pub fn try_sum<'a>(
+        ccy: Ccy,
+        amounts: impl IntoIterator<Item = &'a Money>,
+    ) -> Result<Money, RateslibError> {
+        amounts
+            .into_iter()
+            .try_fold(Money::zero(ccy), |acc, m| acc.try_add(m))
+    }
+
+    /// Return the amount expressed in the currency `target` at the spot rate of `fx`.
+    pub fn convert(&self, target: &Ccy, fx: &FXRates) -> Result<Money, RateslibError> {
+        if self.ccy == *target {
+            return Ok(self.clone());
+        }
+        let rate = fx.rate(&self.ccy, target).ok_or_else(|| {
+            RateslibError::value(format!(
+                "`FXRates` cannot convert between '{}' and '{}'.",
+                self.ccy.name, target.name
+            ))
+        })?;
+        Ok(Money::new(self.amount.try_mul(&rate)?, *target))
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+    fn neg(self) -> Money {
+        Money::new(-self.amount, self.ccy)
+    }
+}
+
+/// An amount denominated in a currency and paid on a date.
+///
+/// Cashflows are only combined with cashflows of the same currency and payment date.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cashflow {
+    pub money: Money,
+    pub payment: NaiveDateTime,
+}
+
+impl Cashflow {
+    pub fn new(amount: Number, ccy: Ccy, payment: NaiveDateTime) -> Self {
+        Self {
+            money: Money::new(amount, ccy),
+            payment,
+        }
+    }
+
+    pub fn amount(&self) -> &Number {
+        &self.money.amount
+    }
+
+    pub fn ccy(&self) -> Ccy {
+        self.money.ccy
+    }
+
+    fn check_payment(&self, other: &Cashflow, operator: &str) -> Result<(), RateslibError> {
+        match self.payment == other.payment {
+            true => Ok(()),
+            false => Err(RateslibError::Unsupported(format!(
+                "Cannot combine cashflows paid on different dates: {} {} {}",
+                self.payment, operator, other.payment
+            ))),
+        }
+    }
+
+    /// Return the sum, or raise if the currencies or payment dates differ.
+    pub fn try_add(&self, other: &Cashflow) -> Result<Cashflow, RateslibError> {
+        self.check_payment(other, "+")?;
+        Ok(Cashflow {
+            money: self.money.try_add(&other.money)?,
+            payment: self.payment,
+        })
+    }
+
+    /// Return the difference, or raise if the currencies or payment dates differ.
+    pub fn try_sub(&self, other: &Cashflow) -> Result<Cashflow, RateslibError> {
+        self.check_payment(other, "-")?;
+        Ok(Cashflow {
+            money: self.money.try_sub(&other.money)?,
+            payment: self.payment,
+        })
+    }
+}
+
+impl Neg for Cashflow {
+    type Output = Cashflow;
+    fn neg(self) -> Cashflow {
+        Cashflow {
+            money: -self.money,
+            payment: self.payment,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::ndt;
+    use crate::dual::{Dual, Dual2};
+    use crate::fx::rates::FXRate;
+
+    fn usd() -> Ccy {
+        Ccy::try_new("usd").unwrap()
+    }
+
+    fn eur() -> Ccy {
+        Ccy::try_new("eur").unwrap()
+    }
+
+    #[test]
+    fn test_money_arithmetic() {
+        let a = Money::new(Number::F64(10.0), usd());
+        let b = Money::new(Number::F64(4.0), usd());
+        assert_eq!(a.try_add(&b).unwrap(), Money::new(Number::F64(14.0), usd()));
+        assert_eq!(a.try_sub(&b).unwrap(), Money::new(Number::F64(6.0), usd()));
+        assert_eq!(-b.clone(), Money::new(Number::F64(-4.0), usd()));
+        assert_eq!(
+            Money::try_sum(usd(), [&a, &b, &b]).unwrap(),
+            Money::new(Number::F64(18.0), usd())
+        );
+    }
+
+    #[test]
+    fn test_money_currency_mismatch() {
+        let a = Money::new(Number::F64(10.0), usd());
+        let b = Money::new(Number::F64(4.0), eur());
+        assert_eq!(
+            a.try_add(&b).unwrap_err(),
+            RateslibError::CurrencyMismatch {
+                operation: "usd + eur".to_string()
+            }
+        );
+        assert!(Money::try_sum(usd(), [&a, &b]).is_err());
+        // mixed dual types also raise rather than panic
+        let d = Money::new(Number::Dual(Dual::new(1.0, vec!["x".to_string()])), usd());
+        let d2 = Money::new(Number::Dual2(Dual2::new(1.0, vec!["x".to_string()])), usd());
+        assert!(d.try_add(&d2).is_err());
+    }
+
+    #[test]
+    fn test_money_convert() {
+        let fxr = FXRates::try_new(
+            vec![FXRate::try_new("eur", "usd", Number::F64(1.08), None).unwrap()],
+            None,
+        )
+        .unwrap();
+        let a = Money::new(Number::F64(100.0), eur());
+        let converted = a.convert(&usd(), &fxr).unwrap();
+        assert_eq!(converted.ccy, usd());
+        assert!((f64::from(&converted.amount) - 108.0).abs() < 1e-9);
+        assert!(a.convert(&Ccy::try_new("jpy").unwrap(), &fxr).is_err());
+    }
+
+    #[test]
+    fn test_cashflow_arithmetic() {
+        let a = Cashflow::new(Number::F64(10.0), usd(), ndt(2025, 1, 1));
+        let b = Cashflow::new(Number::F64(4.0), usd(), ndt(2025, 1, 1));
+        assert_eq!(a.try_add(&b).unwrap().amount(), &Number::F64(14.0));
+        assert_eq!(
+            (-a.clone()).try_sub(&b).unwrap().amount(),
+            &Number::F64(-14.0)
+        );
+        let c = Cashflow::new(Number::F64(4.0), usd(), ndt(2025, 1, 2));
+        assert!(a.try_add(&c).is_err());
+        let d = Cashflow::new(Number::F64(4.0), eur(), ndt(2025, 1, 1));
+        assert!(a.try_add(&d).is_err());
+    }
+}
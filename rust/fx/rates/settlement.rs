@@ -0,0 +1,298 @@
+use crate::error::RateslibError;
+use crate::fx::rates::{Cashflow, Ccy, Money};
+use chrono::NaiveDateTime;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// The number of decimal places retained when an amount is converted to a [Decimal], beyond
+/// which any remaining digits are kept only as a sticky digit so that rounding is unaffected.
+const MAX_SCALE: u32 = 30;
+
+/// An exact decimal number of `mantissa` units of `10^-scale`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Decimal {
+    pub(crate) mantissa: i128,
+    pub(crate) scale: u32,
+}
+
+impl Decimal {
+    pub fn new(mantissa: i128, scale: u32) -> Self {
+        Self { mantissa, scale }
+    }
+
+    /// Convert an `f64` by its shortest decimal representation, so that amounts such as
+    /// `2.675` are not distorted by their binary approximation.
+    pub fn try_from_f64(value: f64) -> Result<Self, RateslibError> {
+        let unsupported =
+            || RateslibError::Unsupported(format!("Cannot convert {} to a decimal amount.", value));
+        if !value.is_finite() {
+            return Err(unsupported());
+        }
+        let repr = format!("{}", value.abs());
+        let (int, frac) = repr.split_once('.').unwrap_or((&repr, ""));
+        let (kept, rest) = frac.split_at(frac.len().min(MAX_SCALE as usize));
+        let sticky = rest.bytes().any(|b| b != b'0');
+        let mut mantissa: i128 = 0;
+        for b in int
+            .bytes()
+            .chain(kept.bytes())
+            .chain(sticky.then_some(b'1'))
+        {
+            mantissa = mantissa
+                .checked_mul(10)
+                .and_then(|m| m.checked_add((b - b'0') as i128))
+                .ok_or_else(unsupported)?;
+        }
+        let scale = kept.len() as u32 + sticky as u32;
+        Ok(Self::new(value.signum() as i128 * mantissa, scale))
+    }
+
+    pub fn mantissa(&self) -> i128 {
+        self.mantissa
+    }
+
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    /// Return the number rounded by the `mode` to a multiple of `increment` units of the
+    /// `places`th decimal place, such as 5 units of the second place for 0.05.
+    pub fn round(&self, places: u32, increment: u32, mode: RoundingMode) -> Self {
+        let increment = increment.max(1) as i128;
+        let (mantissa, divisor) = match self.scale >= places {
+            true => (self.mantissa, increment * 10_i128.pow(self.scale - places)),
+            false => (self.mantissa * 10_i128.pow(places - self.scale), increment),
+        };
+        let (q, r) = (mantissa / divisor, mantissa % divisor);
+        let away = match mode {
+            RoundingMode::Down => false,
+            RoundingMode::HalfUp => 2 * r.abs() >= divisor,
+            RoundingMode::HalfEven => {
+                2 * r.abs() > divisor || (2 * r.abs() == divisor && q % 2 != 0)
+            }
+        };
+        let q = match away && r != 0 {
+            true => q + r.signum(),
+            false => q,
+        };
+        Self::new(q * increment, places)
+    }
+
+    /// Return the sum, or `None` on overflow.
+    pub fn checked_add(&self, other: &Decimal) -> Option<Decimal> {
+        let scale = self.scale.max(other.scale);
+        let align = |d: &Decimal| {
+            d.mantissa
+                .checked_mul(10_i128.checked_pow(scale - d.scale)?)
+        };
+        Some(Self::new(align(self)?.checked_add(align(other)?)?, scale))
+    }
+}
+
+impl From<Decimal> for f64 {
+    fn from(value: Decimal) -> f64 {
+        value.mantissa as f64 / 10_f64.powi(value.scale as i32)
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = self.mantissa.unsigned_abs().to_string();
+        let digits = format!("{:0>width$}", digits, width = self.scale as usize + 1);
+        let (int, frac) = digits.split_at(digits.len() - self.scale as usize);
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+        match frac.is_empty() {
+            true => write!(f, "{}{}", sign, int),
+            false => write!(f, "{}{}.{}", sign, int, frac),
+        }
+    }
+}
+
+/// The direction in which an amount between two settlement units is rounded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum RoundingMode {
+    /// To the nearest unit, with ties away from zero.
+    #[default]
+    HalfUp,
+    /// To the nearest unit, with ties to an even unit.
+    HalfEven,
+    /// Towards zero.
+    Down,
+}
+
+/// The rounding of settlement amounts in a currency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SettlementRule {
+    /// The number of decimal places of the minor unit.
+    pub decimals: u32,
+    /// The minimum denomination in minor units, such as 5 for a currency settled in 0.05.
+    pub increment: u32,
+    pub mode: RoundingMode,
+}
+
+impl SettlementRule {
+    pub fn new(decimals: u32, increment: u32, mode: RoundingMode) -> Self {
+        Self {
+            decimals,
+            increment,
+            mode,
+        }
+    }
+
+    /// Return the rule of the ISO 4217 minor unit of `ccy`, rounding half up.
+    pub fn for_ccy(ccy: &Ccy) -> Self {
+        let decimals = match ccy.name.as_str() {
+            "bif" | "clp" | "djf" | "gnf" | "isk" | "jpy" | "kmf" | "krw" | "pyg" | "rwf"
+            | "ugx" | "vnd" | "vuv" | "xaf" | "xof" | "xpf" => 0,
+            "bhd" | "iqd" | "jod" | "kwd" | "lyd" | "omr" | "tnd" => 3,
+            _ => 2,
+        };
+        Self::new(decimals, 1, RoundingMode::HalfUp)
+    }
+}
+
+/// An amount to be settled in a currency on a payment date, in exact decimal units.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Settlement {
+    pub payment: NaiveDateTime,
+    pub ccy: Ccy,
+    pub amount: Decimal,
+}
+
+/// The rounding of amounts to the units in which they are settled, by currency.
+///
+/// Each cashflow is rounded from its shortest decimal representation by the [SettlementRule]
+/// of its currency, by default its ISO 4217 minor unit, so that settlement amounts match those
+/// of back-office systems to the unit.
+///
+/// # Examples
+///
+/// ```rust
+/// # use rateslib::fx::rates::{Ccy, Money, RoundingMode, SettlementRounding, SettlementRule};
+/// # use rateslib::dual::Number;
+/// let chf = Ccy::try_new("chf").unwrap();
+/// let rounding = SettlementRounding::default()
+///     .with_rule(chf, SettlementRule::new(2, 5, RoundingMode::HalfUp));
+/// let amount = rounding.round(&Money::new(Number::F64(1234.5749), chf)).unwrap();
+/// assert_eq!(amount.to_string(), "1234.55");
+/// let usd = Money::new(Number::F64(2.675), Ccy::try_new("usd").unwrap());
+/// assert_eq!(rounding.round(&usd).unwrap().to_string(), "2.68");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SettlementRounding {
+    pub(crate) rules: HashMap<Ccy, SettlementRule>,
+}
+
+impl SettlementRounding {
+    /// Return the rounding with the `rule` for `ccy`, replacing its default.
+    pub fn with_rule(mut self, ccy: Ccy, rule: SettlementRule) -> Self {
+        self.rules.insert(ccy, rule);
+        self
+    }
+
+    /// Return the rule of `ccy`.
+    pub fn rule(&self, ccy: &Ccy) -> SettlementRule {
+        self.rules
+            .get(ccy)
+            .copied()
+            .unwrap_or_else(|| SettlementRule::for_ccy(ccy))
+    }
+
+    /// Return the amount of `money` rounded by the rule of its currency.
+    pub fn round(&self, money: &Money) -> Result<Decimal, RateslibError> {
+        let rule = self.rule(&money.ccy);
+        Ok(Decimal::try_from_f64(f64::from(&money.amount))?.round(
+            rule.decimals,
+            rule.increment,
+            rule.mode,
+        ))
+    }
+
+    /// Return the rounded `cashflows` netted by payment date and currency, in order of payment.
+    ///
+    /// Each cashflow is rounded before netting, as each is confirmed separately.
+    pub fn settle(&self, cashflows: &[Cashflow]) -> Result<Vec<Settlement>, RateslibError> {
+        let mut netted: IndexMap<(NaiveDateTime, Ccy), Decimal> = IndexMap::new();
+        for c in cashflows.iter() {
+            let amount = self.round(&c.money)?;
+            let entry = netted
+                .entry((c.payment, c.ccy()))
+                .or_insert(Decimal::new(0, amount.scale));
+            *entry = entry.checked_add(&amount).ok_or_else(|| {
+                RateslibError::Unsupported("Netted settlement amount overflows.".to_string())
+            })?;
+        }
+        let mut settlements: Vec<Settlement> = netted
+            .into_iter()
+            .map(|((payment, ccy), amount)| Settlement {
+                payment,
+                ccy,
+                amount,
+            })
+            .collect();
+        settlements.sort_by_key(|s| s.payment);
+        Ok(settlements)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::ndt;
+    use crate::dual::Number;
+
+    #[test]
+    fn test_decimal_rounding() {
+        let d = |x: f64| Decimal::try_from_f64(x).unwrap();
+        assert_eq!(d(2.675), Decimal::new(2675, 3));
+        assert_eq!(
+            d(-0.125).round(2, 1, RoundingMode::HalfUp).to_string(),
+            "-0.13"
+        );
+        assert_eq!(
+            d(-0.125).round(2, 1, RoundingMode::HalfEven).to_string(),
+            "-0.12"
+        );
+        assert_eq!(
+            d(0.135).round(2, 1, RoundingMode::HalfEven).to_string(),
+            "0.14"
+        );
+        assert_eq!(d(1.999).round(2, 1, RoundingMode::Down).to_string(), "1.99");
+        assert_eq!(
+            d(1234.5).round(0, 1, RoundingMode::HalfEven).to_string(),
+            "1234"
+        );
+        assert_eq!(
+            d(1.025).round(2, 5, RoundingMode::HalfUp).to_string(),
+            "1.05"
+        );
+        assert_eq!(d(3.0).round(2, 1, RoundingMode::HalfUp).to_string(), "3.00");
+        assert_eq!(
+            d(0.004).round(2, 1, RoundingMode::HalfUp).to_string(),
+            "0.00"
+        );
+        assert!(Decimal::try_from_f64(f64::NAN).is_err());
+        assert!(Decimal::try_from_f64(1e300).is_err());
+        // digits beyond the retained scale are kept as a sticky digit
+        assert_eq!(d(-1e-35), Decimal::new(-1, MAX_SCALE + 1));
+    }
+
+    #[test]
+    fn test_settle_cashflows() {
+        let (usd, jpy) = (Ccy::try_new("usd").unwrap(), Ccy::try_new("jpy").unwrap());
+        let cashflows = vec![
+            Cashflow::new(Number::F64(100.005), usd, ndt(2025, 6, 1)),
+            Cashflow::new(Number::F64(-1_000_000.4), jpy, ndt(2025, 3, 1)),
+            Cashflow::new(Number::F64(0.005), usd, ndt(2025, 6, 1)),
+        ];
+        let settlements = SettlementRounding::default().settle(&cashflows).unwrap();
+        assert_eq!(settlements.len(), 2);
+        assert_eq!(settlements[0].ccy, jpy);
+        assert_eq!(settlements[0].amount.to_string(), "-1000000");
+        // each cashflow is rounded before netting
+        assert_eq!(settlements[1].amount.to_string(), "100.02");
+        assert_eq!(f64::from(settlements[1].amount), 100.02);
+    }
+}
@@ -1,9 +1,12 @@
 //! Wrapper module to export Rust FX rate data types to Python using pyo3 bindings.
 
+use crate::curves::curve_py::Curve;
+use crate::curves::PricingCurve;
 use crate::dual::{ADOrder, Number, NumberArray2};
 use crate::fx::rates::{Ccy, FXRate, FXRates};
 use bincode::{deserialize, serialize};
 use chrono::prelude::*;
+use indexmap::IndexMap;
 use ndarray::Axis;
 use pyo3::prelude::*;
 // use std::collections::HashMap;
@@ -17,7 +20,7 @@ use pyo3::types::PyBytes;
 impl Ccy {
     #[new]
     fn new_py(name: &str) -> PyResult<Self> {
-        Ccy::try_new(name)
+        Ok(Ccy::try_new(name)?)
     }
 
     #[getter]
@@ -42,7 +45,7 @@ impl Ccy {
     pub fn __getstate__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
         Ok(PyBytes::new_bound(py, &serialize(&self).unwrap()))
     }
-    pub fn __getnewargs__<'py>(&self) -> PyResult<(String,)> {
+    pub fn __getnewargs__(&self) -> PyResult<(String,)> {
         Ok(((*(self.name)).clone(),))
     }
 }
@@ -56,7 +59,7 @@ impl FXRate {
         rate: Number,
         settlement: Option<NaiveDateTime>,
     ) -> PyResult<Self> {
-        FXRate::try_new(lhs, rhs, rate, settlement)
+        Ok(FXRate::try_new(lhs, rhs, rate, settlement)?)
     }
 
     #[getter]
@@ -115,7 +118,7 @@ impl FXRate {
     pub fn __getstate__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
         Ok(PyBytes::new_bound(py, &serialize(&self).unwrap()))
     }
-    pub fn __getnewargs__<'py>(&self) -> PyResult<(String, String, Number, Option<NaiveDateTime>)> {
+    pub fn __getnewargs__(&self) -> PyResult<(String, String, Number, Option<NaiveDateTime>)> {
         Ok((
             (*(self.pair.0.name)).clone(),
             (*(self.pair.1.name)).clone(),
@@ -145,7 +148,7 @@ impl FXRates {
     // }
     #[new]
     fn new_py(fx_rates: Vec<FXRate>, base: Option<Ccy>) -> PyResult<Self> {
-        FXRates::try_new(fx_rates, base)
+        Ok(FXRates::try_new(fx_rates, base)?)
     }
 
     #[getter]
@@ -224,9 +227,39 @@ impl FXRates {
         Ok(self.rate(lhs, rhs))
     }
 
+    /// Convert a list of cashflows into a single currency.
+    ///
+    /// Parameters
+    /// ----------
+    /// cashflows: list[tuple[float, Ccy, datetime or None]]
+    ///     The amount, currency and settlement date of each cashflow.
+    /// target: Ccy
+    ///     The currency into which each cashflow is converted.
+    /// curves: dict[Ccy, Curve], optional
+    ///     The discount curves of each currency, required to convert any cashflow settling on a
+    ///     date other than the settlement of the FX rates at a forward FX rate.
+    ///
+    /// Returns
+    /// -------
+    /// list[float, Dual or Dual2]
+    #[pyo3(name = "convert_many", signature = (cashflows, target, curves = None))]
+    fn convert_many_py(
+        &self,
+        cashflows: Vec<(Number, Ccy, Option<NaiveDateTime>)>,
+        target: Ccy,
+        curves: Option<IndexMap<Ccy, Curve>>,
+    ) -> PyResult<Vec<Number>> {
+        let curves: Option<IndexMap<Ccy, &dyn PricingCurve>> = curves.as_ref().map(|c| {
+            c.iter()
+                .map(|(k, v)| (*k, v as &dyn PricingCurve))
+                .collect()
+        });
+        Ok(self.convert_many(&cashflows, &target, curves.as_ref())?)
+    }
+
     #[pyo3(name = "update")]
     fn update_py(&mut self, fx_rates: Vec<FXRate>) -> PyResult<()> {
-        self.update(fx_rates)
+        Ok(self.update(fx_rates)?)
     }
 
     #[pyo3(name = "set_ad_order")]
@@ -254,7 +287,7 @@ impl FXRates {
     pub fn __getstate__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
         Ok(PyBytes::new_bound(py, &serialize(&self).unwrap()))
     }
-    pub fn __getnewargs__<'py>(&self) -> PyResult<(Vec<FXRate>, Option<Ccy>)> {
+    pub fn __getnewargs__(&self) -> PyResult<(Vec<FXRate>, Option<Ccy>)> {
         Ok((self.fx_rates.clone(), Some(self.currencies[0])))
     }
 
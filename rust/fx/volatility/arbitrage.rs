@@ -0,0 +1,181 @@
+use crate::dual::Number;
+use crate::error::RateslibError;
+use crate::fx::options::GkMarket;
+use crate::fx::volatility::FxVolSurface;
+use crate::models::black76;
+use serde::{Deserialize, Serialize};
+
+/// A negative density implied by the smile at an expiry, measured by the second difference
+/// of undiscounted call prices at a strike.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ButterflyViolation {
+    pub expiry: f64,
+    /// The log-moneyness, `ln(K / F)`, of the central strike.
+    pub moneyness: f64,
+    pub strike: f64,
+    /// The value of the butterfly per unit strike spacing squared, which is negative.
+    pub magnitude: f64,
+}
+
+/// A decrease in total implied variance at a log-moneyness between consecutive expiries.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CalendarViolation {
+    pub moneyness: f64,
+    pub start: f64,
+    pub end: f64,
+    /// The decrease in total variance, which is positive.
+    pub magnitude: f64,
+}
+
+/// The arbitrage violations of a volatility surface.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct ArbitrageReport {
+    pub butterfly: Vec<ButterflyViolation>,
+    pub calendar: Vec<CalendarViolation>,
+}
+
+impl ArbitrageReport {
+    /// Return whether no violations were found.
+    pub fn is_arbitrage_free(&self) -> bool {
+        self.butterfly.is_empty() && self.calendar.is_empty()
+    }
+}
+
+impl FxVolSurface {
+    /// Check the surface for butterfly and calendar spread arbitrage at each of its expiries
+    /// on a grid of increasing log-moneyness, `ln(K / F)`.
+    ///
+    /// Violations smaller than `tolerance` in magnitude are ignored.
+    pub fn arbitrage_report(
+        &self,
+        market: &GkMarket,
+        moneyness: &[f64],
+        tolerance: f64,
+    ) -> Result<ArbitrageReport, RateslibError> {
+        if moneyness.len() < 3 || moneyness.windows(2).any(|w| w[1] <= w[0]) {
+            return Err(RateslibError::value(
+                "`moneyness` must contain at least three increasing values.",
+            ));
+        }
+        let mut report = ArbitrageReport::default();
+        let mut previous: Option<(f64, Vec<f64>)> = None;
+        for smile in self.smiles.iter() {
+            let t = smile.expiry;
+            let forward = f64::from(&market.forward(t));
+            let strikes: Vec<f64> = moneyness.iter().map(|y| forward * y.exp()).collect();
+            let vols: Vec<f64> = strikes
+                .iter()
+                .map(|k| f64::from(&smile.vol(&Number::F64(forward), &Number::F64(*k))))
+                .collect();
+            let calls: Vec<f64> = strikes
+                .iter()
+                .zip(vols.iter())
+                .map(|(k, v)| {
+                    f64::from(&black76(
+                        &Number::F64(forward),
+                        *k,
+                        t,
+                        &Number::F64(*v),
+                        &Number::F64(1.0),
+                        1.0,
+                    ))
+                })
+                .collect();
+            for i in 1..strikes.len() - 1 {
+                let (h0, h1) = (strikes[i] - strikes[i - 1], strikes[i + 1] - strikes[i]);
+                let density = 2.0
+                    * ((calls[i + 1] - calls[i]) / h1 - (calls[i] - calls[i - 1]) / h0)
+                    / (h0 + h1);
+                if density < -tolerance {
+                    report.butterfly.push(ButterflyViolation {
+                        expiry: t,
+                        moneyness: moneyness[i],
+                        strike: strikes[i],
+                        magnitude: density,
+                    });
+                }
+            }
+
+            let variance: Vec<f64> = vols.iter().map(|v| v * v * t).collect();
+            if let Some((start, prior)) = previous {
+                for (i, (w0, w1)) in prior.iter().zip(variance.iter()).enumerate() {
+                    if w0 - w1 > tolerance {
+                        report.calendar.push(CalendarViolation {
+                            moneyness: moneyness[i],
+                            start,
+                            end: t,
+                            magnitude: w0 - w1,
+                        });
+                    }
+                }
+            }
+            previous = Some((t, variance));
+        }
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fx::volatility::surface::tests::smile_fixture;
+    use crate::fx::volatility::FxDeltaVolSmile;
+
+    fn market() -> GkMarket {
+        GkMarket::new(
+            Number::F64(1.10),
+            Number::F64(0.04),
+            Number::F64(0.02),
+            Number::F64(0.10),
+        )
+    }
+
+    fn grid() -> Vec<f64> {
+        (-10..=10).map(|i| i as f64 * 0.01).collect()
+    }
+
+    fn smile(expiry: f64, atm: f64, rr: f64, bf: f64) -> FxDeltaVolSmile {
+        FxDeltaVolSmile::try_new(expiry, Number::F64(atm), Number::F64(rr), Number::F64(bf))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_arbitrage_free() {
+        let surface = FxVolSurface::try_new(vec![smile_fixture(0.5), smile_fixture(1.0)]).unwrap();
+        let report = surface.arbitrage_report(&market(), &grid(), 1e-12).unwrap();
+        assert!(report.is_arbitrage_free());
+    }
+
+    #[test]
+    fn test_calendar_violation() {
+        let surface =
+            FxVolSurface::try_new(vec![smile(0.5, 0.12, 0.0, 0.0), smile(1.0, 0.05, 0.0, 0.0)])
+                .unwrap();
+        let report = surface.arbitrage_report(&market(), &grid(), 1e-12).unwrap();
+        assert!(report.butterfly.is_empty());
+        assert_eq!(report.calendar.len(), grid().len());
+        let v = report.calendar[10];
+        assert_eq!((v.start, v.end, v.moneyness), (0.5, 1.0, 0.0));
+        assert!((v.magnitude - (0.12 * 0.12 * 0.5 - 0.05 * 0.05)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_butterfly_violation() {
+        // a strongly negative butterfly concaves the smile into a negative density at the money
+        let surface = FxVolSurface::try_new(vec![smile(0.5, 0.10, 0.0, -0.03)]).unwrap();
+        let report = surface.arbitrage_report(&market(), &grid(), 1e-12).unwrap();
+        assert!(!report.butterfly.is_empty());
+        assert!(report
+            .butterfly
+            .iter()
+            .all(|v| v.magnitude < 0.0 && v.expiry == 0.5));
+    }
+
+    #[test]
+    fn test_invalid_grid() {
+        let surface = FxVolSurface::try_new(vec![smile_fixture(0.5)]).unwrap();
+        assert!(surface
+            .arbitrage_report(&market(), &[0.0, 0.1], 0.0)
+            .is_err());
+    }
+}
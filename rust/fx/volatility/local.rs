@@ -0,0 +1,229 @@
+use crate::dual::Number;
+use crate::error::RateslibError;
+use crate::fx::options::GkMarket;
+use crate::fx::volatility::FxVolSurface;
+use serde::{Deserialize, Serialize};
+
+/// Step of the finite differences of total variance in expiry and log-moneyness.
+const FD_STEP: f64 = 1e-4;
+
+/// Controls limiting the local volatility where the surface admits arbitrage.
+///
+/// Calendar arbitrage, a decreasing total variance, is floored at the `floor` volatility and
+/// butterfly arbitrage, a non-positive Dupire denominator, is floored at `min_denominator`.
+/// Local volatilities are capped at `cap`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LocalVolSmoothing {
+    pub floor: f64,
+    pub cap: f64,
+    pub min_denominator: f64,
+}
+
+impl Default for LocalVolSmoothing {
+    fn default() -> Self {
+        Self {
+            floor: 0.01,
+            cap: 2.0,
+            min_denominator: 1e-2,
+        }
+    }
+}
+
+/// A Dupire local volatility surface on a grid of expiries and log-moneyness, `ln(S / F(t))`,
+/// to the forward of the spot at each expiry.
+///
+/// Local volatilities between grid points are bilinearly interpolated and extrapolated flat.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LocalVol {
+    pub(crate) times: Vec<f64>,
+    pub(crate) moneyness: Vec<f64>,
+    /// Local volatilities indexed by time then log-moneyness.
+    pub(crate) vols: Vec<Vec<f64>>,
+    pub(crate) spot: f64,
+    pub(crate) drift: f64,
+}
+
+impl LocalVol {
+    /// Extract the local volatility of an implied volatility `surface` with Gatheral's form of
+    /// the Dupire equation in total variance, differentiated numerically.
+    pub fn try_new(
+        surface: &FxVolSurface,
+        market: &GkMarket,
+        times: Vec<f64>,
+        moneyness: Vec<f64>,
+        smoothing: LocalVolSmoothing,
+    ) -> Result<Self, RateslibError> {
+        let increasing = |v: &[f64]| !v.is_empty() && v.windows(2).all(|w| w[1] > w[0]);
+        if !increasing(&times) || !increasing(&moneyness) || times[0] <= 0.0 {
+            return Err(RateslibError::value(
+                "`times` and `moneyness` of a `LocalVol` must increase and `times` be positive.",
+            ));
+        }
+        let spot = f64::from(&market.spot);
+        let drift = f64::from(&market.domestic_rate) - f64::from(&market.foreign_rate);
+        let variance = |t: f64, y: f64| -> Result<f64, RateslibError> {
+            let forward = Number::F64(spot * (drift * t).exp());
+            let strike = Number::F64(spot * (drift * t + y).exp());
+            let vol = f64::from(&surface.vol(&forward, &strike, t)?);
+            Ok(vol * vol * t)
+        };
+
+        let h = FD_STEP;
+        let mut vols = Vec::with_capacity(times.len());
+        for t in times.iter() {
+            let mut row = Vec::with_capacity(moneyness.len());
+            for y in moneyness.iter() {
+                let w = variance(*t, *y)?;
+                let dw_dt = match *t > h {
+                    true => (variance(t + h, *y)? - variance(t - h, *y)?) / (2.0 * h),
+                    false => (variance(t + h, *y)? - w) / h,
+                };
+                let (up, down) = (variance(*t, y + h)?, variance(*t, y - h)?);
+                let dw_dy = (up - down) / (2.0 * h);
+                let d2w_dy2 = (up - 2.0 * w + down) / (h * h);
+                let denominator = 1.0 - y / w * dw_dy
+                    + 0.25 * (-0.25 - 1.0 / w + y * y / (w * w)) * dw_dy * dw_dy
+                    + 0.5 * d2w_dy2;
+                let local = match dw_dt > 0.0 {
+                    true => (dw_dt / denominator.max(smoothing.min_denominator)).sqrt(),
+                    false => smoothing.floor,
+                };
+                row.push(local.clamp(smoothing.floor, smoothing.cap));
+            }
+            vols.push(row);
+        }
+        Ok(Self {
+            times,
+            moneyness,
+            vols,
+            spot,
+            drift,
+        })
+    }
+
+    pub fn times(&self) -> &[f64] {
+        &self.times
+    }
+
+    pub fn moneyness(&self) -> &[f64] {
+        &self.moneyness
+    }
+
+    /// Return the local volatility at time `t`, in years, and `spot` level.
+    pub fn vol(&self, t: f64, spot: f64) -> f64 {
+        let y = (spot / self.spot).ln() - self.drift * t;
+        let (i, u) = bracket(&self.times, t);
+        let (j, v) = bracket(&self.moneyness, y);
+        let (i1, j1) = (
+            (i + 1).min(self.times.len() - 1),
+            (j + 1).min(self.moneyness.len() - 1),
+        );
+        let row = |i: usize| self.vols[i][j] * (1.0 - v) + self.vols[i][j1] * v;
+        row(i) * (1.0 - u) + row(i1) * u
+    }
+}
+
+/// Return the index of the interval of the increasing `grid` containing `x` and the weight
+/// of its upper point, clamped to the ends of the grid.
+fn bracket(grid: &[f64], x: f64) -> (usize, f64) {
+    if grid.len() == 1 {
+        return (0, 0.0);
+    }
+    let i = grid.partition_point(|g| *g <= x).clamp(1, grid.len() - 1) - 1;
+    let w = ((x - grid[i]) / (grid[i + 1] - grid[i])).clamp(0.0, 1.0);
+    (i, w)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fx::volatility::surface::tests::smile_fixture;
+    use crate::fx::volatility::FxDeltaVolSmile;
+
+    fn market() -> GkMarket {
+        GkMarket::new(
+            Number::F64(1.10),
+            Number::F64(0.04),
+            Number::F64(0.02),
+            Number::F64(0.10),
+        )
+    }
+
+    fn flat(expiry: f64, atm: f64) -> FxDeltaVolSmile {
+        FxDeltaVolSmile::try_new(expiry, Number::F64(atm), Number::F64(0.0), Number::F64(0.0))
+            .unwrap()
+    }
+
+    fn grid() -> (Vec<f64>, Vec<f64>) {
+        (vec![0.25, 0.5, 0.75], vec![-0.1, -0.05, 0.0, 0.05, 0.1])
+    }
+
+    #[test]
+    fn test_flat_surface() {
+        let surface = FxVolSurface::try_new(vec![flat(0.5, 0.1)]).unwrap();
+        let (times, moneyness) = grid();
+        let lv =
+            LocalVol::try_new(&surface, &market(), times, moneyness, Default::default()).unwrap();
+        for v in lv.vols.iter().flatten() {
+            assert!((v - 0.1).abs() < 1e-6);
+        }
+        assert!((lv.vol(0.3, 1.0) - 0.1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_forward_variance() {
+        let surface = FxVolSurface::try_new(vec![flat(0.5, 0.10), flat(1.0, 0.12)]).unwrap();
+        let lv = LocalVol::try_new(
+            &surface,
+            &market(),
+            vec![0.75],
+            vec![0.0],
+            Default::default(),
+        )
+        .unwrap();
+        let expected = ((0.12_f64.powi(2) - 0.10_f64.powi(2) * 0.5) / 0.5).sqrt();
+        assert!((lv.vols[0][0] - expected).abs() < 1e-6);
+        assert_eq!(lv.vol(2.0, 1.5), lv.vols[0][0]);
+    }
+
+    #[test]
+    fn test_skew() {
+        // the local volatility skew is steeper than the negative implied skew
+        let surface = FxVolSurface::try_new(vec![smile_fixture(0.5)]).unwrap();
+        let (times, moneyness) = grid();
+        let lv =
+            LocalVol::try_new(&surface, &market(), times, moneyness, Default::default()).unwrap();
+        let row = &lv.vols[1];
+        assert!(row[1] > row[2] && row[2] > row[3]);
+        let forward = market().forward(0.5);
+        let implied = |y: f64| {
+            let strike = Number::F64(f64::from(&forward) * y.exp());
+            f64::from(&surface.vol(&forward, &strike, 0.5).unwrap())
+        };
+        assert!(row[1] - row[3] > implied(-0.05) - implied(0.05));
+    }
+
+    #[test]
+    fn test_calendar_arbitrage_floored() {
+        let surface = FxVolSurface::try_new(vec![flat(0.5, 0.12), flat(1.0, 0.05)]).unwrap();
+        let smoothing = LocalVolSmoothing {
+            floor: 0.02,
+            ..Default::default()
+        };
+        let lv = LocalVol::try_new(&surface, &market(), vec![0.75], vec![0.0], smoothing).unwrap();
+        assert_eq!(lv.vols[0][0], 0.02);
+    }
+
+    #[test]
+    fn test_invalid_grid() {
+        let surface = FxVolSurface::try_new(vec![flat(0.5, 0.1)]).unwrap();
+        let lv = LocalVol::try_new(
+            &surface,
+            &market(),
+            vec![0.0],
+            vec![0.0],
+            Default::default(),
+        );
+        assert!(lv.is_err());
+    }
+}
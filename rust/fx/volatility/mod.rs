@@ -0,0 +1,25 @@
+//! Create FX volatility smiles and surfaces from market quotes.
+//!
+//! An [FxDeltaVolSmile] is defined at a single expiry by at-the-money, 25 delta risk reversal
+//! and 25 delta butterfly quotes, and an [FxVolSurface] interpolates smiles between expiries.
+//! Quotes are [Number] so that values derived from the surface are differentiable with respect
+//! to them. A [LocalVol] surface is extracted from an [FxVolSurface] by the Dupire equation
+//! for simulation of the spot, and an [ArbitrageReport] locates the butterfly and calendar
+//! spread arbitrage of a surface. [VolTime] measures the time to an intraday expiry with
+//! non-business days, such as weekends, weighted in the accrual of variance.
+//!
+//! [Number]: crate::dual::Number
+
+pub(crate) mod surface;
+pub use crate::fx::volatility::surface::{FxDeltaVolSmile, FxVolSurface};
+
+mod local;
+pub use crate::fx::volatility::local::{LocalVol, LocalVolSmoothing};
+
+mod arbitrage;
+pub use crate::fx::volatility::arbitrage::{
+    ArbitrageReport, ButterflyViolation, CalendarViolation,
+};
+
+mod time;
+pub use crate::fx::volatility::time::{time_to_expiry, VolTime};
@@ -0,0 +1,188 @@
+use crate::dual::{MathFuncs, Number};
+use crate::error::RateslibError;
+use num_traits::Pow;
+use serde::{Deserialize, Serialize};
+
+/// An FX volatility smile at a single `expiry`, in years, quoted by an at-the-money `atm`
+/// volatility and 25 delta risk reversal, `rr25`, and butterfly, `bf25`, volatilities.
+///
+/// The at-the-money strike is delta neutral and the 25 delta strikes use unadjusted forward
+/// deltas. The 25 delta call and put volatilities are `atm + bf25 ± rr25 / 2`. Volatilities
+/// between the three pillar strikes are given by the first order Vanna-Volga approximation of
+/// Castagna and Mercurio.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FxDeltaVolSmile {
+    pub(crate) expiry: f64,
+    pub(crate) atm: Number,
+    pub(crate) rr25: Number,
+    pub(crate) bf25: Number,
+}
+
+impl FxDeltaVolSmile {
+    pub fn try_new(
+        expiry: f64,
+        atm: Number,
+        rr25: Number,
+        bf25: Number,
+    ) -> Result<Self, RateslibError> {
+        let smile = Self {
+            expiry,
+            atm,
+            rr25,
+            bf25,
+        };
+        let pillar_vols = smile.pillar_vols();
+        if expiry <= 0.0 || pillar_vols.iter().any(|v| *v <= 0.0) {
+            return Err(RateslibError::value(
+                "`expiry` and the pillar volatilities of an `FxDeltaVolSmile` must be positive.",
+            ));
+        }
+        Ok(smile)
+    }
+
+    pub fn expiry(&self) -> f64 {
+        self.expiry
+    }
+
+    /// Return the 25 delta put, at-the-money and 25 delta call volatilities.
+    pub fn pillar_vols(&self) -> [Number; 3] {
+        let wing = &self.atm + &self.bf25;
+        [
+            &wing - &self.rr25 * 0.5,
+            self.atm.clone(),
+            &wing + &self.rr25 * 0.5,
+        ]
+    }
+
+    /// Return the 25 delta put, at-the-money and 25 delta call strikes for a `forward`.
+    pub fn pillar_strikes(&self, forward: &Number) -> [Number; 3] {
+        let t = self.expiry;
+        let d = 0.75_f64.inv_norm_cdf();
+        let [put, atm, call] = self.pillar_vols();
+        let strike = |vol: &Number, d1: f64| -> Number {
+            forward * (vol * (-d1 * t.sqrt()) + vol.pow(2.0) * (0.5 * t)).exp()
+        };
+        [strike(&put, d), strike(&atm, 0.0), strike(&call, -d)]
+    }
+
+    /// Return the volatility at a `strike` for a `forward`.
+    pub fn vol(&self, forward: &Number, strike: &Number) -> Number {
+        let [k1, k2, k3] = self.pillar_strikes(forward);
+        let [v1, v2, v3] = self.pillar_vols();
+        let ln = |a: &Number, b: &Number| (a / b).log();
+        let y1 = ln(&k2, strike) * ln(&k3, strike) / (ln(&k2, &k1) * ln(&k3, &k1));
+        let y2 = ln(strike, &k1) * ln(&k3, strike) / (ln(&k2, &k1) * ln(&k3, &k2));
+        let y3 = ln(strike, &k1) * ln(strike, &k2) / (ln(&k3, &k1) * ln(&k3, &k2));
+        y1 * v1 + y2 * v2 + y3 * v3
+    }
+}
+
+/// A term structure of [FxDeltaVolSmile].
+///
+/// Between expiries the at-the-money total variance and the risk reversal and butterfly
+/// volatilities are interpolated linearly, and beyond the first and last expiries the quotes
+/// are extrapolated flat.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FxVolSurface {
+    pub(crate) smiles: Vec<FxDeltaVolSmile>,
+}
+
+impl FxVolSurface {
+    pub fn try_new(smiles: Vec<FxDeltaVolSmile>) -> Result<Self, RateslibError> {
+        if smiles.is_empty() || smiles.windows(2).any(|w| w[1].expiry <= w[0].expiry) {
+            return Err(RateslibError::value(
+                "`smiles` of an `FxVolSurface` must be non-empty with increasing expiries.",
+            ));
+        }
+        Ok(Self { smiles })
+    }
+
+    pub fn smiles(&self) -> &[FxDeltaVolSmile] {
+        &self.smiles
+    }
+
+    /// Return the smile at an `expiry`, in years.
+    pub fn smile(&self, expiry: f64) -> Result<FxDeltaVolSmile, RateslibError> {
+        let i = self.smiles.partition_point(|s| s.expiry < expiry);
+        let (atm, rr25, bf25) = if i == 0 {
+            let s = &self.smiles[0];
+            (s.atm.clone(), s.rr25.clone(), s.bf25.clone())
+        } else if i == self.smiles.len() {
+            let s = &self.smiles[i - 1];
+            (s.atm.clone(), s.rr25.clone(), s.bf25.clone())
+        } else {
+            let (a, b) = (&self.smiles[i - 1], &self.smiles[i]);
+            let w = (expiry - a.expiry) / (b.expiry - a.expiry);
+            let variance =
+                (&a.atm).pow(2.0) * (a.expiry * (1.0 - w)) + (&b.atm).pow(2.0) * (b.expiry * w);
+            (
+                (variance / expiry).pow(0.5),
+                &a.rr25 * (1.0 - w) + &b.rr25 * w,
+                &a.bf25 * (1.0 - w) + &b.bf25 * w,
+            )
+        };
+        FxDeltaVolSmile::try_new(expiry, atm, rr25, bf25)
+    }
+
+    /// Return the volatility at a `strike` and `expiry` for a `forward` to that expiry.
+    pub fn vol(
+        &self,
+        forward: &Number,
+        strike: &Number,
+        expiry: f64,
+    ) -> Result<Number, RateslibError> {
+        Ok(self.smile(expiry)?.vol(forward, strike))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::dual::Dual;
+
+    /// A smile with quotes as the variables "atm", "rr" and "bf".
+    pub(crate) fn smile_fixture(expiry: f64) -> FxDeltaVolSmile {
+        let var = |x: f64, v: &str| Number::Dual(Dual::new(x, vec![v.to_string()]));
+        FxDeltaVolSmile::try_new(
+            expiry,
+            var(0.10, "atm"),
+            var(-0.015, "rr"),
+            var(0.004, "bf"),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_pillars() {
+        let smile = smile_fixture(0.5);
+        let f = Number::F64(1.10);
+        let [k1, k2, k3] = smile.pillar_strikes(&f);
+        assert!(k1 < k2 && k2 < k3);
+        for (k, v) in smile
+            .pillar_strikes(&f)
+            .iter()
+            .zip(smile.pillar_vols().iter())
+        {
+            assert!((f64::from(&smile.vol(&f, k)) - f64::from(v)).abs() < 1e-12);
+        }
+        // the negative risk reversal skews the volatility to low strikes
+        let [v1, _, v3] = smile.pillar_vols();
+        assert!(v1 > v3);
+    }
+
+    #[test]
+    fn test_surface_interpolation() {
+        let surface = FxVolSurface::try_new(vec![smile_fixture(0.5), smile_fixture(1.0)]).unwrap();
+        let smile = surface.smile(0.75).unwrap();
+        assert!((f64::from(&smile.atm) - 0.10).abs() < 1e-12);
+        assert_eq!(surface.smile(2.0).unwrap().atm, smile_fixture(1.0).atm);
+        assert!(FxVolSurface::try_new(vec![smile_fixture(1.0), smile_fixture(0.5)]).is_err());
+    }
+
+    #[test]
+    fn test_invalid_smile() {
+        let smile =
+            FxDeltaVolSmile::try_new(0.5, Number::F64(0.05), Number::F64(0.2), Number::F64(0.0));
+        assert!(smile.is_err());
+    }
+}
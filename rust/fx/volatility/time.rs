@@ -0,0 +1,124 @@
+use crate::calendars::DateRoll;
+use crate::dual::Number;
+use crate::error::RateslibError;
+use chrono::{Duration, NaiveDateTime, NaiveTime};
+
+const SECONDS_PER_DAY: f64 = 86400.0;
+
+/// Return the time, in years of 365 days, from `eval` to `expiry` including the times of day,
+/// so that an option on its expiry day has a positive time to expiry until its cut.
+pub fn time_to_expiry(eval: &NaiveDateTime, expiry: &NaiveDateTime) -> f64 {
+    (*expiry - *eval).num_seconds() as f64 / (365.0 * SECONDS_PER_DAY)
+}
+
+/// A measure of the time over which the variance of an FX rate accrues, weighting each moment
+/// of a non-business day of a `calendar`, such as a weekend, by a `weight` relative to a
+/// business day.
+///
+/// Variance time is expressed in years of 365 days of the average weight of a week without
+/// holidays, `(5 + 2 * weight) / 7`, so that with a unit `weight` and no holidays it is equal
+/// to [time_to_expiry].
+#[derive(Debug, Clone)]
+pub struct VolTime<U: DateRoll> {
+    weight: f64,
+    calendar: U,
+}
+
+impl<U: DateRoll> VolTime<U> {
+    pub fn try_new(weight: f64, calendar: U) -> Result<Self, RateslibError> {
+        if !(0.0..=1.0).contains(&weight) {
+            return Err(RateslibError::value(
+                "The non-business day `weight` of a `VolTime` must be between 0 and 1.",
+            ));
+        }
+        Ok(Self { weight, calendar })
+    }
+
+    /// Return the variance time, in years, from `eval` to `expiry`.
+    pub fn time(&self, eval: &NaiveDateTime, expiry: &NaiveDateTime) -> Result<f64, RateslibError> {
+        if expiry < eval {
+            return Err(RateslibError::value(
+                "`expiry` of a variance time cannot be before `eval`.",
+            ));
+        }
+        let mut weighted_days = 0.0;
+        let mut start = *eval;
+        while start < *expiry {
+            let midnight = start.date().and_time(NaiveTime::MIN);
+            let end = (midnight + Duration::days(1)).min(*expiry);
+            let weight = match self.calendar.is_bus_day(&midnight) {
+                true => 1.0,
+                false => self.weight,
+            };
+            weighted_days += weight * (end - start).num_seconds() as f64 / SECONDS_PER_DAY;
+            start = end;
+        }
+        Ok(weighted_days / (365.0 * (5.0 + 2.0 * self.weight) / 7.0))
+    }
+
+    /// Return the volatility in calendar time equivalent to a volatility `vol` quoted in
+    /// variance time, such that both accrue the same variance from `eval` to `expiry`.
+    ///
+    /// Options priced with the [time_to_expiry] and this volatility reflect the weighting of
+    /// non-business days before expiry.
+    pub fn calendar_vol(
+        &self,
+        vol: &Number,
+        eval: &NaiveDateTime,
+        expiry: &NaiveDateTime,
+    ) -> Result<Number, RateslibError> {
+        let t = time_to_expiry(eval, expiry);
+        if t <= 0.0 {
+            return Err(RateslibError::value(
+                "`expiry` must be after `eval` to measure a volatility.",
+            ));
+        }
+        Ok(vol * (self.time(eval, expiry)? / t).sqrt())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::{ndt, NamedCal};
+    use crate::fx::options::{FxVanillaOption, GkMarket, OptionType};
+
+    #[test]
+    fn test_time_to_expiry_intraday() {
+        let eval = ndt(2024, 3, 15) + Duration::hours(8);
+        let expiry = ndt(2024, 3, 15) + Duration::hours(14);
+        assert!((time_to_expiry(&eval, &expiry) - 0.25 / 365.0).abs() < 1e-15);
+        // an option on its expiry day retains time value before its cut
+        let market = GkMarket::new(
+            Number::F64(1.10),
+            Number::F64(0.04),
+            Number::F64(0.02),
+            Number::F64(0.10),
+        );
+        let call = FxVanillaOption::new(1.10, time_to_expiry(&eval, &expiry), OptionType::Call)
+            .npv(&market)
+            .unwrap();
+        assert!(f64::from(&call) > 0.0);
+    }
+
+    #[test]
+    fn test_weekend_weighting() {
+        let cal = NamedCal::try_new("tgt").unwrap();
+        // from Friday noon to Monday noon spans one business day and a weekend
+        let (fri, mon) = (
+            ndt(2024, 3, 15) + Duration::hours(12),
+            ndt(2024, 3, 18) + Duration::hours(12),
+        );
+        let vt = VolTime::try_new(0.0, cal.clone()).unwrap();
+        assert!((vt.time(&fri, &mon).unwrap() - 1.0 / (365.0 * 5.0 / 7.0)).abs() < 1e-15);
+        let vt = VolTime::try_new(1.0, cal.clone()).unwrap();
+        let t = time_to_expiry(&fri, &mon);
+        assert!((vt.time(&fri, &mon).unwrap() - t).abs() < 1e-15);
+        // a volatility accruing over the weekend at a reduced weight is lower in calendar time
+        let vt = VolTime::try_new(0.3, cal).unwrap();
+        let vol = vt.calendar_vol(&Number::F64(0.1), &fri, &mon).unwrap();
+        assert!(f64::from(&vol) < 0.1);
+        assert!(vt.time(&mon, &fri).is_err());
+        assert!(VolTime::try_new(1.5, NamedCal::try_new("all").unwrap()).is_err());
+    }
+}
@@ -0,0 +1,224 @@
+//! Compare pricing results to golden files, to catch changes which shift prices silently.
+//!
+//! A [GoldenFile] records the NPV and [DeltaLadder] of named valuations and compares them,
+//! within a [GoldenTolerance], to those saved in a JSON file under `rust/golden/data`. Setting
+//! the environment variable `RATESLIB_UPDATE_GOLDEN` saves the results instead, for a change
+//! which is intended to shift prices. The harness is compiled with the `golden` feature.
+
+use crate::dual::Number;
+use crate::error::RateslibError;
+use crate::risk::DeltaLadder;
+use indexmap::{IndexMap, IndexSet};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// The environment variable which saves results to golden files instead of comparing them.
+pub const UPDATE_GOLDEN: &str = "RATESLIB_UPDATE_GOLDEN";
+
+/// The pricing results of a valuation saved to a golden file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GoldenRecord {
+    pub npv: f64,
+    /// The delta ladder by curve and tenor, which is empty if not recorded.
+    pub delta: IndexMap<String, IndexMap<String, f64>>,
+}
+
+impl GoldenRecord {
+    /// Create the [GoldenRecord] of a `value`, with its delta `ladder`, if given.
+    pub fn new(value: &Number, ladder: Option<&DeltaLadder>) -> Self {
+        Self {
+            npv: f64::from(value),
+            delta: ladder.map(|l| l.grid().clone()).unwrap_or_default(),
+        }
+    }
+
+    fn get(&self, curve: &str, tenor: &str) -> Option<f64> {
+        self.delta.get(curve)?.get(tenor).copied()
+    }
+}
+
+/// The absolute and relative differences within which results match their golden values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GoldenTolerance {
+    pub abs: f64,
+    pub rel: f64,
+}
+
+impl Default for GoldenTolerance {
+    fn default() -> Self {
+        Self {
+            abs: 1e-8,
+            rel: 1e-10,
+        }
+    }
+}
+
+impl GoldenTolerance {
+    fn matches(&self, golden: f64, actual: f64) -> bool {
+        (actual - golden).abs() <= self.abs.max(self.rel * golden.abs())
+    }
+}
+
+/// The results of named valuations to compare with a golden file.
+#[derive(Debug, Clone, Default)]
+pub struct GoldenFile {
+    pub(crate) name: String,
+    pub(crate) records: IndexMap<String, GoldenRecord>,
+}
+
+impl GoldenFile {
+    /// Create an empty set of results compared with the golden file `name`.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            records: IndexMap::new(),
+        }
+    }
+
+    /// Return the path of the golden file.
+    pub fn path(&self) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("rust/golden/data")
+            .join(format!("{}.json", self.name))
+    }
+
+    /// Add the result of a valuation under `key`, replacing any result of the same key.
+    pub fn record(&mut self, key: &str, record: GoldenRecord) {
+        self.records.insert(key.to_string(), record);
+    }
+
+    /// Compare the results with the golden file, raising with every mismatch, or save them if
+    /// [UPDATE_GOLDEN] is set.
+    pub fn check(&self, tolerance: GoldenTolerance) -> Result<(), RateslibError> {
+        if std::env::var_os(UPDATE_GOLDEN).is_some() {
+            return self.save();
+        }
+        let path = self.path();
+        let json = std::fs::read_to_string(&path).map_err(|e| {
+            RateslibError::value(format!(
+                "Golden file '{}' cannot be read, set {} to create it: {}",
+                path.display(),
+                UPDATE_GOLDEN,
+                e
+            ))
+        })?;
+        let golden: IndexMap<String, GoldenRecord> = serde_json::from_str(&json)
+            .map_err(|e| RateslibError::value(format!("Golden file is invalid: {}", e)))?;
+        let mismatches = self.compare(&golden, tolerance);
+        match mismatches.is_empty() {
+            true => Ok(()),
+            false => Err(RateslibError::value(format!(
+                "Results differ from golden file '{}':\n{}",
+                self.name,
+                mismatches.join("\n")
+            ))),
+        }
+    }
+
+    /// Return a description of each difference from the `golden` results.
+    pub(crate) fn compare(
+        &self,
+        golden: &IndexMap<String, GoldenRecord>,
+        tolerance: GoldenTolerance,
+    ) -> Vec<String> {
+        let mut mismatches = vec![];
+        for key in golden.keys().filter(|k| !self.records.contains_key(*k)) {
+            mismatches.push(format!("  {}: not recorded", key));
+        }
+        for (key, actual) in self.records.iter() {
+            let Some(expected) = golden.get(key) else {
+                mismatches.push(format!("  {}: not in the golden file", key));
+                continue;
+            };
+            if !tolerance.matches(expected.npv, actual.npv) {
+                mismatches.push(format!(
+                    "  {}: npv {} != golden {}",
+                    key, actual.npv, expected.npv
+                ));
+            }
+            let buckets: IndexSet<(&String, &String)> = expected
+                .delta
+                .iter()
+                .chain(actual.delta.iter())
+                .flat_map(|(c, tenors)| tenors.keys().map(move |t| (c, t)))
+                .collect();
+            for (curve, tenor) in buckets {
+                let (e, a) = (expected.get(curve, tenor), actual.get(curve, tenor));
+                let matches = match (e, a) {
+                    (Some(e), Some(a)) => tolerance.matches(e, a),
+                    _ => false,
+                };
+                if !matches {
+                    mismatches.push(format!(
+                        "  {}: delta {} {} {:?} != golden {:?}",
+                        key, curve, tenor, a, e
+                    ));
+                }
+            }
+        }
+        mismatches
+    }
+
+    fn save(&self) -> Result<(), RateslibError> {
+        let path = self.path();
+        let json = serde_json::to_string_pretty(&self.records)
+            .map_err(|e| RateslibError::value(e.to_string()))?;
+        std::fs::create_dir_all(path.parent().unwrap())
+            .and_then(|_| std::fs::write(&path, json + "\n"))
+            .map_err(|e| RateslibError::value(format!("Golden file cannot be written: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fx::rates::Ccy;
+    use crate::instruments::instrument::tests::irs_fixture;
+    use crate::instruments::Instrument;
+    use crate::periods::Curves;
+    use crate::risk::mapping::tests::calibration_fixture;
+
+    #[test]
+    fn test_golden_irs() {
+        let (calibration, mapping) = calibration_fixture("eur");
+        let curves = Curves::new(Some(&calibration.curve), Some(&calibration.curve));
+        let eur = Ccy::try_new("eur").unwrap();
+        let mut golden = GoldenFile::new("irs");
+        for (i, tenor) in ["1Y", "2Y", "5Y", "10Y"].iter().enumerate() {
+            let npv = calibration.instruments[i].npv(&curves).unwrap();
+            let ladder = DeltaLadder::try_new(&npv, &mapping, eur).unwrap();
+            golden.record(
+                &format!("eur_irs_{}", tenor),
+                GoldenRecord::new(&npv, Some(&ladder)),
+            );
+        }
+        let npv = irs_fixture(3.5).npv(&curves).unwrap();
+        golden.record("off_market_irs", GoldenRecord::new(&npv, None));
+        golden.check(GoldenTolerance::default()).unwrap();
+    }
+
+    #[test]
+    fn test_compare_reports_mismatches() {
+        let record = |npv: f64| GoldenRecord {
+            npv,
+            delta: IndexMap::from_iter([(
+                "eur".to_string(),
+                IndexMap::from_iter([("1Y".to_string(), 1.0)]),
+            )]),
+        };
+        let mut golden = IndexMap::new();
+        golden.insert("a".to_string(), record(100.0));
+        golden.insert("b".to_string(), record(1.0));
+        let mut actual = GoldenFile::new("test");
+        actual.record("a", record(100.0 + 1e-12));
+        actual.record("c", record(1.0));
+        let mut shifted = record(100.0);
+        shifted.delta["eur"]["1Y"] = 1.1;
+        actual.record("a", shifted);
+        let mismatches = actual.compare(&golden, GoldenTolerance::default());
+        assert_eq!(mismatches.len(), 3);
+        assert!(mismatches[0].contains("b: not recorded"));
+        assert!(mismatches[1].contains("a: delta eur 1Y Some(1.1)"));
+        assert!(mismatches[2].contains("c: not in the golden file"));
+    }
+}
@@ -0,0 +1,237 @@
+use crate::calendars::{Convention, DateRoll};
+use crate::dual::Number;
+use crate::error::RateslibError;
+use crate::instruments::{FixedRateBond, Instrument};
+use crate::legs::{FloatLeg, Leg};
+use crate::periods::Curves;
+use crate::scheduling::Schedule;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// The structure of an [AssetSwap], determining the notional of its floating leg.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AswType {
+    /// The investor pays par for the bond and swap together, exchanging the difference between
+    /// par and the dirty price of the bond at settlement, and the floating leg has a notional
+    /// of par.
+    ParPar,
+    /// The floating leg has a notional of the dirty price of the bond, exchanged at maturity,
+    /// so that no upfront payment is made.
+    MarketValue,
+}
+
+/// An asset swap of a [FixedRateBond] bought at a clean `price` on `settlement`, paying the
+/// bond's coupons and redemption in exchange for a [FloatLeg] plus a spread.
+///
+/// Values are per 100 face value of the bond. The [rate](Instrument::rate) of the asset swap
+/// is its spread, in bps, so that asset swap spreads may be calibrated by the
+/// [Solver](crate::solver::Solver).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssetSwap {
+    pub(crate) bond: FixedRateBond,
+    pub(crate) price: f64,
+    pub(crate) settlement: NaiveDateTime,
+    pub(crate) asw_type: AswType,
+    pub(crate) leg2: FloatLeg,
+}
+
+impl AssetSwap {
+    /// Create an [AssetSwap] with a floating leg on the `float_schedule`, which must terminate
+    /// at the maturity of the `bond`, with a `float_spread` in bps.
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_new<U: DateRoll>(
+        bond: FixedRateBond,
+        price: f64,
+        settlement: NaiveDateTime,
+        asw_type: AswType,
+        float_schedule: Schedule,
+        float_convention: Convention,
+        float_spread: f64,
+        calendar: &U,
+    ) -> Result<Self, RateslibError> {
+        if float_schedule.termination() != bond.maturity() {
+            return Err(RateslibError::value(
+                "The floating leg of an `AssetSwap` must terminate at the maturity of the bond.",
+            ));
+        }
+        let dirty = price + bond.accrued(&settlement)?;
+        let leg2 = match asw_type {
+            AswType::ParPar => FloatLeg::try_new(
+                float_schedule,
+                float_spread,
+                -100.0,
+                float_convention,
+                false,
+                calendar,
+            )?,
+            AswType::MarketValue => FloatLeg::try_new(
+                float_schedule,
+                float_spread,
+                -dirty,
+                float_convention,
+                true,
+                calendar,
+            )?,
+        };
+        Ok(Self {
+            bond,
+            price,
+            settlement,
+            asw_type,
+            leg2,
+        })
+    }
+
+    pub fn bond(&self) -> &FixedRateBond {
+        &self.bond
+    }
+
+    pub fn leg2(&self) -> &FloatLeg {
+        &self.leg2
+    }
+
+    pub fn asw_type(&self) -> AswType {
+        self.asw_type
+    }
+
+    /// Return the dirty price of the bond at settlement, per 100 face value.
+    pub fn dirty_price(&self) -> Result<f64, RateslibError> {
+        Ok(self.price + self.bond.accrued(&self.settlement)?)
+    }
+}
+
+impl Instrument for AssetSwap {
+    /// Return the NPV, to the investor, of receiving the floating leg and paying the cashflows
+    /// of the bond after settlement, less any upfront payment.
+    ///
+    /// A par-par asset swap exchanges only the coupons of the bond, the investor retaining its
+    /// redemption.
+    fn npv(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        let disc = curves.discounting()?;
+        let df = disc.df(&self.settlement);
+        let bond = self.bond.curve_dirty_price(curves, &self.settlement)? * df.clone();
+        let adjustment = match self.asw_type {
+            AswType::ParPar => {
+                let redemption = *self.bond.leg1.schedule().pschedule().last().unwrap();
+                disc.df(&redemption) * 100.0 - df * (100.0 - self.dirty_price()?)
+            }
+            AswType::MarketValue => Number::F64(0.0),
+        };
+        Ok(self.leg2.npv(curves)? - bond + adjustment)
+    }
+
+    /// Return the asset swap spread, in bps, for which the NPV of the asset swap is zero.
+    fn rate(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        let npv = self.npv(curves)?;
+        let a_delta = self.leg2.analytic_delta(curves)?;
+        Ok(npv / a_delta + self.leg2.float_spread())
+    }
+
+    fn cashflows(&self, curves: &Curves) -> Result<Vec<(NaiveDateTime, Number)>, RateslibError> {
+        let mut cashflows = self.bond.cashflows(curves)?;
+        if let AswType::ParPar = self.asw_type {
+            cashflows.pop();
+        }
+        cashflows.retain(|(d, _)| *d >= self.settlement);
+        cashflows.iter_mut().for_each(|(_, c)| *c = -c.clone());
+        cashflows.extend(self.leg2.cashflows(curves)?);
+        Ok(cashflows)
+    }
+
+    fn analytic_delta(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        self.leg2.analytic_delta(curves)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::{ndt, Modifier, NamedCal, RollDay};
+    use crate::instruments::bond::tests::bond_fixture;
+    use crate::periods::period::tests::curve_fixture;
+    use crate::scheduling::Frequency;
+
+    fn asw(price: f64, asw_type: AswType, float_spread: f64) -> AssetSwap {
+        let cal = NamedCal::try_new("all").unwrap();
+        let schedule = Schedule::try_new(
+            ndt(2024, 3, 11),
+            ndt(2030, 3, 7),
+            Frequency::Months { number: 3 },
+            RollDay::Unspecified {},
+            Modifier::Act,
+            &cal,
+            0,
+        )
+        .unwrap();
+        AssetSwap::try_new(
+            bond_fixture(4.0, ndt(2030, 3, 7)),
+            price,
+            ndt(2024, 3, 11),
+            asw_type,
+            schedule,
+            Convention::Act365F,
+            float_spread,
+            &cal,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_asw_spread_is_par() {
+        let curve = curve_fixture("c");
+        let curves = Curves::new(Some(&curve), Some(&curve));
+        for asw_type in [AswType::ParPar, AswType::MarketValue] {
+            let spread = f64::from(&asw(98.0, asw_type, 0.0).rate(&curves).unwrap());
+            let npv = asw(98.0, asw_type, spread).npv(&curves).unwrap();
+            assert!(f64::from(&npv).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_asw_spreads_reflect_richness() {
+        // a bond priced at its curve price has zero asset swap spread, and a cheaper bond
+        // pays a positive spread which is larger in market value terms below par
+        let curve = curve_fixture("c");
+        let curves = Curves::new(Some(&curve), Some(&curve));
+        let bond = bond_fixture(4.0, ndt(2030, 3, 7));
+        let model = f64::from(
+            &(bond.curve_dirty_price(&curves, &ndt(2024, 3, 11)).unwrap()
+                - bond.accrued(&ndt(2024, 3, 11)).unwrap()),
+        );
+        let fair = asw(model, AswType::ParPar, 0.0).rate(&curves).unwrap();
+        assert!(f64::from(&fair).abs() < 1e-9);
+        let cheap = model - 15.0;
+        let pp = f64::from(&asw(cheap, AswType::ParPar, 0.0).rate(&curves).unwrap());
+        let mva = f64::from(&asw(cheap, AswType::MarketValue, 0.0).rate(&curves).unwrap());
+        assert!(mva > pp && pp > 0.0);
+        // on a single curve the spreads differ by the ratio of the notionals
+        let dirty = asw(cheap, AswType::MarketValue, 0.0).dirty_price().unwrap();
+        assert!((mva - pp * 100.0 / dirty).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_asw_float_schedule_must_match() {
+        let cal = NamedCal::try_new("all").unwrap();
+        let schedule = Schedule::try_new(
+            ndt(2024, 3, 11),
+            ndt(2029, 3, 7),
+            Frequency::Months { number: 3 },
+            RollDay::Unspecified {},
+            Modifier::Act,
+            &cal,
+            0,
+        )
+        .unwrap();
+        assert!(AssetSwap::try_new(
+            bond_fixture(4.0, ndt(2030, 3, 7)),
+            98.0,
+            ndt(2024, 3, 11),
+            AswType::ParPar,
+            schedule,
+            Convention::Act365F,
+            0.0,
+            &cal,
+        )
+        .is_err());
+    }
+}
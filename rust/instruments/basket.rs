@@ -0,0 +1,268 @@
+use crate::calendars::DateRoll;
+use crate::curves::nodes::NodesTimestamp;
+use crate::curves::{CurveDF, CurveInterpolation, PricingCurve};
+use crate::dual::{get_variable_tags, ADOrder, Gradient1, Number};
+use crate::error::RateslibError;
+use crate::instruments::{FixedRateBond, Instrument};
+use crate::parallel::par_map;
+use crate::periods::Curves;
+use chrono::{DateTime, NaiveDateTime};
+use indexmap::IndexMap;
+use ndarray::Array1;
+
+/// The market value and yield analytics of a bond, or the market value weighted analytics of a
+/// [BondBasket].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BondAnalytics {
+    /// The dirty value of the holding.
+    pub market_value: f64,
+    /// The yield to maturity, in percent.
+    pub ytm: f64,
+    /// The modified duration.
+    pub duration: f64,
+    /// The spread, in bps, of the yield over that of the price implied by a curve.
+    pub spread: f64,
+}
+
+/// A basket of [FixedRateBond]s, such as the constituents of a bond index, each held with a
+/// face value at a clean price.
+///
+/// Analytics of the constituents are measured in parallel and aggregated by market value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BondBasket {
+    pub(crate) constituents: Vec<(FixedRateBond, f64, f64)>,
+}
+
+impl BondBasket {
+    /// Create a [BondBasket] of `constituents` of a bond, its face value and its clean price.
+    pub fn try_new(constituents: Vec<(FixedRateBond, f64, f64)>) -> Result<Self, RateslibError> {
+        if constituents.is_empty() {
+            return Err(RateslibError::value(
+                "A `BondBasket` requires at least one constituent.",
+            ));
+        }
+        Ok(Self { constituents })
+    }
+
+    pub fn constituents(&self) -> &[(FixedRateBond, f64, f64)] {
+        &self.constituents
+    }
+
+    /// Return the [BondAnalytics] of each constituent at `settlement`, with spreads to the
+    /// prices implied by discounting on the `curve`.
+    pub fn constituent_analytics<C: PricingCurve + Sync>(
+        &self,
+        curve: &C,
+        settlement: &NaiveDateTime,
+    ) -> Result<Vec<BondAnalytics>, RateslibError> {
+        par_map(&self.constituents, |(bond, face, price)| {
+            let ytm = bond.ytm(*price, settlement, false)?;
+            let curves = Curves::new(None, Some(curve));
+            let model = f64::from(&bond.curve_dirty_price(&curves, settlement)?);
+            Ok(BondAnalytics {
+                market_value: bond.settlement_cash(*price, settlement, *face)?,
+                ytm,
+                duration: bond.modified_duration(ytm, settlement)?,
+                spread: 100.0 * (ytm - bond.ytm(model, settlement, true)?),
+            })
+        })?
+        .into_iter()
+        .collect()
+    }
+
+    /// Return the total market value of the basket and its market value weighted yield,
+    /// duration and spread.
+    pub fn analytics<C: PricingCurve + Sync>(
+        &self,
+        curve: &C,
+        settlement: &NaiveDateTime,
+    ) -> Result<BondAnalytics, RateslibError> {
+        let constituents = self.constituent_analytics(curve, settlement)?;
+        let market_value: f64 = constituents.iter().map(|a| a.market_value).sum();
+        let weighted = |f: fn(&BondAnalytics) -> f64| {
+            constituents
+                .iter()
+                .map(|a| a.market_value * f(a))
+                .sum::<f64>()
+                / market_value
+        };
+        Ok(BondAnalytics {
+            market_value,
+            ytm: weighted(|a| a.ytm),
+            duration: weighted(|a| a.duration),
+            spread: weighted(|a| a.spread),
+        })
+    }
+
+    /// Return the key rate risk of the basket to each node date of a first order AD `curve`,
+    /// after its initial node.
+    ///
+    /// The risk to each node is the change in value of the holdings for a 1bp increase of the
+    /// continuously compounded Act365F zero rate to the node.
+    pub fn key_rate_risk<T, U>(
+        &self,
+        curve: &CurveDF<T, U>,
+    ) -> Result<IndexMap<NaiveDateTime, f64>, RateslibError>
+    where
+        T: CurveInterpolation + Sync,
+        U: DateRoll + Sync,
+    {
+        let nodes: Vec<(i64, f64)> = match &curve.nodes {
+            NodesTimestamp::Dual(m) if curve.ad() == ADOrder::One => {
+                m.iter().map(|(k, v)| (*k, v.real)).collect()
+            }
+            _ => {
+                return Err(RateslibError::value(
+                    "Key rate risk requires a `curve` with first order AD.",
+                ))
+            }
+        };
+        let tags = get_variable_tags(&curve.id, nodes.len());
+        let gradients = par_map(&self.constituents, |(bond, face, _)| {
+            match bond.npv(&Curves::new(None, Some(curve)))? {
+                Number::Dual(d) => {
+                    Ok::<_, RateslibError>(d.gradient1(tags.clone()) * (*face / 100.0))
+                }
+                _ => Ok(Array1::zeros(tags.len())),
+            }
+        })?;
+        let mut risk = IndexMap::new();
+        for gradient in gradients {
+            let gradient = gradient?;
+            for (i, (k, df)) in nodes.iter().enumerate().skip(1) {
+                let t = (k - nodes[0].0) as f64 / (86400.0 * 365.0);
+                let date = DateTime::from_timestamp(*k, 0).unwrap().naive_utc();
+                *risk.entry(date).or_insert(0.0) -= gradient[i] * t * df * 0.0001;
+            }
+        }
+        Ok(risk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::{ndt, Convention, Modifier, NamedCal};
+    use crate::curves::LogLinearInterpolator;
+    use crate::curves::Nodes;
+    use crate::instruments::bond::tests::bond_fixture;
+    use crate::periods::period::tests::curve_fixture;
+
+    fn basket() -> BondBasket {
+        BondBasket::try_new(vec![
+            (bond_fixture(4.0, ndt(2027, 3, 7)), 2e6, 101.0),
+            (bond_fixture(2.5, ndt(2030, 3, 7)), 1e6, 97.0),
+            (bond_fixture(3.0, ndt(2033, 9, 7)), 3e6, 99.5),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_basket_analytics_are_market_value_weighted() {
+        let curve = curve_fixture("c");
+        let s = ndt(2024, 5, 20);
+        let basket = basket();
+        let constituents = basket.constituent_analytics(&curve, &s).unwrap();
+        let (bond, face, price) = &basket.constituents()[1];
+        assert!((constituents[1].ytm - bond.ytm(*price, &s, false).unwrap()).abs() < 1e-12);
+        assert_eq!(
+            constituents[1].market_value,
+            bond.settlement_cash(*price, &s, *face).unwrap()
+        );
+        let total = basket.analytics(&curve, &s).unwrap();
+        let mv: f64 = constituents.iter().map(|a| a.market_value).sum();
+        let duration: f64 = constituents
+            .iter()
+            .map(|a| a.market_value * a.duration)
+            .sum::<f64>()
+            / mv;
+        assert_eq!(total.market_value, mv);
+        assert!((total.duration - duration).abs() < 1e-12);
+        assert!(total.duration > constituents[0].duration);
+        assert!(BondBasket::try_new(vec![]).is_err());
+    }
+
+    #[test]
+    fn test_basket_analytics_are_cancelled() {
+        let curve = curve_fixture("c");
+        let token = crate::cancel::CancellationToken::new();
+        token.cancel();
+        let result = token.run(|| basket().analytics(&curve, &ndt(2024, 5, 20)));
+        assert!(matches!(result, Err(RateslibError::Cancelled)));
+    }
+
+    #[test]
+    fn test_spread_to_curve() {
+        // a bond priced at the curve has zero spread and a cheaper bond a positive spread
+        let curve = curve_fixture("c");
+        let s = ndt(2024, 1, 1);
+        let bond = bond_fixture(3.0, ndt(2030, 3, 7));
+        let curves = Curves::new(None, Some(&curve));
+        let fair = f64::from(&bond.rate(&curves).unwrap());
+        let basket =
+            BondBasket::try_new(vec![(bond.clone(), 1e6, fair), (bond, 1e6, fair - 2.0)]).unwrap();
+        let a = basket.constituent_analytics(&curve, &s).unwrap();
+        assert!(a[0].spread.abs() < 1e-8);
+        assert!(a[1].spread > 30.0);
+    }
+
+    #[test]
+    fn test_key_rate_risk() {
+        let nodes = Nodes::Dual(IndexMap::from_iter(
+            [
+                (ndt(2024, 1, 1), 1.0),
+                (ndt(2029, 1, 1), 0.9),
+                (ndt(2034, 1, 1), 0.8),
+            ]
+            .into_iter()
+            .enumerate()
+            .map(|(i, (d, v))| {
+                let tag = format!("c{}", i);
+                (d, crate::dual::Dual::new(v, vec![tag]))
+            }),
+        ));
+        let curve = CurveDF::try_new(
+            nodes,
+            LogLinearInterpolator::new(),
+            "c",
+            Convention::Act365F,
+            Modifier::ModF,
+            None,
+            NamedCal::try_new("all").unwrap(),
+        )
+        .unwrap();
+        let basket = basket();
+        let risk = basket.key_rate_risk(&curve).unwrap();
+        assert_eq!(
+            risk.keys().copied().collect::<Vec<_>>(),
+            vec![ndt(2029, 1, 1), ndt(2034, 1, 1)]
+        );
+        // the total risk is close to that of a parallel shift of the zero rates
+        let total: f64 = risk.values().sum();
+        let value = |shift: f64| -> f64 {
+            let shifted = CurveDF::try_new(
+                Nodes::F64(IndexMap::from_iter([
+                    (ndt(2024, 1, 1), 1.0),
+                    (ndt(2029, 1, 1), 0.9 * (-shift * 1826.0 / 365.0).exp()),
+                    (ndt(2034, 1, 1), 0.8 * (-shift * 3653.0 / 365.0).exp()),
+                ])),
+                LogLinearInterpolator::new(),
+                "c",
+                Convention::Act365F,
+                Modifier::ModF,
+                None,
+                NamedCal::try_new("all").unwrap(),
+            )
+            .unwrap();
+            let curves = Curves::new(None, Some(&shifted));
+            basket
+                .constituents()
+                .iter()
+                .map(|(b, face, _)| f64::from(&b.npv(&curves).unwrap()) * face / 100.0)
+                .sum()
+        };
+        let fd = value(0.0001) - value(0.0);
+        assert!((total - fd).abs() / fd.abs() < 1e-3);
+        assert!(basket.key_rate_risk(&curve_fixture("c")).is_err());
+    }
+}
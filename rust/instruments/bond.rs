@@ -0,0 +1,863 @@
+use crate::calendars::{Convention, DateRoll, Modifier, RollDay};
+use crate::curves::{PricingCurve, RollMethod, RolledCurve};
+use crate::dual::Number;
+use crate::error::RateslibError;
+use crate::instruments::horizon::{accrual, static_pnl};
+use crate::instruments::Instrument;
+use crate::legs::{FixedLeg, Leg};
+use crate::periods::{Curves, Period, PeriodType};
+use crate::scheduling::{Frequency, Schedule};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// The rule determining the ex-dividend date of each coupon, after which a bond settles
+/// without the right to receive that coupon.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ExDividend {
+    /// A number of business days before the coupon date.
+    BusinessDays { days: i8 },
+    /// A number of calendar days before the coupon date, rolled back to a business day.
+    CalendarDays { days: i8 },
+}
+
+impl ExDividend {
+    /// The ex-dividend rule of UK gilts, 7 business days before the coupon date.
+    pub const UK_GILT: ExDividend = ExDividend::BusinessDays { days: 7 };
+
+    /// The ex-interest rule of Australian government bonds, 7 calendar days before the
+    /// coupon date.
+    pub const ACGB: ExDividend = ExDividend::CalendarDays { days: 7 };
+
+    /// Return the ex-dividend date of a coupon paid on `date`.
+    pub fn ex_div_date<U: DateRoll>(&self, date: &NaiveDateTime, calendar: &U) -> NaiveDateTime {
+        match self {
+            ExDividend::BusinessDays { days } => calendar.lag(date, -days, false),
+            ExDividend::CalendarDays { days } => {
+                calendar.add_days(date, -days, &Modifier::P, false)
+            }
+        }
+    }
+}
+
+/// The market convention relating the yield of a bond to its price.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum YieldConvention {
+    /// Street convention, compounding at the coupon frequency over the fractional first
+    /// period.
+    #[default]
+    Street,
+    /// Japanese simple yield, the annual coupon plus the pull to par amortized linearly over
+    /// the years to maturity, relative to the clean price.
+    JapaneseSimple,
+    /// Moosmüller, compounding at the coupon frequency but discounting with simple interest
+    /// over the fractional first period.
+    Moosmuller,
+    /// Braess-Fangmeyer, compounding annually over whole years and discounting with simple
+    /// interest within each year.
+    BraessFangmeyer,
+}
+
+/// A fixed rate bond paying coupons on a [Schedule] and redeeming at par.
+///
+/// Prices are expressed per 100 of face value and yields are in percent under the
+/// [YieldConvention] of the bond, by default street convention.
+///
+/// A bond with an [ExDividend] rule settling in the ex-dividend period of a coupon, between
+/// its ex-dividend date and coupon date, does not receive that coupon and has negative accrued
+/// interest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FixedRateBond {
+    pub(crate) leg1: FixedLeg,
+    /// The ex-dividend date of each coupon, if different from its coupon date.
+    #[serde(default)]
+    pub(crate) ex_div_dates: Option<Vec<NaiveDateTime>>,
+    #[serde(default)]
+    pub(crate) yield_convention: YieldConvention,
+}
+
+impl FixedRateBond {
+    /// Create a [FixedRateBond] with a `fixed_rate` in percent, measuring coupon day count
+    /// fractions with the `convention` and `calendar`.
+    pub fn try_new<U: DateRoll>(
+        schedule: Schedule,
+        fixed_rate: f64,
+        convention: Convention,
+        calendar: &U,
+    ) -> Result<Self, RateslibError> {
+        if let Frequency::Zero {} = schedule.frequency() {
+            return Err(RateslibError::value(
+                "A `FixedRateBond` requires a coupon `frequency`.",
+            ));
+        }
+        let leg1 = FixedLeg::try_new(schedule, fixed_rate, -100.0, convention, true, calendar)?;
+        Ok(Self {
+            leg1,
+            ex_div_dates: None,
+            yield_convention: YieldConvention::default(),
+        })
+    }
+
+    /// Return the bond with the ex-dividend date of each coupon determined by the `ex_div`
+    /// rule under the `calendar`.
+    pub fn with_ex_div<U: DateRoll>(mut self, ex_div: ExDividend, calendar: &U) -> Self {
+        self.ex_div_dates = Some(
+            self.coupons()
+                .iter()
+                .map(|(_, end, _)| ex_div.ex_div_date(end, calendar))
+                .collect(),
+        );
+        self
+    }
+
+    /// Return the bond with its yields measured under the `yield_convention`.
+    pub fn with_yield_convention(mut self, yield_convention: YieldConvention) -> Self {
+        self.yield_convention = yield_convention;
+        self
+    }
+
+    pub fn yield_convention(&self) -> YieldConvention {
+        self.yield_convention
+    }
+
+    /// Return the ex-dividend date of the coupon with index `i`.
+    pub(crate) fn ex_div_date(&self, i: usize) -> NaiveDateTime {
+        match &self.ex_div_dates {
+            Some(dates) => dates[i],
+            None => self.coupons()[i].1,
+        }
+    }
+
+    /// Return whether a bond settling on `settlement` receives the coupon with index `i`.
+    fn receives_coupon(&self, i: usize, settlement: &NaiveDateTime) -> bool {
+        *settlement < self.ex_div_date(i)
+    }
+
+    pub fn leg1(&self) -> &FixedLeg {
+        &self.leg1
+    }
+
+    pub fn fixed_rate(&self) -> f64 {
+        self.leg1.fixed_rate()
+    }
+
+    /// Return the maturity of the bond.
+    pub fn maturity(&self) -> NaiveDateTime {
+        self.leg1.schedule().termination()
+    }
+
+    /// Return the number of coupon payments per annum.
+    pub fn frequency(&self) -> f64 {
+        self.leg1.schedule().frequency().periods_per_annum()
+    }
+
+    /// Return the accrual start, accrual end and amount, per 100 face value, of each coupon.
+    pub(crate) fn coupons(&self) -> Vec<(NaiveDateTime, NaiveDateTime, f64)> {
+        self.leg1
+            .periods()
+            .iter()
+            .filter_map(|p| match p {
+                PeriodType::Fixed(f) => Some((
+                    f.base().start(),
+                    f.base().end(),
+                    f.fixed_rate() * f.base().dcf(),
+                )),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Return the index of the coupon period in which `settlement` falls.
+    fn period_index(&self, settlement: &NaiveDateTime) -> Result<usize, RateslibError> {
+        self.coupons()
+            .iter()
+            .position(|(s, e, _)| s <= settlement && settlement < e)
+            .ok_or_else(|| {
+                RateslibError::value("`settlement` must be within the coupon schedule of the bond.")
+            })
+    }
+
+    /// Return the fraction of the coupon period, measured in calendar days, remaining after
+    /// `settlement`.
+    fn remaining_fraction(
+        &self,
+        settlement: &NaiveDateTime,
+    ) -> Result<(usize, f64), RateslibError> {
+        let i = self.period_index(settlement)?;
+        let (start, end, _) = self.coupons()[i];
+        let r = (end - *settlement).num_days() as f64 / (end - start).num_days() as f64;
+        Ok((i, r))
+    }
+
+    /// Return the interest accrued, per 100 face value, at `settlement`.
+    ///
+    /// Interest accrues linearly in calendar days over each coupon period. In the ex-dividend
+    /// period of a coupon the accrued interest is negative, being the interest still to accrue
+    /// until the coupon date.
+    pub fn accrued(&self, settlement: &NaiveDateTime) -> Result<f64, RateslibError> {
+        let (i, r) = self.remaining_fraction(settlement)?;
+        let c = self.coupons()[i].2;
+        match self.receives_coupon(i, settlement) {
+            true => Ok(c * (1.0 - r)),
+            false => Ok(-c * r),
+        }
+    }
+
+    /// Return the cash paid, for a `face` value, to settle the bond at a clean `price` on
+    /// `settlement`.
+    pub fn settlement_cash(
+        &self,
+        price: f64,
+        settlement: &NaiveDateTime,
+        face: f64,
+    ) -> Result<f64, RateslibError> {
+        Ok((price + self.accrued(settlement)?) * face / 100.0)
+    }
+
+    /// Return the price of the bond, per 100 face value, at a given `ytm`, in percent.
+    pub fn price_from_ytm(
+        &self,
+        ytm: f64,
+        settlement: &NaiveDateTime,
+        dirty: bool,
+    ) -> Result<f64, RateslibError> {
+        Ok(self.dirty_price_and_derivative(ytm, settlement)?.0
+            - if dirty {
+                0.0
+            } else {
+                self.accrued(settlement)?
+            })
+    }
+
+    /// Return the modified duration of the bond at a given `ytm`, in percent, which is the
+    /// proportional decrease in its dirty price for a unit increase in its yield.
+    pub fn modified_duration(
+        &self,
+        ytm: f64,
+        settlement: &NaiveDateTime,
+    ) -> Result<f64, RateslibError> {
+        let (price, derivative) = self.dirty_price_and_derivative(ytm, settlement)?;
+        Ok(-100.0 * derivative / price)
+    }
+
+    /// Return whether the holder of a bond from `settlement` to `forward_settlement` receives
+    /// the coupon with index `i`, being entitled to it at its ex-dividend date.
+    fn coupon_received_between(
+        &self,
+        i: usize,
+        settlement: &NaiveDateTime,
+        forward_settlement: &NaiveDateTime,
+    ) -> bool {
+        self.receives_coupon(i, settlement) && !self.receives_coupon(i, forward_settlement)
+    }
+
+    /// Return the dirty price of the bond at a `ytm` and its derivative with respect to `ytm`.
+    fn dirty_price_and_derivative(
+        &self,
+        ytm: f64,
+        settlement: &NaiveDateTime,
+    ) -> Result<(f64, f64), RateslibError> {
+        let (i, r) = self.remaining_fraction(settlement)?;
+        let f = self.frequency();
+        if let YieldConvention::JapaneseSimple = self.yield_convention {
+            let t = (self.maturity() - *settlement).num_days() as f64 / 365.0;
+            let numerator = self.fixed_rate() + 100.0 / t;
+            let denominator = ytm / 100.0 + 1.0 / t;
+            return Ok((
+                numerator / denominator + self.accrued(settlement)?,
+                -numerator / (100.0 * denominator * denominator),
+            ));
+        }
+        let coupons = self.coupons();
+        let n = coupons.len() - i;
+        let (mut price, mut derivative) = (0.0, 0.0);
+        let ex_div = !self.receives_coupon(i, settlement);
+        for (k, (_, _, c)) in coupons[i..].iter().enumerate() {
+            let c = if k == 0 && ex_div { 0.0 } else { *c };
+            let cf = if k == n - 1 { c + 100.0 } else { c };
+            let (d, dd) = self.discount_factor(ytm, k as f64, r, f);
+            price += cf * d;
+            derivative += cf * dd;
+        }
+        Ok((price, derivative))
+    }
+
+    /// Return the discount factor at a `ytm` of a cashflow `k` whole coupon periods after the
+    /// next coupon date, itself a fraction `r` of a period away, and its derivative with
+    /// respect to `ytm`, for a bond paying `f` coupons per annum.
+    fn discount_factor(&self, ytm: f64, k: f64, r: f64, f: f64) -> (f64, f64) {
+        let q = ytm / (100.0 * f);
+        match self.yield_convention {
+            YieldConvention::Street | YieldConvention::JapaneseSimple => {
+                let d = (1.0 + q).powf(-(k + r));
+                (d, -d * (k + r) / ((1.0 + q) * 100.0 * f))
+            }
+            YieldConvention::Moosmuller => {
+                let d = (1.0 + q).powf(-k) / (1.0 + r * q);
+                (d, -d * (k / (1.0 + q) + r / (1.0 + r * q)) / (100.0 * f))
+            }
+            YieldConvention::BraessFangmeyer => {
+                let y = ytm / 100.0;
+                let t = (k + r) / f;
+                let (m, a) = (t.floor(), t - t.floor());
+                let d = (1.0 + y).powf(-m) / (1.0 + a * y);
+                (d, -d * (m / (1.0 + y) + a / (1.0 + a * y)) / 100.0)
+            }
+        }
+    }
+
+    /// Return the yield to maturity, in percent, of the bond at a given `price`.
+    pub fn ytm(
+        &self,
+        price: f64,
+        settlement: &NaiveDateTime,
+        dirty: bool,
+    ) -> Result<f64, RateslibError> {
+        let target = price
+            + if dirty {
+                0.0
+            } else {
+                self.accrued(settlement)?
+            };
+        let mut ytm = self.fixed_rate();
+        for _ in 0..50 {
+            let (p, dp) = self.dirty_price_and_derivative(ytm, settlement)?;
+            let step = (p - target) / dp;
+            ytm -= step;
+            if step.abs() < 1e-12 {
+                return Ok(ytm);
+            }
+        }
+        Err(RateslibError::value(
+            "`ytm` did not converge for the given `price`.",
+        ))
+    }
+
+    /// Return the dirty price of the bond at `settlement` from its coupons discounted on the
+    /// `curves`, excluding coupons paid before `settlement` and a coupon for which `settlement`
+    /// is ex-dividend.
+    pub(crate) fn curve_dirty_price(
+        &self,
+        curves: &Curves,
+        settlement: &NaiveDateTime,
+    ) -> Result<Number, RateslibError> {
+        let disc = curves.discounting()?;
+        if *settlement < disc.initial_date() {
+            return Err(RateslibError::value(
+                "`settlement` cannot be before the initial date of the discounting curve.",
+            ));
+        }
+        let mut npv = self
+            .leg1
+            .periods()
+            .iter()
+            .filter(|p| p.payment() >= *settlement)
+            .try_fold(Number::F64(0.0), |acc, p| {
+                Ok::<_, RateslibError>(acc + p.npv(curves)?)
+            })?;
+        if let Ok(i) = self.period_index(settlement) {
+            if !self.receives_coupon(i, settlement) {
+                let (_, end, c) = self.coupons()[i];
+                npv = npv - disc.df(&end) * c;
+            }
+        }
+        Ok(npv / disc.df(settlement))
+    }
+
+    /// Return the forward price, per 100 face value, at `forward_settlement` of a bond
+    /// financed at a `repo_rate`, in percent, accruing under the `convention`.
+    ///
+    /// Coupons received before `forward_settlement` are reinvested at the `repo_rate`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn fwd_from_repo<U: DateRoll>(
+        &self,
+        price: f64,
+        settlement: &NaiveDateTime,
+        forward_settlement: &NaiveDateTime,
+        repo_rate: f64,
+        convention: Convention,
+        dirty: bool,
+        calendar: &U,
+    ) -> Result<f64, RateslibError> {
+        let d_price = price
+            + if dirty {
+                0.0
+            } else {
+                self.accrued(settlement)?
+            };
+        let dcf = repo_dcf(&convention, settlement, forward_settlement, calendar)?;
+        let mut total = d_price * (1.0 + repo_rate * dcf / 100.0);
+        for (i, (_, end, c)) in self.coupons().into_iter().enumerate() {
+            if self.coupon_received_between(i, settlement, forward_settlement) {
+                let dcf = repo_dcf(&convention, &end, forward_settlement, calendar)?;
+                total -= c * (1.0 + repo_rate * dcf / 100.0);
+            }
+        }
+        Ok(total
+            - if dirty {
+                0.0
+            } else {
+                self.accrued(forward_settlement)?
+            })
+    }
+
+    /// Return the repo rate, in percent, implied by a `forward_price` at
+    /// `forward_settlement`, accruing under the `convention`.
+    ///
+    /// This is the inverse of [FixedRateBond::fwd_from_repo].
+    #[allow(clippy::too_many_arguments)]
+    pub fn repo_from_fwd<U: DateRoll>(
+        &self,
+        price: f64,
+        settlement: &NaiveDateTime,
+        forward_settlement: &NaiveDateTime,
+        forward_price: f64,
+        convention: Convention,
+        dirty: bool,
+        calendar: &U,
+    ) -> Result<f64, RateslibError> {
+        let (p_0, p_t) = match dirty {
+            true => (price, forward_price),
+            false => (
+                price + self.accrued(settlement)?,
+                forward_price + self.accrued(forward_settlement)?,
+            ),
+        };
+        let dcf = repo_dcf(&convention, settlement, forward_settlement, calendar)?;
+        let mut numerator = p_t - p_0;
+        let mut denominator = p_0 * dcf;
+        for (i, (_, end, c)) in self.coupons().into_iter().enumerate() {
+            if self.coupon_received_between(i, settlement, forward_settlement) {
+                let dcf = repo_dcf(&convention, &end, forward_settlement, calendar)?;
+                numerator += c;
+                denominator -= c * dcf;
+            }
+        }
+        Ok(numerator / denominator * 100.0)
+    }
+
+    /// Return the forward price, per 100 face value, at `forward_settlement` of a bond
+    /// financed on a `repo` curve, whose discount factors determine the financing cost and
+    /// the reinvestment of coupons received before `forward_settlement`.
+    pub fn fwd_from_repo_curve(
+        &self,
+        price: f64,
+        settlement: &NaiveDateTime,
+        forward_settlement: &NaiveDateTime,
+        repo: &dyn PricingCurve,
+        dirty: bool,
+    ) -> Result<f64, RateslibError> {
+        let d_price = price
+            + if dirty {
+                0.0
+            } else {
+                self.accrued(settlement)?
+            };
+        let df_s = f64::from(repo.df(settlement));
+        let mut pv = d_price;
+        for (i, (_, end, c)) in self.coupons().into_iter().enumerate() {
+            if self.coupon_received_between(i, settlement, forward_settlement) {
+                pv -= c * f64::from(repo.df(&end)) / df_s;
+            }
+        }
+        let forward = pv * df_s / f64::from(repo.df(forward_settlement));
+        Ok(forward
+            - if dirty {
+                0.0
+            } else {
+                self.accrued(forward_settlement)?
+            })
+    }
+
+    /// Return the clean price of the bond at `settlement` discounted on a `curve` which has
+    /// rolled forward, with unchanged shape, from its initial date to `settlement`.
+    pub fn rolled_price(
+        &self,
+        settlement: &NaiveDateTime,
+        curve: &dyn PricingCurve,
+    ) -> Result<f64, RateslibError> {
+        let rolled = RolledCurve::try_new(curve, *settlement, RollMethod::Unchanged)?;
+        let df = |d: &NaiveDateTime| f64::from(rolled.df(d));
+        let coupons = self.coupons();
+        let mut price = 0.0;
+        for (k, (_, end, c)) in coupons.iter().enumerate() {
+            if end > settlement {
+                let c = if self.receives_coupon(k, settlement) {
+                    *c
+                } else {
+                    0.0
+                };
+                let cf = if k == coupons.len() - 1 { c + 100.0 } else { c };
+                price += cf * df(end);
+            }
+        }
+        Ok(price / df(settlement) - self.accrued(settlement)?)
+    }
+
+    /// Return the carry and roll-down, in price points, of holding the bond from `settlement`
+    /// to `forward_settlement` financed at a `repo_rate`.
+    ///
+    /// Carry is the clean price less the clean forward price, which is the net of coupon
+    /// income and financing cost. Roll-down is the gain in clean price at `forward_settlement`
+    /// on the `curve` rolled forward with unchanged shape, relative to an unchanged yield.
+    #[allow(clippy::too_many_arguments)]
+    pub fn carry_roll_down<U: DateRoll>(
+        &self,
+        price: f64,
+        settlement: &NaiveDateTime,
+        forward_settlement: &NaiveDateTime,
+        repo_rate: f64,
+        convention: Convention,
+        curve: &dyn PricingCurve,
+        calendar: &U,
+    ) -> Result<BondCarry, RateslibError> {
+        let forward = self.fwd_from_repo(
+            price,
+            settlement,
+            forward_settlement,
+            repo_rate,
+            convention,
+            false,
+            calendar,
+        )?;
+        let ytm = self.ytm(self.rolled_price(settlement, curve)?, settlement, false)?;
+        let unchanged = self.price_from_ytm(ytm, forward_settlement, false)?;
+        let rolled = self.rolled_price(forward_settlement, curve)?;
+        Ok(BondCarry {
+            carry: price - forward,
+            roll_down: rolled - unchanged,
+        })
+    }
+}
+
+/// The carry and roll-down of a bond over a horizon, in price points.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BondCarry {
+    pub carry: f64,
+    pub roll_down: f64,
+}
+
+/// Return the day count fraction of a repo accruing between two dates.
+pub(crate) fn repo_dcf<U: DateRoll>(
+    convention: &Convention,
+    start: &NaiveDateTime,
+    end: &NaiveDateTime,
+    calendar: &U,
+) -> Result<f64, RateslibError> {
+    convention.dcf(
+        start,
+        end,
+        None,
+        None,
+        None,
+        &RollDay::Unspecified {},
+        calendar,
+    )
+}
+
+impl FixedRateBond {
+    /// Return the carry of the bond to a `horizon`, which is its coupons accrued pro rata to
+    /// the `horizon` from the initial date of the discounting curve.
+    pub fn carry(&self, curves: &Curves, horizon: &NaiveDateTime) -> Result<Number, RateslibError> {
+        accrual(self.leg1.periods(), curves, horizon)
+    }
+
+    /// Return the roll-down of the bond to a `horizon`, which is its P&L under unchanged
+    /// `curves` less its [carry](FixedRateBond::carry).
+    pub fn rolldown(
+        &self,
+        curves: &Curves,
+        horizon: &NaiveDateTime,
+    ) -> Result<Number, RateslibError> {
+        Ok(static_pnl(self, self, curves, horizon)? - self.carry(curves, horizon)?)
+    }
+}
+
+impl Instrument for FixedRateBond {
+    fn npv(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        self.leg1.npv(curves)
+    }
+
+    /// Return the clean price of the bond implied by the `curves`, settling at the initial
+    /// date of the discounting curve.
+    fn rate(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        let settlement = curves.discounting()?.initial_date();
+        Ok(self.curve_dirty_price(curves, &settlement)? - self.accrued(&settlement)?)
+    }
+
+    fn cashflows(&self, curves: &Curves) -> Result<Vec<(NaiveDateTime, Number)>, RateslibError> {
+        self.leg1.cashflows(curves)
+    }
+
+    fn analytic_delta(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        self.leg1.analytic_delta(curves)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::calendars::{ndt, NamedCal};
+    use crate::periods::period::tests::curve_fixture;
+
+    pub(crate) fn bond_fixture(fixed_rate: f64, maturity: NaiveDateTime) -> FixedRateBond {
+        let cal = NamedCal::try_new("all").unwrap();
+        let schedule = Schedule::try_new(
+            ndt(2020, 3, 7),
+            maturity,
+            Frequency::Months { number: 6 },
+            RollDay::Unspecified {},
+            Modifier::Act,
+            &cal,
+            0,
+        )
+        .unwrap();
+        FixedRateBond::try_new(schedule, fixed_rate, Convention::ActActICMA, &cal).unwrap()
+    }
+
+    #[test]
+    fn test_carry_is_accrued_coupon() {
+        let bond = bond_fixture(4.0, ndt(2030, 3, 7));
+        let curve = curve_fixture("c");
+        let curves = Curves::new(None, Some(&curve));
+        let (s, h) = (ndt(2024, 1, 1), ndt(2024, 2, 1));
+        let carry = f64::from(&bond.carry(&curves, &h).unwrap());
+        let expected = bond.accrued(&h).unwrap() - bond.accrued(&s).unwrap();
+        assert!((carry - expected).abs() < 1e-12);
+        // the total P&L under unchanged curves is the change in dirty price on rolled curves
+        let total = carry + f64::from(&bond.rolldown(&curves, &h).unwrap());
+        let rolled = RolledCurve::try_new(&curve, h, RollMethod::Unchanged).unwrap();
+        let dirty =
+            |c: &dyn PricingCurve| f64::from(&bond.npv(&Curves::new(None, Some(c))).unwrap());
+        assert!((total - (dirty(&rolled) - dirty(&curve))).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_accrued() {
+        let bond = bond_fixture(4.0, ndt(2030, 3, 7));
+        assert_eq!(bond.accrued(&ndt(2024, 3, 7)).unwrap(), 0.0);
+        // 92 days of a 184 day period
+        let accrued = bond.accrued(&ndt(2024, 6, 7)).unwrap();
+        assert!((accrued - 1.0).abs() < 1e-12);
+        assert!(bond.accrued(&ndt(2031, 1, 1)).is_err());
+    }
+
+    #[test]
+    fn test_ex_div_accrued() {
+        let cal = NamedCal::try_new("all").unwrap();
+        let bond = bond_fixture(4.0, ndt(2030, 3, 7)).with_ex_div(ExDividend::UK_GILT, &cal);
+        assert_eq!(bond.ex_div_date(8), ndt(2024, 8, 31));
+        // 4 days before the 7th September 2024 coupon of a 184 day period
+        let accrued = bond.accrued(&ndt(2024, 9, 3)).unwrap();
+        assert!((accrued + 2.0 * 4.0 / 184.0).abs() < 1e-12);
+        assert!(bond.accrued(&ndt(2024, 8, 30)).unwrap() > 0.0);
+        let cash = bond.settlement_cash(99.0, &ndt(2024, 9, 3), 1e6).unwrap();
+        assert!((cash - (99.0 + accrued) * 1e4).abs() < 1e-6);
+        assert_eq!(
+            ExDividend::ACGB.ex_div_date(&ndt(2024, 9, 7), &cal),
+            ndt(2024, 8, 31)
+        );
+    }
+
+    #[test]
+    fn test_ex_div_dirty_price() {
+        let cal = NamedCal::try_new("all").unwrap();
+        let cum = bond_fixture(4.0, ndt(2030, 3, 7));
+        let ex = cum.clone().with_ex_div(ExDividend::UK_GILT, &cal);
+        let s = ndt(2024, 9, 3);
+        // the clean price is unchanged by the exclusion of the coupon, which is exactly
+        // offset by the negative accrued, and the dirty price excludes it
+        let (p_cum, p_ex) = (
+            cum.price_from_ytm(4.0, &s, false).unwrap(),
+            ex.price_from_ytm(4.0, &s, false).unwrap(),
+        );
+        let ytm_ex = ex.ytm(p_ex, &s, false).unwrap();
+        assert!((ytm_ex - 4.0).abs() < 1e-9);
+        let d_cum = cum.price_from_ytm(4.0, &s, true).unwrap();
+        let d_ex = ex.price_from_ytm(4.0, &s, true).unwrap();
+        let v = 1.0 / (1.0 + 0.02_f64);
+        assert!((d_cum - d_ex - 2.0 * v.powf(4.0 / 184.0)).abs() < 1e-10);
+        assert!((p_cum - p_ex).abs() < 0.01);
+        // a repo over the ex-dividend date does not receive the coupon
+        let fwd_cum = cum
+            .fwd_from_repo(
+                99.0,
+                &s,
+                &ndt(2024, 10, 1),
+                5.0,
+                Convention::Act365F,
+                true,
+                &cal,
+            )
+            .unwrap();
+        let fwd_ex = ex
+            .fwd_from_repo(
+                99.0,
+                &s,
+                &ndt(2024, 10, 1),
+                5.0,
+                Convention::Act365F,
+                true,
+                &cal,
+            )
+            .unwrap();
+        assert!(fwd_cum < fwd_ex);
+    }
+
+    #[test]
+    fn test_japanese_simple_yield() {
+        let bond = bond_fixture(4.0, ndt(2030, 3, 7))
+            .with_yield_convention(YieldConvention::JapaneseSimple);
+        let settlement = ndt(2025, 3, 7);
+        // 1826 days to maturity
+        let t = 1826.0 / 365.0;
+        let price = 98.0;
+        let expected = (4.0 + (100.0 - price) / t) / price * 100.0;
+        let ytm = bond.ytm(price, &settlement, false).unwrap();
+        assert!((ytm - expected).abs() < 1e-10);
+        let round_trip = bond.price_from_ytm(ytm, &settlement, false).unwrap();
+        assert!((round_trip - price).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_yield_conventions_round_trip() {
+        let settlement = ndt(2024, 5, 20);
+        for convention in [
+            YieldConvention::Street,
+            YieldConvention::JapaneseSimple,
+            YieldConvention::Moosmuller,
+            YieldConvention::BraessFangmeyer,
+        ] {
+            let bond = bond_fixture(4.0, ndt(2030, 3, 7)).with_yield_convention(convention);
+            for ytm in [1.0, 4.0, 7.5] {
+                let price = bond.price_from_ytm(ytm, &settlement, false).unwrap();
+                assert!((bond.ytm(price, &settlement, false).unwrap() - ytm).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_yield_conventions_on_coupon_date() {
+        // without a fractional period Moosmüller and street convention agree
+        let s = ndt(2024, 3, 7);
+        let street = bond_fixture(4.0, ndt(2030, 3, 7));
+        let moosmuller = street
+            .clone()
+            .with_yield_convention(YieldConvention::Moosmuller);
+        let (p1, p2) = (
+            street.price_from_ytm(5.0, &s, true).unwrap(),
+            moosmuller.price_from_ytm(5.0, &s, true).unwrap(),
+        );
+        assert!((p1 - p2).abs() < 1e-10);
+        // in a fractional period simple interest discounts more than compounding
+        let s = ndt(2024, 5, 20);
+        assert!(
+            moosmuller.price_from_ytm(5.0, &s, true).unwrap()
+                < street.price_from_ytm(5.0, &s, true).unwrap()
+        );
+        // Braess-Fangmeyer at an annual yield discounts a cashflow a year and a half away at
+        // one year compounded and half a year simple
+        let bf = street.with_yield_convention(YieldConvention::BraessFangmeyer);
+        let (d, _) = bf.discount_factor(5.0, 2.0, 1.0, 2.0);
+        assert!((d - 1.0 / (1.05 * 1.025)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_price_ytm_round_trip() {
+        let bond = bond_fixture(4.0, ndt(2030, 3, 7));
+        let settlement = ndt(2024, 5, 20);
+        // a bond priced at its coupon rate on a coupon date is at par
+        let par = bond.price_from_ytm(4.0, &ndt(2024, 3, 7), false).unwrap();
+        assert!((par - 100.0).abs() < 1e-10);
+        for ytm in [1.0, 4.0, 7.5] {
+            let price = bond.price_from_ytm(ytm, &settlement, false).unwrap();
+            assert!((bond.ytm(price, &settlement, false).unwrap() - ytm).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_modified_duration() {
+        let bond = bond_fixture(4.0, ndt(2030, 3, 7));
+        let s = ndt(2024, 5, 20);
+        let duration = bond.modified_duration(4.0, &s).unwrap();
+        let p = |y: f64| bond.price_from_ytm(y, &s, true).unwrap();
+        let fd = (p(3.99) - p(4.01)) / (0.0002 * p(4.0));
+        assert!((duration - fd).abs() < 1e-6);
+        assert!(duration > 4.0 && duration < 5.5);
+    }
+
+    #[test]
+    fn test_fwd_from_repo() {
+        let cal = NamedCal::try_new("all").unwrap();
+        let bond = bond_fixture(4.0, ndt(2030, 3, 7));
+        let (s, f) = (ndt(2024, 4, 1), ndt(2024, 6, 1));
+        let fwd = bond
+            .fwd_from_repo(99.0, &s, &f, 5.0, Convention::Act365F, true, &cal)
+            .unwrap();
+        assert!((fwd - 99.0 * (1.0 + 0.05 * 61.0 / 365.0)).abs() < 1e-12);
+        // a coupon received during the repo reduces the forward price
+        let f = ndt(2024, 10, 1);
+        let fwd = bond
+            .fwd_from_repo(99.0, &s, &f, 5.0, Convention::Act365F, true, &cal)
+            .unwrap();
+        let expected = 99.0 * (1.0 + 0.05 * 183.0 / 365.0) - 2.0 * (1.0 + 0.05 * 24.0 / 365.0);
+        assert!((fwd - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_repo_from_fwd_round_trip() {
+        let cal = NamedCal::try_new("all").unwrap();
+        let bond = bond_fixture(4.0, ndt(2030, 3, 7));
+        let (s, f) = (ndt(2024, 4, 1), ndt(2024, 10, 1));
+        for dirty in [true, false] {
+            let fwd = bond
+                .fwd_from_repo(99.0, &s, &f, 3.5, Convention::Act360, dirty, &cal)
+                .unwrap();
+            let repo = bond
+                .repo_from_fwd(99.0, &s, &f, fwd, Convention::Act360, dirty, &cal)
+                .unwrap();
+            assert!((repo - 3.5).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_fwd_from_repo_curve() {
+        let cal = NamedCal::try_new("all").unwrap();
+        let curve = curve_fixture("repo");
+        let bond = bond_fixture(4.0, ndt(2030, 3, 7));
+        let (s, f) = (ndt(2024, 1, 1), ndt(2024, 7, 1));
+        let fwd = bond
+            .fwd_from_repo_curve(99.0, &s, &f, &curve, false)
+            .unwrap();
+        // the equivalent simple repo rate with the coupon reinvested at the curve rate
+        let repo = bond
+            .repo_from_fwd(99.0, &s, &f, fwd, Convention::Act365F, false, &cal)
+            .unwrap();
+        assert!((repo - 2.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_carry_roll_down() {
+        let cal = NamedCal::try_new("all").unwrap();
+        let curve = curve_fixture("c");
+        let bond = bond_fixture(4.0, ndt(2030, 3, 7));
+        let (s, f) = (ndt(2024, 1, 1), ndt(2024, 4, 1));
+        let price = bond.rolled_price(&s, &curve).unwrap();
+        let result = bond
+            .carry_roll_down(price, &s, &f, 2.0, Convention::Act365F, &curve, &cal)
+            .unwrap();
+        // a 4% coupon financed at 2% has positive carry
+        assert!(result.carry > 0.0);
+        // on a flat curve the rolled price is close to the unchanged yield price
+        assert!(result.roll_down.abs() < 0.05);
+    }
+
+    #[test]
+    fn test_curve_price() {
+        let curve = curve_fixture("c");
+        let curves = Curves::new(None, Some(&curve));
+        let bond = bond_fixture(2.0, ndt(2029, 3, 7));
+        let price = f64::from(&bond.rate(&curves).unwrap());
+        // a 2% coupon bond on a 2% continuously compounded curve is just below par
+        assert!(price > 99.0 && price < 100.0);
+    }
+}
@@ -0,0 +1,338 @@
+use crate::calendars::{Convention, DateRoll};
+use crate::error::RateslibError;
+use crate::instruments::FixedRateBond;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use statrs::distribution::{ContinuousCDF, Normal};
+
+/// A bond future deliverable into any bond of a basket, each converted by its conversion
+/// factor.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BondFuture {
+    pub(crate) basket: Vec<FixedRateBond>,
+    pub(crate) delivery: NaiveDateTime,
+    pub(crate) coupon: f64,
+    pub(crate) conversion_factors: Vec<f64>,
+}
+
+/// The repo financing of the bonds of a basket from a settlement date to delivery.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Financing {
+    /// The clean prices of each bond at `settlement`.
+    pub prices: Vec<f64>,
+    /// The repo rates, in percent, at which each bond is financed.
+    pub repo_rates: Vec<f64>,
+    pub settlement: NaiveDateTime,
+    pub convention: Convention,
+}
+
+/// The value of the delivery option of a [BondFuture] under a switch option model.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeliveryOption {
+    /// The value of the option, in price points, to the short of the future.
+    pub value: f64,
+    /// The futures price net of the value of the delivery option.
+    pub fair_price: f64,
+    /// The probability of each bond of the basket being cheapest-to-deliver at delivery.
+    pub ctd_probabilities: Vec<f64>,
+}
+
+impl BondFuture {
+    /// Create a [BondFuture] with conversion factors determined as the clean price, per unit of
+    /// face value, of each bond at `delivery` at a yield equal to the notional `coupon`.
+    pub fn try_new(
+        basket: Vec<FixedRateBond>,
+        delivery: NaiveDateTime,
+        coupon: f64,
+    ) -> Result<Self, RateslibError> {
+        if basket.is_empty() {
+            return Err(RateslibError::value(
+                "The `basket` of a `BondFuture` cannot be empty.",
+            ));
+        }
+        let conversion_factors = basket
+            .iter()
+            .map(|b| Ok(b.price_from_ytm(coupon, &delivery, false)? / 100.0))
+            .collect::<Result<Vec<f64>, RateslibError>>()?;
+        Ok(Self {
+            basket,
+            delivery,
+            coupon,
+            conversion_factors,
+        })
+    }
+
+    pub fn basket(&self) -> &[FixedRateBond] {
+        &self.basket
+    }
+
+    pub fn delivery(&self) -> NaiveDateTime {
+        self.delivery
+    }
+
+    pub fn conversion_factors(&self) -> &[f64] {
+        &self.conversion_factors
+    }
+
+    fn validate(&self, financing: &Financing) -> Result<(), RateslibError> {
+        let n = self.basket.len();
+        if financing.prices.len() != n || financing.repo_rates.len() != n {
+            return Err(RateslibError::value(
+                "`prices` and `repo_rates` must be given for each bond of the basket.",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Return the gross basis of each bond, which is its clean price less the converted
+    /// futures price.
+    pub fn gross_basis(&self, future_price: f64, prices: &[f64]) -> Vec<f64> {
+        prices
+            .iter()
+            .zip(self.conversion_factors.iter())
+            .map(|(p, cf)| p - future_price * cf)
+            .collect()
+    }
+
+    /// Return the clean forward price at delivery of each bond financed in repo.
+    pub fn forward_prices<U: DateRoll>(
+        &self,
+        financing: &Financing,
+        calendar: &U,
+    ) -> Result<Vec<f64>, RateslibError> {
+        self.validate(financing)?;
+        self.basket
+            .iter()
+            .zip(financing.prices.iter().zip(financing.repo_rates.iter()))
+            .map(|(b, (p, r))| {
+                b.fwd_from_repo(
+                    *p,
+                    &financing.settlement,
+                    &self.delivery,
+                    *r,
+                    financing.convention,
+                    false,
+                    calendar,
+                )
+            })
+            .collect()
+    }
+
+    /// Return the net basis of each bond, which is its forward price at delivery less the
+    /// converted futures price.
+    pub fn net_basis<U: DateRoll>(
+        &self,
+        future_price: f64,
+        financing: &Financing,
+        calendar: &U,
+    ) -> Result<Vec<f64>, RateslibError> {
+        Ok(self
+            .forward_prices(financing, calendar)?
+            .iter()
+            .zip(self.conversion_factors.iter())
+            .map(|(f, cf)| f - future_price * cf)
+            .collect())
+    }
+
+    /// Return the index of the bond with the lowest net basis, which is the current
+    /// cheapest-to-deliver.
+    pub fn ctd_index<U: DateRoll>(
+        &self,
+        future_price: f64,
+        financing: &Financing,
+        calendar: &U,
+    ) -> Result<usize, RateslibError> {
+        let net_basis = self.net_basis(future_price, financing, calendar)?;
+        Ok(argmin(&net_basis))
+    }
+
+    /// Return the repo rate, in percent, implied by financing each bond of the basket and
+    /// delivering it into the future at `future_price`.
+    ///
+    /// The `repo_rates` of the `financing` are not used.
+    pub fn implied_repo<U: DateRoll>(
+        &self,
+        future_price: f64,
+        financing: &Financing,
+        calendar: &U,
+    ) -> Result<Vec<f64>, RateslibError> {
+        self.validate(financing)?;
+        self.basket
+            .iter()
+            .zip(financing.prices.iter().zip(self.conversion_factors.iter()))
+            .map(|(b, (p, cf))| {
+                b.repo_from_fwd(
+                    *p,
+                    &financing.settlement,
+                    &self.delivery,
+                    future_price * cf,
+                    financing.convention,
+                    false,
+                    calendar,
+                )
+            })
+            .collect()
+    }
+
+    /// Value the delivery option under a switch option model.
+    ///
+    /// The forward yields of the bonds of the basket at delivery are shifted in parallel by a
+    /// normally distributed amount with volatility `yield_vol`, in bps per annum, and the
+    /// converted forward prices are evaluated at `points` equally probable quantiles. The
+    /// short delivers the cheapest bond in each scenario, so the fair futures price is the
+    /// expected minimum converted price. The option value is the excess of the expected
+    /// converted price of the current cheapest-to-deliver over the fair price.
+    pub fn delivery_option<U: DateRoll>(
+        &self,
+        financing: &Financing,
+        yield_vol: f64,
+        points: usize,
+        calendar: &U,
+    ) -> Result<DeliveryOption, RateslibError> {
+        if points == 0 || yield_vol < 0.0 {
+            return Err(RateslibError::value(
+                "`points` must be positive and `yield_vol` non-negative.",
+            ));
+        }
+        let forwards = self.forward_prices(financing, calendar)?;
+        let yields = self
+            .basket
+            .iter()
+            .zip(forwards.iter())
+            .map(|(b, f)| b.ytm(*f, &self.delivery, false))
+            .collect::<Result<Vec<f64>, RateslibError>>()?;
+        let converted: Vec<f64> = forwards
+            .iter()
+            .zip(self.conversion_factors.iter())
+            .map(|(f, cf)| f / cf)
+            .collect();
+        let ctd = argmin(&converted);
+
+        let t = (self.delivery - financing.settlement).num_days() as f64 / 365.0;
+        let sd = yield_vol / 100.0 * t.max(0.0).sqrt();
+        let normal = Normal::new(0.0, 1.0).unwrap();
+        let (mut fair_price, mut ctd_price) = (0.0, 0.0);
+        let mut ctd_probabilities = vec![0.0; self.basket.len()];
+        let w = 1.0 / points as f64;
+        for k in 0..points {
+            let shift = sd * normal.inverse_cdf((k as f64 + 0.5) * w);
+            let scenario = self
+                .basket
+                .iter()
+                .zip(yields.iter().zip(self.conversion_factors.iter()))
+                .map(|(b, (y, cf))| Ok(b.price_from_ytm(y + shift, &self.delivery, false)? / cf))
+                .collect::<Result<Vec<f64>, RateslibError>>()?;
+            let i = argmin(&scenario);
+            fair_price += w * scenario[i];
+            ctd_price += w * scenario[ctd];
+            ctd_probabilities[i] += w;
+        }
+        Ok(DeliveryOption {
+            value: ctd_price - fair_price,
+            fair_price,
+            ctd_probabilities,
+        })
+    }
+}
+
+fn argmin(values: &[f64]) -> usize {
+    values
+        .iter()
+        .enumerate()
+        .fold(0, |m, (i, v)| if *v < values[m] { i } else { m })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::{ndt, NamedCal};
+    use crate::instruments::bond::tests::bond_fixture;
+
+    fn future_fixture() -> BondFuture {
+        let basket = vec![
+            bond_fixture(1.0, ndt(2031, 3, 7)),
+            bond_fixture(4.0, ndt(2033, 9, 7)),
+            bond_fixture(6.0, ndt(2035, 3, 7)),
+        ];
+        BondFuture::try_new(basket, ndt(2024, 9, 7), 6.0).unwrap()
+    }
+
+    fn financing_fixture(future: &BondFuture, ytm: f64) -> Financing {
+        let settlement = ndt(2024, 6, 7);
+        let prices = future
+            .basket()
+            .iter()
+            .map(|b| b.price_from_ytm(ytm, &settlement, false).unwrap())
+            .collect();
+        Financing {
+            prices,
+            repo_rates: vec![4.0; 3],
+            settlement,
+            convention: Convention::Act365F,
+        }
+    }
+
+    #[test]
+    fn test_conversion_factors() {
+        let future = future_fixture();
+        let cfs = future.conversion_factors();
+        assert!(cfs[0] < 1.0 && cfs[1] < 1.0);
+        // an exact number of periods at the notional coupon is at par
+        assert!((cfs[2] - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_gross_and_net_basis() {
+        let cal = NamedCal::try_new("all").unwrap();
+        let future = future_fixture();
+        let financing = financing_fixture(&future, 4.5);
+        let gross = future.gross_basis(110.0, &financing.prices);
+        let net = future.net_basis(110.0, &financing, &cal).unwrap();
+        let forwards = future.forward_prices(&financing, &cal).unwrap();
+        for i in 0..3 {
+            assert!((gross[i] - net[i] - (financing.prices[i] - forwards[i])).abs() < 1e-10);
+        }
+        assert!(future.ctd_index(110.0, &financing, &cal).unwrap() < 3);
+    }
+
+    #[test]
+    fn test_implied_repo() {
+        let cal = NamedCal::try_new("all").unwrap();
+        let future = future_fixture();
+        let financing = financing_fixture(&future, 4.5);
+        let net = future.net_basis(110.0, &financing, &cal).unwrap();
+        let repos = future.implied_repo(110.0, &financing, &cal).unwrap();
+        // a positive net basis implies a repo rate below the financing rate
+        for i in 0..3 {
+            assert_eq!(net[i] > 0.0, repos[i] < financing.repo_rates[i]);
+        }
+    }
+
+    #[test]
+    fn test_delivery_option() {
+        let cal = NamedCal::try_new("all").unwrap();
+        let future = future_fixture();
+        let financing = financing_fixture(&future, 5.9);
+
+        let zero_vol = future.delivery_option(&financing, 0.0, 11, &cal).unwrap();
+        assert!(zero_vol.value.abs() < 1e-12);
+        assert!(zero_vol
+            .ctd_probabilities
+            .iter()
+            .any(|p| (*p - 1.0).abs() < 1e-12));
+
+        let option = future.delivery_option(&financing, 100.0, 51, &cal).unwrap();
+        assert!(option.value > 0.0);
+        let total: f64 = option.ctd_probabilities.iter().sum();
+        assert!((total - 1.0).abs() < 1e-12);
+        // with yields close to the notional coupon, volatility changes the CTD
+        assert!(
+            option
+                .ctd_probabilities
+                .iter()
+                .filter(|p| **p > 0.0)
+                .count()
+                > 1
+        );
+    }
+}
@@ -0,0 +1,273 @@
+use crate::curves::PricingCurve;
+use crate::error::RateslibError;
+use crate::instruments::FixedRateBond;
+use crate::lattice::{value_on_lattice, LatticePayoff, TrinomialTree};
+use crate::models::HullWhite;
+use crate::solver::brent;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// A [FixedRateBond] which the issuer may call, or the holder may put, on a set of coupon
+/// dates at given clean prices per 100 face value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CallableBond {
+    pub(crate) bond: FixedRateBond,
+    pub(crate) exercise: Vec<(NaiveDateTime, f64)>,
+    pub(crate) callable: bool,
+}
+
+/// The value of a [CallableBond] on a lattice.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallableValue {
+    pub clean_price: f64,
+    pub dirty_price: f64,
+    /// The value of the embedded option to its holder, per 100 face value.
+    pub option_value: f64,
+    /// The risk neutral probability of the bond being called, or put, on each exercise date.
+    pub exercise_probabilities: Vec<(NaiveDateTime, f64)>,
+}
+
+impl CallableBond {
+    /// Create a [CallableBond] callable by the issuer, if `callable`, or puttable by the
+    /// holder otherwise, on each `exercise` date at its clean price.
+    pub fn try_new(
+        bond: FixedRateBond,
+        mut exercise: Vec<(NaiveDateTime, f64)>,
+        callable: bool,
+    ) -> Result<Self, RateslibError> {
+        exercise.sort_by_key(|a| a.0);
+        let coupon_dates: Vec<NaiveDateTime> = bond.coupons().iter().map(|c| c.1).collect();
+        if exercise.is_empty() || exercise.iter().any(|(d, _)| !coupon_dates.contains(d)) {
+            return Err(RateslibError::value(
+                "`exercise` dates of a `CallableBond` must be coupon dates of the bond.",
+            ));
+        }
+        Ok(Self {
+            bond,
+            exercise,
+            callable,
+        })
+    }
+
+    pub fn bond(&self) -> &FixedRateBond {
+        &self.bond
+    }
+
+    /// Build the tree of the `model` over the remaining life of the bond.
+    fn tree(
+        &self,
+        curve: &dyn PricingCurve,
+        model: &HullWhite,
+        steps_per_year: usize,
+    ) -> Result<TrinomialTree, RateslibError> {
+        let horizon =
+            (self.bond.maturity() - curve.initial_date()).num_seconds() as f64 / (365.0 * 86400.0);
+        if horizon <= 0.0 {
+            return Err(RateslibError::value(
+                "The bond has matured before the initial date of the `curve`.",
+            ));
+        }
+        let steps = ((horizon * steps_per_year as f64).ceil() as usize).max(1);
+        model.tree(curve, horizon, steps)
+    }
+
+    /// Return the value on a `tree`, settling at its initial date, with an option adjusted
+    /// spread, `oas`, in bps, added to the short rate.
+    fn value_on_tree(
+        &self,
+        tree: &TrinomialTree,
+        settlement: &NaiveDateTime,
+        oas: f64,
+    ) -> Result<CallableValue, RateslibError> {
+        let payoff = RemainingPayoff {
+            bond: self,
+            settlement: *settlement,
+        };
+        let value = value_on_lattice(tree, &payoff, oas / 10000.0)?;
+        let dirty_price = match self.callable {
+            true => value.underlying - value.option,
+            false => value.underlying + value.option,
+        };
+        Ok(CallableValue {
+            clean_price: dirty_price - self.bond.accrued(settlement)?,
+            dirty_price,
+            option_value: value.option,
+            exercise_probabilities: payoff
+                .exercise_dates()
+                .into_iter()
+                .zip(value.exercise_probabilities)
+                .collect(),
+        })
+    }
+
+    /// Price the bond on a trinomial tree of the Hull-White `model` fitted to the `curve`,
+    /// settling at its initial date, with an option adjusted spread, `oas`, in bps.
+    pub fn price(
+        &self,
+        curve: &dyn PricingCurve,
+        model: &HullWhite,
+        steps_per_year: usize,
+        oas: f64,
+    ) -> Result<CallableValue, RateslibError> {
+        let tree = self.tree(curve, model, steps_per_year)?;
+        self.value_on_tree(&tree, &curve.initial_date(), oas)
+    }
+
+    /// Return the option adjusted spread, in bps, for which the bond is valued at a
+    /// `clean_price`, solved with [brent].
+    pub fn oas(
+        &self,
+        clean_price: f64,
+        curve: &dyn PricingCurve,
+        model: &HullWhite,
+        steps_per_year: usize,
+    ) -> Result<f64, RateslibError> {
+        let tree = self.tree(curve, model, steps_per_year)?;
+        let settlement = curve.initial_date();
+        brent(
+            |oas| Ok(self.value_on_tree(&tree, &settlement, oas)?.clean_price - clean_price),
+            -2000.0,
+            5000.0,
+            1e-10,
+            200,
+        )
+    }
+
+    /// Return the option adjusted modified duration and convexity of the bond at an `oas`, in
+    /// bps, measured by central differences of the dirty price to a 1bp shift in the `oas`.
+    pub fn duration_convexity(
+        &self,
+        curve: &dyn PricingCurve,
+        model: &HullWhite,
+        steps_per_year: usize,
+        oas: f64,
+    ) -> Result<(f64, f64), RateslibError> {
+        let tree = self.tree(curve, model, steps_per_year)?;
+        let settlement = curve.initial_date();
+        let p = |s: f64| -> Result<f64, RateslibError> {
+            Ok(self.value_on_tree(&tree, &settlement, s)?.dirty_price)
+        };
+        let (down, mid, up) = (p(oas - 1.0)?, p(oas)?, p(oas + 1.0)?);
+        let h = 0.0001;
+        Ok((
+            (down - up) / (2.0 * mid * h),
+            (down + up - 2.0 * mid) / (mid * h * h),
+        ))
+    }
+}
+
+/// The cashflows and exercise dates of a [CallableBond] after a settlement date.
+struct RemainingPayoff<'a> {
+    bond: &'a CallableBond,
+    settlement: NaiveDateTime,
+}
+
+impl LatticePayoff for RemainingPayoff<'_> {
+    fn cashflows(&self) -> Vec<(NaiveDateTime, f64)> {
+        let coupons = self.bond.bond.coupons();
+        let n = coupons.len();
+        coupons
+            .into_iter()
+            .enumerate()
+            .filter(|(_, (_, end, _))| *end > self.settlement)
+            .map(|(k, (_, end, c))| (end, if k == n - 1 { c + 100.0 } else { c }))
+            .collect()
+    }
+
+    fn exercise_dates(&self) -> Vec<NaiveDateTime> {
+        self.bond
+            .exercise
+            .iter()
+            .filter(|(d, _)| *d > self.settlement)
+            .map(|(d, _)| *d)
+            .collect()
+    }
+
+    fn exercise_value(&self, exercise: usize, underlying: f64) -> f64 {
+        let remaining: Vec<&(NaiveDateTime, f64)> = self
+            .bond
+            .exercise
+            .iter()
+            .filter(|(d, _)| *d > self.settlement)
+            .collect();
+        let strike = remaining[exercise].1;
+        match self.bond.callable {
+            true => underlying - strike,
+            false => strike - underlying,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::ndt;
+    use crate::instruments::bond::tests::bond_fixture;
+    use crate::periods::period::tests::curve_fixture;
+
+    fn callable_fixture(callable: bool) -> CallableBond {
+        let bond = bond_fixture(4.0, ndt(2034, 3, 7));
+        let exercise = (2027..2034).map(|y| (ndt(y, 3, 7), 100.0)).collect();
+        CallableBond::try_new(bond, exercise, callable).unwrap()
+    }
+
+    #[test]
+    fn test_callable_below_straight() {
+        let curve = curve_fixture("c");
+        let model = HullWhite::try_new(0.03, 0.01).unwrap();
+        let callable = callable_fixture(true)
+            .price(&curve, &model, 12, 0.0)
+            .unwrap();
+        let puttable = callable_fixture(false)
+            .price(&curve, &model, 12, 0.0)
+            .unwrap();
+        let straight = f64::from(
+            &crate::instruments::Instrument::rate(
+                callable_fixture(true).bond(),
+                &crate::periods::Curves::new(None, Some(&curve)),
+            )
+            .unwrap(),
+        );
+        assert!(callable.option_value > 0.0);
+        assert!(callable.clean_price < straight);
+        assert!(puttable.clean_price > callable.clean_price);
+        // a 4% bond on a 2% curve is very likely to be called at the first date
+        let (_, p) = callable.exercise_probabilities[0];
+        assert!(p > 0.5);
+        let total: f64 = callable.exercise_probabilities.iter().map(|(_, p)| p).sum();
+        assert!(total <= 1.0 + 1e-12);
+    }
+
+    #[test]
+    fn test_oas_round_trip() {
+        let curve = curve_fixture("c");
+        let model = HullWhite::try_new(0.03, 0.01).unwrap();
+        let bond = callable_fixture(true);
+        let price = bond.price(&curve, &model, 12, 75.0).unwrap().clean_price;
+        let oas = bond.oas(price, &curve, &model, 12).unwrap();
+        assert!((oas - 75.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_duration_convexity() {
+        let curve = curve_fixture("c");
+        let model = HullWhite::try_new(0.03, 0.01).unwrap();
+        let (callable_dur, callable_cvx) = callable_fixture(true)
+            .duration_convexity(&curve, &model, 12, 0.0)
+            .unwrap();
+        // a call struck far out of the money is never exercised
+        let bond = bond_fixture(4.0, ndt(2034, 3, 7));
+        let straight = CallableBond::try_new(bond, vec![(ndt(2027, 3, 7), 1e6)], true).unwrap();
+        let (straight_dur, straight_cvx) = straight
+            .duration_convexity(&curve, &model, 12, 0.0)
+            .unwrap();
+        assert!(callable_dur > 0.0 && callable_dur < straight_dur);
+        assert!(callable_cvx < straight_cvx);
+    }
+
+    #[test]
+    fn test_invalid_exercise() {
+        let bond = bond_fixture(4.0, ndt(2034, 3, 7));
+        assert!(CallableBond::try_new(bond, vec![(ndt(2027, 1, 1), 100.0)], true).is_err());
+    }
+}
@@ -0,0 +1,139 @@
+use crate::calendars::{Convention, DateRoll, Modifier, RollDay};
+use crate::dual::Number;
+use crate::error::RateslibError;
+use crate::instruments::Instrument;
+use crate::legs::{CreditPremiumLeg, CreditProtectionLeg, Leg};
+use crate::periods::{Curves, Recovery};
+use crate::scheduling::{Frequency, Schedule};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// A credit default swap paying a [CreditPremiumLeg] and receiving a [CreditProtectionLeg] on a
+/// common [Schedule], so that a positive notional buys protection.
+///
+/// The forecasting curve returns survival probabilities of the reference entity and the
+/// discounting curve discounts the cashflows.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cds {
+    pub(crate) leg1: CreditPremiumLeg,
+    pub(crate) leg2: CreditProtectionLeg,
+}
+
+impl Cds {
+    /// Create a [Cds] accruing the `fixed_rate`, in percent, on an Act360 basis with the premium
+    /// accrued up to default, and recovering the `recovery` rate of the notional on default.
+    pub fn try_new<U: DateRoll>(
+        schedule: Schedule,
+        fixed_rate: f64,
+        notional: f64,
+        recovery: Recovery,
+        calendar: &U,
+    ) -> Result<Self, RateslibError> {
+        let leg1 = CreditPremiumLeg::try_new(
+            schedule.clone(),
+            fixed_rate,
+            notional,
+            Convention::Act360,
+            true,
+            calendar,
+        )?;
+        let leg2 = CreditProtectionLeg::new(schedule, notional, recovery);
+        Ok(Self { leg1, leg2 })
+    }
+
+    /// Create a [Cds] with the standard schedule from `effective` to the `maturity`, which is
+    /// a 20th of March, June, September or December, with quarterly periods rolling on the
+    /// 20th adjusted following by the `calendar` and any short stub at the front.
+    pub fn standard<U: DateRoll>(
+        effective: NaiveDateTime,
+        maturity: NaiveDateTime,
+        fixed_rate: f64,
+        notional: f64,
+        recovery: Recovery,
+        calendar: &U,
+    ) -> Result<Self, RateslibError> {
+        let schedule = Schedule::try_new(
+            effective,
+            maturity,
+            Frequency::Months { number: 3 },
+            RollDay::Int { day: 20 },
+            Modifier::F,
+            calendar,
+            0,
+        )?;
+        Self::try_new(schedule, fixed_rate, notional, recovery, calendar)
+    }
+
+    pub fn leg1(&self) -> &CreditPremiumLeg {
+        &self.leg1
+    }
+
+    pub fn leg2(&self) -> &CreditProtectionLeg {
+        &self.leg2
+    }
+
+    pub fn maturity(&self) -> NaiveDateTime {
+        self.leg2.schedule().termination()
+    }
+}
+
+impl Instrument for Cds {
+    fn npv(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        Ok(self.leg1.npv(curves)? + self.leg2.npv(curves)?)
+    }
+
+    /// Return the par spread, in percent, for which the NPV of the swap is zero.
+    fn rate(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        let npv = self.npv(curves)?;
+        let a_delta = self.leg1.analytic_delta(curves)?;
+        Ok(npv / (a_delta * 100.0) + self.leg1.fixed_rate())
+    }
+
+    fn cashflows(&self, curves: &Curves) -> Result<Vec<(NaiveDateTime, Number)>, RateslibError> {
+        let mut cashflows = self.leg1.cashflows(curves)?;
+        cashflows.extend(self.leg2.cashflows(curves)?);
+        Ok(cashflows)
+    }
+
+    fn analytic_delta(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        self.leg1.analytic_delta(curves)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::{ndt, NamedCal};
+    use crate::periods::period::tests::curve_fixture;
+
+    #[test]
+    fn test_cds_par_spread() {
+        let cal = NamedCal::try_new("nyc").unwrap();
+        let cds = Cds::standard(
+            ndt(2024, 1, 2),
+            ndt(2029, 3, 20),
+            1.0,
+            1e7,
+            0.4.into(),
+            &cal,
+        )
+        .unwrap();
+        assert_eq!(cds.leg1().periods().len(), 21);
+        // a hazard rate of 2% implies a spread of about 0.6 * 2% on an Act360 basis, by the
+        // credit triangle
+        let (disc, hazard) = (curve_fixture("d"), curve_fixture("h"));
+        let curves = Curves::new(Some(&hazard), Some(&disc));
+        let rate = f64::from(&cds.rate(&curves).unwrap());
+        assert!((rate - 1.2 * 360.0 / 365.0).abs() < 0.005);
+        let par = Cds::standard(
+            ndt(2024, 1, 2),
+            ndt(2029, 3, 20),
+            rate,
+            1e7,
+            0.4.into(),
+            &cal,
+        )
+        .unwrap();
+        assert!(f64::from(&par.npv(&curves).unwrap()).abs() < 1e-6);
+    }
+}
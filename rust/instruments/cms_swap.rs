@@ -0,0 +1,132 @@
+use crate::calendars::{Convention, DateRoll};
+use crate::dual::Number;
+use crate::error::RateslibError;
+use crate::instruments::Instrument;
+use crate::legs::{CmsLeg, FixedLeg, Leg};
+use crate::periods::{CmsIndex, CmsMethod, Curves};
+use crate::scheduling::Schedule;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// A swap paying a [FixedLeg] and receiving a [CmsLeg] on a common [Schedule].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CmsSwap {
+    pub(crate) leg1: FixedLeg,
+    pub(crate) leg2: CmsLeg,
+}
+
+impl CmsSwap {
+    /// Create a [CmsSwap], with a `cms_spread` in bps on the CMS leg, whose rates are convexity
+    /// adjusted with the lognormal `volatility` by the [CmsMethod].
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_new<U: DateRoll>(
+        schedule: Schedule,
+        fixed_rate: f64,
+        notional: f64,
+        fixed_convention: Convention,
+        index: CmsIndex,
+        volatility: Number,
+        method: CmsMethod,
+        cms_convention: Convention,
+        cms_spread: f64,
+        calendar: &U,
+    ) -> Result<Self, RateslibError> {
+        let leg1 = FixedLeg::try_new(
+            schedule.clone(),
+            fixed_rate,
+            notional,
+            fixed_convention,
+            false,
+            calendar,
+        )?;
+        let leg2 = CmsLeg::try_new(
+            schedule,
+            index,
+            volatility,
+            method,
+            cms_spread,
+            -notional,
+            cms_convention,
+            calendar,
+        )?;
+        Ok(Self { leg1, leg2 })
+    }
+
+    pub fn leg1(&self) -> &FixedLeg {
+        &self.leg1
+    }
+
+    pub fn leg2(&self) -> &CmsLeg {
+        &self.leg2
+    }
+}
+
+impl Instrument for CmsSwap {
+    fn npv(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        Ok(self.leg1.npv(curves)? + self.leg2.npv(curves)?)
+    }
+
+    /// Return the fixed rate, in percent, for which the NPV of the swap is zero.
+    fn rate(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        let npv = self.npv(curves)?;
+        let a_delta = self.leg1.analytic_delta(curves)?;
+        Ok(npv / (a_delta * 100.0) + self.leg1.fixed_rate())
+    }
+
+    fn cashflows(&self, curves: &Curves) -> Result<Vec<(NaiveDateTime, Number)>, RateslibError> {
+        let mut cashflows = self.leg1.cashflows(curves)?;
+        cashflows.extend(self.leg2.cashflows(curves)?);
+        Ok(cashflows)
+    }
+
+    fn analytic_delta(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        self.leg1.analytic_delta(curves)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::{ndt, Modifier, NamedCal, RollDay};
+    use crate::periods::period::tests::curve_fixture;
+    use crate::scheduling::Frequency;
+
+    fn swap(fixed_rate: f64, volatility: f64) -> CmsSwap {
+        let cal = NamedCal::try_new("all").unwrap();
+        let schedule = Schedule::try_new(
+            ndt(2025, 1, 1),
+            ndt(2028, 1, 1),
+            Frequency::Months { number: 12 },
+            RollDay::Unspecified {},
+            Modifier::Act,
+            &cal,
+            0,
+        )
+        .unwrap();
+        CmsSwap::try_new(
+            schedule,
+            fixed_rate,
+            1e6,
+            Convention::Act365F,
+            CmsIndex::try_new(60, 12).unwrap(),
+            Number::F64(volatility),
+            CmsMethod::Replication { points: 40 },
+            Convention::Act365F,
+            0.0,
+            &cal,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_cms_swap_rate_is_par_and_convex() {
+        let curve = curve_fixture("c");
+        let curves = Curves::new(Some(&curve), Some(&curve));
+        let rate = f64::from(&swap(1.0, 0.2).rate(&curves).unwrap());
+        assert!(f64::from(&swap(rate, 0.2).npv(&curves).unwrap()).abs() < 1e-6);
+        // the convexity adjustment raises the par rate above that without volatility
+        let flat = f64::from(&swap(1.0, 1e-6).rate(&curves).unwrap());
+        assert!(rate > flat && flat > 1.9);
+        assert_eq!(swap(rate, 0.2).cashflows(&curves).unwrap().len(), 6);
+    }
+}
@@ -0,0 +1,377 @@
+use crate::calendars::DateRoll;
+use crate::curves::PricingCurve;
+use crate::dual::Number;
+use crate::error::RateslibError;
+use crate::instruments::{Cds, Instrument};
+use crate::periods::{CurveMap, Curves, Recovery};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// A family of credit indices, which determines the standard coupon and recovery rate of its
+/// series.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CreditIndexFamily {
+    /// CDX North American Investment Grade.
+    CdxIg,
+    /// CDX North American High Yield.
+    CdxHy,
+    /// iTraxx Europe Main.
+    ItraxxMain,
+    /// iTraxx Europe Crossover.
+    ItraxxXover,
+}
+
+impl CreditIndexFamily {
+    /// Return the family of *"cdx.ig"*, *"cdx.hy"*, *"itraxx.main"* or *"itraxx.xover"*.
+    pub fn try_new(name: &str) -> Result<Self, RateslibError> {
+        match name.to_lowercase().as_str() {
+            "cdx.ig" => Ok(CreditIndexFamily::CdxIg),
+            "cdx.hy" => Ok(CreditIndexFamily::CdxHy),
+            "itraxx.main" => Ok(CreditIndexFamily::ItraxxMain),
+            "itraxx.xover" => Ok(CreditIndexFamily::ItraxxXover),
+            _ => Err(RateslibError::value(format!(
+                "'{}' must be one of 'cdx.ig', 'cdx.hy', 'itraxx.main' or 'itraxx.xover'.",
+                name
+            ))),
+        }
+    }
+
+    /// Return the standard coupon, in percent.
+    pub fn coupon(&self) -> f64 {
+        match self {
+            CreditIndexFamily::CdxIg | CreditIndexFamily::ItraxxMain => 1.0,
+            CreditIndexFamily::CdxHy | CreditIndexFamily::ItraxxXover => 5.0,
+        }
+    }
+
+    /// Return the standard recovery rate at which the index is quoted.
+    pub fn recovery(&self) -> f64 {
+        match self {
+            CreditIndexFamily::CdxHy => 0.3,
+            _ => 0.4,
+        }
+    }
+}
+
+/// A reference entity of a [CreditIndex], with its weight in the original index.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Constituent {
+    pub name: String,
+    pub weight: f64,
+    pub defaulted: bool,
+}
+
+impl Constituent {
+    pub fn new(name: &str, weight: f64) -> Self {
+        Self {
+            name: name.to_string(),
+            weight,
+            defaulted: false,
+        }
+    }
+}
+
+/// A credit index, such as a series of CDX or iTraxx, which is a credit default swap on a basket
+/// of equally or otherwise weighted reference entities, paying a standard coupon.
+///
+/// Each default of a constituent is settled separately and removes it from the index, whose
+/// version is incremented and whose factor, the fraction of the original notional which remains,
+/// is reduced by the weight of the constituent. The index is priced as a single [Cds] on the
+/// forecasting curve of the index, quoted as points upfront, or intrinsically from the curves of
+/// its surviving constituents, and the difference between the two spreads is the index basis.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CreditIndex {
+    pub(crate) family: CreditIndexFamily,
+    pub(crate) series: u32,
+    pub(crate) version: u32,
+    pub(crate) notional: f64,
+    pub(crate) constituents: Vec<Constituent>,
+    /// The swap of unit notional on which the index is priced.
+    pub(crate) cds: Cds,
+}
+
+impl CreditIndex {
+    /// Create version 1 of a `series` of the `family` from `effective` to `maturity`, of the
+    /// original `notional` with the `constituents`, whose weights must sum to one.
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_new<U: DateRoll>(
+        family: CreditIndexFamily,
+        series: u32,
+        effective: NaiveDateTime,
+        maturity: NaiveDateTime,
+        notional: f64,
+        constituents: Vec<Constituent>,
+        calendar: &U,
+    ) -> Result<Self, RateslibError> {
+        let total: f64 = constituents.iter().map(|c| c.weight).sum();
+        if (total - 1.0).abs() > 1e-9 || constituents.iter().any(|c| c.weight <= 0.0) {
+            return Err(RateslibError::value(
+                "The weights of the constituents of a `CreditIndex` must be positive and sum to one.",
+            ));
+        }
+        let recovery = Recovery::from(family.recovery());
+        let cds = Cds::standard(
+            effective,
+            maturity,
+            family.coupon(),
+            1.0,
+            recovery,
+            calendar,
+        )?;
+        Ok(Self {
+            family,
+            series,
+            version: 1,
+            notional,
+            constituents,
+            cds,
+        })
+    }
+
+    /// Create version 1 of a `series` with equally weighted constituents of the `names`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_new_equal<U: DateRoll>(
+        family: CreditIndexFamily,
+        series: u32,
+        effective: NaiveDateTime,
+        maturity: NaiveDateTime,
+        notional: f64,
+        names: &[&str],
+        calendar: &U,
+    ) -> Result<Self, RateslibError> {
+        let weight = 1.0 / names.len() as f64;
+        let constituents = names.iter().map(|n| Constituent::new(n, weight)).collect();
+        Self::try_new(
+            family,
+            series,
+            effective,
+            maturity,
+            notional,
+            constituents,
+            calendar,
+        )
+    }
+
+    /// Return the next version of the index after the default of the constituent `name`.
+    pub fn with_default(mut self, name: &str) -> Result<Self, RateslibError> {
+        match self
+            .constituents
+            .iter_mut()
+            .find(|c| c.name == name && !c.defaulted)
+        {
+            Some(c) => c.defaulted = true,
+            None => {
+                return Err(RateslibError::value(format!(
+                    "'{}' is not a surviving constituent of the `CreditIndex`.",
+                    name
+                )))
+            }
+        }
+        self.version += 1;
+        Ok(self)
+    }
+
+    pub fn family(&self) -> CreditIndexFamily {
+        self.family
+    }
+
+    pub fn series(&self) -> u32 {
+        self.series
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn constituents(&self) -> &[Constituent] {
+        &self.constituents
+    }
+
+    /// Return the fraction of the original notional of the surviving constituents.
+    pub fn factor(&self) -> f64 {
+        self.surviving().map(|c| c.weight).sum()
+    }
+
+    /// Return the original notional.
+    pub fn notional(&self) -> f64 {
+        self.notional
+    }
+
+    /// Return the standard coupon, in percent.
+    pub fn coupon(&self) -> f64 {
+        self.family.coupon()
+    }
+
+    /// Return the NPV to the buyer of protection, as a percentage of the remaining notional,
+    /// which is the upfront payment for the standard coupon.
+    pub fn points_upfront(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        Ok(self.cds.npv(curves)? * 100.0)
+    }
+
+    /// Return the NPV of the index from the curves of its surviving constituents in `curves`,
+    /// resolved by name, discounted on the `discounting` curve.
+    pub fn intrinsic_npv(
+        &self,
+        curves: &CurveMap,
+        discounting: &dyn PricingCurve,
+    ) -> Result<Number, RateslibError> {
+        let npv = self.surviving().try_fold(Number::F64(0.0), |acc, c| {
+            let curves = Curves::new(Some(curves.get(&c.name)?), Some(discounting));
+            Ok::<Number, RateslibError>(acc + self.cds.npv(&curves)? * c.weight)
+        })?;
+        Ok(npv * self.notional)
+    }
+
+    /// Return the intrinsic spread, in percent, which is the average of the par spreads of the
+    /// surviving constituents weighted by their risky annuities.
+    pub fn intrinsic_spread(
+        &self,
+        curves: &CurveMap,
+        discounting: &dyn PricingCurve,
+    ) -> Result<Number, RateslibError> {
+        let (mut protection, mut annuity) = (Number::F64(0.0), Number::F64(0.0));
+        for c in self.surviving() {
+            let curves = Curves::new(Some(curves.get(&c.name)?), Some(discounting));
+            let a_delta = self.cds.analytic_delta(&curves)? * c.weight;
+            protection = protection + self.cds.rate(&curves)? * &a_delta;
+            annuity = annuity + a_delta;
+        }
+        Ok(protection / annuity)
+    }
+
+    /// Return the index basis, in percent, which is the par spread of the index on the `index`
+    /// curve less its intrinsic spread from the curves of its constituents.
+    pub fn basis(
+        &self,
+        index: &dyn PricingCurve,
+        curves: &CurveMap,
+        discounting: &dyn PricingCurve,
+    ) -> Result<Number, RateslibError> {
+        let spread = self
+            .cds
+            .rate(&Curves::new(Some(index), Some(discounting)))?;
+        Ok(spread - self.intrinsic_spread(curves, discounting)?)
+    }
+
+    pub(crate) fn surviving(&self) -> impl Iterator<Item = &Constituent> {
+        self.constituents.iter().filter(|c| !c.defaulted)
+    }
+}
+
+impl Instrument for CreditIndex {
+    /// Return the NPV to the buyer of protection on the remaining notional, priced on the
+    /// forecasting curve of the index.
+    fn npv(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        Ok(self.cds.npv(curves)? * (self.notional * self.factor()))
+    }
+
+    /// Return the par spread, in percent, of the index.
+    fn rate(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        self.cds.rate(curves)
+    }
+
+    fn cashflows(&self, curves: &Curves) -> Result<Vec<(NaiveDateTime, Number)>, RateslibError> {
+        let notional = self.notional * self.factor();
+        Ok(self
+            .cds
+            .cashflows(curves)?
+            .into_iter()
+            .map(|(d, c)| (d, c * notional))
+            .collect())
+    }
+
+    fn analytic_delta(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        Ok(self.cds.analytic_delta(curves)? * (self.notional * self.factor()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::{ndt, NamedCal};
+    use crate::curves::HazardCurve;
+    use crate::periods::period::tests::curve_fixture;
+
+    fn hazard(id: &str, spread: f64) -> HazardCurve {
+        let cal = NamedCal::try_new("nyc").unwrap();
+        let disc = curve_fixture("d");
+        let maturities = [ndt(2029, 6, 20)];
+        HazardCurve::bootstrap_standard(
+            id,
+            ndt(2024, 1, 1),
+            &maturities,
+            &[spread],
+            0.4.into(),
+            &disc,
+            cal,
+        )
+        .unwrap()
+    }
+
+    fn index() -> CreditIndex {
+        let cal = NamedCal::try_new("nyc").unwrap();
+        CreditIndex::try_new_equal(
+            CreditIndexFamily::CdxIg,
+            42,
+            ndt(2024, 1, 1),
+            ndt(2029, 6, 20),
+            1e7,
+            &["a", "b", "c", "d"],
+            &cal,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_credit_index_upfront_and_factor() {
+        let disc = curve_fixture("d");
+        let curve = hazard("cdx", 1.0);
+        let curves = Curves::new(Some(curve.curve()), Some(&disc));
+        let cdx = index();
+        // quoted at the coupon, there is no upfront payment
+        assert!(f64::from(&cdx.points_upfront(&curves).unwrap()).abs() < 1e-8);
+        let wide = hazard("cdx", 1.5);
+        let curves = Curves::new(Some(wide.curve()), Some(&disc));
+        let upfront = f64::from(&cdx.points_upfront(&curves).unwrap());
+        assert!(upfront > 0.0);
+        let npv = f64::from(&cdx.npv(&curves).unwrap());
+        assert!((npv - upfront / 100.0 * 1e7).abs() < 1e-6);
+        // a default reduces the factor and increments the version
+        let cdx = cdx.with_default("b").unwrap();
+        assert_eq!((cdx.version(), cdx.factor()), (2, 0.75));
+        assert!((f64::from(&cdx.npv(&curves).unwrap()) - 0.75 * npv).abs() < 1e-6);
+        assert!(cdx.with_default("b").is_err());
+    }
+
+    #[test]
+    fn test_credit_index_intrinsic_and_basis() {
+        let disc = curve_fixture("d");
+        let curves: Vec<HazardCurve> = [0.6, 0.8, 1.2, 2.0]
+            .iter()
+            .zip(["a", "b", "c", "d"])
+            .map(|(s, n)| hazard(n, *s))
+            .collect();
+        let map = ["a", "b", "c", "d"]
+            .iter()
+            .zip(curves.iter())
+            .fold(CurveMap::new(), |m, (n, c)| m.with_curve(n, c.curve()));
+        let cdx = index();
+        let intrinsic = f64::from(&cdx.intrinsic_spread(&map, &disc).unwrap());
+        // risky annuities are lower for wider names, so the intrinsic spread is below the mean
+        assert!(intrinsic < 1.15 && intrinsic > 1.0);
+        // an index curve at the intrinsic spread has no basis, and prices close to the intrinsic
+        // value up to the dispersion of the constituents
+        let flat = hazard("cdx", intrinsic);
+        let basis = f64::from(&cdx.basis(flat.curve(), &map, &disc).unwrap());
+        assert!(basis.abs() < 1e-9);
+        let flat_curves = Curves::new(Some(flat.curve()), Some(&disc));
+        let (npv, intrinsic_npv) = (
+            f64::from(&cdx.npv(&flat_curves).unwrap()),
+            f64::from(&cdx.intrinsic_npv(&map, &disc).unwrap()),
+        );
+        assert!((npv - intrinsic_npv).abs() < 1e-3 * npv);
+        let cdx = cdx.with_default("d").unwrap();
+        assert!(f64::from(&cdx.intrinsic_spread(&map, &disc).unwrap()) < intrinsic);
+        assert!(CreditIndexFamily::try_new("ITRAXX.XOVER").unwrap().coupon() == 5.0);
+    }
+}
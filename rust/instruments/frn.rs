@@ -0,0 +1,307 @@
+use crate::calendars::{Convention, DateRoll};
+use crate::dual::Number;
+use crate::error::RateslibError;
+use crate::instruments::Instrument;
+use crate::legs::{FloatLeg, Leg};
+use crate::periods::{Curves, Period, PeriodType};
+use crate::scheduling::{Frequency, Schedule};
+use chrono::NaiveDateTime;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+/// A floating rate note paying coupons of a floating rate plus a quoted margin on a
+/// [Schedule] and redeeming at par.
+///
+/// Prices are expressed per 100 of face value. The discount margin of a price is the spread,
+/// in basis points, over the forecast floating rates at which the cashflows are discounted
+/// with simple interest over each coupon period to that price.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FloatRateNote {
+    pub(crate) leg1: FloatLeg,
+}
+
+impl FloatRateNote {
+    /// Create a [FloatRateNote] with a `quoted_margin` in bps, measuring coupon day count
+    /// fractions with the `convention` and `calendar`.
+    pub fn try_new<U: DateRoll>(
+        schedule: Schedule,
+        quoted_margin: f64,
+        convention: Convention,
+        calendar: &U,
+    ) -> Result<Self, RateslibError> {
+        if let Frequency::Zero {} = schedule.frequency() {
+            return Err(RateslibError::value(
+                "A `FloatRateNote` requires a coupon `frequency`.",
+            ));
+        }
+        let leg1 = FloatLeg::try_new(schedule, quoted_margin, -100.0, convention, true, calendar)?;
+        Ok(Self { leg1 })
+    }
+
+    /// Return the note with the known `fixings`, in percent, of the coupon periods whose
+    /// accrual starts on each date.
+    pub fn with_fixings(mut self, fixings: &IndexMap<NaiveDateTime, f64>) -> Self {
+        for period in self.leg1.periods.iter_mut() {
+            if let PeriodType::Float(p) = period {
+                if let Some(f) = fixings.get(&p.base.start) {
+                    p.fixing = Some(*f);
+                }
+            }
+        }
+        self
+    }
+
+    pub fn leg1(&self) -> &FloatLeg {
+        &self.leg1
+    }
+
+    /// Return the quoted margin, in bps.
+    pub fn quoted_margin(&self) -> f64 {
+        self.leg1.float_spread()
+    }
+
+    /// Return the maturity of the note.
+    pub fn maturity(&self) -> NaiveDateTime {
+        self.leg1.schedule().termination()
+    }
+
+    /// Return the coupon periods remaining at `settlement`, with the fraction, measured in
+    /// calendar days, of the first remaining period after `settlement`.
+    fn remaining_periods(
+        &self,
+        settlement: &NaiveDateTime,
+    ) -> Result<(Vec<&PeriodType>, f64), RateslibError> {
+        let periods: Vec<&PeriodType> = self
+            .leg1
+            .periods()
+            .iter()
+            .filter(|p| matches!(p, PeriodType::Float(_)))
+            .collect();
+        let i = periods
+            .iter()
+            .position(|p| {
+                let base = p.base().expect("Floating periods accrue");
+                base.start() <= *settlement && *settlement < base.end()
+            })
+            .ok_or_else(|| {
+                RateslibError::value("`settlement` must be within the coupon schedule of the note.")
+            })?;
+        let base = periods[i].base().expect("Floating periods accrue");
+        let r = (base.end() - *settlement).num_days() as f64
+            / (base.end() - base.start()).num_days() as f64;
+        Ok((periods[i..].to_vec(), r))
+    }
+
+    /// Return the interest accrued, per 100 face value, at `settlement`, from the fixing or
+    /// forecast rate of the current coupon period.
+    pub fn accrued(
+        &self,
+        settlement: &NaiveDateTime,
+        curves: &Curves,
+    ) -> Result<Number, RateslibError> {
+        let (periods, r) = self.remaining_periods(settlement)?;
+        Ok(periods[0].cashflow(curves)? * (1.0 - r))
+    }
+
+    /// Return the dirty price of the note at a discount margin `dm`, in bps, settling at the
+    /// initial date of the discounting curve, and its derivative with respect to `dm`.
+    fn dirty_price_and_derivative(
+        &self,
+        dm: f64,
+        curves: &Curves,
+    ) -> Result<(Number, f64), RateslibError> {
+        let settlement = curves.discounting()?.initial_date();
+        let (periods, r) = self.remaining_periods(&settlement)?;
+        let n = periods.len();
+        let qm = self.quoted_margin();
+        let (mut price, mut derivative) = (Number::F64(0.0), 0.0);
+        let (mut d, mut dlog) = (Number::F64(1.0), 0.0);
+        for (k, period) in periods.iter().enumerate() {
+            let PeriodType::Float(p) = period else {
+                unreachable!("Only floating periods are remaining")
+            };
+            let tau = match k {
+                0 => p.base.dcf() * r,
+                _ => p.base.dcf(),
+            };
+            // the discount rate is the index rate, excluding the quoted margin, plus `dm`
+            let rate = p.rate(curves)? + (dm - qm) / 100.0;
+            let factor = rate * (tau / 100.0) + 1.0;
+            dlog += (tau / 10000.0) / f64::from(&factor);
+            d = d / factor;
+            let cf = match k == n - 1 {
+                true => p.cashflow(curves)? + 100.0,
+                false => p.cashflow(curves)?,
+            };
+            derivative -= f64::from(&cf) * f64::from(&d) * dlog;
+            price = price + cf * d.clone();
+        }
+        Ok((price, derivative))
+    }
+
+    /// Return the price of the note, per 100 face value, at a discount margin `dm`, in bps,
+    /// settling at the initial date of the discounting curve.
+    pub fn price_from_dm(
+        &self,
+        dm: f64,
+        curves: &Curves,
+        dirty: bool,
+    ) -> Result<Number, RateslibError> {
+        let settlement = curves.discounting()?.initial_date();
+        let dirty_price = self.dirty_price_and_derivative(dm, curves)?.0;
+        match dirty {
+            true => Ok(dirty_price),
+            false => Ok(dirty_price - self.accrued(&settlement, curves)?),
+        }
+    }
+
+    /// Return the discount margin, in bps, of the note at a given `price`, settling at the
+    /// initial date of the discounting curve.
+    ///
+    /// The margin is solved by Newton iterations on its value, and a final Newton step on the
+    /// [Number] price carries the sensitivity of the margin to the `curves`.
+    pub fn dm(&self, price: f64, curves: &Curves, dirty: bool) -> Result<Number, RateslibError> {
+        let settlement = curves.discounting()?.initial_date();
+        let target = match dirty {
+            true => Number::F64(price),
+            false => self.accrued(&settlement, curves)? + price,
+        };
+        let mut dm = self.quoted_margin();
+        for _ in 0..50 {
+            let (p, dp) = self.dirty_price_and_derivative(dm, curves)?;
+            let step = f64::from(&(p - target.clone())) / dp;
+            dm -= step;
+            if step.abs() < 1e-12 {
+                let (p, dp) = self.dirty_price_and_derivative(dm, curves)?;
+                return Ok((target - p) / dp + dm);
+            }
+        }
+        Err(RateslibError::value(
+            "`dm` did not converge for the given `price`.",
+        ))
+    }
+}
+
+impl Instrument for FloatRateNote {
+    fn npv(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        self.leg1.npv(curves)
+    }
+
+    /// Return the clean price of the note implied by the `curves`, settling at the initial
+    /// date of the discounting curve.
+    fn rate(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        let disc = curves.discounting()?;
+        let settlement = disc.initial_date();
+        Ok(self.leg1.npv(curves)? / disc.df(&settlement) - self.accrued(&settlement, curves)?)
+    }
+
+    fn cashflows(&self, curves: &Curves) -> Result<Vec<(NaiveDateTime, Number)>, RateslibError> {
+        self.leg1.cashflows(curves)
+    }
+
+    fn analytic_delta(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        self.leg1.analytic_delta(curves)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::{ndt, Modifier, NamedCal, RollDay};
+    use crate::curves::PricingCurve;
+    use crate::dual::Gradient1;
+    use crate::periods::period::tests::curve_fixture;
+
+    fn frn_fixture(quoted_margin: f64) -> FloatRateNote {
+        let cal = NamedCal::try_new("all").unwrap();
+        let schedule = Schedule::try_new(
+            ndt(2023, 11, 15),
+            ndt(2027, 2, 15),
+            Frequency::Months { number: 3 },
+            RollDay::Unspecified {},
+            Modifier::Act,
+            &cal,
+            0,
+        )
+        .unwrap();
+        FloatRateNote::try_new(schedule, quoted_margin, Convention::Act365F, &cal)
+            .unwrap()
+            .with_fixings(&IndexMap::from_iter([(ndt(2023, 11, 15), 2.5)]))
+    }
+
+    #[test]
+    fn test_dm_equal_to_quoted_margin_at_par() {
+        // forecast and discounted on the same curve a note at its quoted margin is at par
+        // on a coupon date
+        let curve = curve_fixture("c");
+        let curves = Curves::new(Some(&curve), Some(&curve));
+        let cal = NamedCal::try_new("all").unwrap();
+        let schedule = Schedule::try_new(
+            ndt(2024, 1, 1),
+            ndt(2027, 1, 1),
+            Frequency::Months { number: 3 },
+            RollDay::Unspecified {},
+            Modifier::Act,
+            &cal,
+            0,
+        )
+        .unwrap();
+        let frn = FloatRateNote::try_new(schedule, 50.0, Convention::Act365F, &cal).unwrap();
+        let price = f64::from(&frn.price_from_dm(50.0, &curves, false).unwrap());
+        assert!((price - 100.0).abs() < 1e-9);
+        let dm = f64::from(&frn.dm(100.0, &curves, false).unwrap());
+        assert!((dm - 50.0).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_dm_price_round_trip() {
+        let curve = curve_fixture("c");
+        let curves = Curves::new(Some(&curve), Some(&curve));
+        let frn = frn_fixture(25.0);
+        // the first coupon uses the fixing
+        let accrued = f64::from(&frn.accrued(&ndt(2024, 1, 1), &curves).unwrap());
+        assert!((accrued - 2.75 * 47.0 / 365.0).abs() < 1e-12);
+        for dm in [-10.0, 25.0, 140.0] {
+            let price = f64::from(&frn.price_from_dm(dm, &curves, false).unwrap());
+            let solved = f64::from(&frn.dm(price, &curves, false).unwrap());
+            assert!((solved - dm).abs() < 1e-8);
+        }
+        // a higher discount margin lowers the price
+        let (p1, p2) = (
+            f64::from(&frn.price_from_dm(20.0, &curves, true).unwrap()),
+            f64::from(&frn.price_from_dm(30.0, &curves, true).unwrap()),
+        );
+        assert!(p2 < p1);
+        assert!(frn.price_from_dm(0.0, &Curves::default(), true).is_err());
+    }
+
+    #[test]
+    fn test_dm_sensitivity_to_curve() {
+        let mut curve = curve_fixture("c");
+        curve.set_ad_order(crate::dual::ADOrder::One).unwrap();
+        let curves = Curves::new(Some(&curve), Some(&curve));
+        let frn = frn_fixture(25.0);
+        let dm = frn.dm(99.5, &curves, false).unwrap();
+        let Number::Dual(d) = &dm else {
+            panic!("The margin carries the sensitivity of the curve")
+        };
+        assert!(d.dual().iter().any(|g| g.abs() > 0.0));
+        // the price at the solved margin is the target price
+        let price = frn.price_from_dm(f64::from(&dm), &curves, false).unwrap();
+        assert!((f64::from(&price) - 99.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_frn_instrument() {
+        let curve = curve_fixture("c");
+        let curves = Curves::new(Some(&curve), Some(&curve));
+        let frn = frn_fixture(0.0);
+        // the curve implied clean price is the discounted cashflows less the accrued of the
+        // fixed first coupon
+        let price = f64::from(&frn.rate(&curves).unwrap());
+        let df = f64::from(curve.df(&ndt(2024, 1, 1)));
+        let npv = f64::from(&frn.npv(&curves).unwrap());
+        assert!((price - (npv / df - 2.5 * 47.0 / 365.0)).abs() < 1e-10);
+        assert_eq!(frn.cashflows(&curves).unwrap().len(), 14);
+    }
+}
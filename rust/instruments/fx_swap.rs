@@ -0,0 +1,259 @@
+use crate::curves::PricingCurve;
+use crate::dual::{Gradient1, Number, Vars};
+use crate::error::RateslibError;
+use crate::fx::rates::{FXPair, FXRate};
+use chrono::NaiveDateTime;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+/// An FX swap exchanging a `notional` of the base currency of a `pair` for the quote currency
+/// at a `near_rate` on the `near` date, and back at the near rate plus forward `points` on the
+/// `far` date.
+///
+/// A positive notional receives the base currency at the near date and pays it at the far
+/// date. Points are quoted in pips of the rate, by default `0.0001`, and values are in the
+/// quote currency.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FxSwap {
+    pub(crate) pair: FXPair,
+    pub(crate) notional: f64,
+    pub(crate) near: NaiveDateTime,
+    pub(crate) far: NaiveDateTime,
+    pub(crate) near_rate: f64,
+    pub(crate) points: f64,
+    pub(crate) pip: f64,
+}
+
+/// The sensitivities of the value of an [FxSwap] split between the spot rate and the variables
+/// of the discount curves of each currency.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FxSwapDelta {
+    /// The change in value for a unit increase in the spot rate.
+    pub spot: f64,
+    /// The sensitivities to the variables of the base currency curve.
+    pub base_curve: IndexMap<String, f64>,
+    /// The sensitivities to the variables of the quote currency curve.
+    pub quote_curve: IndexMap<String, f64>,
+}
+
+impl FxSwap {
+    pub fn try_new(
+        pair: FXPair,
+        notional: f64,
+        near: NaiveDateTime,
+        far: NaiveDateTime,
+        near_rate: f64,
+        points: f64,
+    ) -> Result<Self, RateslibError> {
+        if far <= near {
+            return Err(RateslibError::value(
+                "The `far` date of an `FxSwap` must be after its `near` date.",
+            ));
+        }
+        Ok(Self {
+            pair,
+            notional,
+            near,
+            far,
+            near_rate,
+            points,
+            pip: 0.0001,
+        })
+    }
+
+    /// Return the swap with points quoted in a `pip` of the rate, such as `0.01` for JPY.
+    pub fn with_pip(mut self, pip: f64) -> Self {
+        self.pip = pip;
+        self
+    }
+
+    pub fn pair(&self) -> FXPair {
+        self.pair
+    }
+
+    pub fn points(&self) -> f64 {
+        self.points
+    }
+
+    /// Return the outright forward rate at a `date` implied by the `spot` rate and the
+    /// discount curves of the base and quote currencies.
+    pub fn forward(
+        &self,
+        spot: &FXRate,
+        base_curve: &dyn PricingCurve,
+        quote_curve: &dyn PricingCurve,
+        date: &NaiveDateTime,
+    ) -> Result<Number, RateslibError> {
+        if spot.pair != self.pair {
+            return Err(RateslibError::value(format!(
+                "The `spot` rate of an `FxSwap` must be for '{}'.",
+                self.pair
+            )));
+        }
+        let s = spot.settlement.ok_or_else(|| {
+            RateslibError::value("The `spot` rate of an `FxSwap` requires a `settlement` date.")
+        })?;
+        Ok(&spot.rate * (base_curve.df(date) / base_curve.df(&s))
+            / (quote_curve.df(date) / quote_curve.df(&s)))
+    }
+
+    /// Return the value of the swap, in the quote currency.
+    pub fn npv(
+        &self,
+        spot: &FXRate,
+        base_curve: &dyn PricingCurve,
+        quote_curve: &dyn PricingCurve,
+    ) -> Result<Number, RateslibError> {
+        let far_rate = self.near_rate + self.points * self.pip;
+        let leg = |date: &NaiveDateTime, rate: f64| -> Result<Number, RateslibError> {
+            Ok((self.forward(spot, base_curve, quote_curve, date)? - rate) * quote_curve.df(date))
+        };
+        Ok((leg(&self.near, self.near_rate)? - leg(&self.far, far_rate)?) * self.notional)
+    }
+
+    /// Return the points for which the value of the swap at its near rate is zero.
+    pub fn par_points(
+        &self,
+        spot: &FXRate,
+        base_curve: &dyn PricingCurve,
+        quote_curve: &dyn PricingCurve,
+    ) -> Result<Number, RateslibError> {
+        let near = self.forward(spot, base_curve, quote_curve, &self.near)?;
+        let far = self.forward(spot, base_curve, quote_curve, &self.far)?;
+        let (df_near, df_far) = (quote_curve.df(&self.near), quote_curve.df(&self.far));
+        let par_far = (far * df_far.clone() - (near - self.near_rate) * df_near) / df_far;
+        Ok((par_far - self.near_rate) / self.pip)
+    }
+
+    /// Return the [FxSwapDelta] of the value of the swap, for a `spot` rate and curves with
+    /// first order AD.
+    pub fn delta(
+        &self,
+        spot: &FXRate,
+        base_curve: &dyn PricingCurve,
+        quote_curve: &dyn PricingCurve,
+    ) -> Result<FxSwapDelta, RateslibError> {
+        let npv = match self.npv(spot, base_curve, quote_curve)? {
+            Number::Dual(d) => d,
+            _ => {
+                return Err(RateslibError::value(
+                    "The delta of an `FxSwap` requires a `spot` rate and curves with AD.",
+                ))
+            }
+        };
+        let spot_vars: Vec<String> = match &spot.rate {
+            Number::Dual(d) => d.vars().iter().cloned().collect(),
+            _ => vec![],
+        };
+        // the index of a variable of a curve with identifier `id`
+        let node = |var: &str, id: &str| var.strip_prefix(id)?.parse::<usize>().ok();
+        let gradient = npv.gradient1(npv.vars().iter().cloned().collect());
+        let mut delta = FxSwapDelta {
+            spot: 0.0,
+            base_curve: IndexMap::new(),
+            quote_curve: IndexMap::new(),
+        };
+        for (var, g) in npv.vars().iter().zip(gradient.iter()) {
+            if spot_vars.contains(var) {
+                delta.spot += g;
+            } else if node(var, base_curve.id()).is_some() {
+                delta.base_curve.insert(var.clone(), *g);
+            } else if node(var, quote_curve.id()).is_some() {
+                delta.quote_curve.insert(var.clone(), *g);
+            }
+        }
+        delta
+            .base_curve
+            .sort_by_cached_key(|k, _| node(k, base_curve.id()));
+        delta
+            .quote_curve
+            .sort_by_cached_key(|k, _| node(k, quote_curve.id()));
+        Ok(delta)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::{ndt, Convention, Modifier, NamedCal};
+    use crate::curves::{CurveDF, LogLinearInterpolator, Nodes};
+    use crate::dual::{ADOrder, Dual};
+    use crate::periods::period::tests::curve_fixture;
+
+    fn fx_swap(points: f64) -> FxSwap {
+        FxSwap::try_new(
+            FXPair::try_new("eur", "usd").unwrap(),
+            1e6,
+            ndt(2024, 1, 3),
+            ndt(2024, 7, 3),
+            1.10,
+            points,
+        )
+        .unwrap()
+    }
+
+    fn spot() -> FXRate {
+        let rate = Number::Dual(Dual::new(1.10, vec!["fx_eurusd".to_string()]));
+        FXRate::try_new("eur", "usd", rate, Some(ndt(2024, 1, 3))).unwrap()
+    }
+
+    #[test]
+    fn test_par_points() {
+        // a higher usd rate implies positive forward points
+        let eur = curve_fixture("eur");
+        let usd = CurveDF::try_new(
+            Nodes::F64(IndexMap::from_iter([
+                (ndt(2024, 1, 1), 1.0),
+                (ndt(2034, 1, 1), 0.6),
+            ])),
+            LogLinearInterpolator::new(),
+            "usd",
+            Convention::Act365F,
+            Modifier::ModF,
+            None,
+            NamedCal::try_new("all").unwrap(),
+        )
+        .unwrap();
+        let swap = fx_swap(0.0);
+        let points = f64::from(&swap.par_points(&spot(), &eur, &usd).unwrap());
+        let forward = swap.forward(&spot(), &eur, &usd, &ndt(2024, 7, 3)).unwrap();
+        // at a near rate of spot the par points are the forward less spot
+        assert!(points > 0.0);
+        assert!((points - (f64::from(&forward) - 1.10) * 1e4).abs() < 1e-9);
+        let par = fx_swap(points);
+        assert!(f64::from(&par.npv(&spot(), &eur, &usd).unwrap()).abs() < 1e-6);
+        let jpy = swap.with_pip(0.01).par_points(&spot(), &eur, &usd).unwrap();
+        assert!((f64::from(&jpy) * 100.0 - points).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_delta_split() {
+        let mut eur = curve_fixture("eur");
+        let mut usd = curve_fixture("usd");
+        eur.set_ad_order(ADOrder::One).unwrap();
+        usd.set_ad_order(ADOrder::One).unwrap();
+        let swap = fx_swap(25.0);
+        let delta = swap.delta(&spot(), &eur, &usd).unwrap();
+        assert_eq!(
+            delta.base_curve.keys().collect::<Vec<_>>(),
+            vec!["eur0", "eur1"]
+        );
+        assert_eq!(
+            delta.quote_curve.keys().collect::<Vec<_>>(),
+            vec!["usd0", "usd1"]
+        );
+        // the spot delta is the difference of the base currency legs' present values
+        let df = |d: &NaiveDateTime| f64::from(&eur.df(d)) / f64::from(&eur.df(&ndt(2024, 1, 3)));
+        let expected = 1e6
+            * (df(&ndt(2024, 1, 3)) - df(&ndt(2024, 7, 3)))
+            * f64::from(&usd.df(&ndt(2024, 1, 3)));
+        assert!((delta.spot - expected).abs() < 1e-6);
+        // without AD the delta is unavailable
+        let spot = FXRate::try_new("eur", "usd", Number::F64(1.1), Some(ndt(2024, 1, 3))).unwrap();
+        let (eur, usd) = (curve_fixture("eur"), curve_fixture("usd"));
+        assert!(swap.delta(&spot, &eur, &usd).is_err());
+        let wrong = FXRate::try_new("gbp", "usd", Number::F64(1.3), Some(ndt(2024, 1, 3))).unwrap();
+        assert!(swap.npv(&wrong, &eur, &usd).is_err());
+        assert!(FxSwap::try_new(swap.pair, 1.0, swap.far, swap.near, 1.1, 0.0).is_err());
+    }
+}
@@ -0,0 +1,198 @@
+use crate::calendars::{get_imm, Convention, DateRoll, Modifier, RollDay};
+use crate::error::RateslibError;
+use crate::instruments::Irs;
+use crate::scheduling::{Frequency, Schedule};
+use chrono::prelude::*;
+
+/// Return the next `n` quarterly IMM dates, in March, June, September and December, strictly
+/// after `date`.
+pub fn imm_dates(date: &NaiveDateTime, n: usize) -> Vec<NaiveDateTime> {
+    let (mut year, mut month) = (date.year(), date.month().div_ceil(3) * 3);
+    let mut dates = Vec::with_capacity(n);
+    while dates.len() < n {
+        let imm = get_imm(year, month);
+        if imm > *date {
+            dates.push(imm);
+        }
+        month += 3;
+        if month > 12 {
+            (year, month) = (year + 1, 3);
+        }
+    }
+    dates
+}
+
+/// Return par swaps, of zero fixed rate and unit notional, starting on each of the next `n`
+/// IMM dates after `date` with each of the `tenors`, in months, and rolling on IMM dates.
+///
+/// Swaps have a fixed and floating leg with a common `frequency` and `convention`, and dates
+/// adjusted modified following by the `calendar`, and are ordered by start then tenor.
+pub fn imm_swaps<U: DateRoll>(
+    date: &NaiveDateTime,
+    n: usize,
+    tenors: &[u32],
+    frequency: Frequency,
+    convention: Convention,
+    calendar: &U,
+) -> Result<Vec<Irs>, RateslibError> {
+    let roll = RollDay::IMM {};
+    let mut swaps = Vec::with_capacity(n * tenors.len());
+    for start in imm_dates(date, n) {
+        for tenor in tenors.iter() {
+            let end = calendar.add_months(&start, *tenor as i32, &Modifier::Act, &roll, false)?;
+            let schedule =
+                Schedule::try_new(start, end, frequency, roll, Modifier::ModF, calendar, 0)?;
+            swaps.push(Irs::try_new(
+                schedule, 0.0, 1.0, convention, convention, 0.0, calendar,
+            )?);
+        }
+    }
+    Ok(swaps)
+}
+
+/// Return single period OIS, of zero fixed rate and unit notional, between each pair of
+/// consecutive central bank `meetings`, such as MPC or FOMC meeting dates.
+///
+/// Each swap accrues from the date on which the policy rate set at a meeting becomes
+/// effective, `effective_lag` business days after the meeting under the `calendar`, until
+/// the effective date of the policy rate set at the next meeting.
+pub fn meeting_swaps<U: DateRoll>(
+    meetings: &[NaiveDateTime],
+    effective_lag: i8,
+    convention: Convention,
+    calendar: &U,
+) -> Result<Vec<Irs>, RateslibError> {
+    if meetings.windows(2).any(|w| w[0] >= w[1]) {
+        return Err(RateslibError::value(
+            "`meetings` must be in strictly increasing order.",
+        ));
+    }
+    let effective: Vec<NaiveDateTime> = meetings
+        .iter()
+        .map(|d| calendar.lag(d, effective_lag, false))
+        .collect();
+    effective
+        .windows(2)
+        .map(|w| {
+            let schedule = Schedule::try_new(
+                w[0],
+                w[1],
+                Frequency::Zero {},
+                RollDay::Unspecified {},
+                Modifier::Act,
+                calendar,
+                0,
+            )?;
+            Irs::try_new(schedule, 0.0, 1.0, convention, convention, 0.0, calendar)
+        })
+        .collect()
+}
+
+/// Return forward rate agreements, as single period swaps of zero fixed rate and unit
+/// notional, starting a number of `starts`, in months, after the `effective` date with a
+/// common `tenor`, in months, such as the 1x4, 2x5 and 3x6 FRAs for `starts` of 1, 2 and 3 and
+/// a `tenor` of 3.
+///
+/// Dates are adjusted modified following by the `calendar`.
+pub fn fra_strip<U: DateRoll>(
+    effective: &NaiveDateTime,
+    starts: &[u32],
+    tenor: u32,
+    convention: Convention,
+    calendar: &U,
+) -> Result<Vec<Irs>, RateslibError> {
+    if tenor == 0 {
+        return Err(RateslibError::value(
+            "The `tenor` of a FRA must be positive.",
+        ));
+    }
+    let roll = RollDay::Unspecified {};
+    starts
+        .iter()
+        .map(|start| {
+            let start =
+                calendar.add_months(effective, *start as i32, &Modifier::ModF, &roll, false)?;
+            let end = calendar.add_months(&start, tenor as i32, &Modifier::Act, &roll, false)?;
+            let schedule = Schedule::try_new(
+                start,
+                end,
+                Frequency::Zero {},
+                roll,
+                Modifier::ModF,
+                calendar,
+                0,
+            )?;
+            Irs::try_new(schedule, 0.0, 1.0, convention, convention, 0.0, calendar)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::{ndt, NamedCal};
+    use crate::curves::PricingCurve;
+    use crate::instruments::Instrument;
+    use crate::periods::period::tests::curve_fixture;
+    use crate::periods::Curves;
+
+    #[test]
+    fn test_imm_dates() {
+        assert_eq!(
+            imm_dates(&ndt(2024, 3, 20), 3),
+            vec![ndt(2024, 6, 19), ndt(2024, 9, 18), ndt(2024, 12, 18)]
+        );
+        assert_eq!(imm_dates(&ndt(2024, 3, 19), 1), vec![ndt(2024, 3, 20)]);
+        assert_eq!(imm_dates(&ndt(2024, 11, 1), 2)[1], ndt(2025, 3, 19));
+    }
+
+    #[test]
+    fn test_imm_swaps() {
+        let cal = NamedCal::try_new("tgt").unwrap();
+        let swaps = imm_swaps(
+            &ndt(2024, 1, 1),
+            4,
+            &[12, 24],
+            Frequency::Months { number: 3 },
+            Convention::Act360,
+            &cal,
+        )
+        .unwrap();
+        assert_eq!(swaps.len(), 8);
+        let schedule = swaps[3].leg1().schedule();
+        assert_eq!(schedule.uschedule()[0], ndt(2024, 6, 19));
+        // every period is a full IMM quarter
+        assert!(schedule
+            .uschedule()
+            .iter()
+            .all(|d| *d == get_imm(d.year(), d.month())));
+        assert_eq!(schedule.termination(), ndt(2026, 6, 17));
+    }
+
+    #[test]
+    fn test_meeting_swaps() {
+        let cal = NamedCal::try_new("all").unwrap();
+        let meetings = [ndt(2024, 1, 31), ndt(2024, 3, 20), ndt(2024, 5, 1)];
+        let swaps = meeting_swaps(&meetings, 1, Convention::Act360, &cal).unwrap();
+        assert_eq!(swaps.len(), 2);
+        assert_eq!(
+            swaps[1].leg1().schedule().aschedule(),
+            &[ndt(2024, 3, 21), ndt(2024, 5, 2)]
+        );
+        assert!(meeting_swaps(&[meetings[1], meetings[0]], 1, Convention::Act360, &cal).is_err());
+    }
+
+    #[test]
+    fn test_fra_strip() {
+        let cal = NamedCal::try_new("all").unwrap();
+        let fras = fra_strip(&ndt(2024, 1, 1), &[1, 2, 3], 3, Convention::Act365F, &cal).unwrap();
+        assert_eq!(fras.len(), 3);
+        // the rate of a FRA is the forward rate over its period
+        let curve = curve_fixture("c");
+        let curves = Curves::new(Some(&curve), Some(&curve));
+        let forward = curve.rate(&ndt(2024, 3, 1), &ndt(2024, 6, 1)).unwrap();
+        let rate = fras[1].rate(&curves).unwrap();
+        assert!((f64::from(&rate) - f64::from(&forward)).abs() < 1e-12);
+        assert!(fra_strip(&ndt(2024, 1, 1), &[1], 0, Convention::Act365F, &cal).is_err());
+    }
+}
@@ -0,0 +1,96 @@
+use crate::curves::{PricingCurve, RollMethod, RolledCurve};
+use crate::dual::Number;
+use crate::error::RateslibError;
+use crate::instruments::Instrument;
+use crate::periods::{Curves, Period, PeriodType};
+use chrono::NaiveDateTime;
+
+/// Return the initial date of the discounting curve, validating it is before the `horizon`.
+pub(crate) fn initial_date(
+    curves: &Curves,
+    horizon: &NaiveDateTime,
+) -> Result<NaiveDateTime, RateslibError> {
+    let initial = curves.discounting()?.initial_date();
+    if *horizon <= initial {
+        return Err(RateslibError::value(
+            "`horizon` must be after the initial date of the discounting curve.",
+        ));
+    }
+    Ok(initial)
+}
+
+/// Return the cashflows of the accruing `periods` accrued, pro rata by days, between the
+/// initial date of the discounting curve and the `horizon`.
+pub(crate) fn accrual(
+    periods: &[PeriodType],
+    curves: &Curves,
+    horizon: &NaiveDateTime,
+) -> Result<Number, RateslibError> {
+    let initial = initial_date(curves, horizon)?;
+    let mut total = Number::F64(0.0);
+    for period in periods.iter() {
+        let Some(base) = period.base() else {
+            continue;
+        };
+        let (start, end) = (base.start().max(initial), base.end().min(*horizon));
+        if end > start {
+            let fraction =
+                (end - start).num_days() as f64 / (base.end() - base.start()).num_days() as f64;
+            total = total + period.cashflow(curves)? * fraction;
+        }
+    }
+    Ok(total)
+}
+
+/// Set the fixing of each floating period which fixes before the `horizon` to its current
+/// forecast, so that the fixing is retained when the curves are rolled.
+pub(crate) fn fix_periods(
+    periods: &mut [PeriodType],
+    curves: &Curves,
+    horizon: &NaiveDateTime,
+) -> Result<(), RateslibError> {
+    for period in periods.iter_mut() {
+        if let PeriodType::Float(p) = period {
+            if p.fixing.is_none() && p.base.start < *horizon {
+                p.fixing = Some(f64::from(
+                    &curves.forecasting()?.rate(&p.base.start, &p.base.end)?,
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Return the P&L of an `instrument` to the `horizon` under unchanged curves: its NPV on the
+/// rolled curves at the `horizon`, plus the cashflows paid before it, less its current NPV.
+///
+/// The `fixed` instrument is the `instrument` with fixings set by [fix_periods]. Values are
+/// not discounted between the initial date and the `horizon`.
+pub(crate) fn static_pnl(
+    instrument: &dyn Instrument,
+    fixed: &dyn Instrument,
+    curves: &Curves,
+    horizon: &NaiveDateTime,
+) -> Result<Number, RateslibError> {
+    let initial = initial_date(curves, horizon)?;
+    fn roll<'a>(
+        curve: Option<&'a dyn PricingCurve>,
+        horizon: &NaiveDateTime,
+    ) -> Result<Option<RolledCurve<'a>>, RateslibError> {
+        curve
+            .map(|c| RolledCurve::try_new(c, *horizon, RollMethod::Unchanged))
+            .transpose()
+    }
+    let forecasting = roll(curves.forecasting, horizon)?;
+    let discounting = roll(curves.discounting, horizon)?;
+    let rolled = Curves::new(
+        forecasting.as_ref().map(|c| c as &dyn PricingCurve),
+        discounting.as_ref().map(|c| c as &dyn PricingCurve),
+    );
+    let paid = instrument
+        .cashflows(curves)?
+        .into_iter()
+        .filter(|(date, _)| initial < *date && date < horizon)
+        .fold(Number::F64(0.0), |acc, (_, cf)| acc + cf);
+    Ok(fixed.npv(&rolled)? + paid - instrument.npv(curves)?)
+}
@@ -0,0 +1,502 @@
+use crate::cancel;
+use crate::curves::with_curve_cache;
+use crate::dual::{with_buffer_pool, Gradient1, Gradient2, Number, Reduction};
+use crate::error::RateslibError;
+use crate::fx::rates::{Cashflow, Ccy, Settlement, SettlementRounding};
+use crate::periods::Curves;
+use crate::progress::{self, ProgressEvent, ProgressStage};
+use crate::trace::{event, Level, Span};
+use chrono::NaiveDateTime;
+use indexmap::IndexMap;
+use ndarray::{Array1, Array2};
+use num_traits::Zero;
+
+/// Valuation of a financial instrument against a set of [Curves].
+///
+/// Instruments are `Send` and `Sync` so that a [Portfolio] may be distributed across threads
+/// for pricing.
+pub trait Instrument: Send + Sync {
+    /// Return the NPV of the instrument.
+    fn npv(&self, curves: &Curves) -> Result<Number, RateslibError>;
+
+    /// Return the mid-market rate of the instrument, which is the value targeted when the
+    /// instrument is used for calibration.
+    fn rate(&self, curves: &Curves) -> Result<Number, RateslibError>;
+
+    /// Return the payment date and cashflow of each period of the instrument.
+    fn cashflows(&self, curves: &Curves) -> Result<Vec<(NaiveDateTime, Number)>, RateslibError>;
+
+    /// Return the change in NPV of the instrument for a 1bp increase in its rate.
+    fn analytic_delta(&self, curves: &Curves) -> Result<Number, RateslibError>;
+}
+
+/// The metadata of an instrument held in a [Portfolio], by which its values may be aggregated.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct InstrumentTags {
+    pub book: Option<String>,
+    pub counterparty: Option<String>,
+    pub currency: Option<String>,
+}
+
+impl InstrumentTags {
+    pub fn new(book: Option<&str>, counterparty: Option<&str>, currency: Option<&str>) -> Self {
+        Self {
+            book: book.map(str::to_string),
+            counterparty: counterparty.map(str::to_string),
+            currency: currency.map(|c| c.to_lowercase()),
+        }
+    }
+
+    /// Return the value of the tag `key`, if any.
+    pub fn get(&self, key: TagKey) -> Option<&str> {
+        match key {
+            TagKey::Book => self.book.as_deref(),
+            TagKey::Counterparty => self.counterparty.as_deref(),
+            TagKey::Currency => self.currency.as_deref(),
+        }
+    }
+}
+
+/// A tag of [InstrumentTags] by which a [Portfolio] is aggregated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TagKey {
+    Book,
+    Counterparty,
+    Currency,
+}
+
+impl TagKey {
+    pub fn try_new(key: &str) -> Result<Self, RateslibError> {
+        match key.to_lowercase().as_str() {
+            "book" => Ok(TagKey::Book),
+            "counterparty" => Ok(TagKey::Counterparty),
+            "currency" => Ok(TagKey::Currency),
+            _ => Err(RateslibError::value(format!(
+                "Tag '{}' must be one of 'book', 'counterparty' or 'currency'.",
+                key
+            ))),
+        }
+    }
+}
+
+/// The aggregated values of the instruments of a [Portfolio] sharing the same tags.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagAggregate {
+    /// The values of the aggregated tags, in order, with `None` for untagged instruments.
+    pub tags: Vec<Option<String>>,
+    /// The number of instruments aggregated.
+    pub count: usize,
+    /// The netted NPV of the instruments.
+    pub npv: f64,
+    /// The netted first derivatives of the NPV with respect to each variable.
+    pub delta: Array1<f64>,
+    /// The netted second derivatives of the NPV with respect to each pair of variables, which
+    /// are zero unless the curves carry second order AD.
+    pub gamma: Array2<f64>,
+}
+
+/// A collection of instruments of arbitrary types, valued against a common set of curves.
+///
+/// Each instrument is held with [InstrumentTags], by default empty, by which the values of the
+/// portfolio may be [aggregated](Portfolio::aggregate).
+#[derive(Default)]
+pub struct Portfolio {
+    pub(crate) instruments: Vec<Box<dyn Instrument>>,
+    pub(crate) tags: Vec<InstrumentTags>,
+}
+
+impl Portfolio {
+    pub fn new(instruments: Vec<Box<dyn Instrument>>) -> Self {
+        let tags = vec![InstrumentTags::default(); instruments.len()];
+        Self { instruments, tags }
+    }
+
+    /// Add an instrument to the portfolio.
+    pub fn push(&mut self, instrument: Box<dyn Instrument>) {
+        self.push_tagged(instrument, InstrumentTags::default())
+    }
+
+    /// Add an instrument to the portfolio with its `tags`.
+    pub fn push_tagged(&mut self, instrument: Box<dyn Instrument>, tags: InstrumentTags) {
+        self.instruments.push(instrument);
+        self.tags.push(tags);
+    }
+
+    pub fn instruments(&self) -> &[Box<dyn Instrument>] {
+        &self.instruments
+    }
+
+    pub fn tags(&self) -> &[InstrumentTags] {
+        &self.tags
+    }
+
+    pub fn len(&self) -> usize {
+        self.instruments.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.instruments.is_empty()
+    }
+
+    /// Return the NPV of the portfolio as the sum of the NPVs of its instruments.
+    pub fn npv(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        let _span = Span::enter(Level::Debug, "instruments", || {
+            format!("npv{{instruments: {}}}", self.instruments.len())
+        });
+        pooled(|| {
+            self.instruments
+                .iter()
+                .enumerate()
+                .try_fold(Number::zero(), |acc, (k, i)| {
+                    cancel::check()?;
+                    let acc = acc.try_add(&i.npv(curves)?)?;
+                    self.report(k)?;
+                    Ok(acc)
+                })
+        })
+    }
+
+    /// Return the NPV of the portfolio as the sum of the NPVs of its instruments, accumulated
+    /// in the order of the `reduction`.
+    pub fn npv_reduced(
+        &self,
+        curves: &Curves,
+        reduction: Reduction,
+    ) -> Result<Number, RateslibError> {
+        reduction.sum(&self.npvs(curves)?)
+    }
+
+    /// Return the NPV of each instrument.
+    pub fn npvs(&self, curves: &Curves) -> Result<Vec<Number>, RateslibError> {
+        let _span = Span::enter(Level::Debug, "instruments", || {
+            format!("npvs{{instruments: {}}}", self.instruments.len())
+        });
+        pooled(|| {
+            self.instruments
+                .iter()
+                .enumerate()
+                .map(|(k, i)| {
+                    cancel::check()?;
+                    let npv = i.npv(curves)?;
+                    self.report(k)?;
+                    Ok(npv)
+                })
+                .collect()
+        })
+    }
+
+    /// Return the mid-market rate of each instrument, for use by a
+    /// [SolverSystem](crate::solver::SolverSystem) calibrating to the portfolio.
+    pub fn rates(&self, curves: &Curves) -> Result<Vec<Number>, RateslibError> {
+        let _span = Span::enter(Level::Debug, "instruments", || {
+            format!("rates{{instruments: {}}}", self.instruments.len())
+        });
+        pooled(|| {
+            self.instruments
+                .iter()
+                .enumerate()
+                .map(|(k, i)| {
+                    cancel::check()?;
+                    let rate = i.rate(curves)?;
+                    self.report(k)?;
+                    Ok(rate)
+                })
+                .collect()
+        })
+    }
+
+    /// Return the payment date and cashflow of every period of every instrument.
+    pub fn cashflows(
+        &self,
+        curves: &Curves,
+    ) -> Result<Vec<(NaiveDateTime, Number)>, RateslibError> {
+        let mut cashflows = Vec::new();
+        for (k, i) in self.instruments.iter().enumerate() {
+            cancel::check()?;
+            cashflows.extend(i.cashflows(curves)?);
+            self.report(k)?;
+        }
+        Ok(cashflows)
+    }
+
+    /// Return the cashflows of every instrument rounded to settlement amounts by `rounding`
+    /// and netted by payment date and currency.
+    ///
+    /// The cashflows of an instrument are in the currency of its tags, or `ccy` if untagged.
+    pub fn settlements(
+        &self,
+        curves: &Curves,
+        ccy: Ccy,
+        rounding: &SettlementRounding,
+    ) -> Result<Vec<Settlement>, RateslibError> {
+        let mut cashflows = Vec::new();
+        for (k, (i, tags)) in self.instruments.iter().zip(self.tags.iter()).enumerate() {
+            cancel::check()?;
+            let ccy = match &tags.currency {
+                Some(c) => Ccy::try_new(c)?,
+                None => ccy,
+            };
+            cashflows.extend(
+                i.cashflows(curves)?
+                    .into_iter()
+                    .map(|(payment, amount)| Cashflow::new(amount, ccy, payment)),
+            );
+            self.report(k)?;
+        }
+        rounding.settle(&cashflows)
+    }
+
+    /// Return the analytic delta of the portfolio as the sum of those of its instruments.
+    pub fn analytic_delta(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        self.instruments
+            .iter()
+            .enumerate()
+            .try_fold(Number::zero(), |acc, (k, i)| {
+                cancel::check()?;
+                let acc = acc.try_add(&i.analytic_delta(curves)?)?;
+                self.report(k)?;
+                Ok(acc)
+            })
+    }
+
+    /// Report the valuation of the `k`th instrument.
+    fn report(&self, k: usize) -> Result<(), RateslibError> {
+        progress::report(ProgressEvent::new(
+            ProgressStage::Valuation,
+            k + 1,
+            self.instruments.len(),
+        ))
+    }
+
+    /// Return the NPV, delta and gamma of the instruments netted within each group of equal
+    /// values of the tags `by`, in order of the first instrument of each group.
+    ///
+    /// Delta and gamma are measured with respect to the `vars` of the curves.
+    pub fn aggregate(
+        &self,
+        curves: &Curves,
+        by: &[TagKey],
+        vars: &[String],
+    ) -> Result<Vec<TagAggregate>, RateslibError> {
+        let npvs = self.npvs(curves)?;
+        let mut groups: IndexMap<Vec<Option<String>>, (usize, Number)> = IndexMap::new();
+        for (tags, npv) in self.tags.iter().zip(npvs) {
+            let key = by
+                .iter()
+                .map(|k| tags.get(*k).map(str::to_string))
+                .collect();
+            let group = groups.entry(key).or_insert((0, Number::zero()));
+            group.0 += 1;
+            group.1 = group.1.try_add(&npv)?;
+        }
+        let n = vars.len();
+        Ok(groups
+            .into_iter()
+            .map(|(tags, (count, npv))| {
+                let (delta, gamma) = match &npv {
+                    Number::F64(_) => (Array1::zeros(n), Array2::zeros((n, n))),
+                    Number::Dual(d) => (d.gradient1(vars.to_vec()), Array2::zeros((n, n))),
+                    Number::Dual2(d) => (d.gradient1(vars.to_vec()), d.gradient2(vars.to_vec())),
+                };
+                TagAggregate {
+                    tags,
+                    count,
+                    npv: f64::from(&npv),
+                    delta,
+                    gamma,
+                }
+            })
+            .collect())
+    }
+}
+
+/// Evaluate a pricing call with a buffer pool for the intermediate gradients of its dual numbers,
+/// and a cache of the discount factors of its curves.
+fn pooled<R>(f: impl FnOnce() -> R) -> R {
+    let ((result, cache), stats) = with_buffer_pool(|| with_curve_cache(f));
+    event(Level::Trace, "instruments", || {
+        format!(
+            "buffer pool: {} allocations, {} reuses; curve cache: {} misses, {} hits",
+            stats.allocations, stats.reuses, cache.misses, cache.hits
+        )
+    });
+    result
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::calendars::{ndt, Convention, Modifier, NamedCal, RollDay};
+    use crate::instruments::Irs;
+    use crate::periods::period::tests::{curve_fixture, is_close};
+    use crate::scheduling::{Frequency, Schedule};
+
+    pub(crate) fn irs_fixture(fixed_rate: f64) -> Irs {
+        let cal = NamedCal::try_new("all").unwrap();
+        let schedule = Schedule::try_new(
+            ndt(2024, 1, 1),
+            ndt(2026, 1, 1),
+            Frequency::Months { number: 12 },
+            RollDay::Unspecified {},
+            Modifier::Act,
+            &cal,
+            0,
+        )
+        .unwrap();
+        Irs::try_new(
+            schedule,
+            fixed_rate,
+            1e6,
+            Convention::Act365F,
+            Convention::Act365F,
+            0.0,
+            &cal,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_portfolio_sums() {
+        let curve = curve_fixture("c");
+        let curves = Curves::new(Some(&curve), Some(&curve));
+        let (a, b) = (irs_fixture(1.0), irs_fixture(3.0));
+        let expected = f64::from(&a.npv(&curves).unwrap()) + f64::from(&b.npv(&curves).unwrap());
+        let portfolio = Portfolio::new(vec![Box::new(a), Box::new(b)]);
+        assert_eq!(portfolio.len(), 2);
+        assert!(is_close(&portfolio.npv(&curves).unwrap(), expected));
+        let pairwise = portfolio.npv_reduced(&curves, Reduction::Pairwise).unwrap();
+        assert!(is_close(&pairwise, expected));
+        assert_eq!(portfolio.rates(&curves).unwrap().len(), 2);
+        assert_eq!(portfolio.cashflows(&curves).unwrap().len(), 8);
+    }
+
+    #[test]
+    fn test_portfolio_settlements() {
+        let curve = curve_fixture("c");
+        let curves = Curves::new(Some(&curve), Some(&curve));
+        let mut portfolio = Portfolio::new(vec![Box::new(irs_fixture(1.0))]);
+        let tags = InstrumentTags::new(None, None, Some("jpy"));
+        portfolio.push_tagged(Box::new(irs_fixture(3.0)), tags);
+        let usd = Ccy::try_new("usd").unwrap();
+        let settlements = portfolio
+            .settlements(&curves, usd, &SettlementRounding::default())
+            .unwrap();
+        // each instrument pays on two dates in its own currency
+        assert_eq!(settlements.len(), 4);
+        for s in settlements.iter() {
+            let scale = if s.ccy == usd { 2 } else { 0 };
+            assert_eq!(s.amount.scale(), scale);
+        }
+    }
+
+    #[test]
+    fn test_aggregate_by_tags() {
+        let mut curve = curve_fixture("c");
+        curve.set_ad_order(crate::dual::ADOrder::Two).unwrap();
+        let curves = Curves::new(Some(&curve), Some(&curve));
+        let mut portfolio = Portfolio::default();
+        for (rate, book, cpty) in [(1.0, "a", "x"), (3.0, "a", "y"), (2.5, "b", "x")] {
+            let tags = InstrumentTags::new(Some(book), Some(cpty), Some("USD"));
+            portfolio.push_tagged(Box::new(irs_fixture(rate)), tags);
+        }
+        portfolio.push(Box::new(irs_fixture(2.0)));
+        let vars = vec!["c0".to_string(), "c1".to_string()];
+        let by_book = portfolio
+            .aggregate(&curves, &[TagKey::Book], &vars)
+            .unwrap();
+        assert_eq!(
+            by_book.iter().map(|a| a.tags.clone()).collect::<Vec<_>>(),
+            vec![
+                vec![Some("a".to_string())],
+                vec![Some("b".to_string())],
+                vec![None]
+            ]
+        );
+        assert_eq!(by_book[0].count, 2);
+        // the netted values of a group are the sums of those of its instruments
+        let npvs = portfolio.npvs(&curves).unwrap();
+        let expected = &npvs[0] + &npvs[1];
+        assert!((by_book[0].npv - f64::from(&expected)).abs() < 1e-9);
+        match expected {
+            Number::Dual2(d) => {
+                assert_eq!(by_book[0].delta, d.gradient1(vars.clone()));
+                assert_eq!(by_book[0].gamma, d.gradient2(vars.clone()));
+            }
+            _ => panic!("the NPV on a second order curve must be `Dual2`"),
+        }
+        let by_currency = portfolio
+            .aggregate(&curves, &[TagKey::Currency, TagKey::Counterparty], &vars)
+            .unwrap();
+        assert_eq!(by_currency.len(), 3);
+        assert_eq!(
+            by_currency[0].tags,
+            vec![Some("usd".to_string()), Some("x".to_string())]
+        );
+        let total: f64 = by_currency.iter().map(|a| a.npv).sum();
+        assert!((total - f64::from(&portfolio.npv(&curves).unwrap())).abs() < 1e-9);
+        assert!(TagKey::try_new("desk").is_err());
+    }
+
+    /// An instrument of a fixed NPV.
+    struct FixedNpv(Number);
+
+    impl Instrument for FixedNpv {
+        fn npv(&self, _curves: &Curves) -> Result<Number, RateslibError> {
+            Ok(self.0.clone())
+        }
+        fn rate(&self, _curves: &Curves) -> Result<Number, RateslibError> {
+            Ok(Number::zero())
+        }
+        fn cashflows(
+            &self,
+            _curves: &Curves,
+        ) -> Result<Vec<(NaiveDateTime, Number)>, RateslibError> {
+            Ok(vec![])
+        }
+        fn analytic_delta(&self, _curves: &Curves) -> Result<Number, RateslibError> {
+            Ok(Number::zero())
+        }
+    }
+
+    #[test]
+    fn test_aggregate_mixed_orders() {
+        let curve = curve_fixture("c");
+        let curves = Curves::new(Some(&curve), Some(&curve));
+        let x = vec!["x".to_string()];
+        let portfolio = Portfolio::new(vec![
+            Box::new(FixedNpv(Number::Dual(crate::dual::Dual::new(
+                1.0,
+                x.clone(),
+            )))),
+            Box::new(FixedNpv(Number::Dual2(crate::dual::Dual2::new(
+                2.0,
+                x.clone(),
+            )))),
+        ]);
+        let err = portfolio.aggregate(&curves, &[], &x).unwrap_err();
+        assert!(matches!(err, RateslibError::MixedDualTypes { .. }));
+    }
+
+    #[test]
+    fn test_pricing_is_traced() {
+        let curve = curve_fixture("traced");
+        let curves = Curves::new(Some(&curve), Some(&curve));
+        let portfolio =
+            Portfolio::new(vec![Box::new(irs_fixture(1.0)), Box::new(irs_fixture(2.0))]);
+        crate::trace::capture(true);
+        crate::trace::set_filter("instruments=trace,curves=trace").unwrap();
+        portfolio.npv(&curves).unwrap();
+        crate::trace::set_filter("off").unwrap();
+        let records = crate::trace::take_records();
+        assert!(records
+            .iter()
+            .any(|r| r.starts_with("DEBUG instruments: npv{instruments: 2} closed in")));
+        // both legs of both swaps measure the same three dates
+        assert!(records
+            .iter()
+            .any(|r| r.contains("curve cache: 3 misses, 13 hits")));
+        assert!(records
+            .iter()
+            .any(|r| r.starts_with("TRACE curves: interpolated_value{id: traced")));
+    }
+}
@@ -0,0 +1,439 @@
+//! Wrapper module to export the Rust instrument interface to Python using pyo3 bindings.
+
+use crate::cancel::CancellationToken;
+use crate::curves::curve_py::Curve;
+use crate::curves::PricingCurve;
+use crate::dual::{Number, Reduction};
+use crate::error::RateslibError;
+use crate::instruments::{Instrument, InstrumentTags, Portfolio, TagKey};
+use crate::periods::Curves;
+use crate::progress::progress_py::release;
+use chrono::NaiveDateTime;
+use pyo3::exceptions::{PyNotImplementedError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+/// Base class for instruments defined in Python.
+///
+/// Subclasses implement `npv`, `rate`, `cashflows` and `analytic_delta`, each of which is
+/// passed a `CurvesView` over the curves the instrument is being valued against.
+#[pyclass(module = "rateslib.rs", name = "Instrument", subclass)]
+pub(crate) struct PyInstrumentBase {}
+
+#[pymethods]
+impl PyInstrumentBase {
+    #[new]
+    fn new_py() -> Self {
+        PyInstrumentBase {}
+    }
+
+    fn npv(&self, _curves: &Bound<'_, CurvesView>) -> PyResult<Number> {
+        Err(PyNotImplementedError::new_err(
+            "`npv` must be implemented by a subclass.",
+        ))
+    }
+
+    fn rate(&self, _curves: &Bound<'_, CurvesView>) -> PyResult<Number> {
+        Err(PyNotImplementedError::new_err(
+            "`rate` must be implemented by a subclass.",
+        ))
+    }
+
+    fn cashflows(&self, _curves: &Bound<'_, CurvesView>) -> PyResult<Vec<(NaiveDateTime, Number)>> {
+        Err(PyNotImplementedError::new_err(
+            "`cashflows` must be implemented by a subclass.",
+        ))
+    }
+
+    fn analytic_delta(&self, _curves: &Bound<'_, CurvesView>) -> PyResult<Number> {
+        Err(PyNotImplementedError::new_err(
+            "`analytic_delta` must be implemented by a subclass.",
+        ))
+    }
+}
+
+type CurvePtr = *const (dyn PricingCurve + 'static);
+
+/// A view of the [Curves] an instrument defined in Python is being valued against.
+///
+/// The view is only valid for the duration of the call into Python in which it is passed.
+/// Retaining and using it afterwards raises.
+#[pyclass(module = "rateslib.rs", unsendable)]
+pub(crate) struct CurvesView {
+    forecasting: Option<CurvePtr>,
+    discounting: Option<CurvePtr>,
+    valid: bool,
+}
+
+impl CurvesView {
+    fn new(curves: &Curves) -> Self {
+        // SAFETY: the lifetime of the curves is erased so that they may be referenced from
+        // Python. The view is invalidated by `with_view` before the borrow ends and every
+        // dereference checks `valid`.
+        let erase = |c: &dyn PricingCurve| -> CurvePtr {
+            unsafe { std::mem::transmute::<&dyn PricingCurve, &'static dyn PricingCurve>(c) }
+        };
+        Self {
+            forecasting: curves.forecasting.map(erase),
+            discounting: curves.discounting.map(erase),
+            valid: true,
+        }
+    }
+
+    fn curve(&self, forecasting: bool) -> PyResult<&dyn PricingCurve> {
+        if !self.valid {
+            return Err(PyValueError::new_err(
+                "`CurvesView` cannot be used outside of the valuation it was passed to.",
+            ));
+        }
+        let (ptr, name) = match forecasting {
+            true => (self.forecasting, "forecasting"),
+            false => (self.discounting, "discounting"),
+        };
+        match ptr {
+            // SAFETY: `valid` guarantees the referenced curves are still borrowed.
+            Some(p) => Ok(unsafe { &*p }),
+            None => Err(PyValueError::new_err(format!(
+                "A `{}` curve is required to value this instrument.",
+                name
+            ))),
+        }
+    }
+}
+
+#[pymethods]
+impl CurvesView {
+    /// Return the discount factor, or survival probability, at a date.
+    #[pyo3(signature = (date, forecasting=false))]
+    fn df(&self, date: NaiveDateTime, forecasting: bool) -> PyResult<Number> {
+        Ok(self.curve(forecasting)?.df(&date))
+    }
+
+    /// Return the simple forward rate, in percent, between two dates.
+    #[pyo3(signature = (start, end, forecasting=true))]
+    fn rate(
+        &self,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+        forecasting: bool,
+    ) -> PyResult<Number> {
+        Ok(self.curve(forecasting)?.rate(&start, &end)?)
+    }
+
+    /// Return the index value at a date.
+    #[pyo3(signature = (date, forecasting=true))]
+    fn index_value(&self, date: NaiveDateTime, forecasting: bool) -> PyResult<Number> {
+        Ok(self.curve(forecasting)?.index_value(&date)?)
+    }
+
+    /// Return the initial node date of a curve.
+    #[pyo3(signature = (forecasting=false))]
+    fn initial_date(&self, forecasting: bool) -> PyResult<NaiveDateTime> {
+        Ok(self.curve(forecasting)?.initial_date())
+    }
+}
+
+/// An instrument defined in Python, as a subclass of `Instrument`, held by Rust.
+pub struct PyInstrument {
+    obj: Py<PyAny>,
+}
+
+impl PyInstrument {
+    /// Wrap a Python object implementing the methods of `Instrument`.
+    pub fn new(obj: Py<PyAny>) -> Self {
+        Self { obj }
+    }
+
+    /// Call a method of the Python object with a view of the `curves`, which is invalidated
+    /// on return.
+    fn call<T: for<'py> FromPyObject<'py>>(&self, method: &str, curves: &Curves) -> PyResult<T> {
+        Python::with_gil(|py| {
+            let view = Py::new(py, CurvesView::new(curves))?;
+            let result = self.obj.call_method1(py, method, (view.clone_ref(py),));
+            view.borrow_mut(py).valid = false;
+            result?.extract(py)
+        })
+    }
+}
+
+impl Instrument for PyInstrument {
+    fn npv(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        Ok(self.call("npv", curves)?)
+    }
+
+    fn rate(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        Ok(self.call("rate", curves)?)
+    }
+
+    fn cashflows(&self, curves: &Curves) -> Result<Vec<(NaiveDateTime, Number)>, RateslibError> {
+        Ok(self.call("cashflows", curves)?)
+    }
+
+    fn analytic_delta(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        Ok(self.call("analytic_delta", curves)?)
+    }
+}
+
+/// A portfolio of instruments defined in Python, priced by Rust.
+#[pyclass(module = "rateslib.rs", name = "Portfolio")]
+pub(crate) struct PyPortfolio {
+    inner: Portfolio,
+}
+
+#[pymethods]
+impl PyPortfolio {
+    #[new]
+    fn new_py(instruments: Vec<Py<PyAny>>) -> Self {
+        let instruments: Vec<Box<dyn Instrument>> = instruments
+            .into_iter()
+            .map(|i| Box::new(PyInstrument::new(i)) as Box<dyn Instrument>)
+            .collect();
+        PyPortfolio {
+            inner: Portfolio::new(instruments),
+        }
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Add an instrument to the portfolio, optionally tagged with its `book`, `counterparty`
+    /// and `currency`.
+    #[pyo3(signature = (instrument, book=None, counterparty=None, currency=None))]
+    fn push(
+        &mut self,
+        instrument: Py<PyAny>,
+        book: Option<&str>,
+        counterparty: Option<&str>,
+        currency: Option<&str>,
+    ) {
+        self.inner.push_tagged(
+            Box::new(PyInstrument::new(instrument)),
+            InstrumentTags::new(book, counterparty, currency),
+        )
+    }
+
+    #[pyo3(signature = (forecasting=None, discounting=None, cancel=None, progress=None, reduction=None))]
+    fn npv(
+        &self,
+        py: Python<'_>,
+        forecasting: Option<Curve>,
+        discounting: Option<Curve>,
+        cancel: Option<CancellationToken>,
+        progress: Option<Py<PyAny>>,
+        reduction: Option<Reduction>,
+    ) -> PyResult<Number> {
+        release(py, cancel, progress, || {
+            let curves = py_curves(&forecasting, &discounting);
+            match reduction {
+                Some(reduction) => self.inner.npv_reduced(&curves, reduction),
+                None => self.inner.npv(&curves),
+            }
+        })
+    }
+
+    #[pyo3(signature = (forecasting=None, discounting=None, cancel=None, progress=None))]
+    fn npvs(
+        &self,
+        py: Python<'_>,
+        forecasting: Option<Curve>,
+        discounting: Option<Curve>,
+        cancel: Option<CancellationToken>,
+        progress: Option<Py<PyAny>>,
+    ) -> PyResult<Vec<Number>> {
+        release(py, cancel, progress, || {
+            self.inner.npvs(&py_curves(&forecasting, &discounting))
+        })
+    }
+
+    #[pyo3(signature = (forecasting=None, discounting=None, cancel=None, progress=None))]
+    fn rates(
+        &self,
+        py: Python<'_>,
+        forecasting: Option<Curve>,
+        discounting: Option<Curve>,
+        cancel: Option<CancellationToken>,
+        progress: Option<Py<PyAny>>,
+    ) -> PyResult<Vec<Number>> {
+        release(py, cancel, progress, || {
+            self.inner.rates(&py_curves(&forecasting, &discounting))
+        })
+    }
+
+    #[pyo3(signature = (forecasting=None, discounting=None, cancel=None, progress=None))]
+    fn cashflows(
+        &self,
+        py: Python<'_>,
+        forecasting: Option<Curve>,
+        discounting: Option<Curve>,
+        cancel: Option<CancellationToken>,
+        progress: Option<Py<PyAny>>,
+    ) -> PyResult<Vec<(NaiveDateTime, Number)>> {
+        release(py, cancel, progress, || {
+            self.inner.cashflows(&py_curves(&forecasting, &discounting))
+        })
+    }
+
+    #[pyo3(signature = (forecasting=None, discounting=None, cancel=None, progress=None))]
+    fn analytic_delta(
+        &self,
+        py: Python<'_>,
+        forecasting: Option<Curve>,
+        discounting: Option<Curve>,
+        cancel: Option<CancellationToken>,
+        progress: Option<Py<PyAny>>,
+    ) -> PyResult<Number> {
+        release(py, cancel, progress, || {
+            self.inner
+                .analytic_delta(&py_curves(&forecasting, &discounting))
+        })
+    }
+
+    /// Return a record of the netted `npv`, `delta` and `gamma`, with respect to the `vars`,
+    /// and the `count` of instruments for each group of equal values of the tags `by`.
+    #[pyo3(signature = (by, vars, forecasting=None, discounting=None, cancel=None, progress=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn aggregate<'py>(
+        &self,
+        py: Python<'py>,
+        by: Vec<String>,
+        vars: Vec<String>,
+        forecasting: Option<Curve>,
+        discounting: Option<Curve>,
+        cancel: Option<CancellationToken>,
+        progress: Option<Py<PyAny>>,
+    ) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        let keys = by
+            .iter()
+            .map(|k| TagKey::try_new(k))
+            .collect::<Result<Vec<_>, _>>()?;
+        let aggregates = release(py, cancel, progress, || {
+            self.inner
+                .aggregate(&py_curves(&forecasting, &discounting), &keys, &vars)
+        })?;
+        aggregates
+            .into_iter()
+            .map(|a| {
+                let record = PyDict::new_bound(py);
+                for (k, v) in by.iter().zip(a.tags) {
+                    record.set_item(k.to_lowercase(), v)?;
+                }
+                record.set_item("count", a.count)?;
+                record.set_item("npv", a.npv)?;
+                record.set_item("delta", a.delta.to_vec())?;
+                record.set_item(
+                    "gamma",
+                    a.gamma.outer_iter().map(|r| r.to_vec()).collect::<Vec<_>>(),
+                )?;
+                Ok(record)
+            })
+            .collect()
+    }
+}
+
+fn py_curves<'a>(forecasting: &'a Option<Curve>, discounting: &'a Option<Curve>) -> Curves<'a> {
+    Curves::new(
+        forecasting.as_ref().map(|c| c as &dyn PricingCurve),
+        discounting.as_ref().map(|c| c as &dyn PricingCurve),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::ndt;
+    use crate::error::error_py::CancelledError;
+    use crate::periods::period::tests::{curve_fixture, is_close};
+    use crate::progress::ProgressEvent;
+    use pyo3::types::PyDict;
+
+    fn py_instrument(py: Python<'_>, code: &str) -> Py<PyAny> {
+        let globals = PyDict::new_bound(py);
+        globals
+            .set_item("Instrument", py.get_type_bound::<PyInstrumentBase>())
+            .unwrap();
+        py.run_bound(code, Some(&globals), None).unwrap();
+        globals.get_item("obj").unwrap().unwrap().unbind()
+    }
+
+    #[test]
+    fn test_py_instrument_in_portfolio() {
+        pyo3::prepare_freethreaded_python();
+        let obj = Python::with_gil(|py| {
+            py_instrument(
+                py,
+                r#"
+from datetime import datetime
+class ZeroBond(Instrument):
+    def npv(self, curves):
+        return 100.0 * curves.df(datetime(2025, 1, 1))
+    def rate(self, curves):
+        return curves.rate(datetime(2024, 1, 1), datetime(2025, 1, 1))
+    def cashflows(self, curves):
+        return [(datetime(2025, 1, 1), 100.0)]
+obj = ZeroBond()
+"#,
+            )
+        });
+        let curve = curve_fixture("c");
+        let curves = Curves::new(Some(&curve), Some(&curve));
+        let portfolio = Portfolio::new(vec![Box::new(PyInstrument::new(obj))]);
+        let df = (-0.02_f64 * 366.0 / 365.0).exp();
+        assert!(is_close(&portfolio.npv(&curves).unwrap(), 100.0 * df));
+        assert!(is_close(
+            &portfolio.rates(&curves).unwrap()[0],
+            (1.0 / df - 1.0) / (366.0 / 365.0) * 100.0
+        ));
+        assert_eq!(portfolio.cashflows(&curves).unwrap()[0].0, ndt(2025, 1, 1));
+        // methods not overridden by the subclass raise
+        assert!(portfolio.analytic_delta(&curves).is_err());
+        // valuing with the GIL released reacquires it to call the instrument
+        let token = CancellationToken::new();
+        Python::with_gil(|py| {
+            let npv = release(py, Some(token.clone()), None, || {
+                portfolio.npv(&Curves::new(Some(&curve), Some(&curve)))
+            });
+            assert!(is_close(&npv.unwrap(), 100.0 * df));
+            token.cancel();
+            let npv = release(py, Some(token), None, || {
+                portfolio.npv(&Curves::new(Some(&curve), Some(&curve)))
+            });
+            assert!(npv.unwrap_err().is_instance_of::<CancelledError>(py));
+            // a progress callback receives an event for each instrument
+            let events = pyo3::types::PyList::empty_bound(py);
+            let callback = events.getattr("append").unwrap().unbind();
+            let npv = release(py, None, Some(callback), || {
+                portfolio.npvs(&Curves::new(Some(&curve), Some(&curve)))
+            });
+            assert!(npv.is_ok());
+            let event: ProgressEvent = events.get_item(0).unwrap().extract().unwrap();
+            assert_eq!((event.completed, event.total), (1, 1));
+        });
+    }
+
+    #[test]
+    fn test_curves_view_invalidated() {
+        pyo3::prepare_freethreaded_python();
+        let obj = Python::with_gil(|py| {
+            py_instrument(
+                py,
+                r#"
+class Leaky(Instrument):
+    view = None
+    def npv(self, curves):
+        Leaky.view = curves
+        return 0.0
+obj = Leaky()
+"#,
+            )
+        });
+        let curve = curve_fixture("c");
+        let instrument = PyInstrument::new(obj);
+        assert!(instrument.npv(&Curves::new(None, Some(&curve))).is_ok());
+        Python::with_gil(|py| {
+            let view = instrument.obj.getattr(py, "view").unwrap();
+            let result = view.call_method1(py, "initial_date", ());
+            assert!(result.is_err());
+        });
+    }
+}
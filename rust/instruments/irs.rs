@@ -0,0 +1,166 @@
+use crate::calendars::{Convention, DateRoll};
+use crate::dual::Number;
+use crate::error::RateslibError;
+use crate::instruments::horizon::{accrual, fix_periods, static_pnl};
+use crate::instruments::Instrument;
+use crate::legs::{FixedLeg, FloatLeg, Leg};
+use crate::periods::Curves;
+use crate::scheduling::Schedule;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// An interest rate swap paying a [FixedLeg] and receiving a [FloatLeg] on a common
+/// [Schedule].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Irs {
+    pub(crate) leg1: FixedLeg,
+    pub(crate) leg2: FloatLeg,
+}
+
+impl Irs {
+    /// Create an [Irs], with a `float_spread` in bps on the floating leg.
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_new<U: DateRoll>(
+        schedule: Schedule,
+        fixed_rate: f64,
+        notional: f64,
+        fixed_convention: Convention,
+        float_convention: Convention,
+        float_spread: f64,
+        calendar: &U,
+    ) -> Result<Self, RateslibError> {
+        let leg1 = FixedLeg::try_new(
+            schedule.clone(),
+            fixed_rate,
+            notional,
+            fixed_convention,
+            false,
+            calendar,
+        )?;
+        let leg2 = FloatLeg::try_new(
+            schedule,
+            float_spread,
+            -notional,
+            float_convention,
+            false,
+            calendar,
+        )?;
+        Ok(Self { leg1, leg2 })
+    }
+
+    pub fn leg1(&self) -> &FixedLeg {
+        &self.leg1
+    }
+
+    pub fn leg2(&self) -> &FloatLeg {
+        &self.leg2
+    }
+
+    /// Return the carry of the swap to a `horizon`, which is the cashflows of its periods,
+    /// forecast from the `curves`, accrued pro rata to the `horizon`.
+    pub fn carry(&self, curves: &Curves, horizon: &NaiveDateTime) -> Result<Number, RateslibError> {
+        Ok(accrual(self.leg1.periods(), curves, horizon)?
+            + accrual(self.leg2.periods(), curves, horizon)?)
+    }
+
+    /// Return the roll-down of the swap to a `horizon`, which is its P&L under unchanged
+    /// `curves` less its [carry](Irs::carry).
+    ///
+    /// Floating periods fixing before the `horizon` retain their current forecast rates.
+    pub fn rolldown(
+        &self,
+        curves: &Curves,
+        horizon: &NaiveDateTime,
+    ) -> Result<Number, RateslibError> {
+        let mut fixed = self.clone();
+        fix_periods(&mut fixed.leg2.periods, curves, horizon)?;
+        Ok(static_pnl(self, &fixed, curves, horizon)? - self.carry(curves, horizon)?)
+    }
+}
+
+impl Instrument for Irs {
+    fn npv(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        Ok(self.leg1.npv(curves)? + self.leg2.npv(curves)?)
+    }
+
+    /// Return the fixed rate, in percent, for which the NPV of the swap is zero.
+    fn rate(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        let npv = self.npv(curves)?;
+        let a_delta = self.leg1.analytic_delta(curves)?;
+        Ok(npv / (a_delta * 100.0) + self.leg1.fixed_rate())
+    }
+
+    fn cashflows(&self, curves: &Curves) -> Result<Vec<(NaiveDateTime, Number)>, RateslibError> {
+        let mut cashflows = self.leg1.cashflows(curves)?;
+        cashflows.extend(self.leg2.cashflows(curves)?);
+        Ok(cashflows)
+    }
+
+    fn analytic_delta(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        self.leg1.analytic_delta(curves)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::{ndt, Modifier, NamedCal};
+    use crate::curves::{CurveDF, LogLinearInterpolator, Nodes};
+    use crate::instruments::instrument::tests::irs_fixture;
+    use crate::periods::period::tests::curve_fixture;
+    use indexmap::IndexMap;
+
+    #[test]
+    fn test_irs_rate_is_par() {
+        let curve = curve_fixture("c");
+        let curves = Curves::new(Some(&curve), Some(&curve));
+        let irs = irs_fixture(1.0);
+        let rate = f64::from(&irs.rate(&curves).unwrap());
+        let par = irs_fixture(rate);
+        assert!(f64::from(&par.npv(&curves).unwrap()).abs() < 1e-6);
+        assert!((f64::from(&par.rate(&curves).unwrap()) - rate).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_carry_rolldown_flat_curve() {
+        // forward rates are realised on a flat curve so a par swap has no P&L to the horizon
+        let curve = curve_fixture("c");
+        let curves = Curves::new(Some(&curve), Some(&curve));
+        let irs = irs_fixture(f64::from(&irs_fixture(1.0).rate(&curves).unwrap()));
+        let h = ndt(2024, 7, 1);
+        let carry = f64::from(&irs.carry(&curves, &h).unwrap());
+        let rolldown = f64::from(&irs.rolldown(&curves, &h).unwrap());
+        assert!((carry + rolldown).abs() < 1e-6);
+        assert!(irs.carry(&curves, &ndt(2024, 1, 1)).is_err());
+    }
+
+    #[test]
+    fn test_carry_rolldown_upward_curve() {
+        let nodes = Nodes::F64(IndexMap::from_iter(vec![
+            (ndt(2024, 1, 1), 1.0_f64),
+            (ndt(2025, 1, 1), 0.99),
+            (ndt(2026, 1, 1), 0.96),
+        ]));
+        let curve = CurveDF::try_new(
+            nodes,
+            LogLinearInterpolator::new(),
+            "c",
+            Convention::Act365F,
+            Modifier::ModF,
+            None,
+            NamedCal::try_new("all").unwrap(),
+        )
+        .unwrap();
+        let curves = Curves::new(Some(&curve), Some(&curve));
+        let irs = irs_fixture(f64::from(&irs_fixture(1.0).rate(&curves).unwrap()));
+        // paying the par rate above the first fixing, which rolls down to lower forecasts
+        let h = ndt(2024, 7, 1);
+        let carry = f64::from(&irs.carry(&curves, &h).unwrap());
+        // 182 days of the first period of 366 days are accrued
+        let float = (1.0 / 0.99 - 1.0) * 365.0 / 366.0 * 100.0;
+        let expected = (float - irs.leg1.fixed_rate()) * 182.0 / 365.0 * 1e6 / 100.0;
+        assert!(expected < 0.0);
+        assert!((carry - expected).abs() < 1e-6);
+        assert!(f64::from(&irs.rolldown(&curves, &h).unwrap()) < 0.0);
+    }
+}
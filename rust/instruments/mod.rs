@@ -0,0 +1,71 @@
+//! Create instruments, which are priced from legs and periods against a set of curves.
+//!
+//! Every instrument implements the [Instrument] trait and may be held in a [Portfolio]
+//! alongside instruments of other types. Instruments defined in Python, by subclassing the
+//! exported `Instrument` class, are wrapped as a [PyInstrument] so that they can be priced and
+//! calibrated alongside native instruments.
+
+pub(crate) mod instrument;
+pub use crate::instruments::instrument::{
+    Instrument, InstrumentTags, Portfolio, TagAggregate, TagKey,
+};
+
+mod horizon;
+
+mod irs;
+pub use crate::instruments::irs::Irs;
+
+mod cms_swap;
+pub use crate::instruments::cms_swap::CmsSwap;
+
+mod zcs;
+pub use crate::instruments::zcs::ZeroCouponSwap;
+
+mod cds;
+pub use crate::instruments::cds::Cds;
+
+mod credit_index;
+pub use crate::instruments::credit_index::{Constituent, CreditIndex, CreditIndexFamily};
+
+mod tranche;
+pub use crate::instruments::tranche::{BaseCorrelation, CdoTranche, TrancheModel};
+
+mod fx_swap;
+pub use crate::instruments::fx_swap::{FxSwap, FxSwapDelta};
+
+mod par_grid;
+pub use crate::instruments::par_grid::{par_rate_grid, ParRateGrid};
+
+mod generators;
+pub use crate::instruments::generators::{fra_strip, imm_dates, imm_swaps, meeting_swaps};
+
+mod bond;
+pub use crate::instruments::bond::{BondCarry, ExDividend, FixedRateBond, YieldConvention};
+
+mod asw;
+pub use crate::instruments::asw::{AssetSwap, AswType};
+
+mod basket;
+pub use crate::instruments::basket::{BondAnalytics, BondBasket};
+
+mod bond_future;
+pub use crate::instruments::bond_future::{BondFuture, DeliveryOption, Financing};
+
+mod frn;
+pub use crate::instruments::frn::FloatRateNote;
+
+mod spec;
+pub use crate::instruments::spec::{
+    get_spec, register_spec, reset_spec, spec_names, InstrumentSpec,
+};
+
+mod callable;
+pub use crate::instruments::callable::{CallableBond, CallableValue};
+
+mod swaption;
+pub use crate::instruments::swaption::{BermudanSwaption, BermudanValue};
+
+#[cfg(feature = "python")]
+pub(crate) mod instrument_py;
+#[cfg(feature = "python")]
+pub use crate::instruments::instrument_py::PyInstrument;
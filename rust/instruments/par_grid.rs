@@ -0,0 +1,142 @@
+use crate::calendars::{Convention, DateRoll, Modifier, RollDay};
+use crate::dual::Number;
+use crate::error::RateslibError;
+use crate::instruments::{Instrument, Irs};
+use crate::periods::Curves;
+use crate::scheduling::{Frequency, Schedule};
+use chrono::NaiveDateTime;
+
+/// Par swap rates and forward rates, in percent, on a grid of forward starts and tenors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParRateGrid {
+    /// The forward starts, in months from the effective date.
+    pub starts: Vec<u32>,
+    /// The tenors, in months from each forward start.
+    pub tenors: Vec<u32>,
+    /// Par swap rates indexed by forward start then tenor.
+    pub rates: Vec<Vec<Number>>,
+    /// Simple forward rates over each forward start and tenor, indexed as `rates`.
+    pub forwards: Vec<Vec<Number>>,
+}
+
+impl ParRateGrid {
+    /// Return the par swap rate and forward rate of a forward `start` and `tenor`, in months,
+    /// if on the grid.
+    pub fn get(&self, start: u32, tenor: u32) -> Option<(&Number, &Number)> {
+        let i = self.starts.iter().position(|s| *s == start)?;
+        let j = self.tenors.iter().position(|t| *t == tenor)?;
+        Some((&self.rates[i][j], &self.forwards[i][j]))
+    }
+}
+
+/// Return the [ParRateGrid] of swaps on the `curves` with forward `starts` and `tenors`, in
+/// months from the `effective` date, such as a 5y5y swap with a start and tenor of 60.
+///
+/// Swaps have a fixed and floating leg with a common `frequency` and `convention`, and dates
+/// adjusted following and modified following by the `calendar`.
+#[allow(clippy::too_many_arguments)]
+pub fn par_rate_grid<U: DateRoll>(
+    curves: &Curves,
+    effective: &NaiveDateTime,
+    starts: &[u32],
+    tenors: &[u32],
+    frequency: Frequency,
+    convention: Convention,
+    calendar: &U,
+) -> Result<ParRateGrid, RateslibError> {
+    if tenors.contains(&0) {
+        return Err(RateslibError::value(
+            "`tenors` of a par rate grid must be positive.",
+        ));
+    }
+    let forecasting = curves.forecasting()?;
+    let roll = RollDay::Unspecified {};
+    let mut rates = Vec::with_capacity(starts.len());
+    let mut forwards = Vec::with_capacity(starts.len());
+    for start in starts.iter() {
+        let start_date =
+            calendar.add_months(effective, *start as i32, &Modifier::F, &roll, false)?;
+        let (mut rate_row, mut forward_row) = (Vec::new(), Vec::new());
+        for tenor in tenors.iter() {
+            let end_date =
+                calendar.add_months(&start_date, *tenor as i32, &Modifier::Act, &roll, false)?;
+            let schedule = Schedule::try_new(
+                start_date,
+                end_date,
+                frequency,
+                roll,
+                Modifier::ModF,
+                calendar,
+                0,
+            )?;
+            let irs = Irs::try_new(schedule, 0.0, 1.0, convention, convention, 0.0, calendar)?;
+            rate_row.push(irs.rate(curves)?);
+            let end = irs.leg1().schedule().termination();
+            forward_row.push(forecasting.rate(&start_date, &end)?);
+        }
+        rates.push(rate_row);
+        forwards.push(forward_row);
+    }
+    Ok(ParRateGrid {
+        starts: starts.to_vec(),
+        tenors: tenors.to_vec(),
+        rates,
+        forwards,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::{ndt, NamedCal};
+    use crate::instruments::instrument::tests::irs_fixture;
+    use crate::periods::period::tests::curve_fixture;
+
+    #[test]
+    fn test_par_rate_grid() {
+        let curve = curve_fixture("c");
+        let curves = Curves::new(Some(&curve), Some(&curve));
+        let cal = NamedCal::try_new("all").unwrap();
+        let annual = Frequency::Months { number: 12 };
+        let grid = par_rate_grid(
+            &curves,
+            &ndt(2024, 1, 1),
+            &[0, 12, 60],
+            &[12, 24, 60],
+            annual,
+            Convention::Act365F,
+            &cal,
+        )
+        .unwrap();
+        assert_eq!(grid.rates.len(), 3);
+        // a spot starting 2y swap is the swap fixture
+        let (spot_2y, _) = grid.get(0, 24).unwrap();
+        let expected = irs_fixture(2.0).rate(&curves).unwrap();
+        assert!((f64::from(spot_2y) - f64::from(&expected)).abs() < 1e-12);
+        // a single period swap is a forward rate
+        let (rate, forward) = grid.get(12, 12).unwrap();
+        assert!((f64::from(rate) - f64::from(forward)).abs() < 1e-12);
+        // the 5y5y rate is close to the flat curve rate
+        let (five_five, _) = grid.get(60, 60).unwrap();
+        assert!((f64::from(five_five) - 2.02).abs() < 0.01);
+        assert!(grid.get(24, 12).is_none());
+    }
+
+    #[test]
+    fn test_invalid_tenor() {
+        let curve = curve_fixture("c");
+        let curves = Curves::new(Some(&curve), Some(&curve));
+        let cal = NamedCal::try_new("all").unwrap();
+        let annual = Frequency::Months { number: 12 };
+        let grid = par_rate_grid(
+            &curves,
+            &ndt(2024, 1, 1),
+            &[0],
+            &[0],
+            annual,
+            Convention::Act365F,
+            &cal,
+        );
+        assert!(grid.is_err());
+    }
+}
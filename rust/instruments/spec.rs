@@ -0,0 +1,328 @@
+use crate::calendars::{Convention, Modifier, NamedCal, RollDay};
+use crate::error::RateslibError;
+use crate::instruments::{
+    ExDividend, FixedRateBond, FloatRateNote, Irs, YieldConvention, ZeroCouponSwap,
+};
+use crate::periods::ZeroQuote;
+use crate::scheduling::{Frequency, Schedule};
+use chrono::{Datelike, NaiveDateTime};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::sync::{OnceLock, RwLock};
+
+/// The market conventions of an instrument, registered by name such as `"usd_irs"`.
+///
+/// Fields not relevant to an instrument are ignored by its constructor, for example the
+/// `yield_convention` of a swap. A spec may be modified with struct update syntax, as in
+/// `InstrumentSpec { payment_lag: 0, ..get_spec("usd_irs")? }`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InstrumentSpec {
+    /// The name of the calendar of the instrument, such as `"nyc"`.
+    pub calendar: String,
+    pub frequency: Frequency,
+    /// The day count convention of fixed rate periods.
+    pub fixed_convention: Convention,
+    /// The day count convention of floating rate periods.
+    pub float_convention: Convention,
+    pub modifier: Modifier,
+    /// The number of business days after each accrual end date that payment occurs.
+    pub payment_lag: i8,
+    pub ex_div: Option<ExDividend>,
+    pub yield_convention: YieldConvention,
+}
+
+impl InstrumentSpec {
+    fn swap(calendar: &str, months: u32, fixed: Convention, float: Convention, lag: i8) -> Self {
+        let frequency = match months {
+            0 => Frequency::Zero {},
+            number => Frequency::Months { number },
+        };
+        Self {
+            calendar: calendar.to_string(),
+            frequency,
+            fixed_convention: fixed,
+            float_convention: float,
+            modifier: Modifier::ModF,
+            payment_lag: lag,
+            ex_div: None,
+            yield_convention: YieldConvention::Street,
+        }
+    }
+
+    fn bond(calendar: &str, months: u32, ex_div: Option<ExDividend>) -> Self {
+        Self {
+            modifier: Modifier::F,
+            ex_div,
+            ..Self::swap(
+                calendar,
+                months,
+                Convention::ActActICMA,
+                Convention::ActActICMA,
+                0,
+            )
+        }
+    }
+
+    /// Return the calendar of the spec.
+    pub fn named_calendar(&self) -> Result<NamedCal, RateslibError> {
+        NamedCal::try_new(&self.calendar)
+    }
+
+    /// Return the [Schedule] of an instrument of the spec from `effective` to `termination`,
+    /// with a roll day of the day of `termination`.
+    pub fn schedule(
+        &self,
+        effective: NaiveDateTime,
+        termination: NaiveDateTime,
+    ) -> Result<Schedule, RateslibError> {
+        Schedule::try_new(
+            effective,
+            termination,
+            self.frequency,
+            RollDay::Int {
+                day: termination.day(),
+            },
+            self.modifier,
+            &self.named_calendar()?,
+            self.payment_lag,
+        )
+    }
+}
+
+fn default_specs() -> IndexMap<String, InstrumentSpec> {
+    use Convention::{Act360, Act365F, ThirtyE360};
+    use InstrumentSpec as S;
+    IndexMap::from_iter(
+        [
+            ("eur_irs", S::swap("tgt", 12, ThirtyE360, Act360, 1)),
+            ("usd_irs", S::swap("nyc", 12, Act360, Act360, 2)),
+            ("gbp_irs", S::swap("ldn", 12, Act365F, Act365F, 0)),
+            ("chf_irs", S::swap("zur", 12, Act360, Act360, 2)),
+            ("jpy_irs", S::swap("tyo", 12, Act365F, Act365F, 2)),
+            ("sek_irs", S::swap("stk", 12, Act360, Act360, 1)),
+            ("nok_irs", S::swap("osl", 12, Act365F, Act365F, 2)),
+            ("sofr_zcs", S::swap("nyc", 12, Act360, Act360, 2)),
+            ("estr_zcs", S::swap("tgt", 12, Act360, Act360, 1)),
+            ("sonia_zcs", S::swap("ldn", 12, Act365F, Act365F, 0)),
+            ("ust", S::bond("nyc", 6, None)),
+            ("ukt", S::bond("ldn", 6, Some(ExDividend::UK_GILT))),
+            ("bund", S::bond("tgt", 12, None)),
+            ("usd_frn", S::swap("nyc", 3, Act360, Act360, 0)),
+        ]
+        .map(|(k, v)| (k.to_string(), v)),
+    )
+}
+
+fn registry() -> &'static RwLock<IndexMap<String, InstrumentSpec>> {
+    static REGISTRY: OnceLock<RwLock<IndexMap<String, InstrumentSpec>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(default_specs()))
+}
+
+/// Return the [InstrumentSpec] registered under `name`.
+pub fn get_spec(name: &str) -> Result<InstrumentSpec, RateslibError> {
+    registry()
+        .read()
+        .unwrap()
+        .get(&name.to_lowercase())
+        .cloned()
+        .ok_or_else(|| {
+            RateslibError::value(format!(
+                "`spec` '{}' is not a known instrument specification.",
+                name
+            ))
+        })
+}
+
+/// Register an [InstrumentSpec] under `name`, replacing any spec, including a default spec,
+/// of the same name.
+pub fn register_spec(name: &str, spec: InstrumentSpec) {
+    registry()
+        .write()
+        .unwrap()
+        .insert(name.to_lowercase(), spec);
+}
+
+/// Restore the default spec of `name`, or remove a user defined spec.
+pub fn reset_spec(name: &str) {
+    let name = name.to_lowercase();
+    let mut specs = registry().write().unwrap();
+    match default_specs().shift_remove(&name) {
+        Some(spec) => specs.insert(name, spec),
+        None => specs.shift_remove(&name),
+    };
+}
+
+/// Return the names of the registered specs.
+pub fn spec_names() -> Vec<String> {
+    registry().read().unwrap().keys().cloned().collect()
+}
+
+impl Irs {
+    /// Create an [Irs] with the conventions of a `spec`, paying a `fixed_rate`, in percent, on
+    /// a `notional`.
+    pub fn try_from_spec(
+        spec: &InstrumentSpec,
+        effective: NaiveDateTime,
+        termination: NaiveDateTime,
+        fixed_rate: f64,
+        notional: f64,
+    ) -> Result<Self, RateslibError> {
+        Irs::try_new(
+            spec.schedule(effective, termination)?,
+            fixed_rate,
+            notional,
+            spec.fixed_convention,
+            spec.float_convention,
+            0.0,
+            &spec.named_calendar()?,
+        )
+    }
+}
+
+impl ZeroCouponSwap {
+    /// Create a [ZeroCouponSwap] with the conventions of a `spec`, whose frequency is that of
+    /// the compounding periods of the fixed leg, paying a `fixed_rate`, in percent and
+    /// compounded by the `quote`, on a `notional`.
+    pub fn try_from_spec(
+        spec: &InstrumentSpec,
+        effective: NaiveDateTime,
+        termination: NaiveDateTime,
+        fixed_rate: f64,
+        quote: ZeroQuote,
+        notional: f64,
+    ) -> Result<Self, RateslibError> {
+        ZeroCouponSwap::try_new(
+            spec.schedule(effective, termination)?,
+            fixed_rate,
+            notional,
+            spec.fixed_convention,
+            quote,
+            spec.float_convention,
+            0.0,
+            &spec.named_calendar()?,
+        )
+    }
+}
+
+impl FixedRateBond {
+    /// Create a [FixedRateBond] with the conventions of a `spec`, including its ex-dividend
+    /// rule and yield convention, with a `fixed_rate` in percent.
+    pub fn try_from_spec(
+        spec: &InstrumentSpec,
+        effective: NaiveDateTime,
+        termination: NaiveDateTime,
+        fixed_rate: f64,
+    ) -> Result<Self, RateslibError> {
+        let calendar = spec.named_calendar()?;
+        let bond = FixedRateBond::try_new(
+            spec.schedule(effective, termination)?,
+            fixed_rate,
+            spec.fixed_convention,
+            &calendar,
+        )?
+        .with_yield_convention(spec.yield_convention);
+        Ok(match spec.ex_div {
+            Some(ex_div) => bond.with_ex_div(ex_div, &calendar),
+            None => bond,
+        })
+    }
+}
+
+impl FloatRateNote {
+    /// Create a [FloatRateNote] with the conventions of a `spec` and a `quoted_margin` in bps.
+    pub fn try_from_spec(
+        spec: &InstrumentSpec,
+        effective: NaiveDateTime,
+        termination: NaiveDateTime,
+        quoted_margin: f64,
+    ) -> Result<Self, RateslibError> {
+        FloatRateNote::try_new(
+            spec.schedule(effective, termination)?,
+            quoted_margin,
+            spec.float_convention,
+            &spec.named_calendar()?,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::ndt;
+
+    #[test]
+    fn test_default_specs() {
+        let spec = get_spec("USD_IRS").unwrap();
+        assert_eq!(spec.payment_lag, 2);
+        assert_eq!(spec.frequency, Frequency::Months { number: 12 });
+        assert!(get_spec("xyz_irs").is_err());
+        assert!(spec_names().contains(&"sonia_zcs".to_string()));
+        let zcs = ZeroCouponSwap::try_from_spec(
+            &get_spec("sofr_zcs").unwrap(),
+            ndt(2024, 1, 3),
+            ndt(2029, 1, 3),
+            3.0,
+            ZeroQuote::Annualised,
+            1e6,
+        )
+        .unwrap();
+        assert_eq!(zcs.leg1().schedule().n_periods(), 5);
+        assert_eq!(zcs.leg2().schedule().n_periods(), 1);
+    }
+
+    #[test]
+    fn test_register_and_override_spec() {
+        let custom = InstrumentSpec {
+            payment_lag: 0,
+            frequency: Frequency::Months { number: 6 },
+            ..get_spec("usd_irs").unwrap()
+        };
+        register_spec("test_custom_irs", custom.clone());
+        assert_eq!(get_spec("test_custom_irs").unwrap(), custom);
+        let irs = Irs::try_from_spec(
+            &get_spec("test_custom_irs").unwrap(),
+            ndt(2024, 1, 3),
+            ndt(2026, 1, 3),
+            3.0,
+            1e6,
+        )
+        .unwrap();
+        assert_eq!(irs.leg1().schedule().n_periods(), 4);
+        reset_spec("test_custom_irs");
+        assert!(get_spec("test_custom_irs").is_err());
+
+        // the override of a default spec is reset to the default
+        let default = get_spec("nok_irs").unwrap();
+        register_spec(
+            "nok_irs",
+            InstrumentSpec {
+                payment_lag: 5,
+                ..default.clone()
+            },
+        );
+        assert_eq!(get_spec("nok_irs").unwrap().payment_lag, 5);
+        reset_spec("nok_irs");
+        assert_eq!(get_spec("nok_irs").unwrap(), default);
+    }
+
+    #[test]
+    fn test_bond_from_spec() {
+        let bond = FixedRateBond::try_from_spec(
+            &get_spec("ukt").unwrap(),
+            ndt(2020, 3, 7),
+            ndt(2030, 3, 7),
+            4.0,
+        )
+        .unwrap();
+        // the gilt is ex-dividend 7 business days before the 7th September 2024 coupon
+        assert!(bond.accrued(&ndt(2024, 9, 3)).unwrap() < 0.0);
+        let frn = FloatRateNote::try_from_spec(
+            &get_spec("usd_frn").unwrap(),
+            ndt(2024, 1, 16),
+            ndt(2027, 1, 16),
+            50.0,
+        )
+        .unwrap();
+        assert_eq!(frn.quoted_margin(), 50.0);
+    }
+}
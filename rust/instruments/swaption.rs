@@ -0,0 +1,244 @@
+use crate::calendars::{Convention, DateRoll};
+use crate::curves::PricingCurve;
+use crate::error::RateslibError;
+use crate::lattice::{value_on_lattice, LatticePayoff};
+use crate::legs::{FixedLeg, Leg};
+use crate::models::HullWhite;
+use crate::periods::PeriodType;
+use crate::scheduling::Schedule;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// An option to enter into the remainder of a fixed versus floating swap on any of a set of
+/// exercise dates.
+///
+/// Each exercise date must be the accrual start date of a period of the underlying swap and
+/// exercise enters into all periods from that date. The floating leg is assumed to be
+/// forecast from the discounting curve, without spread.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BermudanSwaption {
+    pub(crate) fixed_leg: FixedLeg,
+    pub(crate) notional: f64,
+    pub(crate) exercise: Vec<NaiveDateTime>,
+    pub(crate) payer: bool,
+}
+
+/// The value of a [BermudanSwaption] and its exercise boundary.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BermudanValue {
+    /// The value of the option at the initial date of the curve.
+    pub npv: f64,
+    /// For each exercise date the short rate at the boundary of the exercise region, which
+    /// is the lowest exercising rate of a payer or highest exercising rate of a receiver, if
+    /// exercise is optimal at any node.
+    pub exercise_boundary: Vec<(NaiveDateTime, Option<f64>)>,
+    /// The risk neutral probability of exercise on each exercise date.
+    pub exercise_probabilities: Vec<f64>,
+}
+
+impl BermudanSwaption {
+    /// Create a [BermudanSwaption] into a swap on a `schedule` with a `fixed_rate`, in percent,
+    /// which pays the fixed rate if `payer` and receives it otherwise.
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_new<U: DateRoll>(
+        schedule: Schedule,
+        fixed_rate: f64,
+        notional: f64,
+        convention: Convention,
+        mut exercise: Vec<NaiveDateTime>,
+        payer: bool,
+        calendar: &U,
+    ) -> Result<Self, RateslibError> {
+        exercise.sort();
+        exercise.dedup();
+        let starts = &schedule.aschedule()[..schedule.n_periods()];
+        if exercise.is_empty() || exercise.iter().any(|d| !starts.contains(d)) {
+            return Err(RateslibError::value(
+                "`exercise` dates must be accrual start dates of the underlying `schedule`.",
+            ));
+        }
+        let fixed_leg =
+            FixedLeg::try_new(schedule, fixed_rate, notional, convention, false, calendar)?;
+        Ok(Self {
+            fixed_leg,
+            notional,
+            exercise,
+            payer,
+        })
+    }
+
+    pub fn exercise(&self) -> &[NaiveDateTime] {
+        &self.exercise
+    }
+
+    /// Price the swaption by backward induction on a trinomial tree of the Hull-White
+    /// `model` fitted to the `curve`, with `steps_per_year` time steps.
+    ///
+    /// Cashflow and exercise dates are placed at the nearest step of the tree.
+    pub fn price(
+        &self,
+        curve: &dyn PricingCurve,
+        model: &HullWhite,
+        steps_per_year: usize,
+    ) -> Result<BermudanValue, RateslibError> {
+        let initial = curve.initial_date();
+        if self.exercise[0] < initial {
+            return Err(RateslibError::value(
+                "`exercise` dates of a `BermudanSwaption` must not precede the curve.",
+            ));
+        }
+        let maturity = self.fixed_leg.schedule().termination();
+        let horizon = (maturity - initial).num_seconds() as f64 / (365.0 * 86400.0);
+        let steps = ((horizon * steps_per_year as f64).ceil() as usize).max(1);
+        let tree = model.tree(curve, horizon, steps)?;
+        let value = value_on_lattice(&tree, self, 0.0)?;
+        let exercise_boundary = value
+            .exercise_region
+            .iter()
+            .map(|r| r.map(|(lo, hi)| if self.payer { lo } else { hi }))
+            .collect::<Vec<Option<f64>>>();
+        Ok(BermudanValue {
+            npv: value.option,
+            exercise_boundary: self
+                .exercise
+                .iter()
+                .copied()
+                .zip(exercise_boundary)
+                .collect(),
+            exercise_probabilities: value.exercise_probabilities,
+        })
+    }
+}
+
+impl LatticePayoff for BermudanSwaption {
+    /// Return the cashflows of the fixed leg and a final exchange of notional, whose value on
+    /// an accrual start date determines the value of the swap entered into on that date.
+    fn cashflows(&self) -> Vec<(NaiveDateTime, f64)> {
+        let mut cashflows: Vec<(NaiveDateTime, f64)> = self
+            .fixed_leg
+            .periods()
+            .iter()
+            .filter_map(|p| match p {
+                PeriodType::Fixed(f) => Some((
+                    f.base().end(),
+                    self.notional * f.base().dcf() * f.fixed_rate() * 0.01,
+                )),
+                _ => None,
+            })
+            .collect();
+        cashflows.push((self.fixed_leg.schedule().termination(), self.notional));
+        cashflows
+    }
+
+    fn exercise_dates(&self) -> Vec<NaiveDateTime> {
+        self.exercise.clone()
+    }
+
+    fn exercise_value(&self, _exercise: usize, underlying: f64) -> f64 {
+        match self.payer {
+            true => self.notional - underlying,
+            false => underlying - self.notional,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::{ndt, Modifier, NamedCal, RollDay};
+    use crate::periods::period::tests::curve_fixture;
+    use crate::scheduling::Frequency;
+
+    fn swaption_fixture(
+        exercise: Vec<NaiveDateTime>,
+        fixed_rate: f64,
+        payer: bool,
+    ) -> BermudanSwaption {
+        let cal = NamedCal::try_new("all").unwrap();
+        let schedule = Schedule::try_new(
+            ndt(2025, 1, 1),
+            ndt(2030, 1, 1),
+            Frequency::Months { number: 12 },
+            RollDay::Unspecified {},
+            Modifier::Act,
+            &cal,
+            0,
+        )
+        .unwrap();
+        BermudanSwaption::try_new(
+            schedule,
+            fixed_rate,
+            1e6,
+            Convention::Act365F,
+            exercise,
+            payer,
+            &cal,
+        )
+        .unwrap()
+    }
+
+    fn exercise_dates() -> Vec<NaiveDateTime> {
+        (2025..2030).map(|y| ndt(y, 1, 1)).collect()
+    }
+
+    #[test]
+    fn test_bermudan_exceeds_european() {
+        let curve = curve_fixture("c");
+        let model = HullWhite::try_new(0.03, 0.01).unwrap();
+        let bermudan = swaption_fixture(exercise_dates(), 2.0, true)
+            .price(&curve, &model, 24)
+            .unwrap();
+        for date in exercise_dates() {
+            let european = swaption_fixture(vec![date], 2.0, true)
+                .price(&curve, &model, 24)
+                .unwrap();
+            assert!(european.npv > 0.0);
+            assert!(bermudan.npv >= european.npv - 1e-8);
+        }
+        assert_eq!(bermudan.exercise_boundary.len(), 5);
+        assert!(bermudan.exercise_boundary.iter().all(|(_, b)| b.is_some()));
+        let total: f64 = bermudan.exercise_probabilities.iter().sum();
+        assert!(total > 0.0 && total <= 1.0 + 1e-12);
+    }
+
+    #[test]
+    fn test_low_volatility_intrinsic() {
+        // with negligible volatility an out-of-the-money swaption has negligible value
+        let curve = curve_fixture("c");
+        let model = HullWhite::try_new(0.03, 1e-6).unwrap();
+        let otm = swaption_fixture(exercise_dates(), 4.0, true)
+            .price(&curve, &model, 12)
+            .unwrap();
+        assert!(otm.npv.abs() < 1e-6);
+        assert!(otm.exercise_boundary.iter().all(|(_, b)| b.is_none()));
+        let itm = swaption_fixture(exercise_dates(), 4.0, false)
+            .price(&curve, &model, 12)
+            .unwrap();
+        assert!(itm.npv > 1e4);
+    }
+
+    #[test]
+    fn test_invalid_exercise() {
+        let cal = NamedCal::try_new("all").unwrap();
+        let schedule = Schedule::try_new(
+            ndt(2025, 1, 1),
+            ndt(2030, 1, 1),
+            Frequency::Months { number: 12 },
+            RollDay::Unspecified {},
+            Modifier::Act,
+            &cal,
+            0,
+        )
+        .unwrap();
+        let result = BermudanSwaption::try_new(
+            schedule,
+            2.0,
+            1e6,
+            Convention::Act365F,
+            vec![ndt(2025, 6, 1)],
+            true,
+            &cal,
+        );
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,481 @@
+use crate::curves::PricingCurve;
+use crate::dual::{get_variable_tags, Dual, MathFuncs, Number};
+use crate::error::RateslibError;
+use crate::instruments::{CreditIndex, Instrument};
+use crate::legs::Leg;
+use crate::periods::{CurveMap, Curves, Period};
+use chrono::NaiveDateTime;
+use num_traits::Pow;
+use std::f64::consts::PI;
+
+/// The base correlation of the Gaussian copula of a credit index by detachment point, as a
+/// fraction of the pool, interpolated linearly and flat outside the strikes.
+///
+/// Each correlation is a [Number], so that correlations created by [BaseCorrelation::variable]
+/// carry the correlation risk of a tranche in its gradient.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BaseCorrelation {
+    pub(crate) strikes: Vec<f64>,
+    pub(crate) correlations: Vec<Number>,
+}
+
+impl BaseCorrelation {
+    /// Create a [BaseCorrelation] of the `correlations` at increasing `strikes`.
+    pub fn try_new(strikes: Vec<f64>, correlations: Vec<Number>) -> Result<Self, RateslibError> {
+        if strikes.is_empty()
+            || strikes.len() != correlations.len()
+            || strikes.windows(2).any(|w| w[0] >= w[1])
+            || correlations
+                .iter()
+                .any(|c| !(0.0..1.0).contains(&f64::from(c)))
+        {
+            return Err(RateslibError::value(
+                "`BaseCorrelation` requires a correlation in [0, 1) at each increasing strike.",
+            ));
+        }
+        Ok(Self {
+            strikes,
+            correlations,
+        })
+    }
+
+    /// Create a [BaseCorrelation] whose `correlations` are variables tagged by the `id` and the
+    /// index of the strike.
+    pub fn variable(
+        id: &str,
+        strikes: Vec<f64>,
+        correlations: &[f64],
+    ) -> Result<Self, RateslibError> {
+        let correlations = correlations
+            .iter()
+            .zip(get_variable_tags(id, correlations.len()))
+            .map(|(c, tag)| Number::Dual(Dual::new(*c, vec![tag])))
+            .collect();
+        Self::try_new(strikes, correlations)
+    }
+
+    /// Return the base correlation at the detachment `strike`.
+    pub fn correlation(&self, strike: f64) -> Number {
+        let i = self.strikes.partition_point(|k| *k <= strike);
+        match i {
+            0 => self.correlations[0].clone(),
+            i if i == self.strikes.len() => self.correlations[i - 1].clone(),
+            i => {
+                let (k1, k2) = (self.strikes[i - 1], self.strikes[i]);
+                let (c1, c2) = (&self.correlations[i - 1], &self.correlations[i]);
+                c1 + &((c2 - c1) * ((strike - k1) / (k2 - k1)))
+            }
+        }
+    }
+}
+
+/// The model of the loss distribution of the pool of a [CdoTranche] under a one factor
+/// Gaussian copula.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrancheModel {
+    /// The large homogeneous pool, whose loss conditional on the common factor is its expected
+    /// loss, at the weighted average default probability of the constituents.
+    Lhp,
+    /// The exact loss distribution of an equally weighted pool of constituents with
+    /// heterogeneous default probabilities, by the recursion of Andersen, Sidenius and Basu.
+    FinitePool,
+}
+
+/// A synthetic CDO tranche of a [CreditIndex], absorbing the losses of the pool between its
+/// attachment and detachment points, as fractions of the surviving pool, and paying a running
+/// coupon on its outstanding notional.
+///
+/// The expected loss of the tranche is the difference of the expected losses of the base
+/// tranches to its detachment and attachment points, each at its own [BaseCorrelation]. The
+/// premium of each period of the index accrues on the average outstanding notional and
+/// default losses are paid at the end of each period. The common factor is integrated by the
+/// trapezoidal rule over `points` nodes, by default 201, between -7 and 7.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CdoTranche {
+    pub(crate) index: CreditIndex,
+    pub(crate) attachment: f64,
+    pub(crate) detachment: f64,
+    pub(crate) coupon: f64,
+    pub(crate) notional: f64,
+    pub(crate) correlation: BaseCorrelation,
+    pub(crate) model: TrancheModel,
+    pub(crate) points: usize,
+}
+
+impl CdoTranche {
+    /// Create a [CdoTranche] of the `index` paying a running `coupon`, in percent, on the
+    /// `notional` of the tranche, so that a positive notional buys protection.
+    pub fn try_new(
+        index: CreditIndex,
+        attachment: f64,
+        detachment: f64,
+        coupon: f64,
+        notional: f64,
+        correlation: BaseCorrelation,
+        model: TrancheModel,
+    ) -> Result<Self, RateslibError> {
+        if !(0.0 <= attachment && attachment < detachment && detachment <= 1.0) {
+            return Err(RateslibError::value(
+                "A `CdoTranche` requires 0 <= attachment < detachment <= 1.",
+            ));
+        }
+        if model == TrancheModel::FinitePool {
+            let weights: Vec<f64> = index.surviving().map(|c| c.weight).collect();
+            if weights.iter().any(|w| (w - weights[0]).abs() > 1e-12) {
+                return Err(RateslibError::value(
+                    "The finite pool model of a `CdoTranche` requires equally weighted constituents.",
+                ));
+            }
+        }
+        Ok(Self {
+            index,
+            attachment,
+            detachment,
+            coupon,
+            notional,
+            correlation,
+            model,
+            points: 201,
+        })
+    }
+
+    /// Return the tranche with the common factor integrated over `points` nodes.
+    pub fn with_points(mut self, points: usize) -> Self {
+        self.points = points.max(3);
+        self
+    }
+
+    pub fn attachment(&self) -> f64 {
+        self.attachment
+    }
+
+    pub fn detachment(&self) -> f64 {
+        self.detachment
+    }
+
+    /// Return the expected loss of the tranche on `date`, as a fraction of its notional, with
+    /// the survival curves of the constituents resolved by name from `curves`.
+    pub fn expected_loss(
+        &self,
+        date: &NaiveDateTime,
+        curves: &CurveMap,
+    ) -> Result<Number, RateslibError> {
+        let pool = self.pool(date, curves)?;
+        let base = |strike: f64| match strike > 0.0 {
+            true => self.base_loss(&pool, strike, self.correlation.correlation(strike)),
+            false => Number::F64(0.0),
+        };
+        let (k1, k2) = (self.attachment, self.detachment);
+        Ok((base(k2) - base(k1)) / (k2 - k1))
+    }
+
+    /// Return the NPV to the buyer of protection, with the survival curves of the constituents
+    /// resolved by name from `curves`, discounted on the `discounting` curve.
+    pub fn npv_pool(
+        &self,
+        curves: &CurveMap,
+        discounting: &dyn PricingCurve,
+    ) -> Result<Number, RateslibError> {
+        let (protection, annuity) = self.legs(curves, discounting)?;
+        Ok((protection - annuity * (self.coupon * 0.01)) * self.notional)
+    }
+
+    /// Return the par spread, in percent, with the survival curves of the constituents resolved
+    /// by name from `curves`, discounted on the `discounting` curve.
+    pub fn rate_pool(
+        &self,
+        curves: &CurveMap,
+        discounting: &dyn PricingCurve,
+    ) -> Result<Number, RateslibError> {
+        let (protection, annuity) = self.legs(curves, discounting)?;
+        Ok(protection / annuity * 100.0)
+    }
+
+    /// Return the protection leg and the risky annuity of the premium leg, per unit notional.
+    fn legs(
+        &self,
+        curves: &CurveMap,
+        discounting: &dyn PricingCurve,
+    ) -> Result<(Number, Number), RateslibError> {
+        let initial = discounting.initial_date();
+        let (mut protection, mut annuity) = (Number::F64(0.0), Number::F64(0.0));
+        // the loss at the end of each period is the loss at the start of the next
+        let mut loss: Option<Number> = None;
+        for p in self.index.cds.leg1.periods() {
+            if p.payment() < initial {
+                continue;
+            }
+            let base = p
+                .base()
+                .expect("the premium periods of a credit index accrue");
+            let l0 = match loss.take() {
+                Some(l) => l,
+                None => self.expected_loss(&base.start().max(initial), curves)?,
+            };
+            let l1 = self.expected_loss(&base.end(), curves)?;
+            let df = discounting.df(&p.payment());
+            annuity = annuity + &df * ((2.0 - &l0 - &l1) * (0.5 * base.dcf()));
+            protection = protection + df * (&l1 - l0);
+            loss = Some(l1);
+        }
+        Ok((protection, annuity))
+    }
+
+    /// Return the loss given default and the default probability on `date` of each surviving
+    /// constituent, with its weight in the surviving pool.
+    fn pool(
+        &self,
+        date: &NaiveDateTime,
+        curves: &CurveMap,
+    ) -> Result<Vec<(f64, Number)>, RateslibError> {
+        let factor = self.index.factor();
+        let lgd = 1.0 - self.index.family.recovery();
+        self.index
+            .surviving()
+            .map(|c| {
+                let default = 1.0 - curves.get(&c.name)?.df(date);
+                Ok((c.weight / factor * lgd, default))
+            })
+            .collect()
+    }
+
+    /// Return the expected loss of the base tranche to the `strike`, as a fraction of the pool,
+    /// at the `correlation`.
+    fn base_loss(&self, pool: &[(f64, Number)], strike: f64, rho: Number) -> Number {
+        let (a, b) = (rho.clone().pow(0.5), (1.0 - rho).pow(0.5));
+        let min = |loss: Number| match f64::from(&loss) < strike {
+            true => loss,
+            false => Number::F64(strike),
+        };
+        // the default thresholds of the pool, or of its average constituent
+        let thresholds: Vec<(f64, Number)> = match self.model {
+            TrancheModel::Lhp => {
+                let lgd: f64 = pool.iter().map(|(l, _)| l).sum();
+                let p = pool
+                    .iter()
+                    .fold(Number::F64(0.0), |acc, (l, p)| acc + p * *l)
+                    / lgd;
+                vec![(lgd, p)]
+            }
+            TrancheModel::FinitePool => pool.to_vec(),
+        };
+        let thresholds: Vec<(f64, Number)> = thresholds
+            .into_iter()
+            .filter(|(_, p)| f64::from(p) > 0.0)
+            .map(|(l, p)| (l, p.inv_norm_cdf()))
+            .collect();
+        if thresholds.is_empty() {
+            return Number::F64(0.0);
+        }
+        let mut value = Number::F64(0.0);
+        for (m, w) in factor_nodes(self.points) {
+            let conditional = |c: &Number| ((c - &a * m) / &b).norm_cdf();
+            let loss = match self.model {
+                TrancheModel::Lhp => {
+                    let (lgd, c) = &thresholds[0];
+                    min(conditional(c) * *lgd)
+                }
+                TrancheModel::FinitePool => {
+                    let mut dist = vec![Number::F64(1.0)];
+                    for (_, c) in thresholds.iter() {
+                        let q = conditional(c);
+                        let mut next = vec![Number::F64(0.0); dist.len() + 1];
+                        for (k, d) in dist.iter().enumerate() {
+                            next[k] = &next[k] + &(d * &(1.0 - &q));
+                            next[k + 1] = &next[k + 1] + &(d * &q);
+                        }
+                        dist = next;
+                    }
+                    let unit = thresholds[0].0;
+                    dist.into_iter()
+                        .enumerate()
+                        .fold(Number::F64(0.0), |acc, (k, d)| {
+                            acc + d * min(Number::F64(k as f64 * unit))
+                        })
+                }
+            };
+            value = value + loss * w;
+        }
+        value
+    }
+}
+
+/// Return the nodes and trapezoidal weights of `n` points between -7 and 7 of the standard
+/// normal density, normalised to sum to one.
+fn factor_nodes(n: usize) -> Vec<(f64, f64)> {
+    let h = 14.0 / (n - 1) as f64;
+    let nodes: Vec<(f64, f64)> = (0..n)
+        .map(|i| {
+            let m = -7.0 + i as f64 * h;
+            let end = if i == 0 || i == n - 1 { 0.5 } else { 1.0 };
+            (m, end * (-0.5 * m * m).exp() / (2.0 * PI).sqrt())
+        })
+        .collect();
+    let total: f64 = nodes.iter().map(|(_, w)| w).sum();
+    nodes.into_iter().map(|(m, w)| (m, w / total)).collect()
+}
+
+impl Instrument for CdoTranche {
+    /// Return the NPV to the buyer of protection with every constituent on the forecasting
+    /// curve of the index.
+    fn npv(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        let map = CurveMap::new()
+            .with_curve("index", curves.forecasting()?)
+            .with_fallback("index");
+        self.npv_pool(&map, curves.discounting()?)
+    }
+
+    /// Return the par spread, in percent, with every constituent on the forecasting curve of
+    /// the index.
+    fn rate(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        let map = CurveMap::new()
+            .with_curve("index", curves.forecasting()?)
+            .with_fallback("index");
+        self.rate_pool(&map, curves.discounting()?)
+    }
+
+    fn cashflows(&self, _curves: &Curves) -> Result<Vec<(NaiveDateTime, Number)>, RateslibError> {
+        Err(RateslibError::value(
+            "The cashflows of a `CdoTranche` are contingent on the losses of its pool.",
+        ))
+    }
+
+    fn analytic_delta(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        let map = CurveMap::new()
+            .with_curve("index", curves.forecasting()?)
+            .with_fallback("index");
+        let (_, annuity) = self.legs(&map, curves.discounting()?)?;
+        Ok(annuity * (self.notional * 0.0001))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::{ndt, NamedCal};
+    use crate::curves::HazardCurve;
+    use crate::dual::Gradient1;
+    use crate::instruments::{Constituent, CreditIndexFamily};
+    use crate::periods::period::tests::curve_fixture;
+
+    fn index(names: &[&str]) -> CreditIndex {
+        let cal = NamedCal::try_new("nyc").unwrap();
+        CreditIndex::try_new_equal(
+            CreditIndexFamily::CdxIg,
+            42,
+            ndt(2024, 1, 1),
+            ndt(2029, 6, 20),
+            1e7,
+            names,
+            &cal,
+        )
+        .unwrap()
+    }
+
+    fn hazard(id: &str, spread: f64) -> HazardCurve {
+        let cal = NamedCal::try_new("nyc").unwrap();
+        let disc = curve_fixture("d");
+        let maturities = [ndt(2029, 6, 20)];
+        let initial = ndt(2024, 1, 1);
+        HazardCurve::bootstrap_standard(id, initial, &maturities, &[spread], 0.4.into(), &disc, cal)
+            .unwrap()
+    }
+
+    fn tranche(k1: f64, k2: f64, model: TrancheModel, names: &[&str]) -> CdoTranche {
+        let correlation =
+            BaseCorrelation::variable("rho", vec![0.03, 0.07, 0.15], &[0.3, 0.4, 0.5]);
+        CdoTranche::try_new(index(names), k1, k2, 1.0, 1e7, correlation.unwrap(), model).unwrap()
+    }
+
+    #[test]
+    fn test_tranches_sum_to_index() {
+        // at a flat correlation the expected losses of adjacent tranches sum to the pool
+        let disc = curve_fixture("d");
+        let curve = hazard("cdx", 1.0);
+        let map = CurveMap::new()
+            .with_curve("cdx", curve.curve())
+            .with_fallback("cdx");
+        let flat = BaseCorrelation::try_new(vec![0.1], vec![Number::F64(0.3)]).unwrap();
+        let date = ndt(2027, 1, 1);
+        let strikes = [0.0, 0.03, 0.07, 0.15, 1.0];
+        let total = strikes.windows(2).fold(0.0, |acc, k| {
+            let t = CdoTranche::try_new(
+                index(&["a"]),
+                k[0],
+                k[1],
+                1.0,
+                1e7,
+                flat.clone(),
+                TrancheModel::Lhp,
+            )
+            .unwrap();
+            acc + f64::from(t.expected_loss(&date, &map).unwrap()) * (k[1] - k[0])
+        });
+        let expected = 0.6 * (1.0 - f64::from(curve.survival(&date)));
+        assert!((total - expected).abs() < 1e-10);
+        // equity is riskier than the index, and the senior tranche less risky
+        let curves = Curves::new(Some(curve.curve()), Some(&disc));
+        let equity = tranche(0.0, 0.03, TrancheModel::Lhp, &["a"]);
+        let senior = tranche(0.15, 1.0, TrancheModel::Lhp, &["a"]);
+        assert!(f64::from(&equity.rate(&curves).unwrap()) > 1.0);
+        assert!(f64::from(&senior.rate(&curves).unwrap()) < 1.0);
+        assert!(tranche(0.0, 0.03, TrancheModel::Lhp, &["a"])
+            .with_points(3)
+            .rate(&curves)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_finite_pool_and_correlation_risk() {
+        let disc = curve_fixture("d");
+        let names: Vec<String> = (0..30).map(|i| format!("n{}", i)).collect();
+        let names: Vec<&str> = names.iter().map(|n| n.as_str()).collect();
+        let curve = hazard("cdx", 1.0);
+        let curves = Curves::new(Some(curve.curve()), Some(&disc));
+        // the finite pool converges to the large pool, with a small granularity correction
+        let lhp = tranche(0.03, 0.07, TrancheModel::Lhp, &names);
+        let finite = tranche(0.03, 0.07, TrancheModel::FinitePool, &names).with_points(41);
+        let (s_lhp, s_finite) = (lhp.rate(&curves).unwrap(), finite.rate(&curves).unwrap());
+        assert!((f64::from(&s_lhp) / f64::from(&s_finite) - 1.0).abs() < 0.1);
+        // the mezzanine spread depends on the base correlations of both its strikes
+        let Number::Dual(d) = s_lhp else {
+            panic!("the base correlations are dual numbers")
+        };
+        let rho = |i: usize| d.gradient1(vec![format!("rho{}", i)])[0];
+        assert!(rho(0) != 0.0 && rho(1) != 0.0 && rho(2) == 0.0);
+        let bumped = BaseCorrelation::try_new(
+            vec![0.03, 0.07, 0.15],
+            vec![Number::F64(0.3), Number::F64(0.4 + 1e-6), Number::F64(0.5)],
+        )
+        .unwrap();
+        let t = CdoTranche::try_new(
+            index(&names),
+            0.03,
+            0.07,
+            1.0,
+            1e7,
+            bumped,
+            TrancheModel::Lhp,
+        );
+        let fd = (f64::from(&t.unwrap().rate(&curves).unwrap()) - d.real()) / 1e-6;
+        assert!((rho(1) - fd).abs() < 1e-3 * fd.abs().max(1.0));
+        let cal = NamedCal::try_new("nyc").unwrap();
+        let constituents = vec![Constituent::new("a", 0.3), Constituent::new("b", 0.7)];
+        let (start, end) = (ndt(2024, 1, 1), ndt(2029, 6, 20));
+        let unequal = CreditIndex::try_new(
+            CreditIndexFamily::CdxIg,
+            1,
+            start,
+            end,
+            1e7,
+            constituents,
+            &cal,
+        )
+        .unwrap();
+        let flat = BaseCorrelation::try_new(vec![0.1], vec![Number::F64(0.3)]).unwrap();
+        let t =
+            |model| CdoTranche::try_new(unequal.clone(), 0.0, 0.1, 1.0, 1e7, flat.clone(), model);
+        assert!(t(TrancheModel::FinitePool).is_err());
+        assert!(t(TrancheModel::Lhp).is_ok());
+    }
+}
@@ -0,0 +1,201 @@
+use crate::calendars::{Convention, DateRoll};
+use crate::dual::Number;
+use crate::error::RateslibError;
+use crate::instruments::Instrument;
+use crate::legs::{FloatLeg, Leg, ZeroFixedLeg};
+use crate::periods::{Curves, Period, ZeroQuote};
+use crate::scheduling::Schedule;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// A zero coupon swap paying a [ZeroFixedLeg], compounded over the periods of a [Schedule],
+/// and receiving a [FloatLeg] with a single period over the same dates, whose rate compounds
+/// the forecast floating rate to the common payment date.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ZeroCouponSwap {
+    pub(crate) leg1: ZeroFixedLeg,
+    pub(crate) leg2: FloatLeg,
+}
+
+impl ZeroCouponSwap {
+    /// Create a [ZeroCouponSwap], whose `fixed_rate` compounds by the `quote` over the periods
+    /// of the `schedule`, with a `float_spread` in bps on the floating leg.
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_new<U: DateRoll>(
+        schedule: Schedule,
+        fixed_rate: f64,
+        notional: f64,
+        fixed_convention: Convention,
+        quote: ZeroQuote,
+        float_convention: Convention,
+        float_spread: f64,
+        calendar: &U,
+    ) -> Result<Self, RateslibError> {
+        let leg2 = FloatLeg::try_new(
+            schedule.to_zero(),
+            float_spread,
+            -notional,
+            float_convention,
+            false,
+            calendar,
+        )?;
+        let leg1 = ZeroFixedLeg::try_new(
+            schedule,
+            fixed_rate,
+            notional,
+            fixed_convention,
+            quote,
+            calendar,
+        )?;
+        Ok(Self { leg1, leg2 })
+    }
+
+    pub fn leg1(&self) -> &ZeroFixedLeg {
+        &self.leg1
+    }
+
+    pub fn leg2(&self) -> &FloatLeg {
+        &self.leg2
+    }
+
+    /// Return the fixed rate, in percent and compounded by the `quote`, for which the NPV of
+    /// the swap is zero.
+    ///
+    /// The rate carries the sensitivities of the `curves`, so that the same swap may be quoted
+    /// as an annualised rate or a zero rate.
+    pub fn rate_with_quote(
+        &self,
+        curves: &Curves,
+        quote: ZeroQuote,
+    ) -> Result<Number, RateslibError> {
+        let period = self.leg1.period();
+        let df = curves.discounting()?.df(&period.payment());
+        let growth = self.leg2.npv(curves)? / (df * period.base.notional) + 1.0;
+        quote.implied_rate(&growth, &period.dcfs)
+    }
+}
+
+impl Instrument for ZeroCouponSwap {
+    fn npv(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        Ok(self.leg1.npv(curves)? + self.leg2.npv(curves)?)
+    }
+
+    /// Return the fixed rate, in percent and compounded by the quote of the fixed leg, for
+    /// which the NPV of the swap is zero.
+    fn rate(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        self.rate_with_quote(curves, self.leg1.quote())
+    }
+
+    fn cashflows(&self, curves: &Curves) -> Result<Vec<(NaiveDateTime, Number)>, RateslibError> {
+        let mut cashflows = self.leg1.cashflows(curves)?;
+        cashflows.extend(self.leg2.cashflows(curves)?);
+        Ok(cashflows)
+    }
+
+    fn analytic_delta(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        self.leg1.analytic_delta(curves)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::{ndt, Modifier, NamedCal, RollDay};
+    use crate::curves::{CurveDF, LogLinearInterpolator, Nodes, PricingCurve};
+    use crate::dual::{Dual, Gradient1};
+    use crate::periods::period::tests::curve_fixture;
+    use crate::scheduling::Frequency;
+    use indexmap::IndexMap;
+
+    fn swap(fixed_rate: f64, quote: ZeroQuote) -> ZeroCouponSwap {
+        let cal = NamedCal::try_new("all").unwrap();
+        let schedule = Schedule::try_new(
+            ndt(2024, 1, 1),
+            ndt(2034, 1, 1),
+            Frequency::Months { number: 12 },
+            RollDay::Unspecified {},
+            Modifier::Act,
+            &cal,
+            0,
+        )
+        .unwrap();
+        ZeroCouponSwap::try_new(
+            schedule,
+            fixed_rate,
+            1e6,
+            Convention::One,
+            quote,
+            Convention::Act365F,
+            0.0,
+            &cal,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_zcs_rate_is_par() {
+        let curve = curve_fixture("c");
+        let curves = Curves::new(Some(&curve), Some(&curve));
+        for quote in [
+            ZeroQuote::Annualised,
+            ZeroQuote::Simple,
+            ZeroQuote::Continuous,
+        ] {
+            let rate = f64::from(&swap(1.0, quote).rate(&curves).unwrap());
+            assert!(f64::from(&swap(rate, quote).npv(&curves).unwrap()).abs() < 1e-6);
+        }
+        // each leg pays a single cashflow at maturity
+        let cashflows = swap(1.0, ZeroQuote::Annualised).cashflows(&curves).unwrap();
+        assert_eq!(cashflows.len(), 2);
+        assert!(cashflows.iter().all(|(d, _)| *d == ndt(2034, 1, 1)));
+    }
+
+    #[test]
+    fn test_zcs_quote_conventions() {
+        // a continuously compounded 2% curve over a whole number of years
+        let curve = curve_fixture("c");
+        let curves = Curves::new(Some(&curve), Some(&curve));
+        let zcs = swap(1.0, ZeroQuote::Annualised);
+        let t: f64 = zcs.leg1().period().dcfs().iter().sum();
+        let growth = 1.0 / f64::from(&curve.df(&ndt(2034, 1, 1)));
+        let annualised = f64::from(&zcs.rate(&curves).unwrap());
+        assert!((annualised - 100.0 * (growth.powf(1.0 / t) - 1.0)).abs() < 1e-10);
+        let zero = zcs.rate_with_quote(&curves, ZeroQuote::Continuous).unwrap();
+        assert!((f64::from(&zero) - 100.0 * growth.ln() / t).abs() < 1e-10);
+        let simple = zcs.rate_with_quote(&curves, ZeroQuote::Simple).unwrap();
+        assert!((f64::from(&simple) - 100.0 * (growth - 1.0) / t).abs() < 1e-10);
+        assert!(f64::from(&simple) > annualised && annualised > f64::from(&zero));
+    }
+
+    #[test]
+    fn test_zcs_rate_sensitivity() {
+        // the par rate carries the sensitivity to the final discount factor of the curve
+        let curve = |v: f64| {
+            let nodes = Nodes::Dual(IndexMap::from_iter([
+                (ndt(2024, 1, 1), Dual::new(1.0, vec![])),
+                (ndt(2034, 1, 1), Dual::new(v, vec!["v".to_string()])),
+            ]));
+            CurveDF::try_new(
+                nodes,
+                LogLinearInterpolator::new(),
+                "c",
+                Convention::Act365F,
+                Modifier::ModF,
+                None,
+                NamedCal::try_new("all").unwrap(),
+            )
+            .unwrap()
+        };
+        let zcs = swap(1.0, ZeroQuote::Annualised);
+        let rate = |v: f64| {
+            let c = curve(v);
+            zcs.rate(&Curves::new(Some(&c), Some(&c))).unwrap()
+        };
+        let gradient = match rate(0.8) {
+            Number::Dual(d) => d.gradient1(vec!["v".to_string()])[0],
+            _ => panic!("the rate of a zero coupon swap must carry sensitivities"),
+        };
+        let fd = (f64::from(&rate(0.8 + 1e-6)) - f64::from(&rate(0.8 - 1e-6))) / 2e-6;
+        assert!((gradient - fd).abs() / fd.abs() < 1e-6);
+    }
+}
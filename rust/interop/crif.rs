@@ -0,0 +1,310 @@
+use crate::calendars::DateRoll;
+use crate::curves::nodes::NodesTimestamp;
+use crate::curves::{CurveDF, CurveInterpolation};
+use crate::dual::{get_variable_tags, Dual, Exogenous, Gradient1};
+use crate::fx::rates::{Ccy, FXRates};
+use indexmap::IndexMap;
+
+/// The SIMM interest rate tenors and their year fractions.
+const SIMM_TENORS: [(&str, f64); 12] = [
+    ("2w", 14.0 / 365.0),
+    ("1m", 1.0 / 12.0),
+    ("3m", 0.25),
+    ("6m", 0.5),
+    ("1y", 1.0),
+    ("2y", 2.0),
+    ("3y", 3.0),
+    ("5y", 5.0),
+    ("10y", 10.0),
+    ("15y", 15.0),
+    ("20y", 20.0),
+    ("30y", 30.0),
+];
+
+/// The risk type of a [CrifRow].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CrifRiskType {
+    IRCurve,
+    IRVol,
+    FX,
+}
+
+impl CrifRiskType {
+    /// Return the CRIF name of the risk type.
+    pub fn name(&self) -> &'static str {
+        match self {
+            CrifRiskType::IRCurve => "Risk_IRCurve",
+            CrifRiskType::IRVol => "Risk_IRVol",
+            CrifRiskType::FX => "Risk_FX",
+        }
+    }
+}
+
+/// A row of the ISDA SIMM Common Risk Interchange Format.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrifRow {
+    pub trade_id: String,
+    pub risk_type: CrifRiskType,
+    pub qualifier: String,
+    pub bucket: String,
+    pub label1: String,
+    pub label2: String,
+    pub amount: f64,
+    pub amount_currency: String,
+}
+
+/// Return the SIMM interest rate bucket of a currency.
+fn ir_bucket(currency: &str) -> &'static str {
+    match currency {
+        "USD" | "EUR" | "GBP" | "CHF" | "AUD" | "NZD" | "CAD" | "SEK" | "NOK" | "DKK" | "HKD"
+        | "KRW" | "SGD" | "TWD" => "1",
+        "JPY" => "2",
+        _ => "3",
+    }
+}
+
+/// Allocate an `amount` at a time `t`, in years, linearly between the adjacent SIMM tenors.
+fn allocate(amounts: &mut IndexMap<&'static str, f64>, t: f64, amount: f64) {
+    let i = SIMM_TENORS.partition_point(|(_, s)| *s < t);
+    if i == 0 || i == SIMM_TENORS.len() {
+        let (label, _) = SIMM_TENORS[i.min(SIMM_TENORS.len() - 1)];
+        *amounts.entry(label).or_insert(0.0) += amount;
+        return;
+    }
+    let ((l0, t0), (l1, t1)) = (SIMM_TENORS[i - 1], SIMM_TENORS[i]);
+    let w = (t - t0) / (t1 - t0);
+    *amounts.entry(l0).or_insert(0.0) += amount * (1.0 - w);
+    *amounts.entry(l1).or_insert(0.0) += amount * w;
+}
+
+/// Return rows ordered by SIMM tenor for the non-zero allocated `amounts`.
+fn tenor_rows(
+    amounts: IndexMap<&'static str, f64>,
+    row: impl Fn(&str, f64) -> CrifRow,
+) -> Vec<CrifRow> {
+    SIMM_TENORS
+        .iter()
+        .filter_map(|(label, _)| amounts.get(label).map(|a| (label, *a)))
+        .filter(|(_, a)| *a != 0.0)
+        .map(|(label, a)| row(label, a))
+        .collect()
+}
+
+/// Return the `Risk_IRCurve` rows of an `npv`, in `currency`, with sensitivity to the node
+/// variables of a discount factor `curve` forecasting the `sub_curve`, such as `"OIS"` or
+/// `"Libor3m"`.
+///
+/// The sensitivity to each node is its change in value for a 1bp increase of the
+/// continuously compounded Act365F zero rate to the node, allocated linearly to the adjacent
+/// SIMM tenors.
+pub fn ir_curve_crif<T: CurveInterpolation, U: DateRoll>(
+    trade_id: &str,
+    npv: &Dual,
+    curve: &CurveDF<T, U>,
+    currency: &Ccy,
+    sub_curve: &str,
+) -> Vec<CrifRow> {
+    let nodes: Vec<(i64, f64)> = match &curve.nodes {
+        NodesTimestamp::F64(m) => m.iter().map(|(k, v)| (*k, *v)).collect(),
+        NodesTimestamp::Dual(m) => m.iter().map(|(k, v)| (*k, v.real)).collect(),
+        NodesTimestamp::Dual2(m) => m.iter().map(|(k, v)| (*k, v.real)).collect(),
+    };
+    let tags = get_variable_tags(&curve.id, nodes.len());
+    let gradient = npv.gradient1(tags);
+    let mut amounts = IndexMap::new();
+    for ((k, df), g) in nodes.iter().zip(gradient.iter()) {
+        let t = (k - nodes[0].0) as f64 / (86400.0 * 365.0);
+        if t > 0.0 {
+            allocate(&mut amounts, t, -g * t * df * 0.0001);
+        }
+    }
+    let ccy = currency.name.to_uppercase();
+    tenor_rows(amounts, |label, amount| CrifRow {
+        trade_id: trade_id.to_string(),
+        risk_type: CrifRiskType::IRCurve,
+        qualifier: ccy.clone(),
+        bucket: ir_bucket(&ccy).to_string(),
+        label1: label.to_string(),
+        label2: sub_curve.to_string(),
+        amount,
+        amount_currency: ccy.clone(),
+    })
+}
+
+/// Return the `Risk_FX` rows of an `npv`, in `currency`, with sensitivity to the variables of
+/// the `fx` rates.
+///
+/// The sensitivity to each foreign currency is the change in value for a 1% appreciation of
+/// that currency against the `currency` of the `npv`.
+pub fn fx_crif(trade_id: &str, npv: &Dual, fx: &FXRates, currency: &Ccy) -> Vec<CrifRow> {
+    let mut amounts: IndexMap<Ccy, f64> = IndexMap::new();
+    for fxr in fx.fx_rates.iter() {
+        let tag = Exogenous::fx(&fxr.pair.to_string()).tag();
+        let delta = npv.gradient1(vec![tag])[0] * f64::from(&fxr.rate) * 0.01;
+        // an increase in the rate appreciates the left currency against the right
+        *amounts.entry(fxr.pair.0).or_insert(0.0) += delta;
+        *amounts.entry(fxr.pair.1).or_insert(0.0) -= delta;
+    }
+    let amount_currency = currency.name.to_uppercase();
+    amounts
+        .into_iter()
+        .filter(|(ccy, amount)| ccy != currency && *amount != 0.0)
+        .map(|(ccy, amount)| CrifRow {
+            trade_id: trade_id.to_string(),
+            risk_type: CrifRiskType::FX,
+            qualifier: ccy.name.to_uppercase(),
+            bucket: String::new(),
+            label1: String::new(),
+            label2: String::new(),
+            amount,
+            amount_currency: amount_currency.clone(),
+        })
+        .collect()
+}
+
+/// Return the `Risk_IRVol` rows of an `npv`, in `currency`, with sensitivity to volatility
+/// variables, each given by its tag and option expiry in years.
+///
+/// The sensitivity is the change in value for a shift of `unit` in each variable, which is
+/// one percentage point of volatility, allocated linearly to the adjacent SIMM tenors.
+pub fn ir_vol_crif(
+    trade_id: &str,
+    npv: &Dual,
+    vols: &[(String, f64)],
+    unit: f64,
+    currency: &Ccy,
+) -> Vec<CrifRow> {
+    let mut amounts = IndexMap::new();
+    for (tag, expiry) in vols.iter() {
+        allocate(
+            &mut amounts,
+            *expiry,
+            npv.gradient1(vec![tag.clone()])[0] * unit,
+        );
+    }
+    let ccy = currency.name.to_uppercase();
+    tenor_rows(amounts, |label, amount| CrifRow {
+        trade_id: trade_id.to_string(),
+        risk_type: CrifRiskType::IRVol,
+        qualifier: ccy.clone(),
+        bucket: String::new(),
+        label1: label.to_string(),
+        label2: String::new(),
+        amount,
+        amount_currency: ccy.clone(),
+    })
+}
+
+/// Format CRIF rows as CSV with a header row.
+pub fn crif_csv(rows: &[CrifRow]) -> String {
+    let mut csv = String::from(
+        "TradeID,ProductClass,RiskType,Qualifier,Bucket,Label1,Label2,Amount,AmountCurrency\n",
+    );
+    for r in rows.iter() {
+        csv.push_str(&format!(
+            "{},RatesFX,{},{},{},{},{},{},{}\n",
+            r.trade_id,
+            r.risk_type.name(),
+            r.qualifier,
+            r.bucket,
+            r.label1,
+            r.label2,
+            r.amount,
+            r.amount_currency
+        ));
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::ndt;
+    use crate::curves::PricingCurve;
+    use crate::dual::{ADOrder, Number};
+    use crate::fx::rates::FXRate;
+    use crate::periods::period::tests::curve_fixture;
+
+    #[test]
+    fn test_allocate() {
+        let mut amounts = IndexMap::new();
+        allocate(&mut amounts, 4.0, 10.0);
+        allocate(&mut amounts, 0.01, 1.0);
+        allocate(&mut amounts, 40.0, 2.0);
+        assert_eq!(amounts["3y"], 5.0);
+        assert_eq!(amounts["5y"], 5.0);
+        assert_eq!(amounts["2w"], 1.0);
+        assert_eq!(amounts["30y"], 2.0);
+    }
+
+    #[test]
+    fn test_ir_curve_crif() {
+        let mut curve = curve_fixture("c");
+        curve.set_ad_order(ADOrder::One).unwrap();
+        // a cashflow received in 5 years, interpolated from the node at 10 years
+        let npv = Dual::from(&(curve.df(&ndt(2029, 1, 1)) * 1e6));
+        let rows = ir_curve_crif("T1", &npv, &curve, &Ccy::try_new("eur").unwrap(), "OIS");
+        // a single node just beyond 10 years allocated mostly to the 10y tenor
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1].label1, "15y");
+        assert!(rows[1].amount.abs() < rows[0].amount.abs() * 0.01);
+        assert_eq!(
+            (rows[0].label1.as_str(), rows[0].bucket.as_str()),
+            ("10y", "1")
+        );
+        // the cashflow loses value as rates rise
+        assert!(rows[0].amount < 0.0);
+        assert!(crif_csv(&rows).starts_with("TradeID,"));
+        assert!(crif_csv(&rows)
+            .lines()
+            .nth(1)
+            .unwrap()
+            .starts_with("T1,RatesFX,Risk_IRCurve,EUR,1,10y,OIS,"));
+    }
+
+    #[test]
+    fn test_fx_crif() {
+        let fxr = FXRates::try_new(
+            vec![
+                FXRate::try_new("eur", "usd", Number::F64(1.08), None).unwrap(),
+                FXRate::try_new("usd", "jpy", Number::F64(150.0), None).unwrap(),
+            ],
+            None,
+        )
+        .unwrap();
+        let mut fxr = fxr;
+        fxr.set_ad_order(ADOrder::One).unwrap();
+        let (eur, usd, jpy) = (
+            Ccy::try_new("eur").unwrap(),
+            Ccy::try_new("usd").unwrap(),
+            Ccy::try_new("jpy").unwrap(),
+        );
+        // 1mm EUR and 100mm JPY valued in USD
+        let npv = Dual::from(
+            &(fxr.rate(&eur, &usd).unwrap() * 1e6 + fxr.rate(&jpy, &usd).unwrap() * 1e8),
+        );
+        let rows = fx_crif("T1", &npv, &fxr, &usd);
+        assert_eq!(rows.len(), 2);
+        let amount = |q: &str| rows.iter().find(|r| r.qualifier == q).unwrap().amount;
+        assert!((amount("EUR") - 10800.0).abs() < 1e-6);
+        assert!((amount("JPY") - 1e8 / 150.0 * 0.01).abs() < 1e-6);
+        assert!(rows.iter().all(|r| r.amount_currency == "USD"));
+    }
+
+    #[test]
+    fn test_ir_vol_crif() {
+        let vol = Dual::new(0.2, vec!["v1".to_string()]);
+        let npv = &vol * 1000.0;
+        let rows = ir_vol_crif(
+            "T1",
+            &npv,
+            &[("v1".to_string(), 1.5)],
+            0.01,
+            &Ccy::try_new("usd").unwrap(),
+        );
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].label1, "1y");
+        assert!((rows[0].amount - 5.0).abs() < 1e-12);
+        assert_eq!(rows[0].risk_type.name(), "Risk_IRVol");
+    }
+}
@@ -0,0 +1,632 @@
+use crate::calendars::{ndt, Convention, Modifier, NamedCal, RollDay};
+use crate::error::RateslibError;
+use crate::instruments::{BermudanSwaption, Irs};
+use crate::interop::XmlElement;
+use crate::legs::{CustomLeg, FixedLeg, FloatLeg};
+use crate::periods::{CashflowPeriod, PeriodType};
+use crate::scheduling::{Frequency, Schedule};
+use chrono::{Datelike, NaiveDate, NaiveDateTime};
+
+/// A cross-currency swap imported from FpML, with a leg in each currency.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrossCurrencySwap {
+    /// The currency and periods, including exchanges of notional, of each leg.
+    pub legs: Vec<(String, CustomLeg<PeriodType>)>,
+}
+
+/// A product imported from FpML.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FpmlProduct {
+    Swap(Irs),
+    /// A forward rate agreement represented as a single period swap paid at its end.
+    Fra(Irs),
+    CrossCurrencySwap(CrossCurrencySwap),
+    Swaption(BermudanSwaption),
+}
+
+/// A trade imported from FpML.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FpmlTrade {
+    pub trade_id: Option<String>,
+    pub product: FpmlProduct,
+}
+
+/// Parse the trades of an FpML `document` from the perspective of a `party`, referenced by
+/// its FpML identifier.
+///
+/// Supported products are fixed against floating interest rate swaps, FRAs, cross-currency
+/// swaps and European or Bermudan swaptions, with constant notionals, rates and spreads.
+/// A stream paid by the `party` has a positive notional. Exercise dates of swaptions are
+/// mapped to the first accrual start date of the underlying swap on or after them.
+pub fn parse_fpml(document: &str, party: &str) -> Result<Vec<FpmlTrade>, RateslibError> {
+    let root = XmlElement::parse(document)?;
+    let trades: Vec<&XmlElement> = match root.name.as_str() {
+        "trade" => vec![&root],
+        _ => root.children_named("trade").collect(),
+    };
+    trades.into_iter().map(|t| parse_trade(t, party)).collect()
+}
+
+fn parse_trade(trade: &XmlElement, party: &str) -> Result<FpmlTrade, RateslibError> {
+    let trade_id = trade
+        .find(&["tradeHeader", "partyTradeIdentifier", "tradeId"])
+        .map(|e| e.text.clone());
+    let product = if let Some(swap) = trade.child("swap") {
+        parse_swap(swap, party)?
+    } else if let Some(fra) = trade.child("fra") {
+        FpmlProduct::Fra(parse_fra(fra, party)?)
+    } else if let Some(swaption) = trade.child("swaption") {
+        FpmlProduct::Swaption(parse_swaption(swaption, party)?)
+    } else {
+        return Err(err("a trade has no supported product"));
+    };
+    Ok(FpmlTrade { trade_id, product })
+}
+
+fn err(message: &str) -> RateslibError {
+    RateslibError::value(format!("Unsupported FpML: {}.", message))
+}
+
+/// The rate of a stream, a fixed rate in percent or a floating spread in basis points.
+enum StreamRate {
+    Fixed(f64),
+    Floating(f64),
+}
+
+struct Stream {
+    /// `1` if paid by the party and `-1` if received.
+    sign: f64,
+    payer: String,
+    schedule: Schedule,
+    notional: f64,
+    currency: String,
+    convention: Convention,
+    rate: StreamRate,
+    initial_exchange: bool,
+    final_exchange: bool,
+    calendar: NamedCal,
+}
+
+impl Stream {
+    fn periods(&self) -> Result<Vec<PeriodType>, RateslibError> {
+        let notional = self.sign * self.notional;
+        let (s, conv, cal) = (self.schedule.clone(), self.convention, &self.calendar);
+        let mut periods = match self.rate {
+            StreamRate::Fixed(r) => {
+                FixedLeg::try_new(s, r, notional, conv, self.final_exchange, cal)?.periods
+            }
+            StreamRate::Floating(z) => {
+                FloatLeg::try_new(s, z, notional, conv, self.final_exchange, cal)?.periods
+            }
+        };
+        if self.initial_exchange {
+            let effective = self.schedule.aschedule()[0];
+            periods.insert(
+                0,
+                PeriodType::Cashflow(CashflowPeriod::new(-notional, effective)),
+            );
+        }
+        Ok(periods)
+    }
+}
+
+fn text<'a>(e: &'a XmlElement, name: &str) -> Result<&'a str, RateslibError> {
+    e.descendant(name)
+        .map(|c| c.text.as_str())
+        .ok_or_else(|| err(&format!("missing `{}`", name)))
+}
+
+fn number(e: &XmlElement, name: &str) -> Result<f64, RateslibError> {
+    text(e, name)?
+        .parse()
+        .map_err(|_| err(&format!("`{}` is not a number", name)))
+}
+
+fn date(s: &str) -> Result<NaiveDateTime, RateslibError> {
+    let d = NaiveDate::parse_from_str(s.get(..10).unwrap_or(s), "%Y-%m-%d")
+        .map_err(|_| err(&format!("invalid date `{}`", s)))?;
+    Ok(ndt(d.year(), d.month(), d.day()))
+}
+
+fn party_ref(e: &XmlElement, name: &str) -> Result<String, RateslibError> {
+    e.child(name)
+        .and_then(|r| r.attribute("href"))
+        .map(|h| h.trim_start_matches('#').to_string())
+        .ok_or_else(|| err(&format!("missing `{}`", name)))
+}
+
+fn convention(s: &str) -> Result<Convention, RateslibError> {
+    Ok(match s {
+        "ACT/360" => Convention::Act360,
+        "ACT/365.FIXED" => Convention::Act365F,
+        "ACT/ACT.ISDA" => Convention::ActActISDA,
+        "ACT/ACT.ICMA" | "ACT/ACT.ISMA" => Convention::ActActICMA,
+        "30/360" => Convention::Thirty360,
+        "30E/360" => Convention::ThirtyE360,
+        "30E/360.ISDA" => Convention::Thirty360ISDA,
+        "1/1" => Convention::One,
+        "BUS/252" => Convention::Bus252,
+        _ => return Err(err(&format!("day count fraction `{}`", s))),
+    })
+}
+
+fn modifier(s: &str) -> Result<Modifier, RateslibError> {
+    Ok(match s {
+        "NONE" => Modifier::Act,
+        "FOLLOWING" => Modifier::F,
+        "MODFOLLOWING" => Modifier::ModF,
+        "PRECEDING" => Modifier::P,
+        "MODPRECEDING" => Modifier::ModP,
+        _ => return Err(err(&format!("business day convention `{}`", s))),
+    })
+}
+
+/// Return the union of the calendars of the business centers within an element.
+fn calendar(e: Option<&XmlElement>) -> Result<NamedCal, RateslibError> {
+    let mut names: Vec<&str> = Vec::new();
+    if let Some(centers) = e.and_then(|e| e.descendant("businessCenters")) {
+        for center in centers.children_named("businessCenter") {
+            names.push(match center.text.as_str() {
+                "EUTA" => "tgt",
+                "GBLO" => "ldn",
+                "USNY" => "nyc",
+                "JPTO" => "tyo",
+                "CHZU" => "zur",
+                "SEST" => "stk",
+                "NOOS" => "osl",
+                "AUSY" => "syd",
+                "CATO" => "tro",
+                "NZWE" => "wlg",
+                c => return Err(err(&format!("business center `{}`", c))),
+            });
+        }
+    }
+    match names.is_empty() {
+        true => NamedCal::try_new("all"),
+        false => NamedCal::try_new(&names.join(",")),
+    }
+}
+
+fn frequency(e: &XmlElement) -> Result<(Frequency, RollDay), RateslibError> {
+    let multiplier = number(e, "periodMultiplier")? as u32;
+    let frequency = match text(e, "period")? {
+        "M" => Frequency::Months { number: multiplier },
+        "Y" => Frequency::Months {
+            number: 12 * multiplier,
+        },
+        "T" => Frequency::Zero {},
+        p => return Err(err(&format!("period `{}`", p))),
+    };
+    let roll = match e.descendant("rollConvention").map(|r| r.text.as_str()) {
+        None | Some("NONE") => RollDay::Unspecified {},
+        Some("EOM") => RollDay::EoM {},
+        Some("IMM") => RollDay::IMM {},
+        Some(day) => RollDay::Int {
+            day: day
+                .parse()
+                .map_err(|_| err(&format!("roll convention `{}`", day)))?,
+        },
+    };
+    Ok((frequency, roll))
+}
+
+fn parse_stream(stream: &XmlElement, party: &str) -> Result<Stream, RateslibError> {
+    let payer = party_ref(stream, "payerPartyReference")?;
+    let receiver = party_ref(stream, "receiverPartyReference")?;
+    let sign = match (payer == party, receiver == party) {
+        (true, _) => 1.0,
+        (_, true) => -1.0,
+        _ => return Err(err(&format!("`{}` is not a party to a stream", party))),
+    };
+    let dates = stream
+        .child("calculationPeriodDates")
+        .ok_or_else(|| err("missing `calculationPeriodDates`"))?;
+    let effective = date(text(
+        dates.child("effectiveDate").unwrap_or(dates),
+        "unadjustedDate",
+    )?)?;
+    let termination = date(text(
+        dates
+            .child("terminationDate")
+            .ok_or_else(|| err("missing `terminationDate`"))?,
+        "unadjustedDate",
+    )?)?;
+    let adjustments = dates.child("calculationPeriodDatesAdjustments");
+    let calendar = calendar(adjustments)?;
+    let modifier = match adjustments {
+        Some(a) => modifier(text(a, "businessDayConvention")?)?,
+        None => Modifier::Act,
+    };
+    let (frequency, roll) = frequency(
+        dates
+            .child("calculationPeriodFrequency")
+            .ok_or_else(|| err("missing `calculationPeriodFrequency`"))?,
+    )?;
+    let payment_lag = match stream.find(&["paymentDates", "paymentDaysOffset"]) {
+        Some(offset) => number(offset, "periodMultiplier")? as i8,
+        None => 0,
+    };
+    let schedule = Schedule::try_new(
+        effective,
+        termination,
+        frequency,
+        roll,
+        modifier,
+        &calendar,
+        payment_lag,
+    )?;
+
+    let calculation = stream
+        .descendant("calculation")
+        .ok_or_else(|| err("missing `calculation`"))?;
+    let notional_schedule = calculation
+        .child("notionalSchedule")
+        .ok_or_else(|| err("missing `notionalSchedule`"))?;
+    let rate = if let Some(fixed) = calculation.child("fixedRateSchedule") {
+        StreamRate::Fixed(number(fixed, "initialValue")? * 100.0)
+    } else if let Some(floating) = calculation.child("floatingRateCalculation") {
+        let spread = match floating.child("spreadSchedule") {
+            Some(s) => number(s, "initialValue")? * 10000.0,
+            None => 0.0,
+        };
+        StreamRate::Floating(spread)
+    } else {
+        return Err(err("a stream has neither a fixed nor a floating rate"));
+    };
+    let exchange = |name: &str| {
+        stream
+            .child("principalExchanges")
+            .and_then(|p| p.child(name))
+            .is_some_and(|e| e.text == "true")
+    };
+    Ok(Stream {
+        sign,
+        payer,
+        schedule,
+        notional: number(notional_schedule, "initialValue")?,
+        currency: text(notional_schedule, "currency")?.to_string(),
+        convention: convention(text(calculation, "dayCountFraction")?)?,
+        rate,
+        initial_exchange: exchange("initialExchange"),
+        final_exchange: exchange("finalExchange"),
+        calendar,
+    })
+}
+
+/// Return the fixed and floating streams of a single currency swap.
+fn fixed_floating(swap: &XmlElement, party: &str) -> Result<(Stream, Stream), RateslibError> {
+    let streams: Vec<Stream> = swap
+        .children_named("swapStream")
+        .map(|s| parse_stream(s, party))
+        .collect::<Result<_, _>>()?;
+    let mut streams = streams.into_iter();
+    match (streams.next(), streams.next(), streams.next()) {
+        (Some(a), Some(b), None) => match (&a.rate, &b.rate) {
+            (StreamRate::Fixed(_), StreamRate::Floating(_)) => Ok((a, b)),
+            (StreamRate::Floating(_), StreamRate::Fixed(_)) => Ok((b, a)),
+            _ => Err(err("a swap must have one fixed and one floating stream")),
+        },
+        _ => Err(err("a swap must have two streams")),
+    }
+}
+
+fn parse_swap(swap: &XmlElement, party: &str) -> Result<FpmlProduct, RateslibError> {
+    let streams: Vec<Stream> = swap
+        .children_named("swapStream")
+        .map(|s| parse_stream(s, party))
+        .collect::<Result<_, _>>()?;
+    if streams.len() == 2 && streams[0].currency != streams[1].currency {
+        let legs = streams
+            .iter()
+            .map(|s| Ok((s.currency.clone(), CustomLeg::new(s.periods()?))))
+            .collect::<Result<_, RateslibError>>()?;
+        return Ok(FpmlProduct::CrossCurrencySwap(CrossCurrencySwap { legs }));
+    }
+    let (fixed, floating) = fixed_floating(swap, party)?;
+    if fixed.notional != floating.notional {
+        return Err(err("the streams of a swap must have equal notionals"));
+    }
+    let (StreamRate::Fixed(rate), StreamRate::Floating(spread)) = (&fixed.rate, &floating.rate)
+    else {
+        unreachable!()
+    };
+    let notional = fixed.sign * fixed.notional;
+    Ok(FpmlProduct::Swap(Irs {
+        leg1: FixedLeg::try_new(
+            fixed.schedule.clone(),
+            *rate,
+            notional,
+            fixed.convention,
+            false,
+            &fixed.calendar,
+        )?,
+        leg2: FloatLeg::try_new(
+            floating.schedule.clone(),
+            *spread,
+            -notional,
+            floating.convention,
+            false,
+            &floating.calendar,
+        )?,
+    }))
+}
+
+fn parse_fra(fra: &XmlElement, party: &str) -> Result<Irs, RateslibError> {
+    let buyer = party_ref(fra, "buyerPartyReference")?;
+    let seller = party_ref(fra, "sellerPartyReference")?;
+    // the buyer pays the fixed rate
+    let sign = match (buyer == party, seller == party) {
+        (true, _) => 1.0,
+        (_, true) => -1.0,
+        _ => return Err(err(&format!("`{}` is not a party to a FRA", party))),
+    };
+    let calendar = calendar(fra.child("paymentDate"))?;
+    let schedule = Schedule::try_new(
+        date(text(fra, "adjustedEffectiveDate")?)?,
+        date(text(fra, "adjustedTerminationDate")?)?,
+        Frequency::Zero {},
+        RollDay::Unspecified {},
+        Modifier::Act,
+        &calendar,
+        0,
+    )?;
+    let notional = fra
+        .child("notional")
+        .ok_or_else(|| err("missing `notional`"))?;
+    let convention = convention(text(fra, "dayCountFraction")?)?;
+    Irs::try_new(
+        schedule,
+        number(fra, "fixedRate")? * 100.0,
+        sign * number(notional, "amount")?,
+        convention,
+        convention,
+        0.0,
+        &calendar,
+    )
+}
+
+fn parse_swaption(swaption: &XmlElement, party: &str) -> Result<BermudanSwaption, RateslibError> {
+    let buyer = party_ref(swaption, "buyerPartyReference")?;
+    let seller = party_ref(swaption, "sellerPartyReference")?;
+    let sign = match (buyer == party, seller == party) {
+        (true, _) => 1.0,
+        (_, true) => -1.0,
+        _ => return Err(err(&format!("`{}` is not a party to a swaption", party))),
+    };
+    let swap = swaption
+        .child("swap")
+        .ok_or_else(|| err("missing underlying `swap`"))?;
+    let (fixed, _) = fixed_floating(swap, party)?;
+    let exercise = swaption
+        .child("europeanExercise")
+        .or_else(|| swaption.child("bermudaExercise"))
+        .ok_or_else(|| err("a swaption must have European or Bermudan exercise"))?;
+    let dates: Vec<NaiveDateTime> = match exercise.descendant("bermudaExerciseDates") {
+        Some(d) => d
+            .descendant("adjustableDates")
+            .ok_or_else(|| err("missing `adjustableDates`"))?
+            .children_named("unadjustedDate")
+            .map(|e| date(&e.text))
+            .collect::<Result<_, _>>()?,
+        None => vec![date(text(exercise, "unadjustedDate")?)?],
+    };
+    let starts = &fixed.schedule.aschedule()[..fixed.schedule.n_periods()];
+    let exercise = dates
+        .iter()
+        .map(|d| {
+            starts
+                .iter()
+                .find(|s| *s >= d)
+                .copied()
+                .ok_or_else(|| err("an exercise date is after the last accrual start"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let StreamRate::Fixed(rate) = fixed.rate else {
+        unreachable!()
+    };
+    BermudanSwaption::try_new(
+        fixed.schedule.clone(),
+        rate,
+        sign * fixed.notional,
+        fixed.convention,
+        exercise,
+        fixed.payer == buyer,
+        &fixed.calendar,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::periods::Period;
+
+    fn stream(payer: &str, receiver: &str, currency: &str, rate: &str, extra: &str) -> String {
+        format!(
+            r#"<swapStream>
+                <payerPartyReference href="{payer}"/>
+                <receiverPartyReference href="{receiver}"/>
+                <calculationPeriodDates>
+                    <effectiveDate><unadjustedDate>2024-03-15</unadjustedDate></effectiveDate>
+                    <terminationDate><unadjustedDate>2026-03-15</unadjustedDate></terminationDate>
+                    <calculationPeriodDatesAdjustments>
+                        <businessDayConvention>MODFOLLOWING</businessDayConvention>
+                        <businessCenters><businessCenter>EUTA</businessCenter></businessCenters>
+                    </calculationPeriodDatesAdjustments>
+                    <calculationPeriodFrequency>
+                        <periodMultiplier>6</periodMultiplier><period>M</period>
+                        <rollConvention>15</rollConvention>
+                    </calculationPeriodFrequency>
+                </calculationPeriodDates>
+                <paymentDates><paymentDaysOffset>
+                    <periodMultiplier>2</periodMultiplier><period>D</period>
+                </paymentDaysOffset></paymentDates>
+                <calculationPeriodAmount><calculation>
+                    <notionalSchedule><notionalStepSchedule>
+                        <initialValue>10000000</initialValue><currency>{currency}</currency>
+                    </notionalStepSchedule></notionalSchedule>
+                    {rate}
+                    <dayCountFraction>ACT/360</dayCountFraction>
+                </calculation></calculationPeriodAmount>
+                {extra}
+            </swapStream>"#
+        )
+    }
+
+    const FIXED: &str = "<fixedRateSchedule><initialValue>0.025</initialValue></fixedRateSchedule>";
+    const FLOATING: &str = "<floatingRateCalculation><floatingRateIndex>EUR-EURIBOR</floatingRateIndex>\
+        <spreadSchedule><initialValue>0.001</initialValue></spreadSchedule></floatingRateCalculation>";
+
+    fn document(product: &str) -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+            <dataDocument xmlns="http://www.fpml.org/FpML-5/confirmation">
+                <trade>
+                    <tradeHeader><partyTradeIdentifier>
+                        <partyReference href="party1"/><tradeId>T1</tradeId>
+                    </partyTradeIdentifier></tradeHeader>
+                    {product}
+                </trade>
+                <party id="party1"/><party id="party2"/>
+            </dataDocument>"#
+        )
+    }
+
+    #[test]
+    fn test_swap() {
+        let swap = format!(
+            "<swap>{}{}</swap>",
+            stream("party2", "party1", "EUR", FLOATING, ""),
+            stream("party1", "party2", "EUR", FIXED, "")
+        );
+        let trades = parse_fpml(&document(&swap), "party1").unwrap();
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].trade_id.as_deref(), Some("T1"));
+        let FpmlProduct::Swap(irs) = &trades[0].product else {
+            panic!("expected a swap")
+        };
+        let cal = NamedCal::try_new("tgt").unwrap();
+        let schedule = Schedule::try_new(
+            ndt(2024, 3, 15),
+            ndt(2026, 3, 15),
+            Frequency::Months { number: 6 },
+            RollDay::Int { day: 15 },
+            Modifier::ModF,
+            &cal,
+            2,
+        )
+        .unwrap();
+        let expected = Irs::try_new(
+            schedule,
+            2.5,
+            1e7,
+            Convention::Act360,
+            Convention::Act360,
+            10.0,
+            &cal,
+        )
+        .unwrap();
+        assert_eq!(irs, &expected);
+        // the counterparty receives the fixed rate
+        let trades = parse_fpml(&document(&swap), "party2").unwrap();
+        let FpmlProduct::Swap(irs) = &trades[0].product else {
+            panic!("expected a swap")
+        };
+        assert_eq!(irs.leg1().periods[0].base().unwrap().notional(), -1e7);
+    }
+
+    #[test]
+    fn test_fra() {
+        let fra = r#"<fra>
+            <buyerPartyReference href="party2"/><sellerPartyReference href="party1"/>
+            <adjustedEffectiveDate>2024-06-17</adjustedEffectiveDate>
+            <adjustedTerminationDate>2024-09-17</adjustedTerminationDate>
+            <paymentDate><unadjustedDate>2024-06-17</unadjustedDate><dateAdjustments>
+                <businessDayConvention>FOLLOWING</businessDayConvention>
+                <businessCenters><businessCenter>GBLO</businessCenter></businessCenters>
+            </dateAdjustments></paymentDate>
+            <dayCountFraction>ACT/365.FIXED</dayCountFraction>
+            <notional><currency>GBP</currency><amount>5000000</amount></notional>
+            <fixedRate>0.0475</fixedRate>
+        </fra>"#;
+        let trades = parse_fpml(&document(fra), "party1").unwrap();
+        let FpmlProduct::Fra(irs) = &trades[0].product else {
+            panic!("expected a FRA")
+        };
+        assert_eq!(irs.leg1().periods.len(), 1);
+        let base = irs.leg1().periods[0].base().unwrap();
+        assert_eq!(
+            (base.start(), base.end()),
+            (ndt(2024, 6, 17), ndt(2024, 9, 17))
+        );
+        assert_eq!(base.notional(), -5e6);
+        assert_eq!(irs.leg1().fixed_rate, 4.75);
+    }
+
+    #[test]
+    fn test_cross_currency_swap() {
+        let exchanges = "<principalExchanges><initialExchange>true</initialExchange>\
+            <finalExchange>true</finalExchange></principalExchanges>";
+        let swap = format!(
+            "<swap>{}{}</swap>",
+            stream("party1", "party2", "EUR", FLOATING, exchanges),
+            stream("party2", "party1", "USD", FLOATING, exchanges)
+        );
+        let trades = parse_fpml(&document(&swap), "party1").unwrap();
+        let FpmlProduct::CrossCurrencySwap(xcs) = &trades[0].product else {
+            panic!("expected a cross-currency swap")
+        };
+        assert_eq!(xcs.legs[0].0, "EUR");
+        assert_eq!(xcs.legs[1].0, "USD");
+        let eur = &xcs.legs[0].1.periods;
+        // initial exchange, four floating periods and final exchange
+        assert_eq!(eur.len(), 6);
+        assert_eq!(
+            eur[0],
+            PeriodType::Cashflow(CashflowPeriod::new(-1e7, ndt(2024, 3, 15)))
+        );
+        assert_eq!(eur[5].payment(), ndt(2026, 3, 18));
+        assert_eq!(xcs.legs[1].1.periods[1].base().unwrap().notional(), -1e7);
+    }
+
+    #[test]
+    fn test_swaption() {
+        let swaption = format!(
+            r#"<swaption>
+                <buyerPartyReference href="party1"/><sellerPartyReference href="party2"/>
+                <bermudaExercise><bermudaExerciseDates><adjustableDates>
+                    <unadjustedDate>2024-03-13</unadjustedDate>
+                    <unadjustedDate>2025-03-13</unadjustedDate>
+                </adjustableDates></bermudaExerciseDates></bermudaExercise>
+                <swap>{}{}</swap>
+            </swaption>"#,
+            stream("party1", "party2", "EUR", FIXED, ""),
+            stream("party2", "party1", "EUR", FLOATING, "")
+        );
+        let trades = parse_fpml(&document(&swaption), "party1").unwrap();
+        let FpmlProduct::Swaption(option) = &trades[0].product else {
+            panic!("expected a swaption")
+        };
+        assert_eq!(option.exercise(), &[ndt(2024, 3, 15), ndt(2025, 3, 17)]);
+        assert!(option.payer);
+        assert_eq!(option.notional, 1e7);
+    }
+
+    #[test]
+    fn test_unsupported() {
+        let basis = format!(
+            "<swap>{}{}</swap>",
+            stream("party1", "party2", "EUR", FLOATING, ""),
+            stream("party2", "party1", "EUR", FLOATING, "")
+        );
+        assert!(parse_fpml(&document(&basis), "party1").is_err());
+        let swap = format!(
+            "<swap>{}{}</swap>",
+            stream("party1", "party2", "EUR", FLOATING, ""),
+            stream("party2", "party1", "EUR", FIXED, "")
+        );
+        assert!(parse_fpml(&document(&swap), "party3").is_err());
+        let swap = swap.replace("ACT/360", "ACT/364");
+        assert!(parse_fpml(&document(&swap), "party1").is_err());
+        assert!(parse_fpml(&document("<capFloor/>"), "party1").is_err());
+    }
+}
@@ -0,0 +1,107 @@
+//! Wrapper module to export the calibration of curves to market quotes to Python using pyo3
+//! bindings.
+
+use crate::cancel::CancellationToken;
+use crate::curves::curve_py::Curve;
+use crate::error::RateslibError;
+use crate::interop::{calibrate_curve, MarketQuote};
+use crate::progress::progress_py::release;
+use chrono::NaiveDateTime;
+use pyo3::prelude::*;
+
+/// Calibrate a curve to the mid-market rates of swaps, with the GIL released.
+///
+/// Parameters
+/// ----------
+/// quotes: list[tuple[str, str, float]]
+///     The `spec`, `tenor` and `rate`, in percent, of each calibrating swap.
+/// effective: datetime
+///     The initial date of the curve and effective date of the swaps.
+/// id: str
+///     The identifier of the curve.
+/// cancel: CancellationToken, optional
+///     A token by which another thread may interrupt the calibration.
+/// progress: Callable[[ProgressEvent], None], optional
+///     A callable of each `ProgressEvent` of the calibration, which aborts it by raising.
+///
+/// Returns
+/// -------
+/// tuple[Curve, int]
+///     The calibrated curve and the number of iterations of the solver.
+#[pyfunction]
+#[pyo3(name = "calibrate_curve", signature = (quotes, effective, id, cancel=None, progress=None))]
+pub(crate) fn calibrate_curve_py(
+    py: Python<'_>,
+    quotes: Vec<(String, String, f64)>,
+    effective: NaiveDateTime,
+    id: String,
+    cancel: Option<CancellationToken>,
+    progress: Option<Py<PyAny>>,
+) -> PyResult<(Curve, usize)> {
+    let quotes: Vec<MarketQuote> = quotes
+        .into_iter()
+        .map(|(spec, tenor, rate)| MarketQuote { spec, tenor, rate })
+        .collect();
+    release(py, cancel, progress, || {
+        let calibration = calibrate_curve(&quotes, effective, &id)?;
+        if !calibration.result.status.is_success() {
+            return Err(RateslibError::value(format!(
+                "The calibration of curve '{}' failed with status {:?} after {} iterations.",
+                id, calibration.result.status, calibration.result.iterations
+            )));
+        }
+        Ok((
+            Curve::from(&calibration.curve),
+            calibration.result.iterations,
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::ndt;
+    use crate::curves::PricingCurve;
+    use crate::error::error_py::CancelledError;
+    use crate::progress::{ProgressEvent, ProgressStage};
+    use pyo3::types::PyList;
+
+    #[test]
+    fn test_calibrate_curve_py() {
+        pyo3::prepare_freethreaded_python();
+        let quotes = vec![
+            ("eur_irs".to_string(), "1Y".to_string(), 3.10),
+            ("eur_irs".to_string(), "2Y".to_string(), 2.85),
+        ];
+        Python::with_gil(|py| {
+            let events = PyList::empty_bound(py);
+            let callback = events.getattr("append").unwrap().unbind();
+            let (curve, iterations) = calibrate_curve_py(
+                py,
+                quotes.clone(),
+                ndt(2024, 1, 3),
+                "eur".to_string(),
+                None,
+                Some(callback),
+            )
+            .unwrap();
+            assert_eq!(f64::from(&curve.df(&ndt(2024, 1, 3))), 1.0);
+            assert!(iterations > 0);
+            // an event is reported for each iteration of the solver
+            let event: ProgressEvent = events.get_item(0).unwrap().extract().unwrap();
+            assert_eq!(event.stage, ProgressStage::Calibration);
+            assert_eq!(events.len(), iterations);
+            let token = CancellationToken::new();
+            token.cancel();
+            let result = calibrate_curve_py(
+                py,
+                quotes,
+                ndt(2024, 1, 3),
+                "eur".to_string(),
+                Some(token),
+                None,
+            );
+            assert!(result.err().unwrap().is_instance_of::<CancelledError>(py));
+        });
+    }
+}
@@ -0,0 +1,295 @@
+use crate::calendars::{Convention, DateRoll, Modifier, NamedCal, RollDay, Tenor};
+use crate::curves::nodes::NodesTimestamp;
+use crate::curves::{evict, CurveDF, LogLinearInterpolator, Nodes};
+use crate::dual::{get_variable_tags, Dual};
+use crate::error::RateslibError;
+use crate::instruments::{get_spec, Instrument, Irs};
+use crate::json::JSON;
+use crate::periods::Curves;
+use crate::scheduling::{Frequency, Schedule};
+use crate::solver::{Solver, SolverResult, SolverSystem};
+use chrono::{Datelike, NaiveDateTime};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+/// The mid-market rate, in percent, of a calibrating instrument identified by the name of its
+/// registered [InstrumentSpec](crate::instruments::InstrumentSpec), `spec`, and its `tenor`
+/// from the effective date, such as `"18M"` or `"10Y"`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MarketQuote {
+    pub spec: String,
+    pub tenor: String,
+    pub rate: f64,
+}
+
+impl JSON for MarketQuote {}
+
+/// Return the number of months of a tenor such as `"6M"` or `"2Y"`.
+fn tenor_months(tenor: &str) -> Result<u32, RateslibError> {
+    match Tenor::try_new(tenor)? {
+        Tenor::Months(n) if n > 0 => Ok(n as u32),
+        _ => Err(RateslibError::value(format!(
+            "`tenor` '{}' is invalid.",
+            tenor.trim().to_uppercase()
+        ))),
+    }
+}
+
+/// Read [MarketQuote]s from CSV with the columns `spec`, `tenor` and `rate`.
+///
+/// A header row, blank lines and lines starting with `#` are ignored.
+pub fn read_quotes_csv(csv: &str) -> Result<Vec<MarketQuote>, RateslibError> {
+    let mut quotes = Vec::new();
+    for (i, line) in csv.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(',').map(|f| f.trim()).collect();
+        if quotes.is_empty() && fields[0].eq_ignore_ascii_case("spec") {
+            continue;
+        }
+        let invalid = || RateslibError::value(format!("Invalid quote on line {}.", i + 1));
+        let [spec, tenor, rate] = fields[..] else {
+            return Err(invalid());
+        };
+        quotes.push(MarketQuote {
+            spec: spec.to_string(),
+            tenor: tenor.to_string(),
+            rate: rate.parse().map_err(|_| invalid())?,
+        });
+    }
+    Ok(quotes)
+}
+
+/// Return the calibrating swap of a [MarketQuote] starting on an `effective` date.
+pub fn quote_instrument(
+    quote: &MarketQuote,
+    effective: NaiveDateTime,
+) -> Result<Irs, RateslibError> {
+    let spec = get_spec(&quote.spec)?;
+    let calendar = spec.named_calendar()?;
+    let months = tenor_months(&quote.tenor)?;
+    let roll = RollDay::Int {
+        day: effective.day(),
+    };
+    let termination =
+        calendar.add_months(&effective, months as i32, &Modifier::Act, &roll, false)?;
+    let frequency = match months <= spec.frequency.months() {
+        true => Frequency::Zero {},
+        false => spec.frequency,
+    };
+    let schedule = Schedule::try_new(
+        effective,
+        termination,
+        frequency,
+        roll,
+        spec.modifier,
+        &calendar,
+        spec.payment_lag,
+    )?;
+    Irs::try_new(
+        schedule,
+        quote.rate,
+        1e6,
+        spec.fixed_convention,
+        spec.float_convention,
+        0.0,
+        &calendar,
+    )
+}
+
+/// A curve calibrated to [MarketQuote]s.
+pub struct CurveCalibration {
+    /// The calibrated curve, whose discount factors carry first order sensitivity to the
+    /// node variables.
+    pub curve: CurveDF<LogLinearInterpolator, NamedCal>,
+    /// The calibrating instrument of each quote.
+    pub instruments: Vec<Irs>,
+    pub result: SolverResult,
+}
+
+/// A single curve whose node discount factors determine the rates of its instruments.
+struct QuoteSystem {
+    curve: CurveDF<LogLinearInterpolator, NamedCal>,
+    instruments: Vec<Irs>,
+    tags: Vec<String>,
+}
+
+impl SolverSystem for QuoteSystem {
+    fn variable_tags(&self) -> Vec<String> {
+        self.tags.clone()
+    }
+
+    fn variables(&self) -> Vec<f64> {
+        let Nodes::Dual(nodes) = Nodes::from(self.curve.nodes.clone()) else {
+            unreachable!()
+        };
+        nodes.values().skip(1).map(|d| d.real).collect()
+    }
+
+    fn set_variables(&mut self, values: &[f64]) -> Result<(), RateslibError> {
+        let NodesTimestamp::Dual(nodes) = &self.curve.nodes else {
+            unreachable!()
+        };
+        let keys: Vec<i64> = nodes.keys().copied().collect();
+        let mut nodes = IndexMap::from_iter([(keys[0], Dual::new(1.0, vec![]))]);
+        for ((k, v), tag) in keys[1..].iter().zip(values).zip(self.tags.iter()) {
+            nodes.insert(*k, Dual::new(*v, vec![tag.clone()]));
+        }
+        self.curve.nodes = NodesTimestamp::Dual(nodes);
+        evict(self.curve.address());
+        Ok(())
+    }
+
+    fn rates(&self) -> Result<Vec<Dual>, RateslibError> {
+        let curves = Curves::new(Some(&self.curve), Some(&self.curve));
+        self.instruments
+            .iter()
+            .map(|i| Ok(Dual::from(&i.rate(&curves)?)))
+            .collect()
+    }
+
+    fn is_valid(&self) -> bool {
+        self.variables().iter().all(|v| *v > 0.0)
+    }
+}
+
+/// Calibrate a curve, with identifier `id` and initial date `effective`, to the swaps of
+/// `quotes` with the default [Solver].
+///
+/// The curve has a node at the final payment date of each instrument, which must be
+/// distinct, and log-linear interpolation of discount factors on the calendar of the first
+/// quote.
+pub fn calibrate_curve(
+    quotes: &[MarketQuote],
+    effective: NaiveDateTime,
+    id: &str,
+) -> Result<CurveCalibration, RateslibError> {
+    if quotes.is_empty() {
+        return Err(RateslibError::value("At least one quote is required."));
+    }
+    let mut instruments = quotes
+        .iter()
+        .map(|q| quote_instrument(q, effective))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut quotes = quotes.to_vec();
+    let end = |irs: &Irs| *irs.leg1().schedule().pschedule().last().unwrap();
+    let mut order: Vec<usize> = (0..instruments.len()).collect();
+    order.sort_by_key(|i| end(&instruments[*i]));
+    instruments = order.iter().map(|i| instruments[*i].clone()).collect();
+    quotes = order.iter().map(|i| quotes[*i].clone()).collect();
+    if instruments.windows(2).any(|w| end(&w[0]) == end(&w[1])) {
+        return Err(RateslibError::value(
+            "Quotes must have distinct maturities to calibrate a curve.",
+        ));
+    }
+
+    let tags: Vec<String> = get_variable_tags(id, quotes.len() + 1)[1..].to_vec();
+    let mut nodes = IndexMap::from_iter([(effective, Dual::new(1.0, vec![]))]);
+    for ((q, irs), tag) in quotes.iter().zip(instruments.iter()).zip(tags.iter()) {
+        // initial discount factors from the quoted rates as continuously compounded
+        let t = (end(irs) - effective).num_days() as f64 / 365.0;
+        nodes.insert(
+            end(irs),
+            Dual::new((-q.rate / 100.0 * t).exp(), vec![tag.clone()]),
+        );
+    }
+    let curve = CurveDF::try_new(
+        Nodes::Dual(nodes),
+        LogLinearInterpolator::new(),
+        id,
+        Convention::Act365F,
+        Modifier::ModF,
+        None,
+        get_spec(&quotes[0].spec)?.named_calendar()?,
+    )?;
+    let mut system = QuoteSystem {
+        curve,
+        instruments,
+        tags,
+    };
+    let solver = Solver::try_new_default(quotes.iter().map(|q| q.rate).collect())?;
+    let result = solver.iterate(&mut system)?;
+    Ok(CurveCalibration {
+        curve: system.curve,
+        instruments: system.instruments,
+        result,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::ndt;
+
+    const CSV: &str = "spec,tenor,rate
+        # EUR swaps
+        eur_irs, 1Y, 3.10
+        eur_irs, 5Y, 2.65
+
+        eur_irs, 2Y, 2.85
+        eur_irs, 10Y, 2.70
+    ";
+
+    #[test]
+    fn test_read_quotes_csv() {
+        let quotes = read_quotes_csv(CSV).unwrap();
+        assert_eq!(quotes.len(), 4);
+        assert_eq!(
+            quotes[1],
+            MarketQuote {
+                spec: "eur_irs".to_string(),
+                tenor: "5Y".to_string(),
+                rate: 2.65
+            }
+        );
+        assert!(read_quotes_csv("eur_irs,1Y").is_err());
+        assert!(read_quotes_csv("eur_irs,1Y,x").is_err());
+        let json = quotes[0].to_json().unwrap();
+        assert_eq!(MarketQuote::from_json(&json).unwrap(), quotes[0]);
+    }
+
+    #[test]
+    fn test_quote_instrument() {
+        let quote = MarketQuote {
+            spec: "eur_irs".to_string(),
+            tenor: "18M".to_string(),
+            rate: 3.0,
+        };
+        let irs = quote_instrument(&quote, ndt(2024, 1, 3)).unwrap();
+        let schedule = irs.leg1().schedule();
+        assert_eq!(schedule.n_periods(), 2);
+        assert_eq!(schedule.termination(), ndt(2025, 7, 3));
+        for tenor in ["0Y", "3W", "Y"] {
+            let q = MarketQuote {
+                tenor: tenor.to_string(),
+                ..quote.clone()
+            };
+            assert!(quote_instrument(&q, ndt(2024, 1, 3)).is_err());
+        }
+        let q = MarketQuote {
+            spec: "xyz".to_string(),
+            ..quote
+        };
+        assert!(quote_instrument(&q, ndt(2024, 1, 3)).is_err());
+    }
+
+    #[test]
+    fn test_calibrate_curve() {
+        let quotes = read_quotes_csv(CSV).unwrap();
+        let effective = ndt(2024, 1, 3);
+        let calibration = calibrate_curve(&quotes, effective, "eur").unwrap();
+        assert!(calibration.result.status.is_success());
+        let curves = Curves::new(Some(&calibration.curve), Some(&calibration.curve));
+        for quote in quotes.iter() {
+            let irs = quote_instrument(quote, effective).unwrap();
+            let rate = f64::from(&irs.rate(&curves).unwrap());
+            assert!((rate - quote.rate).abs() < 1e-6);
+        }
+        let mut duplicate = quotes.clone();
+        duplicate.push(quotes[0].clone());
+        assert!(calibrate_curve(&duplicate, effective, "eur").is_err());
+        assert!(calibrate_curve(&[], effective, "eur").is_err());
+    }
+}
@@ -0,0 +1,25 @@
+//! Import trades and market data from external formats.
+//!
+//! [parse_fpml] reads a subset of FpML into instruments, using a minimal [XmlElement] parser
+//! of the document. [calibrate_curve] builds a curve from [MarketQuote]s, which may be read
+//! from CSV with [read_quotes_csv] or from JSON. Sensitivities are exported to ISDA SIMM
+//! [CrifRow]s for margin calculation.
+
+mod xml;
+pub use crate::interop::xml::XmlElement;
+
+mod fpml;
+pub use crate::interop::fpml::{parse_fpml, CrossCurrencySwap, FpmlProduct, FpmlTrade};
+
+#[cfg(feature = "python")]
+pub(crate) mod interop_py;
+
+mod market_data;
+pub use crate::interop::market_data::{
+    calibrate_curve, quote_instrument, read_quotes_csv, CurveCalibration, MarketQuote,
+};
+
+mod crif;
+pub use crate::interop::crif::{
+    crif_csv, fx_crif, ir_curve_crif, ir_vol_crif, CrifRiskType, CrifRow,
+};
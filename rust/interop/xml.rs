@@ -0,0 +1,269 @@
+use crate::error::RateslibError;
+
+/// An element of an XML document.
+///
+/// Names are stored without any namespace prefix, and text is the concatenated, trimmed and
+/// unescaped character data directly within the element.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct XmlElement {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<XmlElement>,
+    pub text: String,
+}
+
+impl XmlElement {
+    /// Parse the root element of an XML `document`.
+    ///
+    /// The parser supports elements, attributes, character data, CDATA sections and the
+    /// predefined and numeric entities, and skips declarations, processing instructions
+    /// and comments.
+    pub fn parse(document: &str) -> Result<Self, RateslibError> {
+        let mut parser = Parser {
+            src: document,
+            pos: 0,
+        };
+        parser.skip_misc()?;
+        let root = parser.element()?;
+        parser.skip_misc()?;
+        if parser.pos < parser.src.len() {
+            return Err(parser.error("content after the root element"));
+        }
+        Ok(root)
+    }
+
+    /// Return the value of an attribute, ignoring any namespace prefix.
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(k, _)| local(k) == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Return the first child element with a `name`.
+    pub fn child(&self, name: &str) -> Option<&XmlElement> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    /// Return the child elements with a `name`.
+    pub fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a XmlElement> {
+        self.children.iter().filter(move |c| c.name == name)
+    }
+
+    /// Return the first element reached by a `path` of child names.
+    pub fn find(&self, path: &[&str]) -> Option<&XmlElement> {
+        path.iter().try_fold(self, |e, name| e.child(name))
+    }
+
+    /// Return the first descendant element, depth first, with a `name`.
+    pub fn descendant(&self, name: &str) -> Option<&XmlElement> {
+        self.children.iter().find_map(|c| match c.name == name {
+            true => Some(c),
+            false => c.descendant(name),
+        })
+    }
+}
+
+fn local(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> RateslibError {
+        RateslibError::value(format!("Invalid XML at byte {}: {}.", self.pos, message))
+    }
+
+    fn rest(&self) -> &str {
+        &self.src[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let trimmed = self.rest().trim_start();
+        self.pos = self.src.len() - trimmed.len();
+    }
+
+    fn skip_past(&mut self, end: &str) -> Result<(), RateslibError> {
+        match self.rest().find(end) {
+            Some(i) => {
+                self.pos += i + end.len();
+                Ok(())
+            }
+            None => Err(self.error(&format!("missing `{}`", end))),
+        }
+    }
+
+    /// Skip whitespace, comments, processing instructions and declarations.
+    fn skip_misc(&mut self) -> Result<(), RateslibError> {
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if self.rest().starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if self.rest().starts_with("<!") {
+                self.skip_past(">")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn name(&mut self) -> Result<String, RateslibError> {
+        let end = self
+            .rest()
+            .find(|c: char| c.is_whitespace() || matches!(c, '>' | '/' | '='))
+            .unwrap_or(self.rest().len());
+        if end == 0 {
+            return Err(self.error("expected a name"));
+        }
+        let name = self.rest()[..end].to_string();
+        self.pos += end;
+        Ok(name)
+    }
+
+    fn element(&mut self) -> Result<XmlElement, RateslibError> {
+        if !self.rest().starts_with('<') {
+            return Err(self.error("expected an element"));
+        }
+        self.pos += 1;
+        let qualified = self.name()?;
+        let mut element = XmlElement {
+            name: local(&qualified).to_string(),
+            ..Default::default()
+        };
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("/>") {
+                self.pos += 2;
+                return Ok(element);
+            } else if self.rest().starts_with('>') {
+                self.pos += 1;
+                break;
+            }
+            let key = self.name()?;
+            self.skip_whitespace();
+            if !self.rest().starts_with('=') {
+                return Err(self.error("expected `=` after an attribute name"));
+            }
+            self.pos += 1;
+            self.skip_whitespace();
+            let quote = self.rest().chars().next();
+            let Some(quote @ ('"' | '\'')) = quote else {
+                return Err(self.error("expected a quoted attribute value"));
+            };
+            self.pos += 1;
+            let end = self
+                .rest()
+                .find(quote)
+                .ok_or_else(|| self.error("unterminated attribute value"))?;
+            let value = unescape(&self.rest()[..end]).map_err(|e| self.error(&e))?;
+            self.pos += end + 1;
+            element.attributes.push((key, value));
+        }
+
+        let mut text = String::new();
+        loop {
+            if self.rest().starts_with("</") {
+                self.pos += 2;
+                let closing = self.name()?;
+                if closing != qualified {
+                    return Err(self.error(&format!("`{}` closed by `{}`", qualified, closing)));
+                }
+                self.skip_whitespace();
+                if !self.rest().starts_with('>') {
+                    return Err(self.error("expected `>`"));
+                }
+                self.pos += 1;
+                element.text = text.trim().to_string();
+                return Ok(element);
+            } else if self.rest().starts_with("<![CDATA[") {
+                self.pos += 9;
+                let end = self
+                    .rest()
+                    .find("]]>")
+                    .ok_or_else(|| self.error("unterminated CDATA"))?;
+                text.push_str(&self.rest()[..end]);
+                self.pos += end + 3;
+            } else if self.rest().starts_with("<!--") || self.rest().starts_with("<?") {
+                self.skip_misc()?;
+            } else if self.rest().starts_with('<') {
+                element.children.push(self.element()?);
+            } else if self.rest().is_empty() {
+                return Err(self.error(&format!("unclosed element `{}`", qualified)));
+            } else {
+                let end = self.rest().find('<').unwrap_or(self.rest().len());
+                text.push_str(&unescape(&self.rest()[..end]).map_err(|e| self.error(&e))?);
+                self.pos += end;
+            }
+        }
+    }
+}
+
+fn unescape(s: &str) -> Result<String, String> {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(i) = rest.find('&') {
+        out.push_str(&rest[..i]);
+        let end = rest[i..]
+            .find(';')
+            .ok_or_else(|| "unterminated entity".to_string())?;
+        let entity = &rest[i + 1..i + end];
+        let c = match entity {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code = match entity.strip_prefix("#x") {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => entity.strip_prefix('#').and_then(|d| d.parse().ok()),
+                };
+                code.and_then(char::from_u32)
+                    .ok_or_else(|| format!("unknown entity `{}`", entity))?
+            }
+        };
+        out.push(c);
+        rest = &rest[i + end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let doc = r##"<?xml version="1.0"?>
+            <!-- a comment -->
+            <fpml:root xmlns:fpml="http://www.fpml.org" id='r1'>
+                <a href="#p1"/>
+                <b>x &amp; y &#65;<![CDATA[<z>]]></b>
+                <b>2</b>
+            </fpml:root>"##;
+        let root = XmlElement::parse(doc).unwrap();
+        assert_eq!(root.name, "root");
+        assert_eq!(root.attribute("id"), Some("r1"));
+        assert_eq!(root.child("a").unwrap().attribute("href"), Some("#p1"));
+        assert_eq!(root.child("b").unwrap().text, "x & y A<z>");
+        assert_eq!(root.children_named("b").count(), 2);
+        assert!(root.find(&["a", "b"]).is_none());
+        assert_eq!(root.descendant("b").unwrap().text, "x & y A<z>");
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(XmlElement::parse("<a><b></a>").is_err());
+        assert!(XmlElement::parse("<a>").is_err());
+        assert!(XmlElement::parse("<a x=1/>").is_err());
+        assert!(XmlElement::parse("<a/><b/>").is_err());
+        assert!(XmlElement::parse("<a>&bad;</a>").is_err());
+    }
+}
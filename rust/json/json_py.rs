@@ -70,6 +70,7 @@ mod tests {
     use super::*;
     use crate::dual::Dual;
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_serialized_object() {
         let x = Dual::new(2.5, vec!["x".to_string()]);
         let json = DeserializedObj::Dual(x.clone()).to_json().unwrap();
@@ -1,5 +1,6 @@
 //! Allows serialization and deserialization to JSON, with the ``serde`` crate.
 
+#[cfg(feature = "python")]
 pub mod json_py;
 
 use serde::{Deserialize, Serialize};
@@ -0,0 +1,17 @@
+//! Price instruments with early exercise by backward induction on a lattice of a short rate
+//! model.
+//!
+//! A [TrinomialTree] is fitted to a discount curve and any type implementing [LatticePayoff],
+//! which defines the cashflows of an underlying security and the value of exercising an option
+//! on it, is valued with [value_on_lattice]. Options on a lognormal spot rate, such as FX, are
+//! valued on a [SpotTree].
+
+mod trinomial;
+pub(crate) use crate::lattice::trinomial::date_at;
+pub use crate::lattice::trinomial::TrinomialTree;
+
+mod payoff;
+pub use crate::lattice::payoff::{value_on_lattice, LatticePayoff, LatticeValue};
+
+mod spot;
+pub use crate::lattice::spot::SpotTree;
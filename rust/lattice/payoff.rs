@@ -0,0 +1,169 @@
+use crate::error::RateslibError;
+use crate::lattice::TrinomialTree;
+use chrono::NaiveDateTime;
+
+/// An option with exercise on a set of dates into an underlying security with fixed
+/// cashflows.
+pub trait LatticePayoff {
+    /// Return the payment date and amount of each cashflow of the underlying security.
+    fn cashflows(&self) -> Vec<(NaiveDateTime, f64)>;
+
+    /// Return the ordered dates on which the option may be exercised.
+    fn exercise_dates(&self) -> Vec<NaiveDateTime>;
+
+    /// Return the value to the holder of exercising on the exercise date indexed by
+    /// `exercise`, given the value of the `underlying` excluding any cashflow paid on that date.
+    fn exercise_value(&self, exercise: usize, underlying: f64) -> f64;
+}
+
+/// The value of a [LatticePayoff] on a lattice.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LatticeValue {
+    /// The value of the underlying security.
+    pub underlying: f64,
+    /// The value of the option to the holder.
+    pub option: f64,
+    /// For each exercise date the lowest and highest short rate of the nodes at which
+    /// exercise is optimal, if any.
+    pub exercise_region: Vec<Option<(f64, f64)>>,
+    /// For each exercise date the risk neutral probability of the option being exercised on
+    /// that date, having not been exercised before.
+    pub exercise_probabilities: Vec<f64>,
+}
+
+/// Value a `payoff` by backward induction on a `tree`, discounting at the short rate plus a
+/// constant `spread`, as a decimal.
+///
+/// Cashflow and exercise dates are placed at the nearest step of the tree.
+pub fn value_on_lattice(
+    tree: &TrinomialTree,
+    payoff: &dyn LatticePayoff,
+    spread: f64,
+) -> Result<LatticeValue, RateslibError> {
+    let steps = tree.steps();
+    let width = tree.width();
+    let mut cashflows = vec![0.0; steps + 1];
+    for (date, amount) in payoff.cashflows() {
+        cashflows[tree.step_of(&date)?] += amount;
+    }
+    let exercise_steps = payoff
+        .exercise_dates()
+        .iter()
+        .map(|d| tree.step_of(d))
+        .collect::<Result<Vec<usize>, RateslibError>>()?;
+    let spread_df = (-spread * tree.dt()).exp();
+
+    let mut underlying = vec![cashflows[steps]; width];
+    let mut option = vec![0.0; width];
+    let mut exercised = vec![vec![false; width]; exercise_steps.len()];
+    let mut exercise_region = vec![None; exercise_steps.len()];
+    for step in (0..steps).rev() {
+        underlying = tree.rollback(&underlying, step);
+        option = tree.rollback(&option, step);
+        underlying.iter_mut().for_each(|v| *v *= spread_df);
+        option.iter_mut().for_each(|v| *v *= spread_df);
+        for (e, _) in exercise_steps
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| **s == step)
+        {
+            let mut region: Option<(f64, f64)> = None;
+            for i in 0..width {
+                let value = payoff.exercise_value(e, underlying[i]);
+                if value > option[i] {
+                    option[i] = value;
+                    exercised[e][i] = true;
+                    let r = tree.short_rate(step, i);
+                    region = Some(match region {
+                        None => (r, r),
+                        Some((lo, hi)) => (lo.min(r), hi.max(r)),
+                    });
+                }
+            }
+            exercise_region[e] = region;
+        }
+        underlying.iter_mut().for_each(|v| *v += cashflows[step]);
+    }
+
+    // forward induction of the probability of reaching each node unexercised
+    let mut exercise_probabilities = vec![0.0; exercise_steps.len()];
+    let mut probabilities = vec![0.0; width];
+    probabilities[tree.root()] = 1.0;
+    for step in 0..=exercise_steps.iter().copied().max().unwrap_or(0) {
+        for (e, _) in exercise_steps
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| **s == step)
+        {
+            for i in 0..width {
+                if exercised[e][i] {
+                    exercise_probabilities[e] += probabilities[i];
+                    probabilities[i] = 0.0;
+                }
+            }
+        }
+        if step < steps {
+            probabilities = tree.forward(&probabilities);
+        }
+    }
+
+    Ok(LatticeValue {
+        underlying: underlying[tree.root()],
+        option: option[tree.root()],
+        exercise_region,
+        exercise_probabilities,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::ndt;
+    use crate::periods::period::tests::curve_fixture;
+
+    struct ZeroBondCall {
+        strike: f64,
+    }
+
+    impl LatticePayoff for ZeroBondCall {
+        fn cashflows(&self) -> Vec<(NaiveDateTime, f64)> {
+            vec![(ndt(2029, 1, 1), 100.0)]
+        }
+
+        fn exercise_dates(&self) -> Vec<NaiveDateTime> {
+            vec![ndt(2026, 1, 1)]
+        }
+
+        fn exercise_value(&self, _exercise: usize, underlying: f64) -> f64 {
+            underlying - self.strike
+        }
+    }
+
+    #[test]
+    fn test_zero_bond_call() {
+        let curve = curve_fixture("c");
+        let tree = TrinomialTree::try_hull_white(0.05, 0.01, &curve, 5.0, 100).unwrap();
+        let payoff = ZeroBondCall { strike: 94.0 };
+        let value = value_on_lattice(&tree, &payoff, 0.0).unwrap();
+        let df = |d| f64::from(crate::curves::PricingCurve::df(&curve, &d));
+        assert!((value.underlying - 100.0 * df(ndt(2029, 1, 1))).abs() < 1e-2);
+        // the call is worth at least its discounted forward intrinsic value
+        let intrinsic = 100.0 * df(ndt(2029, 1, 1)) - 94.0 * df(ndt(2026, 1, 1));
+        assert!(value.option >= intrinsic - 1e-6);
+        let p = value.exercise_probabilities[0];
+        assert!(p > 0.0 && p < 1.0);
+        let (lo, hi) = value.exercise_region[0].unwrap();
+        assert!(lo <= hi);
+    }
+
+    #[test]
+    fn test_spread_reduces_value() {
+        let curve = curve_fixture("c");
+        let tree = TrinomialTree::try_hull_white(0.05, 0.01, &curve, 5.0, 50).unwrap();
+        let payoff = ZeroBondCall { strike: 0.0 };
+        let base = value_on_lattice(&tree, &payoff, 0.0).unwrap();
+        let spread = value_on_lattice(&tree, &payoff, 0.01).unwrap();
+        let ratio = spread.underlying / base.underlying;
+        assert!((ratio - (-0.01_f64 * 5.0).exp()).abs() < 1e-3);
+    }
+}
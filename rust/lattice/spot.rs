@@ -0,0 +1,127 @@
+use crate::error::RateslibError;
+
+/// A recombining trinomial tree of a lognormal spot rate with a constant cost of carry.
+///
+/// The spot moves by a factor `u = exp(σ √(2 dt))` up, or `1 / u` down, in each step.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpotTree {
+    spot: f64,
+    steps: usize,
+    u: f64,
+    p: [f64; 3],
+    df: f64,
+}
+
+impl SpotTree {
+    /// Create a [SpotTree] to `expiry`, in years, of `steps` equal time steps with a
+    /// discounting `rate`, a cost of `carry` and a `volatility`, as decimals per annum.
+    pub fn try_new(
+        spot: f64,
+        expiry: f64,
+        rate: f64,
+        carry: f64,
+        volatility: f64,
+        steps: usize,
+    ) -> Result<Self, RateslibError> {
+        if steps == 0 || expiry <= 0.0 || volatility <= 0.0 || spot <= 0.0 {
+            return Err(RateslibError::value(
+                "A `SpotTree` requires positive `spot`, `expiry`, `volatility` and `steps`.",
+            ));
+        }
+        let dt = expiry / steps as f64;
+        let a = (carry * dt / 2.0).exp();
+        let (eu, ed) = (
+            (volatility * (dt / 2.0).sqrt()).exp(),
+            (-volatility * (dt / 2.0).sqrt()).exp(),
+        );
+        let pu = ((a - ed) / (eu - ed)).powi(2);
+        let pd = ((eu - a) / (eu - ed)).powi(2);
+        Ok(Self {
+            spot,
+            steps,
+            u: (volatility * (2.0 * dt).sqrt()).exp(),
+            p: [pd, 1.0 - pu - pd, pu],
+            df: (-rate * dt).exp(),
+        })
+    }
+
+    /// Return the spot at node `j`, from `-step` to `step`, of a step.
+    fn node(&self, j: i64) -> f64 {
+        self.spot * self.u.powi(j as i32)
+    }
+
+    /// Return the values at the first two steps, which are one and three nodes wide, of a
+    /// derivative with terminal `payoff`, exercisable at every step if `early_exercise`.
+    fn rollback<F: Fn(f64) -> f64>(&self, payoff: &F, early_exercise: bool) -> Vec<Vec<f64>> {
+        let n = self.steps as i64;
+        let mut values: Vec<f64> = (-n..=n).map(|j| payoff(self.node(j))).collect();
+        let mut history = Vec::new();
+        for step in (0..n).rev() {
+            values = (0..(2 * step + 1) as usize)
+                .map(|i| {
+                    let continuation = self.df
+                        * (self.p[0] * values[i]
+                            + self.p[1] * values[i + 1]
+                            + self.p[2] * values[i + 2]);
+                    match early_exercise {
+                        true => continuation.max(payoff(self.node(i as i64 - step))),
+                        false => continuation,
+                    }
+                })
+                .collect();
+            if step <= 1 {
+                history.push(values.clone());
+            }
+        }
+        history.reverse();
+        history
+    }
+
+    /// Return the value of a derivative with terminal `payoff`, exercisable at every step if
+    /// `early_exercise`.
+    pub fn value<F: Fn(f64) -> f64>(&self, payoff: F, early_exercise: bool) -> f64 {
+        self.rollback(&payoff, early_exercise)[0][0]
+    }
+
+    /// Return the value, delta and gamma of a derivative, measured from the nodes of the
+    /// first step.
+    pub fn value_delta_gamma<F: Fn(f64) -> f64>(
+        &self,
+        payoff: F,
+        early_exercise: bool,
+    ) -> (f64, f64, f64) {
+        let history = self.rollback(&payoff, early_exercise);
+        let value = history[0][0];
+        if history.len() < 2 {
+            return (value, f64::NAN, f64::NAN);
+        }
+        let v = &history[1];
+        let (sd, sm, su) = (self.node(-1), self.spot, self.node(1));
+        let delta_up = (v[2] - v[1]) / (su - sm);
+        let delta_down = (v[1] - v[0]) / (sm - sd);
+        let delta = (v[2] - v[0]) / (su - sd);
+        let gamma = (delta_up - delta_down) / ((su - sd) / 2.0);
+        (value, delta, gamma)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_european_convergence() {
+        // Black-Scholes value of a 1y ATM call, S=K=100, r=5%, q=0, vol=20%
+        let tree = SpotTree::try_new(100.0, 1.0, 0.05, 0.05, 0.2, 400).unwrap();
+        let value = tree.value(|s| (s - 100.0).max(0.0), false);
+        assert!((value - 10.450583572185565).abs() < 5e-3);
+    }
+
+    #[test]
+    fn test_probabilities() {
+        let tree = SpotTree::try_new(1.1, 0.5, 0.04, 0.02, 0.1, 50).unwrap();
+        assert!(tree.p.iter().all(|p| *p > 0.0));
+        assert!((tree.p.iter().sum::<f64>() - 1.0).abs() < 1e-14);
+        assert!(SpotTree::try_new(1.1, 0.5, 0.04, 0.02, 0.1, 0).is_err());
+    }
+}
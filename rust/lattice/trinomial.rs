@@ -0,0 +1,242 @@
+use crate::curves::PricingCurve;
+use crate::error::RateslibError;
+use chrono::{Duration, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+/// Return the date a number of years, measured as Act365F, after an initial date.
+pub(crate) fn date_at(initial: &NaiveDateTime, t: f64) -> NaiveDateTime {
+    *initial + Duration::seconds((t * 365.0 * 86400.0).round() as i64)
+}
+
+/// The branching of a node to the nodes `k - 1`, `k` and `k + 1` with probabilities `p`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Branch {
+    k: i64,
+    p: [f64; 3],
+}
+
+/// A trinomial tree of the short rate fitted to a discount curve.
+///
+/// Node `j` at step `m` has a short rate, for the period `dt`, of `alpha[m] + j dx`. Values
+/// on the tree are indexed by `j + jmax`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrinomialTree {
+    pub(crate) initial: NaiveDateTime,
+    pub(crate) dt: f64,
+    pub(crate) dx: f64,
+    pub(crate) jmax: i64,
+    pub(crate) alpha: Vec<f64>,
+    branching: Vec<Branch>,
+}
+
+impl TrinomialTree {
+    /// Build a tree of the Hull-White short rate model with `mean_reversion`, `a`, and
+    /// normal `volatility`, `σ`, of `steps` equal time steps up to `horizon` years from the
+    /// initial date of the `curve`.
+    ///
+    /// The drift of each step is determined by forward induction of Arrow-Debreu prices so
+    /// that the tree reprices the discount factors of the `curve`.
+    pub fn try_hull_white(
+        mean_reversion: f64,
+        volatility: f64,
+        curve: &dyn PricingCurve,
+        horizon: f64,
+        steps: usize,
+    ) -> Result<Self, RateslibError> {
+        if steps == 0 || horizon <= 0.0 {
+            return Err(RateslibError::value(
+                "A tree requires a positive `horizon` and number of `steps`.",
+            ));
+        }
+        let dt = horizon / steps as f64;
+        let dx = volatility * (3.0 * dt).sqrt();
+        let m = -mean_reversion * dt;
+        let jmax = ((0.184 / (mean_reversion * dt)).ceil() as i64).max(1);
+        let width = (2 * jmax + 1) as usize;
+
+        let branching: Vec<Branch> = (-jmax..=jmax)
+            .map(|j| {
+                let (jf, m2) = (j as f64, (j * j) as f64 * m * m);
+                if jmax == 0 {
+                    Branch {
+                        k: 0,
+                        p: [0.0, 1.0, 0.0],
+                    }
+                } else if j == jmax {
+                    Branch {
+                        k: j - 1,
+                        p: [
+                            (m2 + jf * m) / 2.0 + 1.0 / 6.0,
+                            -m2 - 2.0 * jf * m - 1.0 / 3.0,
+                            (m2 + 3.0 * jf * m) / 2.0 + 7.0 / 6.0,
+                        ],
+                    }
+                } else if j == -jmax {
+                    Branch {
+                        k: j + 1,
+                        p: [
+                            (m2 - 3.0 * jf * m) / 2.0 + 7.0 / 6.0,
+                            -m2 + 2.0 * jf * m - 1.0 / 3.0,
+                            (m2 - jf * m) / 2.0 + 1.0 / 6.0,
+                        ],
+                    }
+                } else {
+                    Branch {
+                        k: j,
+                        p: [
+                            (m2 - jf * m) / 2.0 + 1.0 / 6.0,
+                            2.0 / 3.0 - m2,
+                            (m2 + jf * m) / 2.0 + 1.0 / 6.0,
+                        ],
+                    }
+                }
+            })
+            .collect();
+
+        let initial = curve.initial_date();
+        let df = |t: f64| -> f64 { f64::from(curve.df(&date_at(&initial, t))) };
+        let mut alpha = Vec::with_capacity(steps);
+        // Arrow-Debreu prices of each node
+        let mut q = vec![0.0; width];
+        q[jmax as usize] = 1.0;
+        for step in 0..steps {
+            let p_next = df((step + 1) as f64 * dt);
+            let sum: f64 = (0..width)
+                .map(|i| q[i] * (-((i as i64 - jmax) as f64) * dx * dt).exp())
+                .sum();
+            let a = (sum / p_next).ln() / dt;
+            alpha.push(a);
+            let mut q_next = vec![0.0; width];
+            for (i, branch) in branching.iter().enumerate() {
+                if q[i] == 0.0 {
+                    continue;
+                }
+                let r = a + (i as i64 - jmax) as f64 * dx;
+                let discounted = q[i] * (-r * dt).exp();
+                for (n, p) in branch.p.iter().enumerate() {
+                    q_next[(branch.k - 1 + n as i64 + jmax) as usize] += p * discounted;
+                }
+            }
+            q = q_next;
+        }
+        Ok(TrinomialTree {
+            initial,
+            dt,
+            dx,
+            jmax,
+            alpha,
+            branching,
+        })
+    }
+
+    /// Return the number of time steps of the tree.
+    pub fn steps(&self) -> usize {
+        self.alpha.len()
+    }
+
+    /// Return the number of nodes at each step.
+    pub fn width(&self) -> usize {
+        (2 * self.jmax + 1) as usize
+    }
+
+    /// Return the time step, in years.
+    pub fn dt(&self) -> f64 {
+        self.dt
+    }
+
+    /// Return the step nearest to a `date`, which must be within the horizon of the tree.
+    pub fn step_of(&self, date: &NaiveDateTime) -> Result<usize, RateslibError> {
+        let t = (*date - self.initial).num_seconds() as f64 / (365.0 * 86400.0);
+        let step = (t / self.dt).round();
+        if step < 0.0 || step > self.steps() as f64 {
+            return Err(RateslibError::value(
+                "`date` is outside of the horizon of the tree.",
+            ));
+        }
+        Ok(step as usize)
+    }
+
+    /// Return the node index of the initial node.
+    pub fn root(&self) -> usize {
+        self.jmax as usize
+    }
+
+    /// Return the short rate at node index `i` of `step`.
+    pub fn short_rate(&self, step: usize, i: usize) -> f64 {
+        self.alpha[step] + (i as i64 - self.jmax) as f64 * self.dx
+    }
+
+    /// Return the values at `step` of a security with `values` at `step + 1`, discounted
+    /// over the time step at the short rate of each node.
+    pub fn rollback(&self, values: &[f64], step: usize) -> Vec<f64> {
+        self.branching
+            .iter()
+            .enumerate()
+            .map(|(i, branch)| {
+                let expected: f64 = branch
+                    .p
+                    .iter()
+                    .enumerate()
+                    .map(|(n, p)| p * values[(branch.k - 1 + n as i64 + self.jmax) as usize])
+                    .sum();
+                expected * (-self.short_rate(step, i) * self.dt).exp()
+            })
+            .collect()
+    }
+
+    /// Return the risk neutral probabilities of each node at the next step given the
+    /// `probabilities` of each node at a step.
+    pub fn forward(&self, probabilities: &[f64]) -> Vec<f64> {
+        let mut next = vec![0.0; self.width()];
+        for (branch, q) in self.branching.iter().zip(probabilities.iter()) {
+            for (n, p) in branch.p.iter().enumerate() {
+                next[(branch.k - 1 + n as i64 + self.jmax) as usize] += p * q;
+            }
+        }
+        next
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::ndt;
+    use crate::periods::period::tests::curve_fixture;
+
+    #[test]
+    fn test_tree_reprices_curve() {
+        let curve = curve_fixture("c");
+        let tree = TrinomialTree::try_hull_white(0.05, 0.01, &curve, 5.0, 100).unwrap();
+        let mut values = vec![1.0; tree.width()];
+        for step in (0..tree.steps()).rev() {
+            values = tree.rollback(&values, step);
+        }
+        let df = f64::from(curve.df(&date_at(&ndt(2024, 1, 1), 5.0)));
+        assert!((values[tree.root()] - df).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_branch_probabilities() {
+        let curve = curve_fixture("c");
+        let tree = TrinomialTree::try_hull_white(0.1, 0.015, &curve, 10.0, 40).unwrap();
+        for branch in tree.branching.iter() {
+            assert!((branch.p.iter().sum::<f64>() - 1.0).abs() < 1e-14);
+            assert!(branch.p.iter().all(|p| *p >= 0.0));
+        }
+        assert_eq!(tree.step_of(&ndt(2026, 12, 31)).unwrap(), 12);
+        assert!(tree.step_of(&ndt(2035, 1, 1)).is_err());
+    }
+
+    #[test]
+    fn test_forward_conserves_probability() {
+        let curve = curve_fixture("c");
+        let tree = TrinomialTree::try_hull_white(0.05, 0.01, &curve, 5.0, 20).unwrap();
+        let mut probabilities = vec![0.0; tree.width()];
+        probabilities[tree.root()] = 1.0;
+        for _ in 0..tree.steps() {
+            probabilities = tree.forward(&probabilities);
+        }
+        assert!((probabilities.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!(probabilities[0] > 0.0);
+    }
+}
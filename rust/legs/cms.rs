@@ -0,0 +1,74 @@
+use crate::calendars::{Convention, DateRoll};
+use crate::dual::Number;
+use crate::error::RateslibError;
+use crate::legs::leg::base_periods;
+use crate::legs::Leg;
+use crate::periods::{CmsIndex, CmsMethod, CmsPeriod, PeriodType};
+use crate::scheduling::Schedule;
+use serde::{Deserialize, Serialize};
+
+/// A leg of [CmsPeriod]s on a common [CmsIndex] generated from a [Schedule].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CmsLeg {
+    pub(crate) schedule: Schedule,
+    pub(crate) index: CmsIndex,
+    pub(crate) spread: f64,
+    pub(crate) periods: Vec<PeriodType>,
+}
+
+impl CmsLeg {
+    /// Create a [CmsLeg], with a `spread` in bps and a lognormal `volatility` of the swap
+    /// rate common to every period, measuring day count fractions with the `convention` and
+    /// `calendar`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_new<U: DateRoll>(
+        schedule: Schedule,
+        index: CmsIndex,
+        volatility: Number,
+        method: CmsMethod,
+        spread: f64,
+        notional: f64,
+        convention: Convention,
+        calendar: &U,
+    ) -> Result<Self, RateslibError> {
+        let periods: Vec<PeriodType> = base_periods(&schedule, notional, convention, calendar)?
+            .into_iter()
+            .map(|base| {
+                PeriodType::Cms(CmsPeriod::new(
+                    base,
+                    index,
+                    volatility.clone(),
+                    method,
+                    spread,
+                    None,
+                ))
+            })
+            .collect();
+        Ok(Self {
+            schedule,
+            index,
+            spread,
+            periods,
+        })
+    }
+
+    pub fn schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    pub fn index(&self) -> CmsIndex {
+        self.index
+    }
+
+    pub fn spread(&self) -> f64 {
+        self.spread
+    }
+}
+
+impl Leg for CmsLeg {
+    type P = PeriodType;
+
+    fn periods(&self) -> &[PeriodType] {
+        &self.periods
+    }
+}
@@ -0,0 +1,108 @@
+use crate::calendars::{Convention, DateRoll};
+use crate::error::RateslibError;
+use crate::legs::leg::base_periods;
+use crate::legs::Leg;
+use crate::periods::{CreditPremiumPeriod, CreditProtectionPeriod, PeriodType, Recovery};
+use crate::scheduling::Schedule;
+use serde::{Deserialize, Serialize};
+
+/// A leg of [CreditPremiumPeriod]s generated from a [Schedule], paying a fixed rate conditional
+/// on survival.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CreditPremiumLeg {
+    pub(crate) schedule: Schedule,
+    pub(crate) fixed_rate: f64,
+    pub(crate) periods: Vec<PeriodType>,
+}
+
+impl CreditPremiumLeg {
+    /// Create a [CreditPremiumLeg], measuring day count fractions with the `convention` and
+    /// `calendar`, and valuing the premium accrued up to default if `premium_accrued`.
+    pub fn try_new<U: DateRoll>(
+        schedule: Schedule,
+        fixed_rate: f64,
+        notional: f64,
+        convention: Convention,
+        premium_accrued: bool,
+        calendar: &U,
+    ) -> Result<Self, RateslibError> {
+        let periods = base_periods(&schedule, notional, convention, calendar)?
+            .into_iter()
+            .map(|base| {
+                PeriodType::CreditPremium(CreditPremiumPeriod::new(
+                    base,
+                    fixed_rate,
+                    premium_accrued,
+                ))
+            })
+            .collect();
+        Ok(Self {
+            schedule,
+            fixed_rate,
+            periods,
+        })
+    }
+
+    pub fn schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    pub fn fixed_rate(&self) -> f64 {
+        self.fixed_rate
+    }
+}
+
+impl Leg for CreditPremiumLeg {
+    type P = PeriodType;
+
+    fn periods(&self) -> &[PeriodType] {
+        &self.periods
+    }
+}
+
+/// A leg of [CreditProtectionPeriod]s over the accrual periods of a [Schedule], paying the loss
+/// given default of the notional on a default before its termination.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CreditProtectionLeg {
+    pub(crate) schedule: Schedule,
+    pub(crate) recovery: Recovery,
+    pub(crate) periods: Vec<PeriodType>,
+}
+
+impl CreditProtectionLeg {
+    pub fn new(schedule: Schedule, notional: f64, recovery: Recovery) -> Self {
+        let periods = schedule
+            .periods()
+            .into_iter()
+            .map(|(start, end, _, _)| {
+                PeriodType::CreditProtection(CreditProtectionPeriod::new(
+                    start,
+                    end,
+                    notional,
+                    recovery.clone(),
+                ))
+            })
+            .collect();
+        Self {
+            schedule,
+            recovery,
+            periods,
+        }
+    }
+
+    pub fn schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    pub fn recovery(&self) -> &Recovery {
+        &self.recovery
+    }
+}
+
+impl Leg for CreditProtectionLeg {
+    type P = PeriodType;
+
+    fn periods(&self) -> &[PeriodType] {
+        &self.periods
+    }
+}
@@ -0,0 +1,95 @@
+use crate::calendars::{Convention, DateRoll};
+use crate::error::RateslibError;
+use crate::legs::leg::base_periods;
+use crate::legs::Leg;
+use crate::periods::{CashflowPeriod, FixedPeriod, PeriodType};
+use crate::scheduling::Schedule;
+use serde::{Deserialize, Serialize};
+
+/// A leg of [FixedPeriod]s generated from a [Schedule], optionally with a final exchange of
+/// notional.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FixedLeg {
+    pub(crate) schedule: Schedule,
+    pub(crate) fixed_rate: f64,
+    pub(crate) periods: Vec<PeriodType>,
+}
+
+impl FixedLeg {
+    /// Create a [FixedLeg], measuring day count fractions with the `convention` and `calendar`.
+    pub fn try_new<U: DateRoll>(
+        schedule: Schedule,
+        fixed_rate: f64,
+        notional: f64,
+        convention: Convention,
+        final_exchange: bool,
+        calendar: &U,
+    ) -> Result<Self, RateslibError> {
+        let mut periods: Vec<PeriodType> = base_periods(&schedule, notional, convention, calendar)?
+            .into_iter()
+            .map(|base| PeriodType::Fixed(FixedPeriod::new(base, fixed_rate)))
+            .collect();
+        if final_exchange {
+            let payment = *schedule.pschedule().last().unwrap();
+            periods.push(PeriodType::Cashflow(CashflowPeriod::new(notional, payment)));
+        }
+        Ok(Self {
+            schedule,
+            fixed_rate,
+            periods,
+        })
+    }
+
+    pub fn schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    pub fn fixed_rate(&self) -> f64 {
+        self.fixed_rate
+    }
+}
+
+impl Leg for FixedLeg {
+    type P = PeriodType;
+
+    fn periods(&self) -> &[PeriodType] {
+        &self.periods
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::{ndt, Modifier, NamedCal, RollDay};
+    use crate::periods::period::tests::{curve_fixture, is_close};
+    use crate::periods::Curves;
+    use crate::scheduling::Frequency;
+
+    #[test]
+    fn test_fixed_leg() {
+        let cal = NamedCal::try_new("all").unwrap();
+        let schedule = Schedule::try_new(
+            ndt(2024, 1, 1),
+            ndt(2026, 1, 1),
+            Frequency::Months { number: 12 },
+            RollDay::Unspecified {},
+            Modifier::Act,
+            &cal,
+            0,
+        )
+        .unwrap();
+        let leg = FixedLeg::try_new(schedule, 2.0, 1e6, Convention::Act365F, true, &cal).unwrap();
+        assert_eq!(leg.periods().len(), 3);
+
+        let curve = curve_fixture("c");
+        let curves = Curves::new(None, Some(&curve));
+        let (df1, df2) = (
+            (-0.02_f64 * 366.0 / 365.0).exp(),
+            (-0.02_f64 * 731.0 / 365.0).exp(),
+        );
+        let expected = -1e6 * 0.02 * (366.0 / 365.0 * df1 + 365.0 / 365.0 * df2) - 1e6 * df2;
+        assert!(is_close(&leg.npv(&curves).unwrap(), expected));
+        let expected = 1e6 * 0.0001 * (366.0 / 365.0 * df1 + df2);
+        assert!(is_close(&leg.analytic_delta(&curves).unwrap(), expected));
+    }
+}
@@ -0,0 +1,129 @@
+use crate::calendars::{Convention, DateRoll};
+use crate::error::RateslibError;
+use crate::legs::leg::base_periods;
+use crate::legs::Leg;
+use crate::periods::{CashflowPeriod, FloatPeriod, PeriodType};
+use crate::scheduling::Schedule;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// A leg of [FloatPeriod]s generated from a [Schedule], optionally with a final exchange of
+/// notional.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FloatLeg {
+    pub(crate) schedule: Schedule,
+    pub(crate) float_spread: f64,
+    pub(crate) periods: Vec<PeriodType>,
+}
+
+impl FloatLeg {
+    /// Create a [FloatLeg], with a `float_spread` in bps, measuring day count fractions with
+    /// the `convention` and `calendar`.
+    pub fn try_new<U: DateRoll>(
+        schedule: Schedule,
+        float_spread: f64,
+        notional: f64,
+        convention: Convention,
+        final_exchange: bool,
+        calendar: &U,
+    ) -> Result<Self, RateslibError> {
+        let mut periods: Vec<PeriodType> = base_periods(&schedule, notional, convention, calendar)?
+            .into_iter()
+            .map(|base| PeriodType::Float(FloatPeriod::new(base, float_spread, None)))
+            .collect();
+        if final_exchange {
+            let payment = *schedule.pschedule().last().unwrap();
+            periods.push(PeriodType::Cashflow(CashflowPeriod::new(notional, payment)));
+        }
+        Ok(Self {
+            schedule,
+            float_spread,
+            periods,
+        })
+    }
+
+    pub fn schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    pub fn float_spread(&self) -> f64 {
+        self.float_spread
+    }
+
+    /// Return the leg with each period split at the index change `dates` within it.
+    pub fn with_change_dates(mut self, dates: &[NaiveDateTime]) -> Self {
+        for period in self.periods.iter_mut() {
+            if let PeriodType::Float(p) = period {
+                *p = p.clone().with_change_dates(dates);
+            }
+        }
+        self
+    }
+}
+
+impl Leg for FloatLeg {
+    type P = PeriodType;
+
+    fn periods(&self) -> &[PeriodType] {
+        &self.periods
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::{ndt, Modifier, NamedCal, RollDay};
+    use crate::periods::period::tests::{curve_fixture, is_close};
+    use crate::periods::Curves;
+    use crate::scheduling::Frequency;
+
+    #[test]
+    fn test_float_leg_par_with_exchange() {
+        // a floating leg with notional exchange, forecast and discounted on the same curve,
+        // is valued at par
+        let cal = NamedCal::try_new("all").unwrap();
+        let schedule = Schedule::try_new(
+            ndt(2024, 1, 1),
+            ndt(2027, 1, 1),
+            Frequency::Months { number: 6 },
+            RollDay::Unspecified {},
+            Modifier::Act,
+            &cal,
+            0,
+        )
+        .unwrap();
+        let leg = FloatLeg::try_new(schedule, 0.0, 1e6, Convention::Act365F, true, &cal).unwrap();
+        let curve = curve_fixture("c");
+        let curves = Curves::new(Some(&curve), Some(&curve));
+        assert!((f64::from(&leg.npv(&curves).unwrap()) + 1e6).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_float_leg_spread() {
+        let cal = NamedCal::try_new("all").unwrap();
+        let schedule = Schedule::try_new(
+            ndt(2024, 1, 1),
+            ndt(2025, 1, 1),
+            Frequency::Zero {},
+            RollDay::Unspecified {},
+            Modifier::Act,
+            &cal,
+            0,
+        )
+        .unwrap();
+        let leg = FloatLeg::try_new(schedule, 10.0, 1e6, Convention::Act365F, false, &cal).unwrap();
+        let curve = curve_fixture("c");
+        let curves = Curves::new(Some(&curve), Some(&curve));
+        let df = (-0.02_f64 * 366.0 / 365.0).exp();
+        let expected = -1e6 * (1.0 - df) - 1e6 * 366.0 / 365.0 * 0.001 * df;
+        assert!(is_close(&leg.npv(&curves).unwrap(), expected));
+        let split = leg
+            .clone()
+            .with_change_dates(&[ndt(2024, 3, 21), ndt(2024, 6, 13)]);
+        let PeriodType::Float(p) = &split.periods[0] else {
+            panic!("the leg has a floating period")
+        };
+        assert_eq!(p.sub_periods().len(), 3);
+        assert!(is_close(&split.npv(&curves).unwrap(), expected));
+    }
+}
@@ -0,0 +1,133 @@
+use crate::calendars::{Convention, DateRoll, RollDay};
+use crate::dual::{Number, Reduction};
+use crate::error::RateslibError;
+use crate::periods::{BasePeriod, Curves, Period};
+use crate::scheduling::{Frequency, Schedule};
+use chrono::NaiveDateTime;
+
+/// A sequence of periods valued against a common set of curves.
+pub trait Leg {
+    type P: Period;
+
+    /// Return the periods of the leg.
+    fn periods(&self) -> &[Self::P];
+
+    /// Return the NPV of the leg as the compensated sum of the NPVs of its periods.
+    fn npv(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        let npvs: Vec<Number> = self
+            .periods()
+            .iter()
+            .map(|p| p.npv(curves))
+            .collect::<Result<_, _>>()?;
+        Reduction::Compensated.sum(&npvs)
+    }
+
+    /// Return the analytic delta of the leg as the compensated sum of the analytic deltas of
+    /// its periods.
+    fn analytic_delta(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        let deltas: Vec<Number> = self
+            .periods()
+            .iter()
+            .map(|p| p.analytic_delta(curves))
+            .collect::<Result<_, _>>()?;
+        Reduction::Compensated.sum(&deltas)
+    }
+
+    /// Return the payment date and cashflow of each period.
+    fn cashflows(&self, curves: &Curves) -> Result<Vec<(NaiveDateTime, Number)>, RateslibError> {
+        self.periods()
+            .iter()
+            .map(|p| Ok((p.payment(), p.cashflow(curves)?)))
+            .collect()
+    }
+}
+
+/// A leg composed of an arbitrary sequence of periods of a single type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomLeg<P: Period> {
+    pub(crate) periods: Vec<P>,
+}
+
+impl<P: Period> CustomLeg<P> {
+    pub fn new(periods: Vec<P>) -> Self {
+        Self { periods }
+    }
+}
+
+impl<P: Period> Leg for CustomLeg<P> {
+    type P = P;
+
+    fn periods(&self) -> &[P] {
+        &self.periods
+    }
+}
+
+/// Return a [BasePeriod] for each period of a `schedule`.
+pub(crate) fn base_periods<U: DateRoll>(
+    schedule: &Schedule,
+    notional: f64,
+    convention: Convention,
+    calendar: &U,
+) -> Result<Vec<BasePeriod>, RateslibError> {
+    let termination = schedule.termination();
+    let frequency_months = match schedule.frequency() {
+        Frequency::Months { number } => Some(number),
+        Frequency::Zero {} => None,
+    };
+    schedule
+        .periods()
+        .into_iter()
+        .map(|(start, end, payment, stub)| {
+            let dcf = convention.dcf(
+                &start,
+                &end,
+                Some(&termination),
+                frequency_months,
+                Some(stub),
+                &RollDay::Unspecified {},
+                calendar,
+            )?;
+            Ok(BasePeriod::new(
+                start, end, payment, notional, convention, dcf, stub,
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::ndt;
+    use crate::periods::period::tests::{curve_fixture, is_close};
+    use crate::periods::{CashflowPeriod, FixedPeriod};
+
+    #[test]
+    fn test_custom_leg() {
+        let curve = curve_fixture("c");
+        let curves = Curves::new(None, Some(&curve));
+        let leg = CustomLeg::new(vec![
+            CashflowPeriod::new(1e6, ndt(2025, 1, 1)),
+            CashflowPeriod::new(-1e6, ndt(2026, 1, 1)),
+        ]);
+        let expected =
+            -1e6 * (-0.02_f64 * 366.0 / 365.0).exp() + 1e6 * (-0.02_f64 * 731.0 / 365.0).exp();
+        assert!(is_close(&leg.npv(&curves).unwrap(), expected));
+        assert_eq!(leg.cashflows(&curves).unwrap().len(), 2);
+        assert!(is_close(&leg.analytic_delta(&curves).unwrap(), 0.0));
+    }
+
+    #[test]
+    fn test_custom_leg_missing_curve() {
+        let base = BasePeriod::new(
+            ndt(2024, 1, 1),
+            ndt(2025, 1, 1),
+            ndt(2025, 1, 1),
+            1e6,
+            Convention::One,
+            1.0,
+            false,
+        );
+        let leg = CustomLeg::new(vec![FixedPeriod::new(base, 1.0)]);
+        assert!(leg.npv(&Curves::default()).is_err());
+    }
+}
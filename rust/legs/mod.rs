@@ -0,0 +1,23 @@
+//! Create legs, which are sequences of periods valued against a common set of curves.
+//!
+//! Every leg implements the [Leg] trait. [FixedLeg], [FloatLeg], [CmsLeg], [ZeroFixedLeg] and the
+//! credit legs are generated from a [Schedule](crate::scheduling::Schedule), whilst a
+//! [CustomLeg] composes any type implementing [Period](crate::periods::Period).
+
+mod leg;
+pub use crate::legs::leg::{CustomLeg, Leg};
+
+mod fixed;
+pub use crate::legs::fixed::FixedLeg;
+
+mod float;
+pub use crate::legs::float::FloatLeg;
+
+mod cms;
+pub use crate::legs::cms::CmsLeg;
+
+mod zero;
+pub use crate::legs::zero::ZeroFixedLeg;
+
+mod credit;
+pub use crate::legs::credit::{CreditPremiumLeg, CreditProtectionLeg};
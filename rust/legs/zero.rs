@@ -0,0 +1,111 @@
+use crate::calendars::{Convention, DateRoll};
+use crate::dual::compensated_f64;
+use crate::error::RateslibError;
+use crate::legs::leg::base_periods;
+use crate::legs::Leg;
+use crate::periods::{BasePeriod, ZeroFixedPeriod, ZeroQuote};
+use crate::scheduling::Schedule;
+use serde::{Deserialize, Serialize};
+
+/// A leg paying a single [ZeroFixedPeriod], compounding a fixed rate over the periods of a
+/// [Schedule] to a payment at its final payment date.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ZeroFixedLeg {
+    pub(crate) schedule: Schedule,
+    pub(crate) periods: Vec<ZeroFixedPeriod>,
+}
+
+impl ZeroFixedLeg {
+    /// Create a [ZeroFixedLeg], compounding the `fixed_rate` by the `quote` over the day count
+    /// fractions of each period of the `schedule`, measured with the `convention` and
+    /// `calendar`.
+    pub fn try_new<U: DateRoll>(
+        schedule: Schedule,
+        fixed_rate: f64,
+        notional: f64,
+        convention: Convention,
+        quote: ZeroQuote,
+        calendar: &U,
+    ) -> Result<Self, RateslibError> {
+        let bases = base_periods(&schedule, notional, convention, calendar)?;
+        let dcfs: Vec<f64> = bases.iter().map(|b| b.dcf).collect();
+        let base = BasePeriod::new(
+            bases[0].start,
+            bases[bases.len() - 1].end,
+            *schedule.pschedule().last().unwrap(),
+            notional,
+            convention,
+            compensated_f64(&dcfs),
+            false,
+        );
+        let period = ZeroFixedPeriod::try_new(base, fixed_rate, quote, dcfs)?;
+        Ok(Self {
+            schedule,
+            periods: vec![period],
+        })
+    }
+
+    pub fn schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    pub fn fixed_rate(&self) -> f64 {
+        self.periods[0].fixed_rate
+    }
+
+    pub fn quote(&self) -> ZeroQuote {
+        self.periods[0].quote
+    }
+
+    pub fn period(&self) -> &ZeroFixedPeriod {
+        &self.periods[0]
+    }
+}
+
+impl Leg for ZeroFixedLeg {
+    type P = ZeroFixedPeriod;
+
+    fn periods(&self) -> &[ZeroFixedPeriod] {
+        &self.periods
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::{ndt, Modifier, NamedCal, RollDay};
+    use crate::periods::period::tests::curve_fixture;
+    use crate::periods::Curves;
+    use crate::scheduling::Frequency;
+
+    #[test]
+    fn test_zero_fixed_leg_single_payment() {
+        let cal = NamedCal::try_new("all").unwrap();
+        let schedule = Schedule::try_new(
+            ndt(2024, 1, 1),
+            ndt(2029, 1, 1),
+            Frequency::Months { number: 12 },
+            RollDay::Unspecified {},
+            Modifier::Act,
+            &cal,
+            0,
+        )
+        .unwrap();
+        let leg = ZeroFixedLeg::try_new(
+            schedule,
+            3.0,
+            1e6,
+            Convention::One,
+            ZeroQuote::Annualised,
+            &cal,
+        )
+        .unwrap();
+        let curve = curve_fixture("c");
+        let curves = Curves::new(None, Some(&curve));
+        let cashflows = leg.cashflows(&curves).unwrap();
+        assert_eq!(cashflows.len(), 1);
+        assert_eq!(cashflows[0].0, ndt(2029, 1, 1));
+        let expected = -1e6 * (1.03_f64.powi(5) - 1.0);
+        assert!((f64::from(&cashflows[0].1) - expected).abs() < 1e-6);
+    }
+}
@@ -10,41 +10,128 @@
 mod tests;
 
 pub mod json;
-use crate::json::json_py::from_json_py;
 
 // type GenericError = Box<dyn std::error::Error + Send + Sync + 'static>;
 // type GenericResult<T> = Result<T, GenericError>;
 
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
 
+pub mod error;
+
+pub mod profiling;
+
+#[cfg(feature = "golden")]
+pub mod golden;
+
+#[cfg(feature = "testing")]
+pub mod testing;
+
+#[cfg(feature = "xva")]
+pub mod xva;
+
+pub mod cancel;
+
+pub mod progress;
+
+pub(crate) mod parallel;
+
+pub mod trace;
+
 pub mod dual;
-use dual::linalg_py::{dsolve1_py, dsolve2_py, fdsolve1_py, fdsolve2_py};
-use dual::{ADOrder, Dual, Dual2};
 
 pub mod splines;
-use splines::spline_py::{bspldnev_single, bsplev_single};
-use splines::{PPSplineDual, PPSplineDual2, PPSplineF64};
 
 pub mod curves;
-use curves::curve_py::Curve;
-use curves::interpolation::interpolation_py::index_left_f64;
-use curves::{
-    FlatBackwardInterpolator, FlatForwardInterpolator, LinearInterpolator,
-    LinearZeroRateInterpolator, LogLinearInterpolator, NullInterpolator,
-};
 
 pub mod calendars;
-use calendars::calendar_py::get_calendar_by_name_py;
-use calendars::{
-    Cal, Convention, Modifier, NamedCal, RollDay, UnionCal, _get_convention_str, _get_modifier_str,
-};
 
 pub mod fx;
-use fx::rates::ccy::Ccy;
-use fx::rates::{FXRate, FXRates};
 
+pub mod solver;
+
+pub mod scheduling;
+
+pub mod periods;
+
+pub mod legs;
+
+pub mod lattice;
+
+pub mod montecarlo;
+
+pub mod models;
+
+pub mod instruments;
+
+pub mod context;
+
+pub mod interop;
+
+pub mod risk;
+
+pub mod ffi;
+
+#[cfg(feature = "python")]
 #[pymodule]
 fn rs(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    use crate::calendars::calendar_py::{get_calendar_by_name_py, load_holiday_updates_py};
+    use crate::calendars::{
+        _get_convention_str, _get_modifier_str, Cal, Convention, Modifier, NamedCal, RollDay,
+        UnionCal,
+    };
+    use crate::cancel::CancellationToken;
+    use crate::curves::curve_py::Curve;
+    use crate::curves::interpolation::interpolation_py::index_left_f64;
+    use crate::curves::{
+        FlatBackwardInterpolator, FlatForwardInterpolator, LinearInterpolator,
+        LinearZeroRateInterpolator, LogLinearInterpolator, NullInterpolator,
+    };
+    use crate::dual::linalg_py::{dsolve1_py, dsolve2_py, fdsolve1_py, fdsolve2_py};
+    use crate::dual::{ADOrder, Dual, Dual2, Reduction};
+    use crate::error::error_py::{
+        CalendarError, CancelledError, CurrencyError, CurveError, DualTypeError, LinalgError,
+        RateslibError, SolverError, SplineError,
+    };
+    use crate::fx::rates::ccy::Ccy;
+    use crate::fx::rates::{FXRate, FXRates};
+    use crate::instruments::instrument_py::{CurvesView, PyInstrumentBase, PyPortfolio};
+    use crate::interop::interop_py::calibrate_curve_py;
+    use crate::json::json_py::from_json_py;
+    use crate::montecarlo::montecarlo_py::mc_european_py;
+    use crate::periods::curve_map_py::PyCurveMap;
+    use crate::progress::{ProgressEvent, ProgressStage};
+    use crate::splines::spline_py::{bspldnev_single, bsplev_single};
+    use crate::splines::{PPSplineDual, PPSplineDual2, PPSplineF64};
+    use crate::trace::trace_py::{capture_logs_py, set_log_filter_py, take_log_records_py};
+
+    // Errors
+    m.add("RateslibError", m.py().get_type_bound::<RateslibError>())?;
+    m.add("CurveError", m.py().get_type_bound::<CurveError>())?;
+    m.add("SplineError", m.py().get_type_bound::<SplineError>())?;
+    m.add("CalendarError", m.py().get_type_bound::<CalendarError>())?;
+    m.add("LinalgError", m.py().get_type_bound::<LinalgError>())?;
+    m.add("SolverError", m.py().get_type_bound::<SolverError>())?;
+    m.add("CurrencyError", m.py().get_type_bound::<CurrencyError>())?;
+    m.add("DualTypeError", m.py().get_type_bound::<DualTypeError>())?;
+    m.add("CancelledError", m.py().get_type_bound::<CancelledError>())?;
+    m.add_class::<CancellationToken>()?;
+    m.add_class::<ProgressEvent>()?;
+    m.add_class::<ProgressStage>()?;
+
+    // Tracing
+    m.add_function(wrap_pyfunction!(set_log_filter_py, m)?)?;
+    m.add_function(wrap_pyfunction!(capture_logs_py, m)?)?;
+    m.add_function(wrap_pyfunction!(take_log_records_py, m)?)?;
+
+    #[cfg(feature = "profiling")]
+    m.add_function(wrap_pyfunction!(profiling::profiling_counters_py, m)?)?;
+
+    #[cfg(feature = "testing")]
+    m.add_function(wrap_pyfunction!(testing::generate_numbers_py, m)?)?;
+    #[cfg(feature = "testing")]
+    m.add_function(wrap_pyfunction!(testing::generate_nodes_py, m)?)?;
+
     // JSON
     m.add_function(wrap_pyfunction!(from_json_py, m)?)?;
 
@@ -52,6 +139,7 @@ fn rs(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<Dual>()?;
     m.add_class::<Dual2>()?;
     m.add_class::<ADOrder>()?;
+    m.add_class::<Reduction>()?;
     m.add_function(wrap_pyfunction!(dsolve1_py, m)?)?;
     m.add_function(wrap_pyfunction!(dsolve2_py, m)?)?;
     m.add_function(wrap_pyfunction!(fdsolve1_py, m)?)?;
@@ -66,6 +154,7 @@ fn rs(m: &Bound<'_, PyModule>) -> PyResult<()> {
 
     // Curves
     m.add_class::<Curve>()?;
+    m.add_class::<PyCurveMap>()?;
     m.add_function(wrap_pyfunction!(index_left_f64, m)?)?;
     m.add_class::<FlatBackwardInterpolator>()?;
     m.add_class::<FlatForwardInterpolator>()?;
@@ -82,6 +171,7 @@ fn rs(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<RollDay>()?;
     m.add_class::<Convention>()?;
     m.add_function(wrap_pyfunction!(get_calendar_by_name_py, m)?)?;
+    m.add_function(wrap_pyfunction!(load_holiday_updates_py, m)?)?;
     m.add_function(wrap_pyfunction!(_get_convention_str, m)?)?;
     m.add_function(wrap_pyfunction!(_get_modifier_str, m)?)?;
 
@@ -90,5 +180,16 @@ fn rs(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<FXRate>()?;
     m.add_class::<FXRates>()?;
 
+    // Instruments
+    m.add_class::<PyInstrumentBase>()?;
+    m.add_class::<CurvesView>()?;
+    m.add_class::<PyPortfolio>()?;
+
+    // Calibration
+    m.add_function(wrap_pyfunction!(calibrate_curve_py, m)?)?;
+
+    // Monte Carlo
+    m.add_function(wrap_pyfunction!(mc_european_py, m)?)?;
+
     Ok(())
 }
@@ -0,0 +1,367 @@
+use crate::curves::PricingCurve;
+use crate::dual::{get_variable_tags, Dual, MathFuncs, Number, SmoothFuncs};
+use crate::error::RateslibError;
+use crate::lattice::date_at;
+use crate::montecarlo::{McConfig, McValue, NormalRng};
+use crate::solver::{Solver, SolverResult, SolverSystem};
+use ndarray::Array1;
+
+/// A parametric local volatility of the short rate, `σ(x) = max(a + b x + c x² / 2, 0)`, of
+/// a displacement of the short rate from the initial forward curve, `x`.
+///
+/// The parameters `a`, `b` and `c` control the level, skew and curvature of the swaption
+/// smile and may be dual numbers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalVolatility {
+    pub(crate) level: Number,
+    pub(crate) skew: Number,
+    pub(crate) curvature: Number,
+}
+
+impl LocalVolatility {
+    pub fn new(level: Number, skew: Number, curvature: Number) -> Self {
+        Self {
+            level,
+            skew,
+            curvature,
+        }
+    }
+
+    /// Return the normal volatility of the short rate at displacement `x`.
+    pub fn vol(&self, x: &Number) -> Number {
+        let v = &self.level + (&self.skew + &self.curvature * x * 0.5) * x;
+        v.max_of(&Number::F64(0.0), None)
+    }
+
+    /// Return the real components of the level, skew and curvature.
+    pub fn parameters(&self) -> [f64; 3] {
+        [
+            f64::from(&self.level),
+            f64::from(&self.skew),
+            f64::from(&self.curvature),
+        ]
+    }
+}
+
+/// The one factor Cheyette, or quasi-Gaussian, model of the short rate,
+/// `r(t) = f(0, t) + x(t)`, with
+///
+/// - `dx = (y - κ x) dt + σ(x) dW`,
+/// - `dy = (σ(x)² - 2 κ y) dt`,
+///
+/// with a constant `mean_reversion`, `κ`, and [LocalVolatility], `σ(x)`. Discount factors are
+/// a function of the state, `P(t, T) = P(0, T) / P(0, t) exp(-G x - G² y / 2)` where
+/// `G = (1 - exp(-κ (T - t))) / κ`, so that the model fits the initial curve by construction.
+/// A constant volatility gives the [HullWhite](crate::models::HullWhite) model.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cheyette {
+    pub(crate) mean_reversion: f64,
+    pub(crate) local_vol: LocalVolatility,
+}
+
+/// The state of a path of a [Cheyette] model at a time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheyetteState {
+    pub x: Number,
+    pub y: Number,
+    /// The integral of `x` to the time, so that the stochastic discount factor is
+    /// `P(0, t) exp(-integral)`.
+    pub integral: Number,
+}
+
+impl Cheyette {
+    /// Create a [Cheyette] model with a positive `mean_reversion`.
+    pub fn try_new(mean_reversion: f64, local_vol: LocalVolatility) -> Result<Self, RateslibError> {
+        if mean_reversion <= 0.0 {
+            return Err(RateslibError::value("`mean_reversion` must be positive."));
+        }
+        Ok(Self {
+            mean_reversion,
+            local_vol,
+        })
+    }
+
+    pub fn mean_reversion(&self) -> f64 {
+        self.mean_reversion
+    }
+
+    pub fn local_vol(&self) -> &LocalVolatility {
+        &self.local_vol
+    }
+
+    fn g(&self, tau: f64) -> f64 {
+        (1.0 - (-self.mean_reversion * tau).exp()) / self.mean_reversion
+    }
+
+    /// Return the discount factor over `tau` years from a `state`, given the ratio of the
+    /// initial discount factors, `P(0, T) / P(0, t)`.
+    pub fn bond(&self, forward_df: f64, tau: f64, state: &CheyetteState) -> Number {
+        let g = self.g(tau);
+        (&state.x * -g - &state.y * (0.5 * g * g)).exp() * forward_df
+    }
+
+    /// Evolve a path to `expiry`, in years, in equal Euler steps, one for each standard normal
+    /// variate of `z`.
+    pub fn simulate(&self, expiry: f64, z: &[f64]) -> CheyetteState {
+        let dt = expiry / z.len() as f64;
+        let k = self.mean_reversion;
+        let (mut x, mut y, mut integral) = (Number::F64(0.0), Number::F64(0.0), Number::F64(0.0));
+        for zi in z {
+            let vol = self.local_vol.vol(&x);
+            let x_next = &x + (&y - &x * k) * dt + &vol * (dt.sqrt() * zi);
+            y = &y + (&vol * &vol - &y * (2.0 * k)) * dt;
+            integral = integral + (&x + &x_next) * (0.5 * dt);
+            x = x_next;
+        }
+        CheyetteState { x, y, integral }
+    }
+
+    /// Return the Monte Carlo value of European swaptions with direction `phi`, `1` for a
+    /// payer and `-1` for a receiver, expiring in `expiry` years into a swap of `tenor`
+    /// years with fixed payments every `period` years, for each of the `strikes`, as decimals.
+    ///
+    /// Values are per unit notional with sensitivity to the dual components of the
+    /// [LocalVolatility], and use common paths for every strike.
+    #[allow(clippy::too_many_arguments)]
+    pub fn swaptions(
+        &self,
+        curve: &dyn PricingCurve,
+        expiry: f64,
+        tenor: f64,
+        period: f64,
+        strikes: &[f64],
+        phi: f64,
+        config: &McConfig,
+    ) -> Result<Vec<McValue>, RateslibError> {
+        if expiry <= 0.0 || tenor < period || period <= 0.0 {
+            return Err(RateslibError::value(
+                "`expiry` and `period` must be positive and `tenor` at least one `period`.",
+            ));
+        }
+        let initial = curve.initial_date();
+        let df = |t: f64| -> f64 { f64::from(curve.df(&date_at(&initial, t))) };
+        let n = (tenor / period).round() as usize;
+        let p0 = df(expiry);
+        let forward_dfs: Vec<(f64, f64)> = (1..=n)
+            .map(|i| (df(expiry + i as f64 * period) / p0, i as f64 * period))
+            .collect();
+
+        let mut rng = NormalRng::new(config.seed);
+        let mut totals = vec![Number::F64(0.0); strikes.len()];
+        let mut samples = vec![Vec::with_capacity(config.paths); strikes.len()];
+        let mut drawn = 0;
+        while drawn < config.paths {
+            let z = rng.normals(config.steps);
+            let mut variates = vec![z.clone()];
+            if config.antithetic {
+                variates.push(z.iter().map(|x| -x).collect());
+            }
+            let mut sample = vec![0.0; strikes.len()];
+            for z in variates.iter() {
+                let state = self.simulate(expiry, z);
+                let bonds: Vec<Number> = forward_dfs
+                    .iter()
+                    .map(|(f, tau)| self.bond(*f, *tau, &state))
+                    .collect();
+                let annuity = bonds.iter().fold(Number::F64(0.0), |a, b| a + b * period);
+                let floating = (bonds.last().unwrap() * -1.0 + 1.0) / &annuity;
+                let discount = (&state.integral * -1.0).exp() * p0;
+                let weight = &discount * &annuity;
+                for (j, strike) in strikes.iter().enumerate() {
+                    let value =
+                        ((&floating - *strike) * phi).max_of(&Number::F64(0.0), None) * &weight;
+                    sample[j] += f64::from(&value) / variates.len() as f64;
+                    totals[j] = &totals[j] + value;
+                }
+            }
+            sample
+                .into_iter()
+                .enumerate()
+                .for_each(|(j, s)| samples[j].push(s));
+            drawn += variates.len();
+        }
+        Ok(totals
+            .into_iter()
+            .zip(samples)
+            .map(|(total, s)| {
+                let m = s.len() as f64;
+                let mean = s.iter().sum::<f64>() / m;
+                let var = s.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (m - 1.0).max(1.0);
+                McValue {
+                    value: total / config.paths as f64,
+                    std_error: (var / m).sqrt(),
+                }
+            })
+            .collect())
+    }
+
+    /// Calibrate the [LocalVolatility] to the `prices` of payer swaptions with the given
+    /// `strikes` and the default [Solver].
+    ///
+    /// Prices are per unit notional and the same paths are used in every iteration, so that
+    /// the calibration is deterministic for a given `config`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn calibrate(
+        &mut self,
+        curve: &dyn PricingCurve,
+        expiry: f64,
+        tenor: f64,
+        period: f64,
+        strikes: &[f64],
+        prices: &[f64],
+        config: &McConfig,
+    ) -> Result<SolverResult, RateslibError> {
+        if strikes.len() != prices.len() || strikes.is_empty() {
+            return Err(RateslibError::value(
+                "`strikes` and `prices` must be non-empty and of equal length.",
+            ));
+        }
+        let mut system = SmileSystem {
+            model: self.clone(),
+            curve,
+            expiry,
+            tenor,
+            period,
+            strikes: strikes.to_vec(),
+            config: *config,
+            tags: get_variable_tags("lv", 3),
+        };
+        system.set_variables(&self.local_vol.parameters())?;
+        let solver = Solver::try_new_default(prices.iter().map(|p| p * 1e4).collect())?;
+        let result = solver.iterate(&mut system)?;
+        let [a, b, c] = system.model.local_vol.parameters();
+        self.local_vol = LocalVolatility::new(Number::F64(a), Number::F64(b), Number::F64(c));
+        Ok(result)
+    }
+}
+
+/// The payer swaption prices, in bps of notional, of a [Cheyette] model as a function of its
+/// local volatility parameters.
+struct SmileSystem<'a> {
+    model: Cheyette,
+    curve: &'a dyn PricingCurve,
+    expiry: f64,
+    tenor: f64,
+    period: f64,
+    strikes: Vec<f64>,
+    config: McConfig,
+    tags: Vec<String>,
+}
+
+impl SolverSystem for SmileSystem<'_> {
+    fn variable_tags(&self) -> Vec<String> {
+        self.tags.clone()
+    }
+
+    fn variables(&self) -> Vec<f64> {
+        self.model.local_vol.parameters().to_vec()
+    }
+
+    fn set_variables(&mut self, values: &[f64]) -> Result<(), RateslibError> {
+        let unit = |i: usize| Array1::from_iter((0..3).map(|j| if i == j { 1.0 } else { 0.0 }));
+        let level = Dual::try_new(values[0], self.tags.clone(), unit(0).to_vec())?;
+        let skew = Dual::clone_from(&level, values[1], unit(1));
+        let curvature = Dual::clone_from(&level, values[2], unit(2));
+        self.model.local_vol = LocalVolatility::new(
+            Number::Dual(level),
+            Number::Dual(skew),
+            Number::Dual(curvature),
+        );
+        Ok(())
+    }
+
+    fn rates(&self) -> Result<Vec<Dual>, RateslibError> {
+        Ok(self
+            .model
+            .swaptions(
+                self.curve,
+                self.expiry,
+                self.tenor,
+                self.period,
+                &self.strikes,
+                1.0,
+                &self.config,
+            )?
+            .into_iter()
+            .map(|v| Dual::from(v.value * 1e4))
+            .collect())
+    }
+
+    fn is_valid(&self) -> bool {
+        f64::from(&self.model.local_vol.level) > 0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::periods::period::tests::curve_fixture;
+
+    fn model(a: f64, b: f64, c: f64) -> Cheyette {
+        let lv = LocalVolatility::new(Number::F64(a), Number::F64(b), Number::F64(c));
+        Cheyette::try_new(0.03, lv).unwrap()
+    }
+
+    #[test]
+    fn test_reprices_curve() {
+        // the expected stochastic discount factor of a bond reprices the curve
+        let m = model(0.01, 0.2, 5.0);
+        let mut rng = NormalRng::new(3);
+        let paths = 4000;
+        let mut total = 0.0;
+        for _ in 0..paths {
+            let state = m.simulate(2.0, &rng.normals(24));
+            let d = f64::from(&(&state.integral * -1.0).exp()) * (-0.04_f64).exp();
+            total += d * f64::from(&m.bond((-0.06_f64).exp(), 3.0, &state));
+        }
+        assert!((total / paths as f64 - (-0.1_f64).exp()).abs() < 5e-4);
+    }
+
+    #[test]
+    fn test_put_call_parity() {
+        let (m, curve) = (model(0.01, -0.5, 10.0), curve_fixture("c"));
+        let config = McConfig::try_new(2000, 12, 1, true).unwrap();
+        let payer = m
+            .swaptions(&curve, 1.0, 2.0, 1.0, &[0.02], 1.0, &config)
+            .unwrap();
+        let receiver = m
+            .swaptions(&curve, 1.0, 2.0, 1.0, &[0.02], -1.0, &config)
+            .unwrap();
+        let df = |t: f64| f64::from(curve.df(&date_at(&curve.initial_date(), t)));
+        let forward = df(1.0) - df(3.0) - 0.02 * (df(2.0) + df(3.0));
+        let value = f64::from(&(&payer[0].value - &receiver[0].value));
+        assert!((value - forward).abs() < 3.0 * (payer[0].std_error + receiver[0].std_error));
+    }
+
+    #[test]
+    fn test_calibrate_round_trip() {
+        let curve = curve_fixture("c");
+        let config = McConfig::try_new(1000, 8, 5, true).unwrap();
+        let strikes = [0.01, 0.02, 0.03];
+        let target = model(0.008, 0.3, 8.0);
+        let prices: Vec<f64> = target
+            .swaptions(&curve, 1.0, 2.0, 1.0, &strikes, 1.0, &config)
+            .unwrap()
+            .iter()
+            .map(|v| f64::from(&v.value))
+            .collect();
+        let mut m = model(0.01, 0.0, 0.0);
+        let result = m
+            .calibrate(&curve, 1.0, 2.0, 1.0, &strikes, &prices, &config)
+            .unwrap();
+        assert!(result.g < 1e-8);
+        let [a, b, c] = m.local_vol().parameters();
+        assert!((a - 0.008).abs() < 1e-5 && (b - 0.3).abs() < 1e-2 && (c - 8.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let lv = LocalVolatility::new(Number::F64(0.01), Number::F64(0.0), Number::F64(0.0));
+        assert!(Cheyette::try_new(0.0, lv).is_err());
+        let config = McConfig::try_new(2, 1, 0, false).unwrap();
+        let curve = curve_fixture("c");
+        assert!(model(0.01, 0.0, 0.0)
+            .swaptions(&curve, 1.0, 0.5, 1.0, &[0.02], 1.0, &config)
+            .is_err());
+    }
+}
@@ -0,0 +1,59 @@
+use crate::curves::PricingCurve;
+use crate::error::RateslibError;
+use crate::lattice::TrinomialTree;
+use serde::{Deserialize, Serialize};
+
+/// The one factor Hull-White short rate model, `dr = (θ(t) - a r) dt + σ dW`.
+///
+/// `θ(t)` is not parametrised directly but determined by fitting the model to a discount curve.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HullWhite {
+    pub(crate) mean_reversion: f64,
+    pub(crate) volatility: f64,
+}
+
+impl HullWhite {
+    /// Create a [HullWhite] model with a positive `mean_reversion`, `a`, and normal
+    /// `volatility`, `σ`, as a decimal per annum.
+    pub fn try_new(mean_reversion: f64, volatility: f64) -> Result<Self, RateslibError> {
+        if mean_reversion <= 0.0 || volatility <= 0.0 {
+            return Err(RateslibError::value(
+                "`mean_reversion` and `volatility` must be positive.",
+            ));
+        }
+        Ok(Self {
+            mean_reversion,
+            volatility,
+        })
+    }
+
+    pub fn mean_reversion(&self) -> f64 {
+        self.mean_reversion
+    }
+
+    pub fn volatility(&self) -> f64 {
+        self.volatility
+    }
+
+    /// Build a [TrinomialTree] of `steps` equal time steps up to `horizon` years from the
+    /// initial date of the `curve`, fitted to reprice its discount factors.
+    pub fn tree(
+        &self,
+        curve: &dyn PricingCurve,
+        horizon: f64,
+        steps: usize,
+    ) -> Result<TrinomialTree, RateslibError> {
+        TrinomialTree::try_hull_white(self.mean_reversion, self.volatility, curve, horizon, steps)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_model() {
+        assert!(HullWhite::try_new(0.0, 0.01).is_err());
+        assert!(HullWhite::try_new(0.05, 0.0).is_err());
+    }
+}
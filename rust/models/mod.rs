@@ -0,0 +1,12 @@
+//! Create pricing models for instruments with optionality, such as the Hull-White short rate
+//! model, the Cheyette model with local volatility and the Black-76 model of a lognormal
+//! forward.
+
+mod hull_white;
+pub use crate::models::hull_white::HullWhite;
+
+mod cheyette;
+pub use crate::models::cheyette::{Cheyette, CheyetteState, LocalVolatility};
+
+mod black;
+pub use crate::models::black::{black76, black76_digital, DigitalMethod, DigitalPayout};
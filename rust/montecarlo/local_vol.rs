@@ -0,0 +1,152 @@
+use crate::cancel;
+use crate::dual::Number;
+use crate::error::RateslibError;
+use crate::fx::volatility::LocalVol;
+use crate::montecarlo::{McConfig, McValue, NormalRng};
+use crate::progress::{self, ProgressEvent, ProgressStage, PATH_BATCH};
+
+/// A local volatility spot process, `dS = c S dt + σ(t, S) S dW`, with the cost of carry, `c`,
+/// of the [LocalVol] surface from which `σ(t, S)` is evaluated at the start of each time step.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalVolProcess {
+    pub(crate) spot: Number,
+    pub(crate) local_vol: LocalVol,
+}
+
+impl LocalVolProcess {
+    /// Create a [LocalVolProcess] with a positive `spot`, which may be a dual number.
+    ///
+    /// The local volatility is evaluated at the real spot of each path so the sensitivities to
+    /// the spot exclude those of the local volatility.
+    pub fn try_new(spot: Number, local_vol: LocalVol) -> Result<Self, RateslibError> {
+        if f64::from(&spot) <= 0.0 {
+            return Err(RateslibError::value(
+                "`spot` of a `LocalVolProcess` must be positive.",
+            ));
+        }
+        Ok(Self { spot, local_vol })
+    }
+
+    /// Return the spot at the end of each of the equal time steps to `expiry`, in years,
+    /// given a standard normal variate for each step.
+    pub fn path(&self, expiry: f64, z: &[f64]) -> Vec<Number> {
+        let dt = expiry / z.len() as f64;
+        let carry = self.local_vol.drift;
+        let mut spot = self.spot.clone();
+        z.iter()
+            .enumerate()
+            .map(|(k, zi)| {
+                let vol = self.local_vol.vol(k as f64 * dt, f64::from(&spot));
+                spot = &spot * ((carry - 0.5 * vol * vol) * dt + vol * dt.sqrt() * zi).exp();
+                spot.clone()
+            })
+            .collect()
+    }
+}
+
+/// Return the value of a `payoff`, of the spot at each time step, paid at `expiry`, in years,
+/// discounted at a continuously compounded `rate`, with pathwise sensitivities to the spot of
+/// the `process`.
+pub fn local_vol_value(
+    process: &LocalVolProcess,
+    expiry: f64,
+    rate: f64,
+    payoff: &dyn Fn(&[Number]) -> Number,
+    config: &McConfig,
+) -> Result<McValue, RateslibError> {
+    if expiry <= 0.0 {
+        return Err(RateslibError::value(
+            "`expiry` of a Monte Carlo simulation must be positive.",
+        ));
+    }
+    let df = (-rate * expiry).exp();
+    let mut rng = NormalRng::new(config.seed);
+    let draws = match config.antithetic {
+        true => config.paths / 2,
+        false => config.paths,
+    };
+
+    let mut samples: Vec<f64> = Vec::with_capacity(draws);
+    let mut total = Number::F64(0.0);
+    for draw in 0..draws {
+        cancel::check()?;
+        if draw > 0 && draw.is_multiple_of(PATH_BATCH) {
+            progress::report(ProgressEvent::new(
+                ProgressStage::MonteCarlo,
+                draw * config.paths / draws,
+                config.paths,
+            ))?;
+        }
+        let z = rng.normals(config.steps);
+        let mut variates = vec![z.clone()];
+        if config.antithetic {
+            variates.push(z.iter().map(|x| -x).collect());
+        }
+        let mut sample = 0.0;
+        for z in variates.iter() {
+            let value = payoff(&process.path(expiry, z)) * df;
+            sample += f64::from(&value) / variates.len() as f64;
+            total = total + value;
+        }
+        samples.push(sample);
+    }
+
+    let n = samples.len() as f64;
+    let mean = samples.iter().sum::<f64>() / n;
+    let variance = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0).max(1.0);
+    Ok(McValue {
+        value: total / config.paths as f64,
+        std_error: (variance / n).sqrt(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dual::SmoothFuncs;
+    use crate::fx::options::GkMarket;
+    use crate::fx::volatility::{FxDeltaVolSmile, FxVolSurface};
+    use crate::models::black76;
+    use crate::montecarlo::{mc_value, GreekMethod, LognormalProcess};
+
+    fn call(path: &[Number]) -> Number {
+        (path.last().unwrap() - 1.12).max_of(&Number::F64(0.0), None)
+    }
+
+    #[test]
+    fn test_flat_surface_reprices_lognormal() {
+        let market = GkMarket::new(
+            Number::F64(1.10),
+            Number::F64(0.04),
+            Number::F64(0.02),
+            Number::F64(0.10),
+        );
+        let smile =
+            FxDeltaVolSmile::try_new(0.5, Number::F64(0.1), Number::F64(0.0), Number::F64(0.0))
+                .unwrap();
+        let surface = FxVolSurface::try_new(vec![smile]).unwrap();
+        let local_vol = LocalVol::try_new(
+            &surface,
+            &market,
+            vec![0.25, 0.5, 0.75],
+            vec![-0.1, -0.05, 0.0, 0.05, 0.1],
+            Default::default(),
+        )
+        .unwrap();
+        let process = LocalVolProcess::try_new(Number::F64(1.10), local_vol).unwrap();
+        let config = McConfig::try_new(20_000, 8, 7, true).unwrap();
+        let mc = local_vol_value(&process, 0.5, 0.04, &call, &config).unwrap();
+
+        let lognormal =
+            LognormalProcess::try_new(Number::F64(1.10), 0.02, Number::F64(0.1)).unwrap();
+        let expected =
+            mc_value(&lognormal, 0.5, 0.04, &call, GreekMethod::Pathwise, &config).unwrap();
+        assert!((f64::from(&mc.value) - f64::from(&expected.value)).abs() < 1e-5);
+
+        let forward = Number::F64(1.10 * (0.01_f64).exp());
+        let df = Number::F64((-0.02_f64).exp());
+        let analytic = black76(&forward, 1.12, 0.5, &Number::F64(0.1), &df, 1.0);
+        assert!((f64::from(&mc.value) - f64::from(&analytic)).abs() < 3.0 * mc.std_error);
+        assert!(LocalVolProcess::try_new(Number::F64(0.0), process.local_vol).is_err());
+    }
+}
@@ -0,0 +1,314 @@
+use crate::cancel;
+use crate::dual::{MathFuncs, Number};
+use crate::error::RateslibError;
+use crate::montecarlo::NormalRng;
+use crate::progress::{self, ProgressEvent, ProgressStage, PATH_BATCH};
+use num_traits::Pow;
+use serde::{Deserialize, Serialize};
+
+/// The number of paths, time steps and seed of a Monte Carlo simulation.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct McConfig {
+    pub(crate) paths: usize,
+    pub(crate) steps: usize,
+    pub(crate) seed: u64,
+    pub(crate) antithetic: bool,
+}
+
+impl McConfig {
+    /// Create a [McConfig] of `paths` paths each of `steps` equal time steps, which, if
+    /// `antithetic`, are generated in pairs with negated normal variates.
+    pub fn try_new(
+        paths: usize,
+        steps: usize,
+        seed: u64,
+        antithetic: bool,
+    ) -> Result<Self, RateslibError> {
+        if paths == 0 || steps == 0 {
+            return Err(RateslibError::value(
+                "`paths` and `steps` of a Monte Carlo simulation must be positive.",
+            ));
+        }
+        if antithetic && !paths.is_multiple_of(2) {
+            return Err(RateslibError::value(
+                "`paths` of an antithetic Monte Carlo simulation must be even.",
+            ));
+        }
+        Ok(Self {
+            paths,
+            steps,
+            seed,
+            antithetic,
+        })
+    }
+
+    pub fn paths(&self) -> usize {
+        self.paths
+    }
+
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+}
+
+/// The method of calculating the sensitivities of a Monte Carlo value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GreekMethod {
+    /// Propagate dual numbers through each path. The sensitivities of a payoff which is
+    /// discontinuous in the spot, such as a digital, are zero almost surely and so are
+    /// incorrect.
+    Pathwise,
+    /// Weight the payoff of each real valued path by the derivative of the log likelihood of
+    /// the path to the spot and volatility. Valid for discontinuous payoffs, but of higher
+    /// variance and to first order only.
+    LikelihoodRatio,
+}
+
+/// A lognormal spot process, `dS = c S dt + σ S dW`, with a constant cost of carry, `c`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LognormalProcess {
+    pub(crate) spot: Number,
+    pub(crate) carry: f64,
+    pub(crate) volatility: Number,
+}
+
+impl LognormalProcess {
+    /// Create a [LognormalProcess] with a positive `spot` and `volatility`, as a decimal per
+    /// annum, either of which may be a dual number.
+    pub fn try_new(spot: Number, carry: f64, volatility: Number) -> Result<Self, RateslibError> {
+        if f64::from(&spot) <= 0.0 || f64::from(&volatility) <= 0.0 {
+            return Err(RateslibError::value(
+                "`spot` and `volatility` of a `LognormalProcess` must be positive.",
+            ));
+        }
+        Ok(Self {
+            spot,
+            carry,
+            volatility,
+        })
+    }
+
+    /// Return the spot at the end of each of the equal time steps to `expiry`, in years,
+    /// given a standard normal variate for each step.
+    pub fn path(&self, expiry: f64, z: &[f64]) -> Vec<Number> {
+        let dt = expiry / z.len() as f64;
+        let drift = (self.volatility.clone().pow(2.0) * -0.5 + self.carry) * dt;
+        let diffusion = &self.volatility * dt.sqrt();
+        let mut spot = self.spot.clone();
+        z.iter()
+            .map(|zi| {
+                spot = &spot * (&drift + &diffusion * *zi).exp();
+                spot.clone()
+            })
+            .collect()
+    }
+
+    /// Return the process with the real components of the spot and volatility.
+    fn real(&self) -> Self {
+        Self {
+            spot: Number::F64(f64::from(&self.spot)),
+            carry: self.carry,
+            volatility: Number::F64(f64::from(&self.volatility)),
+        }
+    }
+}
+
+/// The Monte Carlo value of a payoff and the standard error of its real component.
+#[derive(Debug, Clone, PartialEq)]
+pub struct McValue {
+    pub value: Number,
+    pub std_error: f64,
+}
+
+/// Return the value of a `payoff`, of the spot at each time step, paid at `expiry`, in years,
+/// discounted at a continuously compounded `rate`.
+///
+/// The sensitivities of the value to the dual components of the spot and volatility of the
+/// `process` are calculated with the given `method`.
+pub fn mc_value(
+    process: &LognormalProcess,
+    expiry: f64,
+    rate: f64,
+    payoff: &dyn Fn(&[Number]) -> Number,
+    method: GreekMethod,
+    config: &McConfig,
+) -> Result<McValue, RateslibError> {
+    if expiry <= 0.0 {
+        return Err(RateslibError::value(
+            "`expiry` of a Monte Carlo simulation must be positive.",
+        ));
+    }
+    let df = (-rate * expiry).exp();
+    let mut rng = NormalRng::new(config.seed);
+    let draws = match config.antithetic {
+        true => config.paths / 2,
+        false => config.paths,
+    };
+    let real = process.real();
+    let (s0, vol) = (f64::from(&real.spot), f64::from(&real.volatility));
+    let sqrt_dt = (expiry / config.steps as f64).sqrt();
+
+    let mut samples: Vec<f64> = Vec::with_capacity(draws);
+    let mut total = Number::F64(0.0);
+    let (mut spot_weight, mut vol_weight) = (0.0, 0.0);
+    for draw in 0..draws {
+        cancel::check()?;
+        if draw > 0 && draw.is_multiple_of(PATH_BATCH) {
+            let completed = draw * config.paths / draws;
+            progress::report(ProgressEvent::new(
+                ProgressStage::MonteCarlo,
+                completed,
+                config.paths,
+            ))?;
+        }
+        let z = rng.normals(config.steps);
+        let mut variates = vec![z.clone()];
+        if config.antithetic {
+            variates.push(z.iter().map(|x| -x).collect());
+        }
+        let mut sample = 0.0;
+        for z in variates.iter() {
+            let value = match method {
+                GreekMethod::Pathwise => {
+                    let value = payoff(&process.path(expiry, z)) * df;
+                    let real_value = f64::from(&value);
+                    total = total + value;
+                    real_value
+                }
+                GreekMethod::LikelihoodRatio => {
+                    let value = f64::from(&payoff(&real.path(expiry, z))) * df;
+                    spot_weight += value * z[0] / (s0 * vol * sqrt_dt);
+                    vol_weight += value
+                        * z.iter()
+                            .map(|zi| (zi * zi - 1.0) / vol - zi * sqrt_dt)
+                            .sum::<f64>();
+                    value
+                }
+            };
+            sample += value / variates.len() as f64;
+        }
+        samples.push(sample);
+    }
+
+    let n = samples.len() as f64;
+    let mean = samples.iter().sum::<f64>() / n;
+    let variance = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0).max(1.0);
+    let value = match method {
+        GreekMethod::Pathwise => total / config.paths as f64,
+        GreekMethod::LikelihoodRatio => {
+            let m = config.paths as f64;
+            Number::F64(mean)
+                + (&process.spot - s0) * (spot_weight / m)
+                + (&process.volatility - vol) * (vol_weight / m)
+        }
+    };
+    Ok(McValue {
+        value,
+        std_error: (variance / n).sqrt(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dual::{Dual, Gradient1, SmoothFuncs};
+    use crate::models::{black76, black76_digital, DigitalMethod, DigitalPayout};
+
+    fn process() -> LognormalProcess {
+        LognormalProcess::try_new(
+            Number::Dual(Dual::new(100.0, vec!["s".to_string()])),
+            0.03,
+            Number::Dual(Dual::new(0.2, vec!["v".to_string()])),
+        )
+        .unwrap()
+    }
+
+    fn greeks(n: &Number) -> (f64, f64) {
+        match n {
+            Number::Dual(d) => {
+                let g = d.gradient1(vec!["s".to_string(), "v".to_string()]);
+                (g[0], g[1])
+            }
+            _ => (0.0, 0.0),
+        }
+    }
+
+    /// The analytic value of an option on the spot of the process, expiring in 1y.
+    fn analytic(p: &LognormalProcess, digital: bool) -> Number {
+        let forward = &p.spot * (p.carry).exp();
+        let df = Number::F64((-0.03_f64).exp());
+        match digital {
+            false => black76(&forward, 105.0, 1.0, &p.volatility, &df, 1.0),
+            true => black76_digital(
+                &forward,
+                105.0,
+                1.0,
+                &p.volatility,
+                &df,
+                1.0,
+                DigitalPayout::Cash { amount: 1.0 },
+                DigitalMethod::Analytic,
+            ),
+        }
+    }
+
+    fn call(path: &[Number]) -> Number {
+        (path.last().unwrap() - 105.0).max_of(&Number::F64(0.0), None)
+    }
+
+    fn digital(path: &[Number]) -> Number {
+        match f64::from(path.last().unwrap()) > 105.0 {
+            true => Number::F64(1.0),
+            false => Number::F64(0.0),
+        }
+    }
+
+    #[test]
+    fn test_pathwise_vanilla_greeks() {
+        let p = process();
+        let config = McConfig::try_new(40_000, 1, 11, true).unwrap();
+        let mc = mc_value(&p, 1.0, 0.03, &call, GreekMethod::Pathwise, &config).unwrap();
+        let expected = analytic(&p, false);
+        assert!((f64::from(&mc.value) - f64::from(&expected)).abs() < 3.0 * mc.std_error);
+        let ((delta, vega), (delta_, vega_)) = (greeks(&mc.value), greeks(&expected));
+        assert!((delta - delta_).abs() < 5e-3);
+        assert!((vega - vega_).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_multi_step_paths_reprice() {
+        let p = process();
+        let config = McConfig::try_new(20_000, 4, 3, true).unwrap();
+        let mc = mc_value(&p, 1.0, 0.03, &call, GreekMethod::Pathwise, &config).unwrap();
+        let expected = f64::from(&analytic(&p, false));
+        assert!((f64::from(&mc.value) - expected).abs() < 3.0 * mc.std_error);
+    }
+
+    #[test]
+    fn test_likelihood_ratio_digital_greeks() {
+        let p = process();
+        let config = McConfig::try_new(200_000, 1, 5, false).unwrap();
+        let pathwise = mc_value(&p, 1.0, 0.03, &digital, GreekMethod::Pathwise, &config).unwrap();
+        assert_eq!(greeks(&pathwise.value), (0.0, 0.0));
+        let lr = mc_value(
+            &p,
+            1.0,
+            0.03,
+            &digital,
+            GreekMethod::LikelihoodRatio,
+            &config,
+        )
+        .unwrap();
+        let ((delta, vega), (delta_, vega_)) = (greeks(&lr.value), greeks(&analytic(&p, true)));
+        assert!((delta - delta_).abs() < 5e-4);
+        assert!((vega - vega_).abs() < 0.05);
+        assert_eq!(f64::from(&lr.value), f64::from(&pathwise.value));
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(McConfig::try_new(0, 1, 0, false).is_err());
+        assert!(McConfig::try_new(3, 1, 0, true).is_err());
+        assert!(LognormalProcess::try_new(Number::F64(1.0), 0.0, Number::F64(0.0)).is_err());
+    }
+}
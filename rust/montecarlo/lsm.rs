@@ -0,0 +1,291 @@
+use crate::cancel;
+use crate::dual::Number;
+use crate::error::RateslibError;
+use crate::montecarlo::{LognormalProcess, McConfig, McValue, NormalRng};
+use crate::progress::{self, ProgressEvent, ProgressStage, PATH_BATCH};
+use ndarray::{Array1, Array2};
+use serde::{Deserialize, Serialize};
+
+/// The basis functions of the regression of continuation values on the spot.
+///
+/// Functions are evaluated on the spot relative to its initial value and include a constant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RegressionBasis {
+    /// The monomials `1, x, ..., x^degree`.
+    Polynomial { degree: usize },
+    /// The weighted Laguerre polynomials `exp(-x / 2) L_n(x)` for `n` to `degree`.
+    Laguerre { degree: usize },
+}
+
+impl RegressionBasis {
+    /// Return the number of basis functions.
+    pub fn len(&self) -> usize {
+        match self {
+            RegressionBasis::Polynomial { degree } | RegressionBasis::Laguerre { degree } => {
+                degree + 1
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Return the value of each basis function at `x`.
+    pub fn evaluate(&self, x: f64) -> Vec<f64> {
+        let mut values = Vec::with_capacity(self.len());
+        match self {
+            RegressionBasis::Polynomial { degree } => {
+                let mut v = 1.0;
+                for _ in 0..=*degree {
+                    values.push(v);
+                    v *= x;
+                }
+            }
+            RegressionBasis::Laguerre { degree } => {
+                let w = (-x / 2.0).exp();
+                let (mut prev, mut curr) = (0.0, 1.0);
+                for n in 0..=*degree {
+                    values.push(w * curr);
+                    let next = ((2 * n + 1) as f64 - x) * curr - n as f64 * prev;
+                    (prev, curr) = (curr, next / (n + 1) as f64);
+                }
+            }
+        }
+        values
+    }
+}
+
+/// Return the least squares solution of `a x = b` by Householder QR decomposition.
+///
+/// Columns of `a` which are linearly dependent on previous columns have zero coefficient.
+pub fn qr_least_squares(a: &Array2<f64>, b: &Array1<f64>) -> Result<Array1<f64>, RateslibError> {
+    let (m, n) = a.dim();
+    if m < n || b.len() != m {
+        return Err(RateslibError::value(
+            "Least squares requires at least as many rows as columns and a matching `b`.",
+        ));
+    }
+    let (mut r, mut qtb) = (a.clone(), b.clone());
+    for k in 0..n {
+        let norm = (k..m).map(|i| r[[i, k]].powi(2)).sum::<f64>().sqrt();
+        if norm == 0.0 {
+            continue;
+        }
+        let alpha = -r[[k, k]].signum() * norm;
+        let mut v: Vec<f64> = (k..m).map(|i| r[[i, k]]).collect();
+        v[0] -= alpha;
+        let vnorm2 = v.iter().map(|x| x * x).sum::<f64>();
+        if vnorm2 == 0.0 {
+            continue;
+        }
+        for j in k..n {
+            let dot = (k..m).map(|i| v[i - k] * r[[i, j]]).sum::<f64>() * 2.0 / vnorm2;
+            (k..m).for_each(|i| r[[i, j]] -= dot * v[i - k]);
+        }
+        let dot = (k..m).map(|i| v[i - k] * qtb[i]).sum::<f64>() * 2.0 / vnorm2;
+        (k..m).for_each(|i| qtb[i] -= dot * v[i - k]);
+    }
+    let scale = (0..n).map(|k| r[[k, k]].abs()).fold(0.0, f64::max);
+    let mut x = Array1::<f64>::zeros(n);
+    for k in (0..n).rev() {
+        if r[[k, k]].abs() <= scale * 1e-12 {
+            continue;
+        }
+        let sum = ((k + 1)..n).map(|j| r[[k, j]] * x[j]).sum::<f64>();
+        x[k] = (qtb[k] - sum) / r[[k, k]];
+    }
+    Ok(x)
+}
+
+/// Return the Longstaff-Schwartz value of an option exercisable at the end of every time step
+/// to `expiry`, in years, into the `exercise` value of the spot, discounted at a continuously
+/// compounded `rate`.
+///
+/// The exercise policy is determined on real valued paths by regressing the discounted
+/// realised cashflows of in the money paths on the `basis`. The value is then recalculated
+/// with dual numbers propagated through each path to its exercise under the fixed policy,
+/// which, since the policy is optimal to first order, gives the sensitivities to the dual
+/// components of the spot and volatility of the `process`.
+pub fn lsm_value(
+    process: &LognormalProcess,
+    expiry: f64,
+    rate: f64,
+    exercise: &dyn Fn(&Number) -> Number,
+    basis: RegressionBasis,
+    config: &McConfig,
+) -> Result<McValue, RateslibError> {
+    if expiry <= 0.0 {
+        return Err(RateslibError::value(
+            "`expiry` of a Monte Carlo simulation must be positive.",
+        ));
+    }
+    let steps = config.steps;
+    let dt = expiry / steps as f64;
+    let mut rng = NormalRng::new(config.seed);
+    let mut variates: Vec<Vec<f64>> = Vec::with_capacity(config.paths);
+    while variates.len() < config.paths {
+        cancel::check()?;
+        if !variates.is_empty() && variates.len().is_multiple_of(PATH_BATCH) {
+            progress::report(ProgressEvent::new(
+                ProgressStage::MonteCarlo,
+                variates.len(),
+                config.paths,
+            ))?;
+        }
+        let z = rng.normals(steps);
+        if config.antithetic {
+            variates.push(z.iter().map(|x| -x).collect());
+        }
+        variates.push(z);
+    }
+
+    let real = LognormalProcess::try_new(
+        Number::F64(f64::from(&process.spot)),
+        process.carry,
+        Number::F64(f64::from(&process.volatility)),
+    )?;
+    let s0 = f64::from(&real.spot);
+    let paths: Vec<Vec<f64>> = variates
+        .iter()
+        .map(|z| real.path(expiry, z).iter().map(f64::from).collect())
+        .collect();
+    let intrinsic = |s: f64| f64::from(&exercise(&Number::F64(s)));
+
+    // the exercise step of each path, `steps` meaning unexercised, and its cashflow
+    let mut stopping: Vec<usize> = vec![steps; paths.len()];
+    let mut cashflow: Vec<f64> = vec![0.0; paths.len()];
+    for (i, path) in paths.iter().enumerate() {
+        let value = intrinsic(path[steps - 1]);
+        if value > 0.0 {
+            (stopping[i], cashflow[i]) = (steps - 1, value);
+        }
+    }
+    for step in (0..steps - 1).rev() {
+        let itm: Vec<(usize, f64)> = paths
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (i, intrinsic(p[step])))
+            .filter(|(_, v)| *v > 0.0)
+            .collect();
+        if itm.len() <= basis.len() {
+            continue;
+        }
+        let mut a = Array2::<f64>::zeros((itm.len(), basis.len()));
+        let mut b = Array1::<f64>::zeros(itm.len());
+        for (row, (i, _)) in itm.iter().enumerate() {
+            for (col, v) in basis.evaluate(paths[*i][step] / s0).into_iter().enumerate() {
+                a[[row, col]] = v;
+            }
+            if stopping[*i] < steps {
+                b[row] = cashflow[*i] * (-rate * dt * (stopping[*i] - step) as f64).exp();
+            }
+        }
+        let coefficients = qr_least_squares(&a, &b)?;
+        let continuation = a.dot(&coefficients);
+        for (row, (i, value)) in itm.iter().enumerate() {
+            if *value > continuation[row] {
+                (stopping[*i], cashflow[*i]) = (step, *value);
+            }
+        }
+    }
+
+    let n = paths.len() as f64;
+    let discounted: Vec<f64> = (0..paths.len())
+        .map(|i| match stopping[i] < steps {
+            true => cashflow[i] * (-rate * dt * (stopping[i] + 1) as f64).exp(),
+            false => 0.0,
+        })
+        .collect();
+    let mut samples = discounted.clone();
+    if config.antithetic {
+        samples = discounted.chunks(2).map(|c| (c[0] + c[1]) / 2.0).collect();
+    }
+    let m = samples.len() as f64;
+    let mean = samples.iter().sum::<f64>() / m;
+    let variance = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (m - 1.0).max(1.0);
+
+    let value = match (&process.spot, &process.volatility) {
+        (Number::F64(_), Number::F64(_)) => Number::F64(mean),
+        _ => {
+            let mut total = Number::F64(0.0);
+            for (i, z) in variates.iter().enumerate() {
+                if stopping[i] < steps {
+                    let spot = &process.path(expiry, z)[stopping[i]];
+                    let df = (-rate * dt * (stopping[i] + 1) as f64).exp();
+                    total = total + exercise(spot) * df;
+                }
+            }
+            total / n
+        }
+    };
+    Ok(McValue {
+        value,
+        std_error: (variance / m).sqrt(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dual::{Dual, Gradient1, SmoothFuncs};
+    use crate::lattice::SpotTree;
+    use ndarray::arr2;
+
+    fn put(s: &Number) -> Number {
+        (s * -1.0 + 100.0).max_of(&Number::F64(0.0), None)
+    }
+
+    #[test]
+    fn test_qr_least_squares() {
+        let a = arr2(&[[1.0, 0.0], [1.0, 1.0], [1.0, 2.0], [1.0, 3.0]]);
+        let b = Array1::from_vec(vec![1.0, 3.0, 5.0, 7.0]);
+        let x = qr_least_squares(&a, &b).unwrap();
+        assert!((x[0] - 1.0).abs() < 1e-12 && (x[1] - 2.0).abs() < 1e-12);
+        // a repeated column is given zero weight
+        let a = arr2(&[[1.0, 1.0], [1.0, 1.0], [1.0, 1.0]]);
+        let x = qr_least_squares(&a, &Array1::from_vec(vec![2.0; 3])).unwrap();
+        assert!((x[0] + x[1] - 2.0).abs() < 1e-12);
+        assert!(qr_least_squares(&arr2(&[[1.0, 2.0]]), &Array1::zeros(1)).is_err());
+    }
+
+    #[test]
+    fn test_basis() {
+        let p = RegressionBasis::Polynomial { degree: 2 };
+        assert_eq!(p.evaluate(2.0), vec![1.0, 2.0, 4.0]);
+        // L2(x) = (x^2 - 4x + 2) / 2
+        let l = RegressionBasis::Laguerre { degree: 2 };
+        let w = (-1.5_f64).exp();
+        let expected = [w, w * (1.0 - 3.0), w * (9.0 - 12.0 + 2.0) / 2.0];
+        for (a, b) in l.evaluate(3.0).iter().zip(expected.iter()) {
+            assert!((a - b).abs() < 1e-14);
+        }
+    }
+
+    #[test]
+    fn test_american_put_against_tree() {
+        let tree = SpotTree::try_new(100.0, 1.0, 0.05, 0.05, 0.2, 500).unwrap();
+        let (expected, expected_delta, _) = tree.value_delta_gamma(|s| (100.0 - s).max(0.0), true);
+        let process = LognormalProcess::try_new(
+            Number::Dual(Dual::new(100.0, vec!["s".to_string()])),
+            0.05,
+            Number::F64(0.2),
+        )
+        .unwrap();
+        let config = McConfig::try_new(20_000, 50, 17, true).unwrap();
+        for basis in [
+            RegressionBasis::Polynomial { degree: 3 },
+            RegressionBasis::Laguerre { degree: 3 },
+        ] {
+            let mc = lsm_value(&process, 1.0, 0.05, &put, basis, &config).unwrap();
+            let value = f64::from(&mc.value);
+            // a Bermudan exercise and suboptimal policy bias the value below the tree
+            assert!(value < expected + 3.0 * mc.std_error && value > expected - 0.15);
+            let delta = match &mc.value {
+                Number::Dual(d) => d.gradient1(vec!["s".to_string()])[0],
+                _ => panic!("expected a Dual"),
+            };
+            assert!((delta - expected_delta).abs() < 0.02);
+        }
+    }
+}
@@ -0,0 +1,27 @@
+//! Value path dependent payoffs by Monte Carlo simulation.
+//!
+//! Paths of a [LognormalProcess] are evolved from the normal variates of a seeded
+//! [NormalRng]. The spot and volatility of the process may be dual numbers so that
+//! [mc_value] returns pathwise Greeks, by propagating the dual numbers through each path, or,
+//! for discontinuous payoffs, likelihood ratio Greeks, as selected by the [GreekMethod].
+//! Options with early exercise are valued by Longstaff-Schwartz regression with [lsm_value].
+//! Paths of a [LocalVolProcess] evaluate the volatility of a
+//! [LocalVol](crate::fx::volatility::LocalVol) surface at each time step and are valued with
+//! [local_vol_value].
+
+#[cfg(feature = "python")]
+pub(crate) mod montecarlo_py;
+
+mod rng;
+pub use crate::montecarlo::rng::NormalRng;
+
+mod lognormal;
+pub use crate::montecarlo::lognormal::{
+    mc_value, GreekMethod, LognormalProcess, McConfig, McValue,
+};
+
+mod lsm;
+pub use crate::montecarlo::lsm::{lsm_value, qr_least_squares, RegressionBasis};
+
+mod local_vol;
+pub use crate::montecarlo::local_vol::{local_vol_value, LocalVolProcess};
@@ -0,0 +1,141 @@
+//! Wrapper module to export Monte Carlo valuation to Python using pyo3 bindings.
+
+use crate::cancel::CancellationToken;
+use crate::dual::{Number, SmoothFuncs};
+use crate::montecarlo::{mc_value, GreekMethod, LognormalProcess, McConfig};
+use crate::progress::progress_py::release;
+use pyo3::prelude::*;
+
+/// Value a European option on a lognormal spot by Monte Carlo simulation, with the GIL
+/// released.
+///
+/// Parameters
+/// ----------
+/// spot: float, Dual or Dual2
+///     The initial spot, whose sensitivities are propagated pathwise.
+/// volatility: float, Dual or Dual2
+///     The volatility of the spot, as a decimal per annum.
+/// carry: float
+///     The continuously compounded cost of carry of the spot.
+/// rate: float
+///     The continuously compounded discount rate.
+/// expiry: float
+///     The expiry of the option, in years.
+/// strike: float
+///     The strike of the option.
+/// call: bool
+///     Whether the option is a call, or otherwise a put.
+/// paths: int
+///     The number of simulated paths.
+/// steps: int
+///     The number of time steps of each path.
+/// seed: int
+///     The seed of the normal variates.
+/// antithetic: bool
+///     Whether each path is paired with its antithetic path.
+/// cancel: CancellationToken, optional
+///     A token by which another thread may interrupt the simulation.
+/// progress: Callable[[ProgressEvent], None], optional
+///     A callable of each `ProgressEvent` of the simulation, which aborts it by raising.
+///
+/// Returns
+/// -------
+/// tuple[float | Dual | Dual2, float]
+///     The value of the option and the standard error of its real component.
+#[pyfunction]
+#[pyo3(
+    name = "mc_european",
+    signature = (spot, volatility, carry, rate, expiry, strike, call=true, paths=10000, steps=1, seed=0, antithetic=true, cancel=None, progress=None)
+)]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn mc_european_py(
+    py: Python<'_>,
+    spot: Number,
+    volatility: Number,
+    carry: f64,
+    rate: f64,
+    expiry: f64,
+    strike: f64,
+    call: bool,
+    paths: usize,
+    steps: usize,
+    seed: u64,
+    antithetic: bool,
+    cancel: Option<CancellationToken>,
+    progress: Option<Py<PyAny>>,
+) -> PyResult<(Number, f64)> {
+    let process = LognormalProcess::try_new(spot, carry, volatility)?;
+    let config = McConfig::try_new(paths, steps, seed, antithetic)?;
+    let phi = if call { 1.0 } else { -1.0 };
+    let payoff = move |path: &[Number]| {
+        ((&path[path.len() - 1] - strike) * phi).max_of(&Number::F64(0.0), None)
+    };
+    release(py, cancel, progress, || {
+        let mc = mc_value(
+            &process,
+            expiry,
+            rate,
+            &payoff,
+            GreekMethod::Pathwise,
+            &config,
+        )?;
+        Ok((mc.value, mc.std_error))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::error_py::CancelledError;
+    use crate::models::black76;
+    use pyo3::types::PyDict;
+
+    #[test]
+    fn test_mc_european_py() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let european = |call, cancel, progress| {
+                mc_european_py(
+                    py,
+                    Number::F64(100.0),
+                    Number::F64(0.2),
+                    0.01,
+                    0.03,
+                    1.0,
+                    105.0,
+                    call,
+                    20_000,
+                    1,
+                    3,
+                    true,
+                    cancel,
+                    progress,
+                )
+            };
+            let forward = Number::F64(100.0 * 0.01_f64.exp());
+            let df = Number::F64((-0.03_f64).exp());
+            for (call, phi) in [(true, 1.0), (false, -1.0)] {
+                let (value, std_error) = european(call, None, None).unwrap();
+                let analytic = black76(&forward, 105.0, 1.0, &Number::F64(0.2), &df, phi);
+                assert!((f64::from(&value) - f64::from(&analytic)).abs() < 3.0 * std_error);
+            }
+            let token = CancellationToken::new();
+            token.cancel();
+            let result = european(true, Some(token), None);
+            assert!(result.unwrap_err().is_instance_of::<CancelledError>(py));
+            // a raising callback aborts the simulation at the first batch of paths
+            let globals = PyDict::new_bound(py);
+            py.run_bound(
+                "def abort(event):\n    raise KeyboardInterrupt(event.completed)",
+                Some(&globals),
+                None,
+            )
+            .unwrap();
+            let abort = globals.get_item("abort").unwrap().unwrap().unbind();
+            let result = european(true, None, Some(abort));
+            assert!(result
+                .unwrap_err()
+                .is_instance_of::<pyo3::exceptions::PyKeyboardInterrupt>(py));
+        });
+    }
+}
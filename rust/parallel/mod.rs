@@ -0,0 +1,215 @@
+//! Map work over items across threads, or sequentially where threads are unavailable.
+//!
+//! Threads are not spawned when compiled with the `wasm` feature, or for a `wasm32` target
+//! which has none, so that the same pricing code runs client side in a browser. The core
+//! builds for `wasm32-unknown-unknown` without the default `python` feature. Only the `golden`
+//! feature reads and writes files, and elapsed times are reported as zero on `wasm32` targets,
+//! which have no system clock.
+
+use crate::cancel;
+#[cfg(not(any(feature = "wasm", target_arch = "wasm32")))]
+use crate::cancel::CancellationToken;
+#[cfg(not(any(feature = "wasm", target_arch = "wasm32")))]
+use crate::curves::{cache, with_curve_cache, CacheStats};
+#[cfg(not(any(feature = "wasm", target_arch = "wasm32")))]
+use crate::dual::{
+    hessian_mode, vars_ordering, with_hessian_mode, with_vars_ordering, HessianMode, VarsOrdering,
+};
+use crate::error::RateslibError;
+
+/// The thread local settings of the caller of [par_map], under which each item is mapped.
+#[cfg(not(any(feature = "wasm", target_arch = "wasm32")))]
+struct Context {
+    token: Option<CancellationToken>,
+    hessian_mode: HessianMode,
+    vars_ordering: VarsOrdering,
+    curve_cache: bool,
+}
+
+#[cfg(not(any(feature = "wasm", target_arch = "wasm32")))]
+impl Context {
+    fn capture() -> Self {
+        Self {
+            token: cancel::current(),
+            hessian_mode: hessian_mode(),
+            vars_ordering: vars_ordering(),
+            curve_cache: cache::is_active(),
+        }
+    }
+
+    /// Run `f` on the current thread under the settings of the caller, returning the
+    /// statistics of its curve cache.
+    fn run<R>(&self, f: impl FnOnce() -> R) -> (R, CacheStats) {
+        let f = || {
+            with_hessian_mode(self.hessian_mode, || {
+                with_vars_ordering(self.vars_ordering, f)
+            })
+        };
+        let f = || match &self.token {
+            Some(token) => token.run(f),
+            None => f(),
+        };
+        match self.curve_cache {
+            true => with_curve_cache(f),
+            false => (f(), CacheStats::default()),
+        }
+    }
+}
+
+/// Map `f` over `items` in order, raising if cancelled between items.
+fn map_checked<T, R>(items: &[T], f: impl Fn(&T) -> R) -> Result<Vec<R>, RateslibError> {
+    items
+        .iter()
+        .map(|item| {
+            cancel::check()?;
+            Ok(f(item))
+        })
+        .collect()
+}
+
+/// Map `f` over `items` in parallel, in chunks across the available threads, preserving the
+/// order of the items.
+///
+/// Each thread runs under the cancellation token, progress callback, [HessianMode] and
+/// [VarsOrdering] of the caller, and is cancelled between items. If the caller has a curve
+/// cache each thread caches in its own, whose statistics are added to those of the caller.
+/// Progress events are reported to the callback on the calling thread, and an error of the
+/// callback aborts the map with that error.
+#[cfg(not(any(feature = "wasm", target_arch = "wasm32")))]
+pub(crate) fn par_map<T: Sync, R: Send>(
+    items: &[T],
+    f: impl Fn(&T) -> R + Sync,
+) -> Result<Vec<R>, RateslibError> {
+    use crate::progress::{self, ProgressEvent};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{mpsc, Arc};
+    use std::thread;
+
+    let context = Context::capture();
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk = items.len().div_ceil(threads).max(1);
+    let (sender, receiver) = mpsc::channel::<ProgressEvent>();
+    let forward = progress::is_active().then_some(sender);
+    let aborted = Arc::new(AtomicBool::new(false));
+    thread::scope(|s| {
+        let handles: Vec<_> = items
+            .chunks(chunk)
+            .map(|c| {
+                let (context, f) = (&context, &f);
+                let forward = forward.clone();
+                let aborted = aborted.clone();
+                s.spawn(move || {
+                    context.run(|| match forward {
+                        Some(sender) => progress::with_progress(
+                            move |event| match aborted.load(Ordering::Relaxed) {
+                                true => Err(RateslibError::Cancelled),
+                                false => {
+                                    let _ = sender.send(event.clone());
+                                    Ok(())
+                                }
+                            },
+                            || map_checked(c, f),
+                        ),
+                        None => map_checked(c, f),
+                    })
+                })
+            })
+            .collect();
+        drop(forward);
+        let mut error = None;
+        for event in receiver {
+            if error.is_none() {
+                if let Err(e) = progress::report(event) {
+                    aborted.store(true, Ordering::Relaxed);
+                    error = Some(e);
+                }
+            }
+        }
+        let results: Vec<_> = handles
+            .into_iter()
+            .map(|h| {
+                let (result, stats) = h.join().unwrap();
+                cache::record(stats);
+                result
+            })
+            .collect();
+        match error {
+            Some(e) => Err(e),
+            None => results.into_iter().try_fold(Vec::new(), |mut all, r| {
+                all.extend(r?);
+                Ok(all)
+            }),
+        }
+    })
+}
+
+/// Map `f` over `items` in order, raising if cancelled between items.
+#[cfg(any(feature = "wasm", target_arch = "wasm32"))]
+pub(crate) fn par_map<T: Sync, R: Send>(
+    items: &[T],
+    f: impl Fn(&T) -> R + Sync,
+) -> Result<Vec<R>, RateslibError> {
+    map_checked(items, f)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_par_map_preserves_order() {
+        let items: Vec<usize> = (0..37).collect();
+        let squares = par_map(&items, |i| i * i).unwrap();
+        assert_eq!(squares, items.iter().map(|i| i * i).collect::<Vec<_>>());
+        assert!(par_map(&[] as &[usize], |i| *i).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_par_map_runs_under_caller_context() {
+        use crate::cancel::CancellationToken;
+        use crate::dual::{
+            hessian_mode, vars_ordering, with_hessian_mode, with_vars_ordering, HessianMode,
+            VarsOrdering,
+        };
+
+        let items: Vec<usize> = (0..37).collect();
+        let modes = with_vars_ordering(VarsOrdering::Sorted, || {
+            with_hessian_mode(HessianMode::Lazy, || {
+                par_map(&items, |_| (hessian_mode(), vars_ordering())).unwrap()
+            })
+        });
+        assert!(modes
+            .iter()
+            .all(|m| *m == (HessianMode::Lazy, VarsOrdering::Sorted)));
+
+        let token = CancellationToken::new();
+        token.cancel();
+        let result = token.run(|| par_map(&items, |i| *i));
+        assert!(matches!(result, Err(RateslibError::Cancelled)));
+    }
+
+    #[test]
+    #[cfg(not(any(feature = "wasm", target_arch = "wasm32")))]
+    fn test_par_map_reports_to_caller() {
+        use crate::progress::{report, with_progress, ProgressEvent, ProgressStage};
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let items: Vec<usize> = (0..37).collect();
+        let count = Rc::new(Cell::new(0));
+        let seen = count.clone();
+        let result = with_progress(
+            move |_| {
+                seen.set(seen.get() + 1);
+                Err(RateslibError::value("aborted"))
+            },
+            || {
+                par_map(&items, |i| {
+                    report(ProgressEvent::new(ProgressStage::Valuation, *i, 37))
+                })
+            },
+        );
+        assert!(matches!(result, Err(RateslibError::Value(_))));
+        assert_eq!(count.get(), 1);
+    }
+}
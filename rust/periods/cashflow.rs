@@ -0,0 +1,51 @@
+use crate::dual::Number;
+use crate::error::RateslibError;
+use crate::periods::{Curves, Period};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// A single payment of a known amount, such as a notional exchange or fee.
+///
+/// A positive `notional` is paid, yielding a negative cashflow.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CashflowPeriod {
+    pub(crate) notional: f64,
+    pub(crate) payment: NaiveDateTime,
+}
+
+impl CashflowPeriod {
+    pub fn new(notional: f64, payment: NaiveDateTime) -> Self {
+        Self { notional, payment }
+    }
+}
+
+impl Period for CashflowPeriod {
+    fn payment(&self) -> NaiveDateTime {
+        self.payment
+    }
+
+    fn cashflow(&self, _curves: &Curves) -> Result<Number, RateslibError> {
+        Ok(Number::F64(-self.notional))
+    }
+
+    fn analytic_delta(&self, _curves: &Curves) -> Result<Number, RateslibError> {
+        Ok(Number::F64(0.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::ndt;
+    use crate::periods::period::tests::{curve_fixture, is_close};
+
+    #[test]
+    fn test_cashflow_period() {
+        let curve = curve_fixture("c");
+        let curves = Curves::new(None, Some(&curve));
+        let p = CashflowPeriod::new(1e6, ndt(2025, 1, 1));
+        let df = (-0.02_f64 * 366.0 / 365.0).exp();
+        assert!(is_close(&p.npv(&curves).unwrap(), -1e6 * df));
+        assert_eq!(p.analytic_delta(&curves).unwrap(), Number::F64(0.0));
+    }
+}
@@ -0,0 +1,395 @@
+use crate::curves::PricingCurve;
+use crate::dual::Number;
+use crate::error::RateslibError;
+use crate::models::black76;
+use crate::periods::{BasePeriod, Curves, Period};
+use chrono::{Months, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+/// The swap rate underlying a constant maturity swap coupon: a swap of `tenor_months` from
+/// the fixing date with fixed payments every `frequency_months`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CmsIndex {
+    pub(crate) tenor_months: u32,
+    pub(crate) frequency_months: u32,
+}
+
+impl CmsIndex {
+    pub fn try_new(tenor_months: u32, frequency_months: u32) -> Result<Self, RateslibError> {
+        if frequency_months == 0
+            || tenor_months == 0
+            || !tenor_months.is_multiple_of(frequency_months)
+        {
+            return Err(RateslibError::value(
+                "`tenor_months` of a `CmsIndex` must be a positive multiple of its frequency.",
+            ));
+        }
+        Ok(Self {
+            tenor_months,
+            frequency_months,
+        })
+    }
+
+    /// Return the forward swap rate, as a decimal, and annuity of the swap starting at
+    /// `start` on the `curve`.
+    fn forward(
+        &self,
+        curve: &dyn PricingCurve,
+        start: &NaiveDateTime,
+    ) -> Result<(Number, Number), RateslibError> {
+        let mut annuity = Number::F64(0.0);
+        let mut prev = *start;
+        for i in 1..=(self.tenor_months / self.frequency_months) {
+            let date = *start + Months::new(i * self.frequency_months);
+            annuity = annuity + curve.df(&date) * curve.dcf(&prev, &date)?;
+            prev = date;
+        }
+        let rate = (curve.df(start) - curve.df(&prev)) / &annuity;
+        Ok((rate, annuity))
+    }
+}
+
+/// The method of calculating the convexity adjustment of a constant maturity swap rate.
+///
+/// Both methods map the ratio of the payment discount factor to the annuity linearly in the
+/// swap rate, with a slope from Hagan's standard model of a flat curve.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum CmsMethod {
+    /// Static replication by payer and receiver swaptions, integrated over log-strikes by
+    /// Simpson's rule with `points` intervals either side of the forward.
+    Replication { points: usize },
+    /// Hagan's first order approximation of the swap rate variance, `S² σ² T`.
+    Hagan,
+}
+
+/// Return the expected swap rate of the `index`, in percent, fixing at `start` and paid at
+/// `payment` under its payment measure, with lognormal `volatility` as a decimal.
+fn cms_rate(
+    curve: &dyn PricingCurve,
+    start: &NaiveDateTime,
+    payment: &NaiveDateTime,
+    index: &CmsIndex,
+    volatility: &Number,
+    method: CmsMethod,
+) -> Result<Number, RateslibError> {
+    let t = (*start - curve.initial_date()).num_days() as f64 / 365.0;
+    if t <= 0.0 {
+        return Err(RateslibError::value(
+            "A `fixing` is required for a CMS period which has fixed.",
+        ));
+    }
+    let (forward, _) = index.forward(curve, start)?;
+    let s = f64::from(&forward);
+
+    // the relative slope of the annuity mapping function under a flat curve
+    let q = 12.0 / index.frequency_months as f64;
+    let (n, delay) = (
+        (index.tenor_months / index.frequency_months) as i32,
+        (*payment - *start).num_days() as f64 / 365.0 * q,
+    );
+    let g = 1.0 + s / q;
+    let annuity: f64 = (1..=n).map(|i| g.powi(-i) / q).sum();
+    let d_annuity: f64 = (1..=n)
+        .map(|i| -(i as f64) * g.powi(-i - 1) / (q * q))
+        .sum();
+    let slope = -delay / q / g - d_annuity / annuity;
+
+    let variance = match method {
+        CmsMethod::Hagan => forward.clone() * &forward * volatility * volatility * t,
+        CmsMethod::Replication { points } => {
+            let points = points.max(2) + points % 2;
+            let width = 8.0 * f64::from(volatility) * t.sqrt();
+            let h = width / points as f64;
+            let df = Number::F64(1.0);
+            let mut integral = Number::F64(0.0);
+            for phi in [1.0, -1.0] {
+                for i in 0..=points {
+                    let weight = match i {
+                        0 => 1.0,
+                        _ if i == points => 1.0,
+                        _ if i % 2 == 1 => 4.0,
+                        _ => 2.0,
+                    };
+                    let strike = s * (phi * i as f64 * h).exp();
+                    let option = black76(&forward, strike, t, volatility, &df, phi);
+                    integral = integral + option * (weight * strike * h / 3.0);
+                }
+            }
+            integral * 2.0
+        }
+    };
+    Ok((forward + variance * slope) * 100.0)
+}
+
+/// A period accruing a constant maturity swap rate, in percent, plus a `spread` in bps.
+///
+/// The swap rate of the `index` fixes at the start of the period, at which the lognormal
+/// `volatility` as a decimal is measured on an Act365F basis from the initial date of the
+/// forecasting curve, and includes a convexity adjustment for payment at the end of the
+/// period determined by the [CmsMethod]. A known `fixing` overrides the model. A positive
+/// `notional` pays the coupon, yielding a negative cashflow.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CmsPeriod {
+    pub(crate) base: BasePeriod,
+    pub(crate) index: CmsIndex,
+    pub(crate) volatility: Number,
+    pub(crate) method: CmsMethod,
+    pub(crate) spread: f64,
+    pub(crate) fixing: Option<f64>,
+}
+
+impl CmsPeriod {
+    pub fn new(
+        base: BasePeriod,
+        index: CmsIndex,
+        volatility: Number,
+        method: CmsMethod,
+        spread: f64,
+        fixing: Option<f64>,
+    ) -> Self {
+        Self {
+            base,
+            index,
+            volatility,
+            method,
+            spread,
+            fixing,
+        }
+    }
+
+    pub fn base(&self) -> &BasePeriod {
+        &self.base
+    }
+
+    /// Return the expected coupon of the period, in percent, under the payment measure.
+    pub fn rate(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        let rate = match self.fixing {
+            Some(fixing) => Number::F64(fixing),
+            None => cms_rate(
+                curves.forecasting()?,
+                &self.base.start,
+                &self.base.payment,
+                &self.index,
+                &self.volatility,
+                self.method,
+            )?,
+        };
+        Ok(rate + self.spread / 100.0)
+    }
+}
+
+impl Period for CmsPeriod {
+    fn payment(&self) -> NaiveDateTime {
+        self.base.payment
+    }
+
+    fn cashflow(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        Ok(self.rate(curves)? * (-self.base.notional * self.base.dcf * 0.01))
+    }
+
+    fn analytic_delta(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        Ok(self
+            .base
+            .analytic_delta(curves.discounting()?.df(&self.base.payment)))
+    }
+}
+
+/// A period accruing the spread between two convexity adjusted constant maturity swap rates,
+/// `long` less `short`, in percent, plus a `spread` in bps.
+///
+/// Each rate is determined as for a [CmsPeriod] with its own lognormal volatility, and a
+/// known `fixing` of the rate spread, in percent, overrides the model.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CmsSpreadPeriod {
+    pub(crate) base: BasePeriod,
+    pub(crate) long: (CmsIndex, Number),
+    pub(crate) short: (CmsIndex, Number),
+    pub(crate) method: CmsMethod,
+    pub(crate) spread: f64,
+    pub(crate) fixing: Option<f64>,
+}
+
+impl CmsSpreadPeriod {
+    pub fn new(
+        base: BasePeriod,
+        long: (CmsIndex, Number),
+        short: (CmsIndex, Number),
+        method: CmsMethod,
+        spread: f64,
+        fixing: Option<f64>,
+    ) -> Self {
+        Self {
+            base,
+            long,
+            short,
+            method,
+            spread,
+            fixing,
+        }
+    }
+
+    pub fn base(&self) -> &BasePeriod {
+        &self.base
+    }
+
+    /// Return the expected coupon of the period, in percent, under the payment measure.
+    pub fn rate(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        let rate = match self.fixing {
+            Some(fixing) => Number::F64(fixing),
+            None => {
+                let curve = curves.forecasting()?;
+                let (start, payment) = (&self.base.start, &self.base.payment);
+                cms_rate(
+                    curve,
+                    start,
+                    payment,
+                    &self.long.0,
+                    &self.long.1,
+                    self.method,
+                )? - cms_rate(
+                    curve,
+                    start,
+                    payment,
+                    &self.short.0,
+                    &self.short.1,
+                    self.method,
+                )?
+            }
+        };
+        Ok(rate + self.spread / 100.0)
+    }
+}
+
+impl Period for CmsSpreadPeriod {
+    fn payment(&self) -> NaiveDateTime {
+        self.base.payment
+    }
+
+    fn cashflow(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        Ok(self.rate(curves)? * (-self.base.notional * self.base.dcf * 0.01))
+    }
+
+    fn analytic_delta(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        Ok(self
+            .base
+            .analytic_delta(curves.discounting()?.df(&self.base.payment)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::{ndt, Convention};
+    use crate::dual::{Dual, Gradient1};
+    use crate::periods::period::tests::curve_fixture;
+
+    fn base_fixture() -> BasePeriod {
+        BasePeriod::new(
+            ndt(2026, 1, 1),
+            ndt(2026, 7, 1),
+            ndt(2026, 7, 1),
+            1e6,
+            Convention::Act365F,
+            181.0 / 365.0,
+            false,
+        )
+    }
+
+    fn index() -> CmsIndex {
+        CmsIndex::try_new(60, 12).unwrap()
+    }
+
+    fn vol() -> Number {
+        Number::Dual(Dual::new(0.3, vec!["vol".to_string()]))
+    }
+
+    #[test]
+    fn test_convexity_adjustment() {
+        let curve = curve_fixture("c");
+        let curves = Curves::new(Some(&curve), Some(&curve));
+        let (forward, _) = index().forward(&curve, &ndt(2026, 1, 1)).unwrap();
+        let forward = f64::from(&forward) * 100.0;
+        let replicated = CmsPeriod::new(
+            base_fixture(),
+            index(),
+            vol(),
+            CmsMethod::Replication { points: 200 },
+            0.0,
+            None,
+        )
+        .rate(&curves)
+        .unwrap();
+        let hagan = CmsPeriod::new(base_fixture(), index(), vol(), CmsMethod::Hagan, 0.0, None)
+            .rate(&curves)
+            .unwrap();
+        let (r, h) = (f64::from(&replicated), f64::from(&hagan));
+        // a positive adjustment which, for a flat lognormal smile, replicates the variance
+        // S² (exp(σ² T) - 1) of which the Hagan approximation is the first order term
+        assert!(r > forward && h > forward);
+        let t: f64 = 731.0 / 365.0;
+        assert!(
+            ((r - forward) / (h - forward) - ((0.09 * t).exp() - 1.0) / (0.09 * t)).abs() < 1e-4
+        );
+        // the adjustment increases with volatility
+        let vega = |n: &Number| match n {
+            Number::Dual(d) => d.gradient1(vec!["vol".to_string()])[0],
+            _ => panic!("expected a Dual"),
+        };
+        assert!(vega(&replicated) > 0.0);
+    }
+
+    #[test]
+    fn test_fixing_and_spread() {
+        let curve = curve_fixture("c");
+        let curves = Curves::new(Some(&curve), Some(&curve));
+        let p = CmsPeriod::new(
+            base_fixture(),
+            index(),
+            vol(),
+            CmsMethod::Hagan,
+            25.0,
+            Some(3.0),
+        );
+        assert_eq!(p.rate(&curves).unwrap(), Number::F64(3.25));
+        let cashflow = f64::from(&p.cashflow(&curves).unwrap());
+        assert!((cashflow + 1e6 * 181.0 / 365.0 * 0.0325).abs() < 1e-8);
+        let fixed = BasePeriod::new(
+            ndt(2023, 1, 1),
+            ndt(2023, 7, 1),
+            ndt(2023, 7, 1),
+            1e6,
+            Convention::Act365F,
+            0.5,
+            false,
+        );
+        let p = CmsPeriod::new(fixed, index(), vol(), CmsMethod::Hagan, 0.0, None);
+        assert!(p.rate(&curves).is_err());
+    }
+
+    #[test]
+    fn test_cms_spread() {
+        let curve = curve_fixture("c");
+        let curves = Curves::new(Some(&curve), Some(&curve));
+        let long = (CmsIndex::try_new(120, 12).unwrap(), Number::F64(0.25));
+        let short = (CmsIndex::try_new(24, 12).unwrap(), Number::F64(0.35));
+        let p = CmsSpreadPeriod::new(
+            base_fixture(),
+            long.clone(),
+            short.clone(),
+            CmsMethod::Hagan,
+            10.0,
+            None,
+        );
+        let rate = f64::from(&p.rate(&curves).unwrap());
+        let single = |i: (CmsIndex, Number)| {
+            f64::from(
+                &CmsPeriod::new(base_fixture(), i.0, i.1, CmsMethod::Hagan, 0.0, None)
+                    .rate(&curves)
+                    .unwrap(),
+            )
+        };
+        let expected = single(long) - single(short) + 0.1;
+        assert!((rate - expected).abs() < 1e-12);
+        assert!(CmsIndex::try_new(18, 12).is_err());
+    }
+}
@@ -0,0 +1,350 @@
+use crate::dual::{Dual, MathFuncs, Number};
+use crate::error::RateslibError;
+use crate::periods::{BasePeriod, Curves, Period};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// A premium period of a credit default swap accruing a fixed rate, in percent, which is
+/// paid conditional on survival.
+///
+/// The forecasting curve returns survival probabilities. If `premium_accrued` the premium
+/// accrued up to default is also valued, assuming default occurs at mid-period on average.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CreditPremiumPeriod {
+    pub(crate) base: BasePeriod,
+    pub(crate) fixed_rate: f64,
+    pub(crate) premium_accrued: bool,
+}
+
+impl CreditPremiumPeriod {
+    pub fn new(base: BasePeriod, fixed_rate: f64, premium_accrued: bool) -> Self {
+        Self {
+            base,
+            fixed_rate,
+            premium_accrued,
+        }
+    }
+
+    pub fn base(&self) -> &BasePeriod {
+        &self.base
+    }
+
+    /// Return the survival weighted discount factor of the premium payment.
+    fn risky_df(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        let disc = curves.discounting()?;
+        let hazard = curves.forecasting()?;
+        let df = disc.df(&self.base.payment);
+        let q_end = hazard.df(&self.base.end);
+        if self.premium_accrued {
+            let q_start = hazard.df(&self.base.start);
+            Ok(&df * (&q_end + (q_start - &q_end) * 0.5))
+        } else {
+            Ok(df * q_end)
+        }
+    }
+}
+
+impl Period for CreditPremiumPeriod {
+    fn payment(&self) -> NaiveDateTime {
+        self.base.payment
+    }
+
+    fn cashflow(&self, _curves: &Curves) -> Result<Number, RateslibError> {
+        Ok(Number::F64(
+            -self.base.notional * self.base.dcf * self.fixed_rate * 0.01,
+        ))
+    }
+
+    fn analytic_delta(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        Ok(self.base.analytic_delta(self.risky_df(curves)?))
+    }
+
+    fn npv(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        if self.base.payment < curves.discounting()?.initial_date() {
+            return Ok(Number::F64(0.0));
+        }
+        Ok(self.cashflow(curves)? * self.risky_df(curves)?)
+    }
+}
+
+/// The recovery rate of a reference entity on default, as a fraction of the notional, which is
+/// either constant or a term structure by the date of default.
+///
+/// Each rate is a [Number], so that a rate which is a variable, such as one created by
+/// [Recovery::variable], carries the recovery sensitivity of a credit instrument in its
+/// gradient. A term structure is interpolated linearly between its dates and is flat outside
+/// them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Recovery {
+    Flat(Number),
+    Term(Vec<(NaiveDateTime, Number)>),
+}
+
+impl Recovery {
+    /// Return a constant recovery `rate` which is the variable `id`.
+    pub fn variable(id: &str, rate: f64) -> Self {
+        Recovery::Flat(Number::Dual(Dual::new(rate, vec![id.to_string()])))
+    }
+
+    /// Create a term structure of the recovery rates of defaults on the `nodes` dates, which
+    /// must be in increasing order.
+    pub fn try_term(nodes: Vec<(NaiveDateTime, Number)>) -> Result<Self, RateslibError> {
+        if nodes.is_empty() || nodes.windows(2).any(|w| w[0].0 >= w[1].0) {
+            return Err(RateslibError::value(
+                "A `Recovery` term structure requires dates in increasing order.",
+            ));
+        }
+        Ok(Recovery::Term(nodes))
+    }
+
+    /// Return the recovery rate of a default on `date`.
+    pub fn rate(&self, date: &NaiveDateTime) -> Number {
+        let nodes = match self {
+            Recovery::Flat(rate) => return rate.clone(),
+            Recovery::Term(nodes) => nodes,
+        };
+        let i = nodes.partition_point(|(d, _)| d <= date);
+        match i {
+            0 => nodes[0].1.clone(),
+            i if i == nodes.len() => nodes[i - 1].1.clone(),
+            i => {
+                let ((d1, r1), (d2, r2)) = (&nodes[i - 1], &nodes[i]);
+                let w = (*date - *d1).num_seconds() as f64 / (*d2 - *d1).num_seconds() as f64;
+                r1 + &((r2 - r1) * w)
+            }
+        }
+    }
+}
+
+impl From<f64> for Recovery {
+    fn from(rate: f64) -> Self {
+        Recovery::Flat(Number::F64(rate))
+    }
+}
+
+/// A protection period of a credit default swap, which pays the loss given default,
+/// `1 - recovery`, of the notional on a default between its start and end dates.
+///
+/// The recovery rate of a term structure is measured at the middle of each integration step.
+///
+/// The forecasting curve returns survival probabilities. The payment on default is valued as in
+/// the ISDA standard model, integrated over steps of at most `discretization` days with the
+/// hazard rate and the interest rate assumed constant over each step, which is exact only where
+/// the curves are flat forward between steps. Protection starts no earlier than the initial date
+/// of the discounting curve.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CreditProtectionPeriod {
+    pub(crate) start: NaiveDateTime,
+    pub(crate) end: NaiveDateTime,
+    pub(crate) notional: f64,
+    pub(crate) recovery: Recovery,
+    pub(crate) discretization: u32,
+}
+
+impl CreditProtectionPeriod {
+    pub fn new(
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+        notional: f64,
+        recovery: Recovery,
+    ) -> Self {
+        Self {
+            start,
+            end,
+            notional,
+            recovery,
+            discretization: 23,
+        }
+    }
+
+    /// Return the period integrated over steps of at most `days`, by default 23.
+    pub fn with_discretization(mut self, days: u32) -> Self {
+        self.discretization = days.max(1);
+        self
+    }
+
+    pub fn start(&self) -> NaiveDateTime {
+        self.start
+    }
+
+    pub fn end(&self) -> NaiveDateTime {
+        self.end
+    }
+
+    pub fn notional(&self) -> f64 {
+        self.notional
+    }
+
+    pub fn recovery(&self) -> &Recovery {
+        &self.recovery
+    }
+
+    /// Return the dates of the integration steps from `start`.
+    fn steps(&self, start: NaiveDateTime) -> Vec<NaiveDateTime> {
+        let mut steps = vec![start];
+        let step = chrono::Days::new(self.discretization as u64);
+        while *steps.last().unwrap() + step < self.end {
+            steps.push(*steps.last().unwrap() + step);
+        }
+        steps.push(self.end);
+        steps
+    }
+}
+
+impl Period for CreditProtectionPeriod {
+    fn payment(&self) -> NaiveDateTime {
+        self.end
+    }
+
+    /// Return the expected payment on default, undiscounted, with the recovery rate at the
+    /// middle of the period.
+    fn cashflow(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        let hazard = curves.forecasting()?;
+        let default = hazard.df(&self.start) - hazard.df(&self.end);
+        let mid = self.start + (self.end - self.start) / 2;
+        Ok(default * (1.0 - self.recovery.rate(&mid)) * self.notional)
+    }
+
+    fn analytic_delta(&self, _curves: &Curves) -> Result<Number, RateslibError> {
+        Ok(Number::F64(0.0))
+    }
+
+    fn npv(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        let disc = curves.discounting()?;
+        let hazard = curves.forecasting()?;
+        let start = self.start.max(disc.initial_date());
+        if self.end <= start {
+            return Ok(Number::F64(0.0));
+        }
+        let mut value = Number::F64(0.0);
+        for w in self.steps(start).windows(2) {
+            let (p0, p1) = (disc.df(&w[0]), disc.df(&w[1]));
+            let (q0, q1) = (hazard.df(&w[0]), hazard.df(&w[1]));
+            // with constant rates the step is worth h / (h + r) of the change in P * Q, which
+            // tends to h P Q as h + r tends to zero
+            let h = (&q0 / &q1).log();
+            let x = &h + (&p0 / &p1).log();
+            let default = match f64::from(&x).abs() < 1e-8 {
+                true => &h * &p0 * &q0 * (1.0 - &x * 0.5),
+                false => &h / &x * (&p0 * &q0 - p1 * q1),
+            };
+            let loss = 1.0 - self.recovery.rate(&(w[0] + (w[1] - w[0]) / 2));
+            value = value + default * loss;
+        }
+        Ok(value * self.notional)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::{ndt, Convention, Modifier, NamedCal};
+    use crate::curves::{CurveDF, LogLinearInterpolator, Nodes, PricingCurve};
+    use crate::dual::Gradient1;
+    use crate::periods::period::tests::{curve_fixture, is_close};
+    use indexmap::IndexMap;
+
+    #[test]
+    fn test_credit_premium_period() {
+        let disc = curve_fixture("d");
+        let hazard = curve_fixture("h");
+        let curves = Curves::new(Some(&hazard), Some(&disc));
+        let base = BasePeriod::new(
+            ndt(2024, 3, 20),
+            ndt(2024, 6, 20),
+            ndt(2024, 6, 20),
+            1e6,
+            Convention::Act360,
+            92.0 / 360.0,
+            false,
+        );
+        let cf = -1e6 * 92.0 / 360.0 * 0.01;
+        let df = f64::from(disc.df(&ndt(2024, 6, 20)));
+        let q_start = f64::from(hazard.df(&ndt(2024, 3, 20)));
+
+        let p = CreditPremiumPeriod::new(base.clone(), 1.0, false);
+        assert!(is_close(&p.npv(&curves).unwrap(), cf * df * df));
+
+        let p = CreditPremiumPeriod::new(base, 1.0, true);
+        let expected = cf * df * (df + 0.5 * (q_start - df));
+        assert!(is_close(&p.npv(&curves).unwrap(), expected));
+    }
+
+    #[test]
+    fn test_credit_protection_period() {
+        // with constant hazard and interest rates of 2% the protection is worth half of the
+        // change in the product of the discount factor and survival probability
+        let disc = curve_fixture("d");
+        let hazard = curve_fixture("h");
+        let curves = Curves::new(Some(&hazard), Some(&disc));
+        let (start, end) = (ndt(2024, 3, 20), ndt(2029, 3, 20));
+        let p = CreditProtectionPeriod::new(start, end, 1e6, 0.4.into());
+        let pq = |d| f64::from(disc.df(&d)).powi(2);
+        let expected = 1e6 * 0.6 * 0.5 * (pq(start) - pq(end));
+        assert!((f64::from(&p.npv(&curves).unwrap()) - expected).abs() < 1e-6);
+        // the value is independent of the integration steps
+        let daily = p.clone().with_discretization(1).npv(&curves).unwrap();
+        assert!((f64::from(&daily) - expected).abs() < 1e-6);
+        let (start, end) = (ndt(2023, 1, 1), ndt(2023, 6, 1));
+        let historic = CreditProtectionPeriod::new(start, end, 1e6, 0.4.into());
+        assert!(is_close(&historic.npv(&curves).unwrap(), 0.0));
+    }
+
+    #[test]
+    fn test_credit_protection_zero_total_rate() {
+        // with a hazard rate of 2% and an interest rate of -2% the product of the discount
+        // factor and survival probability is one, and the protection is worth 2% per annum
+        let nodes = Nodes::F64(IndexMap::from_iter(vec![
+            (ndt(2024, 1, 1), 1.0_f64),
+            (ndt(2034, 1, 1), (0.02_f64 * 3653.0 / 365.0).exp()),
+        ]));
+        let disc = CurveDF::try_new(
+            nodes,
+            LogLinearInterpolator::new(),
+            "d",
+            Convention::Act365F,
+            Modifier::ModF,
+            None,
+            NamedCal::try_new("all").unwrap(),
+        )
+        .unwrap();
+        let hazard = curve_fixture("h");
+        let curves = Curves::new(Some(&hazard), Some(&disc));
+        let (start, end) = (ndt(2024, 1, 1), ndt(2029, 1, 1));
+        let p = CreditProtectionPeriod::new(start, end, 1e6, 0.4.into());
+        let expected = 1e6 * 0.6 * 0.02 * 1827.0 / 365.0;
+        assert!((f64::from(&p.npv(&curves).unwrap()) - expected).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_recovery_sensitivity() {
+        let disc = curve_fixture("d");
+        let hazard = curve_fixture("h");
+        let curves = Curves::new(Some(&hazard), Some(&disc));
+        let (start, end) = (ndt(2024, 3, 20), ndt(2029, 3, 20));
+        let flat = CreditProtectionPeriod::new(start, end, 1e6, 0.4.into());
+        let p = CreditProtectionPeriod::new(start, end, 1e6, Recovery::variable("rr", 0.4));
+        let Number::Dual(npv) = p.npv(&curves).unwrap() else {
+            panic!("the variable recovery is a dual number")
+        };
+        // the loss given default is linear in the recovery rate
+        let rr01 = npv.gradient1(vec!["rr".to_string()])[0];
+        assert!((rr01 + f64::from(&flat.npv(&curves).unwrap()) / 0.6).abs() < 1e-6);
+
+        let term = Recovery::try_term(vec![
+            (ndt(2025, 1, 1), Number::F64(0.2)),
+            (ndt(2027, 1, 1), Number::F64(0.6)),
+        ])
+        .unwrap();
+        assert!(is_close(&term.rate(&ndt(2024, 1, 1)), 0.2));
+        assert!(is_close(
+            &term.rate(&ndt(2026, 1, 1)),
+            0.2 + 0.4 * 365.0 / 730.0
+        ));
+        assert!(is_close(&term.rate(&ndt(2030, 1, 1)), 0.6));
+        let p = CreditProtectionPeriod::new(start, end, 1e6, term);
+        let value = f64::from(&p.npv(&curves).unwrap());
+        assert!(value > 0.0 && value < f64::from(&flat.npv(&curves).unwrap()) / 0.6);
+        assert!(Recovery::try_term(vec![]).is_err());
+    }
+}
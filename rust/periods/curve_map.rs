@@ -0,0 +1,167 @@
+use crate::curves::PricingCurve;
+use crate::error::RateslibError;
+use crate::periods::Curves;
+use indexmap::IndexMap;
+
+/// A mapping of names to curves, with aliases of other names and a fallback curve, from which
+/// the [Curves] of a valuation are resolved.
+///
+/// An alias such as `"eur_estr" -> "eur_disc"` maps a name onto another name, which may itself
+/// be an alias. Names which are neither curves nor aliases resolve to the `fallback`, if set.
+#[derive(Clone, Default)]
+pub struct CurveMap<'a> {
+    pub(crate) curves: IndexMap<String, &'a dyn PricingCurve>,
+    pub(crate) aliases: IndexMap<String, String>,
+    pub(crate) fallback: Option<String>,
+}
+
+impl<'a> CurveMap<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the map with a `curve` mapped to `name`, replacing any alias of the same name.
+    pub fn with_curve(mut self, name: &str, curve: &'a dyn PricingCurve) -> Self {
+        self.aliases.shift_remove(name);
+        self.curves.insert(name.to_string(), curve);
+        self
+    }
+
+    /// Return the map with `alias` resolving to `target`, or raise if `alias` names a curve or
+    /// the alias would be circular.
+    pub fn with_alias(mut self, alias: &str, target: &str) -> Result<Self, RateslibError> {
+        if self.curves.contains_key(alias) {
+            return Err(RateslibError::value(format!(
+                "Alias '{}' of the `CurveMap` is already the name of a curve.",
+                alias
+            )));
+        }
+        let mut name = target;
+        while let Some(next) = self.aliases.get(name) {
+            name = next;
+            if name == alias {
+                break;
+            }
+        }
+        if name == alias {
+            return Err(RateslibError::value(format!(
+                "Alias '{}' of the `CurveMap` resolves to itself.",
+                alias
+            )));
+        }
+        self.aliases.insert(alias.to_string(), target.to_string());
+        Ok(self)
+    }
+
+    /// Return the map with names which are neither curves nor aliases resolving to `name`.
+    pub fn with_fallback(mut self, name: &str) -> Self {
+        self.fallback = Some(name.to_string());
+        self
+    }
+
+    /// Return the name of the curve to which `name` resolves.
+    pub fn resolve(&self, name: &str) -> Result<&str, RateslibError> {
+        let mut resolved = name;
+        while let Some(target) = self.aliases.get(resolved) {
+            resolved = target;
+        }
+        if let Some((key, _)) = self.curves.get_key_value(resolved) {
+            return Ok(key);
+        }
+        match &self.fallback {
+            Some(fallback) if self.curves.contains_key(fallback) => Ok(fallback),
+            _ => Err(RateslibError::value(format!(
+                "Curve '{}' is not mapped in the `CurveMap`, which has curves: {}.",
+                name,
+                self.names().join(", ")
+            ))),
+        }
+    }
+
+    /// Return the curve to which `name` resolves.
+    pub fn get(&self, name: &str) -> Result<&'a dyn PricingCurve, RateslibError> {
+        Ok(self.curves[self.resolve(name)?])
+    }
+
+    /// Return the names of the mapped curves, excluding aliases.
+    pub fn names(&self) -> Vec<String> {
+        self.curves.keys().cloned().collect()
+    }
+
+    /// Raise, before any valuation, if any of `names` does not resolve to a curve, listing
+    /// every name which does not.
+    pub fn validate(&self, names: &[&str]) -> Result<(), RateslibError> {
+        let missing: Vec<&str> = names
+            .iter()
+            .filter(|n| self.resolve(n).is_err())
+            .copied()
+            .collect();
+        match missing.is_empty() {
+            true => Ok(()),
+            false => Err(RateslibError::value(format!(
+                "Curves {} are not mapped in the `CurveMap`, which has curves: {}.",
+                missing
+                    .iter()
+                    .map(|n| format!("'{}'", n))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                self.names().join(", ")
+            ))),
+        }
+    }
+
+    /// Return the [Curves] of the curves to which the `forecasting` and `discounting` names
+    /// resolve.
+    pub fn curves(
+        &self,
+        forecasting: Option<&str>,
+        discounting: Option<&str>,
+    ) -> Result<Curves<'a>, RateslibError> {
+        Ok(Curves::new(
+            forecasting.map(|n| self.get(n)).transpose()?,
+            discounting.map(|n| self.get(n)).transpose()?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::periods::period::tests::curve_fixture;
+
+    #[test]
+    fn test_resolve_aliases_and_fallback() {
+        let (disc, estr) = (curve_fixture("eur_disc"), curve_fixture("eur_3m"));
+        let map = CurveMap::new()
+            .with_curve("eur_disc", &disc)
+            .with_curve("eur_3m", &estr)
+            .with_alias("eur_estr", "eur_disc")
+            .unwrap()
+            .with_alias("eur_ois", "eur_estr")
+            .unwrap();
+        assert_eq!(map.resolve("eur_ois").unwrap(), "eur_disc");
+        assert_eq!(map.get("eur_3m").unwrap().id(), "eur_3m");
+        let curves = map.curves(Some("eur_3m"), Some("eur_estr")).unwrap();
+        assert_eq!(curves.discounting().unwrap().id(), "eur_disc");
+        assert!(map.resolve("usd_sofr").is_err());
+        let err = map
+            .validate(&["eur_ois", "usd_sofr", "gbp_sonia"])
+            .unwrap_err();
+        assert!(err.to_string().contains("'usd_sofr', 'gbp_sonia'"));
+        let map = map.with_fallback("eur_disc");
+        assert_eq!(map.resolve("usd_sofr").unwrap(), "eur_disc");
+        assert!(map.validate(&["usd_sofr"]).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_aliases() {
+        let disc = curve_fixture("eur_disc");
+        let map = CurveMap::new()
+            .with_curve("eur_disc", &disc)
+            .with_alias("a", "b")
+            .unwrap();
+        assert!(map.clone().with_alias("eur_disc", "a").is_err());
+        assert!(map.clone().with_alias("b", "a").is_err());
+        assert!(map.with_alias("c", "c").is_err());
+    }
+}
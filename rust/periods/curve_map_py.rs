@@ -0,0 +1,81 @@
+//! Wrapper module to export the Rust curve map to Python using pyo3 bindings.
+
+use crate::curves::curve_py::Curve;
+use crate::curves::PricingCurve;
+use crate::periods::CurveMap;
+use indexmap::IndexMap;
+use pyo3::prelude::*;
+
+/// A mapping of names to curves, with aliases and a fallback, which raises on construction
+/// for circular aliases and on lookup with the names of the mapped curves.
+#[pyclass(module = "rateslib.rs", name = "CurveMap")]
+#[derive(Clone)]
+pub(crate) struct PyCurveMap {
+    curves: IndexMap<String, Curve>,
+    aliases: IndexMap<String, String>,
+    fallback: Option<String>,
+}
+
+impl PyCurveMap {
+    fn map(&self) -> PyResult<CurveMap<'_>> {
+        let mut map = CurveMap::new();
+        for (name, curve) in self.curves.iter() {
+            map = map.with_curve(name, curve as &dyn PricingCurve);
+        }
+        for (alias, target) in self.aliases.iter() {
+            map = map.with_alias(alias, target)?;
+        }
+        Ok(match &self.fallback {
+            Some(fallback) => map.with_fallback(fallback),
+            None => map,
+        })
+    }
+}
+
+#[pymethods]
+impl PyCurveMap {
+    #[new]
+    #[pyo3(signature = (curves, aliases=None, fallback=None))]
+    fn new_py(
+        curves: IndexMap<String, Curve>,
+        aliases: Option<IndexMap<String, String>>,
+        fallback: Option<String>,
+    ) -> PyResult<Self> {
+        let map = Self {
+            curves,
+            aliases: aliases.unwrap_or_default(),
+            fallback,
+        };
+        map.map()?;
+        Ok(map)
+    }
+
+    /// Return the name of the curve to which `name` resolves.
+    fn resolve(&self, name: &str) -> PyResult<String> {
+        Ok(self.map()?.resolve(name)?.to_string())
+    }
+
+    /// Raise if any of `names` does not resolve to a curve.
+    fn validate(&self, names: Vec<String>) -> PyResult<()> {
+        let names: Vec<&str> = names.iter().map(|n| n.as_str()).collect();
+        Ok(self.map()?.validate(&names)?)
+    }
+
+    /// Return the names of the mapped curves, excluding aliases.
+    fn names(&self) -> Vec<String> {
+        self.curves.keys().cloned().collect()
+    }
+
+    fn __getitem__(&self, name: &str) -> PyResult<Curve> {
+        let key = self.resolve(name)?;
+        Ok(self.curves[&key].clone())
+    }
+
+    fn __contains__(&self, name: &str) -> PyResult<bool> {
+        Ok(self.map()?.resolve(name).is_ok())
+    }
+
+    fn __len__(&self) -> usize {
+        self.curves.len()
+    }
+}
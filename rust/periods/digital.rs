@@ -0,0 +1,192 @@
+use crate::dual::Number;
+use crate::error::RateslibError;
+use crate::models::{black76_digital, DigitalMethod, DigitalPayout};
+use crate::periods::{BasePeriod, Curves, Period};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// A digital caplet, or floorlet, on the floating rate of the period, valued with the Black-76
+/// model of a lognormal forward rate.
+///
+/// If the rate fixes above, or for a floorlet below, the `strike`, in percent, the period
+/// accrues a [DigitalPayout::Cash] `amount` in percent, or for a [DigitalPayout::Asset] payout
+/// the floating rate itself. A positive `notional` pays the coupon, yielding a negative
+/// cashflow. The rate fixes at the start of the period, at which the lognormal `volatility`
+/// as a decimal is measured on an Act365F basis from the initial date of the forecasting
+/// curve, and a known `fixing` overrides the model.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DigitalCapletPeriod {
+    pub(crate) base: BasePeriod,
+    pub(crate) strike: f64,
+    pub(crate) cap: bool,
+    pub(crate) payout: DigitalPayout,
+    pub(crate) method: DigitalMethod,
+    pub(crate) volatility: Number,
+    pub(crate) fixing: Option<f64>,
+}
+
+impl DigitalCapletPeriod {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        base: BasePeriod,
+        strike: f64,
+        cap: bool,
+        payout: DigitalPayout,
+        method: DigitalMethod,
+        volatility: Number,
+        fixing: Option<f64>,
+    ) -> Self {
+        Self {
+            base,
+            strike,
+            cap,
+            payout,
+            method,
+            volatility,
+            fixing,
+        }
+    }
+
+    pub fn base(&self) -> &BasePeriod {
+        &self.base
+    }
+
+    /// Return the expected coupon of the period, in percent, under the payment measure.
+    pub fn rate(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        self.expected(curves, self.payout)
+    }
+
+    fn expected(&self, curves: &Curves, payout: DigitalPayout) -> Result<Number, RateslibError> {
+        let phi = if self.cap { 1.0 } else { -1.0 };
+        if let Some(fixing) = self.fixing {
+            let in_the_money = (fixing - self.strike) * phi > 0.0;
+            return Ok(Number::F64(match (in_the_money, payout) {
+                (false, _) => 0.0,
+                (true, DigitalPayout::Cash { amount }) => amount,
+                (true, DigitalPayout::Asset) => fixing,
+            }));
+        }
+        let curve = curves.forecasting()?;
+        let t = (self.base.start - curve.initial_date()).num_days() as f64 / 365.0;
+        if t <= 0.0 {
+            return Err(RateslibError::value(
+                "A `fixing` is required for a `DigitalCapletPeriod` which has fixed.",
+            ));
+        }
+        let forward = curve.rate(&self.base.start, &self.base.end)?;
+        Ok(black76_digital(
+            &forward,
+            self.strike,
+            t,
+            &self.volatility,
+            &Number::F64(1.0),
+            phi,
+            payout,
+            self.method,
+        ))
+    }
+}
+
+impl Period for DigitalCapletPeriod {
+    fn payment(&self) -> NaiveDateTime {
+        self.base.payment
+    }
+
+    fn cashflow(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        Ok(self.rate(curves)? * (-self.base.notional * self.base.dcf * 0.01))
+    }
+
+    /// Return the change in NPV for a 1bp increase in the digital coupon, weighted by the
+    /// probability of the coupon being paid.
+    fn analytic_delta(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        let probability = self.expected(curves, DigitalPayout::Cash { amount: 1.0 })?;
+        Ok(self
+            .base
+            .analytic_delta(curves.discounting()?.df(&self.base.payment))
+            * probability)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::{ndt, Convention};
+    use crate::dual::{Dual, Gradient1};
+    use crate::periods::period::tests::{curve_fixture, is_close};
+    use crate::periods::FloatPeriod;
+
+    fn base_fixture() -> BasePeriod {
+        BasePeriod::new(
+            ndt(2025, 1, 1),
+            ndt(2025, 7, 1),
+            ndt(2025, 7, 1),
+            1e6,
+            Convention::Act365F,
+            181.0 / 365.0,
+            false,
+        )
+    }
+
+    fn caplet(payout: DigitalPayout, cap: bool, method: DigitalMethod) -> DigitalCapletPeriod {
+        let vol = Number::Dual(Dual::new(0.3, vec!["vol".to_string()]));
+        DigitalCapletPeriod::new(base_fixture(), 2.0, cap, payout, method, vol, None)
+    }
+
+    #[test]
+    fn test_cap_floor_parity() {
+        // a digital cap and floor on the fixing together pay the floating rate
+        let curve = curve_fixture("c");
+        let curves = Curves::new(Some(&curve), Some(&curve));
+        let method = DigitalMethod::CallSpread { width: 0.1 };
+        let cap = caplet(DigitalPayout::Asset, true, method)
+            .npv(&curves)
+            .unwrap();
+        let floor = caplet(DigitalPayout::Asset, false, method)
+            .npv(&curves)
+            .unwrap();
+        let float = FloatPeriod::new(base_fixture(), 0.0, None)
+            .npv(&curves)
+            .unwrap();
+        assert!(is_close(&(cap + floor), f64::from(&float)));
+    }
+
+    #[test]
+    fn test_digital_vega() {
+        // the forward is above the strike so a cash digital caplet loses value with volatility
+        let curve = curve_fixture("c");
+        let curves = Curves::new(Some(&curve), Some(&curve));
+        let payout = DigitalPayout::Cash { amount: 1.0 };
+        let npv = caplet(payout, true, DigitalMethod::Analytic)
+            .npv(&curves)
+            .unwrap();
+        let replicated = caplet(payout, true, DigitalMethod::CallSpread { width: 1e-3 })
+            .npv(&curves)
+            .unwrap();
+        let vega = |n: &Number| match n {
+            Number::Dual(d) => d.gradient1(vec!["vol".to_string()])[0],
+            _ => panic!("expected a Dual"),
+        };
+        // the holder pays the coupon with a positive notional
+        assert!(vega(&npv) > 0.0);
+        assert!((vega(&npv) - vega(&replicated)).abs() < 1e-3 * vega(&npv).abs());
+        assert!(f64::from(&npv) < 0.0 && f64::from(&npv) > -1e6 * 181.0 / 365.0 * 0.01);
+    }
+
+    #[test]
+    fn test_fixed_caplet() {
+        let curve = curve_fixture("c");
+        let curves = Curves::new(Some(&curve), Some(&curve));
+        let mut p = caplet(
+            DigitalPayout::Cash { amount: 1.5 },
+            true,
+            DigitalMethod::Analytic,
+        );
+        p.fixing = Some(2.5);
+        assert_eq!(p.rate(&curves).unwrap(), Number::F64(1.5));
+        p.fixing = Some(1.5);
+        assert_eq!(p.rate(&curves).unwrap(), Number::F64(0.0));
+        p.fixing = None;
+        p.base.start = ndt(2023, 7, 1);
+        assert!(p.rate(&curves).is_err());
+    }
+}
@@ -0,0 +1,75 @@
+use crate::dual::Number;
+use crate::error::RateslibError;
+use crate::periods::{BasePeriod, Curves, Period};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// A period accruing a fixed rate, in percent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FixedPeriod {
+    pub(crate) base: BasePeriod,
+    pub(crate) fixed_rate: f64,
+}
+
+impl FixedPeriod {
+    pub fn new(base: BasePeriod, fixed_rate: f64) -> Self {
+        Self { base, fixed_rate }
+    }
+
+    pub fn base(&self) -> &BasePeriod {
+        &self.base
+    }
+
+    pub fn fixed_rate(&self) -> f64 {
+        self.fixed_rate
+    }
+}
+
+impl Period for FixedPeriod {
+    fn payment(&self) -> NaiveDateTime {
+        self.base.payment
+    }
+
+    fn cashflow(&self, _curves: &Curves) -> Result<Number, RateslibError> {
+        Ok(Number::F64(
+            -self.base.notional * self.base.dcf * self.fixed_rate * 0.01,
+        ))
+    }
+
+    fn analytic_delta(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        Ok(self
+            .base
+            .analytic_delta(curves.discounting()?.df(&self.base.payment)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::{ndt, Convention};
+    use crate::periods::period::tests::{curve_fixture, is_close};
+
+    #[test]
+    fn test_fixed_period() {
+        let curve = curve_fixture("c");
+        let curves = Curves::new(None, Some(&curve));
+        let base = BasePeriod::new(
+            ndt(2024, 1, 1),
+            ndt(2025, 1, 1),
+            ndt(2025, 1, 1),
+            1e6,
+            Convention::Act360,
+            366.0 / 360.0,
+            false,
+        );
+        let p = FixedPeriod::new(base, 4.0);
+        let df = (-0.02_f64 * 366.0 / 365.0).exp();
+        let cf = -1e6 * 366.0 / 360.0 * 0.04;
+        assert!(is_close(&p.cashflow(&curves).unwrap(), cf));
+        assert!(is_close(&p.npv(&curves).unwrap(), cf * df));
+        assert!(is_close(
+            &p.analytic_delta(&curves).unwrap(),
+            1e6 * 366.0 / 360.0 * df * 0.0001
+        ));
+    }
+}
@@ -0,0 +1,188 @@
+use crate::dual::Number;
+use crate::error::RateslibError;
+use crate::periods::{BasePeriod, Curves, Period};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// A period accruing a floating rate forecast from a curve, in percent, plus a `float_spread`,
+/// in basis points.
+///
+/// A known `fixing` overrides the forecast rate. A period split at the dates on which its index
+/// changes, such as the effective dates of central bank decisions, forecasts a rate for each
+/// sub-period and compounds them, so that each sub-period projects a step-constant forward.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FloatPeriod {
+    pub(crate) base: BasePeriod,
+    pub(crate) float_spread: f64,
+    pub(crate) fixing: Option<f64>,
+    /// The dates strictly within the period at which it is split, in order.
+    #[serde(default)]
+    pub(crate) change_dates: Vec<NaiveDateTime>,
+}
+
+impl FloatPeriod {
+    pub fn new(base: BasePeriod, float_spread: f64, fixing: Option<f64>) -> Self {
+        Self {
+            base,
+            float_spread,
+            fixing,
+            change_dates: vec![],
+        }
+    }
+
+    /// Return the period split at those of the index change `dates` strictly within it.
+    pub fn with_change_dates(mut self, dates: &[NaiveDateTime]) -> Self {
+        let mut change_dates: Vec<NaiveDateTime> = dates
+            .iter()
+            .filter(|d| **d > self.base.start && **d < self.base.end)
+            .copied()
+            .collect();
+        change_dates.sort();
+        change_dates.dedup();
+        self.change_dates = change_dates;
+        self
+    }
+
+    /// Return the start and end date of each sub-period, which is the whole period if it is
+    /// not split.
+    pub fn sub_periods(&self) -> Vec<(NaiveDateTime, NaiveDateTime)> {
+        let dates: Vec<NaiveDateTime> = std::iter::once(self.base.start)
+            .chain(self.change_dates.iter().copied())
+            .chain(std::iter::once(self.base.end))
+            .collect();
+        dates.windows(2).map(|w| (w[0], w[1])).collect()
+    }
+
+    /// Return the forecast rate of each sub-period, excluding the spread.
+    pub fn sub_rates(&self, curves: &Curves) -> Result<Vec<Number>, RateslibError> {
+        let curve = curves.forecasting()?;
+        self.sub_periods()
+            .iter()
+            .map(|(start, end)| curve.rate(start, end))
+            .collect()
+    }
+
+    pub fn base(&self) -> &BasePeriod {
+        &self.base
+    }
+
+    /// Return the floating rate of the period, including the spread.
+    pub fn rate(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        let rate = match (self.fixing, self.change_dates.is_empty()) {
+            (Some(f), _) => Number::F64(f),
+            (None, true) => curves
+                .forecasting()?
+                .rate(&self.base.start, &self.base.end)?,
+            (None, false) => self.compounded_rate(curves)?,
+        };
+        Ok(rate + self.float_spread / 100.0)
+    }
+
+    /// Return the rates of the sub-periods compounded over the period, with the day count
+    /// fraction of each sub-period its share of the calendar days of the period.
+    fn compounded_rate(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        let days = (self.base.end - self.base.start).num_days() as f64;
+        let mut growth = Number::F64(1.0);
+        for ((start, end), rate) in self.sub_periods().iter().zip(self.sub_rates(curves)?) {
+            let dcf = self.base.dcf * (*end - *start).num_days() as f64 / days;
+            growth = growth.try_mul(&(rate * (dcf / 100.0) + 1.0))?;
+        }
+        Ok((growth - 1.0) * (100.0 / self.base.dcf))
+    }
+}
+
+impl Period for FloatPeriod {
+    fn payment(&self) -> NaiveDateTime {
+        self.base.payment
+    }
+
+    fn cashflow(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        Ok(self.rate(curves)? * (-self.base.notional * self.base.dcf * 0.01))
+    }
+
+    fn analytic_delta(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        Ok(self
+            .base
+            .analytic_delta(curves.discounting()?.df(&self.base.payment)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::{ndt, Convention};
+    use crate::curves::PricingCurve;
+    use crate::periods::period::tests::{curve_fixture, is_close};
+
+    fn base_fixture() -> BasePeriod {
+        BasePeriod::new(
+            ndt(2024, 1, 1),
+            ndt(2024, 7, 1),
+            ndt(2024, 7, 1),
+            1e6,
+            Convention::Act365F,
+            182.0 / 365.0,
+            false,
+        )
+    }
+
+    #[test]
+    fn test_float_period() {
+        let curve = curve_fixture("c");
+        let curves = Curves::new(Some(&curve), Some(&curve));
+        let p = FloatPeriod::new(base_fixture(), 10.0, None);
+        let rate = f64::from(curve.rate(&ndt(2024, 1, 1), &ndt(2024, 7, 1)).unwrap()) + 0.1;
+        assert!(is_close(&p.rate(&curves).unwrap(), rate));
+        let cf = -1e6 * 182.0 / 365.0 * rate * 0.01;
+        assert!(is_close(&p.cashflow(&curves).unwrap(), cf));
+        // a floating period discounted on its own forecasting curve has npv of -N(1 - df)
+        let df = f64::from(curve.df(&ndt(2024, 7, 1)));
+        let p = FloatPeriod::new(base_fixture(), 0.0, None);
+        assert!((f64::from(p.npv(&curves).unwrap()) + 1e6 * (1.0 - df)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_float_period_fixing() {
+        let curves = Curves::default();
+        let p = FloatPeriod::new(base_fixture(), 0.0, Some(3.0));
+        assert!(is_close(&p.rate(&curves).unwrap(), 3.0));
+        let p = FloatPeriod::new(base_fixture(), 0.0, None);
+        assert!(p.rate(&curves).is_err());
+    }
+
+    #[test]
+    fn test_float_period_change_dates() {
+        let curve = curve_fixture("c");
+        let curves = Curves::new(Some(&curve), Some(&curve));
+        let dates = [
+            ndt(2024, 5, 1),
+            ndt(2023, 6, 1),
+            ndt(2024, 3, 21),
+            ndt(2024, 5, 1),
+        ];
+        let p = FloatPeriod::new(base_fixture(), 10.0, None).with_change_dates(&dates);
+        assert_eq!(
+            p.sub_periods(),
+            vec![
+                (ndt(2024, 1, 1), ndt(2024, 3, 21)),
+                (ndt(2024, 3, 21), ndt(2024, 5, 1)),
+                (ndt(2024, 5, 1), ndt(2024, 7, 1)),
+            ]
+        );
+        let rates = p.sub_rates(&curves).unwrap();
+        let days = [80.0, 41.0, 61.0];
+        let growth: f64 = rates
+            .iter()
+            .zip(days)
+            .map(|(r, d)| 1.0 + f64::from(r) * 182.0 / 365.0 * d / 182.0 / 100.0)
+            .product();
+        let expected = (growth - 1.0) / (182.0 / 365.0) * 100.0 + 0.1;
+        assert!(is_close(&p.rate(&curves).unwrap(), expected));
+        // on a curve with the convention of the period the forwards telescope to the whole
+        let whole = FloatPeriod::new(base_fixture(), 10.0, None);
+        assert!(is_close(
+            &p.rate(&curves).unwrap(),
+            f64::from(whole.rate(&curves).unwrap())
+        ));
+    }
+}
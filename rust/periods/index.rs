@@ -0,0 +1,82 @@
+use crate::dual::Number;
+use crate::error::RateslibError;
+use crate::periods::{BasePeriod, Curves, Period};
+use chrono::{Months, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+/// A period accruing a fixed rate, in percent, on a notional scaled by the ratio of an index
+/// value to an `index_base`, such as an inflation linked coupon.
+///
+/// The index value is forecast from the forecasting curve at the period end date less an
+/// `index_lag` in months.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexFixedPeriod {
+    pub(crate) base: BasePeriod,
+    pub(crate) fixed_rate: f64,
+    pub(crate) index_base: f64,
+    pub(crate) index_lag: u32,
+}
+
+impl IndexFixedPeriod {
+    pub fn new(base: BasePeriod, fixed_rate: f64, index_base: f64, index_lag: u32) -> Self {
+        Self {
+            base,
+            fixed_rate,
+            index_base,
+            index_lag,
+        }
+    }
+
+    pub fn base(&self) -> &BasePeriod {
+        &self.base
+    }
+
+    /// Return the ratio of the forecast index value to the `index_base`.
+    pub fn index_ratio(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        let date = self.base.end - Months::new(self.index_lag);
+        Ok(curves.forecasting()?.index_value(&date)? / self.index_base)
+    }
+}
+
+impl Period for IndexFixedPeriod {
+    fn payment(&self) -> NaiveDateTime {
+        self.base.payment
+    }
+
+    fn cashflow(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        Ok(self.index_ratio(curves)?
+            * (-self.base.notional * self.base.dcf * self.fixed_rate * 0.01))
+    }
+
+    fn analytic_delta(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        let df = curves.discounting()?.df(&self.base.payment);
+        Ok(self.base.analytic_delta(df * self.index_ratio(curves)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::{ndt, Convention};
+    use crate::periods::period::tests::{curve_fixture, is_close};
+
+    #[test]
+    fn test_index_fixed_period() {
+        let curve = curve_fixture("c");
+        let curves = Curves::new(Some(&curve), Some(&curve));
+        let base = BasePeriod::new(
+            ndt(2024, 4, 1),
+            ndt(2025, 4, 1),
+            ndt(2025, 4, 1),
+            1e6,
+            Convention::One,
+            1.0,
+            false,
+        );
+        let p = IndexFixedPeriod::new(base, 2.0, 100.0, 3);
+        // the index value on 1st Jan 2025 is 100 / df
+        let ratio = 1.0 / (-0.02_f64 * 366.0 / 365.0).exp();
+        assert!(is_close(&p.index_ratio(&curves).unwrap(), ratio));
+        assert!(is_close(&p.cashflow(&curves).unwrap(), -1e6 * 0.02 * ratio));
+    }
+}
@@ -0,0 +1,42 @@
+//! Create periods which define the individual cashflows of legs and instruments.
+//!
+//! Every period implements the [Period] trait, which values its cashflow against a set of
+//! [Curves], which may be resolved by name from a [CurveMap]. The standard periods are
+//! collected in the [PeriodType] enum, whilst custom period types can implement [Period]
+//! directly and be composed into a [CustomLeg](crate::legs::CustomLeg).
+
+pub(crate) mod period;
+pub use crate::periods::period::{BasePeriod, Curves, Period, PeriodType};
+
+mod curve_map;
+pub use crate::periods::curve_map::CurveMap;
+
+mod fixed;
+pub use crate::periods::fixed::FixedPeriod;
+
+mod float;
+pub use crate::periods::float::FloatPeriod;
+
+mod cashflow;
+pub use crate::periods::cashflow::CashflowPeriod;
+
+mod index;
+pub use crate::periods::index::IndexFixedPeriod;
+
+mod credit;
+pub use crate::periods::credit::{CreditPremiumPeriod, CreditProtectionPeriod, Recovery};
+
+mod digital;
+pub use crate::periods::digital::DigitalCapletPeriod;
+
+mod cms;
+pub use crate::periods::cms::{CmsIndex, CmsMethod, CmsPeriod, CmsSpreadPeriod};
+
+mod quanto;
+pub use crate::periods::quanto::{QuantoAdjustment, QuantoFloatPeriod};
+
+mod zero;
+pub use crate::periods::zero::{ZeroFixedPeriod, ZeroQuote};
+
+#[cfg(feature = "python")]
+pub(crate) mod curve_map_py;
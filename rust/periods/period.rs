@@ -0,0 +1,340 @@
+use crate::calendars::{Convention, DateRoll};
+use crate::curves::PricingCurve;
+use crate::dual::Number;
+use crate::error::RateslibError;
+use crate::periods::{
+    CashflowPeriod, CmsPeriod, CmsSpreadPeriod, CreditPremiumPeriod, CreditProtectionPeriod,
+    DigitalCapletPeriod, FixedPeriod, FloatPeriod, IndexFixedPeriod, QuantoFloatPeriod,
+};
+use crate::scheduling::PaymentLag;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// The curves used to value a period.
+///
+/// The `forecasting` curve forecasts floating rates, index values or survival probabilities,
+/// depending upon the period, and the `discounting` curve discounts cashflows.
+#[derive(Clone, Copy, Default)]
+pub struct Curves<'a> {
+    pub forecasting: Option<&'a dyn PricingCurve>,
+    pub discounting: Option<&'a dyn PricingCurve>,
+}
+
+impl<'a> Curves<'a> {
+    pub fn new(
+        forecasting: Option<&'a dyn PricingCurve>,
+        discounting: Option<&'a dyn PricingCurve>,
+    ) -> Self {
+        Self {
+            forecasting,
+            discounting,
+        }
+    }
+
+    /// Return the forecasting curve or raise if not supplied.
+    pub fn forecasting(&self) -> Result<&'a dyn PricingCurve, RateslibError> {
+        self.forecasting.ok_or_else(|| {
+            RateslibError::value("A `forecasting` curve is required to value this period.")
+        })
+    }
+
+    /// Return the discounting curve or raise if not supplied.
+    pub fn discounting(&self) -> Result<&'a dyn PricingCurve, RateslibError> {
+        self.discounting.ok_or_else(|| {
+            RateslibError::value("A `discounting` curve is required to value this period.")
+        })
+    }
+}
+
+/// Valuation of a single cashflow.
+///
+/// Cashflows are expressed from the perspective of the holder, so that a positive `notional`
+/// on a fixed or floating period is a payment of the rate and yields a negative cashflow.
+pub trait Period {
+    /// Return the payment date of the cashflow.
+    fn payment(&self) -> NaiveDateTime;
+
+    /// Return the cashflow of the period.
+    fn cashflow(&self, curves: &Curves) -> Result<Number, RateslibError>;
+
+    /// Return the change in the NPV of the period for a 1bp increase in its rate.
+    fn analytic_delta(&self, curves: &Curves) -> Result<Number, RateslibError>;
+
+    /// Return the NPV of the period, which is zero if paid before the initial date of the
+    /// discounting curve.
+    fn npv(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        let disc = curves.discounting()?;
+        if self.payment() < disc.initial_date() {
+            return Ok(Number::F64(0.0));
+        }
+        Ok(self.cashflow(curves)? * disc.df(&self.payment()))
+    }
+}
+
+/// The accrual dates, payment date, notional and day count fraction common to an accruing
+/// period.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BasePeriod {
+    pub(crate) start: NaiveDateTime,
+    pub(crate) end: NaiveDateTime,
+    pub(crate) payment: NaiveDateTime,
+    pub(crate) notional: f64,
+    pub(crate) convention: Convention,
+    pub(crate) dcf: f64,
+    pub(crate) stub: bool,
+}
+
+impl BasePeriod {
+    pub fn new(
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+        payment: NaiveDateTime,
+        notional: f64,
+        convention: Convention,
+        dcf: f64,
+        stub: bool,
+    ) -> Self {
+        Self {
+            start,
+            end,
+            payment,
+            notional,
+            convention,
+            dcf,
+            stub,
+        }
+    }
+
+    pub fn start(&self) -> NaiveDateTime {
+        self.start
+    }
+
+    pub fn end(&self) -> NaiveDateTime {
+        self.end
+    }
+
+    pub fn notional(&self) -> f64 {
+        self.notional
+    }
+
+    pub fn dcf(&self) -> f64 {
+        self.dcf
+    }
+
+    pub fn stub(&self) -> bool {
+        self.stub
+    }
+
+    /// Set the payment date of the period by a [PaymentLag] from its accrual end date.
+    pub fn set_payment_lag<U: DateRoll>(&mut self, lag: &PaymentLag, calendar: &U) {
+        self.payment = lag.payment(&self.end, calendar);
+    }
+
+    /// Return the analytic delta of the period to a discount factor, or survival weighted
+    /// discount factor, `df`.
+    pub(crate) fn analytic_delta(&self, df: Number) -> Number {
+        df * (self.notional * self.dcf * 0.0001)
+    }
+}
+
+/// Container for the standard [Period] types.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PeriodType {
+    Fixed(FixedPeriod),
+    Float(FloatPeriod),
+    Cashflow(CashflowPeriod),
+    IndexFixed(IndexFixedPeriod),
+    CreditPremium(CreditPremiumPeriod),
+    CreditProtection(CreditProtectionPeriod),
+    DigitalCaplet(DigitalCapletPeriod),
+    Cms(CmsPeriod),
+    CmsSpread(CmsSpreadPeriod),
+    QuantoFloat(QuantoFloatPeriod),
+}
+
+impl PeriodType {
+    /// Return the [BasePeriod] of an accruing period.
+    pub fn base(&self) -> Option<&BasePeriod> {
+        match self {
+            PeriodType::Fixed(p) => Some(p.base()),
+            PeriodType::Float(p) => Some(p.base()),
+            PeriodType::Cashflow(_) => None,
+            PeriodType::IndexFixed(p) => Some(p.base()),
+            PeriodType::CreditPremium(p) => Some(p.base()),
+            PeriodType::CreditProtection(_) => None,
+            PeriodType::DigitalCaplet(p) => Some(p.base()),
+            PeriodType::Cms(p) => Some(p.base()),
+            PeriodType::CmsSpread(p) => Some(p.base()),
+            PeriodType::QuantoFloat(p) => Some(p.base()),
+        }
+    }
+
+    fn base_mut(&mut self) -> Option<&mut BasePeriod> {
+        match self {
+            PeriodType::Fixed(p) => Some(&mut p.base),
+            PeriodType::Float(p) => Some(&mut p.base),
+            PeriodType::Cashflow(_) => None,
+            PeriodType::IndexFixed(p) => Some(&mut p.base),
+            PeriodType::CreditPremium(p) => Some(&mut p.base),
+            PeriodType::CreditProtection(_) => None,
+            PeriodType::DigitalCaplet(p) => Some(&mut p.base),
+            PeriodType::Cms(p) => Some(&mut p.base),
+            PeriodType::CmsSpread(p) => Some(&mut p.base),
+            PeriodType::QuantoFloat(p) => Some(&mut p.base),
+        }
+    }
+
+    /// Set the payment date of an accruing period by a [PaymentLag] from its accrual end date,
+    /// changing the date at which its cashflow is discounted.
+    ///
+    /// A [CashflowPeriod] has no accrual end date and is unchanged; its payment date is set
+    /// on construction, for example from
+    /// [Schedule::with_payment_lag](crate::scheduling::Schedule::with_payment_lag).
+    pub fn set_payment_lag<U: DateRoll>(&mut self, lag: &PaymentLag, calendar: &U) {
+        if let Some(base) = self.base_mut() {
+            base.set_payment_lag(lag, calendar)
+        }
+    }
+}
+
+impl Period for PeriodType {
+    fn payment(&self) -> NaiveDateTime {
+        match self {
+            PeriodType::Fixed(p) => p.payment(),
+            PeriodType::Float(p) => p.payment(),
+            PeriodType::Cashflow(p) => p.payment(),
+            PeriodType::IndexFixed(p) => p.payment(),
+            PeriodType::CreditPremium(p) => p.payment(),
+            PeriodType::CreditProtection(p) => p.payment(),
+            PeriodType::DigitalCaplet(p) => p.payment(),
+            PeriodType::Cms(p) => p.payment(),
+            PeriodType::CmsSpread(p) => p.payment(),
+            PeriodType::QuantoFloat(p) => p.payment(),
+        }
+    }
+
+    fn cashflow(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        match self {
+            PeriodType::Fixed(p) => p.cashflow(curves),
+            PeriodType::Float(p) => p.cashflow(curves),
+            PeriodType::Cashflow(p) => p.cashflow(curves),
+            PeriodType::IndexFixed(p) => p.cashflow(curves),
+            PeriodType::CreditPremium(p) => p.cashflow(curves),
+            PeriodType::CreditProtection(p) => p.cashflow(curves),
+            PeriodType::DigitalCaplet(p) => p.cashflow(curves),
+            PeriodType::Cms(p) => p.cashflow(curves),
+            PeriodType::CmsSpread(p) => p.cashflow(curves),
+            PeriodType::QuantoFloat(p) => p.cashflow(curves),
+        }
+    }
+
+    fn analytic_delta(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        match self {
+            PeriodType::Fixed(p) => p.analytic_delta(curves),
+            PeriodType::Float(p) => p.analytic_delta(curves),
+            PeriodType::Cashflow(p) => p.analytic_delta(curves),
+            PeriodType::IndexFixed(p) => p.analytic_delta(curves),
+            PeriodType::CreditPremium(p) => p.analytic_delta(curves),
+            PeriodType::CreditProtection(p) => p.analytic_delta(curves),
+            PeriodType::DigitalCaplet(p) => p.analytic_delta(curves),
+            PeriodType::Cms(p) => p.analytic_delta(curves),
+            PeriodType::CmsSpread(p) => p.analytic_delta(curves),
+            PeriodType::QuantoFloat(p) => p.analytic_delta(curves),
+        }
+    }
+
+    fn npv(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        match self {
+            PeriodType::Fixed(p) => p.npv(curves),
+            PeriodType::Float(p) => p.npv(curves),
+            PeriodType::Cashflow(p) => p.npv(curves),
+            PeriodType::IndexFixed(p) => p.npv(curves),
+            PeriodType::CreditPremium(p) => p.npv(curves),
+            PeriodType::CreditProtection(p) => p.npv(curves),
+            PeriodType::DigitalCaplet(p) => p.npv(curves),
+            PeriodType::Cms(p) => p.npv(curves),
+            PeriodType::CmsSpread(p) => p.npv(curves),
+            PeriodType::QuantoFloat(p) => p.npv(curves),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::calendars::{ndt, NamedCal};
+    use crate::curves::{CurveDF, LogLinearInterpolator, Nodes};
+    use indexmap::IndexMap;
+
+    /// A curve with a continuously compounded zero rate of 2% on an Act365F basis.
+    pub(crate) fn curve_fixture(id: &str) -> CurveDF<LogLinearInterpolator, NamedCal> {
+        let nodes = Nodes::F64(IndexMap::from_iter(vec![
+            (ndt(2024, 1, 1), 1.0_f64),
+            (ndt(2034, 1, 1), (-0.02_f64 * 3653.0 / 365.0).exp()),
+        ]));
+        CurveDF::try_new(
+            nodes,
+            LogLinearInterpolator::new(),
+            id,
+            Convention::Act365F,
+            crate::calendars::Modifier::ModF,
+            Some(100.0),
+            NamedCal::try_new("all").unwrap(),
+        )
+        .unwrap()
+    }
+
+    pub(crate) fn is_close(a: &Number, b: f64) -> bool {
+        (f64::from(a) - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn test_curves_missing() {
+        let curves = Curves::default();
+        assert!(curves.discounting().is_err());
+        assert!(curves.forecasting().is_err());
+    }
+
+    #[test]
+    fn test_historic_period_npv() {
+        let curve = curve_fixture("c");
+        let curves = Curves::new(None, Some(&curve));
+        let p = CashflowPeriod::new(1e6, ndt(2023, 6, 1));
+        assert_eq!(p.npv(&curves).unwrap(), Number::F64(0.0));
+    }
+
+    #[test]
+    fn test_set_payment_lag() {
+        let curve = curve_fixture("c");
+        let curves = Curves::new(None, Some(&curve));
+        let base = BasePeriod::new(
+            ndt(2024, 1, 1),
+            ndt(2025, 1, 1),
+            ndt(2025, 1, 1),
+            1e6,
+            Convention::Act365F,
+            1.0,
+            false,
+        );
+        let cal = NamedCal::try_new("all").unwrap();
+        let mut p = PeriodType::Fixed(FixedPeriod::new(base, 2.0));
+        p.set_payment_lag(&PaymentLag::new(2, crate::calendars::Modifier::F), &cal);
+        assert_eq!(p.payment(), ndt(2025, 1, 3));
+        // the cashflow is unchanged but discounted from the lagged date
+        let df = (-0.02_f64 * 368.0 / 365.0).exp();
+        assert!(is_close(&p.npv(&curves).unwrap(), -1e6 * 0.02 * df));
+        let mut c = PeriodType::Cashflow(CashflowPeriod::new(1e6, ndt(2025, 1, 1)));
+        c.set_payment_lag(&PaymentLag::new(2, crate::calendars::Modifier::F), &cal);
+        assert_eq!(c.payment(), ndt(2025, 1, 1));
+    }
+
+    #[test]
+    fn test_period_type_dispatch() {
+        let curve = curve_fixture("c");
+        let curves = Curves::new(None, Some(&curve));
+        let p = CashflowPeriod::new(1e6, ndt(2025, 1, 1));
+        let pt = PeriodType::Cashflow(p.clone());
+        assert_eq!(pt.npv(&curves).unwrap(), p.npv(&curves).unwrap());
+        assert_eq!(pt.payment(), ndt(2025, 1, 1));
+    }
+}
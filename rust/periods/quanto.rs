@@ -0,0 +1,189 @@
+use crate::dual::{MathFuncs, Number};
+use crate::error::RateslibError;
+use crate::periods::{BasePeriod, Curves, Period};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// The lognormal volatilities, as decimals, of a forward rate and of the FX rate, in units of
+/// the payment currency per unit of the index currency, and their correlation.
+///
+/// Each input may be a dual number, so that the sensitivity of a quanto adjusted value to the
+/// correlation can be reported alongside its vegas.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuantoAdjustment {
+    pub(crate) rate_volatility: Number,
+    pub(crate) fx_volatility: Number,
+    pub(crate) correlation: Number,
+}
+
+impl QuantoAdjustment {
+    pub fn try_new(
+        rate_volatility: Number,
+        fx_volatility: Number,
+        correlation: Number,
+    ) -> Result<Self, RateslibError> {
+        if f64::from(&rate_volatility) < 0.0 || f64::from(&fx_volatility) < 0.0 {
+            return Err(RateslibError::value(
+                "Volatilities of a `QuantoAdjustment` must be non-negative.",
+            ));
+        }
+        if f64::from(&correlation).abs() > 1.0 {
+            return Err(RateslibError::value(
+                "`correlation` of a `QuantoAdjustment` must be within [-1, 1].",
+            ));
+        }
+        Ok(Self {
+            rate_volatility,
+            fx_volatility,
+            correlation,
+        })
+    }
+
+    pub fn correlation(&self) -> &Number {
+        &self.correlation
+    }
+
+    /// Return the forward adjusted for payment in the other currency at a time `t`, in years,
+    /// which is `F exp(-ρ σ_F σ_X t)` under the payment measure.
+    pub fn adjust(&self, forward: &Number, t: f64) -> Number {
+        let drift = &self.correlation * &self.rate_volatility * &self.fx_volatility * (-t.max(0.0));
+        forward * drift.exp()
+    }
+}
+
+/// A period accruing a floating rate of an index in one currency, in percent, plus a
+/// `float_spread` in bps, paid in another currency on the notional in that currency.
+///
+/// The rate is forecast from the forecasting curve of the index currency and quanto adjusted
+/// to its fixing at the start of the period, measured on an Act365F basis from the initial
+/// date of that curve, whilst the cashflow is discounted on the discounting curve of the
+/// payment currency. A known `fixing` overrides the adjusted forecast.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuantoFloatPeriod {
+    pub(crate) base: BasePeriod,
+    pub(crate) quanto: QuantoAdjustment,
+    pub(crate) float_spread: f64,
+    pub(crate) fixing: Option<f64>,
+}
+
+impl QuantoFloatPeriod {
+    pub fn new(
+        base: BasePeriod,
+        quanto: QuantoAdjustment,
+        float_spread: f64,
+        fixing: Option<f64>,
+    ) -> Self {
+        Self {
+            base,
+            quanto,
+            float_spread,
+            fixing,
+        }
+    }
+
+    pub fn base(&self) -> &BasePeriod {
+        &self.base
+    }
+
+    pub fn quanto(&self) -> &QuantoAdjustment {
+        &self.quanto
+    }
+
+    /// Return the quanto adjusted floating rate of the period, including the spread.
+    pub fn rate(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        let rate = match self.fixing {
+            Some(f) => Number::F64(f),
+            None => {
+                let curve = curves.forecasting()?;
+                let forward = curve.rate(&self.base.start, &self.base.end)?;
+                let t = (self.base.start - curve.initial_date()).num_seconds() as f64
+                    / (365.0 * 86400.0);
+                self.quanto.adjust(&forward, t)
+            }
+        };
+        Ok(rate + self.float_spread / 100.0)
+    }
+}
+
+impl Period for QuantoFloatPeriod {
+    fn payment(&self) -> NaiveDateTime {
+        self.base.payment
+    }
+
+    fn cashflow(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        Ok(self.rate(curves)? * (-self.base.notional * self.base.dcf * 0.01))
+    }
+
+    fn analytic_delta(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        Ok(self
+            .base
+            .analytic_delta(curves.discounting()?.df(&self.base.payment)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::{ndt, Convention};
+    use crate::curves::PricingCurve;
+    use crate::dual::{Dual, Gradient1};
+    use crate::periods::period::tests::{curve_fixture, is_close};
+    use crate::periods::FloatPeriod;
+
+    fn base_fixture() -> BasePeriod {
+        BasePeriod::new(
+            ndt(2026, 1, 1),
+            ndt(2026, 7, 1),
+            ndt(2026, 7, 1),
+            1e6,
+            Convention::Act365F,
+            181.0 / 365.0,
+            false,
+        )
+    }
+
+    fn quanto(correlation: f64) -> QuantoAdjustment {
+        QuantoAdjustment::try_new(
+            Number::F64(0.3),
+            Number::F64(0.1),
+            Number::Dual(Dual::new(correlation, vec!["rho".to_string()])),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_quanto_adjustment() {
+        let (index, payment) = (curve_fixture("idx"), curve_fixture("pay"));
+        let curves = Curves::new(Some(&index), Some(&payment));
+        let forward = f64::from(index.rate(&ndt(2026, 1, 1), &ndt(2026, 7, 1)).unwrap());
+        let t = 731.0 / 365.0;
+
+        // uncorrelated rates and FX require no adjustment
+        let p = QuantoFloatPeriod::new(base_fixture(), quanto(0.0), 0.0, None);
+        let float = FloatPeriod::new(base_fixture(), 0.0, None);
+        assert!(is_close(
+            &p.npv(&curves).unwrap(),
+            f64::from(&float.npv(&curves).unwrap())
+        ));
+
+        let p = QuantoFloatPeriod::new(base_fixture(), quanto(0.5), 10.0, None);
+        let rate = p.rate(&curves).unwrap();
+        let adjusted = forward * (-0.5 * 0.3 * 0.1 * t).exp();
+        assert!(is_close(&rate, adjusted + 0.1));
+        // the correlation sensitivity of the rate is -σ_F σ_X t times the adjusted forward
+        let d_rho = match &rate {
+            Number::Dual(d) => d.gradient1(vec!["rho".to_string()])[0],
+            _ => panic!("expected a Dual"),
+        };
+        assert!((d_rho + 0.03 * t * adjusted).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_quanto_fixing_and_invalid() {
+        let p = QuantoFloatPeriod::new(base_fixture(), quanto(0.5), 0.0, Some(2.5));
+        assert_eq!(p.rate(&Curves::default()).unwrap(), Number::F64(2.5));
+        let v = Number::F64(0.1);
+        assert!(QuantoAdjustment::try_new(v.clone(), v.clone(), Number::F64(1.5)).is_err());
+        assert!(QuantoAdjustment::try_new(Number::F64(-0.1), v, Number::F64(0.0)).is_err());
+    }
+}
@@ -0,0 +1,187 @@
+use crate::dual::{compensated_f64, Number};
+use crate::error::RateslibError;
+use crate::periods::{BasePeriod, Curves, Period};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// The convention by which a zero coupon fixed rate, in percent, compounds to a single payment
+/// over the day count fractions of its compounding periods.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ZeroQuote {
+    /// Compounded over each compounding period, such that with annual periods the rate is an
+    /// annualised rate, `(1 + r)^T`.
+    #[default]
+    Annualised,
+    /// Accrued simply over the total day count fraction, `1 + r * T`.
+    Simple,
+    /// Compounded continuously over the total day count fraction, `exp(r * T)`.
+    Continuous,
+}
+
+impl ZeroQuote {
+    /// Return the growth factor of a `rate`, as a decimal, over the `dcfs` of the compounding
+    /// periods, and its derivative with respect to the rate.
+    pub(crate) fn growth(&self, rate: f64, dcfs: &[f64]) -> (f64, f64) {
+        let t = compensated_f64(dcfs);
+        match self {
+            ZeroQuote::Annualised => {
+                let g: f64 = dcfs.iter().map(|d| 1.0 + rate * d).product();
+                let dg = dcfs.iter().map(|d| g * d / (1.0 + rate * d)).sum();
+                (g, dg)
+            }
+            ZeroQuote::Simple => (1.0 + rate * t, t),
+            ZeroQuote::Continuous => ((rate * t).exp(), t * (rate * t).exp()),
+        }
+    }
+
+    /// Return the rate, in percent, whose growth over the `dcfs` is `growth`.
+    ///
+    /// The rate is solved by Newton iteration and carries the sensitivities of `growth`.
+    pub(crate) fn implied_rate(
+        &self,
+        growth: &Number,
+        dcfs: &[f64],
+    ) -> Result<Number, RateslibError> {
+        let target = f64::from(growth);
+        if target <= 0.0 {
+            return Err(RateslibError::value(
+                "The growth of a zero coupon rate must be positive.",
+            ));
+        }
+        let mut rate = 0.0;
+        for _ in 0..50 {
+            let (g, dg) = self.growth(rate, dcfs);
+            let step = (g - target) / dg;
+            rate -= step;
+            if step.abs() < 1e-15 {
+                break;
+            }
+        }
+        // a final step in the growth propagates its sensitivities to the rate
+        let (g, dg) = self.growth(rate, dcfs);
+        Ok((growth - g) * (100.0 / dg) + rate * 100.0)
+    }
+}
+
+/// A period paying a fixed rate, in percent, compounded by a [ZeroQuote] over a sequence of
+/// compounding periods, as a single cashflow at the end of the last.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ZeroFixedPeriod {
+    pub(crate) base: BasePeriod,
+    pub(crate) fixed_rate: f64,
+    pub(crate) quote: ZeroQuote,
+    pub(crate) dcfs: Vec<f64>,
+}
+
+impl ZeroFixedPeriod {
+    /// Create a [ZeroFixedPeriod] whose `base` spans the compounding periods, with day count
+    /// fractions `dcfs`.
+    pub fn try_new(
+        base: BasePeriod,
+        fixed_rate: f64,
+        quote: ZeroQuote,
+        dcfs: Vec<f64>,
+    ) -> Result<Self, RateslibError> {
+        if dcfs.is_empty() {
+            return Err(RateslibError::value(
+                "A `ZeroFixedPeriod` requires at least one compounding period.",
+            ));
+        }
+        Ok(Self {
+            base,
+            fixed_rate,
+            quote,
+            dcfs,
+        })
+    }
+
+    pub fn base(&self) -> &BasePeriod {
+        &self.base
+    }
+
+    pub fn fixed_rate(&self) -> f64 {
+        self.fixed_rate
+    }
+
+    pub fn quote(&self) -> ZeroQuote {
+        self.quote
+    }
+
+    pub fn dcfs(&self) -> &[f64] {
+        &self.dcfs
+    }
+}
+
+impl Period for ZeroFixedPeriod {
+    fn payment(&self) -> NaiveDateTime {
+        self.base.payment
+    }
+
+    fn cashflow(&self, _curves: &Curves) -> Result<Number, RateslibError> {
+        let (g, _) = self.quote.growth(self.fixed_rate * 0.01, &self.dcfs);
+        Ok(Number::F64(-self.base.notional * (g - 1.0)))
+    }
+
+    fn analytic_delta(&self, curves: &Curves) -> Result<Number, RateslibError> {
+        let (_, dg) = self.quote.growth(self.fixed_rate * 0.01, &self.dcfs);
+        let df = curves.discounting()?.df(&self.base.payment);
+        Ok(df * (self.base.notional * dg * 0.0001))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::{ndt, Convention};
+    use crate::periods::period::tests::{curve_fixture, is_close};
+
+    fn period(quote: ZeroQuote) -> ZeroFixedPeriod {
+        let base = BasePeriod::new(
+            ndt(2024, 1, 1),
+            ndt(2027, 1, 1),
+            ndt(2027, 1, 1),
+            1e6,
+            Convention::One,
+            3.0,
+            false,
+        );
+        ZeroFixedPeriod::try_new(base, 4.0, quote, vec![1.0; 3]).unwrap()
+    }
+
+    #[test]
+    fn test_zero_fixed_cashflow() {
+        let curve = curve_fixture("c");
+        let curves = Curves::new(None, Some(&curve));
+        for (quote, cf) in [
+            (ZeroQuote::Annualised, -1e6 * (1.04_f64.powi(3) - 1.0)),
+            (ZeroQuote::Simple, -1e6 * 0.12),
+            (ZeroQuote::Continuous, -1e6 * (0.12_f64.exp() - 1.0)),
+        ] {
+            let p = period(quote);
+            assert!(is_close(&p.cashflow(&curves).unwrap(), cf));
+            // the analytic delta matches a 1bp increase in the fixed rate
+            let mut bumped = p.clone();
+            bumped.fixed_rate += 0.01;
+            let fd = f64::from(&(p.npv(&curves).unwrap() - bumped.npv(&curves).unwrap()));
+            let a_delta = f64::from(&p.analytic_delta(&curves).unwrap());
+            assert!((fd - a_delta).abs() / a_delta < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_implied_rate_inverts_growth() {
+        let dcfs = [0.5, 1.0, 1.0];
+        for quote in [
+            ZeroQuote::Annualised,
+            ZeroQuote::Simple,
+            ZeroQuote::Continuous,
+        ] {
+            let (g, _) = quote.growth(0.035, &dcfs);
+            let rate = quote.implied_rate(&Number::F64(g), &dcfs).unwrap();
+            assert!((f64::from(&rate) - 3.5).abs() < 1e-12);
+        }
+        assert!(ZeroQuote::Simple
+            .implied_rate(&Number::F64(-1.0), &dcfs)
+            .is_err());
+    }
+}
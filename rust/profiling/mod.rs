@@ -0,0 +1,99 @@
+//! Count the executions of hot paths of the rust core to catch performance regressions.
+//!
+//! Counters are only incremented when compiled with the `profiling` feature, which also
+//! exposes `snapshot` and `reset`, and their Python equivalents. Without the feature
+//! [record] compiles to nothing.
+
+#[cfg(feature = "profiling")]
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// The hot paths which are counted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Counter {
+    /// Evaluations of a curve at a date.
+    CurveEvaluations,
+    /// Allocations of a new union of the variables of two dual numbers.
+    UnionVars,
+    /// Evaluations of a spline at a point.
+    SplineEvaluations,
+}
+
+#[cfg(feature = "profiling")]
+static COUNTS: [AtomicU64; 3] = [AtomicU64::new(0), AtomicU64::new(0), AtomicU64::new(0)];
+
+/// Increment the `counter`, if compiled with the `profiling` feature.
+#[inline(always)]
+pub fn record(counter: Counter) {
+    #[cfg(feature = "profiling")]
+    COUNTS[counter as usize].fetch_add(1, Ordering::Relaxed);
+    #[cfg(not(feature = "profiling"))]
+    let _ = counter;
+}
+
+/// The values of each [Counter] since the last [reset].
+#[cfg(feature = "profiling")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Counters {
+    pub curve_evaluations: u64,
+    pub union_vars: u64,
+    pub spline_evaluations: u64,
+}
+
+/// Return the values of each [Counter].
+#[cfg(feature = "profiling")]
+pub fn snapshot() -> Counters {
+    let get = |c: Counter| COUNTS[c as usize].load(Ordering::Relaxed);
+    Counters {
+        curve_evaluations: get(Counter::CurveEvaluations),
+        union_vars: get(Counter::UnionVars),
+        spline_evaluations: get(Counter::SplineEvaluations),
+    }
+}
+
+/// Set every [Counter] to zero.
+#[cfg(feature = "profiling")]
+pub fn reset() {
+    COUNTS.iter().for_each(|c| c.store(0, Ordering::Relaxed));
+}
+
+/// Return the profiling counters of the rust core since the last reset.
+///
+/// Only available when compiled with the `profiling` feature.
+///
+/// Parameters
+/// ----------
+/// reset: bool
+///     Whether to reset the counters to zero after reading them.
+///
+/// Returns
+/// -------
+/// dict[str, int]
+#[cfg(all(feature = "profiling", feature = "python"))]
+#[pyo3::pyfunction]
+#[pyo3(name = "profiling_counters", signature = (reset = false))]
+pub fn profiling_counters_py(reset: bool) -> indexmap::IndexMap<String, u64> {
+    let c = snapshot();
+    if reset {
+        self::reset();
+    }
+    indexmap::IndexMap::from_iter([
+        ("curve_evaluations".to_string(), c.curve_evaluations),
+        ("union_vars".to_string(), c.union_vars),
+        ("spline_evaluations".to_string(), c.spline_evaluations),
+    ])
+}
+
+#[cfg(all(test, feature = "profiling"))]
+mod tests {
+    use super::*;
+    use crate::dual::{Dual, Vars};
+
+    #[test]
+    fn test_counters() {
+        let before = snapshot();
+        let x = Dual::new(1.0, vec!["x".to_string()]);
+        let y = Dual::new(1.0, vec!["y".to_string()]);
+        let _ = x.to_union_vars(&y, None);
+        assert!(snapshot().union_vars > before.union_vars);
+    }
+}
@@ -0,0 +1,124 @@
+//! Report the progress of long running computations of the rust core to a callback.
+//!
+//! A computation run [with_progress] reports a [ProgressEvent] after each calibration
+//! iteration, batch of Monte Carlo paths and instrument of a portfolio valuation. A callback
+//! which raises aborts the computation with its error, so that a UI may both display progress
+//! and stop on demand. Work mapped across threads reports to the callback of its caller.
+
+#[cfg(feature = "python")]
+pub(crate) mod progress_py;
+
+use crate::error::RateslibError;
+#[cfg(feature = "python")]
+use pyo3::pyclass;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// The number of Monte Carlo paths between reported events.
+pub(crate) const PATH_BATCH: usize = 1024;
+
+/// The computation reporting a [ProgressEvent].
+#[cfg_attr(feature = "python", pyclass(module = "rateslib.rs"))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ProgressStage {
+    /// Iterations of a [Solver](crate::solver::Solver).
+    Calibration,
+    /// Paths of a Monte Carlo simulation.
+    MonteCarlo,
+    /// Instruments of a [Portfolio](crate::instruments::Portfolio).
+    Valuation,
+}
+
+/// The progress of a computation, as `completed` of at most `total` units of its `stage`.
+#[cfg_attr(feature = "python", pyclass(module = "rateslib.rs"))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgressEvent {
+    pub stage: ProgressStage,
+    pub completed: usize,
+    pub total: usize,
+    /// The objective function of a calibration after the iteration.
+    pub objective: Option<f64>,
+}
+
+type Callback = Rc<dyn Fn(&ProgressEvent) -> Result<(), RateslibError>>;
+
+thread_local! {
+    static CALLBACK: RefCell<Option<Callback>> = const { RefCell::new(None) };
+}
+
+/// Restores the callback of the thread when a run ends, including by a panic.
+struct Restore(Option<Callback>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        CALLBACK.with(|c| *c.borrow_mut() = self.0.take());
+    }
+}
+
+impl ProgressEvent {
+    pub fn new(stage: ProgressStage, completed: usize, total: usize) -> Self {
+        Self {
+            stage,
+            completed,
+            total,
+            objective: None,
+        }
+    }
+
+    /// Return the event with the `objective` function of a calibration.
+    pub fn with_objective(mut self, objective: f64) -> Self {
+        self.objective = Some(objective);
+        self
+    }
+}
+
+/// Run `f` on the current thread reporting its progress to `callback`.
+pub fn with_progress<T>(
+    callback: impl Fn(&ProgressEvent) -> Result<(), RateslibError> + 'static,
+    f: impl FnOnce() -> T,
+) -> T {
+    let previous = CALLBACK.with(|c| c.borrow_mut().replace(Rc::new(callback)));
+    let _restore = Restore(previous);
+    f()
+}
+
+/// Return whether a callback receives the events of the current thread.
+#[cfg(not(any(feature = "wasm", target_arch = "wasm32")))]
+pub(crate) fn is_active() -> bool {
+    CALLBACK.with(|c| c.borrow().is_some())
+}
+
+/// Report an `event` to the callback of the computation running on the current thread, if
+/// any, raising the error of the callback.
+pub fn report(event: ProgressEvent) -> Result<(), RateslibError> {
+    match CALLBACK.with(|c| c.borrow().clone()) {
+        Some(callback) => callback(&event),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_report_and_abort() {
+        let count = Rc::new(Cell::new(0));
+        let seen = count.clone();
+        let result = with_progress(
+            move |e| {
+                seen.set(seen.get() + 1);
+                match e.completed < 2 {
+                    true => Ok(()),
+                    false => Err(RateslibError::value("aborted")),
+                }
+            },
+            || (1..5).try_for_each(|i| report(ProgressEvent::new(ProgressStage::Valuation, i, 4))),
+        );
+        assert!(result.is_err());
+        assert_eq!(count.get(), 2);
+        // outside of a run events are not reported
+        assert!(report(ProgressEvent::new(ProgressStage::Valuation, 2, 4)).is_ok());
+    }
+}
@@ -0,0 +1,69 @@
+//! Wrapper module to export the progress of computations to Python using pyo3 bindings.
+
+use crate::cancel::CancellationToken;
+use crate::error::RateslibError;
+use crate::progress::{with_progress, ProgressEvent, ProgressStage};
+use pyo3::prelude::*;
+
+#[pymethods]
+impl ProgressEvent {
+    #[getter]
+    fn stage(&self) -> ProgressStage {
+        self.stage
+    }
+
+    #[getter]
+    fn completed(&self) -> usize {
+        self.completed
+    }
+
+    #[getter]
+    fn total(&self) -> usize {
+        self.total
+    }
+
+    #[getter]
+    fn objective(&self) -> Option<f64> {
+        self.objective
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        Ok(format!(
+            "<ProgressEvent: {:?} {}/{}>",
+            self.stage, self.completed, self.total
+        ))
+    }
+}
+
+/// Return a progress callback which calls a Python `callback` with each [ProgressEvent].
+pub(crate) fn py_callback(
+    callback: Py<PyAny>,
+) -> impl Fn(&ProgressEvent) -> Result<(), RateslibError> + 'static {
+    move |event| {
+        Python::with_gil(|py| callback.call1(py, (event.clone(),)))
+            .map(|_| ())
+            .map_err(RateslibError::from)
+    }
+}
+
+/// Evaluate `f` with the GIL released, so that other Python threads run during a computation,
+/// under the `cancel` token, if given, by which they may interrupt it, and reporting each
+/// [ProgressEvent] to the `progress` callable, if given.
+pub(crate) fn release<T: Send>(
+    py: Python<'_>,
+    cancel: Option<CancellationToken>,
+    progress: Option<Py<PyAny>>,
+    f: impl FnOnce() -> Result<T, RateslibError> + Send,
+) -> PyResult<T> {
+    py.allow_threads(|| {
+        let cancellable = || match cancel {
+            Some(token) => token.run(f),
+            None => f(),
+        };
+        match progress {
+            Some(callback) => with_progress(py_callback(callback), cancellable),
+            None => cancellable(),
+        }
+    })
+    .map_err(PyErr::from)
+}
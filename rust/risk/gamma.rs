@@ -0,0 +1,127 @@
+use crate::dual::Number;
+use crate::error::RateslibError;
+use crate::fx::rates::Ccy;
+use crate::risk::RiskMapping;
+use ndarray::Array2;
+
+/// The cross-gamma of a value between the rates of the calibrating instruments of a
+/// [RiskMapping], with blocks of instruments by curve.
+///
+/// Each entry is the change in the delta to one instrument, in its `currency` per 1bp, for a
+/// 1bp increase in the rate of another. The matrix is symmetric.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrossGamma {
+    pub(crate) currency: Ccy,
+    pub(crate) labels: Vec<(String, String)>,
+    pub(crate) gamma: Array2<f64>,
+}
+
+impl CrossGamma {
+    /// Create the [CrossGamma] of a second order AD `value` in `currency` with sensitivity to
+    /// the variables of the `mapping`.
+    pub fn try_new(
+        value: &Number,
+        mapping: &RiskMapping,
+        currency: Ccy,
+    ) -> Result<Self, RateslibError> {
+        Ok(Self {
+            currency,
+            labels: mapping.labels.clone(),
+            gamma: mapping.instrument_gamma(value)?,
+        })
+    }
+
+    pub fn currency(&self) -> Ccy {
+        self.currency
+    }
+
+    /// Return the `(curve, tenor)` label of each row and column of the matrix.
+    pub fn labels(&self) -> &[(String, String)] {
+        &self.labels
+    }
+
+    /// Return the full cross-gamma matrix.
+    pub fn matrix(&self) -> &Array2<f64> {
+        &self.gamma
+    }
+
+    /// Return the curves of the instruments, in order of first appearance.
+    pub fn curves(&self) -> Vec<String> {
+        let mut curves: Vec<String> = vec![];
+        for (curve, _) in self.labels.iter() {
+            if !curves.contains(curve) {
+                curves.push(curve.clone());
+            }
+        }
+        curves
+    }
+
+    /// Return the block of the matrix with rows of the instruments of `curve1` and columns of
+    /// those of `curve2`, and the tenors of each.
+    pub fn block(&self, curve1: &str, curve2: &str) -> (Vec<String>, Vec<String>, Array2<f64>) {
+        let index = |curve: &str| -> Vec<usize> {
+            self.labels
+                .iter()
+                .enumerate()
+                .filter(|(_, (c, _))| c == curve)
+                .map(|(i, _)| i)
+                .collect()
+        };
+        let (rows, cols) = (index(curve1), index(curve2));
+        let block = Array2::from_shape_fn((rows.len(), cols.len()), |(i, j)| {
+            self.gamma[[rows[i], cols[j]]]
+        });
+        let tenors = |idx: &[usize]| idx.iter().map(|i| self.labels[*i].1.clone()).collect();
+        (tenors(&rows), tenors(&cols), block)
+    }
+
+    /// Return the sum of the entries of the block between `curve1` and `curve2`, which is the
+    /// gamma to a parallel shift of the instrument rates of both curves.
+    pub fn block_total(&self, curve1: &str, curve2: &str) -> f64 {
+        self.block(curve1, curve2).2.sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dual::{Dual, Dual2};
+    use ndarray::arr2;
+
+    fn mapping() -> RiskMapping {
+        // the rates of a "b" instrument depend on the variables of both curves
+        let tags = vec!["a0".to_string(), "b0".to_string()];
+        let rates = vec![
+            Dual::try_new(2.0, tags.clone(), vec![1.0, 0.0]).unwrap(),
+            Dual::try_new(3.0, tags.clone(), vec![1.0, 1.0]).unwrap(),
+        ];
+        let labels = vec![
+            ("a".to_string(), "1Y".to_string()),
+            ("b".to_string(), "1Y".to_string()),
+        ];
+        RiskMapping::try_new(&rates, tags, labels).unwrap()
+    }
+
+    #[test]
+    fn test_cross_gamma_in_instrument_space() {
+        // a value of r_a * r_b where r_a = a0 and r_b = a0 + b0
+        let a0 = Dual2::new(2.0, vec!["a0".to_string()]);
+        let b0 = Dual2::new(1.0, vec!["b0".to_string()]);
+        let value = Number::Dual2(&a0 * &(&a0 + &b0));
+        let eur = Ccy::try_new("eur").unwrap();
+        let gamma = CrossGamma::try_new(&value, &mapping(), eur).unwrap();
+        let expected = arr2(&[[0.0, 1e-4], [1e-4, 0.0]]);
+        assert!((gamma.matrix() - &expected).iter().all(|x| x.abs() < 1e-15));
+        assert_eq!(gamma.curves(), vec!["a".to_string(), "b".to_string()]);
+        let (rows, cols, block) = gamma.block("a", "b");
+        assert_eq!(
+            (rows, cols),
+            (vec!["1Y".to_string()], vec!["1Y".to_string()])
+        );
+        assert!((block[[0, 0]] - 1e-4).abs() < 1e-15);
+        assert!(gamma.block_total("a", "a").abs() < 1e-15);
+        assert!(
+            CrossGamma::try_new(&Number::Dual(Dual::new(1.0, vec![])), &mapping(), eur).is_err()
+        );
+    }
+}
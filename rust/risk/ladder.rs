@@ -0,0 +1,152 @@
+use crate::dual::Number;
+use crate::error::RateslibError;
+use crate::fx::rates::{Ccy, FXRates};
+use crate::risk::RiskMapping;
+use indexmap::IndexMap;
+
+/// The delta of a value to the rates of the calibrating instruments of a [RiskMapping], in a
+/// grid by curve and tenor.
+///
+/// Each delta is the change in the value, in its `currency`, for a 1bp increase in the rate of
+/// the instrument.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeltaLadder {
+    pub(crate) currency: Ccy,
+    pub(crate) grid: IndexMap<String, IndexMap<String, f64>>,
+}
+
+impl DeltaLadder {
+    /// Create the [DeltaLadder] of a `value` in `currency` with sensitivity to the variables of
+    /// the `mapping`.
+    pub fn try_new(
+        value: &Number,
+        mapping: &RiskMapping,
+        currency: Ccy,
+    ) -> Result<Self, RateslibError> {
+        let delta = mapping.instrument_delta(value)?;
+        let mut grid: IndexMap<String, IndexMap<String, f64>> = IndexMap::new();
+        for ((curve, tenor), d) in mapping.labels.iter().zip(delta.iter()) {
+            *grid
+                .entry(curve.clone())
+                .or_default()
+                .entry(tenor.clone())
+                .or_insert(0.0) += d;
+        }
+        Ok(Self { currency, grid })
+    }
+
+    pub fn currency(&self) -> Ccy {
+        self.currency
+    }
+
+    /// Return the grid of deltas by curve and then tenor, in the order of the instruments.
+    pub fn grid(&self) -> &IndexMap<String, IndexMap<String, f64>> {
+        &self.grid
+    }
+
+    /// Return the delta to the instrument of a `curve` and `tenor`, if any.
+    pub fn get(&self, curve: &str, tenor: &str) -> Option<f64> {
+        self.grid.get(curve)?.get(tenor).copied()
+    }
+
+    /// Return the total delta to the instruments of a `curve`.
+    pub fn curve_total(&self, curve: &str) -> f64 {
+        self.grid.get(curve).map_or(0.0, |t| t.values().sum())
+    }
+
+    /// Return the total delta to every instrument.
+    pub fn total(&self) -> f64 {
+        self.grid.values().flat_map(|t| t.values()).sum()
+    }
+
+    /// Return the ladder converted into a `base` currency at the `fx` rates.
+    pub fn to_base(&self, fx: &FXRates, base: Ccy) -> Result<Self, RateslibError> {
+        let rate = fx.rate(&self.currency, &base).ok_or_else(|| {
+            RateslibError::value(format!(
+                "`fx` has no rate to convert '{}' to '{}'.",
+                self.currency.name, base.name
+            ))
+        })?;
+        let rate = f64::from(&rate);
+        let grid = self
+            .grid
+            .iter()
+            .map(|(curve, tenors)| {
+                let tenors = tenors.iter().map(|(t, d)| (t.clone(), d * rate)).collect();
+                (curve.clone(), tenors)
+            })
+            .collect();
+        Ok(Self {
+            currency: base,
+            grid,
+        })
+    }
+
+    /// Return the sum of `ladders`, each converted into a `base` currency at the `fx` rates.
+    ///
+    /// The grid has the curves and tenors of every ladder, in order of first appearance.
+    pub fn sum(ladders: &[DeltaLadder], fx: &FXRates, base: Ccy) -> Result<Self, RateslibError> {
+        let mut total = DeltaLadder {
+            currency: base,
+            grid: IndexMap::new(),
+        };
+        for ladder in ladders.iter() {
+            for (curve, tenors) in ladder.to_base(fx, base)?.grid.into_iter() {
+                let entry = total.grid.entry(curve).or_default();
+                for (tenor, d) in tenors.into_iter() {
+                    *entry.entry(tenor).or_insert(0.0) += d;
+                }
+            }
+        }
+        Ok(total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::ndt;
+    use crate::fx::rates::FXRate;
+    use crate::instruments::Instrument;
+    use crate::periods::Curves;
+    use crate::risk::mapping::tests::calibration_fixture;
+
+    #[test]
+    fn test_delta_ladder() {
+        let (calibration, mapping) = calibration_fixture("eur");
+        let curves = Curves::new(Some(&calibration.curve), Some(&calibration.curve));
+        let eur = Ccy::try_new("eur").unwrap();
+        let npv = &calibration.instruments[1].npv(&curves).unwrap()
+            - &calibration.instruments[3].npv(&curves).unwrap();
+        let ladder = DeltaLadder::try_new(&npv, &mapping, eur).unwrap();
+        let grid = ladder.grid();
+        assert_eq!(
+            grid["eur"].keys().collect::<Vec<_>>(),
+            vec!["1Y", "2Y", "5Y", "10Y"]
+        );
+        // a long 2Y and short 10Y swap has offsetting deltas at those tenors only
+        let (d2, d10) = (
+            ladder.get("eur", "2Y").unwrap(),
+            ladder.get("eur", "10Y").unwrap(),
+        );
+        assert!(d2 > 0.0 && d10 < 0.0);
+        assert!(ladder.get("eur", "5Y").unwrap().abs() < 1e-5);
+        assert!((ladder.total() - (d2 + d10)).abs() < 1e-5);
+        assert_eq!(ladder.total(), ladder.curve_total("eur"));
+        assert_eq!(ladder.curve_total("usd"), 0.0);
+
+        let usd = Ccy::try_new("usd").unwrap();
+        let fx = FXRates::try_new(
+            vec![FXRate::try_new("eur", "usd", Number::F64(1.1), Some(ndt(2024, 1, 3))).unwrap()],
+            None,
+        )
+        .unwrap();
+        let base = ladder.to_base(&fx, usd).unwrap();
+        assert_eq!(base.currency(), usd);
+        assert!((base.get("eur", "2Y").unwrap() - 1.1 * d2).abs() < 1e-9);
+        let sum = DeltaLadder::sum(&[ladder.clone(), base.clone()], &fx, usd).unwrap();
+        assert!((sum.total() - 2.0 * base.total()).abs() < 1e-6);
+        let gbp = Ccy::try_new("gbp").unwrap();
+        assert!(ladder.to_base(&fx, gbp).is_err());
+    }
+}
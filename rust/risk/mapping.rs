@@ -0,0 +1,155 @@
+use crate::dual::linalg::fdsolve;
+use crate::dual::{Dual, Gradient1, Gradient2, Number};
+use crate::error::RateslibError;
+use crate::solver::SolverSystem;
+use ndarray::{Array1, Array2, Axis};
+
+/// The mapping of sensitivities to the variables of a calibrated system into sensitivities to
+/// the rates of its calibrating instruments, each labelled by a curve and a tenor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RiskMapping {
+    pub(crate) tags: Vec<String>,
+    pub(crate) labels: Vec<(String, String)>,
+    /// Jacobian of the instrument rates of shape (variables, instruments).
+    pub(crate) jac: Array2<f64>,
+}
+
+impl RiskMapping {
+    /// Create a [RiskMapping] from the `rates` of the calibrating instruments, with
+    /// sensitivity to the variable `tags`, and the `(curve, tenor)` label of each instrument.
+    pub fn try_new(
+        rates: &[Dual],
+        tags: Vec<String>,
+        labels: Vec<(String, String)>,
+    ) -> Result<Self, RateslibError> {
+        if rates.len() != labels.len() {
+            return Err(RateslibError::value(
+                "A `RiskMapping` requires a label for each calibrating instrument.",
+            ));
+        }
+        if rates.len() > tags.len() {
+            return Err(RateslibError::value(
+                "A `RiskMapping` cannot have more calibrating instruments than variables.",
+            ));
+        }
+        let mut jac = Array2::<f64>::zeros((tags.len(), rates.len()));
+        for (mut col, rate) in jac.axis_iter_mut(Axis(1)).zip(rates.iter()) {
+            col.assign(&rate.gradient1(tags.clone()));
+        }
+        Ok(Self { tags, labels, jac })
+    }
+
+    /// Create a [RiskMapping] from the current rates of the instruments of a `system`.
+    pub fn from_system<S: SolverSystem>(
+        system: &S,
+        labels: Vec<(String, String)>,
+    ) -> Result<Self, RateslibError> {
+        Self::try_new(&system.rates()?, system.variable_tags(), labels)
+    }
+
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    pub fn labels(&self) -> &[(String, String)] {
+        &self.labels
+    }
+
+    /// Return the sensitivities to the instrument rates of a value with sensitivities
+    /// `gradient` to the variables, solving `J g_r = g_x` in a least squares sense where
+    /// there are more variables than instruments.
+    pub(crate) fn to_instruments(&self, gradient: &Array1<f64>) -> Array1<f64> {
+        let lsq = self.jac.nrows() > self.jac.ncols();
+        fdsolve(&self.jac.view(), &gradient.view(), lsq)
+    }
+
+    /// Return the change in a `value` for a 1bp increase in the rate of each calibrating
+    /// instrument.
+    pub fn instrument_delta(&self, value: &Number) -> Result<Array1<f64>, RateslibError> {
+        let gradient = match value {
+            Number::Dual(d) => d.gradient1(self.tags.clone()),
+            Number::Dual2(d) => d.gradient1(self.tags.clone()),
+            Number::F64(_) => {
+                return Err(RateslibError::value(
+                    "Instrument sensitivities require a value with AD.",
+                ))
+            }
+        };
+        Ok(self.to_instruments(&gradient) * 0.01)
+    }
+
+    /// Return the change in the delta of a second order AD `value` to the rate of each
+    /// calibrating instrument, for a 1bp increase in the rate of each other instrument.
+    ///
+    /// The gamma maps the hessian to the variables by the first order Jacobian, `J^-1 H J^-T`,
+    /// ignoring the second order sensitivities of the instrument rates.
+    pub fn instrument_gamma(&self, value: &Number) -> Result<Array2<f64>, RateslibError> {
+        let hessian = match value {
+            Number::Dual2(d) => d.gradient2(self.tags.clone()),
+            _ => {
+                return Err(RateslibError::value(
+                    "Instrument cross-gamma requires a value with second order AD.",
+                ))
+            }
+        };
+        let n = self.jac.ncols();
+        let mut half = Array2::<f64>::zeros((n, self.tags.len()));
+        for (mut col, h) in half.axis_iter_mut(Axis(1)).zip(hessian.axis_iter(Axis(1))) {
+            col.assign(&self.to_instruments(&h.to_owned()));
+        }
+        let mut gamma = Array2::<f64>::zeros((n, n));
+        for (mut col, row) in gamma.axis_iter_mut(Axis(1)).zip(half.axis_iter(Axis(0))) {
+            col.assign(&self.to_instruments(&row.to_owned()));
+        }
+        Ok(gamma * 0.0001)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::calendars::ndt;
+    use crate::dual::get_variable_tags;
+    use crate::instruments::Instrument;
+    use crate::interop::{calibrate_curve, read_quotes_csv, CurveCalibration};
+    use crate::periods::Curves;
+
+    /// A curve calibrated to 1Y, 2Y, 5Y and 10Y swaps and the mapping to their rates,
+    /// labelled by tenor.
+    pub(crate) fn calibration_fixture(id: &str) -> (CurveCalibration, RiskMapping) {
+        let csv = "eur_irs,1Y,3.10\neur_irs,2Y,2.85\neur_irs,5Y,2.65\neur_irs,10Y,2.70";
+        let quotes = read_quotes_csv(csv).unwrap();
+        let calibration = calibrate_curve(&quotes, ndt(2024, 1, 3), id).unwrap();
+        let curves = Curves::new(Some(&calibration.curve), Some(&calibration.curve));
+        let rates: Vec<Dual> = calibration
+            .instruments
+            .iter()
+            .map(|i| Dual::from(&i.rate(&curves).unwrap()))
+            .collect();
+        let labels = quotes
+            .iter()
+            .map(|q| (id.to_string(), q.tenor.clone()))
+            .collect();
+        let tags = get_variable_tags(id, quotes.len() + 1)[1..].to_vec();
+        let mapping = RiskMapping::try_new(&rates, tags, labels).unwrap();
+        (calibration, mapping)
+    }
+
+    #[test]
+    fn test_instrument_delta_of_calibrating_instrument() {
+        // the delta of a calibrating swap is its own analytic delta to its own rate
+        let (calibration, mapping) = calibration_fixture("eur");
+        let curves = Curves::new(Some(&calibration.curve), Some(&calibration.curve));
+        let irs = &calibration.instruments[2];
+        let delta = mapping
+            .instrument_delta(&irs.npv(&curves).unwrap())
+            .unwrap();
+        let a_delta = f64::from(&irs.analytic_delta(&curves).unwrap());
+        assert!((delta[2] - a_delta).abs() / a_delta < 1e-8);
+        for i in [0, 1, 3] {
+            assert!(delta[i].abs() < 1e-6);
+        }
+        assert!(mapping.instrument_delta(&Number::F64(1.0)).is_err());
+        assert!(RiskMapping::try_new(&[], vec![], vec![("eur".into(), "1Y".into())]).is_err());
+    }
+}
@@ -0,0 +1,16 @@
+//! Risk reports from the AD sensitivities of values to the variables of calibrated curves.
+//!
+//! A [RiskMapping] holds the Jacobian of the calibrating instrument rates of a curve system,
+//! mapping sensitivities to curve variables into sensitivities to instrument rates. A
+//! [DeltaLadder] reports these deltas by curve and tenor, with totals and conversion into a
+//! base currency, and a [CrossGamma] reports the second order sensitivities between instrument
+//! rates, in blocks by pairs of curves.
+
+pub(crate) mod mapping;
+pub use crate::risk::mapping::RiskMapping;
+
+mod ladder;
+pub use crate::risk::ladder::DeltaLadder;
+
+mod gamma;
+pub use crate::risk::gamma::CrossGamma;
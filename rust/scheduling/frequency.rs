@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+/// The frequency of the regular periods of a schedule.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Frequency {
+    /// Regular periods of a given number of months.
+    Months { number: u32 },
+    /// A single period from effective to termination, i.e. a zero coupon.
+    Zero {},
+}
+
+impl Frequency {
+    /// Return the number of months in a regular period.
+    ///
+    /// A zero frequency returns [u32::MAX], which day count conventions interpret as
+    /// longer than one year.
+    pub fn months(&self) -> u32 {
+        match self {
+            Frequency::Months { number } => *number,
+            Frequency::Zero {} => u32::MAX,
+        }
+    }
+
+    /// Return the number of regular periods in a year, or zero for a zero frequency.
+    pub fn periods_per_annum(&self) -> f64 {
+        match self {
+            Frequency::Months { number } => 12.0 / *number as f64,
+            Frequency::Zero {} => 0.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_periods_per_annum() {
+        assert_eq!(Frequency::Months { number: 3 }.periods_per_annum(), 4.0);
+        assert_eq!(Frequency::Zero {}.periods_per_annum(), 0.0);
+        assert_eq!(Frequency::Months { number: 6 }.months(), 6);
+    }
+}
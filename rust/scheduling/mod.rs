@@ -0,0 +1,13 @@
+//! Generate schedules of periods for legs and instruments.
+
+mod frequency;
+pub use crate::scheduling::frequency::Frequency;
+
+mod payment_lag;
+pub use crate::scheduling::payment_lag::PaymentLag;
+
+mod stub;
+pub use crate::scheduling::stub::StubInference;
+
+mod schedule;
+pub use crate::scheduling::schedule::Schedule;
@@ -0,0 +1,61 @@
+use crate::calendars::{DateRoll, Modifier};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// A delay between the end of an accrual period and the payment of its cashflow.
+///
+/// The accrual end date is first adjusted under `modifier` to a business day of the payment
+/// calendar, which may differ from the accrual calendar, and then lagged by `days` business
+/// days enforcing settlement. Many OIS and RFR products pay with a lag of 1 or 2 days.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PaymentLag {
+    pub days: i8,
+    pub modifier: Modifier,
+}
+
+impl PaymentLag {
+    pub fn new(days: i8, modifier: Modifier) -> Self {
+        Self { days, modifier }
+    }
+
+    /// Return the payment date of a period accruing until `end`, under the payment `calendar`.
+    pub fn payment<U: DateRoll>(&self, end: &NaiveDateTime, calendar: &U) -> NaiveDateTime {
+        let end = calendar.roll(end, &self.modifier, true);
+        calendar.lag(&end, self.days, true)
+    }
+}
+
+impl Default for PaymentLag {
+    /// Payment on the accrual end date, rolled forward to a business day.
+    fn default() -> Self {
+        Self::new(0, Modifier::F)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::{ndt, NamedCal};
+
+    #[test]
+    fn test_payment_lag() {
+        let cal = NamedCal::try_new("tgt").unwrap();
+        // Friday 14th June 2024 lagged by 2 business days
+        let lag = PaymentLag::new(2, Modifier::F);
+        assert_eq!(lag.payment(&ndt(2024, 6, 14), &cal), ndt(2024, 6, 18));
+        // Saturday 15th June 2024 is first rolled to the Monday
+        assert_eq!(lag.payment(&ndt(2024, 6, 15), &cal), ndt(2024, 6, 19));
+        assert_eq!(
+            PaymentLag::default().payment(&ndt(2024, 6, 15), &cal),
+            ndt(2024, 6, 17)
+        );
+    }
+
+    #[test]
+    fn test_payment_lag_modifier() {
+        let cal = NamedCal::try_new("tgt").unwrap();
+        // Sunday 30th June 2024 rolls back within the month under ModF
+        let lag = PaymentLag::new(0, Modifier::ModF);
+        assert_eq!(lag.payment(&ndt(2024, 6, 30), &cal), ndt(2024, 6, 28));
+    }
+}
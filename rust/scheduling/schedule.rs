@@ -0,0 +1,539 @@
+use crate::calendars::{DateRoll, Modifier, RollDay};
+use crate::error::RateslibError;
+use crate::scheduling::{Frequency, PaymentLag, StubInference};
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A schedule of period dates between an effective and termination date.
+///
+/// Stubs are placed by a [StubInference], by default generating regular periods backwards
+/// from `termination` so that any stub is a short front stub.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Schedule {
+    pub(crate) effective: NaiveDateTime,
+    pub(crate) termination: NaiveDateTime,
+    pub(crate) frequency: Frequency,
+    pub(crate) modifier: Modifier,
+    pub(crate) payment_lag: i8,
+    /// Unadjusted period dates.
+    pub(crate) uschedule: Vec<NaiveDateTime>,
+    /// Accrual period dates adjusted by the `modifier`.
+    pub(crate) aschedule: Vec<NaiveDateTime>,
+    /// Payment dates of each period.
+    pub(crate) pschedule: Vec<NaiveDateTime>,
+    /// Whether each period is a stub.
+    pub(crate) stubs: Vec<bool>,
+    /// The stub placed by the schedule, if any.
+    #[serde(default)]
+    pub(crate) stub: Option<StubInference>,
+}
+
+impl Schedule {
+    /// Create a [Schedule] with any stub a short front stub.
+    ///
+    /// - `roll` determines the day of month of unadjusted dates and, if unspecified, is the
+    ///   day of `termination`.
+    /// - `payment_lag` is the number of business days after each adjusted period end date that
+    ///   payment occurs.
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_new<U: DateRoll>(
+        effective: NaiveDateTime,
+        termination: NaiveDateTime,
+        frequency: Frequency,
+        roll: RollDay,
+        modifier: Modifier,
+        calendar: &U,
+        payment_lag: i8,
+    ) -> Result<Self, RateslibError> {
+        Self::try_new_with_stub(
+            effective,
+            termination,
+            frequency,
+            roll,
+            modifier,
+            calendar,
+            payment_lag,
+            StubInference::ShortFront,
+        )
+    }
+
+    /// Create a [Schedule] with any stub placed by the `stub` policy.
+    ///
+    /// An unspecified `roll` is the day of the date from which regular periods are generated:
+    /// `termination` for a front stub, `effective` for a back stub and the explicit `back`
+    /// date for explicit stubs. Explicit stub dates must be separated by regular periods. A
+    /// zero `frequency` has a single period and no stub.
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_new_with_stub<U: DateRoll>(
+        effective: NaiveDateTime,
+        termination: NaiveDateTime,
+        frequency: Frequency,
+        roll: RollDay,
+        modifier: Modifier,
+        calendar: &U,
+        payment_lag: i8,
+        stub: StubInference,
+    ) -> Result<Self, RateslibError> {
+        if termination <= effective {
+            return Err(RateslibError::value(
+                "`termination` of a `Schedule` must be after its `effective` date.",
+            ));
+        }
+        stub.validate(&effective, &termination)?;
+        let roll_from = |date: &NaiveDateTime| match roll {
+            RollDay::Unspecified {} => RollDay::Int { day: date.day() },
+            _ => roll,
+        };
+        let add = |date: &NaiveDateTime, months: i32, roll: &RollDay| {
+            calendar.add_months(date, months, &Modifier::Act, roll, false)
+        };
+
+        let (uschedule, inferred) = match (frequency, stub) {
+            (Frequency::Zero {}, StubInference::BothExplicit { .. }) => {
+                return Err(RateslibError::value(
+                    "Explicit stub dates require a `Schedule` with a regular `frequency`.",
+                ))
+            }
+            (Frequency::Zero {}, _) => (vec![effective, termination], None),
+            (Frequency::Months { number }, StubInference::BothExplicit { front, back }) => {
+                let roll = roll_from(&back);
+                let regular = backward_dates(&front, &back, number, &roll, calendar)?;
+                if add(&regular[1], -(number as i32), &roll)? != front {
+                    return Err(RateslibError::value(
+                        "Explicit stub dates must be separated by regular periods.",
+                    ));
+                }
+                let mut uschedule = vec![];
+                if front != effective {
+                    uschedule.push(effective);
+                }
+                uschedule.extend(regular);
+                if back != termination {
+                    uschedule.push(termination);
+                }
+                (uschedule, Some(stub))
+            }
+            (
+                Frequency::Months { number },
+                StubInference::ShortFront | StubInference::LongFront,
+            ) => {
+                let roll = roll_from(&termination);
+                let mut uschedule =
+                    backward_dates(&effective, &termination, number, &roll, calendar)?;
+                let short = add(&uschedule[1], -(number as i32), &roll)? != effective;
+                let inferred = match (short, stub) {
+                    (false, _) => None,
+                    (true, StubInference::LongFront) if uschedule.len() > 2 => {
+                        uschedule.remove(1);
+                        Some(StubInference::LongFront)
+                    }
+                    (true, _) => Some(StubInference::ShortFront),
+                };
+                (uschedule, inferred)
+            }
+            (Frequency::Months { number }, _) => {
+                let roll = roll_from(&effective);
+                let mut uschedule = vec![effective];
+                let mut i = 1_i32;
+                loop {
+                    let date = add(&effective, i * number as i32, &roll)?;
+                    if date >= termination {
+                        break;
+                    }
+                    uschedule.push(date);
+                    i += 1;
+                }
+                uschedule.push(termination);
+                let n = uschedule.len();
+                let short = add(&uschedule[n - 2], number as i32, &roll)? != termination;
+                let inferred = match (short, stub) {
+                    (false, _) => None,
+                    (true, StubInference::LongBack) if n > 2 => {
+                        uschedule.remove(n - 2);
+                        Some(StubInference::LongBack)
+                    }
+                    (true, _) => Some(StubInference::ShortBack),
+                };
+                (uschedule, inferred)
+            }
+        };
+
+        let n = uschedule.len() - 1;
+        let mut stubs = vec![false; n];
+        match inferred {
+            Some(StubInference::ShortFront | StubInference::LongFront) => stubs[0] = true,
+            Some(StubInference::ShortBack | StubInference::LongBack) => stubs[n - 1] = true,
+            Some(StubInference::BothExplicit { front, back }) => {
+                stubs[0] = front != effective;
+                stubs[n - 1] |= back != termination;
+            }
+            None => {}
+        }
+
+        let aschedule: Vec<NaiveDateTime> = uschedule
+            .iter()
+            .map(|d| calendar.roll(d, &modifier, false))
+            .collect();
+        let pschedule: Vec<NaiveDateTime> = aschedule[1..]
+            .iter()
+            .map(|d| calendar.lag(d, payment_lag, true))
+            .collect();
+        Ok(Self {
+            effective,
+            termination,
+            frequency,
+            modifier,
+            payment_lag,
+            uschedule,
+            aschedule,
+            pschedule,
+            stubs,
+            stub: inferred,
+        })
+    }
+
+    /// Return the schedule with the payment date of each period determined by a [PaymentLag]
+    /// from its adjusted accrual end date under the payment `calendar`.
+    pub fn with_payment_lag<U: DateRoll>(&self, lag: &PaymentLag, calendar: &U) -> Self {
+        Self {
+            payment_lag: lag.days,
+            pschedule: self.aschedule[1..]
+                .iter()
+                .map(|d| lag.payment(d, calendar))
+                .collect(),
+            ..self.clone()
+        }
+    }
+
+    /// Return the schedule collapsed to a single zero frequency period, from the effective to
+    /// the termination date, paid on the final payment date.
+    pub fn to_zero(&self) -> Self {
+        let last = |v: &[NaiveDateTime]| *v.last().unwrap();
+        Self {
+            frequency: Frequency::Zero {},
+            uschedule: vec![self.uschedule[0], last(&self.uschedule)],
+            aschedule: vec![self.aschedule[0], last(&self.aschedule)],
+            pschedule: vec![last(&self.pschedule)],
+            stubs: vec![false],
+            stub: None,
+            ..self.clone()
+        }
+    }
+
+    /// Return the stub placed by the schedule, or `None` if every period is regular.
+    ///
+    /// A long stub requested of a schedule with a single stub period is inferred short.
+    pub fn stub(&self) -> Option<StubInference> {
+        self.stub
+    }
+
+    /// Return the number of periods.
+    pub fn n_periods(&self) -> usize {
+        self.stubs.len()
+    }
+
+    pub fn frequency(&self) -> Frequency {
+        self.frequency
+    }
+
+    /// Return the adjusted termination date.
+    pub fn termination(&self) -> NaiveDateTime {
+        *self.aschedule.last().unwrap()
+    }
+
+    /// Return the unadjusted period dates.
+    pub fn uschedule(&self) -> &[NaiveDateTime] {
+        &self.uschedule
+    }
+
+    /// Return the adjusted accrual period dates.
+    pub fn aschedule(&self) -> &[NaiveDateTime] {
+        &self.aschedule
+    }
+
+    /// Return the payment dates of each period.
+    pub fn pschedule(&self) -> &[NaiveDateTime] {
+        &self.pschedule
+    }
+
+    /// Return the adjusted start, adjusted end, payment date and stub flag of each period.
+    pub fn periods(&self) -> Vec<(NaiveDateTime, NaiveDateTime, NaiveDateTime, bool)> {
+        (0..self.n_periods())
+            .map(|i| {
+                (
+                    self.aschedule[i],
+                    self.aschedule[i + 1],
+                    self.pschedule[i],
+                    self.stubs[i],
+                )
+            })
+            .collect()
+    }
+}
+
+/// Return the unadjusted dates from `start` to `end` of regular periods of `months` generated
+/// backwards from `end` under the `roll`, with any irregular period first.
+fn backward_dates<U: DateRoll>(
+    start: &NaiveDateTime,
+    end: &NaiveDateTime,
+    months: u32,
+    roll: &RollDay,
+    calendar: &U,
+) -> Result<Vec<NaiveDateTime>, RateslibError> {
+    let mut dates = vec![*end];
+    let mut i = 1_i32;
+    loop {
+        let date = calendar.add_months(end, -i * months as i32, &Modifier::Act, roll, false)?;
+        if date <= *start {
+            break;
+        }
+        dates.push(date);
+        i += 1;
+    }
+    dates.push(*start);
+    dates.reverse();
+    Ok(dates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::{ndt, NamedCal};
+
+    #[test]
+    fn test_regular_schedule() {
+        let cal = NamedCal::try_new("tgt").unwrap();
+        let s = Schedule::try_new(
+            ndt(2024, 3, 15),
+            ndt(2025, 3, 15),
+            Frequency::Months { number: 3 },
+            RollDay::Unspecified {},
+            Modifier::ModF,
+            &cal,
+            2,
+        )
+        .unwrap();
+        assert_eq!(
+            s.uschedule(),
+            &[
+                ndt(2024, 3, 15),
+                ndt(2024, 6, 15),
+                ndt(2024, 9, 15),
+                ndt(2024, 12, 15),
+                ndt(2025, 3, 15)
+            ]
+        );
+        // 15th June 2024 is a Saturday
+        assert_eq!(s.aschedule()[1], ndt(2024, 6, 17));
+        assert_eq!(s.pschedule()[0], ndt(2024, 6, 19));
+        assert_eq!(s.stubs, vec![false; 4]);
+    }
+
+    #[test]
+    fn test_front_stub() {
+        let cal = NamedCal::try_new("all").unwrap();
+        let s = Schedule::try_new(
+            ndt(2024, 2, 1),
+            ndt(2025, 3, 15),
+            Frequency::Months { number: 6 },
+            RollDay::Unspecified {},
+            Modifier::Act,
+            &cal,
+            0,
+        )
+        .unwrap();
+        assert_eq!(
+            s.uschedule(),
+            &[
+                ndt(2024, 2, 1),
+                ndt(2024, 3, 15),
+                ndt(2024, 9, 15),
+                ndt(2025, 3, 15)
+            ]
+        );
+        assert_eq!(s.stubs, vec![true, false, false]);
+    }
+
+    fn stub_fixture(stub: StubInference) -> Result<Schedule, RateslibError> {
+        Schedule::try_new_with_stub(
+            ndt(2024, 2, 1),
+            ndt(2025, 3, 15),
+            Frequency::Months { number: 6 },
+            RollDay::Unspecified {},
+            Modifier::Act,
+            &NamedCal::try_new("all").unwrap(),
+            0,
+            stub,
+        )
+    }
+
+    #[test]
+    fn test_stub_inference() {
+        let s = stub_fixture(StubInference::ShortFront).unwrap();
+        assert_eq!(s.stub(), Some(StubInference::ShortFront));
+        let s = stub_fixture(StubInference::LongFront).unwrap();
+        assert_eq!(
+            s.uschedule(),
+            &[ndt(2024, 2, 1), ndt(2024, 9, 15), ndt(2025, 3, 15)]
+        );
+        assert_eq!(
+            (s.stubs.clone(), s.stub()),
+            (vec![true, false], Some(StubInference::LongFront))
+        );
+        let s = stub_fixture(StubInference::ShortBack).unwrap();
+        assert_eq!(
+            s.uschedule(),
+            &[
+                ndt(2024, 2, 1),
+                ndt(2024, 8, 1),
+                ndt(2025, 2, 1),
+                ndt(2025, 3, 15)
+            ]
+        );
+        assert_eq!(s.stubs, vec![false, false, true]);
+        let s = stub_fixture(StubInference::LongBack).unwrap();
+        assert_eq!(
+            s.uschedule(),
+            &[ndt(2024, 2, 1), ndt(2024, 8, 1), ndt(2025, 3, 15)]
+        );
+        assert_eq!(s.stub(), Some(StubInference::LongBack));
+    }
+
+    #[test]
+    fn test_stub_inference_regular_and_single() {
+        let cal = NamedCal::try_new("all").unwrap();
+        let make = |termination, stub| {
+            Schedule::try_new_with_stub(
+                ndt(2024, 2, 1),
+                termination,
+                Frequency::Months { number: 6 },
+                RollDay::Unspecified {},
+                Modifier::Act,
+                &cal,
+                0,
+                stub,
+            )
+            .unwrap()
+        };
+        // a regular schedule has no stub under any policy
+        let s = make(ndt(2025, 2, 1), StubInference::LongBack);
+        assert_eq!((s.stub(), s.n_periods()), (None, 2));
+        // a single stub period cannot be long
+        let s = make(ndt(2024, 5, 1), StubInference::LongFront);
+        assert_eq!(
+            (s.stub(), s.stubs.clone()),
+            (Some(StubInference::ShortFront), vec![true])
+        );
+    }
+
+    #[test]
+    fn test_explicit_stubs() {
+        let stub = StubInference::BothExplicit {
+            front: ndt(2024, 3, 15),
+            back: ndt(2024, 9, 15),
+        };
+        let s = stub_fixture(stub).unwrap();
+        assert_eq!(
+            s.uschedule(),
+            &[
+                ndt(2024, 2, 1),
+                ndt(2024, 3, 15),
+                ndt(2024, 9, 15),
+                ndt(2025, 3, 15)
+            ]
+        );
+        assert_eq!(s.stubs, vec![true, false, true]);
+        assert_eq!(s.stub(), Some(stub));
+        // explicit dates must be separated by regular periods
+        let irregular = StubInference::BothExplicit {
+            front: ndt(2024, 3, 1),
+            back: ndt(2024, 9, 15),
+        };
+        assert!(stub_fixture(irregular).is_err());
+        let outside = StubInference::BothExplicit {
+            front: ndt(2024, 1, 15),
+            back: ndt(2024, 9, 15),
+        };
+        assert!(stub_fixture(outside).is_err());
+    }
+
+    #[test]
+    fn test_zero_frequency() {
+        let cal = NamedCal::try_new("all").unwrap();
+        let s = Schedule::try_new(
+            ndt(2024, 2, 1),
+            ndt(2029, 2, 1),
+            Frequency::Zero {},
+            RollDay::Unspecified {},
+            Modifier::Act,
+            &cal,
+            0,
+        )
+        .unwrap();
+        assert_eq!(s.n_periods(), 1);
+        assert_eq!(
+            s.periods()[0],
+            (ndt(2024, 2, 1), ndt(2029, 2, 1), ndt(2029, 2, 1), false)
+        );
+        // a regular schedule collapses to the same zero frequency schedule
+        let regular = Schedule::try_new(
+            ndt(2024, 2, 1),
+            ndt(2029, 2, 1),
+            Frequency::Months { number: 12 },
+            RollDay::Unspecified {},
+            Modifier::Act,
+            &cal,
+            0,
+        )
+        .unwrap();
+        assert_eq!(regular.to_zero(), s);
+    }
+
+    #[test]
+    fn test_with_payment_lag() {
+        let tgt = NamedCal::try_new("tgt").unwrap();
+        let s = Schedule::try_new(
+            ndt(2024, 3, 15),
+            ndt(2025, 3, 15),
+            Frequency::Months { number: 3 },
+            RollDay::Unspecified {},
+            Modifier::ModF,
+            &tgt,
+            2,
+        )
+        .unwrap();
+        let lagged = s.with_payment_lag(&PaymentLag::new(2, Modifier::F), &tgt);
+        assert_eq!(lagged, s);
+        // a payment calendar with a 4th July holiday delays payment of the period ending the
+        // 2nd July 2024 by one day
+        let nyc = NamedCal::try_new("nyc").unwrap();
+        let s = Schedule::try_new(
+            ndt(2024, 1, 2),
+            ndt(2024, 7, 2),
+            Frequency::Months { number: 6 },
+            RollDay::Unspecified {},
+            Modifier::ModF,
+            &tgt,
+            0,
+        )
+        .unwrap();
+        let lagged = s.with_payment_lag(&PaymentLag::new(2, Modifier::F), &nyc);
+        assert_eq!(lagged.pschedule(), &[ndt(2024, 7, 5)]);
+        assert_eq!(lagged.aschedule(), s.aschedule());
+    }
+
+    #[test]
+    fn test_invalid_dates() {
+        let cal = NamedCal::try_new("all").unwrap();
+        let s = Schedule::try_new(
+            ndt(2024, 2, 1),
+            ndt(2024, 2, 1),
+            Frequency::Zero {},
+            RollDay::Unspecified {},
+            Modifier::Act,
+            &cal,
+            0,
+        );
+        assert!(s.is_err());
+    }
+}
@@ -0,0 +1,65 @@
+use crate::error::RateslibError;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// The policy by which a [Schedule](crate::scheduling::Schedule) places a stub period where
+/// its dates do not divide into regular periods.
+///
+/// A front stub is placed by generating regular periods backwards from the termination date
+/// and a back stub forwards from the effective date. A long stub combines a short stub with
+/// its adjacent regular period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum StubInference {
+    #[default]
+    ShortFront,
+    LongFront,
+    ShortBack,
+    LongBack,
+    /// Regular periods between explicit `front` and `back` stub dates, with a front stub from
+    /// the effective date to `front`, and a back stub from `back` to the termination date,
+    /// unless the dates coincide.
+    BothExplicit {
+        front: NaiveDateTime,
+        back: NaiveDateTime,
+    },
+}
+
+impl StubInference {
+    /// Raise if the policy is inconsistent with the `effective` and `termination` dates.
+    pub fn validate(
+        &self,
+        effective: &NaiveDateTime,
+        termination: &NaiveDateTime,
+    ) -> Result<(), RateslibError> {
+        if let StubInference::BothExplicit { front, back } = self {
+            if !(effective <= front && front < back && back <= termination) {
+                return Err(RateslibError::value(
+                    "Explicit stub dates must satisfy `effective <= front < back <= termination`.",
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::ndt;
+
+    #[test]
+    fn test_validate() {
+        let (e, t) = (ndt(2024, 1, 1), ndt(2026, 1, 1));
+        assert!(StubInference::LongBack.validate(&e, &t).is_ok());
+        let explicit = |front, back| StubInference::BothExplicit { front, back };
+        assert!(explicit(ndt(2024, 3, 1), ndt(2025, 9, 1))
+            .validate(&e, &t)
+            .is_ok());
+        assert!(explicit(ndt(2025, 9, 1), ndt(2024, 3, 1))
+            .validate(&e, &t)
+            .is_err());
+        assert!(explicit(ndt(2023, 3, 1), ndt(2025, 9, 1))
+            .validate(&e, &t)
+            .is_err());
+    }
+}
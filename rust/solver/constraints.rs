@@ -0,0 +1,177 @@
+use crate::error::RateslibError;
+
+/// Constraint imposed upon an individual variable of a [SolverSystem](crate::solver::SolverSystem)
+/// during calibration.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum VariableConstraint {
+    /// The variable is unconstrained.
+    Free,
+    /// The variable is held fixed at `value`.
+    Pinned { value: f64 },
+    /// The variable is projected onto the closed interval [`lower`, `upper`] after each step.
+    Bounded { lower: f64, upper: f64 },
+    /// The variable is iterated as its logarithm, so that it remains strictly positive, and is
+    /// projected onto the interval (0, `upper`] after each step, e.g. a discount factor.
+    LogPositive { upper: f64 },
+}
+
+impl VariableConstraint {
+    pub(crate) fn validate(&self) -> Result<(), RateslibError> {
+        let valid = match self {
+            VariableConstraint::Free => true,
+            VariableConstraint::Pinned { value } => value.is_finite(),
+            VariableConstraint::Bounded { lower, upper } => lower <= upper,
+            VariableConstraint::LogPositive { upper } => *upper > 0.0,
+        };
+        match valid {
+            true => Ok(()),
+            false => Err(RateslibError::solver(
+                "`VariableConstraint` parameters are outside of their valid ranges.",
+            )),
+        }
+    }
+
+    /// Project a value onto the feasible set of the constraint.
+    pub(crate) fn project(&self, x: f64) -> Result<f64, RateslibError> {
+        match self {
+            VariableConstraint::Free => Ok(x),
+            VariableConstraint::Pinned { value } => Ok(*value),
+            VariableConstraint::Bounded { lower, upper } => Ok(x.clamp(*lower, *upper)),
+            VariableConstraint::LogPositive { upper } => match x > 0.0 {
+                true => Ok(x.min(*upper)),
+                false => Err(RateslibError::solver(
+                    "A variable with a `LogPositive` constraint must be initialised positive.",
+                )),
+            },
+        }
+    }
+
+    /// Return the derivative of the variable with respect to its iterated parameter.
+    pub(crate) fn scale(&self, x: f64) -> f64 {
+        match self {
+            VariableConstraint::Free | VariableConstraint::Bounded { .. } => 1.0,
+            VariableConstraint::Pinned { .. } => 0.0,
+            VariableConstraint::LogPositive { .. } => x,
+        }
+    }
+
+    /// Apply a `step` in the iterated parameter and return the projected variable.
+    pub(crate) fn apply(&self, x: f64, step: f64) -> f64 {
+        match self {
+            VariableConstraint::Free => x + step,
+            VariableConstraint::Pinned { value } => *value,
+            VariableConstraint::Bounded { lower, upper } => (x + step).clamp(*lower, *upper),
+            VariableConstraint::LogPositive { upper } => (x * step.exp()).min(*upper),
+        }
+    }
+
+    pub(crate) fn is_pinned(&self) -> bool {
+        matches!(self, VariableConstraint::Pinned { .. })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::solver::tests::{solver_fixture, system_fixture};
+    use crate::solver::{SolverAlgorithm, SolverStatus, StepControl};
+
+    #[test]
+    fn test_apply() {
+        let c = VariableConstraint::Bounded {
+            lower: 0.0,
+            upper: 1.0,
+        };
+        assert_eq!(c.apply(0.5, 0.75), 1.0);
+        assert_eq!(c.apply(0.5, -0.75), 0.0);
+        let c = VariableConstraint::LogPositive { upper: 1.0 };
+        assert_eq!(c.apply(0.5, -2.0_f64.ln()), 0.25);
+        assert_eq!(c.apply(0.5, 2.0), 1.0);
+        let c = VariableConstraint::Pinned { value: 0.3 };
+        assert_eq!(c.apply(0.5, 2.0), 0.3);
+    }
+
+    #[test]
+    fn test_project_log_positive_err() {
+        let c = VariableConstraint::LogPositive { upper: 1.0 };
+        assert!(c.project(-0.1).is_err());
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        let c = VariableConstraint::Bounded {
+            lower: 1.0,
+            upper: 0.0,
+        };
+        assert!(c.validate().is_err());
+        let c = VariableConstraint::LogPositive { upper: 0.0 };
+        assert!(c.validate().is_err());
+    }
+
+    #[test]
+    fn test_constraints_length() {
+        let mut system = system_fixture(vec![1.0, 1.0]);
+        let mut solver = solver_fixture(
+            vec![1.0, 2.0],
+            SolverAlgorithm::GaussNewton,
+            StepControl::Full,
+        );
+        solver
+            .set_constraints(vec![VariableConstraint::Free])
+            .unwrap();
+        assert!(solver.iterate(&mut system).is_err());
+    }
+
+    #[test]
+    fn test_bounded_discount_factor() {
+        // a negative rate would require a discount factor above one
+        let mut system = system_fixture(vec![0.9]);
+        let mut solver =
+            solver_fixture(vec![-1.0], SolverAlgorithm::GaussNewton, StepControl::Full);
+        solver
+            .set_constraints(vec![VariableConstraint::Bounded {
+                lower: 1e-12,
+                upper: 1.0,
+            }])
+            .unwrap();
+        let result = solver.iterate(&mut system).unwrap();
+        assert_eq!(result.status, SolverStatus::ConvTol);
+        assert_eq!(system.dfs, vec![1.0]);
+    }
+
+    #[test]
+    fn test_pinned_node() {
+        let mut system = system_fixture(vec![1.0, 1.0]);
+        let mut solver = solver_fixture(
+            vec![1.0, 2.0],
+            SolverAlgorithm::LevenbergMarquardt,
+            StepControl::Full,
+        );
+        solver
+            .set_constraints(vec![
+                VariableConstraint::Pinned { value: 0.995 },
+                VariableConstraint::Free,
+            ])
+            .unwrap();
+        let _ = solver.iterate(&mut system).unwrap();
+        assert_eq!(system.dfs[0], 0.995);
+        assert!((system.dfs[1] - 1.0 / 1.04).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_log_positive_prevents_overshoot() {
+        // the unconstrained full Gauss-Newton step overshoots to a negative discount factor
+        let mut system = system_fixture(vec![1.0]);
+        let mut solver = solver_fixture(
+            vec![1000.0],
+            SolverAlgorithm::GaussNewton,
+            StepControl::Full,
+        );
+        solver
+            .set_constraints(vec![VariableConstraint::LogPositive { upper: 1.0 }])
+            .unwrap();
+        let result = solver.iterate(&mut system).unwrap();
+        assert!(result.status.is_success());
+        assert!((system.dfs[0] - 1.0 / 11.0).abs() < 1e-8);
+    }
+}
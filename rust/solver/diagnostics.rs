@@ -0,0 +1,156 @@
+use crate::dual::linalg::dsolve;
+use ndarray::{Array1, Array2};
+use std::time::Duration;
+
+/// The state of a calibration recorded after an iteration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IterationRecord {
+    /// The value of the objective function.
+    pub g: f64,
+    /// The Euclidean norm of the instrument residuals to their targets.
+    pub residual_norm: f64,
+    /// The largest absolute instrument residual.
+    pub max_residual: f64,
+    /// The Euclidean norm of the change in the variables.
+    pub step_size: f64,
+    /// An estimate of the condition number of the Jacobian normal matrix used for the step,
+    /// or zero unless enabled by [Solver::set_condition_estimates](crate::solver::Solver).
+    pub condition: f64,
+    /// The Levenberg-Marquardt damping parameter used for the step.
+    pub lambda: f64,
+    /// The time elapsed since the calibration started.
+    pub elapsed: Duration,
+}
+
+/// The iteration history of a calibration.
+///
+/// The first record contains the initial state of the system, for which `step_size`,
+/// `condition` and `lambda` are zero.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SolverDiagnostics {
+    pub records: Vec<IterationRecord>,
+}
+
+impl SolverDiagnostics {
+    pub(crate) fn push(&mut self, record: IterationRecord) {
+        self.records.push(record)
+    }
+
+    /// Return the value of the objective function after each iteration.
+    pub fn objective(&self) -> Vec<f64> {
+        self.records.iter().map(|r| r.g).collect()
+    }
+
+    /// Return the residual norm after each iteration.
+    pub fn residual_norms(&self) -> Vec<f64> {
+        self.records.iter().map(|r| r.residual_norm).collect()
+    }
+
+    /// Return the size of the step taken in each iteration.
+    pub fn step_sizes(&self) -> Vec<f64> {
+        self.records.iter().map(|r| r.step_size).collect()
+    }
+
+    /// Return the condition number estimate of each iteration.
+    pub fn condition_numbers(&self) -> Vec<f64> {
+        self.records.iter().map(|r| r.condition).collect()
+    }
+
+    /// Return the largest condition number estimate over all iterations, which, if large,
+    /// indicates the calibrating instruments do not uniquely determine the variables.
+    pub fn max_condition(&self) -> f64 {
+        self.records.iter().map(|r| r.condition).fold(0.0, f64::max)
+    }
+}
+
+/// Estimate the 2-norm condition number of a symmetric positive semi-definite matrix.
+///
+/// The largest and smallest eigenvalues are approximated by power iteration and inverse power
+/// iteration respectively. A singular matrix returns infinity.
+pub(crate) fn condition_estimate(a: &Array2<f64>) -> f64 {
+    const ITERATIONS: usize = 50;
+    let n = a.nrows();
+    if n == 0 {
+        return 1.0;
+    }
+    let normalise = |v: Array1<f64>| -> Option<Array1<f64>> {
+        let norm = v.dot(&v).sqrt();
+        match norm.is_finite() && norm > 0.0 {
+            true => Some(v / norm),
+            false => None,
+        }
+    };
+    let start = Array1::<f64>::from_iter((0..n).map(|i| 1.0 + i as f64 / n as f64));
+
+    let mut v = normalise(start.clone()).unwrap();
+    let mut max_eig = 0.0;
+    for _ in 0..ITERATIONS {
+        let w = a.dot(&v);
+        max_eig = v.dot(&w);
+        match normalise(w) {
+            Some(w) => v = w,
+            None => return f64::INFINITY,
+        }
+    }
+
+    let mut v = normalise(start).unwrap();
+    let mut min_eig = 0.0;
+    for _ in 0..ITERATIONS {
+        let w = dsolve(&a.view(), &v.view(), false);
+        min_eig = 1.0 / v.dot(&w);
+        match normalise(w) {
+            Some(w) => v = w,
+            None => return f64::INFINITY,
+        }
+    }
+    match min_eig.is_finite() && min_eig > 0.0 {
+        true => max_eig / min_eig,
+        false => f64::INFINITY,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::solver::tests::{solver_fixture, system_fixture};
+    use crate::solver::{SolverAlgorithm, StepControl};
+    use ndarray::arr2;
+
+    #[test]
+    fn test_condition_estimate() {
+        let a = arr2(&[[4.0, 0.0], [0.0, 0.5]]);
+        assert!((condition_estimate(&a) - 8.0).abs() < 1e-8);
+        let a = arr2(&[[2.0, 1.0], [1.0, 2.0]]);
+        assert!((condition_estimate(&a) - 3.0).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_condition_estimate_singular() {
+        let a = arr2(&[[1.0, 1.0], [1.0, 1.0]]);
+        assert!(condition_estimate(&a) > 1e12);
+    }
+
+    #[test]
+    fn test_iteration_history() {
+        let mut system = system_fixture(vec![1.0, 1.0, 1.0]);
+        let mut solver = solver_fixture(
+            vec![1.0, 2.0, 3.0],
+            SolverAlgorithm::GaussNewton,
+            StepControl::Full,
+        );
+        let result = solver
+            .iterate(&mut system_fixture(vec![1.0, 1.0, 1.0]))
+            .unwrap();
+        assert_eq!(result.diagnostics.max_condition(), 0.0);
+        solver.set_condition_estimates(true);
+        let result = solver.iterate(&mut system).unwrap();
+        let diagnostics = result.diagnostics;
+        assert_eq!(diagnostics.records.len(), result.iterations + 1);
+        assert_eq!(diagnostics.step_sizes()[0], 0.0);
+        assert!(diagnostics.step_sizes()[1] > 0.0);
+        assert_eq!(*diagnostics.objective().last().unwrap(), result.g);
+        let norms = diagnostics.residual_norms();
+        assert!(norms.windows(2).all(|w| w[1] < w[0]));
+        assert!(diagnostics.max_condition() >= 1.0);
+    }
+}
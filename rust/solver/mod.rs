@@ -0,0 +1,35 @@
+//! Calibrate a system of variables to a set of target instrument rates.
+//!
+//! The [Solver] mirrors the *rateslib (Python)* `Solver` and minimises the weighted sum of
+//! squared differences between instrument rates and their targets, using one of the
+//! algorithms in [SolverAlgorithm]. The size and acceptance of each iterated step is
+//! governed by a [StepControl], which allows pathological initial guesses to converge without
+//! overshooting into financially invalid states, such as negative discount factors.
+//! Individual variables can also be bounded, pinned or log-parametrised with a
+//! [VariableConstraint].
+//! Every [SolverResult] contains [SolverDiagnostics] recording the iteration history
+//! to debug non-convergence.
+//! A [StreamingSolver] recalibrates a system incrementally as its quotes are updated, reusing
+//! the Jacobian of its previous solution.
+//! Scalar equations, such as yields or spreads implied by a price, are solved with [brent].
+
+#[allow(clippy::module_inception)]
+mod solver;
+pub use crate::solver::solver::{
+    Solver, SolverAlgorithm, SolverResult, SolverStatus, SolverSystem,
+};
+
+mod constraints;
+pub use crate::solver::constraints::VariableConstraint;
+
+mod diagnostics;
+pub use crate::solver::diagnostics::{IterationRecord, SolverDiagnostics};
+
+mod step;
+pub use crate::solver::step::StepControl;
+
+mod streaming;
+pub use crate::solver::streaming::StreamingSolver;
+
+mod root;
+pub use crate::solver::root::brent;
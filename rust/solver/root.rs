@@ -0,0 +1,85 @@
+use crate::error::RateslibError;
+
+/// Find a root of a scalar function `f` in the bracket `[a, b]` with Brent's method.
+///
+/// The function values at `a` and `b` must differ in sign. The root is returned when the
+/// bracket is narrower than `tol` or an exact zero is found.
+pub fn brent<F: Fn(f64) -> Result<f64, RateslibError>>(
+    f: F,
+    a: f64,
+    b: f64,
+    tol: f64,
+    max_iter: usize,
+) -> Result<f64, RateslibError> {
+    let (mut a, mut b) = (a, b);
+    let (mut fa, mut fb) = (f(a)?, f(b)?);
+    if fa * fb > 0.0 {
+        return Err(RateslibError::solver(
+            "`brent` requires a bracket whose function values differ in sign.",
+        ));
+    }
+    if fa.abs() < fb.abs() {
+        std::mem::swap(&mut a, &mut b);
+        std::mem::swap(&mut fa, &mut fb);
+    }
+    let (mut c, mut fc) = (a, fa);
+    let mut d = b - a;
+    let mut bisected = true;
+    for _ in 0..max_iter {
+        if fb == 0.0 || (b - a).abs() < tol {
+            return Ok(b);
+        }
+        let mut s = if fa != fc && fb != fc {
+            // inverse quadratic interpolation
+            a * fb * fc / ((fa - fb) * (fa - fc))
+                + b * fa * fc / ((fb - fa) * (fb - fc))
+                + c * fa * fb / ((fc - fa) * (fc - fb))
+        } else {
+            // secant
+            b - fb * (b - a) / (fb - fa)
+        };
+        let bound = (3.0 * a + b) / 4.0;
+        let outside = !((s > bound.min(b)) && (s < bound.max(b)));
+        let slow = match bisected {
+            true => (s - b).abs() >= (b - c).abs() / 2.0 || (b - c).abs() < tol,
+            false => (s - b).abs() >= (c - d).abs() / 2.0 || (c - d).abs() < tol,
+        };
+        bisected = outside || slow;
+        if bisected {
+            s = (a + b) / 2.0;
+        }
+        let fs = f(s)?;
+        d = c;
+        (c, fc) = (b, fb);
+        if fa * fs < 0.0 {
+            (b, fb) = (s, fs);
+        } else {
+            (a, fa) = (s, fs);
+        }
+        if fa.abs() < fb.abs() {
+            std::mem::swap(&mut a, &mut b);
+            std::mem::swap(&mut fa, &mut fb);
+        }
+    }
+    Err(RateslibError::solver(
+        "`brent` did not converge within the maximum number of iterations.",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_brent() {
+        let root = brent(|x| Ok(x * x * x - 2.0 * x - 5.0), 2.0, 3.0, 1e-14, 100).unwrap();
+        assert!((root - 2.0945514815423265).abs() < 1e-12);
+        let root = brent(|x| Ok(x.cos() - x), 0.0, 1.0, 1e-14, 100).unwrap();
+        assert!((root.cos() - root).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_brent_invalid_bracket() {
+        assert!(brent(|x| Ok(x * x + 1.0), -1.0, 1.0, 1e-12, 100).is_err());
+    }
+}
@@ -0,0 +1,614 @@
+use crate::cancel;
+use crate::dual::linalg::dsolve;
+use crate::dual::{Dual, Gradient1};
+use crate::error::RateslibError;
+use crate::progress::{self, ProgressEvent, ProgressStage};
+use crate::solver::constraints::VariableConstraint;
+use crate::solver::diagnostics::{condition_estimate, IterationRecord, SolverDiagnostics};
+use crate::solver::step::StepControl;
+use crate::trace::{event, Level, Span, Stopwatch};
+use ndarray::{Array1, Array2, Axis};
+use std::time::Duration;
+
+/// A system of variables which determines the rates of a set of calibrating instruments.
+pub trait SolverSystem {
+    /// Return the tags of the variables, in the order of [SolverSystem::variables].
+    fn variable_tags(&self) -> Vec<String>;
+
+    /// Return the current values of the variables.
+    fn variables(&self) -> Vec<f64>;
+
+    /// Overwrite the variables of the system with new values.
+    fn set_variables(&mut self, values: &[f64]) -> Result<(), RateslibError>;
+
+    /// Return the rates of the calibrating instruments, with sensitivity to the variable tags.
+    fn rates(&self) -> Result<Vec<Dual>, RateslibError>;
+
+    /// Return whether the current values of the variables yield a financially valid system,
+    /// for example whether all discount factors are positive.
+    fn is_valid(&self) -> bool {
+        true
+    }
+}
+
+/// The optimisation algorithm used to determine each iterated step.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SolverAlgorithm {
+    /// Damped Gauss-Newton where the damping parameter is adapted by the success of each step.
+    LevenbergMarquardt,
+    /// Undamped Gauss-Newton.
+    GaussNewton,
+    /// Steepest descent with an optimal step length under the local quadratic model.
+    GradientDescent,
+}
+
+/// The termination state of a calibration.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SolverStatus {
+    /// The objective function fell below `func_tol`.
+    FuncTol,
+    /// The change in the objective function fell below `conv_tol`.
+    ConvTol,
+    /// The maximum number of iterations was reached.
+    MaxIter,
+    /// The [StepControl] rejected every trial step, so the variables are unchanged.
+    NoProgress,
+}
+
+impl SolverStatus {
+    /// Return the integer state code used by *rateslib (Python)*.
+    pub fn state(&self) -> i8 {
+        match self {
+            SolverStatus::FuncTol => 2,
+            SolverStatus::ConvTol => 1,
+            SolverStatus::MaxIter => -1,
+            SolverStatus::NoProgress => -2,
+        }
+    }
+
+    /// Return whether the calibration was successful.
+    pub fn is_success(&self) -> bool {
+        self.state() > 0
+    }
+}
+
+/// The outcome of a calibration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolverResult {
+    pub status: SolverStatus,
+    /// The final value of the objective function.
+    pub g: f64,
+    pub iterations: usize,
+    pub time: Duration,
+    pub diagnostics: SolverDiagnostics,
+}
+
+/// The state of a system evaluated at its current variables.
+#[derive(Debug, Clone)]
+pub(crate) struct Evaluation {
+    pub(crate) x: Array1<f64>,
+    /// Instrument rates less their targets.
+    pub(crate) residuals: Array1<f64>,
+    /// Jacobian of shape (variables, instruments).
+    pub(crate) jac: Array2<f64>,
+    pub(crate) g: f64,
+}
+
+/// Least squares calibration of a [SolverSystem] to target rates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Solver {
+    pub(crate) s: Array1<f64>,
+    pub(crate) weights: Array1<f64>,
+    pub(crate) algorithm: SolverAlgorithm,
+    pub(crate) max_iter: usize,
+    pub(crate) func_tol: f64,
+    pub(crate) conv_tol: f64,
+    pub(crate) ini_lambda: (f64, f64, f64),
+    pub(crate) step_control: StepControl,
+    pub(crate) constraints: Option<Vec<VariableConstraint>>,
+    pub(crate) condition_estimates: bool,
+}
+
+impl Solver {
+    /// Create a new [Solver].
+    ///
+    /// - `s` are the target rates of the calibrating instruments.
+    /// - `weights` scale the contribution of each instrument and default to one.
+    /// - `ini_lambda` is the initial Levenberg-Marquardt damping parameter and its scaling
+    ///   factors after a successful and a failed iteration, respectively.
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_new(
+        s: Vec<f64>,
+        weights: Option<Vec<f64>>,
+        algorithm: SolverAlgorithm,
+        max_iter: usize,
+        func_tol: f64,
+        conv_tol: f64,
+        ini_lambda: (f64, f64, f64),
+        step_control: StepControl,
+    ) -> Result<Self, RateslibError> {
+        let weights = weights.unwrap_or(vec![1.0; s.len()]);
+        if weights.len() != s.len() {
+            return Err(RateslibError::solver(
+                "`weights` must have the same length as the target rates, `s`.",
+            ));
+        }
+        step_control.validate()?;
+        Ok(Self {
+            s: Array1::from_vec(s),
+            weights: Array1::from_vec(weights),
+            algorithm,
+            max_iter,
+            func_tol,
+            conv_tol,
+            ini_lambda,
+            step_control,
+            constraints: None,
+            condition_estimates: false,
+        })
+    }
+
+    /// Return a [Solver] with the default parameters of *rateslib (Python)*.
+    pub fn try_new_default(s: Vec<f64>) -> Result<Self, RateslibError> {
+        Solver::try_new(
+            s,
+            None,
+            SolverAlgorithm::LevenbergMarquardt,
+            100,
+            1e-11,
+            1e-14,
+            (1000.0, 0.25, 2.0),
+            StepControl::Full,
+        )
+    }
+
+    /// Set a [VariableConstraint] for each variable of the system, in order.
+    pub fn set_constraints(
+        &mut self,
+        constraints: Vec<VariableConstraint>,
+    ) -> Result<(), RateslibError> {
+        for constraint in constraints.iter() {
+            constraint.validate()?;
+        }
+        self.constraints = Some(constraints);
+        Ok(())
+    }
+
+    /// Set whether the condition number of the normal matrix of each step is estimated and
+    /// recorded in the [SolverDiagnostics], at the cost of solving the normal equations many
+    /// times per iteration. By default it is not, and is recorded as zero.
+    pub fn set_condition_estimates(&mut self, estimate: bool) {
+        self.condition_estimates = estimate;
+    }
+
+    /// Iterate the variables of the system until a termination criterion is met.
+    pub fn iterate<S: SolverSystem>(&self, system: &mut S) -> Result<SolverResult, RateslibError> {
+        let _span = Span::enter(Level::Info, "solver", || {
+            format!("iterate{{variables: {}}}", system.variables().len())
+        });
+        let start = Stopwatch::start();
+        self.project(system)?;
+        let mut current = self.evaluate(system)?;
+        let mut diagnostics = SolverDiagnostics::default();
+        diagnostics.push(self.record(&current, 0.0, 0.0, 0.0, start));
+        let mut lambda = self.ini_lambda.0;
+        let mut radius = self.step_control.initial_radius();
+        for i in 0..self.max_iter {
+            cancel::check()?;
+            if current.g < self.func_tol {
+                return Ok(self.result(SolverStatus::FuncTol, i, start, current, diagnostics));
+            }
+            let (a, b) = self.normal_equations(&current);
+            let delta = self.delta(&a, &b, lambda)?;
+            let Some(next) = self
+                .step_control
+                .step(self, system, &current, &delta, &mut radius)?
+            else {
+                return Ok(self.result(
+                    SolverStatus::NoProgress,
+                    i + 1,
+                    start,
+                    current,
+                    diagnostics,
+                ));
+            };
+            let step = &next.x - &current.x;
+            let condition = match self.condition_estimates {
+                true => condition_estimate(&a),
+                false => 0.0,
+            };
+            diagnostics.push(self.record(&next, step.dot(&step).sqrt(), condition, lambda, start));
+            if next.g < current.g {
+                lambda *= self.ini_lambda.1;
+            } else {
+                lambda *= self.ini_lambda.2;
+            }
+            event(Level::Debug, "solver", || {
+                format!(
+                    "iteration {}: g = {:e}, step = {:e}, lambda = {:e}",
+                    i + 1,
+                    next.g,
+                    step.dot(&step).sqrt(),
+                    lambda
+                )
+            });
+            progress::report(
+                ProgressEvent::new(ProgressStage::Calibration, i + 1, self.max_iter)
+                    .with_objective(next.g),
+            )?;
+            let g_prev = current.g;
+            current = next;
+            if (g_prev - current.g).abs() < self.conv_tol {
+                return Ok(self.result(SolverStatus::ConvTol, i + 1, start, current, diagnostics));
+            }
+        }
+        Ok(self.result(
+            SolverStatus::MaxIter,
+            self.max_iter,
+            start,
+            current,
+            diagnostics,
+        ))
+    }
+
+    fn result(
+        &self,
+        status: SolverStatus,
+        iterations: usize,
+        start: Stopwatch,
+        eval: Evaluation,
+        diagnostics: SolverDiagnostics,
+    ) -> SolverResult {
+        event(Level::Info, "solver", || {
+            format!(
+                "{:?} after {} iterations with g = {:e}",
+                status, iterations, eval.g
+            )
+        });
+        SolverResult {
+            status,
+            g: eval.g,
+            iterations,
+            time: start.elapsed(),
+            diagnostics,
+        }
+    }
+
+    fn record(
+        &self,
+        eval: &Evaluation,
+        step_size: f64,
+        condition: f64,
+        lambda: f64,
+        start: Stopwatch,
+    ) -> IterationRecord {
+        IterationRecord {
+            g: eval.g,
+            residual_norm: eval.residuals.dot(&eval.residuals).sqrt(),
+            max_residual: eval.residuals.iter().fold(0.0, |m, r| r.abs().max(m)),
+            step_size,
+            condition,
+            lambda,
+            elapsed: start.elapsed(),
+        }
+    }
+
+    /// Project the initial variables of the system onto the feasible set of its constraints.
+    fn project<S: SolverSystem>(&self, system: &mut S) -> Result<(), RateslibError> {
+        if let Some(constraints) = &self.constraints {
+            let x = system.variables();
+            if constraints.len() != x.len() {
+                return Err(RateslibError::solver(
+                    "The system must have as many variables as there are `constraints`.",
+                ));
+            }
+            let x: Vec<f64> = x
+                .iter()
+                .zip(constraints.iter())
+                .map(|(v, c)| c.project(*v))
+                .collect::<Result<Vec<f64>, RateslibError>>()?;
+            system.set_variables(&x)?;
+        }
+        Ok(())
+    }
+
+    /// Return the variables after a step, `delta`, in the iterated parameters.
+    pub(crate) fn apply_step(&self, x: &Array1<f64>, delta: &Array1<f64>) -> Array1<f64> {
+        match &self.constraints {
+            None => x + delta,
+            Some(constraints) => Array1::from_iter(
+                x.iter()
+                    .zip(delta.iter())
+                    .zip(constraints.iter())
+                    .map(|((v, d), c)| c.apply(*v, *d)),
+            ),
+        }
+    }
+
+    /// Evaluate the residuals, Jacobian and objective function of the system.
+    ///
+    /// The Jacobian is expressed with respect to the iterated parameters of any constraints.
+    pub(crate) fn evaluate<S: SolverSystem>(
+        &self,
+        system: &S,
+    ) -> Result<Evaluation, RateslibError> {
+        let tags = system.variable_tags();
+        let rates = system.rates()?;
+        if rates.len() != self.s.len() {
+            return Err(RateslibError::solver(
+                "The system must return as many rates as there are targets, `s`.",
+            ));
+        }
+        let mut jac = Array2::<f64>::zeros((tags.len(), rates.len()));
+        for (mut col, rate) in jac.axis_iter_mut(Axis(1)).zip(rates.iter()) {
+            col.assign(&rate.gradient1(tags.clone()));
+        }
+        let x = Array1::from_vec(system.variables());
+        if let Some(constraints) = &self.constraints {
+            for ((mut row, c), v) in jac.axis_iter_mut(Axis(0)).zip(constraints).zip(x.iter()) {
+                row *= c.scale(*v);
+            }
+        }
+        let residuals = Array1::from_iter(rates.iter().map(|r| r.real())) - &self.s;
+        let g = self.objective(&residuals);
+        Ok(Evaluation {
+            x,
+            residuals,
+            jac,
+            g,
+        })
+    }
+
+    /// Weighted sum of squared residuals.
+    pub(crate) fn objective(&self, residuals: &Array1<f64>) -> f64 {
+        (residuals * residuals * &self.weights).sum()
+    }
+
+    /// Gradient of the objective function with respect to the variables.
+    pub(crate) fn gradient(&self, eval: &Evaluation) -> Array1<f64> {
+        2.0 * eval.jac.dot(&(&eval.residuals * &self.weights))
+    }
+
+    /// Objective function predicted by the linearised system after a step, `delta`.
+    pub(crate) fn predicted_objective(&self, eval: &Evaluation, delta: &Array1<f64>) -> f64 {
+        self.objective(&(&eval.residuals + &eval.jac.t().dot(delta)))
+    }
+
+    /// Return the normal matrix, `J W J^T`, and the vector, `-J W r`, of the linearised system.
+    pub(crate) fn normal_equations(&self, eval: &Evaluation) -> (Array2<f64>, Array1<f64>) {
+        let jw = &eval.jac * &self.weights;
+        let mut a = jw.dot(&eval.jac.t());
+        if let Some(constraints) = &self.constraints {
+            // pinned variables have no sensitivity and are given a null step
+            for (i, _) in constraints
+                .iter()
+                .enumerate()
+                .filter(|(_, c)| c.is_pinned())
+            {
+                a[[i, i]] = 1.0;
+            }
+        }
+        let b = -jw.dot(&eval.residuals);
+        (a, b)
+    }
+
+    /// Determine the unconstrained step proposed by the algorithm.
+    fn delta(
+        &self,
+        a: &Array2<f64>,
+        b: &Array1<f64>,
+        lambda: f64,
+    ) -> Result<Array1<f64>, RateslibError> {
+        let delta = match self.algorithm {
+            SolverAlgorithm::GaussNewton => dsolve(&a.view(), &b.view(), false),
+            SolverAlgorithm::LevenbergMarquardt => {
+                let a_ = a + lambda * Array2::<f64>::eye(a.nrows());
+                dsolve(&a_.view(), &b.view(), false)
+            }
+            SolverAlgorithm::GradientDescent => {
+                let curvature = b.dot(&a.dot(b));
+                if curvature == 0.0 {
+                    Array1::zeros(b.len())
+                } else {
+                    (b.dot(b) / curvature) * b
+                }
+            }
+        };
+        if delta.iter().any(|v| !v.is_finite()) {
+            return Err(RateslibError::solver(
+                "Solver step is not finite. The Jacobian of the system may be singular.",
+            ));
+        }
+        Ok(delta)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::cancel::CancellationToken;
+    use crate::dual::get_variable_tags;
+
+    /// Discount factors calibrated to simple interest rates, in percent, at each node.
+    pub(crate) struct SimpleRateSystem {
+        pub(crate) dfs: Vec<f64>,
+        pub(crate) times: Vec<f64>,
+    }
+
+    impl SolverSystem for SimpleRateSystem {
+        fn variable_tags(&self) -> Vec<String> {
+            get_variable_tags("v", self.dfs.len())
+        }
+
+        fn variables(&self) -> Vec<f64> {
+            self.dfs.clone()
+        }
+
+        fn set_variables(&mut self, values: &[f64]) -> Result<(), RateslibError> {
+            self.dfs = values.to_vec();
+            Ok(())
+        }
+
+        fn rates(&self) -> Result<Vec<Dual>, RateslibError> {
+            let tags = self.variable_tags();
+            Ok(self
+                .dfs
+                .iter()
+                .zip(self.times.iter())
+                .zip(tags)
+                .map(|((df, t), tag)| (1.0 / Dual::new(*df, vec![tag]) - 1.0) / *t * 100.0)
+                .collect())
+        }
+
+        fn is_valid(&self) -> bool {
+            self.dfs.iter().all(|df| *df > 0.0)
+        }
+    }
+
+    pub(crate) fn system_fixture(dfs: Vec<f64>) -> SimpleRateSystem {
+        SimpleRateSystem {
+            times: vec![1.0, 2.0, 3.0][..dfs.len()].to_vec(),
+            dfs,
+        }
+    }
+
+    pub(crate) fn solver_fixture(
+        s: Vec<f64>,
+        algorithm: SolverAlgorithm,
+        step_control: StepControl,
+    ) -> Solver {
+        Solver::try_new(
+            s,
+            None,
+            algorithm,
+            100,
+            1e-11,
+            1e-14,
+            (1000.0, 0.25, 2.0),
+            step_control,
+        )
+        .unwrap()
+    }
+
+    fn is_close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-6
+    }
+
+    #[test]
+    fn test_weights_length() {
+        let result = Solver::try_new(
+            vec![1.0, 2.0],
+            Some(vec![1.0]),
+            SolverAlgorithm::GaussNewton,
+            100,
+            1e-11,
+            1e-14,
+            (1000.0, 0.25, 2.0),
+            StepControl::Full,
+        );
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn test_rates_length() {
+        let mut system = system_fixture(vec![0.99, 0.98]);
+        let solver = solver_fixture(vec![1.0], SolverAlgorithm::GaussNewton, StepControl::Full);
+        assert!(solver.iterate(&mut system).is_err())
+    }
+
+    #[test]
+    fn test_evaluate_jacobian() {
+        let system = system_fixture(vec![0.5, 0.8]);
+        let solver = solver_fixture(
+            vec![0.0, 0.0],
+            SolverAlgorithm::GaussNewton,
+            StepControl::Full,
+        );
+        let eval = solver.evaluate(&system).unwrap();
+        // dr/dv = -100 / (t v^2)
+        assert!(is_close(eval.jac[[0, 0]], -400.0));
+        assert!(is_close(eval.jac[[1, 1]], -100.0 / (2.0 * 0.64)));
+        assert_eq!(eval.jac[[0, 1]], 0.0);
+        assert!(is_close(eval.g, 100.0_f64.powi(2) + 12.5_f64.powi(2)));
+    }
+
+    #[test]
+    fn test_algorithms_converge() {
+        for algorithm in [
+            SolverAlgorithm::LevenbergMarquardt,
+            SolverAlgorithm::GaussNewton,
+            SolverAlgorithm::GradientDescent,
+        ] {
+            let mut system = system_fixture(vec![1.0, 1.0, 1.0]);
+            let solver = solver_fixture(vec![1.0, 2.0, 3.0], algorithm, StepControl::Full);
+            let result = solver.iterate(&mut system).unwrap();
+            assert!(result.status.is_success());
+            assert!(is_close(system.dfs[0], 1.0 / 1.01));
+            assert!(is_close(system.dfs[1], 1.0 / 1.04));
+            assert!(is_close(system.dfs[2], 1.0 / 1.09));
+        }
+    }
+
+    #[test]
+    fn test_max_iter() {
+        let mut system = system_fixture(vec![1.0, 1.0, 1.0]);
+        let solver = Solver::try_new(
+            vec![1.0, 2.0, 3.0],
+            None,
+            SolverAlgorithm::GradientDescent,
+            2,
+            1e-11,
+            1e-14,
+            (1000.0, 0.25, 2.0),
+            StepControl::Full,
+        )
+        .unwrap();
+        let result = solver.iterate(&mut system).unwrap();
+        assert_eq!(result.status, SolverStatus::MaxIter);
+        assert_eq!(result.status.state(), -1);
+        assert_eq!(result.iterations, 2);
+    }
+
+    #[test]
+    fn test_cancel_calibration() {
+        let token = CancellationToken::new();
+        token.cancel();
+        let mut system = system_fixture(vec![1.0, 1.0, 1.0]);
+        let solver = solver_fixture(
+            vec![1.0, 2.0, 3.0],
+            SolverAlgorithm::GaussNewton,
+            StepControl::Full,
+        );
+        let result = token.run(|| solver.iterate(&mut system));
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "The computation was cancelled."
+        );
+        assert!(solver.iterate(&mut system).unwrap().status.is_success());
+    }
+
+    #[test]
+    fn test_progress_events() {
+        let events = std::rc::Rc::new(std::cell::RefCell::new(vec![]));
+        let seen = events.clone();
+        let mut system = system_fixture(vec![1.0, 1.0, 1.0]);
+        let solver = solver_fixture(
+            vec![1.0, 2.0, 3.0],
+            SolverAlgorithm::GaussNewton,
+            StepControl::Full,
+        );
+        let result = progress::with_progress(
+            move |e| {
+                seen.borrow_mut().push(e.clone());
+                Ok(())
+            },
+            || solver.iterate(&mut system),
+        )
+        .unwrap();
+        let events = events.borrow();
+        assert_eq!(events.len(), result.iterations);
+        assert_eq!(events[0].stage, ProgressStage::Calibration);
+        assert_eq!((events[0].completed, events[0].total), (1, 100));
+        assert_eq!(events.last().unwrap().objective, Some(result.g));
+    }
+}
@@ -0,0 +1,250 @@
+use crate::error::RateslibError;
+use crate::solver::solver::{Evaluation, Solver, SolverSystem};
+use ndarray::Array1;
+
+/// Control of the size and acceptance of each step proposed by a
+/// [SolverAlgorithm](crate::solver::SolverAlgorithm).
+///
+/// Other than [StepControl::Full] every control rejects steps which leave the system in
+/// an invalid state, as determined by [SolverSystem::is_valid], and retries with a
+/// shorter step.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum StepControl {
+    /// Take every proposed step in full.
+    Full,
+    /// Scale every proposed step by a `factor` in (0, 1].
+    Damping { factor: f64, max_backtracks: usize },
+    /// Limit the Euclidean norm of every step to a `radius`, which is expanded up to
+    /// `max_radius` or contracted depending upon the ratio of actual to predicted reduction
+    /// of the objective function.
+    TrustRegion {
+        radius: f64,
+        max_radius: f64,
+        max_backtracks: usize,
+    },
+    /// Backtracking line search, scaling the step by `shrink` until the Armijo sufficient
+    /// decrease condition, with parameter `c`, is satisfied.
+    Armijo {
+        c: f64,
+        shrink: f64,
+        max_backtracks: usize,
+    },
+}
+
+impl StepControl {
+    pub(crate) fn validate(&self) -> Result<(), RateslibError> {
+        let valid = match self {
+            StepControl::Full => true,
+            StepControl::Damping { factor, .. } => *factor > 0.0 && *factor <= 1.0,
+            StepControl::TrustRegion {
+                radius, max_radius, ..
+            } => *radius > 0.0 && *max_radius >= *radius,
+            StepControl::Armijo { c, shrink, .. } => {
+                *c > 0.0 && *c < 1.0 && *shrink > 0.0 && *shrink < 1.0
+            }
+        };
+        match valid {
+            true => Ok(()),
+            false => Err(RateslibError::solver(
+                "`StepControl` parameters are outside of their valid ranges.",
+            )),
+        }
+    }
+
+    pub(crate) fn initial_radius(&self) -> f64 {
+        match self {
+            StepControl::TrustRegion { radius, .. } => *radius,
+            _ => f64::INFINITY,
+        }
+    }
+
+    /// Apply a proposed step, `delta`, to the system and return its evaluated state.
+    ///
+    /// If no acceptable step is found the system is restored to its `current` state and `None`
+    /// is returned.
+    pub(crate) fn step<S: SolverSystem>(
+        &self,
+        solver: &Solver,
+        system: &mut S,
+        current: &Evaluation,
+        delta: &Array1<f64>,
+        radius: &mut f64,
+    ) -> Result<Option<Evaluation>, RateslibError> {
+        match self {
+            StepControl::Full => trial(solver, system, current, delta, 1.0).map(Some),
+            StepControl::Damping {
+                factor,
+                max_backtracks,
+            } => {
+                let mut alpha = *factor;
+                for _ in 0..=*max_backtracks {
+                    let next = trial(solver, system, current, delta, alpha)?;
+                    if acceptable(system, &next) {
+                        return Ok(Some(next));
+                    }
+                    alpha *= 0.5;
+                }
+                restore(system, current)
+            }
+            StepControl::TrustRegion {
+                max_radius,
+                max_backtracks,
+                ..
+            } => {
+                let norm = delta.dot(delta).sqrt();
+                for _ in 0..=*max_backtracks {
+                    let alpha = (*radius / norm).min(1.0);
+                    let on_boundary = alpha < 1.0;
+                    let next = trial(solver, system, current, delta, alpha)?;
+                    let predicted =
+                        current.g - solver.predicted_objective(current, &(alpha * delta));
+                    let rho = (current.g - next.g) / predicted;
+                    if acceptable(system, &next) && rho > 0.0 {
+                        if rho < 0.25 {
+                            *radius *= 0.25;
+                        } else if rho > 0.75 && on_boundary {
+                            *radius = (*radius * 2.0).min(*max_radius);
+                        }
+                        return Ok(Some(next));
+                    }
+                    *radius = 0.25 * radius.min(alpha * norm);
+                }
+                restore(system, current)
+            }
+            StepControl::Armijo {
+                c,
+                shrink,
+                max_backtracks,
+            } => {
+                let slope = solver.gradient(current).dot(delta);
+                let mut alpha = 1.0;
+                for _ in 0..=*max_backtracks {
+                    let next = trial(solver, system, current, delta, alpha)?;
+                    if acceptable(system, &next) && next.g <= current.g + c * alpha * slope {
+                        return Ok(Some(next));
+                    }
+                    alpha *= shrink;
+                }
+                restore(system, current)
+            }
+        }
+    }
+}
+
+/// Set the variables of the system to `current.x + alpha * delta` and evaluate it.
+fn trial<S: SolverSystem>(
+    solver: &Solver,
+    system: &mut S,
+    current: &Evaluation,
+    delta: &Array1<f64>,
+    alpha: f64,
+) -> Result<Evaluation, RateslibError> {
+    let x = solver.apply_step(&current.x, &(alpha * delta));
+    system.set_variables(x.as_slice().unwrap())?;
+    solver.evaluate(system)
+}
+
+fn acceptable<S: SolverSystem>(system: &S, eval: &Evaluation) -> bool {
+    eval.g.is_finite() && system.is_valid()
+}
+
+fn restore<S: SolverSystem>(
+    system: &mut S,
+    current: &Evaluation,
+) -> Result<Option<Evaluation>, RateslibError> {
+    system.set_variables(current.x.as_slice().unwrap())?;
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::solver::tests::{solver_fixture, system_fixture};
+    use crate::solver::{SolverAlgorithm, SolverStatus};
+
+    // A 1000% simple rate from an initial discount factor of one proposes a first
+    // Gauss-Newton step to a discount factor of -9.0, from which the system cannot recover.
+    const S: [f64; 1] = [1000.0];
+
+    #[test]
+    fn test_full_step_overshoots() {
+        let mut system = system_fixture(vec![1.0]);
+        let solver = solver_fixture(S.to_vec(), SolverAlgorithm::GaussNewton, StepControl::Full);
+        let _ = solver.iterate(&mut system);
+        assert!(system.dfs[0] < 0.0);
+    }
+
+    #[test]
+    fn test_controls_converge() {
+        for control in [
+            StepControl::Damping {
+                factor: 0.5,
+                max_backtracks: 20,
+            },
+            StepControl::TrustRegion {
+                radius: 0.1,
+                max_radius: 1.0,
+                max_backtracks: 20,
+            },
+            StepControl::Armijo {
+                c: 1e-4,
+                shrink: 0.5,
+                max_backtracks: 20,
+            },
+        ] {
+            let mut system = system_fixture(vec![1.0]);
+            let solver = solver_fixture(S.to_vec(), SolverAlgorithm::GaussNewton, control);
+            let result = solver.iterate(&mut system).unwrap();
+            assert!(result.status.is_success());
+            assert!((system.dfs[0] - 1.0 / 11.0).abs() < 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_controls_remain_valid() {
+        // inverted initial guess with multiple nodes
+        let control = StepControl::Armijo {
+            c: 1e-4,
+            shrink: 0.5,
+            max_backtracks: 20,
+        };
+        let mut system = system_fixture(vec![0.01, 0.5, 0.999]);
+        let solver = solver_fixture(
+            vec![5.0, 50.0, 500.0],
+            SolverAlgorithm::LevenbergMarquardt,
+            control,
+        );
+        let result = solver.iterate(&mut system).unwrap();
+        assert!(result.status.is_success());
+        assert!((system.dfs[2] - 1.0 / 16.0).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_no_acceptable_step_fails() {
+        let control = StepControl::Damping {
+            factor: 1.0,
+            max_backtracks: 0,
+        };
+        let mut system = system_fixture(vec![1.0]);
+        let solver = solver_fixture(S.to_vec(), SolverAlgorithm::GaussNewton, control);
+        let result = solver.iterate(&mut system).unwrap();
+        assert_eq!(result.status, SolverStatus::NoProgress);
+        assert!(!result.status.is_success());
+        assert_eq!(system.dfs, vec![1.0]);
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        let control = StepControl::Armijo {
+            c: 1.5,
+            shrink: 0.5,
+            max_backtracks: 20,
+        };
+        assert!(control.validate().is_err());
+        let control = StepControl::Damping {
+            factor: 0.0,
+            max_backtracks: 20,
+        };
+        assert!(control.validate().is_err());
+    }
+}
@@ -0,0 +1,266 @@
+use crate::dual::linalg::dsolve;
+use crate::error::RateslibError;
+use crate::solver::diagnostics::{IterationRecord, SolverDiagnostics};
+use crate::solver::solver::{Solver, SolverResult, SolverStatus, SolverSystem};
+use crate::trace::{event, Level, Stopwatch};
+use ndarray::{Array1, Array2, Axis};
+
+/// The factor by which each iteration reusing a Jacobian must reduce the objective function,
+/// before the update falls back to a full calibration.
+const CHORD_REDUCTION: f64 = 0.25;
+
+/// A calibrated [SolverSystem] which is recalibrated incrementally as its instrument quotes
+/// are updated, for example by a live market data feed.
+///
+/// An update re-solves from the previous solution with chord iterations, which reuse the
+/// Jacobian of the last full calibration and so avoid its recalculation and factorisation.
+/// If these fail to converge quickly the update falls back to a full calibration by the
+/// [Solver], warm started from the previous solution, which refreshes the Jacobian.
+pub struct StreamingSolver<S: SolverSystem> {
+    pub(crate) solver: Solver,
+    pub(crate) system: S,
+    /// The Gauss-Newton step per unit residual, of shape (variables, instruments).
+    pub(crate) gain: Array2<f64>,
+    pub(crate) jacobian_age: usize,
+    pub(crate) max_chord_iter: usize,
+    pub(crate) result: SolverResult,
+}
+
+impl<S: SolverSystem> StreamingSolver<S> {
+    /// Calibrate the `system` with the `solver` and create a [StreamingSolver] for its
+    /// subsequent updates, or raise if the calibration is not successful.
+    pub fn try_new(solver: Solver, mut system: S) -> Result<Self, RateslibError> {
+        let result = solver.iterate(&mut system)?;
+        if !result.status.is_success() {
+            return Err(failed(&result));
+        }
+        let mut streaming = Self {
+            solver,
+            system,
+            gain: Array2::zeros((0, 0)),
+            jacobian_age: 0,
+            max_chord_iter: 5,
+            result,
+        };
+        streaming.refresh_jacobian()?;
+        Ok(streaming)
+    }
+
+    /// Return the solver with at most `max_chord_iter` iterations reusing the Jacobian per
+    /// update, before falling back to a full calibration.
+    pub fn with_max_chord_iter(mut self, max_chord_iter: usize) -> Self {
+        self.max_chord_iter = max_chord_iter;
+        self
+    }
+
+    pub fn system(&self) -> &S {
+        &self.system
+    }
+
+    /// Return the current target rates of the instruments.
+    pub fn targets(&self) -> &[f64] {
+        self.solver.s.as_slice().unwrap()
+    }
+
+    /// Return the result of the most recent calibration or update.
+    pub fn result(&self) -> &SolverResult {
+        &self.result
+    }
+
+    /// Return the number of updates since the Jacobian was last evaluated.
+    pub fn jacobian_age(&self) -> usize {
+        self.jacobian_age
+    }
+
+    /// Evaluate the Jacobian of the system at its current variables for reuse by later
+    /// updates.
+    pub fn refresh_jacobian(&mut self) -> Result<(), RateslibError> {
+        let eval = self.solver.evaluate(&self.system)?;
+        let (a, _) = self.solver.normal_equations(&eval);
+        let jw = &eval.jac * &self.solver.weights;
+        let mut gain = Array2::<f64>::zeros(jw.dim());
+        for (mut col, jw_col) in gain.axis_iter_mut(Axis(1)).zip(jw.axis_iter(Axis(1))) {
+            col.assign(&dsolve(&a.view(), &jw_col, false));
+        }
+        if gain.iter().any(|v| !v.is_finite()) {
+            return Err(RateslibError::solver(
+                "The Jacobian of the system is singular and cannot be reused for updates.",
+            ));
+        }
+        self.gain = gain;
+        self.jacobian_age = 0;
+        Ok(())
+    }
+
+    /// Update the target rates of the instruments at the given indices and recalibrate the
+    /// system from its previous solution.
+    ///
+    /// If the fallback full calibration is not successful the targets, variables and Jacobian
+    /// of the previous calibration are restored and an error is raised.
+    pub fn update_quotes(
+        &mut self,
+        quotes: &[(usize, f64)],
+    ) -> Result<&SolverResult, RateslibError> {
+        let n = self.solver.s.len();
+        if let Some((i, _)) = quotes.iter().find(|(i, _)| *i >= n) {
+            return Err(RateslibError::solver(format!(
+                "Quote index {} is out of range for a system of {} instruments.",
+                i, n
+            )));
+        }
+        let targets = self.solver.s.clone();
+        for (i, quote) in quotes.iter() {
+            self.solver.s[*i] = *quote;
+        }
+        let start = Stopwatch::start();
+        let previous = self.system.variables();
+        if let Some(result) = self.chord(start)? {
+            self.jacobian_age += 1;
+            self.result = result;
+            return Ok(&self.result);
+        }
+        event(Level::Debug, "solver", || {
+            "chord iterations did not converge, recalibrating".to_string()
+        });
+        self.system.set_variables(&previous)?;
+        let result = self.solver.iterate(&mut self.system)?;
+        if !result.status.is_success() {
+            self.solver.s = targets;
+            self.system.set_variables(&previous)?;
+            return Err(failed(&result));
+        }
+        self.result = result;
+        self.refresh_jacobian()?;
+        Ok(&self.result)
+    }
+
+    /// Iterate the system with the reused Jacobian, returning `None` if it does not converge.
+    fn chord(&mut self, start: Stopwatch) -> Result<Option<SolverResult>, RateslibError> {
+        let mut x = Array1::from_vec(self.system.variables());
+        let mut residuals = self.residuals()?;
+        let mut g = self.solver.objective(&residuals);
+        let mut diagnostics = SolverDiagnostics::default();
+        diagnostics.push(record(g, &residuals, 0.0, start));
+        for i in 0..=self.max_chord_iter {
+            if g < self.solver.func_tol {
+                return Ok(Some(SolverResult {
+                    status: SolverStatus::FuncTol,
+                    g,
+                    iterations: i,
+                    time: start.elapsed(),
+                    diagnostics,
+                }));
+            }
+            if i == self.max_chord_iter {
+                break;
+            }
+            let delta = -self.gain.dot(&residuals);
+            x = self.solver.apply_step(&x, &delta);
+            self.system.set_variables(x.as_slice().unwrap())?;
+            if !self.system.is_valid() {
+                break;
+            }
+            residuals = self.residuals()?;
+            let g_next = self.solver.objective(&residuals);
+            diagnostics.push(record(g_next, &residuals, delta.dot(&delta).sqrt(), start));
+            if !g_next.is_finite() || g_next >= CHORD_REDUCTION * g {
+                break;
+            }
+            g = g_next;
+        }
+        Ok(None)
+    }
+
+    /// Return the instrument rates less their targets, without their sensitivities.
+    fn residuals(&self) -> Result<Array1<f64>, RateslibError> {
+        let rates = self.system.rates()?;
+        Ok(Array1::from_iter(rates.iter().map(|r| r.real())) - &self.solver.s)
+    }
+}
+
+/// Return the error of an unsuccessful calibration.
+fn failed(result: &SolverResult) -> RateslibError {
+    RateslibError::solver(format!(
+        "The calibration of a `StreamingSolver` failed with status {:?} after {} iterations.",
+        result.status, result.iterations
+    ))
+}
+
+fn record(g: f64, residuals: &Array1<f64>, step_size: f64, start: Stopwatch) -> IterationRecord {
+    IterationRecord {
+        g,
+        residual_norm: residuals.dot(residuals).sqrt(),
+        max_residual: residuals.iter().fold(0.0, |m, r| r.abs().max(m)),
+        step_size,
+        condition: 0.0,
+        lambda: 0.0,
+        elapsed: start.elapsed(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::solver::tests::{solver_fixture, system_fixture, SimpleRateSystem};
+    use crate::solver::{SolverAlgorithm, StepControl};
+
+    fn streaming_fixture() -> StreamingSolver<SimpleRateSystem> {
+        let solver = solver_fixture(
+            vec![1.0, 2.0, 3.0],
+            SolverAlgorithm::LevenbergMarquardt,
+            StepControl::Full,
+        );
+        StreamingSolver::try_new(solver, system_fixture(vec![1.0, 1.0, 1.0])).unwrap()
+    }
+
+    #[test]
+    fn test_update_reuses_jacobian() {
+        let mut streaming = streaming_fixture();
+        assert!(streaming.result().status.is_success());
+        let full_iterations = streaming.result().iterations;
+        let result = streaming.update_quotes(&[(1, 2.01)]).unwrap();
+        assert_eq!(result.status, SolverStatus::FuncTol);
+        assert!(result.iterations < full_iterations);
+        assert_eq!(streaming.jacobian_age(), 1);
+        assert_eq!(streaming.targets(), &[1.0, 2.01, 3.0]);
+        let dfs = &streaming.system().dfs;
+        assert!((dfs[1] - 1.0 / 1.0402).abs() < 1e-7);
+        assert!((dfs[0] - 1.0 / 1.01).abs() < 1e-7);
+        streaming.update_quotes(&[(0, 1.02), (2, 2.99)]).unwrap();
+        assert_eq!(streaming.jacobian_age(), 2);
+        assert!((streaming.system().dfs[2] - 1.0 / 1.0897).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_large_update_recalibrates() {
+        let mut streaming = streaming_fixture().with_max_chord_iter(2);
+        streaming.update_quotes(&[(0, 1.01)]).unwrap();
+        assert_eq!(streaming.jacobian_age(), 1);
+        // a jump from 2% to 50% is too far from the reused Jacobian to converge
+        let result = streaming.update_quotes(&[(1, 50.0)]).unwrap();
+        assert!(result.status.is_success());
+        assert_eq!(streaming.jacobian_age(), 0);
+        assert!((streaming.system().dfs[1] - 0.5).abs() < 1e-7);
+        assert!(streaming.update_quotes(&[(3, 1.0)]).is_err());
+    }
+
+    #[test]
+    fn test_failed_calibration_errors() {
+        let mut solver = solver_fixture(
+            vec![1.0, 2.0, 3.0],
+            SolverAlgorithm::LevenbergMarquardt,
+            StepControl::Full,
+        );
+        solver.max_iter = 1;
+        assert!(StreamingSolver::try_new(solver, system_fixture(vec![1.0, 1.0, 1.0])).is_err());
+
+        let mut streaming = streaming_fixture().with_max_chord_iter(0);
+        let (gain, dfs) = (streaming.gain.clone(), streaming.system().dfs.clone());
+        streaming.solver.max_iter = 1;
+        assert!(streaming.update_quotes(&[(1, 50.0)]).is_err());
+        assert_eq!(streaming.targets(), &[1.0, 2.0, 3.0]);
+        assert_eq!(streaming.gain, gain);
+        assert_eq!(streaming.system().dfs, dfs);
+        assert!(streaming.result().status.is_success());
+    }
+}
@@ -0,0 +1,59 @@
+#[cfg(feature = "python")]
+use pyo3::PyErr;
+use std::fmt;
+
+/// The reasons that a spline cannot be constructed or evaluated.
+///
+/// A spline evaluated outside its knot sequence is zero, by the support of its B-splines,
+/// rather than an error.
+///
+/// Converts into the Python `SplineError`, a subclass of `RateslibError`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SplineError {
+    /// A knot sequence which is not non-decreasing.
+    UnsortedKnots,
+    /// Fewer knots than are required by the order of the spline.
+    InsufficientKnots { required: usize, given: usize },
+    /// A number of coefficients which differs from the dimension of the spline.
+    CoefficientLength { required: usize, given: usize },
+    /// A spline evaluated before its coefficients are solved.
+    Unsolved,
+    /// Another invalid argument to the construction or solving of a spline.
+    Invalid(String),
+}
+
+impl fmt::Display for SplineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SplineError::UnsortedKnots => {
+                write!(
+                    f,
+                    "The knot sequence `t` of a PPSpline must be non-decreasing."
+                )
+            }
+            SplineError::InsufficientKnots { required, given } => write!(
+                f,
+                "A PPSpline of this order requires at least {} knots but {} were given.",
+                required, given
+            ),
+            SplineError::CoefficientLength { required, given } => write!(
+                f,
+                "A PPSpline of dimension {} requires {} coefficients but {} were given.",
+                required, required, given
+            ),
+            SplineError::Unsolved => {
+                write!(f, "Must call `csolve` before evaluating PPSpline.")
+            }
+            SplineError::Invalid(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for SplineError {}
+
+#[cfg(feature = "python")]
+impl From<SplineError> for PyErr {
+    fn from(value: SplineError) -> Self {
+        crate::error::error_py::SplineError::new_err(value.to_string())
+    }
+}
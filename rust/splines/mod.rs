@@ -1,6 +1,10 @@
 //! Toolset to create one dimensional spline curves.
 
+mod error;
+pub use crate::splines::error::SplineError;
+
 mod spline;
+#[cfg(feature = "python")]
 pub(crate) mod spline_py;
 
 pub use crate::splines::spline::{
@@ -1,9 +1,12 @@
 use crate::dual::linalg::{dmul11_, fdmul11_, fdsolve, fouter11_};
 use crate::dual::{Dual, Dual2, Gradient1, Gradient2, Number, NumberMapping};
+use crate::error::RateslibError;
+use crate::profiling::{record, Counter};
+use crate::splines::SplineError;
 use ndarray::{Array1, Array2};
 use num_traits::{Signed, Zero};
-use pyo3::exceptions::{PyTypeError, PyValueError};
-use pyo3::{pyclass, PyErr};
+#[cfg(feature = "python")]
+use pyo3::pyclass;
 use serde::{Deserialize, Serialize};
 use std::{
     cmp::PartialEq,
@@ -62,7 +65,7 @@ pub fn bsplev_single_dual(
 ) -> Dual {
     let b_f64 = bsplev_single_f64(&x.real(), i, k, t, org_k);
     let dbdx_f64 = bspldnev_single_f64(&x.real(), i, k, t, 1, org_k);
-    Dual::clone_from(x, b_f64, dbdx_f64 * x.dual())
+    Dual::clone_from(x, b_f64, dbdx_f64 * &x.dual_view())
 }
 
 /// Evaluate the `x` value on the `i`'th B-spline with order `k` and knot sequence `t`.
@@ -79,9 +82,8 @@ pub fn bsplev_single_dual2(
     let b_f64 = bsplev_single_f64(&x.real(), i, k, t, org_k);
     let dbdx_f64 = bspldnev_single_f64(&x.real(), i, k, t, 1, org_k);
     let d2bdx2_f64 = bspldnev_single_f64(&x.real(), i, k, t, 2, org_k);
-    let dual2 =
-        dbdx_f64 * x.dual2() + 0.5 * d2bdx2_f64 * fouter11_(&x.dual().view(), &x.dual().view());
-    Dual2::clone_from(x, b_f64, dbdx_f64 * x.dual(), dual2)
+    let dual2 = dbdx_f64 * x.dual2() + 0.5 * d2bdx2_f64 * fouter11_(&x.dual_view(), &x.dual_view());
+    Dual2::clone_from(x, b_f64, dbdx_f64 * &x.dual_view(), dual2)
 }
 
 /// Evaluate the `m`'th order derivative of the `x` value on the `i`'th B-spline with
@@ -143,7 +145,7 @@ pub fn bspldnev_single_dual(
 ) -> Dual {
     let b_f64 = bspldnev_single_f64(&x.real(), i, k, t, m, org_k);
     let dbdx_f64 = bspldnev_single_f64(&x.real(), i, k, t, m + 1, org_k);
-    Dual::clone_from(x, b_f64, dbdx_f64 * x.dual())
+    Dual::clone_from(x, b_f64, dbdx_f64 * &x.dual_view())
 }
 
 /// Evaluate the `m`'th order derivative of the `x` value on the `i`'th B-spline with
@@ -162,9 +164,8 @@ pub fn bspldnev_single_dual2(
     let b_f64 = bspldnev_single_f64(&x.real(), i, k, t, m, org_k);
     let dbdx_f64 = bspldnev_single_f64(&x.real(), i, k, t, m + 1, org_k);
     let d2bdx2_f64 = bspldnev_single_f64(&x.real(), i, k, t, m + 2, org_k);
-    let dual2 =
-        dbdx_f64 * x.dual2() + 0.5 * d2bdx2_f64 * fouter11_(&x.dual().view(), &x.dual().view());
-    Dual2::clone_from(x, b_f64, dbdx_f64 * x.dual(), dual2)
+    let dual2 = dbdx_f64 * x.dual2() + 0.5 * d2bdx2_f64 * fouter11_(&x.dual_view(), &x.dual_view());
+    Dual2::clone_from(x, b_f64, dbdx_f64 * &x.dual_view(), dual2)
 }
 
 /// A piecewise polynomial spline of given order and knot sequence.
@@ -192,6 +193,11 @@ impl<T> PPSpline<T> {
     pub fn c(&self) -> &Option<Array1<T>> {
         &self.c
     }
+
+    /// Return the coefficients of the spline, raising if they are not solved.
+    fn coefficients(&self) -> Result<&Array1<T>, SplineError> {
+        self.c.as_ref().ok_or(SplineError::Unsolved)
+    }
 }
 
 impl<T> PPSpline<T>
@@ -201,27 +207,39 @@ where
     for<'a> &'a f64: Mul<&'a T, Output = T>,
 {
     /// Create a PPSpline from its order `k`, knot sequence `t` and optional spline coefficents `c`.
-    pub fn new(k: usize, t: Vec<f64>, c: Option<Vec<T>>) -> Self {
+    pub fn try_new(k: usize, t: Vec<f64>, c: Option<Vec<T>>) -> Result<Self, SplineError> {
         // t is given and is non-decreasing
-        assert!(t.len() > 1);
-        assert!(zip(&t[1..], &t[..(t.len() - 1)]).all(|(a, b)| a >= b));
+        if k == 0 || t.len() <= k || t.len() < 2 {
+            return Err(SplineError::InsufficientKnots {
+                required: (k + 1).max(2),
+                given: t.len(),
+            });
+        }
+        if !zip(&t[1..], &t[..(t.len() - 1)]).all(|(a, b)| a >= b) {
+            return Err(SplineError::UnsortedKnots);
+        }
         let n = t.len() - k;
+        if let Some(c) = &c {
+            if c.len() != n {
+                return Err(SplineError::CoefficientLength {
+                    required: n,
+                    given: c.len(),
+                });
+            }
+        }
         let c_ = c.map(Array1::from_vec);
-        PPSpline { k, t, n, c: c_ }
+        Ok(PPSpline { k, t, n, c: c_ })
     }
 
-    pub fn ppdnev_single(&self, x: &f64, m: usize) -> Result<T, PyErr> {
+    pub fn ppdnev_single(&self, x: &f64, m: usize) -> Result<T, RateslibError> {
+        record(Counter::SplineEvaluations);
+        let c = self.coefficients()?;
         let b: Array1<f64> = Array1::from_vec(
             (0..self.n)
                 .map(|i| bspldnev_single_f64(x, i, &self.k, &self.t, m, None))
                 .collect(),
         );
-        match &self.c {
-            Some(c) => Ok(fdmul11_(&b.view(), &c.view())),
-            None => Err(PyValueError::new_err(
-                "Must call `csolve` before evaluating PPSpline.",
-            )),
-        }
+        Ok(fdmul11_(&b.view(), &c.view()))
     }
 
     pub fn csolve(
@@ -231,14 +249,14 @@ where
         left_n: usize,
         right_n: usize,
         allow_lsq: bool,
-    ) -> Result<(), PyErr> {
+    ) -> Result<(), RateslibError> {
         if tau.len() != self.n && !(allow_lsq && tau.len() > self.n) {
-            return Err(PyValueError::new_err(
+            return Err(RateslibError::spline(
                 "`csolve` cannot complete if length of `tau` < n or `allow_lsq` is false.",
             ));
         }
         if tau.len() != y.len() {
-            return Err(PyValueError::new_err(
+            return Err(RateslibError::spline(
                 "`tau` and `y` must have the same length.",
             ));
         }
@@ -274,7 +292,7 @@ where
 }
 
 impl NumberMapping for PPSpline<f64> {
-    fn mapped_value(&self, x: &Number) -> Result<Number, PyErr> {
+    fn mapped_value(&self, x: &Number) -> Result<Number, RateslibError> {
         match x {
             Number::F64(f) => Ok(Number::F64(self.ppdnev_single(f, 0_usize)?)),
             Number::Dual(d) => Ok(Number::Dual(self.ppdnev_single_dual(d, 0_usize)?)),
@@ -284,37 +302,31 @@ impl NumberMapping for PPSpline<f64> {
 }
 
 impl PPSpline<f64> {
-    pub fn ppdnev_single_dual(&self, x: &Dual, m: usize) -> Result<Dual, PyErr> {
+    pub fn ppdnev_single_dual(&self, x: &Dual, m: usize) -> Result<Dual, RateslibError> {
+        record(Counter::SplineEvaluations);
+        let c = self.coefficients()?;
         let b: Array1<Dual> = Array1::from_vec(
             (0..self.n)
                 .map(|i| bspldnev_single_dual(x, i, &self.k, &self.t, m, None))
                 .collect(),
         );
-        match &self.c {
-            Some(c) => Ok(fdmul11_(&c.view(), &b.view())),
-            None => Err(PyValueError::new_err(
-                "Must call `csolve` before evaluating PPSpline.",
-            )),
-        }
+        Ok(fdmul11_(&c.view(), &b.view()))
     }
 
-    pub fn ppdnev_single_dual2(&self, x: &Dual2, m: usize) -> Result<Dual2, PyErr> {
+    pub fn ppdnev_single_dual2(&self, x: &Dual2, m: usize) -> Result<Dual2, RateslibError> {
+        record(Counter::SplineEvaluations);
+        let c = self.coefficients()?;
         let b: Array1<Dual2> = Array1::from_vec(
             (0..self.n)
                 .map(|i| bspldnev_single_dual2(x, i, &self.k, &self.t, m, None))
                 .collect(),
         );
-        match &self.c {
-            Some(c) => Ok(fdmul11_(&c.view(), &b.view())),
-            None => Err(PyValueError::new_err(
-                "Must call `csolve` before evaluating PPSpline.",
-            )),
-        }
+        Ok(fdmul11_(&c.view(), &b.view()))
     }
 }
 
 impl NumberMapping for PPSpline<Dual> {
-    fn mapped_value(&self, x: &Number) -> Result<Number, PyErr> {
+    fn mapped_value(&self, x: &Number) -> Result<Number, RateslibError> {
         match x {
             Number::F64(f) => Ok(Number::Dual(self.ppdnev_single(f, 0_usize)?)),
             Number::Dual(d) => Ok(Number::Dual(self.ppdnev_single_dual(d, 0_usize)?)),
@@ -324,29 +336,26 @@ impl NumberMapping for PPSpline<Dual> {
 }
 
 impl PPSpline<Dual> {
-    pub fn ppdnev_single_dual2(&self, _x: &Dual2, _m: usize) -> Result<Dual2, PyErr> {
-        Err(PyTypeError::new_err(
-            "Cannot index with type `Dual2` on PPSpline<Dual>`.",
+    pub fn ppdnev_single_dual2(&self, _x: &Dual2, _m: usize) -> Result<Dual2, RateslibError> {
+        Err(RateslibError::Type(
+            "Cannot index with type `Dual2` on PPSpline<Dual>`.".to_string(),
         ))
     }
 
-    pub fn ppdnev_single_dual(&self, x: &Dual, m: usize) -> Result<Dual, PyErr> {
+    pub fn ppdnev_single_dual(&self, x: &Dual, m: usize) -> Result<Dual, RateslibError> {
+        record(Counter::SplineEvaluations);
+        let c = self.coefficients()?;
         let b: Array1<Dual> = Array1::from_vec(
             (0..self.n)
                 .map(|i| bspldnev_single_dual(x, i, &self.k, &self.t, m, None))
                 .collect(),
         );
-        match &self.c {
-            Some(c) => Ok(dmul11_(&c.view(), &b.view())),
-            None => Err(PyValueError::new_err(
-                "Must call `csolve` before evaluating PPSpline.",
-            )),
-        }
+        Ok(dmul11_(&c.view(), &b.view()))
     }
 }
 
 impl NumberMapping for PPSpline<Dual2> {
-    fn mapped_value(&self, x: &Number) -> Result<Number, PyErr> {
+    fn mapped_value(&self, x: &Number) -> Result<Number, RateslibError> {
         match x {
             Number::F64(f) => Ok(Number::Dual2(self.ppdnev_single(f, 0_usize)?)),
             Number::Dual(d) => Ok(Number::Dual(self.ppdnev_single_dual(d, 0_usize)?)),
@@ -356,24 +365,21 @@ impl NumberMapping for PPSpline<Dual2> {
 }
 
 impl PPSpline<Dual2> {
-    pub fn ppdnev_single_dual(&self, _x: &Dual, _m: usize) -> Result<Dual, PyErr> {
-        Err(PyTypeError::new_err(
-            "Cannot index with type `Dual` on PPSpline<Dual2>.",
+    pub fn ppdnev_single_dual(&self, _x: &Dual, _m: usize) -> Result<Dual, RateslibError> {
+        Err(RateslibError::Type(
+            "Cannot index with type `Dual` on PPSpline<Dual2>.".to_string(),
         ))
     }
 
-    pub fn ppdnev_single_dual2(&self, x: &Dual2, m: usize) -> Result<Dual2, PyErr> {
+    pub fn ppdnev_single_dual2(&self, x: &Dual2, m: usize) -> Result<Dual2, RateslibError> {
+        record(Counter::SplineEvaluations);
+        let c = self.coefficients()?;
         let b: Array1<Dual2> = Array1::from_vec(
             (0..self.n)
                 .map(|i| bspldnev_single_dual2(x, i, &self.k, &self.t, m, None))
                 .collect(),
         );
-        match &self.c {
-            Some(c) => Ok(dmul11_(&c.view(), &b.view())),
-            None => Err(PyValueError::new_err(
-                "Must call `csolve` before evaluating PPSpline.",
-            )),
-        }
+        Ok(dmul11_(&c.view(), &b.view()))
     }
 }
 
@@ -381,8 +387,7 @@ impl<T> PartialEq for PPSpline<T>
 where
     T: PartialEq,
 {
-    /// Equality of `PPSpline` if
-
+    /// Equality of `PPSpline` if `k`, `n`, `t` and `c` are all equal.
     fn eq(&self, other: &Self) -> bool {
         if self.k != other.k || self.n != other.n {
             return false;
@@ -400,21 +405,21 @@ where
 }
 
 /// Definitive [f64] type variant of a [PPSpline].
-#[pyclass(module = "rateslib.rs")]
+#[cfg_attr(feature = "python", pyclass(module = "rateslib.rs"))]
 #[derive(Clone, Deserialize, Serialize)]
 pub struct PPSplineF64 {
     pub(crate) inner: PPSpline<f64>,
 }
 
 /// Definitive [Dual] type variant of a [PPSpline].
-#[pyclass(module = "rateslib.rs")]
+#[cfg_attr(feature = "python", pyclass(module = "rateslib.rs"))]
 #[derive(Clone, Deserialize, Serialize)]
 pub struct PPSplineDual {
     pub(crate) inner: PPSpline<Dual>,
 }
 
 /// Definitive [Dual2] type variant of a [PPSpline].
-#[pyclass(module = "rateslib.rs")]
+#[cfg_attr(feature = "python", pyclass(module = "rateslib.rs"))]
 #[derive(Clone, Deserialize, Serialize)]
 pub struct PPSplineDual2 {
     pub(crate) inner: PPSpline<Dual2>,
@@ -564,16 +569,19 @@ mod tests {
 
     #[test]
     fn ppspline_new() {
-        let _pps: PPSpline<f64> = PPSpline::new(
+        let _pps: PPSpline<f64> = PPSpline::try_new(
             4,
             vec![1., 1., 1., 1., 2., 2., 2., 3., 4., 4., 4., 4.],
             None,
-        );
+        )
+        .unwrap();
     }
 
     #[test]
+    #[allow(clippy::useless_vec)]
     fn ppspline_bsplmatrix() {
-        let pps: PPSpline<f64> = PPSpline::new(4, vec![1., 1., 1., 1., 2., 3., 3., 3., 3.], None);
+        let pps: PPSpline<f64> =
+            PPSpline::try_new(4, vec![1., 1., 1., 1., 2., 3., 3., 3., 3.], None).unwrap();
         let result = pps.bsplmatrix(&vec![1., 1., 2., 3., 3.], 2_usize, 2_usize);
         let expected: Array2<f64> = arr2(&[
             [6., -9., 3., 0., 0.],
@@ -586,11 +594,12 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::needless_borrow, clippy::useless_vec)]
     fn csolve_() {
         let t = vec![0., 0., 0., 0., 4., 4., 4., 4.];
         let tau = vec![0., 1., 3., 4.];
         let val = vec![0., 0., 2., 2.];
-        let mut pps: PPSpline<f64> = PPSpline::new(4, t, None);
+        let mut pps: PPSpline<f64> = PPSpline::try_new(4, t, None).unwrap();
         let _ = pps.csolve(&tau, &val, 0, 0, false);
         let expected = vec![0., -1.11111111, 3.111111111111, 2.0];
         let v: Vec<bool> = pps
@@ -606,12 +615,13 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::useless_vec)]
     fn csolve_dual() {
         let t = vec![0., 0., 0., 0., 4., 4., 4., 4.];
         let tau = vec![0., 1., 3., 4.];
         let d1 = Dual::one();
         let val = vec![0. * &d1, 0. * &d1, 2. * &d1, 2. * &d1];
-        let mut pps = PPSpline::new(4, t, None);
+        let mut pps = PPSpline::try_new(4, t, None).unwrap();
         let _ = pps.csolve(&tau, &val, 0, 0, false);
         let expected = vec![0. * &d1, -1.11111111 * &d1, 3.111111111111 * &d1, 2.0 * &d1];
         let v: Vec<bool> = pps
@@ -629,7 +639,7 @@ mod tests {
     #[test]
     fn ppev_single_() {
         let t = vec![1., 1., 1., 1., 2., 2., 2., 3., 4., 4., 4., 4.];
-        let mut pps = PPSpline::new(4, t, None);
+        let mut pps = PPSpline::try_new(4, t, None).unwrap();
         pps.c = Some(arr1(&[1., 2., -1., 2., 1., 1., 2., 2.]));
         let r1 = pps.ppdnev_single(&1.1, 0).unwrap();
         assert!(is_close(&r1, &1.19, None));
@@ -641,19 +651,46 @@ mod tests {
 
     #[test]
     fn partialeq_() {
-        let pp1 = PPSpline::<f64>::new(2, vec![1., 1., 2., 2.], None);
-        let pp2 = PPSpline::<f64>::new(2, vec![1., 1., 2., 2.], None);
+        let pp1 = PPSpline::<f64>::try_new(2, vec![1., 1., 2., 2.], None).unwrap();
+        let pp2 = PPSpline::<f64>::try_new(2, vec![1., 1., 2., 2.], None).unwrap();
         assert!(pp1 == pp2);
-        let pp3 = PPSpline::new(2, vec![1., 1., 2., 2.], Some(vec![1.5, 0.2]));
-        let pp4 = PPSpline::new(2, vec![1., 1., 2., 2.], Some(vec![1.5, 0.2]));
+        let pp3 = PPSpline::try_new(2, vec![1., 1., 2., 2.], Some(vec![1.5, 0.2])).unwrap();
+        let pp4 = PPSpline::try_new(2, vec![1., 1., 2., 2.], Some(vec![1.5, 0.2])).unwrap();
         assert!(pp3 == pp4);
         assert!(pp3 != pp2);
         assert!(pp1 != pp4);
     }
 
     #[test]
-    #[should_panic]
     fn backwards_definition() {
-        let _pp1 = PPSpline::<f64>::new(4, vec![3., 3., 3., 3., 2., 1., 1., 1., 1.], None);
+        let result = PPSpline::<f64>::try_new(4, vec![3., 3., 3., 3., 2., 1., 1., 1., 1.], None);
+        assert_eq!(result.unwrap_err(), SplineError::UnsortedKnots);
+    }
+
+    #[test]
+    fn invalid_definition() {
+        let result = PPSpline::<f64>::try_new(4, vec![1., 1., 1., 1.], None);
+        assert_eq!(
+            result.unwrap_err(),
+            SplineError::InsufficientKnots {
+                required: 5,
+                given: 4
+            }
+        );
+        let result = PPSpline::<f64>::try_new(2, vec![1., 1., 2., 2.], Some(vec![1.0]));
+        assert_eq!(
+            result.unwrap_err(),
+            SplineError::CoefficientLength {
+                required: 2,
+                given: 1
+            }
+        );
+    }
+
+    #[test]
+    fn unsolved_evaluation() {
+        let pp = PPSpline::<f64>::try_new(2, vec![1., 1., 2., 2.], None).unwrap();
+        assert_eq!(pp.coefficients().unwrap_err(), SplineError::Unsolved);
+        assert!(pp.ppdnev_single(&1.5, 0).is_err());
     }
 }
@@ -17,10 +17,10 @@ macro_rules! create_interface {
         #[pymethods]
         impl $name {
             #[new]
-            fn new(k: usize, t: Vec<f64>, c: Option<Vec<$type>>) -> Self {
-                Self {
-                    inner: PPSpline::new(k, t, c),
-                }
+            fn new(k: usize, t: Vec<f64>, c: Option<Vec<$type>>) -> PyResult<Self> {
+                Ok(Self {
+                    inner: PPSpline::try_new(k, t, c)?,
+                })
             }
 
             #[getter]
@@ -75,7 +75,7 @@ macro_rules! create_interface {
                 right_n: usize,
                 allow_lsq: bool
             ) -> PyResult<()> {
-                self.inner.csolve(&tau, &y, left_n, right_n, allow_lsq)
+                Ok(self.inner.csolve(&tau, &y, left_n, right_n, allow_lsq)?)
             }
 
             /// Evaluate a single *x* coordinate value on the pp spline.
@@ -100,7 +100,7 @@ macro_rules! create_interface {
             ///
             fn ppev_single(&self, x: Number) -> PyResult<$type> {
                 match x {
-                    Number::F64(f) => self.inner.ppdnev_single(&f, 0),
+                    Number::F64(f) => Ok(self.inner.ppdnev_single(&f, 0)?),
                     Number::Dual(_) => Err(PyTypeError::new_err(
                         "Cannot index PPSpline with `Dual`, use either `ppev_single(float(x))` or `ppev_single_dual(x)`."
                         )),
@@ -127,8 +127,8 @@ macro_rules! create_interface {
             /// are encountered.
             fn ppev_single_dual(&self, x: Number) -> PyResult<Dual> {
                 match x {
-                    Number::F64(f) => self.inner.ppdnev_single_dual(&Dual::new(f, vec![]), 0),
-                    Number::Dual(d) => self.inner.ppdnev_single_dual(&d, 0),
+                    Number::F64(f) => Ok(self.inner.ppdnev_single_dual(&Dual::new(f, vec![]), 0)?),
+                    Number::Dual(d) => Ok(self.inner.ppdnev_single_dual(&d, 0)?),
                     Number::Dual2(_) => Err(PyTypeError::new_err("Cannot mix `Dual2` and `Dual` types, use `ppev_single_dual2(x)`.")),
                 }
             }
@@ -151,9 +151,9 @@ macro_rules! create_interface {
             /// are encountered.
             fn ppev_single_dual2(&self, x: Number) -> PyResult<Dual2> {
                 match x {
-                    Number::F64(f) => self.inner.ppdnev_single_dual2(&Dual2::new(f, vec![]), 0),
+                    Number::F64(f) => Ok(self.inner.ppdnev_single_dual2(&Dual2::new(f, vec![]), 0)?),
                     Number::Dual(_) => Err(PyTypeError::new_err("Cannot mix `Dual2` and `Dual` types, use `ppev_single_dual(x)`.")),
-                    Number::Dual2(d) => self.inner.ppdnev_single_dual2(&d, 0),
+                    Number::Dual2(d) => Ok(self.inner.ppdnev_single_dual2(&d, 0)?),
                 }
             }
 
@@ -208,7 +208,7 @@ macro_rules! create_interface {
             fn ppdnev_single(&self, x: Number, m: usize) -> PyResult<$type> {
                 match x {
                     Number::Dual(_) => Err(PyTypeError::new_err("Splines cannot be indexed with Duals use `float(x)`.")),
-                    Number::F64(f) => self.inner.ppdnev_single(&f, m),
+                    Number::F64(f) => Ok(self.inner.ppdnev_single(&f, m)?),
                     Number::Dual2(_) => Err(PyTypeError::new_err("Splines cannot be indexed with Duals use `float(x)`.")),
                 }
             }
@@ -233,8 +233,8 @@ macro_rules! create_interface {
             /// data types are encountered.
             fn ppdnev_single_dual(&self, x: Number, m: usize) -> PyResult<Dual> {
                 match x {
-                    Number::F64(f) => self.inner.ppdnev_single_dual(&Dual::new(f, vec![]), m),
-                    Number::Dual(d) => self.inner.ppdnev_single_dual(&d, m),
+                    Number::F64(f) => Ok(self.inner.ppdnev_single_dual(&Dual::new(f, vec![]), m)?),
+                    Number::Dual(d) => Ok(self.inner.ppdnev_single_dual(&d, m)?),
                     Number::Dual2(_) => Err(PyTypeError::new_err("Cannot mix `Dual2` and `Dual` types, use `ppdnev_single_dual2(x)`.")),
                 }
             }
@@ -259,9 +259,9 @@ macro_rules! create_interface {
             /// data types are encountered.
             fn ppdnev_single_dual2(&self, x: Number, m: usize) -> PyResult<Dual2> {
                 match x {
-                    Number::F64(f) => self.inner.ppdnev_single_dual2(&Dual2::new(f, vec![]), m),
+                    Number::F64(f) => Ok(self.inner.ppdnev_single_dual2(&Dual2::new(f, vec![]), m)?),
                     Number::Dual(_) => Err(PyTypeError::new_err("Cannot mix `Dual2` and `Dual` types, use `ppdnev_single_dual(x)`.")),
-                    Number::Dual2(d) => self.inner.ppdnev_single_dual2(&d, m),
+                    Number::Dual2(d) => Ok(self.inner.ppdnev_single_dual2(&d, m)?),
                 }
             }
 
@@ -378,7 +378,7 @@ macro_rules! create_interface {
                 tau: Vec<f64>,
                 left_n: usize,
                 right_n: usize
-            ) -> PyResult<Bound<'_, PyArray2<f64>>> {
+            ) -> PyResult<Bound<'py, PyArray2<f64>>> {
                 Ok(self.inner.bsplmatrix(&tau, left_n, right_n).to_pyarray_bound(py))
             }
 
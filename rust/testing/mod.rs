@@ -0,0 +1,339 @@
+//! Generate random valid inputs to test properties of code built on rateslib.
+//!
+//! A [Generator] produces dual numbers, schedules and curves which satisfy the invariants of
+//! their constructors, from a seed, so that downstream crates can fuzz their own code paths.
+//! [check] runs a property over many generated cases and reports the seed of any failing case
+//! to reproduce it. The generators are compiled with the `testing` feature, which also exposes
+//! their Python equivalents.
+
+use crate::calendars::{Convention, DateRoll, Modifier, NamedCal, RollDay};
+use crate::curves::nodes::Nodes;
+use crate::curves::{CurveDF, LogLinearInterpolator};
+use crate::dual::{ADOrder, Dual, Dual2, Number};
+use crate::error::RateslibError;
+use crate::montecarlo::NormalRng;
+use crate::scheduling::{Frequency, Schedule, StubInference};
+use chrono::{Days, NaiveDate, NaiveDateTime};
+use indexmap::IndexMap;
+use std::fmt;
+
+/// A deterministic generator of random valid inputs.
+#[derive(Debug, Clone)]
+pub struct Generator {
+    rng: NormalRng,
+}
+
+impl Generator {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: NormalRng::new(seed),
+        }
+    }
+
+    /// Return a value uniformly distributed between `low` and `high`.
+    pub fn uniform(&mut self, low: f64, high: f64) -> f64 {
+        low + (high - low) * self.rng.next_uniform()
+    }
+
+    /// Return an integer uniformly distributed on `0..n`, which must be positive.
+    pub fn index(&mut self, n: usize) -> usize {
+        (self.rng.next_u64() % n as u64) as usize
+    }
+
+    /// Return one of the `values`, which must not be empty.
+    pub fn choose<'a, T>(&mut self, values: &'a [T]) -> &'a T {
+        &values[self.index(values.len())]
+    }
+
+    /// Return a date uniformly distributed between `start` and `end`, inclusive.
+    pub fn date(&mut self, start: &NaiveDateTime, end: &NaiveDateTime) -> NaiveDateTime {
+        let days = (*end - *start).num_days().max(0) as usize;
+        *start + Days::new(self.index(days + 1) as u64)
+    }
+
+    /// Return a [Dual] with a real part between -10 and 10 and standard normal gradients to a
+    /// random subset of the `vars`, in their order.
+    pub fn dual(&mut self, vars: &[&str]) -> Dual {
+        let vars = self.subset(vars);
+        let dual = self.rng.normals(vars.len());
+        Dual::try_new(self.uniform(-10.0, 10.0), vars, dual).unwrap()
+    }
+
+    /// Return a [Dual2] as [Generator::dual] with a symmetric second order gradient.
+    pub fn dual2(&mut self, vars: &[&str]) -> Dual2 {
+        let vars = self.subset(vars);
+        let n = vars.len();
+        let dual = self.rng.normals(n);
+        let mut dual2 = vec![0.0; n * n];
+        for i in 0..n {
+            for j in i..n {
+                let x = self.rng.next_normal();
+                dual2[i * n + j] = x;
+                dual2[j * n + i] = x;
+            }
+        }
+        Dual2::try_new(self.uniform(-10.0, 10.0), vars, dual, dual2).unwrap()
+    }
+
+    /// Return a [Number] of the `ad` order, as [Generator::dual] or [Generator::dual2].
+    pub fn number(&mut self, vars: &[&str], ad: ADOrder) -> Number {
+        match ad {
+            ADOrder::Zero => Number::F64(self.uniform(-10.0, 10.0)),
+            ADOrder::One => Number::Dual(self.dual(vars)),
+            ADOrder::Two => Number::Dual2(self.dual2(vars)),
+        }
+    }
+
+    /// Return a [Schedule] on the `calendar` with an effective date between 2000 and 2040, a
+    /// tenor of up to 30 years with any stub, and a random frequency, modifier and payment lag.
+    pub fn schedule<U: DateRoll>(&mut self, calendar: &U) -> Schedule {
+        let roll = RollDay::Unspecified {};
+        loop {
+            let effective = self.date(&ndt(2000, 1, 1), &ndt(2040, 1, 1));
+            let months = *self.choose(&[1_u32, 3, 6, 12]);
+            let periods = 1 + self.index(30 * 12 / months as usize);
+            let Ok(end) = calendar.add_months(
+                &effective,
+                (periods as u32 * months) as i32,
+                &Modifier::Act,
+                &roll,
+                false,
+            ) else {
+                continue;
+            };
+            let termination = end + Days::new(self.index(28) as u64);
+            let stubs = [
+                StubInference::ShortFront,
+                StubInference::LongFront,
+                StubInference::ShortBack,
+                StubInference::LongBack,
+            ];
+            let modifiers = [
+                Modifier::Act,
+                Modifier::F,
+                Modifier::ModF,
+                Modifier::P,
+                Modifier::ModP,
+            ];
+            let schedule = Schedule::try_new_with_stub(
+                effective,
+                termination,
+                Frequency::Months { number: months },
+                roll,
+                *self.choose(&modifiers),
+                calendar,
+                self.index(4) as i8,
+                *self.choose(&stubs),
+            );
+            if let Ok(schedule) = schedule {
+                return schedule;
+            }
+        }
+    }
+
+    /// Return `n` discount factor nodes, which must be at least two, from a unit discount factor
+    /// at an initial date between 2000 and 2040, separated by between one month and four years
+    /// with continuously compounded forward rates between -1% and 10%.
+    pub fn nodes(&mut self, n: usize) -> IndexMap<NaiveDateTime, f64> {
+        let mut date = self.date(&ndt(2000, 1, 1), &ndt(2040, 1, 1));
+        let mut df = 1.0;
+        let mut nodes = IndexMap::from_iter([(date, df)]);
+        for _ in 1..n.max(2) {
+            let days = 30 + self.index(1431) as u64;
+            df *= (-self.uniform(-0.01, 0.10) * days as f64 / 365.0).exp();
+            date = date + Days::new(days);
+            nodes.insert(date, df);
+        }
+        nodes
+    }
+
+    /// Return a log-linear curve with the `id` on [Generator::nodes] of between two and ten
+    /// nodes.
+    pub fn curve(&mut self, id: &str) -> CurveDF<LogLinearInterpolator, NamedCal> {
+        let n = 2 + self.index(9);
+        let nodes = Nodes::F64(self.nodes(n));
+        let calendar = NamedCal::try_new("all").unwrap();
+        CurveDF::try_new(
+            nodes,
+            LogLinearInterpolator::new(),
+            id,
+            Convention::Act365F,
+            Modifier::ModF,
+            None,
+            calendar,
+        )
+        .unwrap()
+    }
+
+    fn subset(&mut self, vars: &[&str]) -> Vec<String> {
+        vars.iter()
+            .filter(|_| self.rng.next_uniform() < 0.5)
+            .map(|v| v.to_string())
+            .collect()
+    }
+}
+
+/// A generated case which fails a property checked by [check].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PropertyFailure {
+    /// The index of the case.
+    pub case: usize,
+    /// The seed of the [Generator] which reproduces the case.
+    pub seed: u64,
+    pub message: String,
+}
+
+impl fmt::Display for PropertyFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Property failed on case {}, reproduced by `Generator::new({})`: {}",
+            self.case, self.seed, self.message
+        )
+    }
+}
+
+impl From<PropertyFailure> for RateslibError {
+    fn from(failure: PropertyFailure) -> Self {
+        RateslibError::value(failure.to_string())
+    }
+}
+
+/// Check a `property` over `cases` generated cases, returning the first case which fails.
+///
+/// Each case is generated by a [Generator] seeded from `seed` and the index of the case, so
+/// that a failing case is reproduced alone by the seed of the [PropertyFailure].
+///
+/// # Examples
+///
+/// ```rust
+/// # use rateslib::dual::ADOrder;
+/// # use rateslib::testing::check;
+/// check(100, 0, |g| {
+///     let (x, y) = (g.number(&["a", "b"], ADOrder::One), g.number(&["b", "c"], ADOrder::One));
+///     match &x + &y == &y + &x {
+///         true => Ok(()),
+///         false => Err(format!("{:?} + {:?} does not commute", x, y)),
+///     }
+/// })
+/// .unwrap();
+/// ```
+pub fn check<F>(cases: usize, seed: u64, mut property: F) -> Result<(), PropertyFailure>
+where
+    F: FnMut(&mut Generator) -> Result<(), String>,
+{
+    for case in 0..cases {
+        let seed = case_seed(seed, case);
+        if let Err(message) = property(&mut Generator::new(seed)) {
+            return Err(PropertyFailure {
+                case,
+                seed,
+                message,
+            });
+        }
+    }
+    Ok(())
+}
+
+fn case_seed(seed: u64, case: usize) -> u64 {
+    NormalRng::new(seed ^ (case as u64).wrapping_mul(0x2545F4914F6CDD1D)).next_u64()
+}
+
+fn ndt(year: i32, month: u32, day: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(year, month, day)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
+}
+
+/// Return `n` random dual numbers of the `ad` order with gradients to subsets of `vars`.
+///
+/// Only available when compiled with the `testing` feature.
+///
+/// Parameters
+/// ----------
+/// seed: int
+///     The seed from which the numbers are generated.
+/// n: int
+///     The number of values.
+/// vars: list[str]
+///     The variables of which each value has gradients to a random subset.
+/// ad: ADOrder
+///     The order of the values.
+///
+/// Returns
+/// -------
+/// list[float, Dual or Dual2]
+#[cfg(feature = "python")]
+#[pyo3::pyfunction]
+#[pyo3(name = "generate_numbers", signature = (seed, n, vars, ad = ADOrder::One))]
+pub fn generate_numbers_py(seed: u64, n: usize, vars: Vec<String>, ad: ADOrder) -> Vec<Number> {
+    let mut g = Generator::new(seed);
+    let vars: Vec<&str> = vars.iter().map(|v| v.as_str()).collect();
+    (0..n).map(|_| g.number(&vars, ad)).collect()
+}
+
+/// Return `n` random valid discount factor nodes of a curve.
+///
+/// Only available when compiled with the `testing` feature.
+///
+/// Parameters
+/// ----------
+/// seed: int
+///     The seed from which the nodes are generated.
+/// n: int
+///     The number of nodes, at least two.
+///
+/// Returns
+/// -------
+/// dict[datetime, float]
+#[cfg(feature = "python")]
+#[pyo3::pyfunction]
+#[pyo3(name = "generate_nodes")]
+pub fn generate_nodes_py(seed: u64, n: usize) -> IndexMap<NaiveDateTime, f64> {
+    Generator::new(seed).nodes(n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curves::nodes::NodesTimestamp;
+
+    #[test]
+    fn test_generated_invariants() {
+        let cal = NamedCal::try_new("ldn,nyc").unwrap();
+        check(200, 7, |g| {
+            let s = g.schedule(&cal);
+            if s.uschedule.windows(2).any(|w| w[0] >= w[1]) {
+                return Err(format!("unordered schedule {:?}", s.uschedule));
+            }
+            let curve = g.curve("c");
+            let NodesTimestamp::F64(nodes) = &curve.nodes else {
+                return Err("the curve nodes are not f64".to_string());
+            };
+            if nodes.values().any(|df| *df <= 0.0) || nodes[0] != 1.0 {
+                return Err(format!("invalid nodes {:?}", nodes));
+            }
+            let Dual2 { vars, dual2, .. } = g.dual2(&["a", "b", "c"]);
+            match *dual2 == dual2.t() && vars.len() == dual2.nrows() {
+                true => Ok(()),
+                false => Err(format!("asymmetric dual2 {:?}", dual2)),
+            }
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_check_reports_failing_case() {
+        let err = check(100, 3, |g| match g.index(10) {
+            0 => Err("zero".to_string()),
+            _ => Ok(()),
+        })
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains(&format!("Generator::new({})", err.seed)));
+        assert_eq!(Generator::new(err.seed).index(10), 0);
+        assert_eq!(Generator::new(5).nodes(4), Generator::new(5).nodes(4));
+    }
+}
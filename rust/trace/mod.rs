@@ -0,0 +1,249 @@
+//! Diagnose performance and convergence with spans and events emitted by the rust core.
+//!
+//! Records are filtered by a [Level] for each target, the module emitting them, with the
+//! syntax of an environment filter, e.g. `"warn,solver=debug,curves=trace"`. The filter is read
+//! from the `RATESLIB_LOG` environment variable on first use and may be replaced with
+//! [set_filter], which is exported to Python. Nothing is recorded by default, and a disabled
+//! record costs a single atomic load.
+//!
+//! Records are written to stderr, or retained in memory for [take_records] after calling
+//! [capture].
+
+#[cfg(feature = "python")]
+pub(crate) mod trace_py;
+
+use crate::error::RateslibError;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::{Mutex, OnceLock, RwLock};
+use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+
+/// The verbosity of a record, in increasing order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Level {
+    Error = 1,
+    Warn = 2,
+    Info = 3,
+    Debug = 4,
+    Trace = 5,
+}
+
+impl Level {
+    fn parse(s: &str) -> Result<Option<Self>, RateslibError> {
+        match s.trim().to_lowercase().as_str() {
+            "off" => Ok(None),
+            "error" => Ok(Some(Level::Error)),
+            "warn" => Ok(Some(Level::Warn)),
+            "info" => Ok(Some(Level::Info)),
+            "debug" => Ok(Some(Level::Debug)),
+            "trace" => Ok(Some(Level::Trace)),
+            other => Err(RateslibError::value(format!(
+                "`{}` is not a valid log level.",
+                other
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// The maximum level enabled for each target, with a default for other targets.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Filter {
+    default: Option<Level>,
+    targets: Vec<(String, Option<Level>)>,
+}
+
+impl Filter {
+    /// Parse a comma separated list of `level` and `target=level` directives.
+    pub fn parse(directives: &str) -> Result<Self, RateslibError> {
+        let mut filter = Filter::default();
+        for directive in directives.split(',').filter(|d| !d.trim().is_empty()) {
+            match directive.split_once('=') {
+                Some((target, level)) => filter
+                    .targets
+                    .push((target.trim().to_string(), Level::parse(level)?)),
+                None => filter.default = Level::parse(directive)?,
+            }
+        }
+        // the most specific target matches first
+        filter.targets.sort_by_key(|t| std::cmp::Reverse(t.0.len()));
+        Ok(filter)
+    }
+
+    /// Return whether a record of `level` from `target` is enabled.
+    pub fn enabled(&self, level: Level, target: &str) -> bool {
+        let max = self
+            .targets
+            .iter()
+            .find(|(t, _)| target == t || target.starts_with(&format!("{}::", t)))
+            .map(|(_, l)| *l)
+            .unwrap_or(self.default);
+        max.is_some_and(|m| level <= m)
+    }
+
+    fn max_level(&self) -> u8 {
+        self.targets
+            .iter()
+            .map(|(_, l)| *l)
+            .chain([self.default])
+            .map(|l| l.map_or(0, |l| l as u8))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(0);
+static CAPTURE: AtomicBool = AtomicBool::new(false);
+static RECORDS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+fn filter() -> &'static RwLock<Filter> {
+    static FILTER: OnceLock<RwLock<Filter>> = OnceLock::new();
+    FILTER.get_or_init(|| {
+        let filter = std::env::var("RATESLIB_LOG")
+            .ok()
+            .and_then(|d| Filter::parse(&d).ok())
+            .unwrap_or_default();
+        MAX_LEVEL.store(filter.max_level(), Ordering::Relaxed);
+        RwLock::new(filter)
+    })
+}
+
+/// Replace the filter of records with the given `directives`.
+pub fn set_filter(directives: &str) -> Result<(), RateslibError> {
+    let parsed = Filter::parse(directives)?;
+    let mut current = filter().write().unwrap();
+    MAX_LEVEL.store(parsed.max_level(), Ordering::Relaxed);
+    *current = parsed;
+    Ok(())
+}
+
+/// Return whether a record of `level` from `target` is enabled.
+pub fn enabled(level: Level, target: &str) -> bool {
+    let filter = filter();
+    if (level as u8) > MAX_LEVEL.load(Ordering::Relaxed) {
+        return false;
+    }
+    filter.read().unwrap().enabled(level, target)
+}
+
+/// Retain records in memory, `true`, or write them to stderr, `false`.
+pub fn capture(retain: bool) {
+    CAPTURE.store(retain, Ordering::Relaxed);
+}
+
+/// Return and clear the records retained in memory.
+pub fn take_records() -> Vec<String> {
+    std::mem::take(&mut *RECORDS.lock().unwrap())
+}
+
+fn emit(level: Level, target: &str, message: &str) {
+    let record = format!("{} {}: {}", level, target, message);
+    match CAPTURE.load(Ordering::Relaxed) {
+        true => RECORDS.lock().unwrap().push(record),
+        false => eprintln!("{}", record),
+    }
+}
+
+/// Emit an event of `level` from `target`, formatting the message only if enabled.
+pub fn event(level: Level, target: &str, message: impl FnOnce() -> String) {
+    if enabled(level, target) {
+        emit(level, target, &message());
+    }
+}
+
+/// The start of a measurement of elapsed time.
+///
+/// Measures no time for a `wasm32` target, which has no system clock.
+#[derive(Debug, Clone, Copy)]
+pub struct Stopwatch {
+    #[cfg(not(target_arch = "wasm32"))]
+    start: Instant,
+}
+
+impl Stopwatch {
+    /// Start measuring from now.
+    pub fn start() -> Self {
+        Self {
+            #[cfg(not(target_arch = "wasm32"))]
+            start: Instant::now(),
+        }
+    }
+
+    /// Return the time elapsed since the start.
+    pub fn elapsed(&self) -> Duration {
+        #[cfg(not(target_arch = "wasm32"))]
+        return self.start.elapsed();
+        #[cfg(target_arch = "wasm32")]
+        Duration::ZERO
+    }
+}
+
+/// A timed region of execution, whose closing is recorded with its duration when dropped.
+pub struct Span {
+    inner: Option<(Level, &'static str, String, Stopwatch)>,
+}
+
+impl Span {
+    /// Enter a span of `level` from `target`, formatting its name only if enabled.
+    pub fn enter(level: Level, target: &'static str, name: impl FnOnce() -> String) -> Self {
+        let inner = match enabled(level, target) {
+            true => {
+                let name = name();
+                emit(level, target, &format!("{} entered", name));
+                Some((level, target, name, Stopwatch::start()))
+            }
+            false => None,
+        };
+        Self { inner }
+    }
+
+    /// Return the time elapsed in an enabled span.
+    pub fn elapsed(&self) -> Option<Duration> {
+        self.inner.as_ref().map(|(_, _, _, start)| start.elapsed())
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        if let Some((level, target, name, start)) = self.inner.take() {
+            emit(
+                level,
+                target,
+                &format!("{} closed in {:?}", name, start.elapsed()),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter() {
+        let f = Filter::parse("warn,solver=debug,solver::step=off").unwrap();
+        assert!(f.enabled(Level::Warn, "curves"));
+        assert!(!f.enabled(Level::Info, "curves"));
+        assert!(f.enabled(Level::Debug, "solver"));
+        assert!(f.enabled(Level::Debug, "solver::diagnostics"));
+        assert!(!f.enabled(Level::Error, "solver::step"));
+        assert!(!f.enabled(Level::Debug, "solvers"));
+        assert_eq!(f.max_level(), Level::Debug as u8);
+        assert!(Filter::parse("loud").is_err());
+        assert!(!Filter::default().enabled(Level::Error, "curves"));
+    }
+}
@@ -0,0 +1,48 @@
+//! Wrapper module to export the tracing configuration to Python using pyo3 bindings.
+
+use crate::trace::{capture, set_filter, take_records};
+use pyo3::prelude::*;
+
+/// Set the filter of records emitted by the rust core.
+///
+/// Parameters
+/// ----------
+/// directives: str
+///     A comma separated list of `level` and `target=level` directives, with levels of
+///     "off", "error", "warn", "info", "debug" or "trace", e.g. "warn,solver=debug".
+///
+/// Returns
+/// -------
+/// None
+#[pyfunction]
+#[pyo3(name = "set_log_filter")]
+pub fn set_log_filter_py(directives: &str) -> PyResult<()> {
+    Ok(set_filter(directives)?)
+}
+
+/// Retain records in memory, for :meth:`take_log_records`, rather than write them to stderr.
+///
+/// Parameters
+/// ----------
+/// retain: bool
+///     Whether to retain records in memory.
+///
+/// Returns
+/// -------
+/// None
+#[pyfunction]
+#[pyo3(name = "capture_logs")]
+pub fn capture_logs_py(retain: bool) {
+    capture(retain)
+}
+
+/// Return and clear the records retained in memory.
+///
+/// Returns
+/// -------
+/// list[str]
+#[pyfunction]
+#[pyo3(name = "take_log_records")]
+pub fn take_log_records_py() -> Vec<String> {
+    take_records()
+}
@@ -0,0 +1,637 @@
+use crate::cancel;
+use crate::curves::{PricingCurve, RollMethod, RolledCurve};
+use crate::dual::{MathFuncs, Number};
+use crate::error::RateslibError;
+use crate::instruments::Portfolio;
+use crate::models::HullWhite;
+use crate::montecarlo::{McConfig, NormalRng};
+use crate::parallel::par_map;
+use crate::periods::Curves;
+use crate::xva::{ExposureProfile, NettingSet};
+use chrono::{Days, NaiveDateTime};
+use num_traits::Pow;
+
+/// The simulation of the exposures of a [Portfolio] under a [HullWhite] model of the rates of
+/// the domestic `curve` and, optionally, a lognormal FX spot of a foreign currency.
+///
+/// The Hull-White factor and the log of the FX spot are simulated exactly between the dates of
+/// the exposures, for the paths and seed of a [McConfig], whose steps are unused. At each date
+/// of each path the instruments of the portfolio are revalued, in parallel over the paths, on
+/// the domestic curve implied by the factor, and those tagged in the foreign currency on the
+/// foreign curve rolled forward and converted at the simulated spot. Floating periods which
+/// have fixed before a date are forecast on the initial curves.
+///
+/// With a credit driver of the counterparty, correlated to the market drivers, the expected
+/// exposures at each date are conditional on the default of the counterparty in the period to
+/// the date, and so reflect wrong-way risk.
+#[derive(Clone)]
+pub struct ExposureEngine<'a> {
+    pub(crate) curve: &'a (dyn PricingCurve + Sync),
+    pub(crate) model: HullWhite,
+    pub(crate) config: McConfig,
+    pub(crate) fx: Option<FxDriver<'a>>,
+    pub(crate) credit: Option<CreditDriver<'a>>,
+}
+
+/// The lognormal FX spot, in domestic units per foreign unit, by which the values of the
+/// instruments of the foreign currency are converted.
+#[derive(Clone)]
+pub(crate) struct FxDriver<'a> {
+    pub(crate) ccy: String,
+    pub(crate) curve: &'a (dyn PricingCurve + Sync),
+    pub(crate) spot: f64,
+    pub(crate) volatility: f64,
+    pub(crate) correlation: f64,
+}
+
+/// The default of the counterparty on its `survival` curve under a Gaussian copula with the
+/// standardised Hull-White factor and FX driver at the end of each period.
+#[derive(Clone)]
+pub(crate) struct CreditDriver<'a> {
+    pub(crate) survival: &'a (dyn PricingCurve + Sync),
+    pub(crate) rates_correlation: Number,
+    pub(crate) fx_correlation: Number,
+}
+
+/// A path of the values of a portfolio and of the standardised Hull-White factor and FX driver
+/// at each date.
+struct SimulatedPath {
+    values: Vec<Number>,
+    drivers: Vec<[f64; 2]>,
+}
+
+/// The probability of default of the counterparty in a period conditional on the standardised
+/// drivers at its end.
+struct ConditionalDefault {
+    threshold: Number,
+    rates: Number,
+    fx: Number,
+    residual: Number,
+}
+
+impl ConditionalDefault {
+    fn probability(&self, drivers: &[f64; 2]) -> Number {
+        ((&self.threshold + &self.rates * drivers[0] + &self.fx * drivers[1]) / &self.residual)
+            .norm_cdf()
+    }
+}
+
+impl<'a> ExposureEngine<'a> {
+    pub fn new(curve: &'a (dyn PricingCurve + Sync), model: HullWhite, config: McConfig) -> Self {
+        Self {
+            curve,
+            model,
+            config,
+            fx: None,
+            credit: None,
+        }
+    }
+
+    /// Return the engine with the instruments tagged in the currency `ccy` valued on the
+    /// foreign `curve` and converted at an FX `spot` of lognormal `volatility`, whose driver
+    /// has the `correlation` to the Hull-White factor.
+    ///
+    /// The FX spot is simulated about its forward on the initial curves.
+    pub fn with_fx(
+        mut self,
+        ccy: &str,
+        curve: &'a (dyn PricingCurve + Sync),
+        spot: f64,
+        volatility: f64,
+        correlation: f64,
+    ) -> Result<Self, RateslibError> {
+        if spot <= 0.0 || volatility < 0.0 {
+            return Err(RateslibError::value(
+                "The FX `spot` must be positive and its `volatility` not negative.",
+            ));
+        }
+        if !(-1.0..=1.0).contains(&correlation) {
+            return Err(RateslibError::value(
+                "The `correlation` of the FX spot must be between -1 and 1.",
+            ));
+        }
+        self.fx = Some(FxDriver {
+            ccy: ccy.to_lowercase(),
+            curve,
+            spot,
+            volatility,
+            correlation,
+        });
+        Ok(self)
+    }
+
+    /// Return the engine with the default of the counterparty on its `survival` curve
+    /// correlated to the Hull-White factor and to the FX driver, if any, by the correlations,
+    /// either of which may be a dual number.
+    ///
+    /// A positive correlation makes default more likely as the driver rises, which is wrong
+    /// way risk for a portfolio whose value rises with it.
+    pub fn with_credit(
+        mut self,
+        survival: &'a (dyn PricingCurve + Sync),
+        rates_correlation: Number,
+        fx_correlation: Number,
+    ) -> Result<Self, RateslibError> {
+        if [&rates_correlation, &fx_correlation]
+            .iter()
+            .any(|c| f64::from(*c).abs() >= 1.0)
+        {
+            return Err(RateslibError::value(
+                "The correlations of the credit driver must be strictly between -1 and 1.",
+            ));
+        }
+        self.credit = Some(CreditDriver {
+            survival,
+            rates_correlation,
+            fx_correlation,
+        });
+        Ok(self)
+    }
+
+    /// Return the [ExposureProfile] of the `portfolio` at the `dates`, with the PFE at the
+    /// `quantile`.
+    pub fn simulate(
+        &self,
+        portfolio: &Portfolio,
+        dates: &[NaiveDateTime],
+        quantile: f64,
+    ) -> Result<ExposureProfile, RateslibError> {
+        let paths = self.paths(portfolio, dates)?;
+        self.profile(paths, dates, quantile)
+    }
+
+    /// Return the [ExposureProfile] of the `netting_set` at the `dates`, net of the collateral
+    /// held under its [Csa](crate::xva::Csa), with the PFE at the `quantile`.
+    ///
+    /// The portfolio is also revalued at each margin call date, on which the collateral
+    /// balance is updated from the value on the path, and the collateral under a call is held
+    /// from the end of the margin period of risk. No collateral is held before the first call.
+    pub fn simulate_netting_set(
+        &self,
+        netting_set: &NettingSet,
+        dates: &[NaiveDateTime],
+        quantile: f64,
+    ) -> Result<ExposureProfile, RateslibError> {
+        let Some(csa) = &netting_set.csa else {
+            return self.simulate(&netting_set.portfolio, dates, quantile);
+        };
+        let initial = self.validate_dates(dates)?;
+        let calls = csa.call_dates(&initial, &dates[dates.len() - 1]);
+        let mut grid: Vec<NaiveDateTime> = dates.iter().chain(calls.iter()).copied().collect();
+        grid.sort();
+        grid.dedup();
+        let index = |date: &NaiveDateTime| grid.binary_search(date).unwrap();
+        let lag = Days::new(csa.margin_period_of_risk as u64);
+        let paths = self
+            .paths(&netting_set.portfolio, &grid)?
+            .into_iter()
+            .map(|p| {
+                let call_values: Vec<Number> =
+                    calls.iter().map(|c| p.values[index(c)].clone()).collect();
+                let balances = csa.balances(&call_values);
+                let values = dates
+                    .iter()
+                    .map(|d| {
+                        let value = &p.values[index(d)];
+                        match calls.iter().take_while(|c| **c + lag <= *d).count() {
+                            0 => value.clone(),
+                            k => value - &balances[k - 1],
+                        }
+                    })
+                    .collect();
+                let drivers = dates.iter().map(|d| p.drivers[index(d)]).collect();
+                SimulatedPath { values, drivers }
+            })
+            .collect();
+        self.profile(paths, dates, quantile)
+    }
+
+    /// Reduce the simulated `paths` at the `dates` to an [ExposureProfile], conditional on the
+    /// default of the counterparty with a credit driver.
+    fn profile(
+        &self,
+        paths: Vec<SimulatedPath>,
+        dates: &[NaiveDateTime],
+        quantile: f64,
+    ) -> Result<ExposureProfile, RateslibError> {
+        let Some(credit) = &self.credit else {
+            let values: Vec<Vec<Number>> = paths.into_iter().map(|p| p.values).collect();
+            return ExposureProfile::from_paths(dates.to_vec(), &values, quantile);
+        };
+        let defaults = self.conditional_defaults(credit, dates)?;
+        let weights: Vec<Vec<Number>> = paths
+            .iter()
+            .map(|p| {
+                p.drivers
+                    .iter()
+                    .zip(defaults.iter())
+                    .map(|(y, d)| d.probability(y))
+                    .collect()
+            })
+            .collect();
+        let values: Vec<Vec<Number>> = paths.into_iter().map(|p| p.values).collect();
+        ExposureProfile::from_weighted_paths(dates.to_vec(), &values, &weights, quantile)
+    }
+
+    /// Return the simulated values of the `portfolio`, in the domestic currency, by path and
+    /// then by each of the increasing `dates` after the initial date of the curve.
+    pub fn values(
+        &self,
+        portfolio: &Portfolio,
+        dates: &[NaiveDateTime],
+    ) -> Result<Vec<Vec<Number>>, RateslibError> {
+        Ok(self
+            .paths(portfolio, dates)?
+            .into_iter()
+            .map(|p| p.values)
+            .collect())
+    }
+
+    fn paths(
+        &self,
+        portfolio: &Portfolio,
+        dates: &[NaiveDateTime],
+    ) -> Result<Vec<SimulatedPath>, RateslibError> {
+        let initial = self.validate_dates(dates)?;
+        let times: Vec<f64> = dates.iter().map(|d| years(&initial, d)).collect();
+        let mut rng = NormalRng::new(self.config.seed);
+        let draws = match self.config.antithetic {
+            true => self.config.paths / 2,
+            false => self.config.paths,
+        };
+        let mut variates = Vec::with_capacity(self.config.paths);
+        for _ in 0..draws {
+            let z = rng.normals(2 * dates.len());
+            if self.config.antithetic {
+                variates.push(z.iter().map(|x| -x).collect());
+            }
+            variates.push(z);
+        }
+        par_map(&variates, |z| self.path(portfolio, dates, &times, z))?
+            .into_iter()
+            .collect()
+    }
+
+    /// Return the initial date of the curve, validating the `dates` are increasing and after it.
+    fn validate_dates(&self, dates: &[NaiveDateTime]) -> Result<NaiveDateTime, RateslibError> {
+        let initial = self.curve.initial_date();
+        if dates.is_empty() || dates[0] <= initial || dates.windows(2).any(|w| w[0] >= w[1]) {
+            return Err(RateslibError::value(
+                "The dates of an exposure simulation must be increasing and after the initial \
+                 date of the curve.",
+            ));
+        }
+        Ok(initial)
+    }
+
+    /// Return the values of the `portfolio` at the `dates` of the path of the normal variates
+    /// `z`, of the Hull-White factor and the FX spot in turn at each date.
+    fn path(
+        &self,
+        portfolio: &Portfolio,
+        dates: &[NaiveDateTime],
+        times: &[f64],
+        z: &[f64],
+    ) -> Result<SimulatedPath, RateslibError> {
+        let (a, sigma) = (self.model.mean_reversion, self.model.volatility);
+        let (mut x, mut w_fx, mut previous) = (0.0, 0.0, 0.0);
+        let mut values = Vec::with_capacity(dates.len());
+        let mut drivers = Vec::with_capacity(dates.len());
+        for (i, (date, t)) in dates.iter().zip(times.iter()).enumerate() {
+            cancel::check()?;
+            let dt = t - previous;
+            previous = *t;
+            let decay = (-a * dt).exp();
+            x = x * decay + sigma * ((1.0 - decay * decay) / (2.0 * a)).sqrt() * z[2 * i];
+            let curve = HullWhiteCurve::try_new(self.curve, date, *t, x, &self.model)?;
+            let domestic = Curves::new(Some(&curve), Some(&curve));
+            let mut value = Number::F64(0.0);
+            let mut foreign_value = Number::F64(0.0);
+            let foreign = match &self.fx {
+                Some(fx) => Some(RolledCurve::try_new(fx.curve, *date, RollMethod::Forward)?),
+                None => None,
+            };
+            let foreign_curves = Curves::new(
+                foreign.as_ref().map(|c| c as &dyn PricingCurve),
+                foreign.as_ref().map(|c| c as &dyn PricingCurve),
+            );
+            for (instrument, tags) in portfolio.instruments().iter().zip(portfolio.tags()) {
+                match &self.fx {
+                    Some(fx) if tags.currency.as_deref() == Some(fx.ccy.as_str()) => {
+                        foreign_value = foreign_value.try_add(&instrument.npv(&foreign_curves)?)?
+                    }
+                    _ => value = value.try_add(&instrument.npv(&domestic)?)?,
+                }
+            }
+            let rho = self.fx.as_ref().map_or(0.0, |fx| fx.correlation);
+            w_fx += dt.sqrt() * (rho * z[2 * i] + (1.0 - rho * rho).sqrt() * z[2 * i + 1]);
+            if let Some(fx) = &self.fx {
+                let log_fx = -0.5 * fx.volatility * fx.volatility * t + fx.volatility * w_fx;
+                let forward = fx.curve.df(date) / self.curve.df(date) * fx.spot;
+                value = value + foreign_value * forward * log_fx.exp();
+            }
+            values.push(value);
+            drivers.push([x / factor_deviation(&self.model, *t), w_fx / t.sqrt()]);
+        }
+        Ok(SimulatedPath { values, drivers })
+    }
+
+    /// Return the [ConditionalDefault] of the counterparty in the period to each of the
+    /// `dates`.
+    ///
+    /// The loadings of the copula on the drivers are those which give the `credit` driver its
+    /// correlations to them, given the correlation of the drivers at each date.
+    fn conditional_defaults(
+        &self,
+        credit: &CreditDriver,
+        dates: &[NaiveDateTime],
+    ) -> Result<Vec<ConditionalDefault>, RateslibError> {
+        if self.fx.is_none() && f64::from(&credit.fx_correlation) != 0.0 {
+            return Err(RateslibError::value(
+                "A correlation of the credit driver to the FX spot requires an FX driver.",
+            ));
+        }
+        let (a, sigma) = (self.model.mean_reversion, self.model.volatility);
+        let rho = self.fx.as_ref().map_or(0.0, |fx| fx.correlation);
+        let (rates, fx) = (&credit.rates_correlation, &credit.fx_correlation);
+        let initial = self.curve.initial_date();
+        let mut survival = credit.survival.df(&initial);
+        let (mut covariance, mut previous) = (0.0, 0.0);
+        let mut defaults = Vec::with_capacity(dates.len());
+        for date in dates.iter() {
+            let t = years(&initial, date);
+            let dt = t - previous;
+            previous = t;
+            let decay = (-a * dt).exp();
+            covariance = covariance * decay
+                + rho * dt.sqrt() * sigma * ((1.0 - decay * decay) / (2.0 * a)).sqrt();
+            let c = covariance / (factor_deviation(&self.model, t) * t.sqrt());
+            let rates_loading = (rates - fx * c) / (1.0 - c * c);
+            let fx_loading = (fx - rates * c) / (1.0 - c * c);
+            let residual = 1.0 - &rates_loading * rates - &fx_loading * fx;
+            if f64::from(&residual) <= 0.0 {
+                return Err(RateslibError::value(
+                    "The correlations of the credit driver are inconsistent with the \
+                     correlation of the market drivers.",
+                ));
+            }
+            let next = credit.survival.df(date);
+            let default = &survival - &next;
+            if f64::from(&default) <= 0.0 {
+                return Err(RateslibError::value(
+                    "The counterparty requires a positive probability of default in each period.",
+                ));
+            }
+            survival = next;
+            defaults.push(ConditionalDefault {
+                threshold: default.inv_norm_cdf(),
+                rates: rates_loading,
+                fx: fx_loading,
+                residual: residual.pow(0.5),
+            });
+        }
+        Ok(defaults)
+    }
+}
+
+/// A view of a discount curve from a future `date` on a path of the Hull-White factor, `x`,
+/// with the closed form discount factors of the model fitted to the curve.
+struct HullWhiteCurve<'a> {
+    rolled: RolledCurve<'a>,
+    /// The short rate less the instantaneous forward rate of the curve at the date.
+    shift: f64,
+    /// The coefficient of the square of `B(t, T)` in the log of the discount factors.
+    convexity: f64,
+    mean_reversion: f64,
+}
+
+impl<'a> HullWhiteCurve<'a> {
+    fn try_new(
+        curve: &'a dyn PricingCurve,
+        date: &NaiveDateTime,
+        t: f64,
+        x: f64,
+        model: &HullWhite,
+    ) -> Result<Self, RateslibError> {
+        let (a, sigma) = (model.mean_reversion, model.volatility);
+        Ok(Self {
+            rolled: RolledCurve::try_new(curve, *date, RollMethod::Forward)?,
+            shift: x + sigma * sigma / (2.0 * a * a) * (1.0 - (-a * t).exp()).powi(2),
+            convexity: sigma * sigma / (4.0 * a) * (1.0 - (-2.0 * a * t).exp()),
+            mean_reversion: a,
+        })
+    }
+}
+
+impl PricingCurve for HullWhiteCurve<'_> {
+    fn id(&self) -> &str {
+        self.rolled.id()
+    }
+
+    fn initial_date(&self) -> NaiveDateTime {
+        self.rolled.initial_date()
+    }
+
+    fn df(&self, date: &NaiveDateTime) -> Number {
+        let tau = years(&self.initial_date(), date);
+        if tau <= 0.0 {
+            return self.rolled.df(date);
+        }
+        let b = (1.0 - (-self.mean_reversion * tau).exp()) / self.mean_reversion;
+        self.rolled.df(date) * (-b * self.shift - self.convexity * b * b).exp()
+    }
+
+    fn dcf(&self, start: &NaiveDateTime, end: &NaiveDateTime) -> Result<f64, RateslibError> {
+        self.rolled.dcf(start, end)
+    }
+
+    fn index_value(&self, date: &NaiveDateTime) -> Result<Number, RateslibError> {
+        self.rolled.index_value(date)
+    }
+}
+
+/// Return the standard deviation of the Hull-White factor at `t` years.
+fn factor_deviation(model: &HullWhite, t: f64) -> f64 {
+    let a = model.mean_reversion;
+    model.volatility * ((1.0 - (-2.0 * a * t).exp()) / (2.0 * a)).sqrt()
+}
+
+fn years(start: &NaiveDateTime, end: &NaiveDateTime) -> f64 {
+    (*end - *start).num_days() as f64 / 365.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::ndt;
+    use crate::dual::{Dual, Gradient1};
+    use crate::instruments::instrument::tests::irs_fixture;
+    use crate::instruments::{Instrument, InstrumentTags};
+    use crate::periods::period::tests::curve_fixture;
+    use crate::xva::Csa;
+
+    fn dates() -> Vec<NaiveDateTime> {
+        vec![
+            ndt(2024, 4, 1),
+            ndt(2024, 10, 1),
+            ndt(2025, 4, 1),
+            ndt(2025, 10, 1),
+        ]
+    }
+
+    #[test]
+    fn test_swap_exposure_profile() {
+        let curve = curve_fixture("c");
+        let curves = Curves::new(Some(&curve), Some(&curve));
+        let par = f64::from(&irs_fixture(2.0).rate(&curves).unwrap());
+        let swap = irs_fixture(par);
+        let portfolio = Portfolio::new(vec![Box::new(swap.clone())]);
+        let model = HullWhite::try_new(0.03, 0.01).unwrap();
+        let config = McConfig::try_new(1000, 1, 1, true).unwrap();
+        let engine = ExposureEngine::new(&curve, model, config);
+        let profile = engine.simulate(&portfolio, &dates(), 0.95).unwrap();
+        for (i, date) in dates().iter().enumerate() {
+            let (epe, ene) = (f64::from(&profile.epe()[i]), f64::from(&profile.ene()[i]));
+            assert!(epe > 0.0 && ene < 0.0 && profile.pfe()[i] > epe);
+            // the mean of the values is the value on the curve rolled forward
+            let rolled = RolledCurve::try_new(&curve, *date, RollMethod::Forward).unwrap();
+            let forward = swap
+                .npv(&Curves::new(Some(&rolled), Some(&rolled)))
+                .unwrap();
+            assert!((epe + ene - f64::from(&forward)).abs() < 0.05 * epe);
+        }
+        assert!(engine
+            .simulate(&portfolio, &[ndt(2023, 1, 1)], 0.95)
+            .is_err());
+    }
+
+    #[test]
+    fn test_exposure_simulation_is_cancelled() {
+        let curve = curve_fixture("c");
+        let portfolio = Portfolio::new(vec![Box::new(irs_fixture(2.0))]);
+        let model = HullWhite::try_new(0.03, 0.01).unwrap();
+        let config = McConfig::try_new(1000, 1, 1, true).unwrap();
+        let engine = ExposureEngine::new(&curve, model, config);
+        let token = crate::cancel::CancellationToken::new();
+        token.cancel();
+        let result = token.run(|| engine.simulate(&portfolio, &dates(), 0.95));
+        assert!(matches!(result, Err(RateslibError::Cancelled)));
+    }
+
+    #[test]
+    fn test_fx_exposure_profile() {
+        let (domestic, foreign) = (curve_fixture("d"), curve_fixture("f"));
+        let mut portfolio = Portfolio::default();
+        let swap = irs_fixture(1.0);
+        portfolio.push_tagged(
+            Box::new(swap.clone()),
+            InstrumentTags::new(None, None, Some("EUR")),
+        );
+        let model = HullWhite::try_new(0.03, 0.01).unwrap();
+        let config = McConfig::try_new(2000, 1, 3, true).unwrap();
+        let engine = ExposureEngine::new(&domestic, model, config)
+            .with_fx("eur", &foreign, 1.1, 0.1, -0.3)
+            .unwrap();
+        let profile = engine.simulate(&portfolio, &dates(), 0.95).unwrap();
+        for (i, date) in dates().iter().enumerate() {
+            let rolled = RolledCurve::try_new(&foreign, *date, RollMethod::Forward).unwrap();
+            let value = f64::from(
+                &swap
+                    .npv(&Curves::new(Some(&rolled), Some(&rolled)))
+                    .unwrap(),
+            );
+            let mean = f64::from(&(&profile.epe()[i] + &profile.ene()[i]));
+            assert!((mean - 1.1 * value).abs() < 0.02 * (1.1 * value).abs());
+        }
+        let engine = ExposureEngine::new(&domestic, model, config);
+        assert!(engine.with_fx("eur", &foreign, 1.1, 0.1, 1.5).is_err());
+    }
+
+    #[test]
+    fn test_wrong_way_risk() {
+        let (curve, hazard) = (curve_fixture("c"), curve_fixture("h"));
+        let portfolio = Portfolio::new(vec![Box::new(irs_fixture(2.0))]);
+        let model = HullWhite::try_new(0.03, 0.01).unwrap();
+        let config = McConfig::try_new(500, 1, 5, true).unwrap();
+        let engine = ExposureEngine::new(&curve, model, config);
+        let epe = |rho: Number| -> Vec<Number> {
+            let credit = engine
+                .clone()
+                .with_credit(&hazard, rho, Number::F64(0.0))
+                .unwrap();
+            credit.simulate(&portfolio, &dates(), 0.95).unwrap().epe
+        };
+        // without correlation the exposures are unconditional
+        let unconditional = engine.simulate(&portfolio, &dates(), 0.95).unwrap();
+        for (a, b) in epe(Number::F64(0.0)).iter().zip(unconditional.epe()) {
+            assert!((f64::from(a) - f64::from(b)).abs() < 1e-9);
+        }
+        let rho = Number::Dual(Dual::new(0.5, vec!["rho".to_string()]));
+        let (up, down) = (epe(Number::F64(0.5 + 1e-5)), epe(Number::F64(0.5 - 1e-5)));
+        for (i, value) in epe(rho).iter().enumerate() {
+            let Number::Dual(d) = value else {
+                panic!("the correlation is a dual number")
+            };
+            let fd = (f64::from(&up[i]) - f64::from(&down[i])) / 2e-5;
+            assert!((d.gradient1(vec!["rho".to_string()])[0] - fd).abs() < 1e-4 * fd.abs());
+            let negative = f64::from(&epe(Number::F64(-0.5))[i]);
+            let zero = f64::from(&unconditional.epe()[i]);
+            assert!((d.real - zero) * (negative - zero) < 0.0);
+        }
+        assert!(engine
+            .clone()
+            .with_credit(&hazard, Number::F64(1.0), Number::F64(0.0))
+            .is_err());
+        let fx = engine
+            .with_credit(&hazard, Number::F64(0.0), Number::F64(0.2))
+            .unwrap();
+        assert!(fx.simulate(&portfolio, &dates(), 0.95).is_err());
+    }
+
+    #[test]
+    fn test_collateralised_exposure() {
+        let curve = curve_fixture("c");
+        let portfolio = || Portfolio::new(vec![Box::new(irs_fixture(2.0))]);
+        let model = HullWhite::try_new(0.03, 0.01).unwrap();
+        let config = McConfig::try_new(200, 1, 7, true).unwrap();
+        let engine = ExposureEngine::new(&curve, model, config);
+        let uncollateralised = NettingSet::new(portfolio());
+        let profile = engine
+            .simulate_netting_set(&uncollateralised, &dates(), 0.95)
+            .unwrap();
+        let expected = engine.simulate(&portfolio(), &dates(), 0.95).unwrap();
+        assert_eq!(profile, expected);
+        // weekly margin with a ten day margin period of risk leaves a small residual exposure
+        let csa = Csa::try_new(0.0, 0.0, 7, 10).unwrap();
+        let collateralised = NettingSet::new(portfolio()).with_csa(csa);
+        let profile = engine
+            .simulate_netting_set(&collateralised, &dates(), 0.95)
+            .unwrap();
+        for (c, u) in profile.epe().iter().zip(expected.epe()) {
+            assert!(f64::from(c) > 0.0 && f64::from(c) < 0.5 * f64::from(u));
+        }
+        // a threshold above any value leaves the values on the grid with the call dates
+        let csa = Csa::try_new(1e9, 0.0, 7, 10).unwrap();
+        let threshold = NettingSet::new(portfolio()).with_csa(csa);
+        let profile = engine
+            .simulate_netting_set(&threshold, &dates(), 0.95)
+            .unwrap();
+        let mut grid = csa.call_dates(&ndt(2024, 1, 1), &ndt(2025, 10, 1));
+        grid.extend(dates());
+        grid.sort();
+        grid.dedup();
+        let values: Vec<Vec<Number>> = engine
+            .values(&portfolio(), &grid)
+            .unwrap()
+            .into_iter()
+            .map(|v| {
+                dates()
+                    .iter()
+                    .map(|d| v[grid.binary_search(d).unwrap()].clone())
+                    .collect()
+            })
+            .collect();
+        let expected = ExposureProfile::from_paths(dates(), &values, 0.95).unwrap();
+        assert_eq!(profile, expected);
+    }
+}
@@ -0,0 +1,127 @@
+use crate::curves::PricingCurve;
+use crate::dual::Number;
+use crate::error::RateslibError;
+use crate::xva::ExposureProfile;
+
+/// The funding of the exposures of a portfolio at a borrowing spread and a lending spread, in
+/// bps over the `discounting` curve, by which its funding and margin valuation adjustments are
+/// measured.
+///
+/// Each adjustment sums the exposure at each date of an [ExposureProfile], discounted and
+/// weighted by the joint probability of survival to the date, over the Act365F year fraction
+/// since the previous date, or since the initial date of the discounting curve for the first.
+/// The spreads are [Number]s so that the adjustments carry their sensitivities.
+#[derive(Clone)]
+pub struct FundingModel<'a> {
+    pub(crate) discounting: &'a dyn PricingCurve,
+    pub(crate) borrowing: Number,
+    pub(crate) lending: Number,
+    pub(crate) survival: Option<&'a dyn PricingCurve>,
+}
+
+impl<'a> FundingModel<'a> {
+    pub fn new(discounting: &'a dyn PricingCurve, borrowing: Number, lending: Number) -> Self {
+        Self {
+            discounting,
+            borrowing,
+            lending,
+            survival: None,
+        }
+    }
+
+    /// Return the model with exposures weighted by the joint survival probabilities of the
+    /// counterparty and the institution on the `survival` curve.
+    pub fn with_survival(mut self, survival: &'a dyn PricingCurve) -> Self {
+        self.survival = Some(survival);
+        self
+    }
+
+    /// Return the funding cost adjustment, the cost of borrowing to fund the EPE, which is not
+    /// positive.
+    pub fn fca(&self, profile: &ExposureProfile) -> Number {
+        self.integrate(profile, &profile.epe) * (&self.borrowing * -0.0001)
+    }
+
+    /// Return the funding benefit adjustment, the benefit of lending the funding received
+    /// against the ENE, which is not negative.
+    pub fn fba(&self, profile: &ExposureProfile) -> Number {
+        self.integrate(profile, &profile.ene) * (&self.lending * -0.0001)
+    }
+
+    /// Return the funding valuation adjustment, the sum of the FCA and the FBA.
+    pub fn fva(&self, profile: &ExposureProfile) -> Number {
+        self.fca(profile) + self.fba(profile)
+    }
+
+    /// Return the margin valuation adjustment, the cost of borrowing to fund the expected
+    /// initial margin of the profile, which is not positive.
+    pub fn mva(&self, profile: &ExposureProfile) -> Result<Number, RateslibError> {
+        let margin = profile.initial_margin.as_ref().ok_or_else(|| {
+            RateslibError::value("The MVA requires an `ExposureProfile` with initial margin.")
+        })?;
+        Ok(self.integrate(profile, margin) * (&self.borrowing * -0.0001))
+    }
+
+    /// Return the sum of the `exposures` at each date of the `profile`, discounted, weighted by
+    /// survival and by the year fraction since the previous date.
+    fn integrate(&self, profile: &ExposureProfile, exposures: &[Number]) -> Number {
+        let mut previous = self.discounting.initial_date();
+        let mut value = Number::F64(0.0);
+        for (date, exposure) in profile.dates.iter().zip(exposures.iter()) {
+            if *date <= previous {
+                continue;
+            }
+            let dt = (*date - previous).num_days() as f64 / 365.0;
+            let mut weight = self.discounting.df(date);
+            if let Some(survival) = self.survival {
+                weight = weight * survival.df(date);
+            }
+            value = value + exposure * &weight * dt;
+            previous = *date;
+        }
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::ndt;
+    use crate::dual::{Dual, Gradient1};
+    use crate::periods::period::tests::curve_fixture;
+
+    #[test]
+    fn test_funding_adjustments() {
+        let disc = curve_fixture("d");
+        let dates: Vec<_> = (1..=4).map(|y| ndt(2024 + y, 1, 1)).collect();
+        let n = Number::F64;
+        let profile = ExposureProfile::try_new(
+            dates.clone(),
+            vec![n(100.0); 4],
+            vec![n(-40.0); 4],
+            vec![150.0; 4],
+        )
+        .unwrap();
+        let borrowing = Number::Dual(Dual::new(50.0, vec!["fund".to_string()]));
+        let model = FundingModel::new(&disc, borrowing, n(20.0));
+        let annuity: f64 = dates
+            .iter()
+            .zip([ndt(2024, 1, 1)].iter().chain(dates.iter()))
+            .map(|(d, p)| f64::from(disc.df(d)) * (*d - *p).num_days() as f64 / 365.0)
+            .sum();
+        let fca = model.fca(&profile);
+        assert!((f64::from(&fca) + 100.0 * 0.005 * annuity).abs() < 1e-9);
+        assert!((f64::from(&model.fba(&profile)) - 40.0 * 0.002 * annuity).abs() < 1e-9);
+        let Number::Dual(d) = fca else {
+            panic!("the borrowing spread is a dual number")
+        };
+        assert!((d.gradient1(vec!["fund".to_string()])[0] + 0.01 * annuity).abs() < 1e-9);
+        // survival reduces the adjustments, and the MVA requires initial margin
+        let hazard = curve_fixture("h");
+        let risky = model.clone().with_survival(&hazard);
+        assert!(f64::from(&risky.fva(&profile)).abs() < f64::from(&model.fva(&profile)).abs());
+        assert!(model.mva(&profile).is_err());
+        let profile = profile.with_initial_margin(vec![n(10.0); 4]).unwrap();
+        assert!((f64::from(&model.mva(&profile).unwrap()) + 10.0 * 0.005 * annuity).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,22 @@
+//! Compute valuation adjustments of portfolios from their simulated exposure profiles.
+//!
+//! An [ExposureEngine] simulates the values of a portfolio at future dates under a Hull-White model
+//! of rates and a lognormal FX spot, with a correlated default of the counterparty for wrong-way
+//! risk, and of a [NettingSet] net of the collateral held under its [Csa]. An [ExposureProfile]
+//! holds the expected positive and negative exposures, the potential future exposure and,
+//! optionally, the expected initial margin of a portfolio at future dates, typically reduced from
+//! the values of Monte Carlo paths with [ExposureProfile::from_paths]. A [FundingModel] integrates
+//! a profile against discount factors, survival probabilities and funding spreads to give the
+//! funding and margin valuation adjustments. The adjustments are compiled with the `xva` feature.
+
+mod profile;
+pub use crate::xva::profile::ExposureProfile;
+
+mod funding;
+pub use crate::xva::funding::FundingModel;
+
+mod exposure;
+pub use crate::xva::exposure::ExposureEngine;
+
+mod netting;
+pub use crate::xva::netting::{Csa, NettingSet};
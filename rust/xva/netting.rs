@@ -0,0 +1,150 @@
+use crate::dual::Number;
+use crate::error::RateslibError;
+use crate::instruments::Portfolio;
+use chrono::{Days, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+/// The terms of a two way credit support annex, by which variation margin is exchanged to
+/// collateralise the value of a [NettingSet].
+///
+/// Margin is called every `margin_frequency` calendar days from the initial date, for the value
+/// of the netting set in excess of the `threshold` of either party, and is transferred only if
+/// the change in the collateral balance is at least the `minimum_transfer` amount. Collateral is
+/// received `margin_period_of_risk` calendar days after the valuation of the call, so that the
+/// collateral at a date is that called on the values of the netting set that many days before.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Csa {
+    pub(crate) threshold: f64,
+    pub(crate) minimum_transfer: f64,
+    pub(crate) margin_frequency: u32,
+    pub(crate) margin_period_of_risk: u32,
+}
+
+impl Csa {
+    /// Create a [Csa] with a `threshold` and `minimum_transfer` amount which are not negative
+    /// and a positive `margin_frequency`, in calendar days.
+    pub fn try_new(
+        threshold: f64,
+        minimum_transfer: f64,
+        margin_frequency: u32,
+        margin_period_of_risk: u32,
+    ) -> Result<Self, RateslibError> {
+        if threshold < 0.0 || minimum_transfer < 0.0 {
+            return Err(RateslibError::value(
+                "The `threshold` and `minimum_transfer` of a `Csa` cannot be negative.",
+            ));
+        }
+        if margin_frequency == 0 {
+            return Err(RateslibError::value(
+                "The `margin_frequency` of a `Csa` must be positive.",
+            ));
+        }
+        Ok(Self {
+            threshold,
+            minimum_transfer,
+            margin_frequency,
+            margin_period_of_risk,
+        })
+    }
+
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+
+    pub fn minimum_transfer(&self) -> f64 {
+        self.minimum_transfer
+    }
+
+    pub fn margin_frequency(&self) -> u32 {
+        self.margin_frequency
+    }
+
+    pub fn margin_period_of_risk(&self) -> u32 {
+        self.margin_period_of_risk
+    }
+
+    /// Return the margin call dates after `initial` whose collateral is received by `end`.
+    pub fn call_dates(&self, initial: &NaiveDateTime, end: &NaiveDateTime) -> Vec<NaiveDateTime> {
+        let last = *end - Days::new(self.margin_period_of_risk as u64);
+        (1..)
+            .map(|k| *initial + Days::new(k * self.margin_frequency as u64))
+            .take_while(|d| *d <= last)
+            .collect()
+    }
+
+    /// Return the collateral balance held after each margin call, from none, given the value of
+    /// the netting set at each call, positive when collateral is received.
+    pub fn balances(&self, values: &[Number]) -> Vec<Number> {
+        let mut balance = Number::F64(0.0);
+        values
+            .iter()
+            .map(|v| {
+                let required = match f64::from(v) {
+                    x if x > self.threshold => v - self.threshold,
+                    x if x < -self.threshold => v + self.threshold,
+                    _ => Number::F64(0.0),
+                };
+                if (f64::from(&required) - f64::from(&balance)).abs() >= self.minimum_transfer {
+                    balance = required;
+                }
+                balance.clone()
+            })
+            .collect()
+    }
+}
+
+/// The instruments of a [Portfolio] with a counterparty whose values are netted on default,
+/// optionally collateralised under a [Csa].
+#[derive(Default)]
+pub struct NettingSet {
+    pub(crate) portfolio: Portfolio,
+    pub(crate) csa: Option<Csa>,
+}
+
+impl NettingSet {
+    pub fn new(portfolio: Portfolio) -> Self {
+        Self {
+            portfolio,
+            csa: None,
+        }
+    }
+
+    /// Return the netting set collateralised under the `csa`.
+    pub fn with_csa(mut self, csa: Csa) -> Self {
+        self.csa = Some(csa);
+        self
+    }
+
+    pub fn portfolio(&self) -> &Portfolio {
+        &self.portfolio
+    }
+
+    pub fn csa(&self) -> Option<&Csa> {
+        self.csa.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::ndt;
+
+    #[test]
+    fn test_csa_balances() {
+        let csa = Csa::try_new(100.0, 20.0, 7, 10).unwrap();
+        let values: Vec<Number> = [50.0, 130.0, 145.0, 120.0, -300.0]
+            .iter()
+            .map(|v| Number::F64(*v))
+            .collect();
+        let balances: Vec<f64> = csa.balances(&values).iter().map(f64::from).collect();
+        // the changes in balance of 15 and then 10 are below the minimum transfer
+        assert_eq!(balances, vec![0.0, 30.0, 30.0, 30.0, -200.0]);
+        let calls = csa.call_dates(&ndt(2025, 1, 1), &ndt(2025, 2, 1));
+        assert_eq!(
+            calls,
+            vec![ndt(2025, 1, 8), ndt(2025, 1, 15), ndt(2025, 1, 22)]
+        );
+        assert!(Csa::try_new(-1.0, 0.0, 1, 10).is_err());
+        assert!(Csa::try_new(0.0, 0.0, 0, 10).is_err());
+    }
+}
@@ -0,0 +1,168 @@
+use crate::dual::Number;
+use crate::error::RateslibError;
+use chrono::NaiveDateTime;
+
+/// The exposures of a portfolio at future dates, in the currency of the portfolio and
+/// undiscounted.
+///
+/// The expected positive exposure, EPE, is the mean of the positive part of the value, the
+/// expected negative exposure, ENE, the mean of the negative part, which is not positive, and
+/// the potential future exposure, PFE, a quantile of the value. Expected exposures are
+/// [Number]s, so that the sensitivities of simulated values are carried to the adjustments.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExposureProfile {
+    pub(crate) dates: Vec<NaiveDateTime>,
+    pub(crate) epe: Vec<Number>,
+    pub(crate) ene: Vec<Number>,
+    pub(crate) pfe: Vec<f64>,
+    pub(crate) initial_margin: Option<Vec<Number>>,
+}
+
+impl ExposureProfile {
+    /// Create an [ExposureProfile] of the exposures at each of the increasing `dates`.
+    pub fn try_new(
+        dates: Vec<NaiveDateTime>,
+        epe: Vec<Number>,
+        ene: Vec<Number>,
+        pfe: Vec<f64>,
+    ) -> Result<Self, RateslibError> {
+        let n = dates.len();
+        if epe.len() != n || ene.len() != n || pfe.len() != n {
+            return Err(RateslibError::value(
+                "An `ExposureProfile` requires an exposure of each type at each date.",
+            ));
+        }
+        if dates.windows(2).any(|w| w[0] >= w[1]) {
+            return Err(RateslibError::value(
+                "The dates of an `ExposureProfile` must be in increasing order.",
+            ));
+        }
+        Ok(Self {
+            dates,
+            epe,
+            ene,
+            pfe,
+            initial_margin: None,
+        })
+    }
+
+    /// Reduce the simulated `values` of a portfolio, by path and then by date, to the profile
+    /// at the `dates` with the PFE at the `quantile`, such as 0.95.
+    pub fn from_paths(
+        dates: Vec<NaiveDateTime>,
+        values: &[Vec<Number>],
+        quantile: f64,
+    ) -> Result<Self, RateslibError> {
+        let weights = vec![vec![Number::F64(1.0); dates.len()]; values.len()];
+        Self::from_weighted_paths(dates, values, &weights, quantile)
+    }
+
+    /// Reduce the simulated `values` of a portfolio as [ExposureProfile::from_paths], with the
+    /// expected exposures at each date the means of the paths weighted by the positive
+    /// `weights`, by path and then by date, such as the probabilities of default conditional
+    /// on each path. The PFE is unweighted.
+    pub fn from_weighted_paths(
+        dates: Vec<NaiveDateTime>,
+        values: &[Vec<Number>],
+        weights: &[Vec<Number>],
+        quantile: f64,
+    ) -> Result<Self, RateslibError> {
+        if values.is_empty()
+            || values.len() != weights.len()
+            || values
+                .iter()
+                .zip(weights.iter())
+                .any(|(v, w)| v.len() != dates.len() || w.len() != dates.len())
+        {
+            return Err(RateslibError::value(
+                "The simulated values of an `ExposureProfile` require a value and a weight at \
+                 each date of each path.",
+            ));
+        }
+        if !(0.0..=1.0).contains(&quantile) {
+            return Err(RateslibError::value(
+                "The quantile of the PFE must be between 0 and 1.",
+            ));
+        }
+        let n = values.len() as f64;
+        let (mut epe, mut ene, mut pfe) = (vec![], vec![], vec![]);
+        for i in 0..dates.len() {
+            let (mut positive, mut negative) = (Number::F64(0.0), Number::F64(0.0));
+            let mut total = Number::F64(0.0);
+            let mut reals = Vec::with_capacity(values.len());
+            for (path, weight) in values.iter().zip(weights.iter()) {
+                let (v, w) = (&path[i], &weight[i]);
+                match f64::from(v) > 0.0 {
+                    true => positive = positive + v * w,
+                    false => negative = negative + v * w,
+                }
+                total = total + w;
+                reals.push(f64::from(v));
+            }
+            if f64::from(&total) <= 0.0 {
+                return Err(RateslibError::value(
+                    "The weights of an `ExposureProfile` must be positive at each date.",
+                ));
+            }
+            epe.push(positive / &total);
+            ene.push(negative / &total);
+            reals.sort_by(f64::total_cmp);
+            let k = ((quantile * n).ceil() as usize).clamp(1, reals.len());
+            pfe.push(reals[k - 1]);
+        }
+        Self::try_new(dates, epe, ene, pfe)
+    }
+
+    /// Return the profile with the expected initial margin posted at each date.
+    pub fn with_initial_margin(mut self, margin: Vec<Number>) -> Result<Self, RateslibError> {
+        if margin.len() != self.dates.len() {
+            return Err(RateslibError::value(
+                "An `ExposureProfile` requires an initial margin at each date.",
+            ));
+        }
+        self.initial_margin = Some(margin);
+        Ok(self)
+    }
+
+    pub fn dates(&self) -> &[NaiveDateTime] {
+        &self.dates
+    }
+
+    pub fn epe(&self) -> &[Number] {
+        &self.epe
+    }
+
+    pub fn ene(&self) -> &[Number] {
+        &self.ene
+    }
+
+    pub fn pfe(&self) -> &[f64] {
+        &self.pfe
+    }
+
+    pub fn initial_margin(&self) -> Option<&[Number]> {
+        self.initial_margin.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendars::ndt;
+
+    #[test]
+    fn test_profile_from_paths() {
+        let dates = vec![ndt(2025, 1, 1), ndt(2026, 1, 1)];
+        let values: Vec<Vec<Number>> = (0..100)
+            .map(|i| vec![Number::F64(i as f64 - 49.5), Number::F64(1.0)])
+            .collect();
+        let profile = ExposureProfile::from_paths(dates.clone(), &values, 0.95).unwrap();
+        // the positive values 0.5, .., 49.5 average 25 over 50 of 100 paths
+        assert_eq!(f64::from(&profile.epe()[0]), 12.5);
+        assert_eq!(f64::from(&profile.ene()[0]), -12.5);
+        assert_eq!(profile.pfe(), &[44.5, 1.0]);
+        assert_eq!(f64::from(&profile.ene()[1]), 0.0);
+        assert!(profile.clone().with_initial_margin(vec![]).is_err());
+        assert!(ExposureProfile::from_paths(dates, &values[..0], 0.95).is_err());
+    }
+}